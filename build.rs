@@ -0,0 +1,20 @@
+fn main() {
+    // The gRPC service and its messages are generated from proto/binwalk.proto, with a vendored protoc
+    #[cfg(feature = "grpc")]
+    compile_grpc_service();
+}
+
+/// Generates the server stubs and messages of the binwalk gRPC service from its protobuf definition
+#[cfg(feature = "grpc")]
+fn compile_grpc_service() {
+    let protoc =
+        protoc_bin_vendored::protoc_bin_path().expect("No vendored protoc for this platform");
+
+    let mut config = prost_build::Config::new();
+    config.protoc_executable(protoc);
+
+    tonic_build::configure()
+        .build_client(false)
+        .compile_protos_with_config(config, &["proto/binwalk.proto"], &["proto"])
+        .expect("Failed to compile proto/binwalk.proto");
+}
@@ -3,9 +3,12 @@
 use aho_corasick::AhoCorasick;
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::{Read, Seek, SeekFrom};
 use std::path;
+use std::time;
 use uuid::Uuid;
 
 // Windows平台相关导入可以在需要时添加
@@ -13,10 +16,24 @@ use uuid::Uuid;
 #[cfg(unix)]
 use std::os::unix;
 
-use crate::common::{is_offset_safe, read_file};
+use crate::blockdev;
+use crate::bruteforce;
+use crate::common::{
+    escape_non_printable, is_offset_safe, read_file, start_parser_watchdog, stop_parser_watchdog,
+};
+use crate::credentials;
+use crate::encoded;
 use crate::extractors;
+use crate::filetype;
+use crate::firmwaredb;
+use crate::hashes;
+use crate::locked;
 use crate::magic;
+use crate::network;
+use crate::padding;
+use crate::patches;
 use crate::signatures;
+use crate::toolchain;
 
 /// Returned on initialization error
 #[derive(Debug, Default, Clone)]
@@ -42,6 +59,40 @@ pub struct AnalysisResults {
     /// File extraction results, as returned by Binwalk::extract.
     /// HashMap key is the corresponding SignatureResult.id value in `file_map`.
     pub extractions: HashMap<String, extractors::common::ExtractionResult>,
+    /// Compiler toolchain and SDK fingerprint; only populated if toolchain analysis was requested
+    #[serde(default)]
+    pub toolchain: toolchain::ToolchainFingerprint,
+    /// URLs, IP addresses, hostnames and credentials found in executables; only populated if network indicator analysis was requested
+    #[serde(default)]
+    pub network: network::NetworkIndicators,
+    /// Private keys, password hashes, AWS access keys and JWT secrets; only populated if credential analysis was requested
+    #[serde(default)]
+    pub credentials: credentials::Credentials,
+    /// Patch signature findings; only populated if patch signatures were loaded
+    #[serde(default)]
+    pub findings: Vec<patches::PatchFinding>,
+    /// Known firmware releases matching the file or its partitions; only populated if a firmware database was loaded
+    #[serde(default)]
+    pub firmware: Vec<firmwaredb::FirmwareMatch>,
+    /// Encrypted content that could not be opened; only populated if extraction was requested
+    #[serde(default)]
+    pub locked: Vec<locked::LockedContent>,
+    /// Padding outside of identified signatures; only populated if padding detection was requested
+    #[serde(default)]
+    pub padding: Vec<padding::PaddingRegion>,
+    /// How much of the file was identified or found to be padding; only populated if padding detection was requested
+    #[serde(default)]
+    pub coverage: padding::Coverage,
+    /// Suspicious, but unconfirmed, findings; these are never part of the file map, and are never extracted
+    #[serde(default)]
+    pub heuristics: Vec<signatures::common::SignatureResult>,
+    /// Hashes of the file, and of the data carved and extracted from it; only populated if hashing was requested
+    #[serde(default)]
+    pub hashes: hashes::ArtifactHashes,
+    /// Requested analyses that were not performed, such as those that need more memory than the memory budget allows;
+    /// if any are listed, the results are incomplete
+    #[serde(default)]
+    pub skipped_analyses: Vec<String>,
 }
 
 /// Analyze files / memory for file signatures
@@ -80,6 +131,24 @@ pub struct Binwalk {
     pub pattern_signature_table: HashMap<usize, signatures::common::Signature>,
     /// Maps signatures to their corresponding extractors
     pub extractor_lookup_table: HashMap<String, Option<extractors::common::Extractor>>,
+    /// Controls how external extraction utilities are run
+    pub external_options: extractors::common::ExternalOptions,
+    /// Signature parsers running longer than this are aborted and their results discarded; None means no limit
+    pub parser_timeout: Option<time::Duration>,
+    /// If not empty, only extracted files of these types (see `filetype::FILE_TYPES`) are kept
+    pub only_types: Vec<String>,
+    /// If set, zlib streams that the signature scan did not identify are brute forced, and those that decompress to at
+    /// least this many bytes are reported, even if they are truncated or corrupt
+    pub zlib_brute_force: Option<usize>,
+    /// If set, raw LZMA streams are brute forced, and those that decompress to at least this many bytes are reported
+    pub lzma_brute_force: Option<usize>,
+    /// If true, long runs of base64 and hex encoded text are decoded, and their decoded data is scanned
+    pub scan_encoded: bool,
+    /// If true, the data of each extracted signature of a known size is hashed; see `ExtractionResult.sha256`
+    pub hash_extracted_data: bool,
+    /// SHA256 hashes of data extracted from a previous version; signatures whose data has one of these hashes are not
+    /// extracted, as their contents are unchanged from that version. Requires `hash_extracted_data`.
+    pub unchanged_data: HashSet<String>,
 }
 
 impl Binwalk {
@@ -107,7 +176,8 @@ impl Binwalk {
     /// The `include` and `exclude` arguments specify include and exclude signature filters. The String values contained
     /// in these arguments must match the `Signature.name` values defined in magic.rs.
     ///
-    /// Additional user-defined signatures may be provided via the `signatures` argument.
+    /// Data-driven signatures, and any user-defined signatures, may be provided via the `signatures` argument; if
+    /// `None`, the signatures defined in the embedded signature bundle are used (see `signatures::bundle`).
     ///
     /// ## Example
     ///
@@ -190,9 +260,20 @@ impl Binwalk {
         // Load all internal signature patterns
         let mut signature_patterns = magic::patterns();
 
-        // Include any user-defined signature patterns
-        if let Some(user_defined_signature_patterns) = signatures {
-            signature_patterns.extend(user_defined_signature_patterns);
+        // Include any data-driven and user-defined signature patterns, defaulting to those of the embedded bundle
+        match signatures {
+            Some(user_defined_signature_patterns) => {
+                signature_patterns.extend(user_defined_signature_patterns)
+            }
+            None => match signatures::bundle::load_embedded_bundle() {
+                Err(e) => {
+                    return Err(BinwalkError::new(&format!(
+                        "Failed to load the embedded signature bundle: {}",
+                        e.message
+                    )));
+                }
+                Ok(bundle) => signature_patterns.extend(bundle.signatures),
+            },
         }
 
         // Load magic signatures
@@ -235,6 +316,26 @@ impl Binwalk {
             }
         }
 
+        // Streams found by a brute force scan have no magic signature of their own, but can still be extracted
+        new_instance.extractor_lookup_table.insert(
+            bruteforce::ZLIB_NAME.to_string(),
+            Some(extractors::zlib::zlib_brute_extractor()),
+        );
+        new_instance.extractor_lookup_table.insert(
+            bruteforce::LZMA_NAME.to_string(),
+            Some(extractors::lzma::raw_lzma_extractor()),
+        );
+
+        // The same goes for encoded data
+        new_instance.extractor_lookup_table.insert(
+            encoded::BASE64_NAME.to_string(),
+            Some(extractors::encoded::base64_extractor()),
+        );
+        new_instance.extractor_lookup_table.insert(
+            encoded::HEX_NAME.to_string(),
+            Some(extractors::encoded::hex_extractor()),
+        );
+
         Ok(new_instance)
     }
 
@@ -260,6 +361,38 @@ impl Binwalk {
     /// assert!(signature_results.len() > 0);
     /// ```
     pub fn scan(&self, file_data: &[u8]) -> Vec<signatures::common::SignatureResult> {
+        self.scan_with_heuristics(file_data).0
+    }
+
+    /// Scan a file for magic signatures, keeping any heuristic signature results.
+    /// Returns a list of validated magic signatures, as returned by `Binwalk::scan`, and a list of heuristic results.
+    ///
+    /// Heuristic results (see `SignatureResult.heuristic`) are suspicious, but unconfirmed, matches. They are kept
+    /// separate from the file map, do not affect which offsets are scanned, and any that fall inside a validated
+    /// signature's data are discarded.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use binwalk::Binwalk;
+    ///
+    /// let target_file = "/bin/ls";
+    /// let data_to_scan = std::fs::read(target_file).expect("Unable to read file");
+    ///
+    /// let binwalker = Binwalk::new();
+    ///
+    /// let (signature_results, heuristic_results) = binwalker.scan_with_heuristics(&data_to_scan);
+    ///
+    /// assert!(signature_results.len() > 0);
+    /// assert!(heuristic_results.iter().all(|result| result.heuristic));
+    /// ```
+    pub fn scan_with_heuristics(
+        &self,
+        file_data: &[u8],
+    ) -> (
+        Vec<signatures::common::SignatureResult>,
+        Vec<signatures::common::SignatureResult>,
+    ) {
         const FILE_START_OFFSET: usize = 0;
 
         let mut index_adjustment: usize = 0;
@@ -271,6 +404,9 @@ impl Binwalk {
         // A list of identified signatures, representing a "map" of the file data
         let mut file_map: Vec<signatures::common::SignatureResult> = vec![];
 
+        // Heuristic signature results, which are reported separately from the file map
+        let mut heuristics: Vec<signatures::common::SignatureResult> = vec![];
+
         /*
          * Check beginning of file for short signatures.
          * These signatures are only valid if they occur at the very beginning of a file.
@@ -289,10 +425,22 @@ impl Binwalk {
                         signature.description, magic_start
                     );
 
-                    if let Ok(mut signature_result) = (signature.parser)(file_data, magic_start) {
+                    if let Ok(mut signature_result) =
+                        self.run_parser(signature, file_data, magic_start)
+                    {
                         // Auto populate some signature result fields
                         signature_result_auto_populate(&mut signature_result, signature);
 
+                        // Heuristic results never claim the start of the file
+                        if signature_result.heuristic {
+                            debug!(
+                                "Found {} heuristic match at offset {:#X}",
+                                signature_result.name, signature_result.offset
+                            );
+                            heuristics.push(signature_result);
+                            continue;
+                        }
+
                         // Add this signature to the file map
                         file_map.push(signature_result.clone());
                         info!(
@@ -369,7 +517,9 @@ impl Binwalk {
                  * Invoke the signature parser to parse and validate the signature.
                  * An error indicates a false positive match for the signature type.
                  */
-                if let Ok(mut signature_result) = (signature.parser)(file_data, magic_offset) {
+                if let Ok(mut signature_result) =
+                    self.run_parser(&signature, file_data, magic_offset)
+                {
                     // Calculate the end of this signature's data
                     let signature_end_offset = signature_result.offset + signature_result.size;
 
@@ -383,6 +533,16 @@ impl Binwalk {
                     // Auto populate some signature result fields
                     signature_result_auto_populate(&mut signature_result, &signature);
 
+                    // Heuristic results do not affect the scan; keep looking for validated signatures
+                    if signature_result.heuristic {
+                        debug!(
+                            "Found {} heuristic match at offset {:#X}",
+                            signature_result.name, signature_result.offset
+                        );
+                        heuristics.push(signature_result);
+                        continue;
+                    }
+
                     // Add this signature to the file map
                     file_map.push(signature_result.clone());
 
@@ -413,6 +573,48 @@ impl Binwalk {
 
         debug!("Aho-Corasick scan found {} magic matches", file_map.len());
 
+        // Brute force any zlib streams outside of the identified signatures, if requested
+        if let Some(min_decompressed_size) = self.zlib_brute_force {
+            for mut zlib_stream in
+                bruteforce::find_zlib_streams(file_data, &file_map, min_decompressed_size)
+            {
+                info!(
+                    "Found brute forced zlib stream at offset {:#X}",
+                    zlib_stream.offset
+                );
+                zlib_stream.id = Uuid::new_v4().to_string();
+                file_map.push(zlib_stream);
+            }
+        }
+
+        // Likewise for raw LZMA streams
+        if let Some(min_decompressed_size) = self.lzma_brute_force {
+            for mut lzma_stream in
+                bruteforce::find_raw_lzma_streams(file_data, &file_map, min_decompressed_size)
+            {
+                info!(
+                    "Found brute forced raw LZMA stream at offset {:#X}",
+                    lzma_stream.offset
+                );
+                lzma_stream.id = Uuid::new_v4().to_string();
+                file_map.push(lzma_stream);
+            }
+        }
+
+        // Decode any encoded data outside of the identified signatures, and scan the decoded data, if requested
+        if self.scan_encoded {
+            for mut encoded_data in
+                encoded::find_encoded_data(file_data, &file_map, |decoded| self.scan(decoded))
+            {
+                info!(
+                    "Found {} encoded data at offset {:#X}",
+                    encoded_data.name, encoded_data.offset
+                );
+                encoded_data.id = Uuid::new_v4().to_string();
+                file_map.push(encoded_data);
+            }
+        }
+
         /*
          * A file's magic bytes do not always start at the beginning of a file, meaning that it is possible
          * that the order in which the signatures were found in the file data is not the order in which we
@@ -554,7 +756,209 @@ impl Binwalk {
 
         debug!("Found {} valid signatures", file_map.len());
 
-        file_map
+        // Heuristic results inside a validated signature's data are explained by that signature
+        heuristics.retain(|heuristic| {
+            !file_map.iter().any(|signature| {
+                heuristic.offset >= signature.offset
+                    && heuristic.offset < signature.offset + signature.size
+            })
+        });
+        heuristics.sort_by_key(|heuristic| heuristic.offset);
+        heuristics.dedup_by(|a, b| a.offset == b.offset && a.name == b.name);
+
+        debug!("Found {} heuristic matches", heuristics.len());
+
+        (file_map, heuristics)
+    }
+
+    /// Runs a signature's parser under the parser watchdog.
+    ///
+    /// If the parser exceeds `Binwalk.parser_timeout`, it is reported and its result is treated as invalid,
+    /// so that a single pathological input can not stall the entire scan.
+    fn run_parser(
+        &self,
+        signature: &signatures::common::Signature,
+        file_data: &[u8],
+        offset: usize,
+    ) -> Result<signatures::common::SignatureResult, signatures::common::SignatureError> {
+        let start_time = time::Instant::now();
+
+        start_parser_watchdog(self.parser_timeout);
+        let parser_result = (signature.parser)(file_data, offset);
+        stop_parser_watchdog();
+
+        if let Some(parser_timeout) = self.parser_timeout {
+            let run_time = start_time.elapsed();
+
+            if run_time >= parser_timeout {
+                warn!(
+                    "{} parser exceeded its time budget of {:?} at offset {:#X} (ran for {:?}); result discarded",
+                    signature.name, parser_timeout, offset, run_time
+                );
+                return Err(signatures::common::SignatureError);
+            }
+        }
+
+        parser_result
+    }
+
+    /// Scan data that is already held in memory for magic signatures.
+    ///
+    /// This is equivalent to [`Binwalk::scan`], and is intended for library consumers whose data never touches the
+    /// disk, such as firmware read out over a debug interface. The results may be extracted with [`Binwalk::extract_bytes`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use binwalk::Binwalk;
+    /// use flate2::{Compression, write::GzEncoder};
+    /// use std::io::Write;
+    ///
+    /// // Gzip compressed data, preceded by some padding, that exists only in memory
+    /// let mut encoder = GzEncoder::new(vec![0xFF; 0x100], Compression::default());
+    /// encoder.write_all(b"Hello, world!").expect("Failed to compress data");
+    /// let memory_dump = encoder.finish().expect("Failed to compress data");
+    ///
+    /// let binwalker = Binwalk::new();
+    ///
+    /// let signature_results = binwalker.scan_bytes(&memory_dump);
+    ///
+    /// assert_eq!(signature_results.len(), 1);
+    /// assert_eq!(signature_results[0].name, "gzip");
+    /// assert_eq!(signature_results[0].offset, 0x100);
+    /// ```
+    pub fn scan_bytes(&self, data: &[u8]) -> Vec<signatures::common::SignatureResult> {
+        self.scan(data)
+    }
+
+    /// Scan a [`bytes::Bytes`] buffer for magic signatures.
+    ///
+    /// Requires the `bytes` feature.
+    #[cfg(feature = "bytes")]
+    pub fn scan_shared_bytes(
+        &self,
+        data: &bytes::Bytes,
+    ) -> Vec<signatures::common::SignatureResult> {
+        self.scan_bytes(data)
+    }
+
+    /// Scan data from a reader, such as stdin, for magic signatures.
+    ///
+    /// Data is read and scanned in fixed size chunks, so the entire input never needs to be held in memory.
+    /// Each chunk is scanned along with some overlapping data from the start of the next chunk, such that
+    /// signatures which straddle chunk boundaries are still identified. Signatures whose data extends
+    /// beyond the scan window will not be reported, and signatures of unknown size will be sized to no more
+    /// than the end of the scan window.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use binwalk::Binwalk;
+    ///
+    /// let target_file = std::fs::File::open("/bin/ls").expect("Unable to open file");
+    ///
+    /// let binwalker = Binwalk::new();
+    ///
+    /// let signature_results = binwalker.scan_reader(target_file).expect("Unable to read file");
+    ///
+    /// for result in &signature_results {
+    ///     println!("{:#X}  {}", result.offset, result.description);
+    /// }
+    ///
+    /// assert!(signature_results.len() > 0);
+    /// ```
+    pub fn scan_reader(
+        &self,
+        reader: impl Read,
+    ) -> Result<Vec<signatures::common::SignatureResult>, std::io::Error> {
+        // Size of each chunk of data read from the reader
+        const CHUNK_SIZE: usize = 32 * 1024 * 1024;
+
+        self.scan_reader_chunks(reader, CHUNK_SIZE)
+    }
+
+    /// Scan data from a reader in chunks of the specified size; each chunk overlaps the next by an eighth of the chunk size
+    fn scan_reader_chunks(
+        &self,
+        mut reader: impl Read,
+        chunk_size: usize,
+    ) -> Result<Vec<signatures::common::SignatureResult>, std::io::Error> {
+        // Size of data appended to each chunk from the next chunk
+        let overlap_size = chunk_size / 8;
+        let window_size = chunk_size + overlap_size;
+
+        let mut file_map: Vec<signatures::common::SignatureResult> = vec![];
+
+        // Offset of the current scan window, relative to the start of the reader data
+        let mut window_offset: usize = 0;
+
+        // End of the last reported signature, relative to the start of the reader data
+        let mut next_valid_offset: usize = 0;
+
+        let mut window: Vec<u8> = Vec::with_capacity(window_size);
+
+        loop {
+            // Fill the scan window, keeping any overlapping data left over from the previous window
+            let bytes_wanted = window_size - window.len();
+            let bytes_read = (&mut reader)
+                .take(bytes_wanted as u64)
+                .read_to_end(&mut window)?;
+
+            let eof = bytes_read < bytes_wanted;
+
+            // Only report signatures that start within this chunk; the overlap belongs to the next window
+            let chunk_end = match eof {
+                true => window.len(),
+                false => chunk_size,
+            };
+
+            debug!(
+                "Scanning {:#X} bytes of reader data at offset {:#X}",
+                window.len(),
+                window_offset
+            );
+
+            for mut signature_result in self.scan(&window) {
+                // Short signatures are only valid at the very beginning of the data
+                if window_offset > 0
+                    && self
+                        .short_signatures
+                        .iter()
+                        .any(|signature| signature.name == signature_result.name)
+                {
+                    continue;
+                }
+
+                if signature_result.offset >= chunk_end {
+                    continue;
+                }
+
+                signature_result.offset += window_offset;
+
+                // Ignore anything that falls inside a previously reported signature's data
+                if signature_result.offset < next_valid_offset {
+                    continue;
+                }
+
+                if signature_result.confidence >= signatures::common::CONFIDENCE_MEDIUM {
+                    next_valid_offset = signature_result.offset + signature_result.size;
+                }
+
+                file_map.push(signature_result);
+            }
+
+            if eof {
+                break;
+            }
+
+            // Carry the overlapping data over to the start of the next window
+            window.drain(0..chunk_size);
+            window_offset += chunk_size;
+        }
+
+        debug!("Found {} valid signatures in reader data", file_map.len());
+
+        Ok(file_map)
     }
 
     /// Extract all extractable signatures found in a file.
@@ -605,6 +1009,38 @@ impl Binwalk {
         file_data: &[u8],
         file_name: impl Into<String>,
         file_map: &Vec<signatures::common::SignatureResult>,
+    ) -> HashMap<String, extractors::common::ExtractionResult> {
+        self.extract_window(file_data, 0, file_name, file_map)
+    }
+
+    /// Extract all extractable signatures found in a window of a file's data.
+    ///
+    /// The window starts at `window_offset` in the file, and signature offsets are relative to the start of the window.
+    /// Extraction output directories are named after each signature's offset in the file.
+    pub fn extract_window(
+        &self,
+        file_data: &[u8],
+        window_offset: usize,
+        file_name: impl Into<String>,
+        file_map: &Vec<signatures::common::SignatureResult>,
+    ) -> HashMap<String, extractors::common::ExtractionResult> {
+        self.extract_window_with_options(
+            file_data,
+            window_offset,
+            file_name,
+            file_map,
+            &self.external_options,
+        )
+    }
+
+    /// Extract the signatures found in a window of a file's data, running external utilities as specified by `external_options`
+    fn extract_window_with_options(
+        &self,
+        file_data: &[u8],
+        window_offset: usize,
+        file_name: impl Into<String>,
+        file_map: &Vec<signatures::common::SignatureResult>,
+        external_options: &extractors::common::ExternalOptions,
     ) -> HashMap<String, extractors::common::ExtractionResult> {
         let file_path = file_name.into();
         let mut extraction_results: HashMap<String, extractors::common::ExtractionResult> =
@@ -623,9 +1059,43 @@ impl Binwalk {
             match &extractor {
                 None => continue,
                 Some(_) => {
+                    // Hash the signature's data, so that data unchanged from a previous version is not extracted again
+                    let data_hash = match self.hash_extracted_data && signature.size > 0 {
+                        false => None,
+                        true => file_data
+                            .get(signature.offset..signature.offset + signature.size)
+                            .map(|signature_data| format!("{:x}", Sha256::digest(signature_data))),
+                    };
+
+                    if let Some(data_hash) = &data_hash {
+                        if self.unchanged_data.contains(data_hash) {
+                            debug!(
+                                "Skipping extraction of {} (ID: {}) at {:#X}, its data is unchanged from the previous version",
+                                signature.name, signature.id, signature.offset
+                            );
+
+                            extraction_results.insert(
+                                signature.id.clone(),
+                                extractors::common::ExtractionResult {
+                                    size: Some(signature.size),
+                                    sha256: Some(data_hash.clone()),
+                                    unchanged: true,
+                                    ..Default::default()
+                                },
+                            );
+                            continue;
+                        }
+                    }
+
                     // Run an extraction for this signature
-                    let mut extraction_result =
-                        extractors::common::execute(file_data, &file_path, signature, &extractor);
+                    let mut extraction_result = extractors::common::execute(
+                        file_data,
+                        window_offset,
+                        &file_path,
+                        signature,
+                        &extractor,
+                        external_options,
+                    );
 
                     if !extraction_result.success {
                         debug!(
@@ -656,13 +1126,22 @@ impl Binwalk {
                             // Re-run the extraction
                             extraction_result = extractors::common::execute(
                                 file_data,
+                                window_offset,
                                 &file_path,
                                 &new_signature,
                                 &extractor,
+                                external_options,
                             );
                         }
                     }
 
+                    // Drop extracted files that are not of a requested type
+                    if extraction_result.success && !self.only_types.is_empty() {
+                        self.prune_extracted_files(&extraction_result.output_directory);
+                    }
+
+                    extraction_result.sha256 = data_hash;
+
                     // Update the HashMap with the result of this extraction attempt
                     extraction_results.insert(signature.id.clone(), extraction_result);
                 }
@@ -672,6 +1151,48 @@ impl Binwalk {
         extraction_results
     }
 
+    /// Deletes extracted files from `output_directory` whose sniffed type is not listed in `Binwalk.only_types`.
+    ///
+    /// Files that contain further extractable data are kept regardless of their type, so that recursive
+    /// extraction can still find the requested file types nested inside them.
+    fn prune_extracted_files(&self, output_directory: &str) {
+        let mut pruned_count: usize = 0;
+
+        for file_path in extractors::common::get_extracted_files(output_directory) {
+            if let Some(file_type) = filetype::sniff_file(&file_path) {
+                if self
+                    .only_types
+                    .iter()
+                    .any(|only_type| only_type == file_type)
+                    || self.is_extractable_file(&file_path)
+                {
+                    continue;
+                }
+
+                match fs::remove_file(&file_path) {
+                    Err(e) => warn!("Failed to remove extracted {file_type} file {file_path}: {e}"),
+                    Ok(_) => pruned_count += 1,
+                }
+            }
+        }
+
+        debug!("Removed {pruned_count} extracted files of unwanted types from {output_directory}");
+    }
+
+    /// Returns true if scanning the specified file finds any data that would be extracted
+    fn is_extractable_file(&self, file_path: &str) -> bool {
+        match read_file(file_path) {
+            Err(_) => false,
+            Ok(file_data) => self.scan(&file_data).iter().any(|signature| {
+                !signature.extraction_declined
+                    && self
+                        .extractor_lookup_table
+                        .get(&signature.name)
+                        .is_some_and(|extractor| extractor.is_some())
+            }),
+        }
+    }
+
     /// Analyze a data buffer and optionally extract the file contents.
     ///
     /// ## Example
@@ -730,7 +1251,7 @@ impl Binwalk {
 
         // Scan file data for signatures
         debug!("Analysis start: {file_path}");
-        results.file_map = self.scan(file_data);
+        (results.file_map, results.heuristics) = self.scan_with_heuristics(file_data);
 
         // Only extract if told to, and if there were some signatures found in this file
         if do_extraction && !results.file_map.is_empty() {
@@ -740,6 +1261,7 @@ impl Binwalk {
                 results.file_map.len()
             );
             results.extractions = self.extract(file_data, &file_path, &results.file_map);
+            results.locked = locked::collect(&results.file_map, &results.extractions);
         }
 
         debug!("Analysis end: {file_path}");
@@ -747,6 +1269,165 @@ impl Binwalk {
         results
     }
 
+    /// Analyze data that is already held in memory, and optionally extract its contents.
+    ///
+    /// Unlike [`Binwalk::analyze_buf`], the data need not exist as a file on disk. Extracted files are written to
+    /// `<Binwalk.base_output_directory>/<name>.extracted`, where `name` is used only to name the extraction directory;
+    /// any leading path components in `name` are ignored. Internal extractors operate directly on `data`, while data
+    /// for external extractors is carved to the extraction directory as usual.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # fn main() { #[allow(non_snake_case)] fn _doctest_main_src_binwalk_rs_analyze_bytes() -> Result<binwalk::Binwalk, binwalk::BinwalkError> {
+    /// use binwalk::Binwalk;
+    /// use flate2::{Compression, write::GzEncoder};
+    /// use std::io::Write;
+    ///
+    /// let extraction_directory = std::env::temp_dir()
+    ///     .join("binwalk_analyze_bytes_doctest")
+    ///     .display()
+    ///     .to_string();
+    ///
+    /// // Gzip compressed data that exists only in memory
+    /// let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    /// encoder.write_all(b"Hello, world!").expect("Failed to compress data");
+    /// let memory_dump = encoder.finish().expect("Failed to compress data");
+    ///
+    /// # std::fs::remove_dir_all(&extraction_directory);
+    /// let mut binwalker = Binwalk::new();
+    /// binwalker.base_output_directory = extraction_directory.clone();
+    ///
+    /// let analysis_results = binwalker.analyze_bytes(&memory_dump, "memory.bin", true);
+    ///
+    /// assert_eq!(analysis_results.file_map.len(), 1);
+    /// assert_eq!(analysis_results.extractions.len(), 1);
+    /// assert_eq!(std::fs::read(std::path::Path::new(&extraction_directory)
+    ///     .join("memory.bin.extracted")
+    ///     .join("0")
+    ///     .join("decompressed.bin"))
+    ///     .expect("Failed to read extracted file"), b"Hello, world!");
+    /// # std::fs::remove_dir_all(&extraction_directory);
+    /// # Ok(binwalker)
+    /// # } _doctest_main_src_binwalk_rs_analyze_bytes(); }
+    /// ```
+    #[allow(dead_code)]
+    pub fn analyze_bytes(
+        &self,
+        data: &[u8],
+        name: impl Into<String>,
+        do_extraction: bool,
+    ) -> AnalysisResults {
+        self.analyze_buf(data, self.in_memory_file_path(name), do_extraction)
+    }
+
+    /// Extract the signatures found in data that is already held in memory, using only the internal extractors.
+    ///
+    /// The internal extractors operate directly on `data`, so that nothing other than the extracted files is written
+    /// to disk; signatures that can only be extracted by an external utility are not extracted. Extracted files are
+    /// written to `<Binwalk.base_output_directory>/<name>.extracted`, as with [`Binwalk::analyze_bytes`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use binwalk::Binwalk;
+    /// use flate2::{Compression, write::GzEncoder};
+    /// use std::io::Write;
+    ///
+    /// let extraction_directory = std::env::temp_dir()
+    ///     .join("binwalk_extract_bytes_doctest")
+    ///     .display()
+    ///     .to_string();
+    ///
+    /// // Gzip compressed data that exists only in memory
+    /// let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    /// encoder.write_all(b"Hello, world!").expect("Failed to compress data");
+    /// let memory_dump = encoder.finish().expect("Failed to compress data");
+    ///
+    /// # std::fs::remove_dir_all(&extraction_directory).ok();
+    /// let mut binwalker = Binwalk::new();
+    /// binwalker.base_output_directory = extraction_directory.clone();
+    ///
+    /// let signature_results = binwalker.scan_bytes(&memory_dump);
+    /// let extraction_results = binwalker.extract_bytes(&memory_dump, "memory.bin", &signature_results);
+    ///
+    /// let output_directory = std::path::Path::new(&extraction_directory)
+    ///     .join("memory.bin.extracted")
+    ///     .join("0");
+    ///
+    /// assert!(extraction_results[&signature_results[0].id].success);
+    /// assert_eq!(std::fs::read_dir(&output_directory).expect("No output directory").count(), 1);
+    /// assert_eq!(std::fs::read(output_directory.join("decompressed.bin"))
+    ///     .expect("Failed to read extracted file"), b"Hello, world!");
+    /// # std::fs::remove_dir_all(&extraction_directory).ok();
+    /// ```
+    #[allow(dead_code)]
+    pub fn extract_bytes(
+        &self,
+        data: &[u8],
+        name: impl Into<String>,
+        file_map: &Vec<signatures::common::SignatureResult>,
+    ) -> HashMap<String, extractors::common::ExtractionResult> {
+        let external_options = extractors::common::ExternalOptions {
+            internal_only: true,
+            ..self.external_options.clone()
+        };
+
+        self.extract_window_with_options(
+            data,
+            0,
+            self.in_memory_file_path(name),
+            file_map,
+            &external_options,
+        )
+    }
+
+    /// Returns the path, in the base output directory, under which the extractions of in-memory data are named
+    #[allow(dead_code)]
+    fn in_memory_file_path(&self, name: impl Into<String>) -> String {
+        let name = name.into();
+
+        // Only the final component of the name is used, so that extractions stay within the output directory
+        let file_name = match path::Path::new(&name).file_name() {
+            Some(file_name) => file_name.to_string_lossy().to_string(),
+            None => {
+                warn!("Invalid name '{name}' for in-memory data, using 'memory' instead");
+                "memory".to_string()
+            }
+        };
+
+        path::Path::new(&self.base_output_directory)
+            .join(file_name)
+            .display()
+            .to_string()
+    }
+
+    /// Analyze a [`bytes::Bytes`] buffer, and optionally extract its contents, as with [`Binwalk::analyze_bytes`].
+    ///
+    /// Requires the `bytes` feature.
+    #[cfg(feature = "bytes")]
+    pub fn analyze_shared_bytes(
+        &self,
+        data: &bytes::Bytes,
+        name: impl Into<String>,
+        do_extraction: bool,
+    ) -> AnalysisResults {
+        self.analyze_bytes(data, name, do_extraction)
+    }
+
+    /// Extract the signatures found in a [`bytes::Bytes`] buffer, as with [`Binwalk::extract_bytes`].
+    ///
+    /// Requires the `bytes` feature.
+    #[cfg(feature = "bytes")]
+    pub fn extract_shared_bytes(
+        &self,
+        data: &bytes::Bytes,
+        name: impl Into<String>,
+        file_map: &Vec<signatures::common::SignatureResult>,
+    ) -> HashMap<String, extractors::common::ExtractionResult> {
+        self.extract_bytes(data, name, file_map)
+    }
+
     /// Analyze a file on disk and optionally extract its contents.
     ///
     /// ## Example
@@ -801,6 +1482,166 @@ impl Binwalk {
 
         self.analyze_buf(&file_data, &file_path, do_extraction)
     }
+
+    /// Analyze a file on disk, and optionally extract its contents, without holding more than about
+    /// `max_memory` bytes of file data in memory at any one time.
+    ///
+    /// The file is scanned as with [`Binwalk::scan_reader`], using scan windows that fit within `max_memory`.
+    /// Each extractable signature is then extracted from a window of up to `max_memory` bytes, starting at
+    /// the signature's offset. Signatures whose data does not fit within the window are not extracted, and are
+    /// listed in the results' `skipped_analyses` instead.
+    ///
+    /// Only file data is held to the budget; signature and extraction results are kept in memory, as their size is
+    /// proportional to the number of signatures found, not to the size of the file.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use binwalk::Binwalk;
+    ///
+    /// let binwalker = Binwalk::new();
+    ///
+    /// // Hold no more than 1MB of file data in memory at a time
+    /// let analysis_results = binwalker.analyze_windowed("/bin/ls", false, 1024 * 1024);
+    ///
+    /// assert!(analysis_results.file_map.len() > 0);
+    /// ```
+    pub fn analyze_windowed(
+        &self,
+        target_file: impl Into<String>,
+        do_extraction: bool,
+        max_memory: usize,
+    ) -> AnalysisResults {
+        // Largest scan chunk size; larger chunks gain nothing but memory usage
+        const MAX_CHUNK_SIZE: usize = 32 * 1024 * 1024;
+
+        let file_path = target_file.into();
+
+        let mut results: AnalysisResults = AnalysisResults {
+            file_path: file_path.clone(),
+            ..Default::default()
+        };
+
+        let mut file = match blockdev::open(&file_path) {
+            Err(e) => {
+                error!("Failed to open file {file_path}: {e}");
+                return results;
+            }
+            Ok(file) => file,
+        };
+
+        // Each scan chunk, plus its overlap with the next chunk, must fit within the memory budget
+        let chunk_size = std::cmp::min(max_memory / 9 * 8, MAX_CHUNK_SIZE);
+
+        if chunk_size == 0 {
+            error!("A memory budget of {max_memory} bytes is too small to analyze {file_path}");
+            results.skipped_analyses.push("scan".to_string());
+            return results;
+        }
+
+        debug!("Windowed analysis start: {file_path}");
+
+        results.file_map = match self.scan_reader_chunks(&mut file, chunk_size) {
+            Err(e) => {
+                error!("Failed to read data from {file_path}: {e}");
+                vec![]
+            }
+            Ok(file_map) => file_map,
+        };
+
+        if do_extraction {
+            for signature in &results.file_map {
+                // Don't bother reading in data for signatures that won't be extracted
+                if signature.extraction_declined
+                    || self.extractor_lookup_table[&signature.name].is_none()
+                {
+                    continue;
+                }
+
+                // Extracting from truncated data would produce corrupt output; skip signatures that don't fit
+                let skipped_extraction = format!(
+                    "extraction of {} at {:#X}",
+                    signature.name, signature.offset
+                );
+
+                if signature.size > max_memory {
+                    warn!(
+                        "{} data in {} at offset {:#X} is larger than the memory budget, not extracting it",
+                        signature.name, file_path, signature.offset
+                    );
+                    results.skipped_analyses.push(skipped_extraction);
+                    continue;
+                }
+
+                let mut window: Vec<u8> = vec![];
+
+                // For signatures of an unknown size, read one byte beyond the budget to tell if the data was cut short
+                let read_size = match signature.size {
+                    0 => max_memory + 1,
+                    size => size,
+                };
+
+                if let Err(e) = file
+                    .seek(SeekFrom::Start(signature.offset as u64))
+                    .and_then(|_| (&mut file).take(read_size as u64).read_to_end(&mut window))
+                {
+                    error!(
+                        "Failed to read {} data from {} at offset {:#X}: {}",
+                        signature.name, file_path, signature.offset, e
+                    );
+                    continue;
+                }
+
+                let window_truncated = window.len() > max_memory;
+                window.truncate(max_memory);
+
+                // The signature's offset and size are relative to the window
+                let mut window_signature = signature.clone();
+                window_signature.offset = 0;
+                window_signature.size = std::cmp::min(signature.size, window.len());
+
+                for (signature_id, extraction_result) in self.extract_window(
+                    &window,
+                    signature.offset,
+                    &file_path,
+                    &vec![window_signature],
+                ) {
+                    // If the extractor may have needed data beyond the window, its output can't be trusted
+                    let extraction_complete = extraction_result
+                        .size
+                        .is_some_and(|size| size < window.len());
+
+                    if window_truncated && !extraction_complete {
+                        warn!(
+                            "{} data in {} at offset {:#X} extends beyond the memory budget, discarding its extraction",
+                            signature.name, file_path, signature.offset
+                        );
+
+                        if !extraction_result.output_directory.is_empty() {
+                            if let Err(e) = fs::remove_dir_all(&extraction_result.output_directory)
+                            {
+                                warn!(
+                                    "Failed to remove {}: {}",
+                                    extraction_result.output_directory, e
+                                );
+                            }
+                        }
+
+                        results.skipped_analyses.push(skipped_extraction.clone());
+                        continue;
+                    }
+
+                    results.extractions.insert(signature_id, extraction_result);
+                }
+            }
+
+            results.locked = locked::collect(&results.file_map, &results.extractions);
+        }
+
+        debug!("Windowed analysis end: {file_path}");
+
+        results
+    }
 }
 
 /// Initializes the extraction output directory
@@ -932,4 +1773,7 @@ fn signature_result_auto_populate(
     signature_result.id = Uuid::new_v4().to_string();
     signature_result.name = signature.name.clone();
     signature_result.always_display = signature.always_display;
+
+    // Descriptions often include file names, version strings, etc. parsed from the file data
+    signature_result.description = escape_non_printable(&signature_result.description);
 }
@@ -0,0 +1,353 @@
+//! Raw Block Device Access
+//!
+//! Allows live devices, such as `/dev/mmcblk0` or `\\.\PhysicalDrive2`, to be analyzed directly instead of from a
+//! `dd` image. Block devices report a size of zero in their file metadata, so their size is queried from the operating
+//! system instead, and some platforms require that device reads start and end on a sector boundary.
+use log::{debug, warn};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Sector size assumed for devices whose sector size can not be determined
+pub const DEFAULT_SECTOR_SIZE: usize = 512;
+
+/// Largest amount of data read from a device at once
+const MAX_READ_SIZE: usize = 1024 * 1024;
+
+/// Anything that can be both read and seeked, such as a `File` or a `BlockDevice`
+pub trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek> ReadSeek for T {}
+
+/// Returns true if the specified path is a raw block device.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::blockdev::is_block_device;
+///
+/// assert!(!is_block_device("/etc/passwd"));
+/// ```
+#[cfg(unix)]
+pub fn is_block_device(file_path: &str) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::metadata(file_path) {
+        Err(_) => false,
+        // Disks are only accessible as character devices on BSD and macOS
+        Ok(metadata) => {
+            metadata.file_type().is_block_device()
+                || (cfg!(not(target_os = "linux")) && metadata.file_type().is_char_device())
+        }
+    }
+}
+
+/// Returns true if the specified path is a raw block device.
+#[cfg(windows)]
+pub fn is_block_device(file_path: &str) -> bool {
+    // Physical drives and volumes are accessed through the device namespace, e.g. \\.\PhysicalDrive2 or \\.\C:
+    file_path.starts_with(r"\\.\")
+}
+
+/// Returns true if the specified path is a raw block device.
+#[cfg(not(any(unix, windows)))]
+pub fn is_block_device(_file_path: &str) -> bool {
+    false
+}
+
+/// Returns the size of a file, in bytes. For block devices, this is the size of the device.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::blockdev::file_size;
+///
+/// let file_size = file_size("/etc/passwd").expect("Failed to get file size");
+///
+/// assert_eq!(file_size, std::fs::metadata("/etc/passwd").unwrap().len());
+/// ```
+pub fn file_size(file_path: &str) -> Result<u64, io::Error> {
+    if is_block_device(file_path) {
+        Ok(BlockDevice::open(file_path)?.size())
+    } else {
+        Ok(std::fs::metadata(file_path)?.len())
+    }
+}
+
+/// Opens a file for reading; block devices are opened as a `BlockDevice`.
+pub fn open(file_path: &str) -> Result<Box<dyn ReadSeek>, io::Error> {
+    if is_block_device(file_path) {
+        Ok(Box::new(BlockDevice::open(file_path)?))
+    } else {
+        Ok(Box::new(File::open(file_path)?))
+    }
+}
+
+/// A raw block device, opened for reading.
+///
+/// Reads and seeks may be of any size and to any offset; the underlying device is only ever read in whole sectors.
+#[derive(Debug)]
+pub struct BlockDevice {
+    file: File,
+    size: u64,
+    sector_size: usize,
+    position: u64,
+}
+
+impl BlockDevice {
+    /// Opens a block device and queries its size and sector size.
+    pub fn open(device_path: &str) -> Result<BlockDevice, io::Error> {
+        let mut file = File::open(device_path)?;
+
+        let (size, sector_size) = match device_geometry(&file) {
+            Ok((size, sector_size)) => (size, sector_size),
+            Err(e) => {
+                warn!("Failed to query the geometry of device {device_path}: {e}");
+                (file.seek(SeekFrom::End(0))?, DEFAULT_SECTOR_SIZE)
+            }
+        };
+
+        // Sector sizes are always a power of two
+        let sector_size = match sector_size.is_power_of_two() {
+            true => sector_size,
+            false => DEFAULT_SECTOR_SIZE,
+        };
+
+        debug!("Opened device {device_path}: {size} bytes, {sector_size} bytes per sector");
+
+        Ok(BlockDevice {
+            file,
+            size,
+            sector_size,
+            position: 0,
+        })
+    }
+
+    /// Size of the device, in bytes
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Size of the device's sectors, in bytes
+    #[allow(dead_code)]
+    pub fn sector_size(&self) -> usize {
+        self.sector_size
+    }
+}
+
+impl Read for BlockDevice {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let sector_size = self.sector_size as u64;
+
+        if buf.is_empty() || self.position >= self.size {
+            return Ok(0);
+        }
+
+        // Read whole sectors spanning the requested data, up to the end of the device
+        let read_start = self.position - (self.position % sector_size);
+        let read_end = (self.position + buf.len().min(MAX_READ_SIZE) as u64)
+            .next_multiple_of(sector_size)
+            .min(self.size);
+
+        let mut sectors: Vec<u8> = vec![0; (read_end - read_start) as usize];
+        let mut sectors_read: usize = 0;
+
+        self.file.seek(SeekFrom::Start(read_start))?;
+
+        while sectors_read < sectors.len() {
+            match self.file.read(&mut sectors[sectors_read..]) {
+                Ok(0) => break,
+                Ok(n) => sectors_read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        // Copy out the requested data, which may not start at a sector boundary
+        let data_start = (self.position - read_start) as usize;
+
+        if sectors_read <= data_start {
+            return Ok(0);
+        }
+
+        let data_size = (sectors_read - data_start).min(buf.len());
+
+        buf[..data_size].copy_from_slice(&sectors[data_start..data_start + data_size]);
+        self.position += data_size as u64;
+
+        Ok(data_size)
+    }
+}
+
+impl Seek for BlockDevice {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+
+        match new_position {
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            }
+        }
+    }
+}
+
+/// Returns the size and sector size of a block device, as reported by the operating system
+#[cfg(target_os = "linux")]
+fn device_geometry(file: &File) -> Result<(u64, usize), io::Error> {
+    use std::ffi::{c_int, c_ulong};
+    use std::os::unix::io::AsRawFd;
+
+    // _IO(0x12, 104)
+    const BLKSSZGET: c_ulong = 0x1268;
+    // _IOR(0x12, 114, size_t)
+    const BLKGETSIZE64: c_ulong = 0x80001272 | ((size_of::<usize>() as c_ulong) << 16);
+
+    unsafe extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    let mut size: u64 = 0;
+    let mut sector_size: c_int = 0;
+
+    // SAFETY: both requests write a single value of the provided type to the provided pointer
+    unsafe {
+        if ioctl(file.as_raw_fd(), BLKGETSIZE64, &mut size as *mut u64) != 0
+            || ioctl(file.as_raw_fd(), BLKSSZGET, &mut sector_size as *mut c_int) != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    Ok((size, sector_size as usize))
+}
+
+/// Returns the size and sector size of a block device, as reported by the operating system
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+fn device_geometry(file: &File) -> Result<(u64, usize), io::Error> {
+    use std::ffi::{c_int, c_ulong};
+    use std::os::unix::io::AsRawFd;
+
+    unsafe extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    let mut sector_size: u32 = 0;
+    let mut size: u64 = 0;
+
+    // macOS reports the device size as a number of sectors, FreeBSD reports it in bytes
+    #[cfg(target_os = "macos")]
+    let (sector_size_request, size_request): (c_ulong, c_ulong) = (
+        // DKIOCGETBLOCKSIZE, DKIOCGETBLOCKCOUNT
+        0x40046418, 0x40086419,
+    );
+    #[cfg(target_os = "freebsd")]
+    let (sector_size_request, size_request): (c_ulong, c_ulong) = (
+        // DIOCGSECTORSIZE, DIOCGMEDIASIZE
+        0x40046480, 0x40086481,
+    );
+
+    // SAFETY: both requests write a single value of the provided type to the provided pointer
+    unsafe {
+        if ioctl(
+            file.as_raw_fd(),
+            sector_size_request,
+            &mut sector_size as *mut u32,
+        ) != 0
+            || ioctl(file.as_raw_fd(), size_request, &mut size as *mut u64) != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    let size = size * sector_size as u64;
+
+    Ok((size, sector_size as usize))
+}
+
+/// Returns the size and sector size of a block device, as reported by the operating system
+#[cfg(windows)]
+fn device_geometry(file: &File) -> Result<(u64, usize), io::Error> {
+    use std::ffi::c_void;
+    use std::os::windows::io::AsRawHandle;
+
+    const IOCTL_DISK_GET_DRIVE_GEOMETRY: u32 = 0x00070000;
+    const IOCTL_DISK_GET_LENGTH_INFO: u32 = 0x0007405C;
+
+    // Index of the BytesPerSector field in a DISK_GEOMETRY structure, when viewed as an array of u32s
+    const BYTES_PER_SECTOR_INDEX: usize = 5;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn DeviceIoControl(
+            device: *mut c_void,
+            control_code: u32,
+            in_buffer: *mut c_void,
+            in_buffer_size: u32,
+            out_buffer: *mut c_void,
+            out_buffer_size: u32,
+            bytes_returned: *mut u32,
+            overlapped: *mut c_void,
+        ) -> i32;
+    }
+
+    let mut size: i64 = 0;
+    let mut geometry: [u32; 6] = [0; 6];
+    let mut bytes_returned: u32 = 0;
+
+    // SAFETY: each output buffer is at least as large as the structure the request writes to it
+    unsafe {
+        if DeviceIoControl(
+            file.as_raw_handle(),
+            IOCTL_DISK_GET_LENGTH_INFO,
+            std::ptr::null_mut(),
+            0,
+            &mut size as *mut i64 as *mut c_void,
+            size_of::<i64>() as u32,
+            &mut bytes_returned,
+            std::ptr::null_mut(),
+        ) == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+
+        // Volumes do not support drive geometry requests; fall back to the default sector size
+        if DeviceIoControl(
+            file.as_raw_handle(),
+            IOCTL_DISK_GET_DRIVE_GEOMETRY,
+            std::ptr::null_mut(),
+            0,
+            geometry.as_mut_ptr() as *mut c_void,
+            size_of_val(&geometry) as u32,
+            &mut bytes_returned,
+            std::ptr::null_mut(),
+        ) == 0
+        {
+            geometry[BYTES_PER_SECTOR_INDEX] = DEFAULT_SECTOR_SIZE as u32;
+        }
+    }
+
+    Ok((size as u64, geometry[BYTES_PER_SECTOR_INDEX] as usize))
+}
+
+/// Returns the size and sector size of a block device, as reported by the operating system
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "freebsd",
+    windows
+)))]
+fn device_geometry(_file: &File) -> Result<(u64, usize), io::Error> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "querying device geometry is not supported on this platform",
+    ))
+}
@@ -0,0 +1,254 @@
+//! Brute force scanning for compressed data streams.
+//!
+//! Signature scans only report compressed data that decompresses completely and correctly. A brute force scan tests
+//! every offset that could start a stream with a bounded decompression instead, and reports streams that decompress to
+//! a useful amount of data even if they are truncated or corrupt. This is slower, and more prone to false positives,
+//! so it is only done on request.
+
+use crate::extractors::inflate::inflate_preview;
+use crate::extractors::lzma::{raw_lzma_decompress, raw_lzma_properties};
+use crate::extractors::zlib::zlib_partial_decompress;
+use crate::signatures::common::{CONFIDENCE_LOW, SignatureResult};
+use crate::signatures::zlib::zlib_magic;
+use crate::structures::zlib::{ZLIB_HEADER_SIZE, parse_zlib_header};
+
+/// Signature name of zlib streams found by a brute force scan
+pub const ZLIB_NAME: &str = "zlib_brute";
+
+/// Human readable description of zlib streams found by a brute force scan
+pub const ZLIB_DESCRIPTION: &str = "Zlib compressed data (brute force)";
+
+/// By default, zlib streams that decompress to fewer than this many bytes are not reported
+pub const DEFAULT_MIN_ZLIB_DECOMPRESSED_SIZE: usize = 1024;
+
+/// Signature name of raw LZMA streams found by a brute force scan
+pub const LZMA_NAME: &str = "lzma_raw";
+
+/// Human readable description of raw LZMA streams found by a brute force scan
+pub const LZMA_DESCRIPTION: &str = "Raw LZMA compressed data (brute force)";
+
+/// By default, raw LZMA streams that decompress to fewer than this many bytes are not reported; the LZMA decoder is far
+/// less strict than the deflate decoder, and happily decodes a kilobyte or two of garbage from arbitrary data
+pub const DEFAULT_MIN_LZMA_DECOMPRESSED_SIZE: usize = 32 * 1024;
+
+/// Tests every zlib header that lies outside of the signatures in `file_map` with a bounded decompression.
+/// Returns a signature result for each stream that decompresses to at least `min_decompressed_size` bytes.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::bruteforce::find_zlib_streams;
+/// use flate2::{Compression, write::ZlibEncoder};
+/// use std::io::Write;
+///
+/// let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+/// for i in 0..1000 {
+///     encoder.write_all(format!("line {}\n", i).as_bytes()).unwrap();
+/// }
+/// let zlib_stream = encoder.finish().unwrap();
+///
+/// // A truncated zlib stream, with no checksum, at offset 16
+/// let mut file_data = vec![0; 16];
+/// file_data.extend(&zlib_stream[..zlib_stream.len() - 64]);
+///
+/// let zlib_streams = find_zlib_streams(&file_data, &[], 1024);
+///
+/// assert_eq!(zlib_streams.len(), 1);
+/// assert_eq!(zlib_streams[0].offset, 16);
+/// ```
+pub fn find_zlib_streams(
+    file_data: &[u8],
+    file_map: &[SignatureResult],
+    min_decompressed_size: usize,
+) -> Vec<SignatureResult> {
+    let mut zlib_streams: Vec<SignatureResult> = vec![];
+    let magics = zlib_magic();
+
+    let mut offset: usize = 0;
+
+    while offset + ZLIB_HEADER_SIZE < file_data.len() {
+        // Streams inside of identified data have already been accounted for
+        if let Some(signature_end) = identified_signature_end(file_map, offset) {
+            offset = signature_end;
+            continue;
+        }
+
+        if magics
+            .iter()
+            .any(|magic| file_data[offset..].starts_with(magic))
+        {
+            if let Ok(zlib_header) = parse_zlib_header(&file_data[offset..]) {
+                // Cheaply rule out false positives before decompressing the entire stream
+                let preview =
+                    inflate_preview(file_data, offset + ZLIB_HEADER_SIZE, min_decompressed_size);
+
+                if preview.len() >= min_decompressed_size {
+                    let dry_run = zlib_partial_decompress(file_data, offset, None);
+
+                    if let Some(zlib_size) = dry_run.size {
+                        zlib_streams.push(SignatureResult {
+                            offset,
+                            size: std::cmp::min(
+                                zlib_size,
+                                next_signature_offset(file_data, file_map, offset) - offset,
+                            ),
+                            name: ZLIB_NAME.to_string(),
+                            confidence: CONFIDENCE_LOW,
+                            description: format!(
+                                "{}, compression level: {}, window size: {} bytes",
+                                ZLIB_DESCRIPTION,
+                                zlib_header.compression_level,
+                                zlib_header.window_size
+                            ),
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+        }
+
+        offset += 1;
+    }
+
+    bound_streams(&mut zlib_streams);
+
+    zlib_streams
+}
+
+/// Tests every offset that lies outside of the signatures in `file_map` and that could start a raw (headerless) LZMA
+/// stream with a bounded decompression, using each of the common LZMA properties.
+/// Returns a signature result for each stream that decompresses to at least `min_decompressed_size` bytes.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::bruteforce::find_raw_lzma_streams;
+/// use liblzma::stream::{LzmaOptions, Stream};
+/// use liblzma::write::XzEncoder;
+/// use std::io::Write;
+///
+/// // LZMA "alone" streams are a 13-byte header, followed by the raw LZMA stream
+/// let options = LzmaOptions::new_preset(6).unwrap();
+/// let mut encoder = XzEncoder::new_stream(Vec::new(), Stream::new_lzma_encoder(&options).unwrap());
+/// for i in 0..10000 {
+///     encoder.write_all(format!("line {}\n", i).as_bytes()).unwrap();
+/// }
+/// let lzma_stream = encoder.finish().unwrap();
+///
+/// // A raw LZMA stream at offset 16
+/// let mut file_data = vec![0xFF; 16];
+/// file_data.extend(&lzma_stream[13..]);
+///
+/// let lzma_streams = find_raw_lzma_streams(&file_data, &[], 32 * 1024);
+///
+/// assert_eq!(lzma_streams.len(), 1);
+/// assert_eq!(lzma_streams[0].offset, 16);
+/// ```
+pub fn find_raw_lzma_streams(
+    file_data: &[u8],
+    file_map: &[SignatureResult],
+    min_decompressed_size: usize,
+) -> Vec<SignatureResult> {
+    // The range coder's initial code follows its leading zero byte
+    const RANGE_CODER_INIT_SIZE: usize = 5;
+
+    // The first symbol in a stream must be a literal; the range coder only decodes one from initial codes below this
+    const FIRST_LITERAL_BOUND: u32 = 0x7FFFFC00;
+
+    // Garbage that decodes to runs of a few byte values is not worth reporting
+    const MIN_DISTINCT_BYTES: usize = 16;
+
+    let mut lzma_streams: Vec<SignatureResult> = vec![];
+
+    let mut offset: usize = 0;
+
+    while offset + RANGE_CODER_INIT_SIZE < file_data.len() {
+        if let Some(signature_end) = identified_signature_end(file_map, offset) {
+            offset = signature_end;
+            continue;
+        }
+
+        let initial_code = u32::from_be_bytes(
+            file_data[offset + 1..offset + RANGE_CODER_INIT_SIZE]
+                .try_into()
+                .unwrap(),
+        );
+
+        // Raw LZMA streams start with a zero byte; an initial code of zero is far more likely to be a run of zeros
+        if file_data[offset] == 0 && initial_code != 0 && initial_code < FIRST_LITERAL_BOUND {
+            if let Some((properties, preview)) =
+                raw_lzma_properties(file_data, offset, min_decompressed_size)
+            {
+                let mut distinct_bytes = [false; 256];
+                for byte in &preview {
+                    distinct_bytes[*byte as usize] = true;
+                }
+
+                if preview.len() >= min_decompressed_size
+                    && distinct_bytes.iter().filter(|seen| **seen).count() >= MIN_DISTINCT_BYTES
+                {
+                    let dry_run = raw_lzma_decompress(file_data, offset, None);
+
+                    if let Some(lzma_size) = dry_run.size {
+                        lzma_streams.push(SignatureResult {
+                            offset,
+                            size: std::cmp::min(
+                                lzma_size,
+                                next_signature_offset(file_data, file_map, offset) - offset,
+                            ),
+                            name: LZMA_NAME.to_string(),
+                            confidence: CONFIDENCE_LOW,
+                            description: format!(
+                                "{}, properties: {:#04X}",
+                                LZMA_DESCRIPTION, properties
+                            ),
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+        }
+
+        offset += 1;
+    }
+
+    bound_streams(&mut lzma_streams);
+
+    lzma_streams
+}
+
+/// If the offset lies inside one of the signatures in the file map, returns the end of that signature's data
+fn identified_signature_end(file_map: &[SignatureResult], offset: usize) -> Option<usize> {
+    file_map
+        .iter()
+        .find(|signature| offset >= signature.offset && offset < signature.offset + signature.size)
+        .map(|signature| signature.offset + signature.size)
+}
+
+/// Returns the offset of the first signature in the file map that starts after the offset, or EOF
+fn next_signature_offset(file_data: &[u8], file_map: &[SignatureResult], offset: usize) -> usize {
+    file_map
+        .iter()
+        .map(|signature| signature.offset)
+        .filter(|signature_offset| *signature_offset > offset)
+        .min()
+        .unwrap_or(file_data.len())
+}
+
+/// Finalizes the sizes and descriptions of brute forced streams
+fn bound_streams(streams: &mut [SignatureResult]) {
+    /*
+     * The decompressor has no way of knowing where a truncated stream ends, and will happily decompress whatever data
+     * follows it, including the next stream; assume that each stream ends no later than where the next one starts.
+     */
+    for i in 0..streams.len() {
+        if let Some(next_offset) = streams.get(i + 1).map(|next_stream| next_stream.offset) {
+            streams[i].size = std::cmp::min(streams[i].size, next_offset - streams[i].offset);
+        }
+
+        streams[i].description = format!(
+            "{}, total size: {} bytes",
+            streams[i].description, streams[i].size
+        );
+    }
+}
@@ -0,0 +1,481 @@
+//! Compares two firmware images and summarizes their differences in the style of release notes.
+//!
+//! Both images are recursively extracted, and the extracted files are compared by path and hash. Software
+//! components and their versions are harvested from the extracted files, so that the report can list which
+//! components were updated, in addition to which files were added, removed, or modified.
+
+use crate::binwalk::Binwalk;
+use crate::common::read_file;
+use crate::extractors::common::get_extracted_files;
+use crate::signatures::common::SignatureResult;
+use crate::toolchain;
+use aho_corasick::AhoCorasick;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Component, Path};
+use walkdir::WalkDir;
+
+/// Version string prefixes of commonly embedded software, and the name of the software they identify
+const SOFTWARE_VERSIONS: [(&str, &str); 13] = [
+    ("Linux version ", "Linux kernel"),
+    ("BusyBox v", "BusyBox"),
+    ("U-Boot ", "U-Boot"),
+    ("OpenSSL ", "OpenSSL"),
+    ("OpenSSH_", "OpenSSH"),
+    ("dropbear_", "Dropbear"),
+    ("dnsmasq-", "dnsmasq"),
+    ("lighttpd/", "lighttpd"),
+    ("nginx/", "nginx"),
+    ("libcurl/", "curl"),
+    ("hostapd v", "hostapd"),
+    ("wpa_supplicant v", "wpa_supplicant"),
+    ("Samba ", "Samba"),
+];
+
+/// Suffix appended to extraction directory names
+const EXTRACTION_DIRECTORY_SUFFIX: &str = ".extracted";
+
+/// Returned when a changelog can not be generated
+#[derive(Debug, Default, Clone)]
+pub struct ChangelogError {
+    pub message: String,
+}
+
+impl ChangelogError {
+    pub fn new(message: &str) -> Self {
+        ChangelogError {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// A software component whose versions differ between the old and new firmware images
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ComponentChange {
+    pub name: String,
+    /// Versions found in the old image; empty if the component was added
+    pub old_versions: Vec<String>,
+    /// Versions found in the new image; empty if the component was removed
+    pub new_versions: Vec<String>,
+}
+
+/// A configuration file that was modified between the old and new firmware images
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub path: String,
+    /// Lines that are only in the old version of the file
+    pub removed_lines: Vec<String>,
+    /// Lines that are only in the new version of the file
+    pub added_lines: Vec<String>,
+}
+
+/// Differences between two firmware images
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Changelog {
+    pub old_image: String,
+    pub new_image: String,
+    pub updated_components: Vec<ComponentChange>,
+    pub added_components: Vec<ComponentChange>,
+    pub removed_components: Vec<ComponentChange>,
+    /// Paths of extracted files, relative to the image, that are only in the new image
+    pub added_files: Vec<String>,
+    /// Paths of extracted files, relative to the image, that are only in the old image
+    pub removed_files: Vec<String>,
+    /// Paths of extracted files, other than configuration files, whose contents changed
+    pub modified_files: Vec<String>,
+    pub changed_configs: Vec<ConfigChange>,
+}
+
+impl Changelog {
+    /// Returns true if no differences were found
+    pub fn is_empty(&self) -> bool {
+        self.updated_components.is_empty()
+            && self.added_components.is_empty()
+            && self.removed_components.is_empty()
+            && self.added_files.is_empty()
+            && self.removed_files.is_empty()
+            && self.modified_files.is_empty()
+            && self.changed_configs.is_empty()
+    }
+}
+
+/// The extracted contents of a firmware image
+#[derive(Debug, Default)]
+pub struct ImageContents {
+    /// Signatures identified in the image itself
+    pub file_map: Vec<SignatureResult>,
+    /// Maps each extracted file's normalized path to its SHA256 hash and its path on disk
+    pub files: BTreeMap<String, (String, String)>,
+    /// Maps each software component to the versions of it that were found
+    pub components: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Extracts both firmware images into sub-directories of `output_directory`, and compares their contents.
+pub fn changelog(
+    old_image: &str,
+    new_image: &str,
+    output_directory: &str,
+) -> Result<Changelog, ChangelogError> {
+    let old_contents = extract_image(
+        old_image,
+        &Path::new(output_directory)
+            .join("old")
+            .display()
+            .to_string(),
+    )?;
+    let new_contents = extract_image(
+        new_image,
+        &Path::new(output_directory)
+            .join("new")
+            .display()
+            .to_string(),
+    )?;
+
+    let mut changelog = Changelog {
+        old_image: old_image.to_string(),
+        new_image: new_image.to_string(),
+        ..Default::default()
+    };
+
+    // Compare software components
+    for (name, new_versions) in &new_contents.components {
+        match old_contents.components.get(name) {
+            None => changelog.added_components.push(ComponentChange {
+                name: name.clone(),
+                new_versions: new_versions.iter().cloned().collect(),
+                ..Default::default()
+            }),
+            Some(old_versions) if old_versions != new_versions => {
+                changelog.updated_components.push(ComponentChange {
+                    name: name.clone(),
+                    old_versions: old_versions.iter().cloned().collect(),
+                    new_versions: new_versions.iter().cloned().collect(),
+                })
+            }
+            Some(_) => (),
+        }
+    }
+
+    for (name, old_versions) in &old_contents.components {
+        if !new_contents.components.contains_key(name) {
+            changelog.removed_components.push(ComponentChange {
+                name: name.clone(),
+                old_versions: old_versions.iter().cloned().collect(),
+                ..Default::default()
+            });
+        }
+    }
+
+    // Compare extracted files
+    for (path, (new_hash, new_file_path)) in &new_contents.files {
+        match old_contents.files.get(path) {
+            None => changelog.added_files.push(path.clone()),
+            Some((old_hash, old_file_path)) if old_hash != new_hash => {
+                match config_change(path, old_file_path, new_file_path) {
+                    None => changelog.modified_files.push(path.clone()),
+                    Some(config_change) => changelog.changed_configs.push(config_change),
+                }
+            }
+            Some(_) => (),
+        }
+    }
+
+    for path in old_contents.files.keys() {
+        if !new_contents.files.contains_key(path) {
+            changelog.removed_files.push(path.clone());
+        }
+    }
+
+    Ok(changelog)
+}
+
+/// Recursively extracts a firmware image, and collects the hashes and software components of its extracted files
+pub fn extract_image(image: &str, output_directory: &str) -> Result<ImageContents, ChangelogError> {
+    let binwalker = match Binwalk::configure(
+        Some(image.to_string()),
+        Some(output_directory.to_string()),
+        None,
+        None,
+        None,
+        false,
+    ) {
+        Err(e) => {
+            return Err(ChangelogError::new(&format!(
+                "Failed to extract {image}: {}",
+                e.message
+            )));
+        }
+        Ok(binwalker) => binwalker,
+    };
+
+    info!("Extracting {image} to {output_directory}");
+
+    let mut contents = ImageContents {
+        ..Default::default()
+    };
+
+    // Components may be found in the image itself, e.g., in an uncompressed bootloader
+    match read_file(image) {
+        Err(e) => {
+            return Err(ChangelogError::new(&format!("Failed to read {image}: {e}")));
+        }
+        Ok(image_data) => harvest_components(&image_data, &mut contents.components),
+    }
+
+    let mut target_files: VecDeque<String> = VecDeque::from([binwalker.base_target_file.clone()]);
+
+    while let Some(target_file) = target_files.pop_front() {
+        let results = binwalker.analyze(&target_file, true);
+
+        if target_file == binwalker.base_target_file {
+            contents.file_map = results.file_map.clone();
+        }
+
+        for extraction_result in results.extractions.values() {
+            if extraction_result.success && !extraction_result.do_not_recurse {
+                target_files.extend(get_extracted_files(&extraction_result.output_directory));
+            }
+        }
+    }
+
+    for entry in WalkDir::new(output_directory)
+        .sort_by_file_name()
+        .into_iter()
+        .flatten()
+    {
+        // Don't follow symlinks, only look at regular files
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let file_path = entry.path().display().to_string();
+
+        // Files that were extracted from, such as archives and file system images, are compared by their contents
+        let is_container = Path::new(&format!("{file_path}{EXTRACTION_DIRECTORY_SUFFIX}")).is_dir();
+
+        match read_file(&file_path) {
+            Err(e) => warn!("Failed to read {file_path}: {e}"),
+            Ok(file_data) if is_container => {
+                harvest_components(&file_data, &mut contents.components)
+            }
+            Ok(file_data) => {
+                let relative_path = entry
+                    .path()
+                    .strip_prefix(output_directory)
+                    .unwrap_or(entry.path());
+
+                // Files extracted from the same data at a different offset share the same normalized path;
+                // keep the offset in the path to tell them apart
+                let mut path = normalize_path(relative_path);
+                if contents.files.contains_key(&path) {
+                    path = relative_path.display().to_string();
+                }
+
+                let mut hasher = Sha256::new();
+                hasher.update(&file_data);
+
+                contents
+                    .files
+                    .insert(path, (format!("{:x}", hasher.finalize()), file_path));
+
+                harvest_components(&file_data, &mut contents.components);
+            }
+        }
+    }
+
+    debug!(
+        "Found {} files and {} software components in {image}",
+        contents.files.len(),
+        contents.components.len()
+    );
+
+    Ok(contents)
+}
+
+/// Normalizes the path of an extracted file so that it can be compared between images.
+///
+/// Extraction directories are named after the file and offset they were extracted from, e.g.
+/// `firmware_v1.bin.extracted/1A0000/squashfs-root/etc/passwd`; the top level extraction directory is removed, and
+/// nested extraction directories are replaced with the name of the file they were extracted from, e.g.
+/// `squashfs-root/etc/passwd`.
+pub fn normalize_path(relative_path: &Path) -> String {
+    let names: Vec<String> = relative_path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let mut normalized: Vec<&str> = vec![];
+    let mut index: usize = 0;
+
+    while index < names.len() {
+        let name = names[index].as_str();
+
+        match name.strip_suffix(EXTRACTION_DIRECTORY_SUFFIX) {
+            // Extraction directories contain one sub-directory per offset, named with the offset in hex
+            Some(file_name)
+                if names
+                    .get(index + 1)
+                    .is_some_and(|offset| usize::from_str_radix(offset, 16).is_ok()) =>
+            {
+                if index > 0 {
+                    normalized.push(file_name);
+                }
+                index += 2;
+            }
+            _ => {
+                normalized.push(name);
+                index += 1;
+            }
+        }
+    }
+
+    normalized.join("/")
+}
+
+/// Identifies software components, and their versions, in the file data
+pub fn harvest_components(file_data: &[u8], components: &mut BTreeMap<String, BTreeSet<String>>) {
+    let fingerprint = toolchain::fingerprint(file_data);
+
+    // Toolchain components are formatted as "<name> <version>", e.g., "uClibc 0.9.33.2"
+    for component in fingerprint
+        .compilers
+        .iter()
+        .chain(&fingerprint.linkers)
+        .chain(&fingerprint.libraries)
+        .chain(&fingerprint.sdks)
+    {
+        if let Some((name, version)) = split_component(component) {
+            components.entry(name).or_default().insert(version);
+        }
+    }
+
+    let searcher = match AhoCorasick::new(SOFTWARE_VERSIONS.iter().map(|(prefix, _)| prefix)) {
+        Ok(searcher) => searcher,
+        Err(_) => return,
+    };
+
+    for pattern_match in searcher.find_iter(file_data) {
+        let (_, name) = SOFTWARE_VERSIONS[pattern_match.pattern().as_usize()];
+
+        if let Some(version) = version_at(&file_data[pattern_match.end()..]) {
+            components
+                .entry(name.to_string())
+                .or_default()
+                .insert(version);
+        }
+    }
+}
+
+/// Splits a toolchain component into its name and version, e.g., "GCC 7.4.0 (Buildroot 2019.02)" is split into
+/// "GCC" and "7.4.0 (Buildroot 2019.02)". Returns None if the component has no version.
+fn split_component(component: &str) -> Option<(String, String)> {
+    let tokens: Vec<&str> = component.split_whitespace().collect();
+
+    let version_index = tokens.iter().position(|token| {
+        token.starts_with(|c: char| c.is_ascii_digit())
+            || *token == ">="
+            || (token.starts_with('r') && token[1..].starts_with(|c: char| c.is_ascii_digit()))
+    })?;
+
+    if version_index == 0 {
+        return None;
+    }
+
+    Some((
+        tokens[..version_index].join(" "),
+        tokens[version_index..].join(" "),
+    ))
+}
+
+/// Returns the version number at the start of the data, if there is one
+fn version_at(data: &[u8]) -> Option<String> {
+    // Longest version number that is expected
+    const MAX_VERSION_LENGTH: usize = 32;
+
+    let version: String = data
+        .iter()
+        .take(MAX_VERSION_LENGTH + 1)
+        .take_while(|b| b.is_ascii_alphanumeric() || b"._-+".contains(b))
+        .map(|b| *b as char)
+        .collect();
+
+    let version = version.trim_end_matches(['.', '-', '_', '+']);
+
+    if version.len() > MAX_VERSION_LENGTH
+        || !version.starts_with(|c: char| c.is_ascii_digit())
+        || !version.contains('.')
+    {
+        return None;
+    }
+
+    Some(version.to_string())
+}
+
+/// If the modified file is a text configuration file, returns the lines that were added and removed
+fn config_change(path: &str, old_file_path: &str, new_file_path: &str) -> Option<ConfigChange> {
+    // File extensions commonly used by configuration files
+    const CONFIG_EXTENSIONS: [&str; 12] = [
+        "conf", "cfg", "config", "ini", "xml", "json", "yaml", "yml", "sh", "lua", "txt", "rc",
+    ];
+    // Directories that configuration files are commonly kept in
+    const CONFIG_DIRECTORIES: [&str; 2] = ["etc", "config"];
+    // Don't list more than this many changed lines per file
+    const MAX_CHANGED_LINES: usize = 100;
+
+    let extension = Path::new(path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let is_config = CONFIG_EXTENSIONS.contains(&extension.as_str())
+        || path
+            .split('/')
+            .any(|directory| CONFIG_DIRECTORIES.contains(&directory));
+
+    if !is_config {
+        return None;
+    }
+
+    let old_lines = text_lines(&read_file(old_file_path).ok()?)?;
+    let new_lines = text_lines(&read_file(new_file_path).ok()?)?;
+
+    let old_set: BTreeSet<&String> = old_lines.iter().collect();
+    let new_set: BTreeSet<&String> = new_lines.iter().collect();
+
+    Some(ConfigChange {
+        path: path.to_string(),
+        removed_lines: old_lines
+            .iter()
+            .filter(|line| !new_set.contains(line))
+            .take(MAX_CHANGED_LINES)
+            .cloned()
+            .collect(),
+        added_lines: new_lines
+            .iter()
+            .filter(|line| !old_set.contains(line))
+            .take(MAX_CHANGED_LINES)
+            .cloned()
+            .collect(),
+    })
+}
+
+/// Splits the file data into lines of text; returns None if the data is not text
+fn text_lines(file_data: &[u8]) -> Option<Vec<String>> {
+    if file_data.contains(&0) {
+        return None;
+    }
+
+    let text = std::str::from_utf8(file_data).ok()?;
+
+    Some(
+        text.lines()
+            .map(|line| line.trim_end().replace('\t', "    "))
+            .filter(|line| !line.is_empty())
+            .collect(),
+    )
+}
@@ -1,6 +1,9 @@
-use clap::{CommandFactory, Parser};
+use crate::presets;
+use crate::report::ReportFormat;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use log::info;
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Clone, Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct CliArgs {
     /// List supported signatures and extractors
@@ -16,29 +19,81 @@ pub struct CliArgs {
     pub quiet: bool,
 
     /// During recursive extraction display *all* results
-    #[arg(short, long)]
+    #[arg(short, long, overrides_with = "no_verbose")]
     pub verbose: bool,
 
     /// Automatically extract known file types
-    #[arg(short, long)]
+    #[arg(short, long, overrides_with = "no_extract")]
     pub extract: bool,
 
     /// Carve both known and unknown file contents to disk
-    #[arg(short, long)]
+    #[arg(short, long, overrides_with = "no_carve")]
     pub carve: bool,
 
+    /// Only keep extracted files of these types
+    #[arg(long, value_delimiter = ',', num_args = 1.., value_parser = clap::builder::PossibleValuesParser::new(crate::filetype::FILE_TYPES))]
+    pub only_types: Option<Vec<String>>,
+
+    /// Clean up JTAG/serial dump artifacts in the target file's data before scanning, in the order given
+    #[arg(long, value_delimiter = ',', num_args = 1.., value_parser = clap::builder::PossibleValuesParser::new(crate::transforms::TRANSFORMS), conflicts_with = "entropy")]
+    pub transform: Option<Vec<String>>,
+
     /// Recursively scan extracted files
-    #[arg(short = 'M', long)]
+    #[arg(short = 'M', long, overrides_with = "no_matryoshka")]
     pub matryoshka: bool,
 
     /// Search for all signatures at all offsets
-    #[arg(short = 'a', long)]
+    #[arg(short = 'a', long, overrides_with = "no_search_all")]
     pub search_all: bool,
 
     /// Generate an entropy graph with Plotly
-    #[arg(short = 'E', long, conflicts_with = "extract")]
+    #[arg(short = 'E', long)]
     pub entropy: bool,
 
+    /// Fingerprint compiler toolchains and vendor SDKs
+    #[arg(short = 'T', long, overrides_with = "no_toolchain")]
+    pub toolchain: bool,
+
+    /// Identify URLs, IP addresses, hostnames and HTTP credentials compiled into executables
+    #[arg(long)]
+    pub network: bool,
+
+    /// Identify private keys, password hashes, AWS access keys and JWT secrets, and write a credentials report to the extraction directory
+    #[arg(long)]
+    pub credentials: bool,
+
+    /// Brute force zlib streams that the signature scan misses, reporting truncated or corrupt streams that decompress to at least MIN_SIZE bytes (default: 1024)
+    #[arg(long, value_name = "MIN_SIZE", num_args = 0..=1, require_equals = true)]
+    pub zlib_brute: Option<Option<usize>>,
+
+    /// Brute force raw LZMA streams that have no header, trying the common LZMA properties and reporting streams that decompress to at least MIN_SIZE bytes (default: 32768)
+    #[arg(long, value_name = "MIN_SIZE", num_args = 0..=1, require_equals = true)]
+    pub lzma_brute: Option<Option<usize>>,
+
+    /// Decode long runs of base64 and hex encoded text, and scan the decoded data
+    #[arg(long)]
+    pub encoded: bool,
+
+    /// Detect padding between identified signatures and report analysis coverage
+    #[arg(long, overrides_with = "no_padding")]
+    pub padding: bool,
+
+    /// Report suspicious, but unconfirmed, findings separately from signature results; optionally, only those of these heuristics
+    #[arg(long, value_name = "NAMES", value_delimiter = ',', num_args = 0..=1, require_equals = true, value_parser = clap::builder::PossibleValuesParser::new(crate::heuristics::HEURISTICS))]
+    pub heuristics: Option<Vec<String>>,
+
+    /// Evaluate patch signatures for known vulnerabilities from this directory
+    #[arg(long)]
+    pub patches: Option<String>,
+
+    /// Identify known firmware releases by looking up image and partition hashes in this CSV or SQLite database
+    #[arg(long, value_name = "FILE")]
+    pub firmware_db: Option<String>,
+
+    /// Evaluate the rules in this policy file against all results, and exit with a failure status if the policy fails
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["entropy", "list"])]
+    pub policy: Option<String>,
+
     /// Save entropy graph as a PNG file
     #[arg(short, long)]
     pub png: Option<String>,
@@ -47,10 +102,114 @@ pub struct CliArgs {
     #[arg(short, long)]
     pub log: Option<String>,
 
+    /// Stream each result to stdout as a JSON Line as soon as it is available
+    #[arg(long, conflicts_with_all = ["entropy", "list"])]
+    pub stream: bool,
+
+    /// Run non-interactively with conservative resource limits and JSON Lines output, for use in CI pipelines
+    #[arg(long, conflicts_with_all = ["entropy", "list"])]
+    pub ci: bool,
+
+    /// List the signatures found in the target file and select which of them to extract
+    #[arg(long, conflicts_with_all = ["stdin", "quiet", "stream", "ci", "entropy", "list"])]
+    pub interactive: bool,
+
+    /// Apply a named preset of options: quick, deep, forensics, portable, or a preset defined in the config file
+    #[arg(long, value_name = "NAME")]
+    pub preset: Option<String>,
+
+    /// Load presets from this config file instead of the default config file
+    #[arg(long, value_name = "FILE", requires = "preset")]
+    pub config: Option<String>,
+
+    /// Do not display all results during recursive extraction, even if the preset does
+    #[arg(long, overrides_with = "verbose")]
+    pub no_verbose: bool,
+
+    /// Do not extract, even if the preset does
+    #[arg(long, overrides_with = "extract")]
+    pub no_extract: bool,
+
+    /// Do not carve, even if the preset does
+    #[arg(long, overrides_with = "carve")]
+    pub no_carve: bool,
+
+    /// Do not recursively scan extracted files, even if the preset does
+    #[arg(long, overrides_with = "matryoshka")]
+    pub no_matryoshka: bool,
+
+    /// Do not search for all signatures at all offsets, even if the preset does
+    #[arg(long, overrides_with = "search_all")]
+    pub no_search_all: bool,
+
+    /// Do not fingerprint toolchains, even if the preset does
+    #[arg(long, overrides_with = "toolchain")]
+    pub no_toolchain: bool,
+
+    /// Do not detect padding, even if the preset does
+    #[arg(long, overrides_with = "padding")]
+    pub no_padding: bool,
+
+    /// Do not write a manifest, even if the preset does
+    #[arg(long, overrides_with = "manifest")]
+    pub no_manifest: bool,
+
+    /// Use external extraction utilities, even if the preset does not
+    #[arg(long, overrides_with = "internal_only")]
+    pub no_internal_only: bool,
+
     /// Manually specify the number of threads to use
     #[arg(short, long)]
     pub threads: Option<usize>,
 
+    /// Record the SHA256 hashes of all extracted files in a manifest.json file in the extraction directory
+    #[arg(long, overrides_with = "no_manifest")]
+    pub manifest: bool,
+
+    /// Include the MD5, SHA1 and SHA256 hashes of each analyzed file, each identified region of data and each extracted file in the JSON results
+    #[arg(long)]
+    pub hashes: bool,
+
+    /// Also write the hashes to a hashes.json file in the extraction directory
+    #[arg(long, requires = "hashes")]
+    pub hashes_manifest: bool,
+
+    /// Write the provenance tree of all extracted files, recording the file, offset and extractor each file was extracted from, to a provenance.json file in the extraction directory
+    #[arg(long)]
+    pub provenance: bool,
+
+    /// Only extract data, and keep extracted files, whose contents differ from those recorded in this manifest from a previous version
+    #[arg(long, value_name = "MANIFEST")]
+    pub previous: Option<String>,
+
+    /// Only use the built-in extractors; data that needs an external extraction utility is not extracted
+    #[arg(long, overrides_with = "no_internal_only")]
+    pub internal_only: bool,
+
+    /// Write a script that mounts the identified file systems to this file (a Windows OSFMount batch file if it ends in .bat or .cmd)
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["entropy", "list"])]
+    pub mount_script: Option<String>,
+
+    /// Write a report of the results to this file, as HTML if it ends in .html, Markdown if it ends in .md, or text otherwise
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["entropy", "list"])]
+    pub report: Option<String>,
+
+    /// Render the report with this Handlebars template instead of the built-in template
+    #[arg(long, value_name = "FILE", requires = "report")]
+    pub report_template: Option<String>,
+
+    /// Limit the file data held in memory to this many megabytes; larger files are analyzed in windows
+    #[arg(long, value_name = "MB", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_memory: Option<u64>,
+
+    /// Stop analyzing extracted files after this many megabytes have been extracted; internal decompressors may also decompress up to this much data
+    #[arg(long, value_name = "MB", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_extract_size: Option<u64>,
+
+    /// Abort any signature parser that runs longer than this many milliseconds on a single match
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub parser_timeout: Option<u64>,
+
     /// Do no scan for these signatures
     #[arg(short = 'x', long, value_delimiter = ',', num_args = 1..)]
     pub exclude: Option<Vec<String>>,
@@ -59,16 +218,136 @@ pub struct CliArgs {
     #[arg(short = 'y', long, value_delimiter = ',', num_args = 1.., conflicts_with = "exclude")]
     pub include: Option<Vec<String>>,
 
+    /// Load user-defined signatures from this directory
+    #[arg(long)]
+    pub plugins: Option<String>,
+
+    /// Load signature definitions from this bundle file, in place of the embedded bundle
+    #[arg(long)]
+    pub signatures: Option<String>,
+
     /// Extract files/folders to a custom directory
     #[arg(short, long, default_value = "extractions")]
     pub directory: String,
 
-    /// Path to the file to analyze
+    /// Upload extracted files and the JSON log file to this s3://bucket/prefix URL
+    #[cfg(feature = "s3")]
+    #[arg(long)]
+    pub upload: Option<String>,
+
+    /// Path to the file to analyze ('-' for stdin, or an s3://bucket/key URL if built with S3 support)
     pub file_name: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Group extracted binaries from multiple firmware images by fuzzy hash similarity
+    Cluster {
+        /// Directory containing the extraction results of each firmware image
+        results_directory: String,
+
+        /// Minimum similarity score (0-100) for binaries to be grouped together
+        #[arg(long, default_value_t = 50, value_parser = clap::value_parser!(u8).range(0..=100))]
+        threshold: u8,
+    },
+
+    /// Find private keys, certificates and password hashes that are shared between multiple firmware images
+    Secrets {
+        /// Directory containing the extraction results of each firmware image
+        results_directory: String,
+    },
+
+    /// Re-hash extracted files and compare them against a manifest written with --manifest
+    Verify {
+        /// Path to the manifest.json file in the extraction directory
+        manifest: String,
+    },
+
+    /// Compare two firmware images and summarize their differences as release notes
+    Changelog {
+        /// Path to the old firmware image
+        old_image: String,
+
+        /// Path to the new firmware image
+        new_image: String,
+
+        /// Report format
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        report: ReportFormat,
+    },
+
+    /// Compare the regions identified in, and the files extracted from, two firmware images
+    Diff {
+        /// Path to the old firmware image
+        old_image: String,
+
+        /// Path to the new firmware image
+        new_image: String,
+    },
+
+    /// Display the results of a completed analysis from a JSON log file written with --log
+    Open {
+        /// Path to the JSON log file
+        results: String,
+
+        /// Browse carved regions and extracted files over a local HTTP server
+        #[arg(long)]
+        serve: bool,
+
+        /// Address for the HTTP server to listen on
+        #[arg(long, default_value = "127.0.0.1:8000", requires = "serve")]
+        listen: String,
+    },
+
+    /// Scan and extract files on request, over gRPC; each scan extracts to a sub-directory of --directory
+    #[cfg(feature = "grpc")]
+    Grpc {
+        /// Address for the gRPC server to listen on
+        #[arg(long, default_value = "127.0.0.1:50051")]
+        listen: String,
+
+        /// File containing the bearer token that clients must present
+        #[arg(long)]
+        token_file: String,
+
+        /// Allow scans of files on the server within this directory; otherwise, files must be uploaded
+        #[arg(long)]
+        root: Option<String>,
+
+        /// Maximum number of scans to keep; the oldest completed scans are deleted to make room for new scans
+        #[arg(long, default_value_t = 64)]
+        max_scans: usize,
+
+        /// Delete completed scans, and their extracted files, after this many seconds
+        #[arg(long, default_value_t = 3600)]
+        scan_ttl: u64,
+    },
+
+    /// List supported signatures and extractors
+    List {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ListFormat::Text)]
+        format: ListFormat,
+    },
+}
+
+/// Output formats for the list of supported signatures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListFormat {
+    /// Human readable table
+    Text,
+    /// Markdown capabilities matrix, for use in documentation
+    Markdown,
 }
 
 pub fn parse() -> CliArgs {
-    let args = CliArgs::parse();
+    // File name used to request reading from stdin
+    const STDIN_FILE_NAME: &str = "-";
+
+    let mut args = CliArgs::parse();
 
     if std::env::args().len() == 1 {
         CliArgs::command()
@@ -77,5 +356,69 @@ pub fn parse() -> CliArgs {
         std::process::exit(0);
     }
 
+    // Allow `binwalk -` as shorthand for `binwalk --stdin`
+    if args.file_name.as_deref() == Some(STDIN_FILE_NAME) {
+        args.stdin = true;
+    }
+
+    // Options from the command line take precedence over the options of the selected preset
+    if let Some(preset_name) = args.preset.clone() {
+        match presets::load_preset(&preset_name, args.config.as_deref()) {
+            Err(e) => {
+                CliArgs::command()
+                    .error(clap::error::ErrorKind::InvalidValue, e.message)
+                    .exit();
+            }
+            Ok(preset) => {
+                info!("Using preset '{preset_name}': {}", preset.description);
+                preset.apply(&mut args);
+            }
+        }
+    }
+
+    // CI mode only emits machine-readable output
+    if args.ci {
+        args.stream = true;
+    }
+
+    // Signatures can only be selected from the target file's data if that data is not read from stdin
+    if args.interactive && args.stdin {
+        CliArgs::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--interactive can not be used when reading from stdin",
+            )
+            .exit();
+    }
+
+    // Interactive selection chooses which signatures to extract
+    if args.interactive {
+        args.extract = true;
+    }
+
+    // Extraction may be enabled by a preset or by --interactive, so these are validated here rather than by clap
+    if args.extract && args.entropy {
+        CliArgs::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--entropy can not be used when extracting",
+            )
+            .exit();
+    }
+
+    if !args.extract && (args.provenance || args.previous.is_some()) {
+        CliArgs::command()
+            .error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "--provenance and --previous require --extract",
+            )
+            .exit();
+    }
+
+    // Streamed JSON Lines are the only stdout output in stream mode
+    if args.stream {
+        args.quiet = true;
+    }
+
     args
 }
@@ -0,0 +1,421 @@
+//! Groups binaries extracted from multiple firmware images by fuzzy hash similarity.
+
+use crate::common::read_file;
+use crate::fuzzyhash::{
+    COMMON_SUBSTRING_LENGTH, FuzzyHash, fuzzy_compare, fuzzy_compare_parsed, fuzzy_hash,
+    parse_fuzzy_hash,
+};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Returned on clustering error
+#[derive(Debug, Default, Clone)]
+pub struct ClusterError {
+    pub message: String,
+}
+
+impl ClusterError {
+    pub fn new(message: &str) -> Self {
+        ClusterError {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// A binary extracted from a firmware image
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ClusteredBinary {
+    /// Name of the firmware image that the binary was extracted from
+    pub image: String,
+    /// Path to the binary, relative to the image's extraction directory
+    pub path: String,
+    pub md5: String,
+    pub fuzzy_hash: String,
+}
+
+/// Status of a cluster's binaries in a firmware image, relative to the previous firmware image
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BinaryStatus {
+    /// Present in the first firmware image
+    Baseline,
+    /// Identical to the previous version
+    Unchanged,
+    /// Similar, but not identical to, the previous version
+    Changed,
+    /// Not present in the previous firmware image
+    Added,
+    /// Present in the previous firmware image, but not this one
+    Removed,
+    /// Not present in this or the previous firmware image
+    Absent,
+}
+
+/// Status of a cluster's binaries in a single firmware image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageStatus {
+    pub image: String,
+    pub status: BinaryStatus,
+    /// Fuzzy hash similarity score to the previous version, for changed binaries
+    pub similarity: Option<usize>,
+    /// Paths of the cluster's binaries in this firmware image
+    pub paths: Vec<String>,
+}
+
+/// A group of similar binaries across one or more firmware images
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BinaryCluster {
+    /// The most common file name of the binaries in the cluster
+    pub name: String,
+    /// True if the binaries changed, or were added or removed, between any of the firmware images
+    pub changed: bool,
+    pub binaries: Vec<ClusteredBinary>,
+    /// Status of the cluster in each firmware image, in order
+    pub history: Vec<ImageStatus>,
+}
+
+/// Binary clustering results
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ClusterResults {
+    /// Names of the analyzed firmware images, in order
+    pub images: Vec<String>,
+    pub clusters: Vec<BinaryCluster>,
+}
+
+/// Groups binaries extracted from multiple firmware images by fuzzy hash similarity.
+///
+/// Each sub-directory of `results_directory` is treated as the extraction results of one firmware image
+/// (e.g., `firmware_v1.bin.extracted`); images are ordered by name. Binaries from different images are
+/// grouped together if their similarity score is at least `threshold` (0-100).
+pub fn cluster(results_directory: &str, threshold: usize) -> Result<ClusterResults, ClusterError> {
+    let mut results = ClusterResults {
+        ..Default::default()
+    };
+
+    let mut binaries: Vec<ClusteredBinary> = vec![];
+
+    for (image_name, image_directory) in image_directories(results_directory)? {
+        let image_binaries = find_binaries(&image_directory, &image_name);
+        debug!(
+            "Found {} binaries in {}",
+            image_binaries.len(),
+            image_directory
+        );
+
+        results.images.push(image_name);
+        binaries.extend(image_binaries);
+    }
+
+    if binaries.is_empty() {
+        return Err(ClusterError::new(&format!(
+            "No binaries found in {results_directory}"
+        )));
+    }
+
+    let parsed_hashes: Vec<Option<FuzzyHash>> = binaries
+        .iter()
+        .map(|binary| parse_fuzzy_hash(&binary.fuzzy_hash))
+        .collect();
+
+    let mut clusters = UnionFind::new(binaries.len());
+
+    // Identical binaries always belong to the same cluster
+    let mut md5_index: HashMap<&str, usize> = HashMap::new();
+    for (i, binary) in binaries.iter().enumerate() {
+        if let Some(first) = md5_index.get(binary.md5.as_str()) {
+            clusters.union(*first, i);
+        } else {
+            md5_index.insert(&binary.md5, i);
+        }
+    }
+
+    // Similar binaries must have a common substring in signatures of the same block size; use that to find candidate pairs
+    let mut substring_index: HashMap<(usize, &[u8]), Vec<usize>> = HashMap::new();
+    for (i, parsed_hash) in parsed_hashes.iter().enumerate() {
+        if let Some(hash) = parsed_hash {
+            let mut substrings: HashSet<(usize, &[u8])> = HashSet::new();
+
+            for (block_size, signature) in [
+                (hash.block_size, &hash.signature1),
+                (hash.block_size * 2, &hash.signature2),
+            ] {
+                for substring in signature.as_bytes().windows(COMMON_SUBSTRING_LENGTH) {
+                    substrings.insert((block_size, substring));
+                }
+            }
+
+            for substring in substrings {
+                substring_index.entry(substring).or_default().push(i);
+            }
+        }
+    }
+
+    let mut compared: HashSet<(usize, usize)> = HashSet::new();
+
+    for candidates in substring_index.values() {
+        for (n, i) in candidates.iter().enumerate() {
+            for j in &candidates[n + 1..] {
+                // Only binaries from different firmware images are compared
+                if binaries[*i].image == binaries[*j].image || !compared.insert((*i, *j)) {
+                    continue;
+                }
+
+                if let (Some(hash1), Some(hash2)) = (&parsed_hashes[*i], &parsed_hashes[*j]) {
+                    if fuzzy_compare_parsed(hash1, hash2) >= threshold {
+                        clusters.union(*i, *j);
+                    }
+                }
+            }
+        }
+    }
+
+    // Group binaries by cluster
+    let mut cluster_members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..binaries.len() {
+        cluster_members.entry(clusters.find(i)).or_default().push(i);
+    }
+
+    for members in cluster_members.values() {
+        let member_binaries: Vec<ClusteredBinary> =
+            members.iter().map(|i| binaries[*i].clone()).collect();
+        results
+            .clusters
+            .push(build_cluster(&results.images, member_binaries));
+    }
+
+    results.clusters.sort_by(|a, b| {
+        a.name
+            .cmp(&b.name)
+            .then(a.binaries[0].path.cmp(&b.binaries[0].path))
+    });
+
+    Ok(results)
+}
+
+/// Returns the name and extraction directory of each firmware image in the results directory, ordered by name.
+///
+/// Each sub-directory of `results_directory` is treated as the extraction results of one firmware image; the image
+/// name is the directory name, without the `.extracted` suffix.
+pub fn image_directories(results_directory: &str) -> Result<Vec<(String, String)>, ClusterError> {
+    // Suffix appended to extraction directory names
+    const EXTRACTION_DIRECTORY_SUFFIX: &str = ".extracted";
+
+    let mut image_directories: Vec<String> = vec![];
+
+    match fs::read_dir(results_directory) {
+        Err(e) => {
+            return Err(ClusterError::new(&format!(
+                "Failed to read directory {results_directory}: {e}"
+            )));
+        }
+        Ok(directory_entries) => {
+            for entry in directory_entries.flatten() {
+                // Binwalk places symlinks to the analyzed files in the extraction directory; only look at directories
+                if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                    image_directories.push(entry.path().display().to_string());
+                }
+            }
+        }
+    }
+
+    image_directories.sort();
+
+    Ok(image_directories
+        .into_iter()
+        .map(|image_directory| {
+            let image_name = Path::new(&image_directory)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let image_name = image_name
+                .strip_suffix(EXTRACTION_DIRECTORY_SUFFIX)
+                .unwrap_or(&image_name)
+                .to_string();
+
+            (image_name, image_directory)
+        })
+        .collect())
+}
+
+/// Finds all ELF and PE binaries in an image's extraction directory
+fn find_binaries(image_directory: &str, image_name: &str) -> Vec<ClusteredBinary> {
+    const ELF_MAGIC: &[u8] = b"\x7FELF";
+    const PE_MAGIC: &[u8] = b"MZ";
+
+    let mut binaries: Vec<ClusteredBinary> = vec![];
+
+    for entry in WalkDir::new(image_directory)
+        .sort_by_file_name()
+        .into_iter()
+        .flatten()
+    {
+        // Don't follow symlinks, only look at regular files
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let file_path = entry.path().display().to_string();
+
+        match read_file(&file_path) {
+            Err(e) => warn!("Failed to read {file_path}: {e}"),
+            Ok(file_data) => {
+                if file_data.starts_with(ELF_MAGIC) || file_data.starts_with(PE_MAGIC) {
+                    let relative_path = entry
+                        .path()
+                        .strip_prefix(image_directory)
+                        .map(|path| path.display().to_string())
+                        .unwrap_or(file_path.clone());
+
+                    binaries.push(ClusteredBinary {
+                        image: image_name.to_string(),
+                        path: relative_path,
+                        md5: format!("{:x}", md5::compute(&file_data)),
+                        fuzzy_hash: fuzzy_hash(&file_data),
+                    });
+                }
+            }
+        }
+    }
+
+    binaries
+}
+
+/// Determines a cluster's name and its status in each firmware image
+fn build_cluster(images: &[String], binaries: Vec<ClusteredBinary>) -> BinaryCluster {
+    let mut cluster = BinaryCluster {
+        ..Default::default()
+    };
+
+    // Name the cluster after the most common file name of its members
+    let mut name_counts: HashMap<String, usize> = HashMap::new();
+    for binary in &binaries {
+        let file_name = Path::new(&binary.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        *name_counts.entry(file_name).or_insert(0) += 1;
+    }
+
+    if let Some((name, _)) = name_counts
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+    {
+        cluster.name = name.clone();
+    }
+
+    // The last version of the cluster's binaries that was seen
+    let mut previous_version: Option<Vec<&ClusteredBinary>> = None;
+    let mut previously_present = false;
+
+    for (image_index, image) in images.iter().enumerate() {
+        let image_binaries: Vec<&ClusteredBinary> = binaries
+            .iter()
+            .filter(|binary| binary.image == *image)
+            .collect();
+
+        let mut image_status = ImageStatus {
+            image: image.clone(),
+            status: BinaryStatus::Absent,
+            similarity: None,
+            paths: image_binaries
+                .iter()
+                .map(|binary| binary.path.clone())
+                .collect(),
+        };
+
+        if image_binaries.is_empty() {
+            if previously_present {
+                image_status.status = BinaryStatus::Removed;
+            }
+        } else {
+            match &previous_version {
+                None if image_index == 0 => image_status.status = BinaryStatus::Baseline,
+                None => image_status.status = BinaryStatus::Added,
+                Some(_) if !previously_present => image_status.status = BinaryStatus::Added,
+                Some(previous_binaries) => {
+                    let previous_md5s: HashSet<&str> = previous_binaries
+                        .iter()
+                        .map(|binary| binary.md5.as_str())
+                        .collect();
+                    let current_md5s: HashSet<&str> = image_binaries
+                        .iter()
+                        .map(|binary| binary.md5.as_str())
+                        .collect();
+
+                    if previous_md5s == current_md5s {
+                        image_status.status = BinaryStatus::Unchanged;
+                    } else {
+                        image_status.status = BinaryStatus::Changed;
+                        image_status.similarity = previous_binaries
+                            .iter()
+                            .flat_map(|previous| {
+                                image_binaries.iter().map(|current| {
+                                    fuzzy_compare(&previous.fuzzy_hash, &current.fuzzy_hash)
+                                })
+                            })
+                            .max();
+                    }
+                }
+            }
+
+            previous_version = Some(image_binaries);
+        }
+
+        previously_present = !image_status.paths.is_empty();
+
+        if matches!(
+            image_status.status,
+            BinaryStatus::Changed | BinaryStatus::Added | BinaryStatus::Removed
+        ) {
+            cluster.changed = true;
+        }
+
+        cluster.history.push(image_status);
+    }
+
+    cluster.binaries = binaries;
+    cluster
+}
+
+/// Disjoint set used to group binaries into clusters
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        UnionFind {
+            parents: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // Path compression
+        let mut node = i;
+        while self.parents[node] != root {
+            let next = self.parents[node];
+            self.parents[node] = root;
+            node = next;
+        }
+
+        root
+    }
+
+    fn union(&mut self, i: usize, j: usize) {
+        let root_i = self.find(i);
+        let root_j = self.find(j);
+
+        if root_i != root_j {
+            self.parents[root_j] = root_i;
+        }
+    }
+}
@@ -1,8 +1,16 @@
 //! Common Functions
+use crate::blockdev;
 use chrono::prelude::DateTime;
 use log::{debug, error};
-use std::fs::File;
+use std::cell::Cell;
 use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    /// Time by which the signature parser running on this thread must finish, if it has a time budget
+    static PARSER_DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
 
 /// Read a data into memory, either from disk or from stdin, and return its contents.
 ///
@@ -37,7 +45,7 @@ pub fn read_stdin() -> Result<Vec<u8>, std::io::Error> {
     }
 }
 
-/// Read a file data into memory and return its contents.
+/// Read a file data into memory and return its contents. Raw block devices are read in their entirety.
 ///
 /// ## Example
 ///
@@ -54,7 +62,7 @@ pub fn read_file(file: impl Into<String>) -> Result<Vec<u8>, std::io::Error> {
     let mut file_data = Vec::new();
     let file_path = file.into();
 
-    match File::open(&file_path) {
+    match blockdev::open(&file_path) {
         Err(e) => {
             error!("Failed to open file {file_path}: {e}");
             Err(e)
@@ -186,6 +194,122 @@ pub fn is_printable_ascii(b: u8) -> bool {
     (ASCII_MIN..=ASCII_MAX).contains(&b)
 }
 
+/// Returns true if the provided byte may be part of a plain text file; this includes printable ASCII characters,
+/// tabs, and bytes that are part of UTF-8 multi-byte sequences.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::common::is_text;
+///
+/// assert!(is_text(b'\t'));
+/// assert!(is_text(0xE2));
+/// assert!(!is_text(0x00));
+/// ```
+pub fn is_text(b: u8) -> bool {
+    const TAB: u8 = 0x09;
+    const UTF8_MIN: u8 = 0x80;
+
+    is_printable_ascii(b) || b == TAB || b >= UTF8_MIN
+}
+
+/// Escapes control characters, and Unicode characters that change the direction of displayed text, in strings parsed
+/// from untrusted data. This prevents terminal escape sequences embedded in file metadata from reaching the terminal.
+///
+/// Characters up to 0xFF are escaped as `\xNN`, all others as `\u{NNNN}`.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::common::escape_non_printable;
+///
+/// assert_eq!(escape_non_printable("version: 1.0\x1B[2J"), "version: 1.0\\x1B[2J");
+/// assert_eq!(escape_non_printable("name: t\u{202E}xt.exe"), "name: t\\u{202E}xt.exe");
+/// assert_eq!(escape_non_printable("name: \u{00E9}t\u{00E9}"), "name: \u{00E9}t\u{00E9}");
+/// ```
+pub fn escape_non_printable(text: &str) -> String {
+    // Unicode bidirectional formatting characters
+    const BIDI_CONTROLS: [std::ops::RangeInclusive<char>; 3] = [
+        '\u{200E}'..='\u{200F}',
+        '\u{202A}'..='\u{202E}',
+        '\u{2066}'..='\u{2069}',
+    ];
+
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if c.is_control() || BIDI_CONTROLS.iter().any(|range| range.contains(&c)) {
+            match (c as u32) <= 0xFF {
+                true => escaped.push_str(&format!("\\x{:02X}", c as u32)),
+                false => escaped.push_str(&format!("\\u{{{:04X}}}", c as u32)),
+            }
+        } else {
+            escaped.push(c);
+        }
+    }
+
+    escaped
+}
+
+/// Escapes text for display in Markdown; non-printable characters are escaped as well, see `escape_non_printable`.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::common::markdown_text;
+///
+/// assert_eq!(markdown_text("*bold* | `code`"), "\\*bold\\* \\| \\`code\\`");
+/// ```
+pub fn markdown_text(text: &str) -> String {
+    escape_non_printable(text)
+        .chars()
+        .map(|c| match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '#' | '|' => format!("\\{c}"),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+/// Escapes text for display in HTML; non-printable characters are escaped as well, see `escape_non_printable`.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::common::html_text;
+///
+/// assert_eq!(html_text("<a href=\"x\">&</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+/// ```
+pub fn html_text(text: &str) -> String {
+    escape_non_printable(text)
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Returns a path relative to a directory, using '/' as the path separator; paths outside of the directory are
+/// returned as-is.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::common::relative_path;
+/// use std::path::Path;
+///
+/// assert_eq!(relative_path(Path::new("/tmp/extractions/a/b.bin"), "/tmp/extractions"), "a/b.bin");
+/// assert_eq!(relative_path(Path::new("/bin/ls"), "/tmp/extractions"), "/bin/ls");
+/// ```
+pub fn relative_path(path: &Path, directory: &str) -> String {
+    match path.strip_prefix(directory) {
+        Err(_) => path.display().to_string(),
+        Ok(relative_path) => relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    }
+}
+
 /// Validates data offsets to prevent out-of-bounds access and infinite loops while parsing file formats.
 ///
 /// ## Notes
@@ -212,6 +336,11 @@ pub fn is_offset_safe(
     next_offset: usize,
     last_offset: Option<usize>,
 ) -> bool {
+    // A parser that has run past its time budget is stopped at its next offset check
+    if parser_deadline_exceeded() {
+        return false;
+    }
+
     // If a previous file offset was specified, ensure that it is less than the next file offset
     if let Some(previous_offset) = last_offset {
         if previous_offset >= next_offset {
@@ -226,3 +355,53 @@ pub fn is_offset_safe(
 
     true
 }
+
+/// Starts the parser watchdog for the current thread, giving the next signature parser `budget` time to run.
+///
+/// While the watchdog is running, `is_offset_safe` and `structures::common::parse` fail once the budget
+/// has been exceeded, causing parsers stuck on pathological input to bail out early. Loops that don't go
+/// through either, such as the decompressors that parsers use to validate compressed data, check
+/// `parser_deadline_exceeded` themselves. A `budget` of None disables the watchdog.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::common::{is_offset_safe, parser_deadline_exceeded, start_parser_watchdog, stop_parser_watchdog};
+/// use binwalk::extractors::inflate::inflate_decompressor;
+/// use flate2::Compression;
+/// use flate2::write::DeflateEncoder;
+/// use std::io::Write;
+/// use std::time::Duration;
+///
+/// let mut encoder = DeflateEncoder::new(vec![], Compression::default());
+/// encoder.write_all(&[0xA5; 0x100000]).unwrap();
+/// let deflate_data = encoder.finish().unwrap();
+///
+/// start_parser_watchdog(Some(Duration::ZERO));
+/// assert!(parser_deadline_exceeded());
+/// assert!(!is_offset_safe(4, 0, None));
+/// // Valid data fails to decompress once the time budget has run out
+/// assert!(!inflate_decompressor(&deflate_data, 0, None).success);
+///
+/// stop_parser_watchdog();
+/// assert!(!parser_deadline_exceeded());
+/// assert!(is_offset_safe(4, 0, None));
+/// assert!(inflate_decompressor(&deflate_data, 0, None).success);
+/// ```
+pub fn start_parser_watchdog(budget: Option<Duration>) {
+    let deadline = budget.and_then(|duration| Instant::now().checked_add(duration));
+    PARSER_DEADLINE.with(|parser_deadline| parser_deadline.set(deadline));
+}
+
+/// Stops the parser watchdog for the current thread
+pub fn stop_parser_watchdog() {
+    PARSER_DEADLINE.with(|parser_deadline| parser_deadline.set(None));
+}
+
+/// Returns true if the parser watchdog is running on the current thread and its time budget has been exceeded
+pub fn parser_deadline_exceeded() -> bool {
+    match PARSER_DEADLINE.with(|parser_deadline| parser_deadline.get()) {
+        None => false,
+        Some(deadline) => Instant::now() >= deadline,
+    }
+}
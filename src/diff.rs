@@ -0,0 +1,313 @@
+//! Compares the contents of two firmware images.
+//!
+//! The signatures identified in each image are aligned by their order and type, so that regions that moved because
+//! data before them grew or shrank are still compared to each other. Regions are reported as added, removed or
+//! changed, and the files extracted from both images are compared by path and hash, which reveals silent patches
+//! that a vendor's release notes may not mention.
+
+use crate::changelog::extract_image;
+use crate::common::read_file;
+use crate::signatures::common::SignatureResult;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Returned when two firmware images can not be compared
+#[derive(Debug, Default, Clone)]
+pub struct DiffError {
+    pub message: String,
+}
+
+/// A region of data identified by a signature
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Region {
+    pub offset: usize,
+    /// Size of the region; for signatures of an unknown size, the region extends to the next signature
+    pub size: usize,
+    pub name: String,
+    pub description: String,
+    pub sha256: String,
+}
+
+/// A region whose data differs between the old and new images
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RegionChange {
+    pub old: Region,
+    pub new: Region,
+}
+
+/// An extracted file that differs between the old and new images
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FileChange {
+    /// Path of the extracted file, relative to the image
+    pub path: String,
+    /// SHA256 hash of the file extracted from the old image; empty if the file was added
+    pub old_sha256: String,
+    /// SHA256 hash of the file extracted from the new image; empty if the file was removed
+    pub new_sha256: String,
+}
+
+/// Differences between two firmware images
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FirmwareDiff {
+    pub old_image: String,
+    pub new_image: String,
+    /// Regions that are only in the new image
+    pub added_regions: Vec<Region>,
+    /// Regions that are only in the old image
+    pub removed_regions: Vec<Region>,
+    /// Regions that are in both images, but whose data changed
+    pub changed_regions: Vec<RegionChange>,
+    /// Number of regions whose data is the same in both images, even if their offsets differ
+    pub unchanged_regions: usize,
+    pub added_files: Vec<FileChange>,
+    pub removed_files: Vec<FileChange>,
+    pub modified_files: Vec<FileChange>,
+}
+
+impl FirmwareDiff {
+    /// Returns true if no differences were found
+    pub fn is_empty(&self) -> bool {
+        self.added_regions.is_empty()
+            && self.removed_regions.is_empty()
+            && self.changed_regions.is_empty()
+            && self.added_files.is_empty()
+            && self.removed_files.is_empty()
+            && self.modified_files.is_empty()
+    }
+}
+
+/// Recursively extracts both firmware images into sub-directories of `output_directory`, and compares the regions
+/// identified in each image, and the files extracted from them.
+pub fn diff(
+    old_image: &str,
+    new_image: &str,
+    output_directory: &str,
+) -> Result<FirmwareDiff, DiffError> {
+    let mut firmware_diff = FirmwareDiff {
+        old_image: old_image.to_string(),
+        new_image: new_image.to_string(),
+        ..Default::default()
+    };
+
+    // Recursively extract each image; this also identifies the regions in each image
+    let old_contents = match extract_image(
+        old_image,
+        &Path::new(output_directory)
+            .join("old")
+            .display()
+            .to_string(),
+    ) {
+        Err(e) => return Err(DiffError { message: e.message }),
+        Ok(contents) => contents,
+    };
+
+    let new_contents = match extract_image(
+        new_image,
+        &Path::new(output_directory)
+            .join("new")
+            .display()
+            .to_string(),
+    ) {
+        Err(e) => return Err(DiffError { message: e.message }),
+        Ok(contents) => contents,
+    };
+
+    let old_regions = image_regions(old_image, &old_contents.file_map)?;
+    let new_regions = image_regions(new_image, &new_contents.file_map)?;
+
+    let old_names: Vec<&str> = old_regions
+        .iter()
+        .map(|region| region.name.as_str())
+        .collect();
+    let new_names: Vec<&str> = new_regions
+        .iter()
+        .map(|region| region.name.as_str())
+        .collect();
+
+    let aligned = align(&old_names, &new_names);
+
+    for (old_index, new_index) in &aligned {
+        let old_region = &old_regions[*old_index];
+        let new_region = &new_regions[*new_index];
+
+        if old_region.sha256 == new_region.sha256 {
+            firmware_diff.unchanged_regions += 1;
+        } else {
+            firmware_diff.changed_regions.push(RegionChange {
+                old: old_region.clone(),
+                new: new_region.clone(),
+            });
+        }
+    }
+
+    for (index, region) in old_regions.iter().enumerate() {
+        if !aligned.iter().any(|(old_index, _)| *old_index == index) {
+            firmware_diff.removed_regions.push(region.clone());
+        }
+    }
+
+    for (index, region) in new_regions.iter().enumerate() {
+        if !aligned.iter().any(|(_, new_index)| *new_index == index) {
+            firmware_diff.added_regions.push(region.clone());
+        }
+    }
+
+    // Compare the files extracted from each image
+    for (path, (new_hash, _)) in &new_contents.files {
+        match old_contents.files.get(path) {
+            None => firmware_diff.added_files.push(FileChange {
+                path: path.clone(),
+                new_sha256: new_hash.clone(),
+                ..Default::default()
+            }),
+            Some((old_hash, _)) if old_hash != new_hash => {
+                firmware_diff.modified_files.push(FileChange {
+                    path: path.clone(),
+                    old_sha256: old_hash.clone(),
+                    new_sha256: new_hash.clone(),
+                })
+            }
+            Some(_) => (),
+        }
+    }
+
+    for (path, (old_hash, _)) in &old_contents.files {
+        if !new_contents.files.contains_key(path) {
+            firmware_diff.removed_files.push(FileChange {
+                path: path.clone(),
+                old_sha256: old_hash.clone(),
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(firmware_diff)
+}
+
+/// Aligns two sequences of signature names, matching as many names as possible without changing the order of
+/// either sequence. Returns the index pairs of the matched names; e.g., ["uimage", "lzma", "squashfs", "jffs2"] and
+/// ["uimage", "lzma", "cpio", "squashfs"] are aligned as [(0, 0), (1, 1), (2, 3)].
+fn align(old_names: &[&str], new_names: &[&str]) -> Vec<(usize, usize)> {
+    // Largest table of alignment scores that will be built; longer sequences are aligned greedily
+    const MAX_TABLE_SIZE: usize = 16 * 1024 * 1024;
+
+    let mut aligned: Vec<(usize, usize)> = vec![];
+
+    // Firmware versions usually share most of their layout; match the common prefix and suffix directly
+    let prefix_size = old_names
+        .iter()
+        .zip(new_names)
+        .take_while(|(old_name, new_name)| old_name == new_name)
+        .count();
+
+    let suffix_size = old_names[prefix_size..]
+        .iter()
+        .rev()
+        .zip(new_names[prefix_size..].iter().rev())
+        .take_while(|(old_name, new_name)| old_name == new_name)
+        .count();
+
+    let old_middle = &old_names[prefix_size..old_names.len() - suffix_size];
+    let new_middle = &new_names[prefix_size..new_names.len() - suffix_size];
+
+    aligned.extend((0..prefix_size).map(|index| (index, index)));
+
+    if (old_middle.len() + 1) * (new_middle.len() + 1) <= MAX_TABLE_SIZE {
+        // Longest common subsequence; lengths[i][j] is the LCS length of old_middle[i..] and new_middle[j..]
+        let columns = new_middle.len() + 1;
+        let mut lengths: Vec<u32> = vec![0; (old_middle.len() + 1) * columns];
+
+        for i in (0..old_middle.len()).rev() {
+            for j in (0..new_middle.len()).rev() {
+                lengths[i * columns + j] = if old_middle[i] == new_middle[j] {
+                    lengths[(i + 1) * columns + j + 1] + 1
+                } else {
+                    std::cmp::max(lengths[(i + 1) * columns + j], lengths[i * columns + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+
+        while i < old_middle.len() && j < new_middle.len() {
+            if old_middle[i] == new_middle[j] {
+                aligned.push((prefix_size + i, prefix_size + j));
+                i += 1;
+                j += 1;
+            } else if lengths[(i + 1) * columns + j] >= lengths[i * columns + j + 1] {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+    } else {
+        // Match each old name with the next new name of the same type
+        let mut next_new: usize = 0;
+
+        for (i, old_name) in old_middle.iter().enumerate() {
+            if let Some(j) = new_middle[next_new..]
+                .iter()
+                .position(|new_name| new_name == old_name)
+            {
+                aligned.push((prefix_size + i, prefix_size + next_new + j));
+                next_new += j + 1;
+            }
+        }
+    }
+
+    aligned.extend((0..suffix_size).map(|index| {
+        (
+            old_names.len() - suffix_size + index,
+            new_names.len() - suffix_size + index,
+        )
+    }));
+
+    aligned
+}
+
+/// Hashes the region of data identified by each signature in a firmware image
+fn image_regions(image: &str, file_map: &[SignatureResult]) -> Result<Vec<Region>, DiffError> {
+    let image_data = match read_file(image) {
+        Err(e) => {
+            return Err(DiffError {
+                message: format!("Failed to read {image}: {e}"),
+            });
+        }
+        Ok(data) => data,
+    };
+
+    Ok(file_map
+        .iter()
+        .enumerate()
+        .map(|(index, signature)| region(&image_data, file_map, index, signature))
+        .collect())
+}
+
+/// Hashes the region of data identified by a signature
+fn region(
+    image_data: &[u8],
+    file_map: &[SignatureResult],
+    index: usize,
+    signature: &SignatureResult,
+) -> Region {
+    let region_end = match signature.size {
+        0 => file_map
+            .get(index + 1)
+            .map(|next_signature| next_signature.offset)
+            .unwrap_or(image_data.len()),
+        size => signature.offset + size,
+    };
+
+    let region_data = image_data
+        .get(signature.offset..region_end)
+        .unwrap_or_default();
+
+    Region {
+        offset: signature.offset,
+        size: region_data.len(),
+        name: signature.name.clone(),
+        description: signature.description.clone(),
+        sha256: format!("{:x}", Sha256::digest(region_data)),
+    }
+}
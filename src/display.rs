@@ -1,6 +1,20 @@
 use crate::binwalk::AnalysisResults;
+use crate::changelog::{Changelog, ComponentChange};
+use crate::cluster::{BinaryStatus, ClusterResults};
+use crate::common::{escape_non_printable, html_text, markdown_text};
+use crate::credentials::{CredentialSummary, Credentials};
+use crate::diff::FirmwareDiff;
 use crate::extractors;
+use crate::firmwaredb::FirmwareMatch;
+use crate::locked::LockedContent;
+use crate::manifest::{DifferentialExtraction, VerificationResults};
+use crate::network::{NetworkIndicators, NetworkSummary};
+use crate::padding::{Coverage, PaddingRegion};
+use crate::patches::{PatchFinding, PatchStatus};
+use crate::policy::{PolicyResults, Severity};
+use crate::secrets::{SecretKind, SecretsResults};
 use crate::signatures;
+use crate::toolchain::{ToolchainFingerprint, ToolchainSummary};
 use colored::ColoredString;
 use colored::Colorize;
 use log::error;
@@ -142,11 +156,14 @@ fn print_footer() {
 }
 
 fn print_signature(signature: &signatures::common::SignatureResult) {
-    let decimal_string = format!("{}", signature.offset);
+    print_signature_row(&format!("{}", signature.offset), signature);
+}
+
+fn print_signature_row(first_column: &str, signature: &signatures::common::SignatureResult) {
     let hexadecimal_string = format!("{:#X}", signature.offset);
     let display_string = format!(
         "{}{}{}",
-        pad_to_length(&decimal_string, COLUMN1_WIDTH),
+        pad_to_length(first_column, COLUMN1_WIDTH),
         pad_to_length(&hexadecimal_string, COLUMN2_WIDTH),
         line_wrap(&signature.description, COLUMN1_WIDTH + COLUMN2_WIDTH)
     );
@@ -182,13 +199,47 @@ fn print_extraction(
             .yellow();
         }
         Some(extraction_result) => {
-            if extraction_result.success {
+            if extraction_result.success && extraction_result.truncated {
                 extraction_message = format!(
-                    "[+] Extraction of {} data at offset {:#X} completed successfully",
+                    "[!] Extraction of {} data at offset {:#X} completed, but decompressed data was truncated",
                     signature.name, signature.offset
                 )
                 .bold()
+                .yellow();
+            } else if extraction_result.success {
+                extraction_message = match extraction_result.decompression_limit {
+                    None => format!(
+                        "[+] Extraction of {} data at offset {:#X} completed successfully",
+                        signature.name, signature.offset
+                    ),
+                    Some(decompression_limit) => format!(
+                        "[+] Extraction of {} data at offset {:#X} completed successfully, with a raised decompression limit of {} bytes",
+                        signature.name, signature.offset, decompression_limit
+                    ),
+                }
+                .bold()
                 .green();
+            } else if extraction_result.unchanged {
+                extraction_message = format!(
+                    "[#] Extraction of {} data at offset {:#X} skipped: data is unchanged from the previous version",
+                    signature.name, signature.offset
+                )
+                .bold()
+                .yellow();
+            } else if let Some(utility) = &extraction_result.unavailable_utility {
+                extraction_message = format!(
+                    "[!] Extraction of {} data at offset {:#X} skipped: requires the external utility '{}', which is disabled in internal-only mode",
+                    signature.name, signature.offset, utility
+                )
+                .bold()
+                .yellow();
+            } else if !signature.locked.is_empty() || !extraction_result.locked.is_empty() {
+                extraction_message = format!(
+                    "[!] Extraction of {} data at offset {:#X} failed: data is encrypted",
+                    signature.name, signature.offset
+                )
+                .bold()
+                .yellow();
             } else {
                 extraction_message = format!(
                     "[-] Extraction of {} data at offset {:#X} failed!",
@@ -232,24 +283,1063 @@ fn print_extractions(
     }
 }
 
+/// Lists signature results by index, so that the user can select which signatures to extract
+pub fn print_signature_selection(
+    file_path: &str,
+    signatures: &[signatures::common::SignatureResult],
+) {
+    let title_text = center_text(&escape_non_printable(file_path));
+
+    println!();
+    println!("{}", title_text.bold().magenta());
+    print_delimiter();
+    print_column_headers("INDEX", "HEXADECIMAL", "DESCRIPTION");
+    print_delimiter();
+
+    for (index, signature) in signatures.iter().enumerate() {
+        print_signature_row(&format!("[{}]", index + 1), signature);
+    }
+
+    print_footer();
+}
+
 pub fn print_analysis_results(quiet: bool, extraction_attempted: bool, results: &AnalysisResults) {
     if quiet {
         return;
     }
 
-    // Print signature results
-    print_header(&results.file_path);
+    // Print signature results; extracted file names come from the analyzed data, and may contain control characters
+    print_header(&escape_non_printable(&results.file_path));
     print_signatures(&results.file_map);
 
+    // If toolchain analysis identified anything, print toolchain results
+    if !results.toolchain.is_empty() {
+        print_toolchain(&results.toolchain);
+    }
+
+    // If network indicator analysis identified anything, print the indicators
+    if !results.network.is_empty() {
+        print_network_indicators(&results.network);
+    }
+
+    // If credential analysis identified anything, print the credentials
+    if !results.credentials.is_empty() {
+        print_credentials(&results.credentials);
+    }
+
+    // If the file, or any of its partitions, matched a known firmware release, print the matches
+    if !results.firmware.is_empty() {
+        print_firmware_matches(&results.firmware);
+    }
+
+    // If padding detection was requested, print the padding regions and analysis coverage
+    if results.coverage.file_size > 0 {
+        print_padding(&results.padding, &results.coverage);
+    }
+
+    // If any patch signatures matched, print the findings
+    if !results.findings.is_empty() {
+        print_findings(&results.findings);
+    }
+
+    // Heuristic findings are unconfirmed, so print them apart from the signature results
+    if !results.heuristics.is_empty() {
+        print_heuristics(&results.heuristics);
+    }
+
     // If extraction was attempted, print extraction results
     if extraction_attempted {
         print_extractions(&results.file_map, &results.extractions);
     }
 
+    // If any encrypted content could not be opened, print what would unlock it
+    if extraction_attempted && !results.locked.is_empty() {
+        print_locked_content(&results.locked);
+    }
+
+    // If any requested analyses were skipped, the results are incomplete
+    if !results.skipped_analyses.is_empty() {
+        print_skipped_analyses(&results.skipped_analyses);
+    }
+
     // Print the footer text
     print_footer();
 }
 
+fn print_skipped_analyses(skipped_analyses: &[String]) {
+    print_delimiter();
+
+    println!(
+        "{}",
+        format!(
+            "[!] Results are incomplete, these analyses were skipped: {}",
+            skipped_analyses.join(", ")
+        )
+        .bold()
+        .yellow()
+    );
+}
+
+fn print_locked_content(locked_content: &[LockedContent]) {
+    print_delimiter();
+
+    for locked in locked_content {
+        // Locked content descriptions may include file names from the analyzed data
+        let description = format!(
+            "Locked content: {}, key material needed: {}",
+            escape_non_printable(&locked.description),
+            locked.key_material
+        );
+
+        let display_line = format!(
+            "{}{}{}",
+            pad_to_length(&locked.offset.to_string(), COLUMN1_WIDTH),
+            pad_to_length(&format!("{:#X}", locked.offset), COLUMN2_WIDTH),
+            line_wrap(&description, COLUMN1_WIDTH + COLUMN2_WIDTH)
+        );
+
+        println!("{}", display_line.yellow());
+    }
+}
+
+fn print_heuristics(heuristics: &[signatures::common::SignatureResult]) {
+    print_delimiter();
+
+    for heuristic in heuristics {
+        let description = format!("Unconfirmed: {}", heuristic.description);
+
+        let display_line = format!(
+            "{}{}{}",
+            pad_to_length(&heuristic.offset.to_string(), COLUMN1_WIDTH),
+            pad_to_length(&format!("{:#X}", heuristic.offset), COLUMN2_WIDTH),
+            line_wrap(&description, COLUMN1_WIDTH + COLUMN2_WIDTH)
+        );
+
+        println!("{}", display_line.yellow().dimmed());
+    }
+}
+
+fn print_firmware_matches(firmware_matches: &[FirmwareMatch]) {
+    print_delimiter();
+
+    for firmware_match in firmware_matches {
+        let description = format!(
+            "Known firmware, matches vendor {} model {} version {}, {}: {} bytes, hash: {}",
+            firmware_match.release.vendor,
+            firmware_match.release.model,
+            firmware_match.release.version,
+            firmware_match.name,
+            firmware_match.size,
+            firmware_match.hash
+        );
+
+        let display_line = format!(
+            "{}{}{}",
+            pad_to_length(&firmware_match.offset.to_string(), COLUMN1_WIDTH),
+            pad_to_length(&format!("{:#X}", firmware_match.offset), COLUMN2_WIDTH),
+            line_wrap(&description, COLUMN1_WIDTH + COLUMN2_WIDTH)
+        );
+
+        println!("{}", display_line.green());
+    }
+}
+
+fn print_padding(padding: &[PaddingRegion], coverage: &Coverage) {
+    print_delimiter();
+
+    for region in padding {
+        let display_line = format!(
+            "{}{}{}",
+            pad_to_length(&region.offset.to_string(), COLUMN1_WIDTH),
+            pad_to_length(&format!("{:#X}", region.offset), COLUMN2_WIDTH),
+            line_wrap(&region.description(), COLUMN1_WIDTH + COLUMN2_WIDTH)
+        );
+
+        println!("{}", display_line.dimmed());
+    }
+
+    let summary = format!(
+        "{} of {} bytes accounted for: {} bytes ({:.1}%) identified, {} bytes ({:.1}%) padding, {} bytes ({:.1}%) unknown",
+        coverage.identified_size + coverage.padding_size,
+        coverage.file_size,
+        coverage.identified_size,
+        coverage.percent(coverage.identified_size),
+        coverage.padding_size,
+        coverage.percent(coverage.padding_size),
+        coverage.unknown_size(),
+        coverage.percent(coverage.unknown_size()),
+    );
+
+    println!(
+        "{}{}",
+        pad_to_length("Coverage", COLUMN1_WIDTH),
+        line_wrap(&summary, COLUMN1_WIDTH)
+    );
+}
+
+fn print_toolchain(fingerprint: &ToolchainFingerprint) {
+    print_delimiter();
+
+    for (category, components) in [
+        ("Compilers", &fingerprint.compilers),
+        ("Linkers", &fingerprint.linkers),
+        ("Libraries", &fingerprint.libraries),
+        ("SDKs", &fingerprint.sdks),
+    ] {
+        if !components.is_empty() {
+            let display_line = format!(
+                "{}{}",
+                pad_to_length(category, COLUMN1_WIDTH),
+                line_wrap(&components.join(", "), COLUMN1_WIDTH)
+            );
+            println!("{}", display_line.cyan());
+        }
+    }
+}
+
+fn print_network_indicators(indicators: &NetworkIndicators) {
+    print_delimiter();
+
+    for (category, category_indicators) in [
+        ("URL", &indicators.urls),
+        ("IP address", &indicators.ip_addresses),
+        ("Hostname", &indicators.hostnames),
+        ("Credentials", &indicators.credentials),
+    ] {
+        for indicator in category_indicators {
+            let display_line = format!(
+                "{}{}{}",
+                pad_to_length(category, COLUMN1_WIDTH),
+                pad_to_length(&format!("{:#X}", indicator.offset), COLUMN2_WIDTH),
+                line_wrap(
+                    &escape_non_printable(&indicator.value),
+                    COLUMN1_WIDTH + COLUMN2_WIDTH
+                )
+            );
+
+            match category {
+                "Credentials" => println!("{}", display_line.red()),
+                _ => println!("{}", display_line.cyan()),
+            }
+        }
+    }
+}
+
+fn print_credentials(credentials: &Credentials) {
+    print_delimiter();
+
+    for (category, category_credentials) in [
+        ("Private key", &credentials.private_keys),
+        ("Password hash", &credentials.password_hashes),
+        ("AWS key", &credentials.aws_keys),
+        ("JWT", &credentials.jwt),
+    ] {
+        for credential in category_credentials {
+            let display_line = format!(
+                "{}{}{}",
+                pad_to_length(category, COLUMN1_WIDTH),
+                pad_to_length(&format!("{:#X}", credential.offset), COLUMN2_WIDTH),
+                line_wrap(
+                    &escape_non_printable(&credential.value),
+                    COLUMN1_WIDTH + COLUMN2_WIDTH
+                )
+            );
+            println!("{}", display_line.red());
+        }
+    }
+}
+
+fn print_findings(findings: &[PatchFinding]) {
+    print_delimiter();
+
+    for finding in findings {
+        let (status, offsets) = match finding.status {
+            PatchStatus::Vulnerable => ("Vulnerable", &finding.vulnerable_offsets),
+            PatchStatus::Fixed => ("Fixed", &finding.fixed_offsets),
+            PatchStatus::Inconclusive => ("Inconclusive", &finding.vulnerable_offsets),
+        };
+
+        let offset_list: Vec<String> = offsets
+            .iter()
+            .map(|offset| format!("{offset:#X}"))
+            .collect();
+
+        let mut description = format!("{}, offset: {}", finding.description, offset_list.join(", "));
+
+        if finding.status == PatchStatus::Inconclusive {
+            description = format!("{description}, vulnerable and fixed patterns both matched");
+        }
+
+        let display_line = format!(
+            "{}{}{}",
+            pad_to_length(&finding.name, COLUMN1_WIDTH),
+            pad_to_length(status, COLUMN2_WIDTH),
+            line_wrap(&description, COLUMN1_WIDTH + COLUMN2_WIDTH)
+        );
+
+        match finding.status {
+            PatchStatus::Vulnerable => println!("{}", display_line.red()),
+            PatchStatus::Fixed => println!("{}", display_line.green()),
+            PatchStatus::Inconclusive => println!("{}", display_line.yellow()),
+        }
+    }
+}
+
+pub fn print_toolchain_summary(quiet: bool, summary: &ToolchainSummary) {
+    if quiet || summary.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", center_text("Toolchain Summary").bold().magenta());
+    print_delimiter();
+    print_column_headers("CATEGORY", "FILE COUNT", "TOOLCHAIN COMPONENT");
+    print_delimiter();
+
+    for (category, components) in [
+        ("Compiler", &summary.compilers),
+        ("Linker", &summary.linkers),
+        ("Library", &summary.libraries),
+        ("SDK", &summary.sdks),
+    ] {
+        // Display the most common components first
+        let mut sorted_components: Vec<(&String, &usize)> = components.iter().collect();
+        sorted_components.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+        for (component, file_count) in sorted_components {
+            let display_line = format!(
+                "{}{}{}",
+                pad_to_length(category, COLUMN1_WIDTH),
+                pad_to_length(&file_count.to_string(), COLUMN2_WIDTH),
+                line_wrap(component, COLUMN1_WIDTH + COLUMN2_WIDTH)
+            );
+            println!("{}", display_line.cyan());
+        }
+    }
+
+    print_footer();
+}
+
+pub fn print_network_summary(quiet: bool, summary: &NetworkSummary) {
+    if quiet || summary.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", center_text("Network Indicators").bold().magenta());
+    print_delimiter();
+    print_column_headers("CATEGORY", "FILE COUNT", "INDICATOR");
+    print_delimiter();
+
+    for (category, indicators) in [
+        ("URL", &summary.urls),
+        ("IP address", &summary.ip_addresses),
+        ("Hostname", &summary.hostnames),
+        ("Credentials", &summary.credentials),
+    ] {
+        for (indicator, locations) in indicators {
+            let location_list: Vec<String> = locations
+                .iter()
+                .map(|location| {
+                    format!(
+                        "{} ({:#X})",
+                        escape_non_printable(&location.file_path),
+                        location.offset
+                    )
+                })
+                .collect();
+
+            let display_line = format!(
+                "{}{}{}",
+                pad_to_length(category, COLUMN1_WIDTH),
+                pad_to_length(&locations.len().to_string(), COLUMN2_WIDTH),
+                line_wrap(
+                    &format!(
+                        "{}, found in: {}",
+                        escape_non_printable(indicator),
+                        location_list.join(", ")
+                    ),
+                    COLUMN1_WIDTH + COLUMN2_WIDTH
+                )
+            );
+
+            match category {
+                "Credentials" => println!("{}", display_line.red()),
+                _ => println!("{}", display_line.cyan()),
+            }
+        }
+    }
+
+    print_footer();
+}
+
+pub fn print_credential_summary(quiet: bool, summary: &CredentialSummary) {
+    if quiet || summary.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", center_text("Credentials").bold().magenta());
+    print_delimiter();
+    print_column_headers("CATEGORY", "FILE COUNT", "CREDENTIAL");
+    print_delimiter();
+
+    for (category, credentials) in [
+        ("Private key", &summary.private_keys),
+        ("Password hash", &summary.password_hashes),
+        ("AWS key", &summary.aws_keys),
+        ("JWT", &summary.jwt),
+    ] {
+        for (credential, locations) in credentials {
+            let location_list: Vec<String> = locations
+                .iter()
+                .map(|location| {
+                    format!(
+                        "{} ({:#X})",
+                        escape_non_printable(&location.file_path),
+                        location.offset
+                    )
+                })
+                .collect();
+
+            let display_line = format!(
+                "{}{}{}",
+                pad_to_length(category, COLUMN1_WIDTH),
+                pad_to_length(&locations.len().to_string(), COLUMN2_WIDTH),
+                line_wrap(
+                    &format!(
+                        "{}, found in: {}",
+                        escape_non_printable(credential),
+                        location_list.join(", ")
+                    ),
+                    COLUMN1_WIDTH + COLUMN2_WIDTH
+                )
+            );
+            println!("{}", display_line.red());
+        }
+    }
+
+    print_footer();
+}
+
+pub fn print_cluster_results(quiet: bool, verbose: bool, results: &ClusterResults) {
+    if quiet {
+        return;
+    }
+
+    let binary_count: usize = results
+        .clusters
+        .iter()
+        .map(|cluster| cluster.binaries.len())
+        .sum();
+    let changed_count = results
+        .clusters
+        .iter()
+        .filter(|cluster| cluster.changed)
+        .count();
+
+    println!();
+    println!("{}", center_text("Binary Clusters").bold().magenta());
+    print_delimiter();
+    print_column_headers("BINARY / IMAGE", "STATUS", "PATH");
+    print_delimiter();
+
+    for cluster in &results.clusters {
+        // Unless verbose output was requested, only display binaries that changed between firmware images
+        if !cluster.changed && !verbose {
+            continue;
+        }
+
+        println!("{}", escape_non_printable(&cluster.name).bold());
+
+        for image_status in &cluster.history {
+            let status = match (image_status.status, image_status.similarity) {
+                (BinaryStatus::Baseline, _) => "baseline".to_string(),
+                (BinaryStatus::Unchanged, _) => "unchanged".to_string(),
+                (BinaryStatus::Changed, Some(similarity)) => {
+                    format!("changed ({similarity}% similar)")
+                }
+                (BinaryStatus::Changed, None) => "changed".to_string(),
+                (BinaryStatus::Added, _) => "added".to_string(),
+                (BinaryStatus::Removed, _) => "removed".to_string(),
+                (BinaryStatus::Absent, _) => "-".to_string(),
+            };
+
+            let display_line = format!(
+                "{}{}{}",
+                pad_to_length(&format!("    {}", image_status.image), COLUMN1_WIDTH),
+                pad_to_length(&status, COLUMN2_WIDTH),
+                line_wrap(
+                    &escape_non_printable(&image_status.paths.join(", ")),
+                    COLUMN1_WIDTH + COLUMN2_WIDTH
+                )
+            );
+
+            match image_status.status {
+                BinaryStatus::Changed => println!("{}", display_line.yellow()),
+                BinaryStatus::Added => println!("{}", display_line.green()),
+                BinaryStatus::Removed => println!("{}", display_line.red()),
+                _ => println!("{display_line}"),
+            }
+        }
+    }
+
+    print_footer();
+
+    println!(
+        "Grouped {} binaries from {} firmware images into {} clusters; {} clusters changed between images",
+        binary_count,
+        results.images.len(),
+        results.clusters.len(),
+        changed_count
+    );
+}
+
+pub fn print_shared_secrets(quiet: bool, results: &SecretsResults) {
+    if quiet {
+        return;
+    }
+
+    println!();
+    println!("{}", center_text("Shared Secrets").bold().magenta());
+    print_delimiter();
+    print_column_headers("SECRET / IMAGE", "OFFSET", "PATH");
+    print_delimiter();
+
+    for secret in &results.shared {
+        let kind = match secret.kind {
+            SecretKind::PrivateKey => "private key",
+            SecretKind::Certificate => "certificate",
+            SecretKind::PasswordHash => "password hash",
+        };
+
+        let heading = format!(
+            "{} in {} images: {}, SHA256: {}",
+            kind,
+            secret.images.len(),
+            escape_non_printable(&secret.description),
+            secret.fingerprint
+        );
+
+        match secret.kind {
+            SecretKind::Certificate => println!("{}", heading.yellow().bold()),
+            _ => println!("{}", heading.red().bold()),
+        }
+
+        for location in &secret.locations {
+            println!(
+                "{}{}{}",
+                pad_to_length(&format!("    {}", location.image), COLUMN1_WIDTH),
+                pad_to_length(&format!("{:#X}", location.offset), COLUMN2_WIDTH),
+                line_wrap(
+                    &escape_non_printable(&location.path),
+                    COLUMN1_WIDTH + COLUMN2_WIDTH
+                )
+            );
+        }
+    }
+
+    print_footer();
+
+    println!(
+        "Found {} unique secrets in {} firmware images; {} secrets are shared between images",
+        results.secret_count,
+        results.images.len(),
+        results.shared.len()
+    );
+}
+
+pub fn print_verification_results(quiet: bool, results: &VerificationResults) {
+    if quiet {
+        return;
+    }
+
+    println!();
+    println!("{}", center_text(&results.manifest).bold().magenta());
+    print_delimiter();
+    print_column_headers("STATUS", "", "PATH");
+    print_delimiter();
+
+    for (status, paths) in [
+        ("modified", &results.modified),
+        ("missing", &results.missing),
+        ("added", &results.added),
+    ] {
+        for path in paths {
+            let display_line = format!(
+                "{}{}",
+                pad_to_length(status, COLUMN1_WIDTH + COLUMN2_WIDTH),
+                line_wrap(&escape_non_printable(path), COLUMN1_WIDTH + COLUMN2_WIDTH)
+            );
+            println!("{}", display_line.red());
+        }
+    }
+
+    print_footer();
+
+    for difference in &results.environment_differences {
+        println!("{}", difference.yellow());
+    }
+
+    let summary = format!(
+        "Verified {} files; {} modified, {} missing, {} not in manifest",
+        results.verified,
+        results.modified.len(),
+        results.missing.len(),
+        results.added.len()
+    );
+
+    match results.is_ok() {
+        true => println!("{}", summary.green()),
+        false => println!("{}", summary.red()),
+    }
+}
+
+pub fn print_differential_summary(quiet: bool, differential: &DifferentialExtraction) {
+    if quiet {
+        return;
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Kept {} changed extracted files; removed {} files ({} bytes) unchanged from the previous version; skipped extraction of {} signatures ({} bytes) unchanged from the previous version",
+            differential.changed_count,
+            differential.unchanged.len(),
+            differential.unchanged_size,
+            differential.skipped_count,
+            differential.skipped_size
+        )
+        .green()
+    );
+}
+
+pub fn print_policy_results(quiet: bool, results: &PolicyResults) {
+    if quiet {
+        return;
+    }
+
+    println!();
+    println!("{}", center_text(&results.policy).bold().magenta());
+    print_delimiter();
+    print_column_headers("SEVERITY", "RULE", "DETAILS");
+    print_delimiter();
+
+    for violation in &results.violations {
+        let display_line = format!(
+            "{}{}{}",
+            pad_to_length(&violation.severity.to_string(), COLUMN1_WIDTH),
+            pad_to_length(&violation.rule, COLUMN2_WIDTH),
+            line_wrap(
+                &escape_non_printable(&format!("{}: {}", violation.file_path, violation.evidence)),
+                COLUMN1_WIDTH + COLUMN2_WIDTH
+            )
+        );
+
+        match violation.severity {
+            severity if severity >= results.fail_on => println!("{}", display_line.red()),
+            Severity::Info => println!("{display_line}"),
+            _ => println!("{}", display_line.yellow()),
+        }
+    }
+
+    print_footer();
+
+    let summary = format!(
+        "Evaluated {} policy rules; {} violations, failing on {} severity or higher",
+        results.rule_count,
+        results.violations.len(),
+        results.fail_on
+    );
+
+    match results.passed() {
+        true => println!("{}", format!("Policy passed: {summary}").green()),
+        false => println!("{}", format!("Policy failed: {summary}").red()),
+    }
+}
+
+pub fn print_changelog(quiet: bool, changelog: &Changelog) {
+    if quiet {
+        return;
+    }
+
+    println!();
+    println!(
+        "{}",
+        center_text(&format!(
+            "{} -> {}",
+            changelog.old_image, changelog.new_image
+        ))
+        .bold()
+        .magenta()
+    );
+    print_delimiter();
+    print_column_headers("CHANGE", "", "DETAILS");
+    print_delimiter();
+
+    for (change, components) in [
+        ("updated component", &changelog.updated_components),
+        ("added component", &changelog.added_components),
+        ("removed component", &changelog.removed_components),
+    ] {
+        for component in components {
+            let display_line = format!(
+                "{}{}",
+                pad_to_length(change, COLUMN1_WIDTH + COLUMN2_WIDTH),
+                line_wrap(
+                    &escape_non_printable(&component_versions(component)),
+                    COLUMN1_WIDTH + COLUMN2_WIDTH
+                )
+            );
+            println!("{}", display_line.bold());
+        }
+    }
+
+    for (change, paths) in [
+        ("added file", &changelog.added_files),
+        ("removed file", &changelog.removed_files),
+        ("modified file", &changelog.modified_files),
+    ] {
+        for path in paths {
+            let display_line = format!(
+                "{}{}",
+                pad_to_length(change, COLUMN1_WIDTH + COLUMN2_WIDTH),
+                line_wrap(&escape_non_printable(path), COLUMN1_WIDTH + COLUMN2_WIDTH)
+            );
+
+            match change {
+                "added file" => println!("{}", display_line.green()),
+                "removed file" => println!("{}", display_line.red()),
+                _ => println!("{}", display_line.yellow()),
+            }
+        }
+    }
+
+    for config in &changelog.changed_configs {
+        println!(
+            "{}{}",
+            pad_to_length("changed config", COLUMN1_WIDTH + COLUMN2_WIDTH),
+            line_wrap(
+                &escape_non_printable(&config.path),
+                COLUMN1_WIDTH + COLUMN2_WIDTH
+            )
+        );
+
+        for line in &config.removed_lines {
+            println!("{}", format!("    - {}", escape_non_printable(line)).red());
+        }
+
+        for line in &config.added_lines {
+            println!(
+                "{}",
+                format!("    + {}", escape_non_printable(line)).green()
+            );
+        }
+    }
+
+    print_footer();
+
+    println!(
+        "{} components updated, {} added, {} removed; {} files added, {} removed, {} modified; {} configs changed",
+        changelog.updated_components.len(),
+        changelog.added_components.len(),
+        changelog.removed_components.len(),
+        changelog.added_files.len(),
+        changelog.removed_files.len(),
+        changelog.modified_files.len(),
+        changelog.changed_configs.len()
+    );
+}
+
+pub fn print_changelog_markdown(quiet: bool, changelog: &Changelog) {
+    if quiet {
+        return;
+    }
+
+    println!(
+        "# Changes from {} to {}",
+        markdown_text(&changelog.old_image),
+        markdown_text(&changelog.new_image)
+    );
+
+    if changelog.is_empty() {
+        println!();
+        println!("No changes.");
+        return;
+    }
+
+    for (heading, components) in [
+        ("Updated Components", &changelog.updated_components),
+        ("Added Components", &changelog.added_components),
+        ("Removed Components", &changelog.removed_components),
+    ] {
+        if !components.is_empty() {
+            println!();
+            println!("## {heading}");
+            println!();
+
+            for component in components {
+                println!("- {}", markdown_text(&component_versions(component)));
+            }
+        }
+    }
+
+    for (heading, paths) in [
+        ("Added Files", &changelog.added_files),
+        ("Removed Files", &changelog.removed_files),
+        ("Modified Files", &changelog.modified_files),
+    ] {
+        if !paths.is_empty() {
+            println!();
+            println!("## {heading}");
+            println!();
+
+            for path in paths {
+                println!("- `{}`", markdown_bytes(path.as_bytes()));
+            }
+        }
+    }
+
+    if !changelog.changed_configs.is_empty() {
+        println!();
+        println!("## Changed Configuration");
+
+        for config in &changelog.changed_configs {
+            println!();
+            println!("### `{}`", markdown_bytes(config.path.as_bytes()));
+            println!();
+            // Configuration lines may contain a three backtick fence, but are unlikely to contain four
+            println!("````diff");
+
+            for line in &config.removed_lines {
+                println!("- {}", escape_non_printable(line));
+            }
+
+            for line in &config.added_lines {
+                println!("+ {}", escape_non_printable(line));
+            }
+
+            println!("````");
+        }
+    }
+}
+
+pub fn print_changelog_html(quiet: bool, changelog: &Changelog) {
+    if quiet {
+        return;
+    }
+
+    let title = format!(
+        "Changes from {} to {}",
+        html_text(&changelog.old_image),
+        html_text(&changelog.new_image)
+    );
+
+    println!("<!DOCTYPE html>");
+    println!("<html>");
+    println!("<head>");
+    println!("<meta charset=\"utf-8\">");
+    println!("<title>{title}</title>");
+    println!("<style>");
+    println!("body {{ font-family: sans-serif; max-width: 60em; margin: auto; }}");
+    println!("code, pre {{ background: #f4f4f4; }}");
+    println!(".added {{ color: #22863a; }}");
+    println!(".removed {{ color: #cb2431; }}");
+    println!("</style>");
+    println!("</head>");
+    println!("<body>");
+    println!("<h1>{title}</h1>");
+
+    if changelog.is_empty() {
+        println!("<p>No changes.</p>");
+    }
+
+    for (heading, components) in [
+        ("Updated Components", &changelog.updated_components),
+        ("Added Components", &changelog.added_components),
+        ("Removed Components", &changelog.removed_components),
+    ] {
+        if !components.is_empty() {
+            println!("<h2>{heading}</h2>");
+            println!("<ul>");
+
+            for component in components {
+                println!("<li>{}</li>", html_text(&component_versions(component)));
+            }
+
+            println!("</ul>");
+        }
+    }
+
+    for (heading, class, paths) in [
+        ("Added Files", "added", &changelog.added_files),
+        ("Removed Files", "removed", &changelog.removed_files),
+        ("Modified Files", "modified", &changelog.modified_files),
+    ] {
+        if !paths.is_empty() {
+            println!("<h2>{heading}</h2>");
+            println!("<ul class=\"{class}\">");
+
+            for path in paths {
+                println!("<li><code>{}</code></li>", html_text(path));
+            }
+
+            println!("</ul>");
+        }
+    }
+
+    if !changelog.changed_configs.is_empty() {
+        println!("<h2>Changed Configuration</h2>");
+
+        for config in &changelog.changed_configs {
+            println!("<h3><code>{}</code></h3>", html_text(&config.path));
+            println!("<pre>");
+
+            for line in &config.removed_lines {
+                println!("<span class=\"removed\">- {}</span>", html_text(line));
+            }
+
+            for line in &config.added_lines {
+                println!("<span class=\"added\">+ {}</span>", html_text(line));
+            }
+
+            println!("</pre>");
+        }
+    }
+
+    println!("</body>");
+    println!("</html>");
+}
+
+/// Formats a component change as "<name> <old versions> -> <new versions>"
+fn component_versions(component: &ComponentChange) -> String {
+    match (
+        component.old_versions.is_empty(),
+        component.new_versions.is_empty(),
+    ) {
+        (true, _) => format!("{} {}", component.name, component.new_versions.join(", ")),
+        (_, true) => format!("{} {}", component.name, component.old_versions.join(", ")),
+        _ => format!(
+            "{} {} -> {}",
+            component.name,
+            component.old_versions.join(", "),
+            component.new_versions.join(", ")
+        ),
+    }
+}
+
+/// Escapes text for display in markdown
+pub fn print_diff(quiet: bool, firmware_diff: &FirmwareDiff) {
+    // Only show this many leading hex digits of each hash
+    const HASH_DISPLAY_LENGTH: usize = 12;
+
+    if quiet {
+        return;
+    }
+
+    println!();
+    println!(
+        "{}",
+        center_text(&format!(
+            "{} -> {}",
+            firmware_diff.old_image, firmware_diff.new_image
+        ))
+        .bold()
+        .magenta()
+    );
+    print_delimiter();
+    print_column_headers("OLD OFFSET", "NEW OFFSET", "DIFFERENCE");
+    print_delimiter();
+
+    if firmware_diff.is_empty() {
+        println!("No differences found");
+    }
+
+    for region in &firmware_diff.removed_regions {
+        let display_line = format!(
+            "{}{}{}",
+            pad_to_length(&format!("{:#X}", region.offset), COLUMN1_WIDTH),
+            pad_to_length("", COLUMN2_WIDTH),
+            line_wrap(
+                &format!("removed: {}", region.description),
+                COLUMN1_WIDTH + COLUMN2_WIDTH
+            )
+        );
+        println!("{}", display_line.red());
+    }
+
+    for region in &firmware_diff.added_regions {
+        let display_line = format!(
+            "{}{}{}",
+            pad_to_length("", COLUMN1_WIDTH),
+            pad_to_length(&format!("{:#X}", region.offset), COLUMN2_WIDTH),
+            line_wrap(
+                &format!("added: {}", region.description),
+                COLUMN1_WIDTH + COLUMN2_WIDTH
+            )
+        );
+        println!("{}", display_line.green());
+    }
+
+    for change in &firmware_diff.changed_regions {
+        let display_line = format!(
+            "{}{}{}",
+            pad_to_length(&format!("{:#X}", change.old.offset), COLUMN1_WIDTH),
+            pad_to_length(&format!("{:#X}", change.new.offset), COLUMN2_WIDTH),
+            line_wrap(
+                &format!(
+                    "changed: {}, size: {} -> {} bytes",
+                    change.new.description, change.old.size, change.new.size
+                ),
+                COLUMN1_WIDTH + COLUMN2_WIDTH
+            )
+        );
+        println!("{}", display_line.yellow());
+    }
+
+    for (change, files) in [
+        ("removed file", &firmware_diff.removed_files),
+        ("added file", &firmware_diff.added_files),
+        ("modified file", &firmware_diff.modified_files),
+    ] {
+        for file in files {
+            let hashes = format!(
+                "{} -> {}",
+                file.old_sha256.get(..HASH_DISPLAY_LENGTH).unwrap_or("none"),
+                file.new_sha256.get(..HASH_DISPLAY_LENGTH).unwrap_or("none")
+            );
+
+            let display_line = format!(
+                "{}{}",
+                pad_to_length(change, COLUMN1_WIDTH + COLUMN2_WIDTH),
+                line_wrap(
+                    &format!("{} ({})", escape_non_printable(&file.path), hashes),
+                    COLUMN1_WIDTH + COLUMN2_WIDTH
+                )
+            );
+
+            match change {
+                "added file" => println!("{}", display_line.green()),
+                "removed file" => println!("{}", display_line.red()),
+                _ => println!("{}", display_line.yellow()),
+            }
+        }
+    }
+
+    print_footer();
+
+    println!(
+        "{} regions added, {} removed, {} changed, {} unchanged; {} files added, {} removed, {} modified",
+        firmware_diff.added_regions.len(),
+        firmware_diff.removed_regions.len(),
+        firmware_diff.changed_regions.len(),
+        firmware_diff.unchanged_regions,
+        firmware_diff.added_files.len(),
+        firmware_diff.removed_files.len(),
+        firmware_diff.modified_files.len()
+    );
+}
+
 // Used by print_signature_list
 #[derive(Debug, Default, Clone)]
 struct SignatureInfo {
@@ -307,6 +1397,9 @@ pub fn print_signature_list(quiet: bool, signatures: &Vec<signatures::common::Si
                     extractors::common::ExtractorType::Internal(_) => {
                         signature_info.extractor = "Built-in".to_string();
                     }
+                    extractors::common::ExtractorType::Wasm(module_path) => {
+                        signature_info.extractor = format!("WebAssembly ({module_path})");
+                    }
                     extractors::common::ExtractorType::None => error!(
                         "An invalid extractor type exists for the '{}' signature",
                         signature.description
@@ -357,6 +1450,112 @@ pub fn print_signature_list(quiet: bool, signatures: &Vec<signatures::common::Si
     println!("Extractable signatures: {extractor_count}");
 }
 
+/// Prints a markdown table describing each signature's magic bytes, extractor and extractor requirements.
+///
+/// Generated from the signature definitions, so that documentation never disagrees with what binwalk supports.
+pub fn print_capabilities_markdown(quiet: bool, signatures: &[signatures::common::Signature]) {
+    // Signatures with many magic patterns only have the first few listed
+    const MAX_MAGIC_PATTERNS: usize = 4;
+    // Long magic patterns are truncated to this many bytes
+    const MAX_MAGIC_LENGTH: usize = 24;
+
+    if quiet {
+        return;
+    }
+
+    let mut sorted_signatures: Vec<&signatures::common::Signature> = signatures.iter().collect();
+    sorted_signatures.sort_by_key(|signature| signature.description.to_lowercase());
+
+    let extractor_count = signatures
+        .iter()
+        .filter(|signature| signature.extractor.is_some())
+        .count();
+
+    println!("# Supported Formats");
+    println!();
+    println!(
+        "Generated by `binwalk list --format markdown` (binwalk v{}): {} signatures, {} extractable.",
+        env!("CARGO_PKG_VERSION"),
+        signatures.len(),
+        extractor_count
+    );
+    println!();
+    println!("| Format | Name | Signature Bytes | Extractor | Extractor Type | Platform Support |");
+    println!("|---|---|---|---|---|---|");
+
+    for signature in sorted_signatures {
+        let mut magic_patterns: Vec<String> = signature
+            .magic
+            .iter()
+            .take(MAX_MAGIC_PATTERNS)
+            .map(|magic| {
+                let mut pattern = markdown_bytes(&magic[..magic.len().min(MAX_MAGIC_LENGTH)]);
+                if magic.len() > MAX_MAGIC_LENGTH {
+                    pattern.push_str("...");
+                }
+                format!("`{pattern}`")
+            })
+            .collect();
+
+        if signature.magic.len() > MAX_MAGIC_PATTERNS {
+            magic_patterns.push(format!(
+                "(+{} more)",
+                signature.magic.len() - MAX_MAGIC_PATTERNS
+            ));
+        }
+
+        let mut magic_description = magic_patterns.join("<br>");
+
+        // Short signatures are only matched at a fixed offset from the start of a file
+        if signature.short {
+            magic_description =
+                format!("{} at offset {}", magic_description, signature.magic_offset);
+        }
+
+        let (extractor, extractor_type, platform_support) = match &signature.extractor {
+            None => ("-".to_string(), "None", "-".to_string()),
+            Some(extractor) => match &extractor.utility {
+                extractors::common::ExtractorType::External(command) => (
+                    format!("`{command}`"),
+                    "External",
+                    format!("Any with `{command}` installed"),
+                ),
+                extractors::common::ExtractorType::Internal(_) => {
+                    ("Built-in".to_string(), "Internal", "All".to_string())
+                }
+                extractors::common::ExtractorType::Wasm(module_path) => {
+                    (format!("`{module_path}`"), "WebAssembly", "All".to_string())
+                }
+                extractors::common::ExtractorType::None => {
+                    ("-".to_string(), "None", "-".to_string())
+                }
+            },
+        };
+
+        println!(
+            "| {} | `{}` | {} | {} | {} | {} |",
+            signature.description.replace('|', "\\|"),
+            signature.name,
+            magic_description,
+            extractor,
+            extractor_type,
+            platform_support
+        );
+    }
+}
+
+/// Formats bytes for display in a markdown code span; printable ASCII is displayed as-is, everything else is hex escaped.
+/// Characters that would break a code span or table cell are escaped as well.
+fn markdown_bytes(data: &[u8]) -> String {
+    data.iter()
+        .map(|byte| match byte {
+            b'\\' | b'`' | b'|' => format!("\\x{byte:02X}"),
+            0x20..=0x7E => (*byte as char).to_string(),
+            _ => format!("\\x{byte:02X}"),
+        })
+        .collect()
+}
+
 pub fn print_stats(
     quiet: bool,
     run_time: time::Instant,
@@ -401,6 +1600,23 @@ pub fn print_stats(
     );
 }
 
+/// Prints the progress of an external extractor to stderr, so that it is not mixed with analysis results
+pub fn print_extraction_progress(progress: &extractors::common::ExtractionProgress) {
+    eprintln!(
+        "{}",
+        format!(
+            "[*] Extraction of {} data at offset {:#X} in {}: {}% complete ({})",
+            progress.signature_name,
+            progress.offset,
+            progress.file_path,
+            progress.percent,
+            progress.utility
+        )
+        .bold()
+        .blue()
+    );
+}
+
 pub fn print_plain(quiet: bool, msg: &str) {
     if !quiet {
         print!("{msg}");
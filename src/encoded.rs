@@ -0,0 +1,149 @@
+//! Detection of base64 and hex encoded data.
+//!
+//! Some vendors wrap firmware sections in text containers, such as XML or JSON update manifests, as base64 or hex
+//! encoded text. Long runs of encoded text outside of the identified signatures are decoded, and the decoded data is
+//! scanned so that its contents can be reported along with the encoded data.
+
+use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureResult};
+use crate::structures::encoded::{EncodedData, parse_base64_text, parse_hex_text};
+
+/// Signature name of base64 encoded data
+pub const BASE64_NAME: &str = "base64";
+
+/// Human readable description of base64 encoded data
+pub const BASE64_DESCRIPTION: &str = "Base64 encoded data";
+
+/// Signature name of hex encoded data
+pub const HEX_NAME: &str = "hex";
+
+/// Human readable description of hex encoded data
+pub const HEX_DESCRIPTION: &str = "Hex encoded data";
+
+/// Finds runs of base64 and hex encoded text that lie outside of the signatures in `file_map`. The decoded data is
+/// passed to `scan`, and the names of the signatures it returns are included in each result's description.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::encoded::{BASE64_NAME, find_encoded_data};
+/// use binwalk::signatures::common::SignatureResult;
+/// use base64::Engine;
+/// use base64::engine::general_purpose::STANDARD;
+///
+/// let data: Vec<u8> = (0..=255).collect();
+/// let file_data = [b"<firmware>".to_vec(), STANDARD.encode(&data).into_bytes(), b"</firmware>".to_vec()].concat();
+///
+/// let encoded_data = find_encoded_data(&file_data, &[], |decoded| {
+///     assert_eq!(decoded, data);
+///     vec![SignatureResult { name: "example".to_string(), ..Default::default() }]
+/// });
+///
+/// assert_eq!(encoded_data.len(), 1);
+/// assert_eq!(encoded_data[0].name, BASE64_NAME);
+/// assert_eq!(encoded_data[0].offset, 10);
+/// assert_eq!(encoded_data[0].size, file_data.len() - 21);
+/// assert!(encoded_data[0].description.contains("decoded contents: example"));
+/// ```
+pub fn find_encoded_data(
+    file_data: &[u8],
+    file_map: &[SignatureResult],
+    scan: impl Fn(&[u8]) -> Vec<SignatureResult>,
+) -> Vec<SignatureResult> {
+    let mut encoded_data: Vec<SignatureResult> = vec![];
+
+    let mut offset: usize = 0;
+
+    while offset < file_data.len() {
+        // Encoded text inside of identified data has already been accounted for
+        if let Some(signature) = file_map.iter().find(|signature| {
+            offset >= signature.offset && offset < signature.offset + signature.size
+        }) {
+            offset = signature.offset + signature.size;
+            continue;
+        }
+
+        // Encoded text can't start in the middle of a word
+        if !is_base64_symbol(file_data[offset])
+            || (offset > 0 && is_base64_symbol(file_data[offset - 1]))
+        {
+            offset += 1;
+            continue;
+        }
+
+        // Hex encoded text is also valid base64 encoded text, so try hex first, unless it is followed by more base64 text
+        let encoded = match parse_hex_text(&file_data[offset..]) {
+            Ok(hex_data)
+                if !file_data
+                    .get(offset + hex_data.size)
+                    .is_some_and(|byte| is_base64_symbol(*byte)) =>
+            {
+                Some((HEX_NAME, HEX_DESCRIPTION, hex_data))
+            }
+            _ => parse_base64_text(&file_data[offset..])
+                .ok()
+                .map(|base64_data| (BASE64_NAME, BASE64_DESCRIPTION, base64_data)),
+        };
+
+        match encoded {
+            Some((name, description, decoded)) => {
+                encoded_data.push(SignatureResult {
+                    offset,
+                    size: decoded.size,
+                    name: name.to_string(),
+                    confidence: CONFIDENCE_MEDIUM,
+                    description: describe_encoded_data(description, &decoded, &scan),
+                    ..Default::default()
+                });
+
+                offset += decoded.size;
+            }
+            None => {
+                // Skip the rest of this word; no part of it is encoded text
+                while offset < file_data.len() && is_base64_symbol(file_data[offset]) {
+                    offset += 1;
+                }
+            }
+        }
+    }
+
+    encoded_data
+}
+
+/// Describes encoded data, and the signatures found in its decoded data
+fn describe_encoded_data(
+    description: &str,
+    decoded: &EncodedData,
+    scan: impl Fn(&[u8]) -> Vec<SignatureResult>,
+) -> String {
+    // Only list this many of the decoded data's signatures
+    const MAX_LISTED_SIGNATURES: usize = 8;
+
+    let mut signature_names: Vec<String> = vec![];
+
+    for signature in scan(&decoded.data) {
+        if !signature_names.contains(&signature.name) {
+            signature_names.push(signature.name);
+        }
+    }
+
+    let decoded_contents = if signature_names.is_empty() {
+        "unknown".to_string()
+    } else if signature_names.len() > MAX_LISTED_SIGNATURES {
+        format!("{} ...", signature_names[..MAX_LISTED_SIGNATURES].join(" "))
+    } else {
+        signature_names.join(" ")
+    };
+
+    format!(
+        "{}, decoded size: {} bytes, decoded contents: {}, total size: {} bytes",
+        description,
+        decoded.data.len(),
+        decoded_contents,
+        decoded.size
+    )
+}
+
+/// Returns true if the byte is one of the standard base64 symbols, which include all of the hex digits
+fn is_base64_symbol(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'+' || byte == b'/'
+}
@@ -1,8 +1,10 @@
-use crate::common::read_input;
+use crate::common::read_stdin;
 use entropy::shannon_entropy;
 use plotly::layout::{Axis, Layout};
 use plotly::{ImageFormat, Plot, Scatter};
 use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
 
 #[derive(Debug, Clone)]
 pub struct EntropyError;
@@ -20,40 +22,51 @@ pub struct FileEntropy {
     pub blocks: Vec<BlockEntropy>,
 }
 
-/// Splits the supplied data up into blocks and calculates the entropy of each block.
-fn blocks(data: &[u8]) -> Vec<BlockEntropy> {
+/// Splits data read from the reader up into blocks and calculates the entropy of each block.
+/// Only one block of data is held in memory at a time.
+fn blocks(mut reader: impl Read, data_len: usize) -> Result<Vec<BlockEntropy>, std::io::Error> {
     const BLOCK_COUNT: usize = 2048;
 
     let mut offset: usize = 0;
 
-    let block_size = if data.len() < BLOCK_COUNT {
-        data.len()
+    let block_size = if data_len < BLOCK_COUNT {
+        data_len
     } else {
-        data.len() / BLOCK_COUNT
+        data_len / BLOCK_COUNT
     };
 
-    let mut chunker = data.chunks(block_size);
+    let mut block_data: Vec<u8> = Vec::with_capacity(block_size);
     let mut entropy_blocks: Vec<BlockEntropy> = vec![];
 
+    // Empty data has no blocks
+    if block_size == 0 {
+        return Ok(entropy_blocks);
+    }
+
     loop {
-        match chunker.next() {
-            None => break,
-            Some(block_data) => {
-                let mut block = BlockEntropy {
-                    ..Default::default()
-                };
-
-                block.start = offset;
-                block.entropy = shannon_entropy(block_data);
-                block.end = block.start + block_data.len();
-
-                offset = block.end;
-                entropy_blocks.push(block);
-            }
+        block_data.clear();
+
+        if (&mut reader)
+            .take(block_size as u64)
+            .read_to_end(&mut block_data)?
+            == 0
+        {
+            break;
         }
+
+        let mut block = BlockEntropy {
+            ..Default::default()
+        };
+
+        block.start = offset;
+        block.entropy = shannon_entropy(&block_data);
+        block.end = block.start + block_data.len();
+
+        offset = block.end;
+        entropy_blocks.push(block);
     }
 
-    entropy_blocks
+    Ok(entropy_blocks)
 }
 
 pub fn plot(
@@ -69,10 +82,17 @@ pub fn plot(
         ..Default::default()
     };
 
-    // Read in the target file data
-    if let Ok(file_data) = read_input(&target_file, stdin) {
-        // Calculate the entropy of each file block
-        file_entropy.blocks = blocks(&file_data);
+    // Calculate the entropy of each file block; files are read one block at a time, but stdin must be read in its entirety to know its size
+    let entropy_blocks = match stdin {
+        true => read_stdin().and_then(|stdin_data| blocks(stdin_data.as_slice(), stdin_data.len())),
+        false => File::open(&target_file).and_then(|file| {
+            let file_size = file.metadata()?.len() as usize;
+            blocks(file, file_size)
+        }),
+    };
+
+    if let Ok(entropy_blocks) = entropy_blocks {
+        file_entropy.blocks = entropy_blocks;
 
         for block in &file_entropy.blocks {
             x.push(block.start);
@@ -139,23 +139,40 @@
 //! }
 //! ```
 
+pub mod android_bootimg;
 pub mod androidsparse;
 pub mod arcadyan;
 pub mod autel;
+pub mod bcm_imagetag;
 pub mod bmp;
 pub mod bzip2;
 pub mod cab;
+pub mod chk;
 pub mod common;
+pub mod config;
+pub mod cpio;
+pub mod cramfs;
 pub mod csman;
 pub mod dahua_zip;
+pub mod der;
+pub mod dex;
 pub mod dmg;
+pub mod dni;
 pub mod dtb;
 pub mod dumpifs;
 pub mod dxbc;
+pub mod efigpt;
+pub mod elf;
 pub mod encfw;
+pub mod encoded;
+pub mod exfat;
+pub mod ext;
+pub mod fat;
+pub mod font;
 pub mod gif;
 pub mod gpg;
 pub mod gzip;
+pub mod ihex;
 pub mod inflate;
 pub mod iso9660;
 pub mod jboot;
@@ -165,28 +182,45 @@ pub mod linux;
 pub mod lz4;
 pub mod lzfse;
 pub mod lzma;
+pub mod lznt1;
 pub mod lzop;
+pub mod lzx;
 pub mod matter_ota;
 pub mod mbr;
 pub mod mh01;
+pub mod mp3;
+pub mod mp4;
+pub mod ntfs;
+pub mod nvram;
+pub mod openwrt;
 pub mod pcap;
 pub mod pem;
 pub mod png;
+pub mod qualcomm;
 pub mod rar;
 pub mod riff;
+pub mod rockchip;
 pub mod romfs;
+pub mod seama;
 pub mod sevenzip;
+pub mod sqlite;
 pub mod squashfs;
 pub mod srec;
 pub mod svg;
 pub mod swapped;
 pub mod tarball;
+pub mod tiano;
+pub mod tplink;
 pub mod trx;
 pub mod tsk;
 pub mod ubi;
+pub mod udf;
 pub mod uefi;
 pub mod uimage;
 pub mod vxworks;
+pub mod wasm;
+pub mod wasm_plugin;
+pub mod webasset;
 pub mod wince;
 pub mod yaffs2;
 pub mod zlib;
@@ -0,0 +1,221 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::android_bootimg::{
+    AndroidBootImageComponent, parse_android_bootimg_header, parse_android_vendor_bootimg_header,
+};
+use log::{error, warn};
+use serde::Serialize;
+
+/// Defines the internal extractor function for splitting Android boot images into their components
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::android_bootimg::android_bootimg_extractor;
+///
+/// match android_bootimg_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn android_bootimg_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_android_bootimg),
+        ..Default::default()
+    }
+}
+
+/// Defines the internal extractor function for splitting Android vendor boot images into their components
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::android_bootimg::android_vendor_bootimg_extractor;
+///
+/// match android_vendor_bootimg_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn android_vendor_bootimg_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_android_vendor_bootimg),
+        ..Default::default()
+    }
+}
+
+/// Splits an Android boot image into its kernel, ramdisk, second stage, DTB and signature components.
+/// The parsed header is written to `bootimg.json`.
+pub fn extract_android_bootimg(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const METADATA_FILE_NAME: &str = "bootimg.json";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(bootimg_header) = parse_android_bootimg_header(&file_data[offset..]) {
+        result = extract_components(
+            file_data,
+            offset,
+            output_directory,
+            &bootimg_header.components,
+            bootimg_header.total_size,
+            METADATA_FILE_NAME,
+            &bootimg_header,
+        );
+    }
+
+    result
+}
+
+/// Splits an Android vendor boot image into its vendor ramdisk, DTB, vendor ramdisk table and bootconfig components.
+/// Each of the vendor ramdisks listed in a version 4 vendor ramdisk table is written to its own file, named
+/// `vendor_ramdisk00`, `vendor_ramdisk01`, etc. The parsed header is written to `vendor_bootimg.json`.
+pub fn extract_android_vendor_bootimg(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const METADATA_FILE_NAME: &str = "vendor_bootimg.json";
+    const VENDOR_RAMDISK_NAME: &str = "vendor_ramdisk";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(bootimg_header) = parse_android_vendor_bootimg_header(&file_data[offset..]) {
+        let mut components: Vec<AndroidBootImageComponent> = vec![];
+
+        for component in &bootimg_header.components {
+            if component.name == VENDOR_RAMDISK_NAME && !bootimg_header.vendor_ramdisks.is_empty() {
+                for (i, vendor_ramdisk) in bootimg_header.vendor_ramdisks.iter().enumerate() {
+                    components.push(AndroidBootImageComponent {
+                        name: format!("{VENDOR_RAMDISK_NAME}{i:02}"),
+                        offset: component.offset + vendor_ramdisk.offset,
+                        size: vendor_ramdisk.size,
+                    });
+                }
+            } else {
+                components.push(component.clone());
+            }
+        }
+
+        result = extract_components(
+            file_data,
+            offset,
+            output_directory,
+            &components,
+            bootimg_header.total_size,
+            METADATA_FILE_NAME,
+            &bootimg_header,
+        );
+    }
+
+    result
+}
+
+/// Writes each component to its own file, along with the image's JSON metadata.
+/// Components truncated by the end of the available data are written up to the end of the data.
+fn extract_components(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+    components: &[AndroidBootImageComponent],
+    total_size: usize,
+    metadata_file_name: &str,
+    metadata: &impl Serialize,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    let available_data = file_data.len() - offset;
+    let mut extracted_count: usize = 0;
+
+    let chroot = Chroot::new(output_directory);
+
+    for component in components {
+        // Components that start beyond the end of the available data can not be extracted
+        if component.offset >= available_data {
+            warn!(
+                "Android boot image {} at offset {:#X} is beyond the end of the available data",
+                component.name,
+                offset + component.offset
+            );
+            continue;
+        }
+
+        let component_start = offset + component.offset;
+        let component_end = offset + available_data.min(component.offset + component.size);
+
+        if component_end - component_start < component.size {
+            warn!(
+                "Only {} of {} bytes of Android boot image {} are available",
+                component_end - component_start,
+                component.size,
+                component.name
+            );
+        }
+
+        if output_directory.is_some()
+            && !chroot.carve_file(
+                &component.name,
+                file_data,
+                component_start,
+                component_end - component_start,
+            )
+        {
+            return result;
+        }
+
+        extracted_count += 1;
+    }
+
+    if extracted_count == 0 {
+        return result;
+    }
+
+    if output_directory.is_some() {
+        match serde_json::to_string_pretty(metadata) {
+            Err(e) => {
+                error!("Failed to convert Android boot image metadata to JSON: {e}");
+                return result;
+            }
+            Ok(metadata_json) => {
+                if !chroot.create_file(metadata_file_name, metadata_json.as_bytes()) {
+                    return result;
+                }
+            }
+        }
+    }
+
+    result.success = true;
+    result.size = Some(total_size.min(available_data));
+
+    result
+}
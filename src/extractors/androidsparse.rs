@@ -13,6 +13,7 @@ use crate::structures::androidsparse;
 /// match android_sparse_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -31,7 +32,10 @@ pub fn android_sparse_extractor() -> Extractor {
     }
 }
 
-/// Android sparse internal extractor
+/// Android sparse internal extractor.
+///
+/// Converts the sparse image to the raw image it describes (typically an ext4 file system), which is written to
+/// `unsparsed.img` in the output directory, where it can be scanned and extracted recursively.
 pub fn extract_android_sparse(
     file_data: &[u8],
     offset: usize,
@@ -45,12 +49,18 @@ pub fn extract_android_sparse(
 
     // Parse the sparse file header
     if let Ok(sparse_header) = androidsparse::parse_android_sparse_header(&file_data[offset..]) {
+        let chroot = Chroot::new(output_directory);
+        let mut unsparsed_image =
+            UnsparsedImage::new(&chroot, OUTFILE_NAME, output_directory.is_none());
         let available_data: usize = file_data.len();
         let mut last_chunk_offset: Option<usize> = None;
         let mut processed_chunk_count: usize = 0;
+        let mut processed_block_count: usize = 0;
         let mut next_chunk_offset: usize = offset + sparse_header.header_size;
 
-        while is_offset_safe(available_data, next_chunk_offset, last_chunk_offset) {
+        while processed_chunk_count < sparse_header.chunk_count
+            && is_offset_safe(available_data, next_chunk_offset, last_chunk_offset)
+        {
             // Parse the next chunk's header
             match androidsparse::parse_android_sparse_chunk_header(&file_data[next_chunk_offset..])
             {
@@ -59,36 +69,38 @@ pub fn extract_android_sparse(
                 }
 
                 Ok(chunk_header) => {
-                    // If not a dry run, extract the data from the next chunk
-                    if output_directory.is_some() {
-                        let chroot = Chroot::new(output_directory);
-                        let chunk_data_start: usize = next_chunk_offset + chunk_header.header_size;
-                        let chunk_data_end: usize = chunk_data_start + chunk_header.data_size;
+                    let chunk_data_start: usize = next_chunk_offset + chunk_header.header_size;
+                    let chunk_data_end: usize = chunk_data_start + chunk_header.data_size;
 
-                        if let Some(chunk_data) = file_data.get(chunk_data_start..chunk_data_end) {
+                    match file_data.get(chunk_data_start..chunk_data_end) {
+                        None => {
+                            break;
+                        }
+                        Some(chunk_data) => {
                             if !extract_chunk(
                                 &sparse_header,
                                 &chunk_header,
                                 chunk_data,
-                                OUTFILE_NAME,
-                                &chroot,
+                                &mut unsparsed_image,
                             ) {
                                 break;
                             }
-                        } else {
-                            break;
                         }
                     }
 
                     processed_chunk_count += 1;
+                    processed_block_count += chunk_header.block_count;
                     last_chunk_offset = Some(next_chunk_offset);
-                    next_chunk_offset += chunk_header.header_size + chunk_header.data_size;
+                    next_chunk_offset = chunk_data_end;
                 }
             }
         }
 
-        // Make sure the number of processed chunks equals the number of chunks reported in the sparse flie header
-        if processed_chunk_count == sparse_header.chunk_count {
+        // Make sure the number of processed chunks and blocks equal the numbers reported in the sparse file header
+        if processed_chunk_count == sparse_header.chunk_count
+            && processed_block_count == sparse_header.block_count
+            && unsparsed_image.flush()
+        {
             result.success = true;
             result.size = Some(next_chunk_offset - offset);
         }
@@ -97,51 +109,110 @@ pub fn extract_android_sparse(
     result
 }
 
-// Extract a sparse file chunk to disk
+/// Buffers writes to the unsparsed image, so that runs of fill and don't care blocks are written in large batches
+struct UnsparsedImage<'a> {
+    chroot: &'a Chroot,
+    file_name: &'a str,
+    dry_run: bool,
+    buffer: Vec<u8>,
+}
+
+impl<'a> UnsparsedImage<'a> {
+    /// Buffered data is written to disk once it exceeds this size
+    const BUFFER_SIZE: usize = 1024 * 1024;
+
+    fn new(chroot: &'a Chroot, file_name: &'a str, dry_run: bool) -> Self {
+        UnsparsedImage {
+            chroot,
+            file_name,
+            dry_run,
+            buffer: vec![],
+        }
+    }
+
+    /// Appends data to the unsparsed image
+    fn write(&mut self, data: &[u8]) -> bool {
+        if self.dry_run {
+            return true;
+        }
+
+        self.buffer.extend_from_slice(data);
+
+        if self.buffer.len() >= Self::BUFFER_SIZE {
+            return self.flush();
+        }
+
+        true
+    }
+
+    /// Appends `count` copies of data to the unsparsed image
+    fn write_repeated(&mut self, data: &[u8], count: usize) -> bool {
+        if self.dry_run {
+            return true;
+        }
+
+        for _ in 0..count {
+            if !self.write(data) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Writes any buffered data to disk
+    fn flush(&mut self) -> bool {
+        if self.dry_run || self.buffer.is_empty() {
+            return true;
+        }
+
+        let success = self.chroot.append_to_file(self.file_name, &self.buffer);
+        self.buffer.clear();
+        success
+    }
+}
+
+// Write a sparse file chunk to the unsparsed image
 fn extract_chunk(
     sparse_header: &androidsparse::AndroidSparseHeader,
     chunk_header: &androidsparse::AndroidSparseChunkHeader,
     chunk_data: &[u8],
-    outfile: &str,
-    chroot: &Chroot,
+    unsparsed_image: &mut UnsparsedImage,
 ) -> bool {
+    // Fill and CRC chunks both contain a single 32-bit value
+    const FILL_VALUE_SIZE: usize = 4;
+
     if chunk_header.is_raw {
         // Raw chunks are just data chunks stored verbatim
-        if !chroot.append_to_file(outfile, chunk_data) {
+        if Some(chunk_header.data_size)
+            != chunk_header
+                .block_count
+                .checked_mul(sparse_header.block_size)
+        {
             return false;
         }
-    } else if chunk_header.is_fill {
-        // Fill chunks are block_count blocks that contain a repeated sequence of data (typically 4-bytes repeated over and over again)
-        for _ in 0..chunk_header.block_count {
-            let mut i = 0;
-            let mut fill_block: Vec<u8> = vec![];
-
-            // Fill each block with the repeated data
-            while i < sparse_header.block_size {
-                fill_block.extend(chunk_data);
-                i += chunk_data.len();
-            }
 
-            // Append fill block to file
-            if !chroot.append_to_file(outfile, &fill_block) {
-                return false;
-            }
+        unsparsed_image.write(chunk_data)
+    } else if chunk_header.is_fill {
+        if chunk_data.len() != FILL_VALUE_SIZE {
+            return false;
         }
-    } else if chunk_header.is_dont_care {
-        let mut null_block: Vec<u8> = vec![];
 
-        // Build a block full of NULL bytes
-        while null_block.len() < sparse_header.block_size {
-            null_block.push(0);
-        }
+        // Fill chunks are block_count blocks that contain a repeated 4-byte value; block sizes are always 4-byte aligned
+        let fill_block: Vec<u8> = chunk_data.repeat(sparse_header.block_size / FILL_VALUE_SIZE);
 
-        // Write block_count NULL blocks to disk
-        for _ in 0..chunk_header.block_count {
-            if !chroot.append_to_file(outfile, &null_block) {
-                return false;
-            }
+        unsparsed_image.write_repeated(&fill_block, chunk_header.block_count)
+    } else if chunk_header.is_dont_care {
+        if !chunk_data.is_empty() {
+            return false;
         }
-    }
 
-    true
+        // Don't care chunks are block_count blocks of unspecified contents; write them as NULL bytes
+        let null_block: Vec<u8> = vec![0; sparse_header.block_size];
+
+        unsparsed_image.write_repeated(&null_block, chunk_header.block_count)
+    } else {
+        // CRC chunks contain a CRC32 of the data so far, and do not produce any output
+        chunk_data.len() == FILL_VALUE_SIZE && chunk_header.block_count == 0
+    }
 }
@@ -14,6 +14,7 @@ const BLOCK_SIZE: usize = 256;
 /// match autel_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
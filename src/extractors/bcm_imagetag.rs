@@ -0,0 +1,70 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::bcm_imagetag::parse_bcm_imagetag;
+
+/// Defines the internal extractor for Broadcom image tagged firmware
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::bcm_imagetag::bcm_imagetag_extractor;
+///
+/// match bcm_imagetag_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn bcm_imagetag_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_bcm_imagetag_regions),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for the bootloader, kernel and rootfs regions of Broadcom image tagged firmware
+pub fn extract_bcm_imagetag_regions(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(image_tag) = parse_bcm_imagetag(&file_data[offset..]) {
+        // All of the tagged data must be present
+        if file_data.len() - offset >= image_tag.total_size() {
+            result.size = Some(image_tag.total_size());
+            result.success = true;
+
+            if output_directory.is_some() {
+                let chroot = Chroot::new(output_directory);
+
+                for (name, region_offset, region_size) in image_tag.regions() {
+                    result.success = chroot.carve_file(
+                        format!("{name}.bin"),
+                        file_data,
+                        offset + region_offset,
+                        region_size,
+                    );
+
+                    if !result.success {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
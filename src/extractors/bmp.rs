@@ -1,7 +1,7 @@
 use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
-use crate::structures::bmp::{get_dib_header_size, parse_bmp_file_header};
+use crate::structures::bmp::{parse_bmp_file_header, parse_dib_header};
 
-/// Defines the internal extractor function for carving out GIF images
+/// Defines the internal extractor function for carving out BMP images
 ///
 /// ```
 /// use std::io::ErrorKind;
@@ -12,6 +12,7 @@ use crate::structures::bmp::{get_dib_header_size, parse_bmp_file_header};
 /// match bmp_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -49,20 +50,30 @@ pub fn extract_bmp_image(
         // The size of the BMP file header
         const BMP_FILE_HEADER_SIZE: usize = 14;
 
-        // Retrieve the size of the header following the BMP file header
-        if let Ok(bmp_header_size) =
-            get_dib_header_size(&file_data[(offset + BMP_FILE_HEADER_SIZE)..])
-        {
-            // The offset that points to the image data cannot point into the second header
-            if bmp_file_header.bitmap_bits_offset >= (BMP_FILE_HEADER_SIZE + bmp_header_size) {
-                // If it was parsed successfully, get the file size
-                result.size = Some(bmp_file_header.size);
-                result.success = true;
+        // Parse the header following the BMP file header
+        if let Some(dib_data) = file_data.get(offset + BMP_FILE_HEADER_SIZE..) {
+            if let Ok(dib_header) = parse_dib_header(dib_data) {
+                // The offset that points to the image data cannot point into the second header,
+                // and the image data must fit inside the reported file size
+                if bmp_file_header.bitmap_bits_offset
+                    >= (BMP_FILE_HEADER_SIZE + dib_header.header_size)
+                    && dib_header.image_data_size.is_some_and(|image_data_size| {
+                        bmp_file_header.bitmap_bits_offset + image_data_size <= bmp_file_header.size
+                    })
+                {
+                    // If it was parsed successfully, get the file size
+                    result.size = Some(bmp_file_header.size);
+                    result.success = true;
 
-                if output_directory.is_some() {
-                    let chroot = Chroot::new(output_directory);
-                    result.success =
-                        chroot.carve_file(OUTFILE_NAME, file_data, offset, bmp_file_header.size);
+                    if output_directory.is_some() {
+                        let chroot = Chroot::new(output_directory);
+                        result.success = chroot.carve_file(
+                            OUTFILE_NAME,
+                            file_data,
+                            offset,
+                            bmp_file_header.size,
+                        );
+                    }
                 }
             }
         }
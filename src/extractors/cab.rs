@@ -1,6 +1,18 @@
 use crate::extractors;
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::extractors::lzx::lzx_decompress;
+use crate::structures::cab::{
+    COMPRESSION_LZX, COMPRESSION_MSZIP, COMPRESSION_NONE, CabinetFile, CabinetFolder,
+    CabinetLayout, cab_data_block_checksum, parse_cab_data_block, parse_cab_file, parse_cab_folder,
+    parse_cab_header, parse_cab_layout,
+};
+use miniz_oxide::inflate::TINFLStatus;
+use miniz_oxide::inflate::core::inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF;
+use miniz_oxide::inflate::core::{DecompressorOxide, decompress};
+use std::path::Path;
 
-/// Describes how to run the cabextract utility to extract MS CAB archives
+/// Describes how to run the cabextract utility to extract MS CAB archives; used for Quantum compressed archives,
+/// which the internal extractor does not support
 ///
 /// ```
 /// use std::io::ErrorKind;
@@ -11,6 +23,7 @@ use crate::extractors;
 /// match cab_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -31,3 +44,225 @@ pub fn cab_extractor() -> extractors::common::Extractor {
         ..Default::default()
     }
 }
+
+/// Defines the internal extractor function for MS CAB archives
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::cab::cab_internal_extractor;
+///
+/// match cab_internal_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn cab_internal_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_cab),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for uncompressed, MSZIP and LZX compressed CAB archives
+pub fn extract_cab(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(cab_data) = file_data.get(offset..) {
+        if let Ok(cab_header) = parse_cab_header(cab_data) {
+            if let Ok(layout) = parse_cab_layout(cab_data) {
+                if let Some((folders, files)) = parse_cab_entries(cab_data, &layout) {
+                    let chroot = Chroot::new(output_directory);
+
+                    for (folder_index, folder) in folders.iter().enumerate() {
+                        // Files split across multiple cabinets can not be extracted from a single cabinet
+                        let folder_files: Vec<&CabinetFile> = files
+                            .iter()
+                            .filter(|file| !file.split && file.folder_index == folder_index)
+                            .collect();
+
+                        if folder_files.is_empty() {
+                            continue;
+                        }
+
+                        let folder_data = match decompress_folder(cab_data, &layout, folder) {
+                            None => return result,
+                            Some(folder_data) => folder_data,
+                        };
+
+                        for file in folder_files {
+                            let file_data = match folder_data
+                                .get(file.folder_offset..file.folder_offset + file.size)
+                            {
+                                None => return result,
+                                Some(file_data) => file_data,
+                            };
+
+                            if output_directory.is_some()
+                                && !extract_file(&chroot, &file.name, file_data)
+                            {
+                                return result;
+                            }
+                        }
+                    }
+
+                    result.size = Some(cab_header.total_size);
+                    result.success = true;
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Parses all folder and file entries in a CAB archive
+fn parse_cab_entries(
+    cab_data: &[u8],
+    layout: &CabinetLayout,
+) -> Option<(Vec<CabinetFolder>, Vec<CabinetFile>)> {
+    const FOLDER_ENTRY_SIZE: usize = 8;
+
+    let mut folders: Vec<CabinetFolder> = vec![];
+    let mut files: Vec<CabinetFile> = vec![];
+
+    let folder_entry_size = FOLDER_ENTRY_SIZE + layout.folder_reserve_size;
+
+    for i in 0..layout.folder_count {
+        let folder_offset = layout.folders_offset + (i * folder_entry_size);
+        folders.push(parse_cab_folder(cab_data.get(folder_offset..)?).ok()?);
+    }
+
+    let mut file_offset = layout.first_file_offset;
+
+    for _ in 0..layout.file_count {
+        let (file, file_entry_size) = parse_cab_file(cab_data.get(file_offset..)?).ok()?;
+        files.push(file);
+        file_offset += file_entry_size;
+    }
+
+    Some((folders, files))
+}
+
+/// Verifies and decompresses all of the data blocks in a CAB folder
+fn decompress_folder(
+    cab_data: &[u8],
+    layout: &CabinetLayout,
+    folder: &CabinetFolder,
+) -> Option<Vec<u8>> {
+    // Compressed data and uncompressed size of each data block
+    let mut data_blocks: Vec<(&[u8], usize)> = vec![];
+    let mut block_offset = folder.data_offset;
+
+    for _ in 0..folder.data_block_count {
+        let block =
+            parse_cab_data_block(cab_data.get(block_offset..)?, layout.data_reserve_size).ok()?;
+
+        let data_start = block_offset + block.header_size;
+        let block_data = cab_data.get(data_start..data_start + block.compressed_size)?;
+
+        // A checksum of zero means the block has no checksum
+        if block.checksum != 0 && cab_data_block_checksum(&block, block_data) != block.checksum {
+            return None;
+        }
+
+        data_blocks.push((block_data, block.uncompressed_size));
+        block_offset = data_start + block.compressed_size;
+    }
+
+    match folder.compression {
+        COMPRESSION_NONE => {
+            let mut folder_data: Vec<u8> = vec![];
+
+            for (block_data, uncompressed_size) in data_blocks {
+                if block_data.len() != uncompressed_size {
+                    return None;
+                }
+                folder_data.extend_from_slice(block_data);
+            }
+
+            Some(folder_data)
+        }
+        COMPRESSION_MSZIP => mszip_decompress(&data_blocks),
+        COMPRESSION_LZX => {
+            // LZX data is a single bitstream spanning all of the folder's data blocks
+            let mut compressed_data: Vec<u8> = vec![];
+            let mut uncompressed_size: usize = 0;
+
+            for (block_data, block_uncompressed_size) in data_blocks {
+                compressed_data.extend_from_slice(block_data);
+                uncompressed_size += block_uncompressed_size;
+            }
+
+            lzx_decompress(&compressed_data, folder.lzx_window_bits, uncompressed_size)
+        }
+        _ => None,
+    }
+}
+
+/// Decompresses MSZIP data blocks; each block is a deflate stream that may reference data from the previous block
+fn mszip_decompress(data_blocks: &[(&[u8], usize)]) -> Option<Vec<u8>> {
+    const MSZIP_SIGNATURE: &[u8] = b"CK";
+    const HISTORY_SIZE: usize = 32 * 1024;
+
+    let mut folder_data: Vec<u8> = vec![];
+
+    for (block_data, uncompressed_size) in data_blocks {
+        let deflate_data = block_data.strip_prefix(MSZIP_SIGNATURE)?;
+
+        // Previously decompressed data precedes the output of this block in the output buffer
+        let history_start = folder_data.len().saturating_sub(HISTORY_SIZE);
+        let history_size = folder_data.len() - history_start;
+
+        let mut output_buffer: Vec<u8> = folder_data[history_start..].to_vec();
+        output_buffer.resize(history_size + uncompressed_size, 0);
+
+        let mut decompressor = DecompressorOxide::new();
+        let (status, _, output_size) = decompress(
+            &mut decompressor,
+            deflate_data,
+            &mut output_buffer,
+            history_size,
+            TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+        );
+
+        if status != TINFLStatus::Done || output_size != *uncompressed_size {
+            return None;
+        }
+
+        folder_data.extend_from_slice(&output_buffer[history_size..]);
+    }
+
+    Some(folder_data)
+}
+
+/// Writes an extracted file to disk
+fn extract_file(chroot: &Chroot, file_name: &str, file_data: &[u8]) -> bool {
+    // CAB file names use backslashes as the path separator
+    let file_path = file_name.replace('\\', "/");
+
+    if let Some(parent_directory) = Path::new(&file_path).parent() {
+        if !parent_directory.as_os_str().is_empty() {
+            chroot.create_directory(parent_directory.display().to_string());
+        }
+    }
+
+    chroot.create_file(chroot.safe_path_join("", &file_path), file_data)
+}
@@ -0,0 +1,79 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::chk::parse_chk_header;
+
+/// Defines the internal extractor for Netgear CHK firmware images
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::chk::chk_extractor;
+///
+/// match chk_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn chk_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_chk_payloads),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for the kernel and rootfs payloads of a CHK firmware image
+pub fn extract_chk_payloads(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const KERNEL_FILE_NAME: &str = "kernel.bin";
+    const ROOTFS_FILE_NAME: &str = "rootfs.bin";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(chk_header) = parse_chk_header(&file_data[offset..]) {
+        let kernel_offset = offset + chk_header.header_size;
+        let rootfs_offset = kernel_offset + chk_header.kernel_size;
+        let image_size = chk_header.header_size + chk_header.kernel_size + chk_header.rootfs_size;
+
+        // The header must be followed by at least one payload, and the payloads must fit in the available data
+        if image_size > chk_header.header_size && file_data.len() - offset >= image_size {
+            result.size = Some(image_size);
+            result.success = true;
+
+            if output_directory.is_some() {
+                let chroot = Chroot::new(output_directory);
+
+                // Images may contain only a kernel, or only a rootfs
+                for (file_name, payload_offset, payload_size) in [
+                    (KERNEL_FILE_NAME, kernel_offset, chk_header.kernel_size),
+                    (ROOTFS_FILE_NAME, rootfs_offset, chk_header.rootfs_size),
+                ] {
+                    if payload_size > 0 {
+                        result.success =
+                            chroot.carve_file(file_name, file_data, payload_offset, payload_size);
+
+                        if !result.success {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
@@ -1,10 +1,16 @@
+use crate::extractors::wasm_plugin::execute_wasm_extractor;
+use crate::locked::LockedContent;
 use crate::signatures::common::SignatureResult;
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::env;
 use std::fs;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path;
 use std::process;
+use std::thread;
+use std::time;
 use walkdir::WalkDir;
 
 #[cfg(windows)]
@@ -18,6 +24,16 @@ use std::os::unix::fs::PermissionsExt;
 /// This contstant in command line arguments will be replaced with the path to the input file
 pub const SOURCE_FILE_PLACEHOLDER: &str = "%e";
 
+/// Default limit on the size of the data that internal extractors decompress into memory
+pub const DEFAULT_DECOMPRESSION_LIMIT: usize = 256 * 1024 * 1024;
+
+thread_local! {
+    /// Limit on the size of the data that internal extractors running on this thread decompress into memory
+    static DECOMPRESSION_LIMIT: Cell<usize> = const { Cell::new(DEFAULT_DECOMPRESSION_LIMIT) };
+    /// Set when an internal extractor running on this thread reaches its decompression limit
+    static DECOMPRESSION_LIMIT_REACHED: Cell<bool> = const { Cell::new(false) };
+}
+
 /// Return value of InternalExtractor upon error
 #[derive(Debug, Clone)]
 pub struct ExtractionError;
@@ -26,11 +42,17 @@ pub struct ExtractionError;
 /// Arguments: file_data, offset, output_directory.
 pub type InternalExtractor = fn(&[u8], usize, Option<&str>) -> ExtractionResult;
 
-/// Enum to define either an Internal or External extractor type
+/// Called with the progress reported by external extractors while they run.
+/// Extractors run in worker threads, so this may be called from several threads at once.
+pub type ProgressCallback = fn(&ExtractionProgress);
+
+/// Enum to define either an Internal, External, or WebAssembly extractor type
 #[derive(Debug, Default, Clone)]
 pub enum ExtractorType {
     External(String),
     Internal(InternalExtractor),
+    /// Path to a sandboxed WebAssembly extractor module
+    Wasm(String),
     #[default]
     None,
 }
@@ -40,6 +62,7 @@ impl PartialEq for ExtractorType {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (ExtractorType::External(s1), ExtractorType::External(s2)) => s1 == s2,
+            (ExtractorType::Wasm(s1), ExtractorType::Wasm(s2)) => s1 == s2,
             (ExtractorType::None, ExtractorType::None) => true,
             // 对于Internal类型，我们只能判断它们都是Internal类型，而不比较函数指针本身
             (ExtractorType::Internal(_), ExtractorType::Internal(_)) => false, // 函数指针不能安全比较
@@ -61,6 +84,9 @@ impl PartialOrd for ExtractorType {
             (_, ExtractorType::None) => Some(std::cmp::Ordering::Greater),
             (ExtractorType::External(_), ExtractorType::Internal(_)) => Some(std::cmp::Ordering::Less),
             (ExtractorType::Internal(_), ExtractorType::External(_)) => Some(std::cmp::Ordering::Greater),
+            (ExtractorType::Wasm(s1), ExtractorType::Wasm(s2)) => s1.partial_cmp(s2),
+            (ExtractorType::Wasm(_), _) => Some(std::cmp::Ordering::Greater),
+            (_, ExtractorType::Wasm(_)) => Some(std::cmp::Ordering::Less),
             // 对于两个Internal类型，我们无法可靠地比较它们
             (ExtractorType::Internal(_), ExtractorType::Internal(_)) => None,
         }
@@ -82,6 +108,43 @@ pub struct Extractor {
     pub exit_codes: Vec<i32>,
     /// Set to true to disable recursion into this extractor's extracted files
     pub do_not_recurse: bool,
+    /// Additional arguments passed to the external command when running non-interactively, to prevent it from prompting for input
+    pub batch_arguments: Vec<String>,
+    /// Set to true if the external command reports its progress on stdout as a percentage
+    pub reports_progress: bool,
+    /// Additional arguments passed to the external command when its progress is being reported, to enable its progress output
+    pub progress_arguments: Vec<String>,
+    /// Extractor to run if this extractor fails, such as an external utility that handles data the internal extractor does not
+    pub fallback: Option<Box<Extractor>>,
+}
+
+/// Controls how external extraction utilities are run
+#[derive(Debug, Default, Clone)]
+pub struct ExternalOptions {
+    /// If true, external utilities are given no stdin and are passed their batch arguments, so they can never wait on a prompt
+    pub non_interactive: bool,
+    /// External utilities still running after this amount of time are killed
+    pub timeout: Option<time::Duration>,
+    /// If set, the progress of external utilities that report their progress is passed to this function
+    pub progress: Option<ProgressCallback>,
+    /// Internal extractors that reach the default decompression limit are run once more with this limit, if it is larger
+    pub max_extract_size: Option<usize>,
+    /// If true, external utilities are never run, and data that only they can extract is not extracted
+    pub internal_only: bool,
+}
+
+/// Progress of a running external extractor
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractionProgress {
+    /// Path to the file being extracted from
+    pub file_path: String,
+    pub signature_name: String,
+    /// File offset of the data being extracted
+    pub offset: usize,
+    /// Name of the extraction utility
+    pub utility: String,
+    /// Percentage of the extraction that is complete
+    pub percent: u8,
 }
 
 /// Stores information about a completed extraction
@@ -98,6 +161,24 @@ pub struct ExtractionResult {
     pub do_not_recurse: bool,
     /// The output directory where the extractor dropped its files, automatically populated by extractors::common::execute
     pub output_directory: String,
+    /// Encrypted content found by the extractor that it could not decrypt
+    #[serde(default)]
+    pub locked: Vec<LockedContent>,
+    /// Set if the extractor reached its decompression limit, and the extracted data is incomplete
+    #[serde(default)]
+    pub truncated: bool,
+    /// If the extractor reached the default decompression limit and was run again with a raised limit, the raised limit
+    #[serde(default)]
+    pub decompression_limit: Option<usize>,
+    /// External utility needed to extract the data, if it was not run because only internal extractors are allowed
+    #[serde(default)]
+    pub unavailable_utility: Option<String>,
+    /// SHA256 hash of the signature's data, if it was hashed before extraction; see `Binwalk.hash_extracted_data`
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Set if the extractor was not run because the signature's data is unchanged from a previous version
+    #[serde(default)]
+    pub unchanged: bool,
 }
 
 /// Stores information about external extractor processes. For internal use only.
@@ -711,6 +792,78 @@ impl Chroot {
         false
     }
 
+    /// Sets the permission bits of an existing file or directory in the chroot directory.
+    ///
+    /// Set-user-ID, set-group-ID and sticky bits are never set, and the owner is always granted read and write access
+    /// (and search access to directories), so that extracted files can still be analyzed and cleaned up.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use binwalk::extractors::common::Chroot;
+    ///
+    /// let chroot_dir = std::path::Path::new("tests")
+    ///     .join("binwalk_unit_tests")
+    ///     .display()
+    ///     .to_string();
+    ///
+    /// let file_name = "config.txt";
+    ///
+    /// # std::fs::remove_dir_all(&chroot_dir);
+    /// let chroot = Chroot::new(Some(&chroot_dir));
+    /// chroot.create_file(file_name, b"AAAA");
+    ///
+    /// assert_eq!(chroot.set_permissions(file_name, 0o640), true);
+    /// # std::fs::remove_dir_all(&chroot_dir);
+    /// ```
+    pub fn set_permissions(&self, file_path: impl Into<String>, mode: u32) -> bool {
+        let safe_file_path: String = self.chrooted_path(file_path);
+
+        match fs::symlink_metadata(&safe_file_path) {
+            Err(e) => {
+                error!("Failed to get permissions for file {safe_file_path}: {e}");
+            }
+            Ok(metadata) => {
+                // Symlink permissions are meaningless, and setting them would change the symlink target's permissions
+                if metadata.is_symlink() {
+                    return false;
+                }
+
+                #[cfg(unix)]
+                {
+                    const PERMISSION_BITS: u32 = 0o777;
+                    const OWNER_FILE_ACCESS: u32 = 0o600;
+                    const OWNER_DIRECTORY_ACCESS: u32 = 0o700;
+
+                    let owner_access = match metadata.is_dir() {
+                        true => OWNER_DIRECTORY_ACCESS,
+                        false => OWNER_FILE_ACCESS,
+                    };
+
+                    let permissions =
+                        fs::Permissions::from_mode((mode & PERMISSION_BITS) | owner_access);
+
+                    match fs::set_permissions(&safe_file_path, permissions) {
+                        Err(e) => {
+                            error!("Failed to set permissions for file {safe_file_path}: {e}");
+                        }
+                        Ok(_) => {
+                            return true;
+                        }
+                    }
+                }
+                #[cfg(windows)]
+                {
+                    // Unix permission bits have no equivalent here
+                    let _ = mode;
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
     /// Creates a symbolic link in the chroot directory, named `symlink_path`, which points to `target_path`.
     ///
     /// Note that both the symlink and target paths will be sanitized to stay in the chroot directory.
@@ -964,18 +1117,25 @@ pub fn get_extracted_files(directory: &str) -> Vec<String> {
 }
 
 /// Executes an extractor for the provided SignatureResult.
+///
+/// The signature offset is relative to `file_data`, which starts at `file_data_offset` in the file at `file_path`;
+/// this is non-zero when only a window of the file's data has been read into memory.
 pub fn execute(
     file_data: &[u8],
+    file_data_offset: usize,
     file_path: &str,
     signature: &SignatureResult,
     extractor: &Option<Extractor>,
+    external_options: &ExternalOptions,
 ) -> ExtractionResult {
     let mut result = ExtractionResult {
         ..Default::default()
     };
 
+    let mut fallback_extractor: Option<Extractor> = None;
+
     // Create an output directory for the extraction
-    if let Ok(output_directory) = create_output_directory(file_path, signature.offset) {
+    if let Ok(output_directory) = create_output_directory(file_path, file_data_offset + signature.offset) {
         // Make sure a defalut extractor was actually defined (this function should not be called if signature.extractor is None)
         match &extractor {
             None => {
@@ -1007,19 +1167,93 @@ pub fn execute(
                     ExtractorType::Internal(func) => {
                         debug!("Executing internal {} extractor", signature.name);
                         // Run the internal extractor function
+                        set_decompression_limit(DEFAULT_DECOMPRESSION_LIMIT);
                         result = func(file_data, signature.offset, Some(&output_directory));
+
+                        // Data that decompresses to more than the default limit is extracted again with the raised limit, if there is one
+                        if decompression_limit_was_reached() {
+                            match external_options
+                                .max_extract_size
+                                .filter(|limit| *limit > DEFAULT_DECOMPRESSION_LIMIT)
+                            {
+                                None => result.truncated = true,
+                                Some(raised_limit) => {
+                                    info!(
+                                        "{} data at offset {:#X} exceeds the decompression limit of {} bytes, retrying with a limit of {} bytes",
+                                        signature.name,
+                                        signature.offset,
+                                        DEFAULT_DECOMPRESSION_LIMIT,
+                                        raised_limit
+                                    );
+
+                                    // Discard the incomplete extraction; the output directory is re-created by the extractor
+                                    if let Err(e) = fs::remove_dir_all(&output_directory) {
+                                        warn!(
+                                            "Failed to clean up extraction directory {output_directory} before retrying: {e}"
+                                        );
+                                    }
+
+                                    set_decompression_limit(raised_limit);
+                                    result =
+                                        func(file_data, signature.offset, Some(&output_directory));
+                                    result.decompression_limit = Some(raised_limit);
+                                    result.truncated = decompression_limit_was_reached();
+                                    set_decompression_limit(DEFAULT_DECOMPRESSION_LIMIT);
+                                }
+                            }
+
+                            if result.truncated {
+                                warn!(
+                                    "{} data at offset {:#X} was truncated at the decompression limit; raise the limit with --max-extract-size",
+                                    signature.name, signature.offset
+                                );
+                            }
+                        }
+
                         // Set the extractor name to "<signature name>_built_in"
                         result.extractor = format!("{}_built_in", signature.name);
                     }
 
+                    ExtractorType::Wasm(module_path) => {
+                        debug!("Executing WebAssembly {} extractor", signature.name);
+
+                        // Pass the signature's data to the extractor; if the size is unknown, pass everything through EOF
+                        let data_end = match signature.size {
+                            0 => file_data.len(),
+                            size => std::cmp::min(signature.offset + size, file_data.len()),
+                        };
+
+                        if let Some(extractor_data) = file_data.get(signature.offset..data_end) {
+                            result = execute_wasm_extractor(
+                                module_path,
+                                extractor_data,
+                                &output_directory,
+                            );
+                            result.size = Some(extractor_data.len());
+                        }
+
+                        result.extractor = module_path.clone();
+                    }
+
+                    // Fail closed, without carving any data, if external utilities aren't allowed
+                    ExtractorType::External(cmd) if external_options.internal_only => {
+                        warn!(
+                            "Extraction of {} data at offset {:#X} requires the external utility '{}', which is disabled in internal-only mode",
+                            signature.name, signature.offset, cmd
+                        );
+                        result.unavailable_utility = Some(cmd.to_string());
+                    }
+
                     ExtractorType::External(cmd) => {
                         // Spawn the external extractor command
                         match spawn(
                             file_data,
+                            file_data_offset,
                             file_path,
                             &output_directory,
                             signature,
                             extractor_definition.clone(),
+                            external_options,
                         ) {
                             Err(e) => {
                                 #[cfg(windows)]
@@ -1037,16 +1271,24 @@ pub fn execute(
                                 
                                 #[cfg(not(windows))]
                                 {
-                                    error!(
-                                        "Failed to spawn external extractor for '{}' signature: {}",
-                                        signature.name, e
-                                    );
+                                    // Systems may have none of the external utilities installed, which is not an error
+                                    if e.kind() == std::io::ErrorKind::NotFound {
+                                        warn!(
+                                            "External extractor '{}' is not installed, '{}' data was not extracted",
+                                            cmd, signature.name
+                                        );
+                                    } else {
+                                        error!(
+                                            "Failed to spawn external extractor for '{}' signature: {}",
+                                            signature.name, e
+                                        );
+                                    }
                                 }
                             }
 
                             Ok(proc_info) => {
                                 // Wait for the external process to exit
-                                match proc_wait(proc_info) {
+                                match proc_wait(proc_info, external_options.timeout) {
                                     Err(_) => {
                                         warn!("External extractor failed!");
                                     }
@@ -1064,6 +1306,7 @@ pub fn execute(
                 // Populate these ExtractionResult fields automatically for all extractors
                 result.output_directory = output_directory.clone();
                 result.do_not_recurse = extractor_definition.do_not_recurse;
+                fallback_extractor = extractor_definition.fallback.map(|fallback| *fallback);
 
                 // If the extractor reported success, make sure it extracted something other than just an empty file
                 if result.success && !was_something_extracted(&result.output_directory) {
@@ -1080,26 +1323,163 @@ pub fn execute(
                     "Failed to clean up extraction directory {output_directory} after extraction failure: {e}"
                 );
             }
+
+            // Give the fallback extractor, if any, a chance to extract the data; it is never run in internal-only mode
+            if let Some(fallback) = fallback_extractor.filter(|_| !external_options.internal_only) {
+                let fallback_name = match &fallback.utility {
+                    ExtractorType::External(name) | ExtractorType::Wasm(name) => name.clone(),
+                    ExtractorType::Internal(_) | ExtractorType::None => "built-in".to_string(),
+                };
+
+                info!(
+                    "{} extractor failed on data at offset {:#X}, falling back to the {} extractor",
+                    signature.name, signature.offset, fallback_name
+                );
+
+                // The signature's preferred extractor, if any, has already been tried
+                let mut fallback_signature = signature.clone();
+                fallback_signature.preferred_extractor = None;
+
+                return execute(
+                    file_data,
+                    file_data_offset,
+                    file_path,
+                    &fallback_signature,
+                    &Some(fallback),
+                    external_options,
+                );
+            }
         }
     }
 
     result
 }
 
+/// Returns the limit on the size of the data that internal extractors may decompress into memory.
+/// Decompressors that can not decompress valid data within this limit must call `report_decompression_limit_reached`.
+pub fn decompression_limit() -> usize {
+    DECOMPRESSION_LIMIT.with(|limit| limit.get())
+}
+
+/// Records that a decompressor reached the decompression limit, and that its data was truncated or not decompressed
+pub fn report_decompression_limit_reached() {
+    DECOMPRESSION_LIMIT_REACHED.with(|limit_reached| limit_reached.set(true));
+}
+
+/// Sets the decompression limit for internal extractors running on the current thread
+fn set_decompression_limit(limit: usize) {
+    DECOMPRESSION_LIMIT.with(|decompression_limit| decompression_limit.set(limit));
+    DECOMPRESSION_LIMIT_REACHED.with(|limit_reached| limit_reached.set(false));
+}
+
+/// Returns true if an internal extractor running on the current thread reached its decompression limit
+fn decompression_limit_was_reached() -> bool {
+    DECOMPRESSION_LIMIT_REACHED.with(|limit_reached| limit_reached.get())
+}
+
+/// Searches for an executable the way the operating system would when running it, and returns its absolute path.
+///
+/// Names that include a directory are looked up relative to the current directory, other names are searched for in
+/// the PATH. On Windows, the directory of the running executable is searched as well, and the extensions listed in
+/// PATHEXT are tried for names that don't have one.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::common::find_executable;
+///
+/// assert_eq!(find_executable("no-such-extraction-utility"), None);
+/// ```
+pub fn find_executable(name: &str) -> Option<path::PathBuf> {
+    let has_directory = path::Path::new(name).components().count() > 1;
+    let mut search_directories: Vec<path::PathBuf> = vec![];
+
+    if name.is_empty() {
+        return None;
+    }
+
+    // An empty directory resolves relative to the current directory
+    if has_directory {
+        search_directories.push(path::PathBuf::new());
+    }
+
+    // Windows looks for executables next to the running executable
+    #[cfg(windows)]
+    if let Some(exe_directory) = env::current_exe()
+        .ok()
+        .and_then(|exe_path| exe_path.parent().map(path::Path::to_path_buf))
+    {
+        search_directories.push(exe_directory);
+    }
+
+    if !has_directory {
+        if let Some(search_path) = env::var_os("PATH") {
+            search_directories.extend(
+                env::split_paths(&search_path)
+                    .filter(|directory| !directory.as_os_str().is_empty()),
+            );
+        }
+    }
+
+    search_directories
+        .iter()
+        .flat_map(|directory| executable_candidates(&directory.join(name)))
+        .find(|candidate| is_executable(candidate))
+        .and_then(|candidate| path::absolute(candidate).ok())
+}
+
+/// Returns the file paths that an executable may have; on Windows, these include the extensions listed in PATHEXT
+fn executable_candidates(executable: &path::Path) -> Vec<path::PathBuf> {
+    let candidates = vec![executable.to_path_buf()];
+
+    #[cfg(windows)]
+    if executable.extension().is_none() {
+        const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
+
+        let path_extensions = env::var("PATHEXT").unwrap_or(DEFAULT_PATHEXT.to_string());
+
+        return candidates
+            .into_iter()
+            .chain(
+                path_extensions
+                    .split(';')
+                    .map(|extension| extension.trim_start_matches('.'))
+                    .filter(|extension| !extension.is_empty())
+                    .map(|extension| executable.with_extension(extension)),
+            )
+            .collect();
+    }
+
+    candidates
+}
+
+/// Returns true if the specified path is a file that can be executed
+fn is_executable(file_path: &path::Path) -> bool {
+    match fs::metadata(file_path) {
+        Err(_) => false,
+        #[cfg(unix)]
+        Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+        #[cfg(not(unix))]
+        Ok(metadata) => metadata.is_file(),
+    }
+}
+
 /// Spawn an external extractor process.
 fn spawn(
     file_data: &[u8],
+    file_data_offset: usize,
     file_path: &str,
     output_directory: &str,
     signature: &SignatureResult,
     mut extractor: Extractor,
+    external_options: &ExternalOptions,
 ) -> Result<ProcInfo, std::io::Error> {
     let chroot = Chroot::new(None);
 
     // This function *only* handles execution of external extraction utilities; internal extractors must be invoked directly
     let command = match &extractor.utility {
         ExtractorType::External(cmd) => cmd.clone(),
-        ExtractorType::Internal(_ext) => {
+        ExtractorType::Internal(_) | ExtractorType::Wasm(_) => {
             error!("Tried to run an internal extractor as an external command!");
             return Err(std::io::Error::other(
                 "attempt to execute an internal extractor as an external command",
@@ -1113,25 +1493,45 @@ fn spawn(
         }
     };
 
+    // Look up the utility before carving any data, so that systems without external utilities don't pay for carving
+    let executable = match find_executable(&command) {
+        Some(executable_path) => executable_path,
+        None => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{command} not found"),
+            ));
+        }
+    };
+
+    // Offset of the signature in the source file
+    let file_offset = file_data_offset + signature.offset;
+
     // Carved file path will be <output directory>/<signature.name>_<hex offset>.<extractor.extension>
     let carved_file = format!(
         "{}{}{}_{:X}.{}",
         output_directory,
         path::MAIN_SEPARATOR,
         signature.name,
-        signature.offset,
+        file_offset,
         extractor.extension
     );
     info!(
         "Carving data from {} {:#X}..{:#X} to {}",
         file_path,
-        signature.offset,
-        signature.offset + signature.size,
+        file_offset,
+        file_offset + signature.size,
         carved_file
     );
 
-    // If the entirety of the source file is this one file type, no need to carve a copy of it, just create a symlink
-    if signature.offset == 0 && signature.size == file_data.len() {
+    // If the entirety of the source file is this one file type, no need to carve a copy of it, just create a symlink.
+    // The size of the file on disk is checked, as file_data may be only a window of the file.
+    let source_file_size = fs::metadata(file_path).map(|md| md.len() as usize).ok();
+
+    if file_offset == 0
+        && signature.size == file_data.len()
+        && source_file_size == Some(file_data.len())
+    {
         if !chroot.create_symlink(&carved_file, file_path) {
             return Err(std::io::Error::other(
                 "Failed to create carved file symlink",
@@ -1144,6 +1544,32 @@ fn spawn(
         }
     }
 
+    // Batch and progress arguments go just before the input file, after any command that the utility expects as its first argument
+    let optional_arguments_index = extractor
+        .arguments
+        .iter()
+        .position(|argument| argument == SOURCE_FILE_PLACEHOLDER)
+        .unwrap_or(extractor.arguments.len());
+
+    if external_options.non_interactive {
+        extractor.arguments.splice(
+            optional_arguments_index..optional_arguments_index,
+            extractor.batch_arguments.clone(),
+        );
+    }
+
+    // Progress is only reported for utilities that report it
+    let progress = external_options
+        .progress
+        .filter(|_| extractor.reports_progress);
+
+    if progress.is_some() {
+        extractor.arguments.splice(
+            optional_arguments_index..optional_arguments_index,
+            extractor.progress_arguments.clone(),
+        );
+    }
+
     // Replace all "%e" command arguments with the path to the carved file
     for i in 0..extractor.arguments.len() {
         if extractor.arguments[i] == SOURCE_FILE_PLACEHOLDER {
@@ -1151,32 +1577,28 @@ fn spawn(
         }
     }
 
-    info!("Spawning process {} {:?}", command, extractor.arguments);
-    
-    // 尝试在Windows上查找带.exe后缀的命令
-    #[cfg(windows)]
-    let cmd_to_use = {
-        // 首先尝试原始命令
-        if process::Command::new(&command).arg("--version").output().is_ok() {
-            command.clone()
-        } else {
-            // 然后尝试添加.exe后缀
-            let cmd_with_exe = format!("{}.exe", command);
-            if process::Command::new(&cmd_with_exe).arg("--version").output().is_ok() {
-                cmd_with_exe
-            } else {
-                command.clone() // 如果都失败，使用原始命令，让错误正常传播
-            }
-        }
+    info!(
+        "Spawning process {} {:?}",
+        executable.display(),
+        extractor.arguments
+    );
+
+    // Without stdin, a utility that prompts for input fails instead of waiting forever
+    let stdin = match external_options.non_interactive {
+        true => process::Stdio::null(),
+        false => process::Stdio::inherit(),
+    };
+
+    // The progress output of a utility is read from its stdout
+    let stdout = match progress {
+        Some(_) => process::Stdio::piped(),
+        None => process::Stdio::null(),
     };
-    
-    // 在非Windows平台上，直接使用原始命令
-    #[cfg(not(windows))]
-    let cmd_to_use = command.clone();
-    
-    match process::Command::new(&cmd_to_use)
+
+    match process::Command::new(&executable)
         .args(&extractor.arguments)
-        .stdout(process::Stdio::null())
+        .stdin(stdin)
+        .stdout(stdout)
         .stderr(process::Stdio::null())
         .current_dir(output_directory)
         .spawn()
@@ -1188,12 +1610,12 @@ fn spawn(
                 if e.kind() == std::io::ErrorKind::NotFound {
                     warn!(
                         "命令 '{}' 在Windows系统中未找到。在Windows平台上，部分提取功能可能受限。请考虑：\n1. 安装对应的Windows版本工具\n2. 在Linux环境中使用完整功能\n3. 检查PATH环境变量是否包含工具路径",
-                        cmd_to_use
+                        command
                     );
                 }
                 debug!(
                     "Failed to execute command {}{:?}: {}",
-                    cmd_to_use, extractor.arguments, e
+                    command, extractor.arguments, e
                 );
             }
             
@@ -1201,13 +1623,30 @@ fn spawn(
             {
                 error!(
                     "Failed to execute command {}{:?}: {}",
-                    cmd_to_use, extractor.arguments, e
+                    command, extractor.arguments, e
                 );
             }
             Err(e)
         }
 
-        Ok(child) => {
+        Ok(mut child) => {
+            if let Some(callback) = progress {
+                if let Some(child_stdout) = child.stdout.take() {
+                    let extraction_progress = ExtractionProgress {
+                        file_path: file_path.to_string(),
+                        signature_name: signature.name.clone(),
+                        offset: file_offset,
+                        utility: command.clone(),
+                        ..Default::default()
+                    };
+
+                    // The reader thread exits when the utility closes its stdout
+                    thread::spawn(move || {
+                        read_progress(child_stdout, extraction_progress, callback)
+                    });
+                }
+            }
+
             // If the process was spawned successfully, return some information about the process
             let proc_info = ProcInfo {
                 child,
@@ -1220,14 +1659,109 @@ fn spawn(
     }
 }
 
-/// Waits for an extraction process to complete.
+/// Reads the progress output of an external utility, passing each new progress percentage to the callback.
+/// To avoid flooding the callback, progress is passed at most once per reporting interval, except for completion.
+fn read_progress(
+    mut utility_output: impl Read,
+    mut progress: ExtractionProgress,
+    callback: ProgressCallback,
+) {
+    const REPORTING_INTERVAL: time::Duration = time::Duration::from_secs(1);
+    const COMPLETE: u8 = 100;
+    // Progress output that is not line based is not buffered indefinitely
+    const MAX_LINE_LENGTH: usize = 4096;
+
+    let mut buffer = [0; 4096];
+    let mut line: Vec<u8> = vec![];
+    let mut last_report: Option<time::Instant> = None;
+
+    while let Ok(read_size) = utility_output.read(&mut buffer) {
+        if read_size == 0 {
+            break;
+        }
+
+        for b in &buffer[..read_size] {
+            // Progress bars are commonly redrawn with carriage returns or backspaces rather than new lines
+            if !b"\r\n\x08".contains(b) && line.len() < MAX_LINE_LENGTH {
+                line.push(*b);
+                continue;
+            }
+
+            if let Some(percent) = parse_progress(&String::from_utf8_lossy(&line)) {
+                if percent > progress.percent
+                    && (percent == COMPLETE
+                        || last_report
+                            .is_none_or(|report_time| report_time.elapsed() >= REPORTING_INTERVAL))
+                {
+                    progress.percent = percent;
+                    last_report = Some(time::Instant::now());
+                    callback(&progress);
+                }
+            }
+
+            line.clear();
+        }
+    }
+}
+
+/// Parses a line of progress output from an external utility, returning the percentage complete.
+///
+/// Both bare percentages, such as those printed by `unsquashfs -percentage`, and percentages followed by a percent sign,
+/// such as those printed in progress bars, are recognized.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::common::parse_progress;
+///
+/// assert_eq!(parse_progress("42"), Some(42));
+/// assert_eq!(parse_progress(" 37% 12 - squashfs-root/bin/busybox"), Some(37));
+/// assert_eq!(parse_progress("[=========|          ] 1000/2000  50%"), Some(50));
+/// assert_eq!(parse_progress("Extracting archive: rootfs.7z"), None);
+/// ```
+pub fn parse_progress(line: &str) -> Option<u8> {
+    const MAX_PERCENT: u8 = 100;
+
+    let line = line.trim();
+
+    let digits = match line.find('%') {
+        Some(percent_sign) => {
+            let before_percent_sign = &line[..percent_sign];
+            let digits_start = before_percent_sign
+                .rfind(|c: char| !c.is_ascii_digit())
+                .map(|i| i + 1)
+                .unwrap_or(0);
+            &before_percent_sign[digits_start..]
+        }
+        None => line,
+    };
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    digits
+        .parse::<u8>()
+        .ok()
+        .filter(|percent| *percent <= MAX_PERCENT)
+}
+
+/// Waits for an extraction process to complete, killing it if it exceeds the specified timeout.
 /// Returns ExtractionError if the extractor was prematurely terminated, else returns an ExtractionResult.
-fn proc_wait(mut worker_info: ProcInfo) -> Result<ExtractionResult, ExtractionError> {
+fn proc_wait(
+    mut worker_info: ProcInfo,
+    timeout: Option<time::Duration>,
+) -> Result<ExtractionResult, ExtractionError> {
     // The standard exit success value is 0
     const EXIT_SUCCESS: i32 = 0;
 
     // Block until child process has terminated
-    match worker_info.child.wait() {
+    let exit_status = match timeout {
+        None => worker_info.child.wait(),
+        Some(max_run_time) => wait_with_timeout(&mut worker_info.child, max_run_time),
+    };
+
+    match exit_status {
         // Child was terminated from an external signal, status unknown, assume failure but do nothing else
         Err(e) => {
             error!("Failed to retreive child process status: {e}");
@@ -1273,6 +1807,34 @@ fn proc_wait(mut worker_info: ProcInfo) -> Result<ExtractionResult, ExtractionEr
     }
 }
 
+/// Waits for a child process to exit; if it is still running after the specified amount of time, it is killed.
+pub fn wait_with_timeout(
+    child: &mut process::Child,
+    timeout: time::Duration,
+) -> Result<process::ExitStatus, std::io::Error> {
+    // How often to check if the process has exited
+    const POLL_INTERVAL: time::Duration = time::Duration::from_millis(10);
+
+    let start_time = time::Instant::now();
+
+    loop {
+        if let Some(exit_status) = child.try_wait()? {
+            return Ok(exit_status);
+        }
+
+        if start_time.elapsed() >= timeout {
+            warn!(
+                "External utility did not exit within {} seconds, killing it",
+                timeout.as_secs()
+            );
+            child.kill()?;
+            return child.wait();
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
 // Create an output directory in which to place extraction results
 fn create_output_directory(file_path: &str, offset: usize) -> Result<String, std::io::Error> {
     let chroot = Chroot::new(None);
@@ -0,0 +1,65 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::config::parse_xor_config;
+
+/// Defines the internal extractor function for XOR obfuscated configuration files
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::config::xor_config_extractor;
+///
+/// match xor_config_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn xor_config_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_xor_config),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for decoding XOR obfuscated XML configuration files
+pub fn extract_xor_config(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTFILE_NAME: &str = "config.xml";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(config_data) = file_data.get(offset..) {
+        if let Ok(xor_config) = parse_xor_config(config_data) {
+            result.size = Some(xor_config.size);
+            result.success = true;
+
+            if output_directory.is_some() {
+                let chroot = Chroot::new(output_directory);
+
+                let decoded_config: Vec<u8> = config_data[0..xor_config.size]
+                    .iter()
+                    .map(|b| b ^ xor_config.key)
+                    .collect();
+
+                result.success = chroot.create_file(OUTFILE_NAME, &decoded_config);
+            }
+        }
+    }
+
+    result
+}
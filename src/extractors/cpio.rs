@@ -0,0 +1,273 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::extractors::sevenzip::sevenzip_extractor;
+use crate::structures::cpio::{CPIOEntryHeader, parse_cpio_entry_header};
+use log::warn;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// File type bits of an entry's mode
+const S_IFMT: usize = 0o170000;
+const S_IFSOCK: usize = 0o140000;
+const S_IFLNK: usize = 0o120000;
+const S_IFREG: usize = 0o100000;
+const S_IFBLK: usize = 0o060000;
+const S_IFDIR: usize = 0o040000;
+const S_IFCHR: usize = 0o020000;
+const S_IFIFO: usize = 0o010000;
+
+/// Any of the executable bits of an entry's mode
+const S_IXANY: usize = 0o111;
+
+/// Defines the internal extractor function for extracting CPIO archives
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::cpio::cpio_extractor;
+///
+/// match cpio_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn cpio_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_cpio),
+        fallback: Some(Box::new(sevenzip_extractor())),
+        ..Default::default()
+    }
+}
+
+/// Internal CPIO extractor, supports newc and odc archives
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::cpio::extract_cpio;
+///
+/// // Archives of hello.txt and bin/run.sh, created by bsdtar, without the trailing zero padding
+/// let newc_archive = b"\
+///     0707010023e0f2000081a4000000000000000000000001000000000000000d000000fe000000000000000000000000\
+///     0000000a00000000hello.txt\0Hello, CPIO!\n\0\0\0\
+///     0707010023e101000081ed000000000000000000000001000000000000000a000000fe000000000000000000000000\
+///     0000000b00000000bin/run.sh\0\0\0\0#!/bin/sh\n\0\0\
+///     0707010000000000000000000000000000000000000001000000000000000000000000000000000000000000000000\
+///     0000000b00000000TRAILER!!!\0\0\0\0";
+/// let odc_archive = b"\
+///     0707071770000000011006440000000000000000010000000000000000000001200000000015hello.txt\0\
+///     Hello, CPIO!\n\
+///     0707071770000000021007550000000000000000010000000000000000000001300000000012bin/run.sh\0\
+///     #!/bin/sh\n\
+///     0707070000000000000000000000000000000000010000000000000000000001300000000000TRAILER!!!\0";
+///
+/// let output_directory = std::env::temp_dir()
+///     .join("binwalk_cpio_doctest")
+///     .display()
+///     .to_string();
+///
+/// for archive in [&newc_archive[..], &odc_archive[..]] {
+///     # std::fs::remove_dir_all(&output_directory).ok();
+///     let result = extract_cpio(archive, 0, Some(&output_directory));
+///
+///     assert_eq!(result.success, true);
+///     assert_eq!(result.size, Some(archive.len()));
+///
+///     let cpio_root = std::path::Path::new(&output_directory).join("cpio-root");
+///     assert_eq!(std::fs::read(cpio_root.join("hello.txt")).unwrap(), b"Hello, CPIO!\n");
+///     assert_eq!(std::fs::read(cpio_root.join("bin").join("run.sh")).unwrap(), b"#!/bin/sh\n");
+/// }
+/// # std::fs::remove_dir_all(&output_directory).ok();
+/// ```
+pub fn extract_cpio(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    // CPIO files will be extracted to this sub-directory of the output directory
+    const OUTPUT_DIRECTORY_NAME: &str = "cpio-root";
+
+    // The last CPIO entry will have this file name
+    const EOF_MARKER: &str = "TRAILER!!!";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    // All CPIO entries, and the offset of their file data
+    let mut entries: Vec<(CPIOEntryHeader, usize)> = vec![];
+    let mut entry_offset = offset;
+    let mut eof_found = false;
+
+    while let Some(entry_data) = file_data.get(entry_offset..) {
+        let entry_header = match parse_cpio_entry_header(entry_data) {
+            Ok(entry_header) => entry_header,
+            Err(_) => break,
+        };
+
+        // All entries in an archive should be the same format
+        if let Some((first_entry, _)) = entries.first() {
+            if entry_header.magic != first_entry.magic {
+                break;
+            }
+        }
+
+        let data_start = entry_offset + entry_header.header_size;
+
+        // File data must be in bounds
+        if file_data
+            .get(data_start..data_start + entry_header.file_size)
+            .is_none()
+        {
+            break;
+        }
+
+        // Padding after the last entry may extend past EOF
+        entry_offset = std::cmp::min(file_data.len(), data_start + entry_header.data_size);
+
+        if entry_header.file_name == EOF_MARKER {
+            eof_found = true;
+            break;
+        }
+
+        entries.push((entry_header, data_start));
+    }
+
+    // A valid archive should have at least one entry, followed by the EOF entry
+    if eof_found && !entries.is_empty() {
+        result.success = true;
+        result.size = Some(entry_offset - offset);
+
+        // Do extraction, if an output directory was provided
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+            let mut file_count: usize = 0;
+
+            if chroot.create_directory(OUTPUT_DIRECTORY_NAME) {
+                let cpio_chroot = Chroot::new(Some(&chroot.chrooted_path(OUTPUT_DIRECTORY_NAME)));
+                file_count = extract_cpio_entries(file_data, &entries, &cpio_chroot);
+            }
+
+            // If no files were extracted, extraction was a failure
+            if file_count == 0 {
+                result.success = false;
+            }
+        }
+    }
+
+    result
+}
+
+/// Extracts all CPIO entries, returns the number of extracted files/directories
+fn extract_cpio_entries(
+    file_data: &[u8],
+    entries: &[(CPIOEntryHeader, usize)],
+    chroot: &Chroot,
+) -> usize {
+    let mut file_count: usize = 0;
+
+    /*
+     * Hard links share the same inode, but only one of the links has the file data:
+     * the last link in newc archives, and the first link in odc archives.
+     * Hard links are extracted as copies of the file data.
+     */
+    let mut hardlink_data: HashMap<usize, &[u8]> = HashMap::new();
+    let mut pending_hardlinks: HashMap<usize, Vec<(String, usize)>> = HashMap::new();
+
+    for (entry, data_start) in entries {
+        let entry_data = &file_data[*data_start..*data_start + entry.file_size];
+
+        // The archive root directory is typically included as "."
+        let file_name = entry.file_name.trim_start_matches("./");
+        if file_name.is_empty() || file_name == "." {
+            continue;
+        }
+
+        let file_path = chroot.safe_path_join("", file_name);
+        let file_type = entry.mode & S_IFMT;
+
+        // Entries are not guaranteed to be preceded by their parent directory entries
+        if let Some(parent_directory) = Path::new(&file_path).parent() {
+            chroot.create_directory(parent_directory.display().to_string());
+        }
+
+        let extraction_success = match file_type {
+            S_IFDIR => chroot.create_directory(&file_path),
+            S_IFREG if entry.nlink > 1 => {
+                if !entry_data.is_empty() {
+                    hardlink_data.insert(entry.inode, entry_data);
+
+                    // Any previously encountered links to this inode can now be created
+                    for (link_path, link_mode) in
+                        pending_hardlinks.remove(&entry.inode).unwrap_or_default()
+                    {
+                        if create_regular_file(chroot, &link_path, entry_data, link_mode) {
+                            file_count += 1;
+                        }
+                    }
+
+                    create_regular_file(chroot, &file_path, entry_data, entry.mode)
+                } else if let Some(link_data) = hardlink_data.get(&entry.inode) {
+                    create_regular_file(chroot, &file_path, link_data, entry.mode)
+                } else {
+                    pending_hardlinks
+                        .entry(entry.inode)
+                        .or_default()
+                        .push((file_path.clone(), entry.mode));
+                    continue;
+                }
+            }
+            S_IFREG => create_regular_file(chroot, &file_path, entry_data, entry.mode),
+            S_IFLNK => {
+                chroot.create_symlink(&file_path, String::from_utf8_lossy(entry_data).to_string())
+            }
+            S_IFCHR => {
+                chroot.create_character_device(&file_path, entry.rdev_major, entry.rdev_minor)
+            }
+            S_IFBLK => chroot.create_block_device(&file_path, entry.rdev_major, entry.rdev_minor),
+            S_IFIFO => chroot.create_fifo(&file_path),
+            S_IFSOCK => chroot.create_socket(&file_path),
+            _ => {
+                warn!("Unknown file type for CPIO file {file_path}");
+                continue;
+            }
+        };
+
+        if extraction_success {
+            file_count += 1;
+        } else {
+            warn!("Failed to extract CPIO file {file_path}");
+        }
+    }
+
+    // Any remaining hard links have no file data; they are empty files
+    for (link_path, link_mode) in pending_hardlinks.into_values().flatten() {
+        if create_regular_file(chroot, &link_path, b"", link_mode) {
+            file_count += 1;
+        }
+    }
+
+    file_count
+}
+
+/// Creates a regular file, and makes it executable if any of the mode's executable bits are set
+fn create_regular_file(chroot: &Chroot, file_path: &str, file_data: &[u8], mode: usize) -> bool {
+    if chroot.create_file(file_path, file_data) {
+        if (mode & S_IXANY) != 0 {
+            chroot.make_executable(file_path);
+        }
+        return true;
+    }
+
+    false
+}
@@ -0,0 +1,318 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::common;
+use crate::structures::cramfs::{
+    CRAMFS_INODE_SIZE, CRAMFS_ROOT_INODE_OFFSET, CramFSHeader, CramFSInode, parse_cramfs_header,
+    parse_cramfs_inode,
+};
+use flate2::bufread::ZlibDecoder;
+use log::warn;
+use std::collections::HashSet;
+use std::io::Read;
+
+/// File type bits of an inode mode
+const S_IFMT: usize = 0o170000;
+const S_IFSOCK: usize = 0o140000;
+const S_IFLNK: usize = 0o120000;
+const S_IFREG: usize = 0o100000;
+const S_IFBLK: usize = 0o060000;
+const S_IFDIR: usize = 0o040000;
+const S_IFCHR: usize = 0o020000;
+const S_IFIFO: usize = 0o010000;
+
+/// Any of the executable bits of an inode mode
+const S_IXANY: usize = 0o111;
+
+/// File data is compressed in blocks of this size
+const CRAMFS_BLOCK_SIZE: usize = 4096;
+
+/// Image flag indicating that block pointers may have the below block flags set
+const CRAMFS_FLAG_EXT_BLOCK_POINTERS: usize = 0x800;
+
+/// Block pointer flags
+const CRAMFS_BLK_FLAG_UNCOMPRESSED: usize = 1 << 31;
+const CRAMFS_BLK_FLAG_DIRECT_PTR: usize = 1 << 30;
+const CRAMFS_BLK_FLAGS: usize = CRAMFS_BLK_FLAG_UNCOMPRESSED | CRAMFS_BLK_FLAG_DIRECT_PTR;
+
+/// Defines the internal extractor function for extracting CramFS file systems
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::cramfs::cramfs_extractor;
+///
+/// match cramfs_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn cramfs_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_cramfs),
+        ..Default::default()
+    }
+}
+
+/// Internal CramFS extractor, supports both little and big endian images
+pub fn extract_cramfs(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    // CramFS files will be extracted to this sub-directory of the output directory
+    const OUTPUT_DIRECTORY_NAME: &str = "cramfs-root";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(cramfs_header) = parse_cramfs_header(&file_data[offset..]) {
+        // All offsets in the image are relative to the start of the image
+        if let Some(cramfs_data) = file_data.get(offset..offset + cramfs_header.size) {
+            if let Some(root_inode_data) = cramfs_data.get(CRAMFS_ROOT_INODE_OFFSET..) {
+                if let Ok(root_inode) =
+                    parse_cramfs_inode(root_inode_data, &cramfs_header.endianness)
+                {
+                    // The root inode must be a directory
+                    if (root_inode.mode & S_IFMT) == S_IFDIR {
+                        result.success = true;
+                        result.size = Some(cramfs_header.size);
+
+                        // Do extraction, if an output directory was provided
+                        if output_directory.is_some() {
+                            let chroot = Chroot::new(output_directory);
+                            let mut file_count: usize = 0;
+
+                            if chroot.create_directory(OUTPUT_DIRECTORY_NAME) {
+                                let cramfs_chroot =
+                                    Chroot::new(Some(&chroot.chrooted_path(OUTPUT_DIRECTORY_NAME)));
+                                let mut visited_directories: HashSet<usize> = HashSet::new();
+
+                                file_count = extract_cramfs_directory(
+                                    cramfs_data,
+                                    &cramfs_header,
+                                    &root_inode,
+                                    "",
+                                    &cramfs_chroot,
+                                    &mut visited_directories,
+                                );
+                            }
+
+                            // If no files were extracted, extraction was a failure
+                            if file_count == 0 {
+                                result.success = false;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Recursively extracts the contents of a CramFS directory, returns the number of extracted files/directories
+fn extract_cramfs_directory(
+    cramfs_data: &[u8],
+    cramfs_header: &CramFSHeader,
+    directory_inode: &CramFSInode,
+    directory_path: &str,
+    chroot: &Chroot,
+    visited_directories: &mut HashSet<usize>,
+) -> usize {
+    let mut file_count: usize = 0;
+
+    // Sanity check, a directory should only be processed once, else infinite recursion could ensue
+    if !visited_directories.insert(directory_inode.data_offset) {
+        return file_count;
+    }
+
+    // Directory entries are a list of inodes, each followed by the file name
+    let mut entry_offset = directory_inode.data_offset;
+    let directory_end = directory_inode.data_offset + directory_inode.size;
+
+    while entry_offset < directory_end {
+        let inode = match cramfs_data
+            .get(entry_offset..)
+            .map(|inode_data| parse_cramfs_inode(inode_data, &cramfs_header.endianness))
+        {
+            Some(Ok(inode)) => inode,
+            _ => {
+                warn!("Invalid CramFS directory entry at offset {entry_offset:#X}");
+                break;
+            }
+        };
+
+        let name_start = entry_offset + CRAMFS_INODE_SIZE;
+        let name_end = name_start + inode.name_length;
+        entry_offset = name_end;
+
+        // File names are NULL padded to a multiple of 4 bytes
+        let file_name = match cramfs_data.get(name_start..name_end) {
+            Some(name_bytes) => String::from_utf8_lossy(name_bytes)
+                .trim_end_matches('\0')
+                .to_string(),
+            None => {
+                warn!("CramFS file name at offset {name_start:#X} is out of bounds");
+                break;
+            }
+        };
+
+        let file_path = chroot.safe_path_join(directory_path, &file_name);
+        let file_type = inode.mode & S_IFMT;
+
+        let extraction_success = match file_type {
+            S_IFDIR => chroot.create_directory(&file_path),
+            S_IFREG => match cramfs_file_data(cramfs_data, cramfs_header, &inode) {
+                Some(file_contents) => chroot.create_file(&file_path, &file_contents),
+                None => false,
+            },
+            S_IFLNK => match cramfs_file_data(cramfs_data, cramfs_header, &inode) {
+                Some(target) => {
+                    chroot.create_symlink(&file_path, String::from_utf8_lossy(&target).to_string())
+                }
+                None => false,
+            },
+            S_IFCHR | S_IFBLK => {
+                // For device files, the inode size field is the device number
+                let major = (inode.size >> 8) & 0xFFF;
+                let minor = (inode.size & 0xFF) | ((inode.size >> 12) & 0xFFF00);
+
+                if file_type == S_IFCHR {
+                    chroot.create_character_device(&file_path, major, minor)
+                } else {
+                    chroot.create_block_device(&file_path, major, minor)
+                }
+            }
+            S_IFIFO => chroot.create_fifo(&file_path),
+            S_IFSOCK => chroot.create_socket(&file_path),
+            _ => {
+                warn!("Unknown file type for CramFS file {file_path}");
+                continue;
+            }
+        };
+
+        if extraction_success {
+            file_count += 1;
+
+            if file_type == S_IFDIR {
+                file_count += extract_cramfs_directory(
+                    cramfs_data,
+                    cramfs_header,
+                    &inode,
+                    &file_path,
+                    chroot,
+                    visited_directories,
+                );
+            } else if file_type == S_IFREG && (inode.mode & S_IXANY) != 0 {
+                // Make executable files executable
+                chroot.make_executable(&file_path);
+            }
+        } else {
+            warn!("Failed to extract CramFS file {file_path}");
+        }
+    }
+
+    file_count
+}
+
+/// Reads and decompresses a file's data
+fn cramfs_file_data(
+    cramfs_data: &[u8],
+    cramfs_header: &CramFSHeader,
+    inode: &CramFSInode,
+) -> Option<Vec<u8>> {
+    let block_pointer_structure = vec![("pointer", "u32")];
+    let block_pointer_size = common::size(&block_pointer_structure);
+
+    let extended_pointers = (cramfs_header.flags & CRAMFS_FLAG_EXT_BLOCK_POINTERS) != 0;
+
+    let mut file_contents: Vec<u8> = vec![];
+
+    // File data is preceded by a list of block pointers, one for each block of the file
+    let block_count = inode.size.div_ceil(CRAMFS_BLOCK_SIZE);
+
+    // Non-direct block pointers point to the end of the block; data for the first block starts immediately after the block pointers
+    let mut block_start = inode.data_offset + (block_count * block_pointer_size);
+
+    for block_index in 0..block_count {
+        let remaining_size = inode.size - file_contents.len();
+        let expected_size = std::cmp::min(remaining_size, CRAMFS_BLOCK_SIZE);
+
+        let pointer_offset = inode.data_offset + (block_index * block_pointer_size);
+        let block_pointer = common::parse(
+            cramfs_data.get(pointer_offset..)?,
+            &block_pointer_structure,
+            &cramfs_header.endianness,
+        )
+        .ok()?["pointer"];
+
+        let mut uncompressed = false;
+        let block_end: usize;
+
+        if extended_pointers && (block_pointer & CRAMFS_BLK_FLAG_DIRECT_PTR) != 0 {
+            // Direct pointers point to the start of the block, as a count of 4-byte words
+            block_start = (block_pointer & !CRAMFS_BLK_FLAGS) << 2;
+
+            if (block_pointer & CRAMFS_BLK_FLAG_UNCOMPRESSED) != 0 {
+                uncompressed = true;
+                block_end = block_start + expected_size;
+            } else {
+                // Compressed blocks are prefixed with their compressed size
+                let block_size_structure = vec![("size", "u16")];
+                let block_size = common::parse(
+                    cramfs_data.get(block_start..)?,
+                    &block_size_structure,
+                    &cramfs_header.endianness,
+                )
+                .ok()?["size"];
+
+                block_start += common::size(&block_size_structure);
+                block_end = block_start + block_size;
+            }
+        } else if extended_pointers {
+            uncompressed = (block_pointer & CRAMFS_BLK_FLAG_UNCOMPRESSED) != 0;
+            block_end = block_pointer & !CRAMFS_BLK_FLAGS;
+        } else {
+            block_end = block_pointer;
+        }
+
+        let block_data = cramfs_data.get(block_start..block_end)?;
+
+        if block_data.is_empty() {
+            // Empty blocks are holes in the file
+            file_contents.resize(file_contents.len() + expected_size, 0);
+        } else if uncompressed {
+            file_contents.extend_from_slice(block_data);
+        } else {
+            let mut decompressed_block: Vec<u8> = vec![];
+            let decompressor = ZlibDecoder::new(block_data);
+
+            if decompressor
+                .take(CRAMFS_BLOCK_SIZE as u64)
+                .read_to_end(&mut decompressed_block)
+                .is_err()
+            {
+                return None;
+            }
+
+            file_contents.extend_from_slice(&decompressed_block);
+        }
+
+        block_start = block_end;
+    }
+
+    file_contents.truncate(inode.size);
+    Some(file_contents)
+}
@@ -0,0 +1,84 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::locked::LockedContent;
+use crate::structures::pkcs::{
+    parse_pkcs7, parse_pkcs12, parse_rsa_private_key, parse_rsa_public_key,
+};
+use crate::structures::x509::parse_x509_certificate;
+
+/// Defines the internal extractor function for carving out DER encoded certificates, keys and PKCS containers
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::der::der_extractor;
+///
+/// match der_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn der_extractor() -> Extractor {
+    Extractor {
+        do_not_recurse: true,
+        utility: ExtractorType::Internal(der_carver),
+        ..Default::default()
+    }
+}
+
+/// Carves a DER encoded certificate, key or PKCS container to a file named after its type
+pub fn der_carver(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    let der_data = &file_data[offset..];
+
+    let carved = if let Ok(certificate) = parse_x509_certificate(der_data) {
+        Some(("certificate.der", certificate.size))
+    } else if let Ok(pkcs7) = parse_pkcs7(der_data) {
+        Some(("pkcs7.p7b", pkcs7.size))
+    } else if let Ok(pkcs12) = parse_pkcs12(der_data) {
+        Some(("pkcs12.p12", pkcs12.size))
+    } else if let Ok(rsa_key) = parse_rsa_private_key(der_data) {
+        Some(("rsa_private_key.der", rsa_key.size))
+    } else if let Ok(rsa_key) = parse_rsa_public_key(der_data) {
+        Some(("rsa_public_key.der", rsa_key.size))
+    } else {
+        None
+    };
+
+    if let Some((file_name, der_size)) = carved {
+        result.size = Some(der_size);
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+            result.success = chroot.carve_file(file_name, file_data, offset, der_size);
+
+            // The keys and certificates in a PKCS#12 key store are encrypted with a password
+            if result.success && file_name == "pkcs12.p12" {
+                result.locked.push(LockedContent::new(
+                    format!("PKCS#12 key store, carved to {file_name}"),
+                    "PKCS#12 password",
+                ));
+            }
+        }
+    }
+
+    result
+}
@@ -0,0 +1,72 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::dex::{parse_dex_header, parse_odex_header, parse_vdex_header};
+
+/// Defines the internal extractor function for carving out DEX, ODEX and VDEX files
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::dex::dex_extractor;
+///
+/// match dex_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn dex_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(dex_carver),
+        ..Default::default()
+    }
+}
+
+/// Carves a DEX, ODEX or VDEX file to a file named after its type; ODEX and VDEX files embed DEX files, which are
+/// identified when the carved file is scanned
+pub fn dex_carver(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    let dex_data = &file_data[offset..];
+
+    let carved = if let Ok(dex_header) = parse_dex_header(dex_data) {
+        Some(("classes.dex", dex_header.size))
+    } else if let Ok(odex_header) = parse_odex_header(dex_data) {
+        Some(("classes.odex", odex_header.size))
+    } else if let Ok(vdex_header) = parse_vdex_header(dex_data) {
+        vdex_header
+            .size
+            .map(|vdex_size| ("classes.vdex", vdex_size))
+    } else {
+        None
+    };
+
+    if let Some((file_name, carved_size)) = carved {
+        if carved_size <= dex_data.len() {
+            result.size = Some(carved_size);
+            result.success = true;
+
+            if output_directory.is_some() {
+                let chroot = Chroot::new(output_directory);
+                result.success = chroot.carve_file(file_name, file_data, offset, carved_size);
+            }
+        }
+    }
+
+    result
+}
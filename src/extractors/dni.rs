@@ -0,0 +1,73 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::dni::{DNI_HEADER_SIZE, parse_dni_header};
+
+/// Defines the internal extractor for DNI firmware images
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::dni::dni_extractor;
+///
+/// match dni_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn dni_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_dni_payload),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for the payload of a DNI firmware image.
+///
+/// DNI headers do not specify the image size; the image is expected to extend to EOF, where it ends with a checksum
+/// byte that makes the sum of all bytes in the image 0xFF.
+pub fn extract_dni_payload(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTPUT_FILE_NAME: &str = "firmware.bin";
+    const CHECKSUM_SIZE: usize = 1;
+    const EXPECTED_CHECKSUM: u8 = 0xFF;
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    let image_data = &file_data[offset..];
+
+    if image_data.len() > DNI_HEADER_SIZE + CHECKSUM_SIZE && parse_dni_header(image_data).is_ok() {
+        let checksum = image_data.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+
+        if checksum == EXPECTED_CHECKSUM {
+            result.size = Some(image_data.len());
+            result.success = true;
+
+            if output_directory.is_some() {
+                let chroot = Chroot::new(output_directory);
+                result.success = chroot.carve_file(
+                    OUTPUT_FILE_NAME,
+                    file_data,
+                    offset + DNI_HEADER_SIZE,
+                    image_data.len() - DNI_HEADER_SIZE - CHECKSUM_SIZE,
+                );
+            }
+        }
+    }
+
+    result
+}
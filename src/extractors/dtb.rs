@@ -1,7 +1,8 @@
-use crate::common::is_offset_safe;
 use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
-use crate::structures::dtb::{parse_dtb_header, parse_dtb_node};
-use log::error;
+use crate::structures::dtb::{
+    DTBTreeNode, parse_dtb_header, parse_dtb_memory_reservations, parse_dtb_tree,
+};
+use log::warn;
 
 /// Defines the internal extractor function for extracting Device Tree Blobs
 ///
@@ -14,6 +15,7 @@ use log::error;
 /// match dtb_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -32,13 +34,17 @@ pub fn dtb_extractor() -> Extractor {
     }
 }
 
-/// Internal extractor for extracting Device Tree Blobs
+/// Internal extractor for Device Tree Blobs.
+///
+/// The device tree is decompiled to device tree source, which is written to `system.dts`. If the DTB is a Flattened
+/// Image Tree (FIT), the data of each image in the FIT's `/images` node, such as kernels, ramdisks and FDTs, is also
+/// written to `images/<image node name>`.
 pub fn extract_dtb(
     file_data: &[u8],
     offset: usize,
     output_directory: Option<&str>,
 ) -> ExtractionResult {
-    let mut heirerarchy: Vec<String> = Vec::new();
+    const DTS_FILE_NAME: &str = "system.dts";
 
     let mut result = ExtractionResult {
         ..Default::default()
@@ -48,58 +54,235 @@ pub fn extract_dtb(
     if let Ok(dtb_header) = parse_dtb_header(&file_data[offset..]) {
         // Get all the DTB data
         if let Some(dtb_data) = file_data.get(offset..offset + dtb_header.total_size) {
-            // DTB node entries start at the structure offset specified in the DTB header
-            let mut entry_offset = dtb_header.struct_offset;
-            let mut previous_entry_offset = None;
-            let available_data = dtb_data.len();
-
-            // Loop over all DTB node entries
-            while is_offset_safe(available_data, entry_offset, previous_entry_offset) {
-                // Parse the next DTB node entry
-                let node = parse_dtb_node(&dtb_header, dtb_data, entry_offset);
-
-                // Beginning of a node, add it to the heirerarchy list
-                if node.begin {
-                    if !node.name.is_empty() {
-                        heirerarchy.push(node.name.clone());
-                    }
-                // End of a node, remove it from the heirerarchy list
-                } else if node.end {
-                    if !heirerarchy.is_empty() {
-                        heirerarchy.pop();
+            // Parse the device tree; this fails if the DTB structure could not be parsed up to its end marker
+            if let Ok(root_node) = parse_dtb_tree(&dtb_header, dtb_data) {
+                let reservations =
+                    parse_dtb_memory_reservations(&dtb_header, dtb_data).unwrap_or_default();
+                let fit_images =
+                    get_fit_images(&root_node, file_data, offset, dtb_header.total_size);
+
+                // FIT images may store image data after the end of the DTB
+                let fit_size = fit_images
+                    .iter()
+                    .map(|fit_image| fit_image.end - offset)
+                    .max()
+                    .unwrap_or(0);
+
+                result.success = true;
+                result.size = Some(dtb_header.total_size.max(fit_size));
+
+                if output_directory.is_some() {
+                    let chroot = Chroot::new(output_directory);
+
+                    if !fit_images.is_empty() && !chroot.create_directory(FIT_IMAGES_DIRECTORY) {
+                        result.success = false;
                     }
-                // End of the DTB structure, return success only if the whole DTB structure was parsed successfully up to the EOF marker
-                } else if node.eof {
-                    result.success = true;
-                    result.size = Some(available_data);
-                    break;
-                // DTB property, extract it to disk
-                } else if node.property {
-                    if output_directory.is_some() {
-                        let chroot = Chroot::new(output_directory);
-                        let dir_path = heirerarchy.join(std::path::MAIN_SEPARATOR_STR);
-                        let file_path = chroot.safe_path_join(&dir_path, &node.name);
-
-                        if !chroot.create_directory(dir_path) {
-                            break;
-                        }
 
-                        if !chroot.create_file(file_path, &node.data) {
-                            break;
+                    for fit_image in &fit_images {
+                        let file_path = chroot.safe_path_join(FIT_IMAGES_DIRECTORY, fit_image.name);
+
+                        if !chroot.create_file(file_path, fit_image.data) {
+                            result.success = false;
                         }
                     }
-                // The only other supported node type is NOP
-                } else if !node.nop {
-                    error!("Unknown or invalid DTB node");
-                    break;
-                }
 
-                // Update offsets to parse the next DTB structure entry
-                previous_entry_offset = Some(entry_offset);
-                entry_offset += node.total_size;
+                    let dts = dts_source(&root_node, &reservations);
+
+                    if !chroot.create_file(DTS_FILE_NAME, dts.as_bytes()) {
+                        result.success = false;
+                    }
+                }
             }
         }
     }
 
     result
 }
+
+/// FIT image data is extracted to this directory
+const FIT_IMAGES_DIRECTORY: &str = "images";
+
+/// Describes an image contained in a FIT
+struct FitImage<'a> {
+    /// Name of the image's node
+    name: &'a str,
+    /// The image data
+    data: &'a [u8],
+    /// Offset of the end of the image data in the file; for images embedded in the DTB, this is the end of the DTB
+    end: usize,
+}
+
+/// Returns the images contained in a FIT. Returns an empty list if the DTB is not a FIT.
+///
+/// Image data is usually embedded in the DTB as the image node's `data` property. It may instead be stored after the
+/// DTB, in which case the `data-size` property gives the size of the data, and its location is given either by the
+/// `data-position` property, relative to the start of the DTB, or by the `data-offset` property, relative to the
+/// 4-byte aligned end of the DTB.
+fn get_fit_images<'a>(
+    root_node: &'a DTBTreeNode,
+    file_data: &'a [u8],
+    offset: usize,
+    dtb_size: usize,
+) -> Vec<FitImage<'a>> {
+    const EXTERNAL_DATA_ALIGNMENT: usize = 4;
+
+    let mut fit_images: Vec<FitImage> = vec![];
+
+    if let Some(images_node) = root_node.child(FIT_IMAGES_DIRECTORY) {
+        for image_node in &images_node.children {
+            if let Some(data) = image_node.property("data") {
+                fit_images.push(FitImage {
+                    name: &image_node.name,
+                    data,
+                    end: offset + dtb_size,
+                });
+                continue;
+            }
+
+            let data_size = image_node.property("data-size").and_then(dtb_u32);
+            let data_start = match (
+                image_node.property("data-position").and_then(dtb_u32),
+                image_node.property("data-offset").and_then(dtb_u32),
+            ) {
+                (Some(data_position), _) => Some(offset + data_position),
+                (None, Some(data_offset)) => {
+                    Some(offset + dtb_size.next_multiple_of(EXTERNAL_DATA_ALIGNMENT) + data_offset)
+                }
+                (None, None) => None,
+            };
+
+            if let (Some(data_start), Some(data_size)) = (data_start, data_size) {
+                match file_data.get(data_start..data_start + data_size) {
+                    None => warn!(
+                        "FIT image {} data at offset {:#X} is beyond the end of the available data",
+                        image_node.name, data_start
+                    ),
+                    Some(data) => fit_images.push(FitImage {
+                        name: &image_node.name,
+                        data,
+                        end: data_start + data_size,
+                    }),
+                }
+            }
+        }
+    }
+
+    fit_images
+}
+
+/// Interprets a property value as a single 32-bit cell
+fn dtb_u32(value: &[u8]) -> Option<usize> {
+    Some(u32::from_be_bytes(value.try_into().ok()?) as usize)
+}
+
+/// Decompiles a device tree to device tree source
+fn dts_source(root_node: &DTBTreeNode, reservations: &[(usize, usize)]) -> String {
+    let mut dts = "/dts-v1/;\n".to_string();
+
+    for (address, size) in reservations {
+        dts.push_str(&format!("/memreserve/ {address:#018x} {size:#018x};\n"));
+    }
+
+    dts.push('\n');
+    dts_node(&mut dts, root_node, 0, false);
+
+    dts
+}
+
+/// Appends the device tree source for a node, and all its children, to `dts`.
+/// Embedded data of FIT images is referenced by the path of its extracted file, rather than included in the source.
+fn dts_node(dts: &mut String, node: &DTBTreeNode, depth: usize, is_fit_image: bool) {
+    let indent = "\t".repeat(depth);
+
+    let node_name = match depth {
+        0 => "/",
+        _ => &node.name,
+    };
+
+    dts.push_str(&format!("{indent}{node_name} {{\n"));
+
+    for (name, value) in &node.properties {
+        if is_fit_image && name == "data" {
+            dts.push_str(&format!(
+                "{indent}\t{name} = /incbin/(\"{FIT_IMAGES_DIRECTORY}/{}\");\n",
+                node.name
+            ));
+        } else if value.is_empty() {
+            dts.push_str(&format!("{indent}\t{name};\n"));
+        } else {
+            dts.push_str(&format!("{indent}\t{name} = {};\n", dts_value(value)));
+        }
+    }
+
+    for child in &node.children {
+        dts.push('\n');
+        dts_node(
+            dts,
+            child,
+            depth + 1,
+            depth == 1 && node.name == FIT_IMAGES_DIRECTORY,
+        );
+    }
+
+    dts.push_str(&format!("{indent}}};\n"));
+}
+
+/// Formats a property value as a list of strings, a list of 32-bit cells, or a byte string, as appropriate
+fn dts_value(value: &[u8]) -> String {
+    const CELL_SIZE: usize = 4;
+
+    if is_string_list(value) {
+        return value[..value.len() - 1]
+            .split(|b| *b == 0)
+            .map(|string| {
+                let mut escaped = String::new();
+
+                for b in string {
+                    match b {
+                        b'"' => escaped.push_str("\\\""),
+                        b'\\' => escaped.push_str("\\\\"),
+                        b'\n' => escaped.push_str("\\n"),
+                        b'\r' => escaped.push_str("\\r"),
+                        b'\t' => escaped.push_str("\\t"),
+                        _ => escaped.push(*b as char),
+                    }
+                }
+
+                format!("\"{escaped}\"")
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+    }
+
+    if value.len().is_multiple_of(CELL_SIZE) {
+        let cells: Vec<String> = value
+            .chunks(CELL_SIZE)
+            .map(|cell| format!("{:#04x}", u32::from_be_bytes(cell.try_into().unwrap())))
+            .collect();
+
+        return format!("<{}>", cells.join(" "));
+    }
+
+    let bytes: Vec<String> = value.iter().map(|b| format!("{b:02x}")).collect();
+
+    format!("[{}]", bytes.join(" "))
+}
+
+/// Returns true if the value is a list of one or more non-empty, NULL terminated, printable strings
+fn is_string_list(value: &[u8]) -> bool {
+    match value.split_last() {
+        Some((0, strings)) => {
+            !strings.is_empty()
+                && strings.first() != Some(&0)
+                && !strings.windows(2).any(|pair| pair == [0, 0])
+                && strings.iter().all(|b| {
+                    *b == 0
+                        || *b == b'\t'
+                        || *b == b'\n'
+                        || *b == b'\r'
+                        || (0x20..=0x7E).contains(b)
+                })
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,97 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::efigpt::parse_efigpt_header;
+
+/// Defines the internal extractor function for EFI GPT partitions
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::efigpt::efigpt_extractor;
+///
+/// match efigpt_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn efigpt_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_efigpt_partitions),
+        ..Default::default()
+    }
+}
+
+/// Validate and extract partitions from an EFI GPT disk image
+pub fn extract_efigpt_partitions(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    let available_data = file_data.len() - offset;
+
+    if let Ok(gpt_header) = parse_efigpt_header(&file_data[offset..]) {
+        // Some EFI images have been observed to define partitions that extend beyond EOF; assume the image extends to EOF
+        result.size = Some(gpt_header.total_size.min(available_data));
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+
+            for (partition_count, partition) in gpt_header.partitions.iter().enumerate() {
+                // Partitions that start beyond EOF have nothing to carve
+                if partition.start >= available_data {
+                    continue;
+                }
+
+                // Partition names are not unique, output file will be: "<name>_partition.<partition count>"
+                let partition_name = match partition.name.is_empty() {
+                    true => &partition.type_name,
+                    false => &partition.name,
+                };
+
+                let file_name = format!(
+                    "{}_partition.{}",
+                    safe_file_name(partition_name),
+                    partition_count
+                );
+
+                result.success = chroot.carve_file(
+                    file_name,
+                    file_data,
+                    offset + partition.start,
+                    partition.size.min(available_data - partition.start),
+                );
+
+                if !result.success {
+                    break;
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Partition names are user defined; replace anything that isn't safe to use in a file name
+fn safe_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.is_ascii_alphanumeric() || "-_. ".contains(c) {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
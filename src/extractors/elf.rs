@@ -0,0 +1,57 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::elf::parse_elf_size;
+
+/// Defines the internal extractor function for carving out ELF files
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::elf::elf_extractor;
+///
+/// match elf_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn elf_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(elf_carver),
+        ..Default::default()
+    }
+}
+
+/// Carves an ELF file out of the surrounding data, as far as its program and section header tables describe
+pub fn elf_carver(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTFILE_NAME: &str = "carved.elf";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(elf_size) = parse_elf_size(&file_data[offset..]) {
+        result.size = Some(elf_size);
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+            result.success = chroot.carve_file(OUTFILE_NAME, file_data, offset, elf_size);
+        }
+    }
+
+    result
+}
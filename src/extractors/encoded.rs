@@ -0,0 +1,106 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::common::StructureError;
+use crate::structures::encoded::{EncodedData, parse_base64_text, parse_hex_text};
+
+/// Name of the file that encoded data is decoded to
+const DECODED_FILE_NAME: &str = "decoded.bin";
+
+/// Defines the internal extractor function for decoding base64 encoded data
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::encoded::base64_extractor;
+///
+/// match base64_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn base64_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(base64_decoder),
+        ..Default::default()
+    }
+}
+
+/// Defines the internal extractor function for decoding hex encoded data
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::encoded::hex_extractor;
+///
+/// match hex_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn hex_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(hex_decoder),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for decoding base64 encoded data
+pub fn base64_decoder(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    decode(parse_base64_text(&file_data[offset..]), output_directory)
+}
+
+/// Internal extractor for decoding hex encoded data
+pub fn hex_decoder(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    decode(parse_hex_text(&file_data[offset..]), output_directory)
+}
+
+/// Writes successfully decoded data to the output directory
+fn decode(
+    encoded: Result<EncodedData, StructureError>,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(encoded_data) = encoded {
+        result.size = Some(encoded_data.size);
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+            result.success = chroot.create_file(DECODED_FILE_NAME, &encoded_data.data);
+        }
+    }
+
+    result
+}
@@ -0,0 +1,344 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::exfat::{
+    EXFAT_DIRECTORY_ENTRY_SIZE, EXFAT_END_OF_DIRECTORY, EXFAT_FILE_ENTRY, EXFATHeader,
+    EXFATStreamEntry, parse_exfat_file_entry, parse_exfat_header, parse_exfat_name_entry,
+    parse_exfat_stream_entry,
+};
+use log::warn;
+use std::collections::HashSet;
+
+/// Defines the internal extractor function for extracting exFAT file systems
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::exfat::exfat_extractor;
+///
+/// match exfat_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn exfat_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_exfat),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for exFAT file systems.
+///
+/// As with FAT, dirty and truncated file systems are extracted as completely as possible.
+pub fn extract_exfat(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(exfat_data) = file_data.get(offset..) {
+        if let Ok(exfat_header) = parse_exfat_header(exfat_data) {
+            // Only the available data is used if the file system is truncated
+            let image_end = std::cmp::min(exfat_header.total_size, exfat_data.len());
+
+            if image_end < exfat_header.total_size {
+                warn!(
+                    "exFAT file system is truncated to {} of {} bytes",
+                    image_end, exfat_header.total_size
+                );
+            }
+
+            if exfat_header.dirty {
+                warn!("exFAT file system was not cleanly unmounted; some files may be incomplete");
+            }
+
+            let exfat_image = ExfatImage {
+                data: &exfat_data[..image_end],
+                header: exfat_header,
+            };
+
+            // The root directory is always recorded in the FAT
+            let root_directory =
+                exfat_image.read_chain(exfat_image.header.root_cluster, None, false);
+            let root_entries = exfat_image.directory_entries(&root_directory);
+
+            if !root_entries.is_empty() {
+                if output_directory.is_none() {
+                    result.success = true;
+                } else {
+                    let chroot = Chroot::new(output_directory);
+                    let mut visited_directories = HashSet::from([exfat_image.header.root_cluster]);
+
+                    let file_count = exfat_image.extract_directory(
+                        &chroot,
+                        &root_entries,
+                        "",
+                        &mut visited_directories,
+                    );
+
+                    result.success = file_count > 0;
+                }
+
+                if result.success {
+                    result.size = Some(exfat_image.data.len());
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// A file or directory in an exFAT directory
+#[derive(Debug, Default, Clone)]
+struct ExfatFile {
+    name: String,
+    directory: bool,
+    stream: EXFATStreamEntry,
+}
+
+/// Provides read-only access to the clusters of an exFAT file system
+struct ExfatImage<'a> {
+    data: &'a [u8],
+    header: EXFATHeader,
+}
+
+impl ExfatImage<'_> {
+    /// Returns the FAT entry for the specified cluster, if it is available
+    fn fat_entry(&self, cluster: usize) -> Option<usize> {
+        let entry_offset = self.header.fat_offset + (cluster * 4);
+        let entry_bytes = self.data.get(entry_offset..entry_offset + 4)?;
+        Some(u32::from_le_bytes(entry_bytes.try_into().unwrap()) as usize)
+    }
+
+    /// Returns true if the cluster number refers to a cluster in the cluster heap
+    fn is_heap_cluster(&self, cluster: usize) -> bool {
+        // The first cluster in the heap is cluster 2
+        cluster >= 2 && cluster < self.header.cluster_count + 2
+    }
+
+    /// Returns the chain of clusters starting at `first_cluster`, up to `max_clusters` long.
+    ///
+    /// Contiguous chains are not recorded in the FAT; other chains end at an end of chain marker,
+    /// or at any free, bad, or previously visited cluster.
+    fn cluster_chain(
+        &self,
+        first_cluster: usize,
+        max_clusters: usize,
+        contiguous: bool,
+    ) -> Vec<usize> {
+        let mut chain: Vec<usize> = vec![];
+        let mut visited_clusters: HashSet<usize> = HashSet::new();
+        let mut cluster = first_cluster;
+
+        while chain.len() < max_clusters
+            && self.is_heap_cluster(cluster)
+            && visited_clusters.insert(cluster)
+        {
+            chain.push(cluster);
+
+            if contiguous {
+                cluster += 1;
+            } else {
+                match self.fat_entry(cluster) {
+                    None => break,
+                    Some(next_cluster) => cluster = next_cluster,
+                }
+            }
+        }
+
+        chain
+    }
+
+    /// Reads up to `size` bytes from the chain of clusters starting at `first_cluster`; if `size` is None, the entire chain is read
+    fn read_chain(&self, first_cluster: usize, size: Option<usize>, contiguous: bool) -> Vec<u8> {
+        let cluster_size = self.header.cluster_size;
+        let max_clusters = match size {
+            None => self.header.cluster_count,
+            Some(size) => size.div_ceil(cluster_size),
+        };
+
+        let mut chain_data: Vec<u8> = vec![];
+
+        for cluster in self.cluster_chain(first_cluster, max_clusters, contiguous) {
+            let cluster_offset = self.header.cluster_heap_offset + ((cluster - 2) * cluster_size);
+            let cluster_end = std::cmp::min(cluster_offset + cluster_size, self.data.len());
+
+            match self.data.get(cluster_offset..cluster_end) {
+                None => break,
+                Some(cluster_data) => chain_data.extend_from_slice(cluster_data),
+            }
+
+            // A partial cluster is the end of the available data
+            if cluster_end < cluster_offset + cluster_size {
+                break;
+            }
+        }
+
+        if let Some(size) = size {
+            chain_data.truncate(size);
+        }
+
+        chain_data
+    }
+
+    /// Returns the contents of a file; data past the file's valid data length reads as zeros
+    fn file_data(&self, stream: &EXFATStreamEntry) -> Vec<u8> {
+        let mut file_data = self.read_chain(
+            stream.first_cluster,
+            Some(stream.valid_data_length),
+            stream.contiguous,
+        );
+
+        // A file's clusters are allocated up to its data length, so it can never be larger than the file system
+        if file_data.len() == stream.valid_data_length {
+            file_data.resize(std::cmp::min(stream.data_length, self.data.len()), 0);
+        }
+
+        file_data
+    }
+
+    /// Parses the file entry sets in a directory; deleted entries and entries other than files and directories are excluded
+    fn directory_entries(&self, directory_data: &[u8]) -> Vec<ExfatFile> {
+        let entries: Vec<&[u8]> = directory_data
+            .chunks_exact(EXFAT_DIRECTORY_ENTRY_SIZE)
+            .collect();
+
+        let mut files: Vec<ExfatFile> = vec![];
+        let mut i: usize = 0;
+
+        while i < entries.len() {
+            let entry_type = entries[i][0];
+
+            if entry_type == EXFAT_END_OF_DIRECTORY {
+                break;
+            }
+
+            // Deleted file entries have a different entry type, and are skipped along with all other entry types
+            if entry_type != EXFAT_FILE_ENTRY {
+                i += 1;
+                continue;
+            }
+
+            let file_entry = match parse_exfat_file_entry(entries[i]) {
+                Err(_) => {
+                    i += 1;
+                    continue;
+                }
+                Ok(file_entry) => file_entry,
+            };
+
+            let set_end = std::cmp::min(i + 1 + file_entry.secondary_count, entries.len());
+
+            // The stream entry immediately follows the file entry, and is followed by the name entries
+            if let Some(stream_entry) = entries.get(i + 1) {
+                if let Ok(stream) = parse_exfat_stream_entry(stream_entry) {
+                    let mut name: Vec<u16> = vec![];
+
+                    for name_entry in &entries[std::cmp::min(i + 2, set_end)..set_end] {
+                        match parse_exfat_name_entry(name_entry) {
+                            Err(_) => break,
+                            Ok(name_part) => name.extend(name_part),
+                        }
+
+                        if name.len() >= stream.name_length {
+                            break;
+                        }
+                    }
+
+                    if name.len() >= stream.name_length {
+                        let name = String::from_utf16_lossy(&name[..stream.name_length]);
+
+                        if !name.is_empty()
+                            && name != "."
+                            && name != ".."
+                            && !name.contains(['/', '\0'])
+                        {
+                            files.push(ExfatFile {
+                                name,
+                                directory: file_entry.directory,
+                                stream,
+                            });
+                        }
+                    }
+                }
+            }
+
+            i = set_end;
+        }
+
+        files
+    }
+
+    /// Extracts the entries of a directory, and all of its sub-directories; returns the number of extracted files
+    fn extract_directory(
+        &self,
+        chroot: &Chroot,
+        files: &[ExfatFile],
+        parent_directory: &str,
+        visited_directories: &mut HashSet<usize>,
+    ) -> usize {
+        let mut file_count: usize = 0;
+
+        for file in files {
+            let file_path = chroot.safe_path_join(parent_directory, &file.name);
+
+            if file.directory {
+                // Directories linked more than once could cause infinite recursion
+                if !visited_directories.insert(file.stream.first_cluster) {
+                    warn!("exFAT directory {file_path} has already been extracted");
+                    continue;
+                }
+
+                if !chroot.create_directory(&file_path) {
+                    warn!("Failed to extract exFAT directory {file_path}");
+                    continue;
+                }
+
+                file_count += 1;
+
+                let directory_data = self.read_chain(
+                    file.stream.first_cluster,
+                    Some(file.stream.data_length),
+                    file.stream.contiguous,
+                );
+                let children = self.directory_entries(&directory_data);
+
+                file_count +=
+                    self.extract_directory(chroot, &children, &file_path, visited_directories);
+            } else {
+                let file_data = self.file_data(&file.stream);
+
+                if file_data.len() < file.stream.data_length {
+                    warn!(
+                        "Only {} of {} bytes of exFAT file {} are available",
+                        file_data.len(),
+                        file.stream.data_length,
+                        file_path
+                    );
+                }
+
+                if chroot.create_file(&file_path, &file_data) {
+                    file_count += 1;
+                } else {
+                    warn!("Failed to extract exFAT file {file_path}");
+                }
+            }
+        }
+
+        file_count
+    }
+}
@@ -0,0 +1,492 @@
+use crate::extractors::common::{
+    Chroot, ExtractionError, ExtractionResult, Extractor, ExtractorType,
+};
+use crate::structures::ext::{
+    EXTHeader, EXTInode, INODE_BLOCK_DATA_SIZE, ROOT_INODE, parse_ext_directory_entry,
+    parse_ext_extent, parse_ext_extent_header, parse_ext_extent_index, parse_ext_group_descriptor,
+    parse_ext_header, parse_ext_inode,
+};
+use log::warn;
+use std::collections::HashSet;
+
+/// Defines the internal extractor function for extracting EXT2/3/4 file systems
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::ext::ext_extractor;
+///
+/// match ext_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn ext_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_ext),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for EXT2/3/4 file systems.
+///
+/// The image is only ever read; files, directories, symlinks and device nodes reachable from the root directory
+/// are extracted, along with their permissions.
+pub fn extract_ext(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(ext_data) = file_data.get(offset..) {
+        if let Ok(ext_header) = parse_ext_header(ext_data) {
+            let unsupported_features = ext_header.unsupported_features();
+
+            if !unsupported_features.is_empty() {
+                warn!(
+                    "EXT image uses unsupported features: {}",
+                    unsupported_features.join(", ")
+                );
+                return result;
+            }
+
+            // Images may be truncated, as is common for partitions carved from eMMC dumps; only the available data is used
+            let image_end = std::cmp::min(ext_header.image_size, ext_data.len());
+
+            if let Ok(ext_image) = ExtImage::new(&ext_data[..image_end], ext_header) {
+                // The root directory must be readable
+                if let Ok(root_inode) = ext_image.inode(ROOT_INODE) {
+                    if let Ok(root_entries) = ext_image.directory_entries(&root_inode) {
+                        if output_directory.is_none() {
+                            result.success = !root_entries.is_empty();
+                        } else {
+                            let chroot = Chroot::new(output_directory);
+                            let mut visited_directories = HashSet::from([ROOT_INODE]);
+
+                            let file_count = ext_image.extract_directory(
+                                &chroot,
+                                &root_entries,
+                                "",
+                                &mut visited_directories,
+                            );
+
+                            result.success = file_count > 0;
+                        }
+
+                        if result.success {
+                            result.size = Some(ext_image.header.image_size);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Provides read-only access to the inodes and file data of an EXT image
+struct ExtImage<'a> {
+    data: &'a [u8],
+    header: EXTHeader,
+    /// Block number of each block group's inode table
+    inode_tables: Vec<usize>,
+}
+
+impl<'a> ExtImage<'a> {
+    fn new(data: &'a [u8], header: EXTHeader) -> Result<ExtImage<'a>, ExtractionError> {
+        // Size of revision 0 inodes; larger inodes only add fields to the end
+        const MIN_INODE_SIZE: usize = 128;
+
+        if header.inodes_per_group == 0
+            || header.blocks_per_group == 0
+            || header.inode_size < MIN_INODE_SIZE
+            || header.inode_size > header.block_size
+        {
+            return Err(ExtractionError);
+        }
+
+        // Group descriptors start in the block following the superblock
+        let group_count = header.inodes_count.div_ceil(header.inodes_per_group);
+        let descriptor_table_offset = (header.first_data_block + 1) * header.block_size;
+
+        let mut inode_tables: Vec<usize> = vec![];
+
+        for group in 0..group_count {
+            let descriptor_offset = descriptor_table_offset + (group * header.descriptor_size);
+            let descriptor_data = data.get(descriptor_offset..).ok_or(ExtractionError)?;
+
+            match parse_ext_group_descriptor(descriptor_data, header.descriptor_size) {
+                Err(_) => return Err(ExtractionError),
+                Ok(descriptor) => inode_tables.push(descriptor.inode_table_block),
+            }
+        }
+
+        Ok(ExtImage {
+            data,
+            header,
+            inode_tables,
+        })
+    }
+
+    /// Returns the data of the specified block
+    fn block(&self, block_number: usize) -> Option<&'a [u8]> {
+        let block_start = block_number.checked_mul(self.header.block_size)?;
+        self.data
+            .get(block_start..block_start.checked_add(self.header.block_size)?)
+    }
+
+    /// Reads and parses the specified inode
+    fn inode(&self, inode_number: usize) -> Result<EXTInode, ExtractionError> {
+        if inode_number == 0 || inode_number > self.header.inodes_count {
+            return Err(ExtractionError);
+        }
+
+        let group = (inode_number - 1) / self.header.inodes_per_group;
+        let index = (inode_number - 1) % self.header.inodes_per_group;
+
+        let inode_offset = self.inode_tables[group]
+            .checked_mul(self.header.block_size)
+            .and_then(|table_offset| table_offset.checked_add(index * self.header.inode_size))
+            .ok_or(ExtractionError)?;
+
+        if let Some(inode_data) = self
+            .data
+            .get(inode_offset..inode_offset + self.header.inode_size)
+        {
+            if let Ok(inode) = parse_ext_inode(inode_data) {
+                return Ok(inode);
+            }
+        }
+
+        Err(ExtractionError)
+    }
+
+    /// Returns the image block number of each of the inode's data blocks; holes in sparse files are block number 0
+    fn block_map(&self, inode: &EXTInode) -> Result<Vec<usize>, ExtractionError> {
+        let block_count = inode.size.div_ceil(self.header.block_size);
+        let mut block_map: Vec<usize> = vec![0; block_count];
+
+        if inode.extents {
+            let mut visited_nodes: HashSet<usize> = HashSet::new();
+            self.map_extent_node(&inode.block_data, &mut block_map, &mut visited_nodes, 0)?;
+        } else {
+            self.map_block_pointers(&inode.block_data, &mut block_map)?;
+        }
+
+        Ok(block_map)
+    }
+
+    /// Maps the file blocks described by an extent tree node, and all of its children
+    fn map_extent_node(
+        &self,
+        node_data: &[u8],
+        block_map: &mut [usize],
+        visited_nodes: &mut HashSet<usize>,
+        level: usize,
+    ) -> Result<(), ExtractionError> {
+        // The kernel limits extent trees to a depth of 5
+        const MAX_DEPTH: usize = 5;
+        // Extent entries and index entries are the same size
+        const ENTRY_SIZE: usize = 12;
+
+        let node_header = parse_ext_extent_header(node_data).map_err(|_| ExtractionError)?;
+
+        if level > MAX_DEPTH || node_header.depth > MAX_DEPTH {
+            return Err(ExtractionError);
+        }
+
+        let mut entry_offset = node_header.header_size;
+
+        for _ in 0..node_header.entry_count {
+            let entry_data = node_data.get(entry_offset..).ok_or(ExtractionError)?;
+
+            if node_header.depth == 0 {
+                let extent = parse_ext_extent(entry_data).map_err(|_| ExtractionError)?;
+
+                // Uninitialized extents read as zeros, which is what unmapped blocks are
+                if extent.initialized {
+                    for i in 0..extent.block_count {
+                        if let Some(mapped_block) = block_map.get_mut(extent.logical_block + i) {
+                            *mapped_block = extent.physical_block + i;
+                        }
+                    }
+                }
+            } else {
+                let child_block =
+                    parse_ext_extent_index(entry_data).map_err(|_| ExtractionError)?;
+
+                // Each node may only be referenced once, else a malformed tree could take forever to walk
+                if !visited_nodes.insert(child_block) {
+                    return Err(ExtractionError);
+                }
+
+                let child_data = self.block(child_block).ok_or(ExtractionError)?;
+                self.map_extent_node(child_data, block_map, visited_nodes, level + 1)?;
+            }
+
+            entry_offset += ENTRY_SIZE;
+        }
+
+        Ok(())
+    }
+
+    /// Maps the file blocks described by the direct and indirect block pointers stored in an inode
+    fn map_block_pointers(
+        &self,
+        block_data: &[u8],
+        block_map: &mut [usize],
+    ) -> Result<(), ExtractionError> {
+        // The first 12 pointers are direct, followed by single, double and triple indirect block pointers
+        const DIRECT_POINTERS: usize = 12;
+        const POINTER_SIZE: usize = 4;
+
+        let mut logical_block: usize = 0;
+
+        for (i, pointer) in block_data.chunks_exact(POINTER_SIZE).enumerate() {
+            let block_number = u32::from_le_bytes(pointer.try_into().unwrap()) as usize;
+            let indirection = i.saturating_sub(DIRECT_POINTERS - 1);
+
+            self.map_block_pointer(block_number, indirection, block_map, &mut logical_block)?;
+        }
+
+        Ok(())
+    }
+
+    /// Maps a block pointer with the specified level of indirection, advancing `logical_block` past all blocks it covers
+    fn map_block_pointer(
+        &self,
+        block_number: usize,
+        indirection: usize,
+        block_map: &mut [usize],
+        logical_block: &mut usize,
+    ) -> Result<(), ExtractionError> {
+        const POINTER_SIZE: usize = 4;
+
+        if *logical_block >= block_map.len() {
+            return Ok(());
+        }
+
+        let pointers_per_block = self.header.block_size / POINTER_SIZE;
+
+        if indirection == 0 {
+            block_map[*logical_block] = block_number;
+            *logical_block += 1;
+        } else if block_number == 0 {
+            // A missing indirect block is a hole covering all the blocks it would have pointed to
+            let covered_blocks = pointers_per_block
+                .checked_pow(indirection as u32)
+                .unwrap_or(usize::MAX);
+            *logical_block = logical_block.saturating_add(covered_blocks);
+        } else {
+            let pointer_block = self.block(block_number).ok_or(ExtractionError)?;
+
+            for pointer in pointer_block.chunks_exact(POINTER_SIZE) {
+                let next_block_number = u32::from_le_bytes(pointer.try_into().unwrap()) as usize;
+
+                self.map_block_pointer(
+                    next_block_number,
+                    indirection - 1,
+                    block_map,
+                    logical_block,
+                )?;
+
+                if *logical_block >= block_map.len() {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the contents of a file, directory or symlink
+    fn file_data(&self, inode: &EXTInode) -> Result<Vec<u8>, ExtractionError> {
+        // Files can not be larger than the image, unless they are sparse; sparse files that large are not extracted
+        if inode.size > self.data.len() {
+            return Err(ExtractionError);
+        }
+
+        let mut file_data: Vec<u8> = vec![0; inode.size];
+
+        for (file_block, file_block_data) in self
+            .block_map(inode)?
+            .iter()
+            .zip(file_data.chunks_mut(self.header.block_size))
+        {
+            if *file_block != 0 {
+                let block_data = self.block(*file_block).ok_or(ExtractionError)?;
+                file_block_data.copy_from_slice(&block_data[..file_block_data.len()]);
+            }
+        }
+
+        Ok(file_data)
+    }
+
+    /// Returns the target path of a symlink
+    fn symlink_target(&self, inode: &EXTInode) -> Result<String, ExtractionError> {
+        // Short symlink targets are stored in place of the inode's block pointers
+        let target = if inode.size < INODE_BLOCK_DATA_SIZE && !inode.extents {
+            inode.block_data[..inode.size].to_vec()
+        } else {
+            self.file_data(inode)?
+        };
+
+        Ok(String::from_utf8_lossy(&target).to_string())
+    }
+
+    /// Returns the inode number and name of each entry in a directory, excluding '.' and '..'
+    fn directory_entries(&self, inode: &EXTInode) -> Result<Vec<(usize, String)>, ExtractionError> {
+        // Size of the fields that precede the name in a directory entry
+        const MIN_ENTRY_SIZE: usize = 8;
+
+        if !inode.directory {
+            return Err(ExtractionError);
+        }
+
+        let directory_data = self.file_data(inode)?;
+        let mut entries: Vec<(usize, String)> = vec![];
+
+        // Directory entries never cross block boundaries; this also skips over hash tree index blocks
+        for block_data in directory_data.chunks(self.header.block_size) {
+            let mut entry_offset: usize = 0;
+
+            while entry_offset + MIN_ENTRY_SIZE <= block_data.len() {
+                match parse_ext_directory_entry(&block_data[entry_offset..]) {
+                    Err(_) => break,
+                    Ok(entry) => {
+                        // Unused entries have an inode number of 0
+                        if entry.inode != 0
+                            && entry.name != "."
+                            && entry.name != ".."
+                            && !entry.name.is_empty()
+                            && !entry.name.contains(['/', '\0'])
+                        {
+                            entries.push((entry.inode, entry.name));
+                        }
+
+                        entry_offset += entry.entry_size;
+                    }
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Extracts the entries of a directory, and all of its sub-directories; returns the number of extracted files
+    fn extract_directory(
+        &self,
+        chroot: &Chroot,
+        entries: &[(usize, String)],
+        parent_directory: &str,
+        visited_directories: &mut HashSet<usize>,
+    ) -> usize {
+        let mut file_count: usize = 0;
+
+        for (inode_number, name) in entries {
+            let file_path = chroot.safe_path_join(parent_directory, name);
+
+            let inode = match self.inode(*inode_number) {
+                Err(_) => {
+                    warn!("Failed to read EXT inode {inode_number} for {file_path}");
+                    continue;
+                }
+                Ok(inode) => inode,
+            };
+
+            let extraction_success: bool = if inode.directory {
+                // Directories linked more than once could cause infinite recursion
+                if !visited_directories.insert(*inode_number) {
+                    warn!("EXT directory {file_path} has already been extracted");
+                    continue;
+                }
+
+                if chroot.create_directory(&file_path) {
+                    match self.directory_entries(&inode) {
+                        Err(_) => warn!("Failed to read EXT directory {file_path}"),
+                        Ok(children) => {
+                            file_count += self.extract_directory(
+                                chroot,
+                                &children,
+                                &file_path,
+                                visited_directories,
+                            );
+                        }
+                    }
+
+                    true
+                } else {
+                    false
+                }
+            } else if inode.regular {
+                match self.file_data(&inode) {
+                    Err(_) => false,
+                    Ok(file_data) => chroot.create_file(&file_path, &file_data),
+                }
+            } else if inode.symlink {
+                match self.symlink_target(&inode) {
+                    Err(_) => false,
+                    Ok(target) => chroot.create_symlink(&file_path, target),
+                }
+            } else if inode.fifo {
+                chroot.create_fifo(&file_path)
+            } else if inode.socket {
+                chroot.create_socket(&file_path)
+            } else if inode.block_device || inode.character_device {
+                let (major, minor) = device_numbers(&inode);
+
+                if inode.block_device {
+                    chroot.create_block_device(&file_path, major, minor)
+                } else {
+                    chroot.create_character_device(&file_path, major, minor)
+                }
+            } else {
+                continue;
+            };
+
+            if extraction_success {
+                file_count += 1;
+
+                // Directory permissions are set last, as they may not permit writing the directory's contents
+                if inode.regular || inode.directory {
+                    chroot.set_permissions(&file_path, inode.permissions as u32);
+                }
+            } else {
+                warn!("Failed to extract EXT file {file_path}");
+            }
+        }
+
+        file_count
+    }
+}
+
+/// Returns the major and minor numbers of a device inode
+fn device_numbers(inode: &EXTInode) -> (usize, usize) {
+    let old_encoding = u32::from_le_bytes(inode.block_data[0..4].try_into().unwrap()) as usize;
+    let new_encoding = u32::from_le_bytes(inode.block_data[4..8].try_into().unwrap()) as usize;
+
+    // Devices with 8-bit major and minor numbers use the old encoding in the first block pointer
+    if old_encoding != 0 {
+        ((old_encoding >> 8) & 0xFF, old_encoding & 0xFF)
+    } else {
+        (
+            (new_encoding >> 8) & 0xFFF,
+            (new_encoding & 0xFF) | ((new_encoding >> 12) & 0xFFF00),
+        )
+    }
+}
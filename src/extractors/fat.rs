@@ -0,0 +1,400 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::fat::{
+    FAT_DIRECTORY_ENTRY_SIZE, FATHeader, fat_short_name_checksum, parse_fat_directory_entry,
+    parse_fat_header,
+};
+use log::warn;
+use std::collections::HashSet;
+
+/// Defines the internal extractor function for extracting FAT12/16/32 file systems
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::fat::fat_extractor;
+///
+/// match fat_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn fat_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_fat),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for FAT12/16/32 file systems.
+///
+/// File systems that were not cleanly unmounted, or that are truncated, are extracted as completely as possible;
+/// files whose data is not entirely available are extracted up to the point where their data ends.
+pub fn extract_fat(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(fat_data) = file_data.get(offset..) {
+        if let Ok(fat_header) = parse_fat_header(fat_data) {
+            // Only the available data is used if the file system is truncated
+            let image_end = std::cmp::min(fat_header.total_size, fat_data.len());
+
+            let fat_image = FatImage {
+                data: &fat_data[..image_end],
+                cluster_size: fat_header.bytes_per_sector * fat_header.sectors_per_cluster,
+                fat_offset: fat_header.reserved_sectors * fat_header.bytes_per_sector,
+                header: fat_header,
+            };
+
+            if fat_image.data.len() < fat_image.header.total_size {
+                warn!(
+                    "FAT file system is truncated to {} of {} bytes",
+                    fat_image.data.len(),
+                    fat_image.header.total_size
+                );
+            }
+
+            if fat_image.is_dirty() {
+                warn!("FAT file system was not cleanly unmounted; some files may be incomplete");
+            }
+
+            let root_entries = fat_image.directory_entries(&fat_image.root_directory());
+
+            if !root_entries.is_empty() {
+                if output_directory.is_none() {
+                    result.success = true;
+                } else {
+                    let chroot = Chroot::new(output_directory);
+                    let mut visited_directories = HashSet::from([fat_image.header.root_cluster]);
+
+                    let file_count = fat_image.extract_directory(
+                        &chroot,
+                        &root_entries,
+                        "",
+                        &mut visited_directories,
+                    );
+
+                    result.success = file_count > 0;
+                }
+
+                if result.success {
+                    result.size = Some(fat_image.data.len());
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// A file or directory in a FAT directory, with its long file name resolved
+#[derive(Debug, Default, Clone)]
+struct FatFile {
+    name: String,
+    directory: bool,
+    first_cluster: usize,
+    size: usize,
+}
+
+/// Provides read-only access to the clusters of a FAT file system
+struct FatImage<'a> {
+    data: &'a [u8],
+    header: FATHeader,
+    cluster_size: usize,
+    fat_offset: usize,
+}
+
+impl FatImage<'_> {
+    /// Returns the FAT entry for the specified cluster, if it is available
+    fn fat_entry(&self, cluster: usize) -> Option<usize> {
+        match self.header.fat_type {
+            12 => {
+                let entry_offset = self.fat_offset + cluster + (cluster / 2);
+                let entry_bytes = self.data.get(entry_offset..entry_offset + 2)?;
+                let entry = u16::from_le_bytes(entry_bytes.try_into().unwrap()) as usize;
+
+                // Odd clusters use the upper 12 bits, even clusters the lower 12 bits
+                match cluster % 2 {
+                    0 => Some(entry & 0xFFF),
+                    _ => Some(entry >> 4),
+                }
+            }
+            16 => {
+                let entry_offset = self.fat_offset + (cluster * 2);
+                let entry_bytes = self.data.get(entry_offset..entry_offset + 2)?;
+                Some(u16::from_le_bytes(entry_bytes.try_into().unwrap()) as usize)
+            }
+            _ => {
+                let entry_offset = self.fat_offset + (cluster * 4);
+                let entry_bytes = self.data.get(entry_offset..entry_offset + 4)?;
+                Some(u32::from_le_bytes(entry_bytes.try_into().unwrap()) as usize & 0x0FFFFFFF)
+            }
+        }
+    }
+
+    /// Returns true if the file system's clean shutdown bit, stored in the second FAT entry, is not set
+    fn is_dirty(&self) -> bool {
+        const FAT16_CLEAN_BIT: usize = 0x8000;
+        const FAT32_CLEAN_BIT: usize = 0x08000000;
+
+        let clean_bit = match self.header.fat_type {
+            16 => FAT16_CLEAN_BIT,
+            32 => FAT32_CLEAN_BIT,
+            // FAT12 has no clean shutdown bit
+            _ => return false,
+        };
+
+        match self.fat_entry(1) {
+            None => false,
+            Some(entry) => entry & clean_bit == 0,
+        }
+    }
+
+    /// Returns true if the cluster number refers to a cluster in the data region
+    fn is_data_cluster(&self, cluster: usize) -> bool {
+        // The first data cluster is cluster 2
+        cluster >= 2 && cluster < self.header.cluster_count + 2
+    }
+
+    /// Returns the chain of clusters starting at `first_cluster`.
+    ///
+    /// The chain ends at an end of chain marker, or at any free, bad, or previously visited cluster.
+    fn cluster_chain(&self, first_cluster: usize) -> Vec<usize> {
+        let mut chain: Vec<usize> = vec![];
+        let mut visited_clusters: HashSet<usize> = HashSet::new();
+        let mut cluster = first_cluster;
+
+        while self.is_data_cluster(cluster) && visited_clusters.insert(cluster) {
+            chain.push(cluster);
+
+            match self.fat_entry(cluster) {
+                None => break,
+                Some(next_cluster) => cluster = next_cluster,
+            }
+        }
+
+        chain
+    }
+
+    /// Reads up to `size` bytes from the chain of clusters starting at `first_cluster`; if `size` is None, the entire chain is read
+    fn read_chain(&self, first_cluster: usize, size: Option<usize>) -> Vec<u8> {
+        let mut chain_data: Vec<u8> = vec![];
+
+        for cluster in self.cluster_chain(first_cluster) {
+            if let Some(size) = size {
+                if chain_data.len() >= size {
+                    break;
+                }
+            }
+
+            let cluster_offset = self.header.data_offset + ((cluster - 2) * self.cluster_size);
+            let cluster_end = std::cmp::min(cluster_offset + self.cluster_size, self.data.len());
+
+            match self.data.get(cluster_offset..cluster_end) {
+                None => break,
+                Some(cluster_data) => chain_data.extend_from_slice(cluster_data),
+            }
+
+            // A partial cluster is the end of the available data
+            if cluster_end < cluster_offset + self.cluster_size {
+                break;
+            }
+        }
+
+        if let Some(size) = size {
+            chain_data.truncate(size);
+        }
+
+        chain_data
+    }
+
+    /// Returns the contents of the root directory
+    fn root_directory(&self) -> Vec<u8> {
+        if self.header.fat_type == 32 {
+            return self.read_chain(self.header.root_cluster, None);
+        }
+
+        // FAT12/16 root directories are a fixed size, and reside between the FATs and the data region
+        let root_directory_end = self.header.root_directory_offset
+            + (self.header.root_entries_count * FAT_DIRECTORY_ENTRY_SIZE);
+
+        match self
+            .data
+            .get(self.header.root_directory_offset..root_directory_end)
+        {
+            None => vec![],
+            Some(root_directory) => root_directory.to_vec(),
+        }
+    }
+
+    /// Parses directory entries, resolving long file names; deleted entries, volume labels, '.' and '..' are excluded
+    fn directory_entries(&self, directory_data: &[u8]) -> Vec<FatFile> {
+        let mut files: Vec<FatFile> = vec![];
+
+        // Parts of the long name preceding the next short name entry, and the checksum they expect
+        let mut long_name_parts: Vec<(usize, Vec<u16>)> = vec![];
+        let mut long_name_checksum: u8 = 0;
+
+        for entry_data in directory_data.chunks_exact(FAT_DIRECTORY_ENTRY_SIZE) {
+            let entry = match parse_fat_directory_entry(entry_data) {
+                Err(_) => break,
+                Ok(entry) => entry,
+            };
+
+            if entry.end_of_directory {
+                break;
+            }
+
+            if entry.deleted {
+                long_name_parts.clear();
+                continue;
+            }
+
+            if entry.long_name {
+                // The last part of a long name is stored first, and starts a new long name
+                if entry.long_name_last {
+                    long_name_parts.clear();
+                    long_name_checksum = entry.long_name_checksum;
+                }
+
+                long_name_parts.push((entry.long_name_order, entry.long_name_part));
+                continue;
+            }
+
+            let long_name = resolve_long_name(&mut long_name_parts);
+
+            if entry.volume_label {
+                continue;
+            }
+
+            // Long names are only valid if they belong to this short name
+            let name = match long_name {
+                Some(name)
+                    if long_name_checksum == fat_short_name_checksum(&entry.raw_short_name) =>
+                {
+                    name
+                }
+                _ => entry.short_name,
+            };
+
+            if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\0']) {
+                continue;
+            }
+
+            files.push(FatFile {
+                name,
+                directory: entry.directory,
+                first_cluster: entry.first_cluster,
+                size: entry.size,
+            });
+        }
+
+        files
+    }
+
+    /// Extracts the entries of a directory, and all of its sub-directories; returns the number of extracted files
+    fn extract_directory(
+        &self,
+        chroot: &Chroot,
+        files: &[FatFile],
+        parent_directory: &str,
+        visited_directories: &mut HashSet<usize>,
+    ) -> usize {
+        let mut file_count: usize = 0;
+
+        for file in files {
+            let file_path = chroot.safe_path_join(parent_directory, &file.name);
+
+            if file.directory {
+                // Directories linked more than once could cause infinite recursion
+                if !visited_directories.insert(file.first_cluster) {
+                    warn!("FAT directory {file_path} has already been extracted");
+                    continue;
+                }
+
+                if !chroot.create_directory(&file_path) {
+                    warn!("Failed to extract FAT directory {file_path}");
+                    continue;
+                }
+
+                file_count += 1;
+
+                let children = self.directory_entries(&self.read_chain(file.first_cluster, None));
+                file_count +=
+                    self.extract_directory(chroot, &children, &file_path, visited_directories);
+            } else {
+                let file_data = self.read_chain(file.first_cluster, Some(file.size));
+
+                if file_data.len() < file.size {
+                    warn!(
+                        "Only {} of {} bytes of FAT file {} are available",
+                        file_data.len(),
+                        file.size,
+                        file_path
+                    );
+                }
+
+                if chroot.create_file(&file_path, &file_data) {
+                    file_count += 1;
+                } else {
+                    warn!("Failed to extract FAT file {file_path}");
+                }
+            }
+        }
+
+        file_count
+    }
+}
+
+/// Assembles a long file name from its parts, clearing the parts; returns None if any part is missing
+fn resolve_long_name(long_name_parts: &mut Vec<(usize, Vec<u16>)>) -> Option<String> {
+    // Long names are terminated by a NULL character, and padded with 0xFFFF
+    const TERMINATOR: u16 = 0;
+    const PADDING: u16 = 0xFFFF;
+
+    if long_name_parts.is_empty() {
+        return None;
+    }
+
+    long_name_parts.sort_by_key(|(order, _part)| *order);
+
+    let mut long_name: Vec<u16> = vec![];
+    let mut complete = true;
+
+    for (i, (order, part)) in long_name_parts.iter().enumerate() {
+        if *order != i + 1 {
+            complete = false;
+            break;
+        }
+
+        long_name.extend(part);
+    }
+
+    long_name_parts.clear();
+
+    if !complete {
+        return None;
+    }
+
+    let name_length = long_name
+        .iter()
+        .position(|character| *character == TERMINATOR || *character == PADDING)
+        .unwrap_or(long_name.len());
+
+    Some(String::from_utf16_lossy(&long_name[..name_length]))
+}
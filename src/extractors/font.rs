@@ -0,0 +1,128 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::font::{parse_sfnt_font, parse_woff_font};
+
+/// Defines the internal extractor function for carving TrueType / OpenType fonts
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::font::sfnt_extractor;
+///
+/// match sfnt_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn sfnt_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_sfnt_font),
+        do_not_recurse: true,
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for carving TrueType / OpenType fonts to disk
+pub fn extract_sfnt_font(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const TTF_OUTFILE_NAME: &str = "font.ttf";
+    const OTF_OUTFILE_NAME: &str = "font.otf";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(sfnt_font) = parse_sfnt_font(&file_data[offset..]) {
+        result.size = Some(sfnt_font.size);
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+
+            let file_name = match sfnt_font.font_type.as_str() {
+                "OpenType" => OTF_OUTFILE_NAME,
+                _ => TTF_OUTFILE_NAME,
+            };
+
+            result.success = chroot.carve_file(file_name, file_data, offset, sfnt_font.size);
+        }
+    }
+
+    result
+}
+
+/// Defines the internal extractor function for carving WOFF web fonts
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::font::woff_extractor;
+///
+/// match woff_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn woff_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_woff_font),
+        do_not_recurse: true,
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for carving WOFF / WOFF2 web fonts to disk
+pub fn extract_woff_font(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const WOFF_OUTFILE_NAME: &str = "font.woff";
+    const WOFF2_OUTFILE_NAME: &str = "font.woff2";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(woff_font) = parse_woff_font(&file_data[offset..]) {
+        result.size = Some(woff_font.size);
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+
+            let file_name = match woff_font.version {
+                2 => WOFF2_OUTFILE_NAME,
+                _ => WOFF_OUTFILE_NAME,
+            };
+
+            result.success = chroot.carve_file(file_name, file_data, offset, woff_font.size);
+        }
+    }
+
+    result
+}
@@ -0,0 +1,284 @@
+use crate::common::parser_deadline_exceeded;
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+
+/// Gaps between records larger than this are not filled; the data on either side is written to separate files
+pub const MAX_GAP_SIZE: u64 = 1024 * 1024;
+
+/// Longest line expected in a hex record file; 255 data bytes, plus the record's address, type, and checksum fields
+const MAX_LINE_LENGTH: usize = 1024;
+
+/// Converted images larger than this are not written
+const MAX_IMAGE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Value used to fill gaps between records, i.e. erased flash
+const FILL_BYTE: u8 = 0xFF;
+
+/// Describes the internal extractor for converting Intel HEX files to binary
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::ihex::ihex_extractor;
+///
+/// match ihex_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn ihex_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_ihex),
+        ..Default::default()
+    }
+}
+
+/// Data decoded from the records of a hex record file, such as an Intel HEX or Motorola S-record file
+#[derive(Debug, Default, Clone)]
+pub struct MemoryImage {
+    /// Address and data of each data record, in the order they appear in the file
+    records: Vec<(u64, Vec<u8>)>,
+}
+
+impl MemoryImage {
+    /// Adds a data record
+    pub fn add(&mut self, address: u64, data: &[u8]) {
+        if !data.is_empty() {
+            self.records.push((address, data.to_vec()));
+        }
+    }
+
+    /// Total number of data bytes in all records
+    pub fn data_size(&self) -> usize {
+        self.records.iter().map(|(_, data)| data.len()).sum()
+    }
+
+    /// Lowest address of any record
+    pub fn load_address(&self) -> Option<u64> {
+        self.records.iter().map(|(address, _)| *address).min()
+    }
+
+    /// Returns the start address and data of each contiguous memory segment.
+    ///
+    /// Gaps between records of up to `MAX_GAP_SIZE` bytes are filled with 0xFF; larger gaps start a new segment.
+    /// Where records overlap, the record that appears later in the file takes precedence.
+    pub fn segments(&self) -> Vec<(u64, Vec<u8>)> {
+        let mut segments: Vec<(u64, Vec<u8>)> = vec![];
+        let mut records: Vec<&(u64, Vec<u8>)> = self.records.iter().collect();
+
+        // Sorting is stable, so overlapping records keep their file order
+        records.sort_by_key(|(address, _)| *address);
+
+        for (address, data) in records {
+            if let Some((segment_address, segment_data)) = segments.last_mut() {
+                let segment_end = *segment_address + segment_data.len() as u64;
+
+                if *address <= segment_end + MAX_GAP_SIZE {
+                    let start = (*address - *segment_address) as usize;
+                    let end = start + data.len();
+
+                    if end > segment_data.len() {
+                        segment_data.resize(end, FILL_BYTE);
+                    }
+
+                    segment_data[start..end].copy_from_slice(data);
+                    continue;
+                }
+            }
+
+            segments.push((*address, data.clone()));
+        }
+
+        segments
+    }
+
+    /// Writes each memory segment to a file. If there is only one segment it is written to `<name>.bin`, else each
+    /// segment is written to `<name>_<address>.bin`.
+    pub fn write(&self, chroot: &Chroot, name: &str) -> bool {
+        let segments = self.segments();
+
+        if segments.iter().map(|(_, data)| data.len()).sum::<usize>() > MAX_IMAGE_SIZE {
+            return false;
+        }
+
+        for (address, data) in &segments {
+            let file_name = match segments.len() {
+                1 => format!("{name}.bin"),
+                _ => format!("{name}_{address:08X}.bin"),
+            };
+
+            if !chroot.create_file(&file_name, data) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// A parsed hex record file
+#[derive(Debug, Default, Clone)]
+pub struct HexRecords {
+    /// Size of the hex record text, in bytes, including the line ending of the last record
+    pub size: usize,
+    /// Number of bits used to encode record addresses
+    pub address_bits: usize,
+    /// Start address of the program, if one was specified
+    pub entry_point: Option<u64>,
+    pub memory: MemoryImage,
+}
+
+/// Converts an Intel HEX file to binary, filling small gaps between records with 0xFF
+pub fn extract_ihex(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTPUT_NAME: &str = "intel-hex";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(hex_records) = parse_ihex(&file_data[offset..]) {
+        result.size = Some(hex_records.size);
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+            result.success = hex_records.memory.write(&chroot, OUTPUT_NAME);
+        }
+    }
+
+    result
+}
+
+/// Parses the Intel HEX records at the start of the data, up to and including the end of file record.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::ihex::parse_ihex;
+///
+/// let ihex = b":020000040800F2\n:0400100001020304E2\n:00000001FF\n";
+///
+/// let hex_records = parse_ihex(ihex).expect("Failed to parse Intel HEX records");
+///
+/// assert_eq!(hex_records.size, ihex.len());
+/// assert_eq!(hex_records.memory.segments(), vec![(0x08000010, vec![1, 2, 3, 4])]);
+/// ```
+pub fn parse_ihex(data: &[u8]) -> Option<HexRecords> {
+    const START_CODE: u8 = b':';
+    // Record types
+    const DATA: u8 = 0x00;
+    const END_OF_FILE: u8 = 0x01;
+    const EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+    const START_SEGMENT_ADDRESS: u8 = 0x03;
+    const EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+    const START_LINEAR_ADDRESS: u8 = 0x05;
+    // Byte count, address, and record type fields
+    const RECORD_HEADER_SIZE: usize = 4;
+
+    let mut hex_records = HexRecords {
+        address_bits: 16,
+        ..Default::default()
+    };
+
+    let mut base_address: u64 = 0;
+    let mut offset: usize = 0;
+
+    while let Some((line, next_offset)) = next_line(data, offset) {
+        if parser_deadline_exceeded() {
+            break;
+        }
+
+        offset = next_offset;
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let record = decode_record(line, START_CODE)?;
+
+        // The sum of all bytes in the record, including the checksum, must be 0
+        if record.len() <= RECORD_HEADER_SIZE
+            || record.len() != RECORD_HEADER_SIZE + record[0] as usize + 1
+            || record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) != 0
+        {
+            return None;
+        }
+
+        let address = u16::from_be_bytes([record[1], record[2]]) as u64;
+        let record_data = &record[RECORD_HEADER_SIZE..record.len() - 1];
+
+        match (record[3], record_data.len()) {
+            (DATA, _) => hex_records.memory.add(base_address + address, record_data),
+            (END_OF_FILE, 0) => {
+                hex_records.size = offset;
+                return Some(hex_records);
+            }
+            (EXTENDED_SEGMENT_ADDRESS, 2) => {
+                base_address = (u16::from_be_bytes([record_data[0], record_data[1]]) as u64) << 4;
+                hex_records.address_bits = hex_records.address_bits.max(20);
+            }
+            (EXTENDED_LINEAR_ADDRESS, 2) => {
+                base_address = (u16::from_be_bytes([record_data[0], record_data[1]]) as u64) << 16;
+                hex_records.address_bits = 32;
+            }
+            // CS:IP
+            (START_SEGMENT_ADDRESS, 4) => {
+                let segment = u16::from_be_bytes([record_data[0], record_data[1]]) as u64;
+                let pointer = u16::from_be_bytes([record_data[2], record_data[3]]) as u64;
+                hex_records.entry_point = Some((segment << 4) + pointer);
+            }
+            (START_LINEAR_ADDRESS, 4) => {
+                hex_records.entry_point = Some(u32::from_be_bytes([
+                    record_data[0],
+                    record_data[1],
+                    record_data[2],
+                    record_data[3],
+                ]) as u64);
+            }
+            _ => return None,
+        }
+    }
+
+    // No end of file record
+    None
+}
+
+/// Returns the next line of text, without its line ending, and the offset of the line after it.
+/// Returns None if there are no more lines, or if the line is too long to be a hex record.
+pub fn next_line(data: &[u8], offset: usize) -> Option<(&[u8], usize)> {
+    let remaining_data = data.get(offset..).filter(|data| !data.is_empty())?;
+
+    let (line, line_size) = match remaining_data
+        .iter()
+        .take(MAX_LINE_LENGTH + 1)
+        .position(|b| *b == b'\n')
+    {
+        Some(line_end) => (&remaining_data[..line_end], line_end + 1),
+        None if remaining_data.len() <= MAX_LINE_LENGTH => (remaining_data, remaining_data.len()),
+        None => return None,
+    };
+
+    Some((line.strip_suffix(b"\r").unwrap_or(line), offset + line_size))
+}
+
+/// Decodes the hex digits in a record that starts with the specified start code
+pub fn decode_record(line: &[u8], start_code: u8) -> Option<Vec<u8>> {
+    match line.split_first() {
+        Some((first, hex_digits)) if *first == start_code => hex::decode(hex_digits).ok(),
+        _ => None,
+    }
+}
@@ -1,4 +1,5 @@
-use crate::extractors::common::Chroot;
+use crate::common::parser_deadline_exceeded;
+use crate::extractors::common::{Chroot, decompression_limit, report_decompression_limit_reached};
 use adler32::RollingAdler32;
 use flate2::bufread::DeflateDecoder;
 use std::io::Read;
@@ -8,6 +9,8 @@ pub struct DeflateResult {
     pub size: usize,
     pub adler32: u32,
     pub success: bool,
+    /// Number of bytes that were decompressed
+    pub decompressed_size: usize,
 }
 
 /// Decompressor for inflating deflated data.
@@ -41,6 +44,11 @@ pub fn inflate_decompressor(
      * can also determine the exact size of the deflated data.
      */
     loop {
+        // Signature validation gives up on data that takes longer than the parser time budget to decompress
+        if parser_deadline_exceeded() {
+            break;
+        }
+
         // Decompress a block of data
         match decompressor.read(&mut decompressed_buffer) {
             Err(_) => {
@@ -68,6 +76,7 @@ pub fn inflate_decompressor(
                         result.success = true;
                         result.adler32 = adler32_checksum.hash();
                         result.size = decompressor.total_in() as usize;
+                        result.decompressed_size = decompressor.total_out() as usize;
                     }
 
                     // Nothing else to do, break
@@ -79,3 +88,76 @@ pub fn inflate_decompressor(
 
     result
 }
+
+/// Decompressor for inflating deflate data streams that may be truncated or corrupt.
+/// Everything up to the end of the stream, the first decompression error, or the decompression limit is kept;
+/// the reported size is the number of compressed bytes consumed to produce it.
+pub fn inflate_partial_decompressor(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> DeflateResult {
+    // Size of decompression buffer
+    const BLOCK_SIZE: usize = 8192;
+    // Output file for decompressed data
+    const OUTPUT_FILE_NAME: &str = "decompressed.bin";
+
+    let mut result = DeflateResult {
+        ..Default::default()
+    };
+
+    let max_decompressed_size = decompression_limit() as u64;
+    let mut adler32_checksum = RollingAdler32::new();
+    let mut decompressed_buffer = [0; BLOCK_SIZE];
+    let mut decompressor = DeflateDecoder::new(&file_data[offset..]);
+
+    loop {
+        if parser_deadline_exceeded() {
+            break;
+        }
+
+        if decompressor.total_out() >= max_decompressed_size {
+            report_decompression_limit_reached();
+            break;
+        }
+
+        match decompressor.read(&mut decompressed_buffer) {
+            // End of the compressed data, or a decompression error; either way, keep what was decompressed
+            Err(_) | Ok(0) => break,
+            Ok(n) => {
+                adler32_checksum.update_buffer(&decompressed_buffer[0..n]);
+
+                if output_directory.is_some() {
+                    let chroot = Chroot::new(output_directory);
+                    if !chroot.append_to_file(OUTPUT_FILE_NAME, &decompressed_buffer[0..n]) {
+                        return result;
+                    }
+                }
+            }
+        }
+    }
+
+    if decompressor.total_out() > 0 {
+        result.success = true;
+        result.adler32 = adler32_checksum.hash();
+        result.size = decompressor.total_in() as usize;
+        result.decompressed_size = decompressor.total_out() as usize;
+    }
+
+    result
+}
+
+/// Decompresses up to `max_size` bytes from the start of a deflate data stream.
+/// Useful for inspecting the contents of compressed data without decompressing all of it.
+pub fn inflate_preview(file_data: &[u8], offset: usize, max_size: usize) -> Vec<u8> {
+    let mut preview: Vec<u8> = Vec::new();
+
+    if let Some(deflate_data) = file_data.get(offset..) {
+        let decompressor = DeflateDecoder::new(deflate_data);
+
+        // Decompression errors are expected if the stream is truncated or invalid; keep whatever was decompressed
+        let _ = decompressor.take(max_size as u64).read_to_end(&mut preview);
+    }
+
+    preview
+}
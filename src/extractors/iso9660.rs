@@ -1,7 +1,14 @@
-use crate::extractors;
-use crate::extractors::sevenzip::sevenzip_extractor;
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::iso9660::{
+    ISO_PRIMARY_VOLUME_DESCRIPTOR, ISO_SUPPLEMENTARY_VOLUME_DESCRIPTOR, ISO_VOLUME_DESCRIPTOR_SIZE,
+    ISO_VOLUME_DESCRIPTOR_TERMINATOR, ISO_VOLUME_DESCRIPTORS_OFFSET, ISODirectoryRecord,
+    ISOVolumeDescriptor, RockRidgeAttributes, parse_iso_directory_record, parse_iso_header,
+    parse_iso_volume_descriptor, parse_rock_ridge_entries, parse_susp_entries,
+};
+use log::warn;
+use std::collections::HashSet;
 
-/// Describes how to run the 7z utility to extract ISO images
+/// Defines the internal extractor function for extracting ISO9660 images
 ///
 /// ```
 /// use std::io::ErrorKind;
@@ -12,6 +19,7 @@ use crate::extractors::sevenzip::sevenzip_extractor;
 /// match iso9660_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -23,10 +31,388 @@ use crate::extractors::sevenzip::sevenzip_extractor;
 ///     }
 /// }
 /// ```
-pub fn iso9660_extractor() -> extractors::common::Extractor {
-    // Same as the normal 7z extractor, but give the carved file an ISO file extension.
-    // The file extension matters, and 7z doesn't handle some ISO sub-formats correctly if the file extension is not '.iso'.
-    let mut extractor = sevenzip_extractor();
-    extractor.extension = "iso".to_string();
-    extractor
+pub fn iso9660_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_iso9660),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for ISO9660 images.
+///
+/// Rock Ridge file names, symlinks and permissions are used if present; otherwise, Joliet file names are used if present.
+/// Truncated images are extracted as completely as possible.
+pub fn extract_iso9660(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    // Volume descriptor sets are terminated well before this many descriptors
+    const MAX_VOLUME_DESCRIPTORS: usize = 64;
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(iso_data) = file_data.get(offset..) {
+        if let Ok(iso_header) = parse_iso_header(iso_data) {
+            // Only the available data is used if the image is truncated
+            let image_end = std::cmp::min(iso_header.image_size, iso_data.len());
+
+            if image_end < iso_header.image_size {
+                warn!(
+                    "ISO9660 image is truncated to {} of {} bytes",
+                    image_end, iso_header.image_size
+                );
+            }
+
+            let mut primary_descriptor: Option<ISOVolumeDescriptor> = None;
+            let mut joliet_descriptor: Option<ISOVolumeDescriptor> = None;
+
+            for i in 0..MAX_VOLUME_DESCRIPTORS {
+                let descriptor_offset =
+                    ISO_VOLUME_DESCRIPTORS_OFFSET + (i * ISO_VOLUME_DESCRIPTOR_SIZE);
+
+                match iso_data
+                    .get(descriptor_offset..image_end)
+                    .and_then(|descriptor_data| parse_iso_volume_descriptor(descriptor_data).ok())
+                {
+                    None => break,
+                    Some(descriptor) => {
+                        if descriptor.descriptor_type == ISO_VOLUME_DESCRIPTOR_TERMINATOR {
+                            break;
+                        } else if descriptor.descriptor_type == ISO_PRIMARY_VOLUME_DESCRIPTOR {
+                            primary_descriptor.get_or_insert(descriptor);
+                        } else if descriptor.descriptor_type == ISO_SUPPLEMENTARY_VOLUME_DESCRIPTOR
+                            && descriptor.joliet
+                        {
+                            joliet_descriptor.get_or_insert(descriptor);
+                        }
+                    }
+                }
+            }
+
+            if let Some(primary_descriptor) = primary_descriptor {
+                let mut iso_image = IsoImage {
+                    data: &iso_data[..image_end],
+                    block_size: primary_descriptor.block_size,
+                    rock_ridge: false,
+                    joliet: false,
+                };
+
+                let mut root_directory = primary_descriptor.root_directory;
+
+                // Rock Ridge entries are indicated by a SUSP indicator in the root directory's "." entry
+                iso_image.rock_ridge = iso_image
+                    .read_extent(root_directory.extent, root_directory.data_length)
+                    .and_then(|directory_data| parse_iso_directory_record(&directory_data).ok())
+                    .is_some_and(|dot_record| {
+                        iso_image.rock_ridge_attributes(&dot_record).susp_indicator
+                    });
+
+                // Rock Ridge names are preferred, as they are not subject to Joliet's naming restrictions
+                if !iso_image.rock_ridge {
+                    if let Some(joliet_descriptor) = joliet_descriptor {
+                        iso_image.joliet = true;
+                        iso_image.block_size = joliet_descriptor.block_size;
+                        root_directory = joliet_descriptor.root_directory;
+                    }
+                }
+
+                let root_entries =
+                    iso_image.directory_entries(root_directory.extent, root_directory.data_length);
+
+                if !root_entries.is_empty() {
+                    if output_directory.is_none() {
+                        result.success = true;
+                    } else {
+                        let chroot = Chroot::new(output_directory);
+                        let mut visited_directories = HashSet::from([root_directory.extent]);
+
+                        let file_count = iso_image.extract_directory(
+                            &chroot,
+                            &root_entries,
+                            "",
+                            &mut visited_directories,
+                        );
+
+                        result.success = file_count > 0;
+                    }
+
+                    if result.success {
+                        result.size = Some(iso_image.data.len());
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// A file, directory or symlink in an ISO9660 directory
+#[derive(Debug, Default, Clone)]
+struct IsoFile {
+    name: String,
+    directory: bool,
+    /// The logical blocks and lengths of each of the file's extents
+    extents: Vec<(usize, usize)>,
+    symlink_target: Option<String>,
+    mode: Option<usize>,
+}
+
+/// Provides read-only access to the logical blocks of an ISO9660 image
+struct IsoImage<'a> {
+    data: &'a [u8],
+    block_size: usize,
+    rock_ridge: bool,
+    joliet: bool,
+}
+
+impl IsoImage<'_> {
+    /// Reads up to `length` bytes starting at the specified logical block; returns None if the block is not available
+    fn read_extent(&self, block: usize, length: usize) -> Option<Vec<u8>> {
+        let extent_start = block.checked_mul(self.block_size)?;
+        let extent_end = std::cmp::min(extent_start.saturating_add(length), self.data.len());
+        self.data
+            .get(extent_start..extent_end)
+            .map(|extent_data| extent_data.to_vec())
+    }
+
+    /// Returns the Rock Ridge attributes of a directory record, following any continuation areas
+    fn rock_ridge_attributes(&self, record: &ISODirectoryRecord) -> RockRidgeAttributes {
+        // Limits the number of continuation areas followed for a single directory record
+        const MAX_CONTINUATIONS: usize = 32;
+
+        let mut attributes = RockRidgeAttributes {
+            ..Default::default()
+        };
+
+        parse_rock_ridge_entries(&parse_susp_entries(&record.system_use), &mut attributes);
+
+        for _ in 0..MAX_CONTINUATIONS {
+            match attributes.continuation.take().and_then(|continuation| {
+                self.read_extent(
+                    continuation.block,
+                    continuation.offset + continuation.length,
+                )
+                .and_then(|extent_data| extent_data.get(continuation.offset..).map(Vec::from))
+            }) {
+                None => break,
+                Some(system_use) => {
+                    parse_rock_ridge_entries(&parse_susp_entries(&system_use), &mut attributes)
+                }
+            }
+        }
+
+        attributes
+    }
+
+    /// Returns the data length of the directory at the specified logical block, as recorded in its "." entry
+    fn directory_length(&self, block: usize) -> Option<usize> {
+        let dot_record =
+            parse_iso_directory_record(&self.read_extent(block, self.block_size)?).ok()?;
+        Some(dot_record.data_length)
+    }
+
+    /// Returns a file name for the directory record; returns None for the "." and ".." entries
+    fn file_name(
+        &self,
+        record: &ISODirectoryRecord,
+        attributes: &RockRidgeAttributes,
+    ) -> Option<String> {
+        // The "." and ".." entries have single byte identifiers of 0x00 and 0x01 respectively
+        if record.identifier == b"\x00" || record.identifier == b"\x01" {
+            return None;
+        }
+
+        if !attributes.name.is_empty() {
+            return Some(String::from_utf8_lossy(&attributes.name).to_string());
+        }
+
+        let mut name = match self.joliet {
+            true => String::from_utf16_lossy(
+                &record
+                    .identifier
+                    .chunks_exact(2)
+                    .map(|character| u16::from_be_bytes([character[0], character[1]]))
+                    .collect::<Vec<u16>>(),
+            ),
+            false => String::from_utf8_lossy(&record.identifier).to_string(),
+        };
+
+        // Strip file version numbers, e.g., "README.TXT;1", and the trailing '.' of file names without an extension
+        if !record.directory {
+            if let Some((file_name, _version)) = name.rsplit_once(';') {
+                name = file_name.to_string();
+            }
+
+            if let Some(file_name) = name.strip_suffix('.') {
+                name = file_name.to_string();
+            }
+        }
+
+        Some(name)
+    }
+
+    /// Parses the directory records in a directory; the "." and ".." entries, and relocated directories, are excluded
+    fn directory_entries(&self, block: usize, length: usize) -> Vec<IsoFile> {
+        let mut files: Vec<IsoFile> = vec![];
+
+        let directory_data = match self.read_extent(block, length) {
+            None => return files,
+            Some(directory_data) => directory_data,
+        };
+
+        // Extents of a multi-extent file preceding its last directory record
+        let mut pending_extents: Vec<(usize, usize)> = vec![];
+        let mut record_offset: usize = 0;
+
+        while record_offset < directory_data.len() {
+            // Directory records do not cross logical block boundaries; unused space at the end of a block is zero filled
+            if directory_data[record_offset] == 0 {
+                record_offset = (record_offset / self.block_size + 1) * self.block_size;
+                continue;
+            }
+
+            let record = match parse_iso_directory_record(&directory_data[record_offset..]) {
+                Err(_) => break,
+                Ok(record) => record,
+            };
+
+            record_offset += record.size;
+
+            if record.multi_extent {
+                pending_extents.push((record.extent, record.data_length));
+                continue;
+            }
+
+            let mut extents = std::mem::take(&mut pending_extents);
+            extents.push((record.extent, record.data_length));
+
+            let attributes = match self.rock_ridge {
+                true => self.rock_ridge_attributes(&record),
+                false => RockRidgeAttributes {
+                    ..Default::default()
+                },
+            };
+
+            // Relocated directories are extracted where their child links are
+            if attributes.relocated {
+                continue;
+            }
+
+            if let Some(name) = self.file_name(&record, &attributes) {
+                if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\0']) {
+                    continue;
+                }
+
+                let mut file = IsoFile {
+                    name,
+                    directory: record.directory,
+                    extents,
+                    mode: attributes.mode,
+                    ..Default::default()
+                };
+
+                if let Some(child_link) = attributes.child_link {
+                    file.directory = true;
+                    file.extents =
+                        vec![(child_link, self.directory_length(child_link).unwrap_or(0))];
+                } else if attributes.symlink {
+                    file.symlink_target = Some(attributes.symlink_target());
+                }
+
+                files.push(file);
+            }
+        }
+
+        files
+    }
+
+    /// Extracts the entries of a directory, and all of its sub-directories; returns the number of extracted files
+    fn extract_directory(
+        &self,
+        chroot: &Chroot,
+        files: &[IsoFile],
+        parent_directory: &str,
+        visited_directories: &mut HashSet<usize>,
+    ) -> usize {
+        let mut file_count: usize = 0;
+
+        for file in files {
+            let file_path = chroot.safe_path_join(parent_directory, &file.name);
+
+            if let Some(symlink_target) = &file.symlink_target {
+                if chroot.create_symlink(&file_path, symlink_target) {
+                    file_count += 1;
+                } else {
+                    warn!("Failed to extract ISO9660 symlink {file_path}");
+                }
+                continue;
+            }
+
+            if file.directory {
+                let (directory_block, directory_length) = file.extents[0];
+
+                // Directories linked more than once could cause infinite recursion
+                if !visited_directories.insert(directory_block) {
+                    warn!("ISO9660 directory {file_path} has already been extracted");
+                    continue;
+                }
+
+                if !chroot.create_directory(&file_path) {
+                    warn!("Failed to extract ISO9660 directory {file_path}");
+                    continue;
+                }
+
+                file_count += 1;
+
+                let children = self.directory_entries(directory_block, directory_length);
+                file_count +=
+                    self.extract_directory(chroot, &children, &file_path, visited_directories);
+            } else {
+                let mut file_data: Vec<u8> = vec![];
+                let mut file_size: usize = 0;
+
+                for (block, length) in &file.extents {
+                    file_size += length;
+
+                    // Data following a missing extent would be misplaced, so extraction stops at the first missing extent
+                    match self.read_extent(*block, *length) {
+                        Some(extent_data) if extent_data.len() == *length => {
+                            file_data.extend(extent_data)
+                        }
+                        Some(extent_data) => {
+                            file_data.extend(extent_data);
+                            break;
+                        }
+                        None => break,
+                    }
+                }
+
+                if file_data.len() < file_size {
+                    warn!(
+                        "Only {} of {} bytes of ISO9660 file {} are available",
+                        file_data.len(),
+                        file_size,
+                        file_path
+                    );
+                }
+
+                if chroot.create_file(&file_path, &file_data) {
+                    file_count += 1;
+                } else {
+                    warn!("Failed to extract ISO9660 file {file_path}");
+                    continue;
+                }
+            }
+
+            if let Some(mode) = file.mode {
+                chroot.set_permissions(&file_path, mode as u32);
+            }
+        }
+
+        file_count
+    }
 }
@@ -1,6 +1,59 @@
-use crate::extractors;
+use crate::extractors::common::{
+    Chroot, ExtractionResult, Extractor, ExtractorType, SOURCE_FILE_PLACEHOLDER,
+    decompression_limit, report_decompression_limit_reached,
+};
+use crate::structures::common;
+use crate::structures::jffs2::{
+    JFFS2_NODE_STRUCT_SIZE, JFFS2_NODETYPE_DIRENT, JFFS2_NODETYPE_INODE, JFFS2Dirent, JFFS2Inode,
+    parse_jffs2_dirent, parse_jffs2_inode, parse_jffs2_node_header,
+};
+use flate2::bufread::ZlibDecoder;
+use liblzma::bufread::XzDecoder;
+use liblzma::stream::Stream;
+use log::{debug, warn};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
 
-/// Describes how to run the jefferson utility to extract JFFS file systems
+/// JFFS2 inode number of the root directory
+const ROOT_INODE: usize = 1;
+
+/// File type bits of an inode mode
+const S_IFMT: usize = 0o170000;
+const S_IFSOCK: usize = 0o140000;
+const S_IFLNK: usize = 0o120000;
+const S_IFREG: usize = 0o100000;
+const S_IFBLK: usize = 0o060000;
+const S_IFDIR: usize = 0o040000;
+const S_IFCHR: usize = 0o020000;
+const S_IFIFO: usize = 0o010000;
+
+/// Any of the executable bits of an inode mode
+const S_IXANY: usize = 0o111;
+
+/// JFFS2 data compression types
+const JFFS2_COMPR_NONE: usize = 0;
+const JFFS2_COMPR_ZERO: usize = 1;
+const JFFS2_COMPR_RTIME: usize = 2;
+const JFFS2_COMPR_ZLIB: usize = 6;
+const JFFS2_COMPR_LZMA: usize = 8;
+
+/// An inode node, and the offset of its data in the JFFS2 image
+#[derive(Debug, Default, Clone)]
+struct JFFS2InodeNode {
+    info: JFFS2Inode,
+    data_start: usize,
+}
+
+/// All directory entries and inodes found in a JFFS2 image
+#[derive(Debug, Default, Clone)]
+struct JFFS2Image {
+    size: usize,
+    endianness: String,
+    dirents: Vec<JFFS2Dirent>,
+    inodes: HashMap<usize, Vec<JFFS2InodeNode>>,
+}
+
+/// Defines the internal extractor function for extracting JFFS2 file systems
 ///
 /// ```
 /// use std::io::ErrorKind;
@@ -11,6 +64,7 @@ use crate::extractors;
 /// match jffs2_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -22,17 +76,523 @@ use crate::extractors;
 ///     }
 /// }
 /// ```
-pub fn jffs2_extractor() -> extractors::common::Extractor {
-    extractors::common::Extractor {
-        utility: extractors::common::ExtractorType::External("jefferson".to_string()),
+pub fn jffs2_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_jffs2),
+        fallback: Some(Box::new(jefferson_extractor())),
+        ..Default::default()
+    }
+}
+
+/// Describes the jefferson utility, which is run on JFFS2 images that the internal extractor fails to extract
+fn jefferson_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::External("jefferson".to_string()),
         extension: "img".to_string(),
         arguments: vec![
             "-f".to_string(), // Force overwrite if output file, for some reason, exists
             "-d".to_string(), // Output to jffs2-root directory
             "jffs2-root".to_string(),
-            extractors::common::SOURCE_FILE_PLACEHOLDER.to_string(),
+            SOURCE_FILE_PLACEHOLDER.to_string(),
         ],
         exit_codes: vec![0, 1, 2],
         ..Default::default()
     }
 }
+
+/// Internal JFFS2 extractor
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::jffs2::extract_jffs2;
+///
+/// // The CRC used by JFFS2: CRC32 with an initial value of 0, and no final XOR
+/// fn crc(data: &[u8]) -> u32 {
+///     let mut crc: u32 = 0;
+///     for byte in data {
+///         crc ^= *byte as u32;
+///         for _ in 0..8 {
+///             crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 };
+///         }
+///     }
+///     crc
+/// }
+///
+/// // Returns a little endian node header and the node fields that follow it, up to the node CRC
+/// fn node(node_type: u16, fields: &[u32], data: &[u8]) -> Vec<u8> {
+///     let node_size = 12 + (fields.len() * 4) + 8 + data.len();
+///     let mut node: Vec<u8> = vec![];
+///     node.extend_from_slice(&0x1985_u16.to_le_bytes());
+///     node.extend_from_slice(&node_type.to_le_bytes());
+///     node.extend_from_slice(&(node_size as u32).to_le_bytes());
+///     node.extend_from_slice(&crc(&node).to_le_bytes());
+///     for field in fields {
+///         node.extend_from_slice(&field.to_le_bytes());
+///     }
+///     node
+/// }
+///
+/// // Directory entry for hello.txt (inode 2), a regular file in the root directory (inode 1)
+/// fn dirent(name: &[u8]) -> Vec<u8> {
+///     let mut dirent = node(0xE001, &[1, 1, 2, 0, name.len() as u32 | (8 << 8)], name);
+///     dirent.extend_from_slice(&crc(&dirent).to_le_bytes());
+///     dirent.extend_from_slice(&crc(name).to_le_bytes());
+///     dirent.extend_from_slice(name);
+///     dirent
+/// }
+///
+/// // A version of inode 2, a 16 byte file, with data at the given file offset
+/// fn inode(version: u32, offset: u32, compression: u32, size: u32, data: &[u8]) -> Vec<u8> {
+///     let fields = [2, version, 0o100644, 0, 16, 0, 0, 0, offset, data.len() as u32, size, compression];
+///     let mut inode = node(0xE002, &fields, data);
+///     let node_crc = crc(&inode);
+///     inode.extend_from_slice(&crc(data).to_le_bytes());
+///     inode.extend_from_slice(&node_crc.to_le_bytes());
+///     inode.extend_from_slice(data);
+///     inode
+/// }
+///
+/// // Nodes are padded to a 4 byte boundary
+/// let mut image: Vec<u8> = vec![];
+/// for node in [
+///     dirent(b"hello.txt"),
+///     // The first 13 bytes of the file are stored uncompressed
+///     inode(1, 0, 0, 13, b"Hello, JFFS2!"),
+///     // The last 3 bytes of the file are zero-compressed
+///     inode(2, 13, 1, 3, b""),
+/// ] {
+///     image.extend(&node);
+///     image.resize(image.len().next_multiple_of(4), 0xFF);
+/// }
+///
+/// let output_directory = std::env::temp_dir()
+///     .join("binwalk_jffs2_doctest")
+///     .display()
+///     .to_string();
+/// # std::fs::remove_dir_all(&output_directory).ok();
+///
+/// let result = extract_jffs2(&image, 0, Some(&output_directory));
+///
+/// assert_eq!(result.success, true);
+/// assert_eq!(result.size, Some(image.len()));
+///
+/// let extracted_file = std::path::Path::new(&output_directory).join("jffs2-root").join("hello.txt");
+/// assert_eq!(std::fs::read(extracted_file).unwrap(), b"Hello, JFFS2!\0\0\0");
+/// # std::fs::remove_dir_all(&output_directory).ok();
+/// ```
+pub fn extract_jffs2(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    // JFFS2 files will be extracted to this sub-directory of the output directory
+    const OUTPUT_DIRECTORY_NAME: &str = "jffs2-root";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(jffs2_image) = process_jffs2_nodes(file_data, offset) {
+        // The root directory must have at least one entry
+        if jffs2_image
+            .dirents
+            .iter()
+            .any(|dirent| dirent.parent_inode == ROOT_INODE)
+        {
+            result.success = true;
+            result.size = Some(jffs2_image.size);
+
+            // Do extraction, if an output directory was provided
+            if output_directory.is_some() {
+                let chroot = Chroot::new(output_directory);
+                let mut file_count: usize = 0;
+
+                if chroot.create_directory(OUTPUT_DIRECTORY_NAME) {
+                    let jffs2_chroot =
+                        Chroot::new(Some(&chroot.chrooted_path(OUTPUT_DIRECTORY_NAME)));
+                    let mut visited_directories: HashSet<usize> = HashSet::new();
+
+                    file_count = extract_jffs2_directory(
+                        file_data,
+                        &jffs2_image,
+                        ROOT_INODE,
+                        "",
+                        &jffs2_chroot,
+                        &mut visited_directories,
+                    );
+                }
+
+                // If no files were extracted, extraction was a failure
+                if file_count == 0 {
+                    result.success = false;
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Walks all nodes in the JFFS2 image, collecting the latest version of each directory entry, and all inode nodes
+fn process_jffs2_nodes(file_data: &[u8], offset: usize) -> Option<JFFS2Image> {
+    // If no valid node has been found within this many bytes, assume we've reached the end of the image
+    const MAX_PAGE_SIZE: usize = 0x20000;
+    // Nodes are aligned to a 4-byte boundary
+    const NODE_ALIGNMENT: usize = 4;
+
+    let first_node = parse_jffs2_node_header(file_data.get(offset..)?).ok()?;
+
+    let mut jffs2_image = JFFS2Image {
+        ..Default::default()
+    };
+
+    // Latest version of each directory entry, keyed by parent inode and name
+    let mut dirents: HashMap<(usize, String), JFFS2Dirent> = HashMap::new();
+
+    let mut node_offset: usize = offset;
+    let mut jffs2_eof: usize = offset;
+
+    while node_offset < file_data.len() && (node_offset - jffs2_eof) <= MAX_PAGE_SIZE {
+        let node_data = &file_data[node_offset..];
+
+        let node_header = match parse_jffs2_node_header(node_data) {
+            Ok(node_header)
+                if node_header.endianness == first_node.endianness
+                    && node_header.size >= JFFS2_NODE_STRUCT_SIZE
+                    && node_header.size <= node_data.len() =>
+            {
+                node_header
+            }
+            _ => {
+                // Not a valid node, keep looking
+                node_offset += NODE_ALIGNMENT;
+                continue;
+            }
+        };
+
+        let node_data = &node_data[..node_header.size];
+
+        match node_header.node_type {
+            JFFS2_NODETYPE_DIRENT => match parse_jffs2_dirent(node_data, &node_header.endianness) {
+                Err(_) => warn!("Invalid JFFS2 directory entry at offset {node_offset:#X}"),
+                Ok(dirent) => {
+                    let key = (dirent.parent_inode, dirent.name.clone());
+
+                    // Only the latest version of a directory entry is relevant
+                    if dirents
+                        .get(&key)
+                        .is_none_or(|existing| existing.version < dirent.version)
+                    {
+                        dirents.insert(key, dirent);
+                    }
+                }
+            },
+            JFFS2_NODETYPE_INODE => match parse_jffs2_inode(node_data, &node_header.endianness) {
+                Err(_) => warn!("Invalid JFFS2 inode at offset {node_offset:#X}"),
+                Ok(inode) => {
+                    let data_start = node_offset + inode.header_size;
+                    jffs2_image
+                        .inodes
+                        .entry(inode.inode)
+                        .or_default()
+                        .push(JFFS2InodeNode {
+                            info: inode,
+                            data_start,
+                        });
+                }
+            },
+            _ => {
+                debug!(
+                    "Ignoring JFFS2 node type {:#X} at offset {node_offset:#X}",
+                    node_header.node_type
+                );
+            }
+        }
+
+        node_offset += node_header.size.next_multiple_of(NODE_ALIGNMENT);
+        jffs2_eof = node_offset;
+    }
+
+    // Directory entries with an inode number of 0 have been deleted
+    jffs2_image.dirents = dirents
+        .into_values()
+        .filter(|dirent| dirent.inode != 0)
+        .collect();
+
+    // Sort entries by name for predictable extraction
+    jffs2_image.dirents.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Inode data is applied in version order
+    for inode_nodes in jffs2_image.inodes.values_mut() {
+        inode_nodes.sort_by_key(|node| node.info.version);
+    }
+
+    jffs2_image.size = jffs2_eof - offset;
+    jffs2_image.endianness = first_node.endianness;
+
+    Some(jffs2_image)
+}
+
+/// Recursively extracts the contents of a JFFS2 directory, returns the number of extracted files/directories
+fn extract_jffs2_directory(
+    file_data: &[u8],
+    jffs2_image: &JFFS2Image,
+    directory_inode: usize,
+    directory_path: &str,
+    chroot: &Chroot,
+    visited_directories: &mut HashSet<usize>,
+) -> usize {
+    // Directory entry types, used if a file has no inode nodes
+    const DT_FIFO: usize = 1;
+    const DT_CHR: usize = 2;
+    const DT_DIR: usize = 4;
+    const DT_BLK: usize = 6;
+    const DT_REG: usize = 8;
+    const DT_LNK: usize = 10;
+    const DT_SOCK: usize = 12;
+
+    let mut file_count: usize = 0;
+
+    // Sanity check, a directory should only be processed once, else infinite recursion could ensue
+    if !visited_directories.insert(directory_inode) {
+        return file_count;
+    }
+
+    for dirent in jffs2_image
+        .dirents
+        .iter()
+        .filter(|dirent| dirent.parent_inode == directory_inode)
+    {
+        let file_path = chroot.safe_path_join(directory_path, &dirent.name);
+        let inode_nodes = jffs2_image.inodes.get(&dirent.inode);
+
+        // Prefer the file type reported by the latest inode
+        let file_type = match inode_nodes.and_then(|nodes| nodes.last()) {
+            Some(latest_node) => latest_node.info.mode & S_IFMT,
+            None => match dirent.dirent_type {
+                DT_FIFO => S_IFIFO,
+                DT_CHR => S_IFCHR,
+                DT_DIR => S_IFDIR,
+                DT_BLK => S_IFBLK,
+                DT_REG => S_IFREG,
+                DT_LNK => S_IFLNK,
+                DT_SOCK => S_IFSOCK,
+                _ => 0,
+            },
+        };
+
+        let file_contents = || match inode_nodes {
+            None => vec![],
+            Some(nodes) => jffs2_inode_data(file_data, nodes),
+        };
+
+        let extraction_success = match file_type {
+            S_IFDIR => chroot.create_directory(&file_path),
+            S_IFREG => chroot.create_file(&file_path, &file_contents()),
+            S_IFLNK => {
+                let target = String::from_utf8_lossy(&file_contents()).to_string();
+                chroot.create_symlink(&file_path, target)
+            }
+            S_IFCHR | S_IFBLK => {
+                let (major, minor) = jffs2_device_number(&file_contents(), &jffs2_image.endianness);
+                if file_type == S_IFCHR {
+                    chroot.create_character_device(&file_path, major, minor)
+                } else {
+                    chroot.create_block_device(&file_path, major, minor)
+                }
+            }
+            S_IFIFO => chroot.create_fifo(&file_path),
+            S_IFSOCK => chroot.create_socket(&file_path),
+            _ => {
+                warn!("Unknown file type for JFFS2 file {file_path}");
+                continue;
+            }
+        };
+
+        if extraction_success {
+            file_count += 1;
+
+            if file_type == S_IFDIR {
+                file_count += extract_jffs2_directory(
+                    file_data,
+                    jffs2_image,
+                    dirent.inode,
+                    &file_path,
+                    chroot,
+                    visited_directories,
+                );
+            } else if file_type == S_IFREG {
+                // Make executable files executable
+                if let Some(latest_node) = inode_nodes.and_then(|nodes| nodes.last()) {
+                    if (latest_node.info.mode & S_IXANY) != 0 {
+                        chroot.make_executable(&file_path);
+                    }
+                }
+            }
+        } else {
+            warn!("Failed to extract JFFS2 file {file_path}");
+        }
+    }
+
+    file_count
+}
+
+/// Reconstructs a file's contents from all of its inode nodes, which must be sorted by version
+fn jffs2_inode_data(file_data: &[u8], inode_nodes: &[JFFS2InodeNode]) -> Vec<u8> {
+    // A node's data never spans more than one page, and pages are at most 64KB
+    const MAX_NODE_DATA_SIZE: usize = 0x10000;
+
+    let max_file_size = decompression_limit();
+    let mut contents: Vec<u8> = vec![];
+
+    for node in inode_nodes {
+        // Each node's file size reflects the size of the file when the node was written; this handles truncation
+        if node.info.file_size > max_file_size {
+            warn!(
+                "JFFS2 inode {} reports a file size of {} bytes, truncating it to {} bytes",
+                node.info.inode, node.info.file_size, max_file_size
+            );
+            report_decompression_limit_reached();
+        }
+        contents.resize(std::cmp::min(node.info.file_size, max_file_size), 0);
+
+        if node.info.decompressed_size > MAX_NODE_DATA_SIZE {
+            warn!(
+                "JFFS2 inode {} has a node with an invalid data size of {} bytes",
+                node.info.inode, node.info.decompressed_size
+            );
+            continue;
+        }
+
+        // Node data past the end of the file is discarded, so there is no need to decompress it
+        if node.info.data_offset >= contents.len() {
+            continue;
+        }
+
+        let compressed_data =
+            &file_data[node.data_start..node.data_start + node.info.compressed_size];
+
+        match jffs2_decompress(
+            compressed_data,
+            node.info.compression,
+            node.info.decompressed_size,
+        ) {
+            None => warn!(
+                "Failed to decompress data for JFFS2 inode {} (compression type {})",
+                node.info.inode, node.info.compression
+            ),
+            Some(node_data) => {
+                // Copy the node data into the file, ignoring anything past the end of the file
+                let copy_size =
+                    std::cmp::min(node_data.len(), contents.len() - node.info.data_offset);
+                contents[node.info.data_offset..node.info.data_offset + copy_size]
+                    .copy_from_slice(&node_data[..copy_size]);
+            }
+        }
+    }
+
+    contents
+}
+
+/// Decompresses inode node data
+fn jffs2_decompress(
+    compressed_data: &[u8],
+    compression: usize,
+    decompressed_size: usize,
+) -> Option<Vec<u8>> {
+    // Maximum memory limit for the LZMA decoder
+    const LZMA_MEM_LIMIT: u64 = 256 * 1024 * 1024;
+    // LZMA properties used by JFFS2: lc=0, lp=0, pb=0, and an 8KB dictionary
+    const LZMA_PROPERTIES: u8 = 0;
+    const LZMA_DICT_SIZE: u32 = 0x2000;
+
+    let mut decompressed_data: Vec<u8> = vec![];
+
+    match compression {
+        JFFS2_COMPR_NONE => {
+            decompressed_data = compressed_data.get(..decompressed_size)?.to_vec();
+        }
+        JFFS2_COMPR_ZERO => {
+            decompressed_data = vec![0; decompressed_size];
+        }
+        JFFS2_COMPR_RTIME => {
+            decompressed_data = rtime_decompress(compressed_data, decompressed_size)?;
+        }
+        JFFS2_COMPR_ZLIB => {
+            ZlibDecoder::new(compressed_data)
+                .take(decompressed_size as u64)
+                .read_to_end(&mut decompressed_data)
+                .ok()?;
+        }
+        JFFS2_COMPR_LZMA => {
+            // JFFS2 LZMA data is a raw LZMA stream; prepend an LZMA header so that it can be decoded
+            let mut lzma_data: Vec<u8> = vec![LZMA_PROPERTIES];
+            lzma_data.extend_from_slice(&LZMA_DICT_SIZE.to_le_bytes());
+            lzma_data.extend_from_slice(&(decompressed_size as u64).to_le_bytes());
+            lzma_data.extend_from_slice(compressed_data);
+
+            let decoder = Stream::new_lzma_decoder(LZMA_MEM_LIMIT).ok()?;
+            XzDecoder::new_stream(lzma_data.as_slice(), decoder)
+                .read_to_end(&mut decompressed_data)
+                .ok()?;
+        }
+        _ => return None,
+    }
+
+    Some(decompressed_data)
+}
+
+/// Decompresses data compressed with the JFFS2 "rtime" compressor
+fn rtime_decompress(compressed_data: &[u8], decompressed_size: usize) -> Option<Vec<u8>> {
+    let mut positions: [usize; 256] = [0; 256];
+    let mut decompressed_data: Vec<u8> = Vec::with_capacity(decompressed_size);
+    let mut compressed_offset: usize = 0;
+
+    while decompressed_data.len() < decompressed_size {
+        let value = *compressed_data.get(compressed_offset)?;
+        let repeat = *compressed_data.get(compressed_offset + 1)? as usize;
+        compressed_offset += 2;
+
+        decompressed_data.push(value);
+
+        let back_offset = positions[value as usize];
+        positions[value as usize] = decompressed_data.len();
+
+        // The repeated bytes may overlap the bytes being written, so they must be copied one at a time
+        for i in back_offset..back_offset + repeat {
+            let repeated_byte = *decompressed_data.get(i)?;
+            decompressed_data.push(repeated_byte);
+        }
+    }
+
+    decompressed_data.truncate(decompressed_size);
+    Some(decompressed_data)
+}
+
+/// Decodes a device file's major and minor numbers from its inode data
+fn jffs2_device_number(device_data: &[u8], endianness: &str) -> (usize, usize) {
+    let device_size = match device_data.len() {
+        2 => "u16",
+        4 => "u32",
+        _ => return (0, 0),
+    };
+
+    match common::parse(device_data, &vec![("device", device_size)], endianness) {
+        Err(_) => (0, 0),
+        Ok(device_number) => {
+            let device = device_number["device"];
+
+            if device_size == "u16" {
+                // Old style, 16-bit device number
+                ((device >> 8) & 0xFF, device & 0xFF)
+            } else {
+                // New style, 32-bit device number
+                (
+                    (device & 0xFFF00) >> 8,
+                    (device & 0xFF) | ((device >> 12) & 0xFFF00),
+                )
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::common::parser_deadline_exceeded;
 use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
 
 /// Defines the internal extractor function for carving out JPEG images
@@ -11,6 +12,7 @@ use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorTy
 /// match jpeg_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -64,9 +66,15 @@ fn get_jpeg_data_size(jpeg_data: &[u8]) -> Option<usize> {
     const MARKER_MAGIC: u8 = 0xFF;
     const SOS_MARKER: u8 = 0xDA;
     const EOF_MARKER: u8 = 0xD9;
+    // How often the parser watchdog is checked while scanning image data for the next marker
+    const DEADLINE_CHECK_INTERVAL: usize = 0x10000;
 
     let mut next_marker_offset: usize = 0;
 
+    // An EOF marker only terminates the image once image data has been seen; this keeps bogus
+    // SOI/EOI pairs in uninitialized data from being reported as images
+    let mut scan_found: bool = false;
+
     // Most JPEG markers include a size field; these do not
     let no_length_markers: Vec<u8> = vec![
         0x00, 0x01, 0xD0, 0xD1, 0xD2, 0xD3, 0xD4, 0xD5, 0xD6, 0xD7, 0xD8, EOF_MARKER,
@@ -76,6 +84,10 @@ fn get_jpeg_data_size(jpeg_data: &[u8]) -> Option<usize> {
     let sos_skip_markers: Vec<u8> = vec![0x00, 0xD0, 0xD1, 0xD2, 0xD3, 0xD4, 0xD5, 0xD6, 0xD7];
 
     loop {
+        if parser_deadline_exceeded() {
+            break;
+        }
+
         // Read the marker magic byte
         match jpeg_data.get(next_marker_offset) {
             None => {
@@ -90,6 +102,11 @@ fn get_jpeg_data_size(jpeg_data: &[u8]) -> Option<usize> {
                 // Include marker magic byte in side of the marker
                 next_marker_offset += 1;
 
+                // Any marker may be preceded by any number of 0xFF fill bytes
+                while jpeg_data.get(next_marker_offset) == Some(&MARKER_MAGIC) {
+                    next_marker_offset += 1;
+                }
+
                 // Read the marker ID byte
                 match jpeg_data.get(next_marker_offset) {
                     None => {
@@ -108,8 +125,15 @@ fn get_jpeg_data_size(jpeg_data: &[u8]) -> Option<usize> {
                                     break;
                                 }
                                 Some(size_bytes) => {
-                                    next_marker_offset +=
+                                    let marker_size =
                                         u16::from_be_bytes(size_bytes.try_into().unwrap()) as usize;
+
+                                    // The length field includes itself, so anything smaller is invalid
+                                    if marker_size < SIZE_FIELD_LENGTH {
+                                        break;
+                                    }
+
+                                    next_marker_offset += marker_size;
                                 }
                             }
                         }
@@ -117,7 +141,15 @@ fn get_jpeg_data_size(jpeg_data: &[u8]) -> Option<usize> {
                         // Start Of Scan markers have a size field, but are immediately followed by data not included int
                         // the size field. Need to scan all the bytes until the next valid JPEG marker is found.
                         if *marker_id == SOS_MARKER {
+                            scan_found = true;
+
                             loop {
+                                if next_marker_offset.is_multiple_of(DEADLINE_CHECK_INTERVAL)
+                                    && parser_deadline_exceeded()
+                                {
+                                    return None;
+                                }
+
                                 // Get the next two bytes
                                 match jpeg_data.get(
                                     next_marker_offset..next_marker_offset + SOS_SCAN_AHEAD_LENGTH,
@@ -142,7 +174,10 @@ fn get_jpeg_data_size(jpeg_data: &[u8]) -> Option<usize> {
 
                         // EOF marker indicates the end of the JPEG image
                         if *marker_id == EOF_MARKER {
-                            return Some(next_marker_offset);
+                            if scan_found {
+                                return Some(next_marker_offset);
+                            }
+                            break;
                         }
                     }
                 }
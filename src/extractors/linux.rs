@@ -11,6 +11,7 @@ use crate::extractors;
 /// match linux_kernel_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -34,5 +35,6 @@ pub fn linux_kernel_extractor() -> extractors::common::Extractor {
             "linux_kernel.elf".to_string(),
         ],
         exit_codes: vec![0],
+        ..Default::default()
     }
 }
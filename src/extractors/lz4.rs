@@ -1,6 +1,13 @@
-use crate::extractors;
+use crate::common::parser_deadline_exceeded;
+use crate::extractors::common::{
+    Chroot, ExtractionResult, Extractor, ExtractorType, SOURCE_FILE_PLACEHOLDER,
+};
+use crate::structures::lz4::{
+    LZ4_FRAME_MAGIC, LZ4_LEGACY_MAGIC, parse_lz4_block_header, parse_lz4_file_header,
+};
+use xxhash_rust::xxh32::Xxh32;
 
-/// Describes how to run the lz4 utility to extract LZ4 compressed files
+/// Defines the internal extractor function for decompressing LZ4 data
 ///
 /// ```
 /// use std::io::ErrorKind;
@@ -11,6 +18,7 @@ use crate::extractors;
 /// match lz4_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -22,17 +30,324 @@ use crate::extractors;
 ///     }
 /// }
 /// ```
-pub fn lz4_extractor() -> extractors::common::Extractor {
-    extractors::common::Extractor {
-        utility: extractors::common::ExtractorType::External("lz4".to_string()),
+pub fn lz4_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(lz4_decompress),
+        fallback: Some(Box::new(lz4_utility_extractor())),
+        ..Default::default()
+    }
+}
+
+/// Describes the lz4 utility, which is run on LZ4 data that the internal extractor fails to decompress
+fn lz4_utility_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::External("lz4".to_string()),
         extension: "lz4".to_string(),
         arguments: vec![
             "-f".to_string(), // Force overwirte if, for some reason, the output file exists
             "-d".to_string(), // Perform a decompression
-            extractors::common::SOURCE_FILE_PLACEHOLDER.to_string(),
+            SOURCE_FILE_PLACEHOLDER.to_string(),
             "decompressed.bin".to_string(), // Output file
         ],
         exit_codes: vec![0],
         ..Default::default()
     }
 }
+
+/// Internal extractor for decompressing LZ4 data in either the frame format or the legacy format
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::lz4::lz4_decompress;
+///
+/// // "Hello, LZ4! " repeated 16 times, compressed by the lz4 utility in the frame and legacy formats
+/// let lz4_frame: &[u8] = &[
+///     0x04, 0x22, 0x4d, 0x18, 0x64, 0x40, 0xa7, 0x16, 0x00, 0x00, 0x00, 0xcf, 0x48, 0x65, 0x6c, 0x6c,
+///     0x6f, 0x2c, 0x20, 0x4c, 0x5a, 0x34, 0x21, 0x20, 0x0c, 0x00, 0x9c, 0x50, 0x4c, 0x5a, 0x34, 0x21,
+///     0x20, 0x00, 0x00, 0x00, 0x00, 0x20, 0xa5, 0xc2, 0xd9,
+/// ];
+/// let lz4_legacy: &[u8] = &[
+///     0x02, 0x21, 0x4c, 0x18, 0x16, 0x00, 0x00, 0x00, 0xcf, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x2c, 0x20,
+///     0x4c, 0x5a, 0x34, 0x21, 0x20, 0x0c, 0x00, 0x9c, 0x50, 0x4c, 0x5a, 0x34, 0x21, 0x20,
+/// ];
+///
+/// let output_directory = std::env::temp_dir()
+///     .join("binwalk_lz4_doctest")
+///     .display()
+///     .to_string();
+///
+/// for lz4_data in [lz4_frame, lz4_legacy] {
+///     # std::fs::remove_dir_all(&output_directory).ok();
+///     let result = lz4_decompress(lz4_data, 0, Some(&output_directory));
+///
+///     assert_eq!(result.success, true);
+///     assert_eq!(result.size, Some(lz4_data.len()));
+///
+///     let decompressed_file = std::path::Path::new(&output_directory).join("decompressed.bin");
+///     assert_eq!(std::fs::read(decompressed_file).unwrap(), "Hello, LZ4! ".repeat(16).as_bytes());
+/// }
+/// # std::fs::remove_dir_all(&output_directory).ok();
+/// ```
+pub fn lz4_decompress(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(lz4_data) = file_data.get(offset..) {
+        let lz4_data_size = if lz4_data.starts_with(LZ4_FRAME_MAGIC) {
+            decompress_frame(lz4_data, output_directory)
+        } else if lz4_data.starts_with(LZ4_LEGACY_MAGIC) {
+            decompress_legacy(lz4_data, output_directory)
+        } else {
+            None
+        };
+
+        if let Some(size) = lz4_data_size {
+            result.size = Some(size);
+            result.success = true;
+        }
+    }
+
+    result
+}
+
+/// Writes decompressed data to the output file, if extraction was requested
+fn write_output(output_directory: Option<&str>, decompressed_data: &[u8]) -> Option<()> {
+    // Output file for decompressed data
+    const OUTPUT_FILE_NAME: &str = "decompressed.bin";
+
+    if output_directory.is_some() {
+        let chroot = Chroot::new(output_directory);
+        if !chroot.append_to_file(OUTPUT_FILE_NAME, decompressed_data) {
+            return None;
+        }
+    }
+
+    Some(())
+}
+
+/// Decompresses an LZ4 frame, returning the size of the compressed frame.
+///
+/// Decompressed data is written out one block at a time, so the entire decompressed frame is never held in memory.
+fn decompress_frame(lz4_data: &[u8], output_directory: Option<&str>) -> Option<usize> {
+    // Content checksum is a 4-byte xxh32 hash of the decompressed data
+    const CONTENT_CHECKSUM_LEN: usize = 4;
+    // Blocks may reference up to 64KB of previously decompressed data
+    const HISTORY_SIZE: usize = 64 * 1024;
+
+    let lz4_header = parse_lz4_file_header(lz4_data).ok()?;
+
+    let mut content_hasher = Xxh32::new(0);
+    let mut decompressed_data: Vec<u8> = vec![];
+    let mut next_block_offset = lz4_header.header_size;
+
+    loop {
+        let block_header = parse_lz4_block_header(
+            lz4_data.get(next_block_offset..)?,
+            lz4_header.block_checksum_present,
+        )
+        .ok()?;
+
+        // The end marker has no data or checksum
+        if block_header.last_block {
+            next_block_offset += block_header.header_size;
+            break;
+        }
+
+        if block_header.data_size > lz4_header.max_block_size {
+            return None;
+        }
+
+        let block_start = next_block_offset + block_header.header_size;
+        let block_data = lz4_data.get(block_start..block_start + block_header.data_size)?;
+
+        // Only the history needed by the next block is kept from previously decompressed blocks
+        if decompressed_data.len() > HISTORY_SIZE {
+            decompressed_data.drain(..decompressed_data.len() - HISTORY_SIZE);
+        }
+
+        let history_size = decompressed_data.len();
+
+        if block_header.uncompressed {
+            decompressed_data.extend_from_slice(block_data);
+        } else {
+            // Blocks may reference data from previous blocks, so they are decompressed following the history
+            decompress_block(
+                block_data,
+                &mut decompressed_data,
+                lz4_header.max_block_size,
+            )?;
+        }
+
+        content_hasher.update(&decompressed_data[history_size..]);
+        write_output(output_directory, &decompressed_data[history_size..])?;
+
+        next_block_offset = block_start + block_header.data_size + block_header.checksum_size;
+    }
+
+    if lz4_header.content_checksum_present {
+        let checksum_bytes =
+            lz4_data.get(next_block_offset..next_block_offset + CONTENT_CHECKSUM_LEN)?;
+        let expected_checksum = u32::from_le_bytes(checksum_bytes.try_into().ok()?);
+
+        if content_hasher.digest() != expected_checksum {
+            return None;
+        }
+
+        next_block_offset += CONTENT_CHECKSUM_LEN;
+    }
+
+    Some(next_block_offset)
+}
+
+/// Decompresses LZ4 legacy format data, returning the size of the compressed data.
+///
+/// The legacy format has no end marker; blocks are processed until one is found that is not valid.
+/// This is expected, as Linux kernel images append the decompressed size to the end of the LZ4 data.
+fn decompress_legacy(lz4_data: &[u8], output_directory: Option<&str>) -> Option<usize> {
+    // Legacy blocks always decompress to 8MB, except for the last block
+    const LEGACY_BLOCK_SIZE: usize = 8 * 1024 * 1024;
+    const BLOCK_HEADER_SIZE: usize = 4;
+
+    // Largest possible size of a compressed legacy block
+    let max_compressed_block_size = LEGACY_BLOCK_SIZE + (LEGACY_BLOCK_SIZE / 255) + 16;
+
+    let mut block_count: usize = 0;
+    let mut decompressed_data: Vec<u8> = vec![];
+    let mut next_block_offset = LZ4_LEGACY_MAGIC.len();
+
+    while let Some(block_header) =
+        lz4_data.get(next_block_offset..next_block_offset + BLOCK_HEADER_SIZE)
+    {
+        if parser_deadline_exceeded() {
+            break;
+        }
+
+        // Legacy streams may be concatenated, in which case the next block header is another magic signature
+        if block_header == LZ4_LEGACY_MAGIC {
+            next_block_offset += BLOCK_HEADER_SIZE;
+            continue;
+        }
+
+        let block_size = u32::from_le_bytes(block_header.try_into().ok()?) as usize;
+
+        if block_size == 0 || block_size > max_compressed_block_size {
+            break;
+        }
+
+        let block_start = next_block_offset + BLOCK_HEADER_SIZE;
+
+        match lz4_data.get(block_start..block_start + block_size) {
+            None => break,
+            Some(block_data) => {
+                // Legacy blocks are independent of each other
+                decompressed_data.clear();
+
+                if decompress_block(block_data, &mut decompressed_data, LEGACY_BLOCK_SIZE).is_none()
+                {
+                    break;
+                }
+
+                write_output(output_directory, &decompressed_data)?;
+            }
+        }
+
+        block_count += 1;
+        next_block_offset = block_start + block_size;
+    }
+
+    if block_count > 0 {
+        return Some(next_block_offset);
+    }
+
+    None
+}
+
+/// Decompresses a single LZ4 block, appending the decompressed data to `output`.
+///
+/// Matches may reference any data already in `output`. Returns the number of decompressed bytes,
+/// or None if the block is malformed or would decompress to more than `max_size` bytes.
+fn decompress_block(block_data: &[u8], output: &mut Vec<u8>, max_size: usize) -> Option<usize> {
+    const MIN_MATCH_LENGTH: usize = 4;
+    const OFFSET_SIZE: usize = 2;
+
+    let output_start = output.len();
+    let mut i: usize = 0;
+
+    loop {
+        let token = *block_data.get(i)?;
+        i += 1;
+
+        // Upper nibble of the token is the literal length
+        let literal_length = read_length(block_data, &mut i, (token >> 4) as usize)?;
+        let literals = block_data.get(i..i + literal_length)?;
+
+        if (output.len() - output_start) + literal_length > max_size {
+            return None;
+        }
+
+        output.extend_from_slice(literals);
+        i += literal_length;
+
+        // The last sequence in a block contains only literals
+        if i == block_data.len() {
+            break;
+        }
+
+        let offset_bytes = block_data.get(i..i + OFFSET_SIZE)?;
+        let match_offset = u16::from_le_bytes(offset_bytes.try_into().ok()?) as usize;
+        i += OFFSET_SIZE;
+
+        if match_offset == 0 || match_offset > output.len() {
+            return None;
+        }
+
+        // Lower nibble of the token is the match length, minus the minimum match length
+        let match_length =
+            read_length(block_data, &mut i, (token & 0x0F) as usize)? + MIN_MATCH_LENGTH;
+
+        if (output.len() - output_start) + match_length > max_size {
+            return None;
+        }
+
+        let match_start = output.len() - match_offset;
+
+        if match_offset >= match_length {
+            output.extend_from_within(match_start..match_start + match_length);
+        } else {
+            // Overlapping matches repeat the most recently decompressed bytes
+            for j in match_start..match_start + match_length {
+                output.push(output[j]);
+            }
+        }
+    }
+
+    Some(output.len() - output_start)
+}
+
+/// Reads an LZ4 literal or match length; a nibble value of 15 is extended by any following bytes, until a byte other than 255 is read
+fn read_length(block_data: &[u8], i: &mut usize, nibble: usize) -> Option<usize> {
+    const EXTENDED_LENGTH: usize = 15;
+    const MAX_BYTE: u8 = 255;
+
+    let mut length = nibble;
+
+    if length == EXTENDED_LENGTH {
+        loop {
+            let byte = *block_data.get(*i)?;
+            *i += 1;
+            length += byte as usize;
+
+            if byte != MAX_BYTE {
+                break;
+            }
+        }
+    }
+
+    Some(length)
+}
@@ -1,4 +1,11 @@
-use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::common::parser_deadline_exceeded;
+use crate::extractors::common::{
+    Chroot, ExtractionResult, Extractor, ExtractorType, decompression_limit,
+    report_decompression_limit_reached,
+};
+use crate::signatures::lzma::COMMON_PROPERTIES;
+use crate::structures::lzma::{LZMA_HEADER_SIZE, LZMA_STREAM_SIZE, parse_lzma_header};
+use crate::structures::xz::XZ_MAGIC;
 use liblzma::stream::{Action, Status, Stream};
 
 /// Defines the internal extractor function for decompressing LZMA/XZ data
@@ -12,6 +19,7 @@ use liblzma::stream::{Action, Status, Stream};
 /// match lzma_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -60,6 +68,20 @@ pub fn lzma_decompress(
         // Tracks current position of bytes consumed from input stream
         let mut stream_position: usize = 0;
 
+        /*
+         * LZMA streams with a bogus decompressed size field would be truncated, or fail to decompress entirely.
+         * For these streams, give the decoder a copy of the header with an unknown (streamed) size; this requires
+         * that the stream be terminated with an end of payload marker, which also serves to validate the stream.
+         */
+        if let Some(lzma_header) = invalid_size_lzma_header(lzma_stream) {
+            match decompressor.process(&lzma_header, &mut output_buf, Action::Run) {
+                Ok(Status::Ok) if decompressor.total_in() as usize == LZMA_HEADER_SIZE => {
+                    stream_position = LZMA_HEADER_SIZE;
+                }
+                _ => return result,
+            }
+        }
+
         /*
          * Loop through all compressed data and decompress it.
          *
@@ -71,6 +93,11 @@ pub fn lzma_decompress(
          * can also determine the exact size of the LZMA data.
          */
         loop {
+            // Stop decompressing if this is a signature parser that has run out of time
+            if parser_deadline_exceeded() {
+                break;
+            }
+
             // Decompress data into output_buf
             match decompressor.process(
                 &lzma_stream[stream_position..],
@@ -128,3 +155,240 @@ pub fn lzma_decompress(
 
     result
 }
+
+/// Decompresses an LZMA/XZ data stream until it fails, ends, or `max_size` bytes have been decompressed.
+/// Returns the number of bytes that were decompressed; used to gauge how plausible a truncated or corrupt stream is.
+pub fn lzma_decompressed_size(file_data: &[u8], offset: usize, max_size: usize) -> usize {
+    // Output buffer size
+    const BLOCK_SIZE: usize = 8192;
+    // Maximum memory limit: 4GB
+    const MEM_LIMIT: u64 = 4 * 1024 * 1024 * 1024;
+
+    let mut output_buf = [0; BLOCK_SIZE];
+    let lzma_stream = &file_data[offset..];
+
+    if let Ok(mut decompressor) = Stream::new_auto_decoder(MEM_LIMIT, 0) {
+        while (decompressor.total_out() as usize) < max_size && !parser_deadline_exceeded() {
+            let total_in = decompressor.total_in();
+            let total_out = decompressor.total_out();
+
+            match decompressor.process(
+                &lzma_stream[total_in as usize..],
+                &mut output_buf,
+                Action::Run,
+            ) {
+                // Stop once the decoder is no longer making progress, e.g., at the end of truncated data
+                Ok(Status::Ok)
+                    if decompressor.total_in() > total_in
+                        || decompressor.total_out() > total_out => {}
+                _ => break,
+            }
+        }
+
+        return decompressor.total_out() as usize;
+    }
+
+    0
+}
+
+/// If the data is an LZMA (not XZ) stream with an invalid decompressed size field, returns a copy of its header
+/// with the decompressed size set to unknown.
+fn invalid_size_lzma_header(lzma_stream: &[u8]) -> Option<Vec<u8>> {
+    const DECOMPRESSED_SIZE_START: usize = 5;
+
+    if !lzma_stream.starts_with(XZ_MAGIC) {
+        if let Ok(lzma_header) = parse_lzma_header(lzma_stream) {
+            if !lzma_header.decompressed_size_valid {
+                let mut patched_header = lzma_stream[..LZMA_HEADER_SIZE].to_vec();
+                patched_header[DECOMPRESSED_SIZE_START..]
+                    .copy_from_slice(&(LZMA_STREAM_SIZE as u64).to_le_bytes());
+                return Some(patched_header);
+            }
+        }
+    }
+
+    None
+}
+
+/// Dictionary size assumed when decompressing raw LZMA streams, which do not specify one; this is large enough for the
+/// dictionaries used by all of the standard LZMA compression presets
+const RAW_LZMA_DICTIONARY_SIZE: usize = 64 * 1024 * 1024;
+
+/// Defines the internal extractor function for decompressing raw (headerless) LZMA streams found by a brute force scan
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::lzma::raw_lzma_extractor;
+///
+/// match raw_lzma_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn raw_lzma_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(raw_lzma_decompress),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for decompressing raw LZMA streams. Raw streams have no header, so the stream's properties are
+/// brute forced, and everything that can be decompressed is kept.
+pub fn raw_lzma_decompress(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    // Output file name
+    const OUTPUT_FILE_NAME: &str = "decompressed.bin";
+    // Amount of data decompressed with each of the common properties to determine which the stream uses
+    const PROPERTIES_PREVIEW_SIZE: usize = 64 * 1024;
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some((properties, _)) = raw_lzma_properties(file_data, offset, PROPERTIES_PREVIEW_SIZE) {
+        let max_size = decompression_limit();
+
+        let (compressed_size, decompressed_size) = decode_raw_lzma(
+            file_data,
+            offset,
+            properties,
+            RAW_LZMA_DICTIONARY_SIZE,
+            max_size,
+            |data| match output_directory {
+                None => true,
+                Some(_) => Chroot::new(output_directory).append_to_file(OUTPUT_FILE_NAME, data),
+            },
+        );
+
+        if decompressed_size >= max_size {
+            report_decompression_limit_reached();
+        }
+
+        if decompressed_size > 0 {
+            result.success = true;
+            result.size = Some(compressed_size);
+        }
+    }
+
+    result
+}
+
+/// Decompresses up to `max_size` bytes of a raw LZMA stream with each of the common LZMA properties.
+/// Returns the properties that decompressed the most data, and the data they decompressed.
+pub fn raw_lzma_properties(
+    file_data: &[u8],
+    offset: usize,
+    max_size: usize,
+) -> Option<(u8, Vec<u8>)> {
+    // The range coder always starts with a zero byte
+    if file_data.get(offset) != Some(&0) {
+        return None;
+    }
+
+    // No data beyond max_size is decompressed, so there's no need to allocate a dictionary any larger than that
+    let dictionary_size = std::cmp::min(max_size.next_power_of_two(), RAW_LZMA_DICTIONARY_SIZE);
+
+    let mut best: Option<(u8, Vec<u8>)> = None;
+
+    for properties in COMMON_PROPERTIES {
+        let mut decompressed: Vec<u8> = vec![];
+
+        decode_raw_lzma(
+            file_data,
+            offset,
+            properties,
+            dictionary_size,
+            max_size,
+            |data| {
+                decompressed.extend(data);
+                true
+            },
+        );
+
+        if !decompressed.is_empty()
+            && best
+                .as_ref()
+                .is_none_or(|(_, best_decompressed)| decompressed.len() > best_decompressed.len())
+        {
+            best = Some((properties, decompressed));
+        }
+    }
+
+    best
+}
+
+/// Decompresses a raw LZMA stream with the given properties, passing each block of decompressed data to `output`, until
+/// the stream ends, decompression fails, `output` returns false, or at least `max_size` bytes have been decompressed.
+/// Returns the number of compressed bytes consumed, and the number of bytes decompressed.
+fn decode_raw_lzma(
+    file_data: &[u8],
+    offset: usize,
+    properties: u8,
+    dictionary_size: usize,
+    max_size: usize,
+    mut output: impl FnMut(&[u8]) -> bool,
+) -> (usize, usize) {
+    // Output buffer size
+    const BLOCK_SIZE: usize = 8192;
+    // Maximum memory limit: 4GB
+    const MEM_LIMIT: u64 = 4 * 1024 * 1024 * 1024;
+
+    let mut output_buf = [0; BLOCK_SIZE];
+    let lzma_stream = &file_data[offset..];
+
+    // Give the decoder a header with the stream's properties, and an unknown decompressed size
+    let mut lzma_header: Vec<u8> = vec![properties];
+    lzma_header.extend((dictionary_size as u32).to_le_bytes());
+    lzma_header.extend((LZMA_STREAM_SIZE as u64).to_le_bytes());
+
+    if let Ok(mut decompressor) = Stream::new_lzma_decoder(MEM_LIMIT) {
+        match decompressor.process(&lzma_header, &mut output_buf, Action::Run) {
+            Ok(Status::Ok) if decompressor.total_in() as usize == LZMA_HEADER_SIZE => (),
+            _ => return (0, 0),
+        }
+
+        while (decompressor.total_out() as usize) < max_size && !parser_deadline_exceeded() {
+            let total_in = decompressor.total_in();
+            let total_out = decompressor.total_out();
+
+            let status = decompressor.process(
+                &lzma_stream[total_in as usize - LZMA_HEADER_SIZE..],
+                &mut output_buf,
+                Action::Run,
+            );
+
+            let n = (decompressor.total_out() - total_out) as usize;
+            if n > 0 && !output(&output_buf[..n]) {
+                break;
+            }
+
+            match status {
+                // Stop once the decoder is no longer making progress, e.g., at the end of truncated data
+                Ok(Status::Ok) if decompressor.total_in() > total_in || n > 0 => {}
+                _ => break,
+            }
+        }
+
+        return (
+            decompressor.total_in() as usize - LZMA_HEADER_SIZE,
+            decompressor.total_out() as usize,
+        );
+    }
+
+    (0, 0)
+}
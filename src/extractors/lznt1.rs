@@ -0,0 +1,117 @@
+/// LZNT1 data is compressed in independent chunks, each of which decompresses to this many bytes
+const CHUNK_SIZE: usize = 4096;
+
+/// Decompresses LZNT1 data, as used by compressed NTFS files.
+///
+/// Chunks that decompress to less than the chunk size, and any data missing from the end of the compressed data,
+/// read as zeros. Returns None if the data is malformed.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::lznt1::lznt1_decompress;
+///
+/// // A single compressed chunk, containing three literal bytes and a six byte back reference
+/// let compressed_data = b"\x05\xB0\x08ABC\x03\x20";
+///
+/// assert_eq!(lznt1_decompress(compressed_data, 9), Some(b"ABCABCABC".to_vec()));
+/// assert_eq!(lznt1_decompress(compressed_data, 10), Some(b"ABCABCABC\x00".to_vec()));
+/// assert_eq!(lznt1_decompress(&compressed_data[..6], 9), None);
+/// ```
+pub fn lznt1_decompress(compressed_data: &[u8], uncompressed_size: usize) -> Option<Vec<u8>> {
+    const COMPRESSED_FLAG: u16 = 0x8000;
+    const CHUNK_SIZE_MASK: u16 = 0x0FFF;
+
+    let mut output: Vec<u8> = Vec::with_capacity(uncompressed_size);
+    let mut chunk_offset: usize = 0;
+
+    while output.len() < uncompressed_size {
+        // A chunk header of 0, or the end of the data, ends the compressed data
+        let chunk_header = match compressed_data.get(chunk_offset..chunk_offset + 2) {
+            None => break,
+            Some(chunk_header) => u16::from_le_bytes([chunk_header[0], chunk_header[1]]),
+        };
+
+        if chunk_header == 0 {
+            break;
+        }
+
+        // The chunk header's size field is the size of the chunk data, minus 1
+        let chunk_start = chunk_offset + 2;
+        let chunk_end = chunk_start + (chunk_header & CHUNK_SIZE_MASK) as usize + 1;
+        let chunk_data = compressed_data.get(chunk_start..chunk_end)?;
+
+        let chunk_output_start = output.len();
+
+        match chunk_header & COMPRESSED_FLAG {
+            0 => output.extend_from_slice(chunk_data),
+            _ => decompress_chunk(chunk_data, &mut output)?,
+        }
+
+        if output.len() - chunk_output_start > CHUNK_SIZE {
+            return None;
+        }
+
+        output.resize(chunk_output_start + CHUNK_SIZE, 0);
+        chunk_offset = chunk_end;
+    }
+
+    output.resize(uncompressed_size, 0);
+
+    Some(output)
+}
+
+/// Decompresses a compressed chunk, appending the decompressed data to `output`
+fn decompress_chunk(chunk_data: &[u8], output: &mut Vec<u8>) -> Option<()> {
+    const MIN_MATCH: usize = 3;
+    const TOKEN_BITS: usize = 12;
+
+    let chunk_output_start = output.len();
+    let mut offset: usize = 0;
+
+    // Each group of eight tokens is preceded by a flag byte; each set bit indicates a back reference, else a literal byte
+    while offset < chunk_data.len() {
+        let flags = chunk_data[offset];
+        offset += 1;
+
+        for bit in 0..8 {
+            if offset >= chunk_data.len() {
+                break;
+            }
+
+            if flags & (1 << bit) == 0 {
+                output.push(chunk_data[offset]);
+                offset += 1;
+                continue;
+            }
+
+            let token = chunk_data.get(offset..offset + 2)?;
+            let token = u16::from_le_bytes([token[0], token[1]]) as usize;
+            offset += 2;
+
+            // The further into the chunk, the more of the token's bits are used for the back reference distance
+            let position = output.len() - chunk_output_start;
+            let mut length_bits = TOKEN_BITS;
+            let mut max_distance = position.checked_sub(1)?;
+
+            while max_distance >= 0x10 {
+                length_bits -= 1;
+                max_distance >>= 1;
+            }
+
+            let length = (token & ((1 << length_bits) - 1)) + MIN_MATCH;
+            let distance = (token >> length_bits) + 1;
+
+            if distance > position || position + length > CHUNK_SIZE {
+                return None;
+            }
+
+            // Back references may overlap the data they are copying
+            for _ in 0..length {
+                output.push(output[output.len() - distance]);
+            }
+        }
+    }
+
+    Some(())
+}
@@ -1,6 +1,13 @@
-use crate::extractors;
+use crate::common::crc32;
+use crate::extractors::common::{
+    Chroot, ExtractionResult, Extractor, ExtractorType, SOURCE_FILE_PLACEHOLDER,
+};
+use crate::structures::lzop::{
+    parse_lzop_block_header, parse_lzop_eof_marker, parse_lzop_file_header,
+};
+use adler32::RollingAdler32;
 
-/// Describes how to run the lzop utility to extract LZO compressed files
+/// Defines the internal extractor function for decompressing LZOP files
 ///
 /// ```
 /// use std::io::ErrorKind;
@@ -11,6 +18,7 @@ use crate::extractors;
 /// match lzop_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -22,17 +30,295 @@ use crate::extractors;
 ///     }
 /// }
 /// ```
-pub fn lzop_extractor() -> extractors::common::Extractor {
-    extractors::common::Extractor {
-        utility: extractors::common::ExtractorType::External("lzop".to_string()),
+pub fn lzop_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(lzop_decompress),
+        fallback: Some(Box::new(lzop_utility_extractor())),
+        ..Default::default()
+    }
+}
+
+/// Describes the lzop utility, which is run on LZOP files that the internal extractor fails to decompress
+fn lzop_utility_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::External("lzop".to_string()),
         extension: "lzo".to_string(),
         arguments: vec![
             "-p".to_string(), // Output to the current directory
             "-N".to_string(), // Restore original file name
             "-d".to_string(), // Perform a decompression
-            extractors::common::SOURCE_FILE_PLACEHOLDER.to_string(),
+            SOURCE_FILE_PLACEHOLDER.to_string(),
         ],
         exit_codes: vec![0],
         ..Default::default()
     }
 }
+
+/// Internal extractor for decompressing LZOP files
+pub fn lzop_decompress(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    // Output file for decompressed data
+    const OUTPUT_FILE_NAME: &str = "decompressed.bin";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(lzop_data) = file_data.get(offset..) {
+        if let Ok(lzop_header) = parse_lzop_file_header(lzop_data) {
+            let chroot = Chroot::new(output_directory);
+            let mut next_block_offset = lzop_header.header_size;
+
+            // Process blocks until the EOF marker, or an invalid block, is encountered
+            while let Some(block_data) = lzop_data.get(next_block_offset..) {
+                if let Ok(eof_marker_size) = parse_lzop_eof_marker(block_data) {
+                    result.size = Some(next_block_offset + eof_marker_size);
+                    result.success = true;
+                    break;
+                }
+
+                match parse_lzop_block_header(block_data, &lzop_header) {
+                    Err(_) => break,
+                    Ok(block_header) => {
+                        let data_start = block_header.header_size + block_header.checksum_size;
+                        let data_end = data_start + block_header.compressed_size;
+
+                        let block_decompressed_data = match block_data.get(data_start..data_end) {
+                            None => break,
+                            Some(compressed_data) => match block_header.uncompressed {
+                                true => compressed_data.to_vec(),
+                                false => {
+                                    match lzo1x_decompress(
+                                        compressed_data,
+                                        block_header.uncompressed_size,
+                                    ) {
+                                        None => break,
+                                        Some(data) => data,
+                                    }
+                                }
+                            },
+                        };
+
+                        if block_decompressed_data.len() != block_header.uncompressed_size {
+                            break;
+                        }
+
+                        // If present, the uncompressed data checksum must match the decompressed data
+                        if let Some(expected_checksum) = block_header.uncompressed_checksum {
+                            let checksum = match lzop_header.uncompressed_adler32_present {
+                                true => {
+                                    RollingAdler32::from_buffer(&block_decompressed_data).hash()
+                                }
+                                false => crc32(&block_decompressed_data),
+                            };
+
+                            if checksum != expected_checksum {
+                                break;
+                            }
+                        }
+
+                        // If extraction was requested, write each block's decompressed data to the output file
+                        if output_directory.is_some()
+                            && !chroot.append_to_file(OUTPUT_FILE_NAME, &block_decompressed_data)
+                        {
+                            break;
+                        }
+
+                        next_block_offset += data_end;
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Decompresses raw LZO1X compressed data, as used by lzop and UBIFS.
+///
+/// Returns None if the data is malformed, or would decompress to more than `max_size` bytes.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::lzop::lzo1x_decompress;
+///
+/// // 4 literal bytes, followed by the LZO1X end of stream marker
+/// let compressed_data = b"\x15ABCD\x11\x00\x00";
+///
+/// assert_eq!(lzo1x_decompress(compressed_data, 4), Some(b"ABCD".to_vec()));
+/// assert_eq!(lzo1x_decompress(compressed_data, 3), None);
+/// ```
+pub fn lzo1x_decompress(compressed_data: &[u8], max_size: usize) -> Option<Vec<u8>> {
+    // Maximum distance of a match encoded by a 2-byte M2 instruction
+    const M2_MAX_OFFSET: usize = 0x0800;
+    // Base distance of matches encoded by M4 instructions
+    const M4_BASE_OFFSET: usize = 0x4000;
+    // Match length of the end of stream marker
+    const EOS_MATCH_LENGTH: usize = 3;
+
+    let mut lzo = LzoReader {
+        data: compressed_data,
+        position: 0,
+        output: vec![],
+        max_size,
+    };
+
+    /*
+     * The state is the number of literals copied after the last match (0-3), or 4 if the last instruction
+     * was a literal run of 4 or more bytes; it changes the meaning of instructions 0-15.
+     */
+    let mut state: usize = 0;
+
+    // A first byte greater than 17 encodes an initial literal run
+    let first_byte = *compressed_data.first()? as usize;
+    if first_byte > 17 {
+        lzo.position += 1;
+        let literal_count = first_byte - 17;
+        lzo.copy_literals(literal_count)?;
+        state = std::cmp::min(literal_count, 4);
+    }
+
+    loop {
+        let instruction = lzo.read_byte()?;
+
+        let match_length: usize;
+        let match_distance: usize;
+        let next_state: usize;
+
+        if instruction < 16 {
+            if state == 0 {
+                // Literal run of 4 or more bytes
+                let literal_count = lzo.read_length(instruction, 15)? + 3;
+                lzo.copy_literals(literal_count)?;
+                state = 4;
+                continue;
+            }
+
+            next_state = instruction & 3;
+            let distance = (instruction >> 2) + (lzo.read_byte()? << 2);
+
+            if state == 4 {
+                // 3 byte match, at a distance just past the range of M2 matches
+                match_length = 3;
+                match_distance = distance + M2_MAX_OFFSET + 1;
+            } else {
+                // 2 byte match, following a short literal run
+                match_length = 2;
+                match_distance = distance + 1;
+            }
+        } else if instruction >= 64 {
+            // M2: 3-8 byte match within 2KB
+            next_state = instruction & 3;
+            match_length = (instruction >> 5) + 1;
+            match_distance = ((instruction >> 2) & 7) + (lzo.read_byte()? << 3) + 1;
+        } else if instruction >= 32 {
+            // M3: match within 16KB
+            match_length = lzo.read_length(instruction & 31, 31)? + 2;
+            let distance = lzo.read_u16()?;
+            next_state = distance & 3;
+            match_distance = (distance >> 2) + 1;
+        } else {
+            // M4: match within 48KB; a distance of zero marks the end of the stream
+            match_length = lzo.read_length(instruction & 7, 7)? + 2;
+            let distance = lzo.read_u16()?;
+            next_state = distance & 3;
+
+            let m4_distance = ((instruction & 8) << 11) + (distance >> 2);
+
+            if m4_distance == 0 {
+                // All compressed data must have been consumed
+                if match_length == EOS_MATCH_LENGTH && lzo.position == compressed_data.len() {
+                    return Some(lzo.output);
+                }
+                return None;
+            }
+
+            match_distance = m4_distance + M4_BASE_OFFSET;
+        }
+
+        lzo.copy_match(match_distance, match_length)?;
+
+        // Each match may be followed by up to 3 literals
+        lzo.copy_literals(next_state)?;
+        state = next_state;
+    }
+}
+
+/// Tracks the state of LZO1X decompression
+struct LzoReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    output: Vec<u8>,
+    max_size: usize,
+}
+
+impl LzoReader<'_> {
+    fn read_byte(&mut self) -> Option<usize> {
+        let byte = *self.data.get(self.position)?;
+        self.position += 1;
+        Some(byte as usize)
+    }
+
+    fn read_u16(&mut self) -> Option<usize> {
+        let low_byte = self.read_byte()?;
+        let high_byte = self.read_byte()?;
+        Some((high_byte << 8) | low_byte)
+    }
+
+    /// Reads an instruction length; a length of zero in the instruction is extended by the following bytes,
+    /// where each zero byte adds 255, and the first non-zero byte ends the length
+    fn read_length(
+        &mut self,
+        instruction_length: usize,
+        max_instruction_length: usize,
+    ) -> Option<usize> {
+        if instruction_length != 0 {
+            return Some(instruction_length);
+        }
+
+        let mut length = max_instruction_length;
+
+        loop {
+            match self.read_byte()? {
+                0 => length += 255,
+                byte => return Some(length + byte),
+            }
+        }
+    }
+
+    fn copy_literals(&mut self, count: usize) -> Option<()> {
+        let literals = self.data.get(self.position..self.position + count)?;
+
+        if self.output.len() + count > self.max_size {
+            return None;
+        }
+
+        self.output.extend_from_slice(literals);
+        self.position += count;
+        Some(())
+    }
+
+    /// Copies previously decompressed data to the end of the output; matches may overlap the data being copied
+    fn copy_match(&mut self, distance: usize, length: usize) -> Option<()> {
+        if distance > self.output.len() || self.output.len() + length > self.max_size {
+            return None;
+        }
+
+        let match_start = self.output.len() - distance;
+
+        if distance >= length {
+            self.output
+                .extend_from_within(match_start..match_start + length);
+        } else {
+            for i in match_start..match_start + length {
+                self.output.push(self.output[i]);
+            }
+        }
+
+        Some(())
+    }
+}
@@ -0,0 +1,501 @@
+/// LZX data is decompressed in 32KB frames; the bitstream is realigned to 16 bits at the end of each frame
+const FRAME_SIZE: usize = 32 * 1024;
+
+/// Number of main tree elements used for literal bytes
+const NUM_CHARS: usize = 256;
+/// Number of match lengths encoded directly in the main tree element
+const NUM_PRIMARY_LENGTHS: usize = 7;
+/// Number of elements in the length tree
+const NUM_SECONDARY_LENGTHS: usize = 249;
+const MIN_MATCH: usize = 2;
+
+const PRETREE_SIZE: usize = 20;
+const ALIGNED_TREE_SIZE: usize = 8;
+
+const BLOCK_TYPE_VERBATIM: usize = 1;
+const BLOCK_TYPE_ALIGNED: usize = 2;
+const BLOCK_TYPE_UNCOMPRESSED: usize = 3;
+
+/// Decompresses LZX data, as used by Microsoft CAB archives.
+///
+/// The compressed data is the concatenated data of all the CAB data blocks in a folder. Returns None if the
+/// data is malformed, or does not decompress to exactly `uncompressed_size` bytes.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::lzx::lzx_decompress;
+///
+/// // A single uncompressed block, containing 4 bytes of data
+/// let compressed_data = b"\x00\x30\x40\x00\x01\x00\x00\x00\x01\x00\x00\x00\x01\x00\x00\x00ABCD";
+///
+/// assert_eq!(lzx_decompress(compressed_data, 15, 4), Some(b"ABCD".to_vec()));
+/// assert_eq!(lzx_decompress(compressed_data, 15, 5), None);
+/// ```
+pub fn lzx_decompress(
+    compressed_data: &[u8],
+    window_bits: usize,
+    uncompressed_size: usize,
+) -> Option<Vec<u8>> {
+    // Number of position slots for each supported window size, from 15 to 21 bits
+    const POSITION_SLOTS: [usize; 7] = [30, 32, 34, 36, 38, 42, 50];
+    const MIN_WINDOW_BITS: usize = 15;
+
+    let position_slots = *POSITION_SLOTS.get(window_bits.checked_sub(MIN_WINDOW_BITS)?)?;
+    let window_size: usize = 1 << window_bits;
+    let (extra_bits, position_base) = position_tables();
+
+    let mut bits = LzxBitReader::new(compressed_data);
+    let mut output: Vec<u8> = vec![];
+
+    // Tree lengths are delta encoded against the lengths used by the previous block
+    let mut main_lengths: Vec<u8> = vec![0; NUM_CHARS + (position_slots * 8)];
+    let mut length_lengths: Vec<u8> = vec![0; NUM_SECONDARY_LENGTHS];
+
+    let mut main_tree = HuffmanTable::default();
+    let mut length_tree = HuffmanTable::default();
+    let mut aligned_tree = HuffmanTable::default();
+
+    let mut repeated_offsets: [usize; 3] = [1, 1, 1];
+    let mut block_type: usize = 0;
+    let mut block_size: usize = 0;
+    let mut block_remaining: usize = 0;
+    // Offset of the next byte of an uncompressed block in the compressed data
+    let mut uncompressed_position: usize = 0;
+
+    // The stream starts with a flag indicating if x86 CALL instructions were translated, and the translation size
+    let mut translation_size: usize = 0;
+    if bits.read(1)? == 1 {
+        translation_size = (bits.read(16)? << 16) | bits.read(16)?;
+    }
+
+    while output.len() < uncompressed_size {
+        // A match at the end of the previous frame may have extended into this frame
+        let frame_end = std::cmp::min(
+            (output.len() - (output.len() % FRAME_SIZE)) + FRAME_SIZE,
+            uncompressed_size,
+        );
+
+        while output.len() < frame_end {
+            if block_remaining == 0 {
+                // Uncompressed blocks are padded to an even size, after which the bitstream resumes
+                if block_type == BLOCK_TYPE_UNCOMPRESSED {
+                    if (block_size & 1) != 0 {
+                        uncompressed_position += 1;
+                    }
+                    bits.reset(uncompressed_position);
+                }
+
+                block_type = bits.read(3)?;
+                block_size = bits.read(24)?;
+                block_remaining = block_size;
+
+                if block_size == 0 {
+                    return None;
+                }
+
+                match block_type {
+                    BLOCK_TYPE_VERBATIM | BLOCK_TYPE_ALIGNED => {
+                        if block_type == BLOCK_TYPE_ALIGNED {
+                            let mut aligned_lengths: [u8; ALIGNED_TREE_SIZE] =
+                                [0; ALIGNED_TREE_SIZE];
+                            for length in aligned_lengths.iter_mut() {
+                                *length = bits.read(3)? as u8;
+                            }
+                            aligned_tree = HuffmanTable::new(&aligned_lengths)?;
+                        }
+
+                        read_lengths(&mut bits, &mut main_lengths[..NUM_CHARS])?;
+                        read_lengths(&mut bits, &mut main_lengths[NUM_CHARS..])?;
+                        main_tree = HuffmanTable::new(&main_lengths)?;
+
+                        if main_tree.is_empty() {
+                            return None;
+                        }
+
+                        // The length tree is empty if no matches longer than the primary lengths are used
+                        read_lengths(&mut bits, &mut length_lengths)?;
+                        length_tree = HuffmanTable::new(&length_lengths)?;
+                    }
+                    BLOCK_TYPE_UNCOMPRESSED => {
+                        uncompressed_position = bits.byte_align();
+
+                        // Uncompressed blocks store the repeated offsets to use for following blocks
+                        for repeated_offset in repeated_offsets.iter_mut() {
+                            let offset_bytes = compressed_data
+                                .get(uncompressed_position..uncompressed_position + 4)?;
+                            *repeated_offset =
+                                u32::from_le_bytes(offset_bytes.try_into().unwrap()) as usize;
+                            uncompressed_position += 4;
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+
+            if block_type == BLOCK_TYPE_UNCOMPRESSED {
+                let copy_size = std::cmp::min(block_remaining, frame_end - output.len());
+                let uncompressed_data = compressed_data
+                    .get(uncompressed_position..uncompressed_position + copy_size)?;
+
+                output.extend_from_slice(uncompressed_data);
+                uncompressed_position += copy_size;
+                block_remaining -= copy_size;
+                continue;
+            }
+
+            let main_element = main_tree.decode(&mut bits)?;
+
+            if main_element < NUM_CHARS {
+                output.push(main_element as u8);
+                block_remaining -= 1;
+                continue;
+            }
+
+            let match_header = main_element - NUM_CHARS;
+
+            // Longer matches encode the rest of the match length in the length tree
+            let mut match_length = match_header & NUM_PRIMARY_LENGTHS;
+            if match_length == NUM_PRIMARY_LENGTHS {
+                match_length += length_tree.decode(&mut bits)?;
+            }
+            match_length += MIN_MATCH;
+
+            let position_slot = match_header >> 3;
+
+            // The first three position slots re-use one of the three most recent match offsets
+            let match_offset = match position_slot {
+                0 => repeated_offsets[0],
+                1 => {
+                    repeated_offsets.swap(0, 1);
+                    repeated_offsets[0]
+                }
+                2 => {
+                    repeated_offsets.swap(0, 2);
+                    repeated_offsets[0]
+                }
+                _ => {
+                    let footer_bits = extra_bits[position_slot];
+                    let mut match_offset = position_base[position_slot] - 2;
+
+                    // Aligned offset blocks encode the low 3 bits of larger offsets with the aligned tree
+                    if block_type == BLOCK_TYPE_ALIGNED && footer_bits >= 3 {
+                        match_offset += bits.read(footer_bits - 3)? << 3;
+                        match_offset += aligned_tree.decode(&mut bits)?;
+                    } else {
+                        match_offset += bits.read(footer_bits)?;
+                    }
+
+                    repeated_offsets = [match_offset, repeated_offsets[0], repeated_offsets[1]];
+                    match_offset
+                }
+            };
+
+            // Matches may extend past the end of the frame, but not past the end of the block
+            if match_offset == 0
+                || match_offset > output.len()
+                || match_offset > window_size
+                || match_length > block_remaining
+                || output.len() + match_length > uncompressed_size
+            {
+                return None;
+            }
+
+            let match_start = output.len() - match_offset;
+
+            if match_offset >= match_length {
+                output.extend_from_within(match_start..match_start + match_length);
+            } else {
+                for i in match_start..match_start + match_length {
+                    output.push(output[i]);
+                }
+            }
+
+            block_remaining -= match_length;
+        }
+
+        bits.align();
+    }
+
+    if bits.overrun() {
+        return None;
+    }
+
+    if translation_size > 0 {
+        undo_call_translation(&mut output, translation_size);
+    }
+
+    Some(output)
+}
+
+/// Builds the number of footer bits and the base offset of each position slot
+fn position_tables() -> ([usize; 51], [usize; 51]) {
+    const MAX_FOOTER_BITS: usize = 17;
+
+    let mut extra_bits: [usize; 51] = [0; 51];
+    let mut position_base: [usize; 51] = [0; 51];
+
+    // Footer bits increase by one every two slots, starting with slot 4, up to a maximum of 17 bits
+    for (slot, footer_bits) in extra_bits.iter_mut().enumerate().skip(4) {
+        *footer_bits = std::cmp::min((slot / 2) - 1, MAX_FOOTER_BITS);
+    }
+
+    for slot in 1..position_base.len() {
+        position_base[slot] = position_base[slot - 1] + (1 << extra_bits[slot - 1]);
+    }
+
+    (extra_bits, position_base)
+}
+
+/// Reads a set of delta encoded tree lengths, which are themselves Huffman encoded with a pre-tree
+fn read_lengths(bits: &mut LzxBitReader, lengths: &mut [u8]) -> Option<()> {
+    // Lengths are stored as a delta from the previous length, modulo 17
+    const LENGTH_MODULUS: u8 = 17;
+    const SHORT_ZERO_RUN: usize = 17;
+    const LONG_ZERO_RUN: usize = 18;
+    const SAME_LENGTH_RUN: usize = 19;
+
+    let mut pretree_lengths: [u8; PRETREE_SIZE] = [0; PRETREE_SIZE];
+    for length in pretree_lengths.iter_mut() {
+        *length = bits.read(4)? as u8;
+    }
+
+    let pretree = HuffmanTable::new(&pretree_lengths)?;
+    let mut i: usize = 0;
+
+    while i < lengths.len() {
+        let (run_length, run_value) = match pretree.decode(bits)? {
+            SHORT_ZERO_RUN => (bits.read(4)? + 4, 0),
+            LONG_ZERO_RUN => (bits.read(5)? + 20, 0),
+            SAME_LENGTH_RUN => {
+                let run_length = bits.read(1)? + 4;
+                let delta = pretree.decode(bits)? as u8;
+                if delta >= LENGTH_MODULUS {
+                    return None;
+                }
+                (
+                    run_length,
+                    (lengths[i] + LENGTH_MODULUS - delta) % LENGTH_MODULUS,
+                )
+            }
+            delta => (
+                1,
+                (lengths[i] + LENGTH_MODULUS - delta as u8) % LENGTH_MODULUS,
+            ),
+        };
+
+        lengths.get_mut(i..i + run_length)?.fill(run_value);
+        i += run_length;
+    }
+
+    Some(())
+}
+
+/// Reverses the translation of x86 CALL instruction targets from relative to absolute offsets
+fn undo_call_translation(data: &mut [u8], translation_size: usize) {
+    const CALL_OPCODE: u8 = 0xE8;
+    // CALL instructions are only translated in the first 32768 frames
+    const MAX_TRANSLATED_FRAMES: usize = 32768;
+    // The last 10 bytes of each frame are never translated
+    const FRAME_TRAILER_SIZE: usize = 10;
+
+    let translation_size = translation_size as i64;
+
+    for (frame_index, frame) in data
+        .chunks_mut(FRAME_SIZE)
+        .take(MAX_TRANSLATED_FRAMES)
+        .enumerate()
+    {
+        if frame.len() <= FRAME_TRAILER_SIZE {
+            continue;
+        }
+
+        let frame_offset = frame_index * FRAME_SIZE;
+        let mut i: usize = 0;
+
+        while i < frame.len() - FRAME_TRAILER_SIZE {
+            if frame[i] != CALL_OPCODE {
+                i += 1;
+                continue;
+            }
+
+            let current_position = (frame_offset + i) as i64;
+            let absolute_offset =
+                i32::from_le_bytes(frame[i + 1..i + 5].try_into().unwrap()) as i64;
+
+            if absolute_offset >= -current_position && absolute_offset < translation_size {
+                let relative_offset = match absolute_offset >= 0 {
+                    true => absolute_offset - current_position,
+                    false => absolute_offset + translation_size,
+                };
+
+                frame[i + 1..i + 5].copy_from_slice(&(relative_offset as i32).to_le_bytes());
+            }
+
+            i += 5;
+        }
+    }
+}
+
+/// Canonical Huffman decoding table
+#[derive(Debug, Default, Clone)]
+struct HuffmanTable {
+    /// Symbol and code length for every possible value of the next `table_bits` bits
+    entries: Vec<(u16, u8)>,
+    table_bits: usize,
+}
+
+impl HuffmanTable {
+    /// Builds a decoding table from a list of code lengths; returns None if the code lengths are not valid.
+    /// Codes that are not complete are allowed, but fail to decode if an unused code is encountered.
+    fn new(lengths: &[u8]) -> Option<HuffmanTable> {
+        const MAX_CODE_LENGTH: usize = 16;
+
+        let table_bits = *lengths.iter().max()? as usize;
+
+        if table_bits > MAX_CODE_LENGTH {
+            return None;
+        }
+
+        let mut table = HuffmanTable {
+            entries: vec![(0, 0); 1 << table_bits],
+            table_bits,
+        };
+
+        // Canonical codes are assigned in order of code length, then symbol value
+        let mut next_code: usize = 0;
+
+        for code_length in 1..=table_bits {
+            for (symbol, length) in lengths.iter().enumerate() {
+                if *length as usize != code_length {
+                    continue;
+                }
+
+                let entry_count = 1 << (table_bits - code_length);
+                let first_entry = next_code << (table_bits - code_length);
+
+                // Too many codes of this length; the code is over-subscribed
+                table
+                    .entries
+                    .get_mut(first_entry..first_entry + entry_count)?
+                    .fill((symbol as u16, code_length as u8));
+
+                next_code += 1;
+            }
+
+            next_code <<= 1;
+        }
+
+        Some(table)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.table_bits == 0
+    }
+
+    fn decode(&self, bits: &mut LzxBitReader) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let (symbol, length) = self.entries[bits.peek(self.table_bits)];
+
+        if length == 0 {
+            return None;
+        }
+
+        bits.consume(length as usize);
+        Some(symbol as usize)
+    }
+}
+
+/// Reads LZX bitstreams, which are a series of little endian 16-bit words, each read most significant bit first
+struct LzxBitReader<'a> {
+    data: &'a [u8],
+    /// Offset of the next 16-bit word to load into the bit buffer
+    position: usize,
+    /// Bits loaded from the data, but not yet consumed; left aligned
+    buffer: u64,
+    bit_count: usize,
+}
+
+impl<'a> LzxBitReader<'a> {
+    fn new(data: &'a [u8]) -> LzxBitReader<'a> {
+        LzxBitReader {
+            data,
+            position: 0,
+            buffer: 0,
+            bit_count: 0,
+        }
+    }
+
+    /// Loads 16-bit words until at least `count` bits are available; reading past the end of the data produces zeros
+    fn fill(&mut self, count: usize) {
+        while self.bit_count < count {
+            let word = match self.data.get(self.position..self.position + 2) {
+                None => 0,
+                Some(word_bytes) => u16::from_le_bytes(word_bytes.try_into().unwrap()),
+            };
+
+            self.buffer |= (word as u64) << (48 - self.bit_count);
+            self.bit_count += 16;
+            self.position += 2;
+        }
+    }
+
+    fn peek(&mut self, count: usize) -> usize {
+        if count == 0 {
+            return 0;
+        }
+
+        self.fill(count);
+        (self.buffer >> (64 - count)) as usize
+    }
+
+    fn consume(&mut self, count: usize) {
+        self.buffer <<= count;
+        self.bit_count -= count;
+    }
+
+    fn read(&mut self, count: usize) -> Option<usize> {
+        let value = self.peek(count);
+        self.consume(count);
+
+        match self.overrun() {
+            true => None,
+            false => Some(value),
+        }
+    }
+
+    /// Returns true if more bits have been consumed than are available in the data
+    fn overrun(&self) -> bool {
+        (self.position * 8) - self.bit_count > self.data.len() * 8
+    }
+
+    /// Discards any remaining bits in the current 16-bit word
+    fn align(&mut self) {
+        self.consume(self.bit_count % 16);
+    }
+
+    /// Discards 1 to 16 bits to reach a 16-bit boundary, as done at the start of uncompressed blocks,
+    /// and returns the byte offset of the following data
+    fn byte_align(&mut self) -> usize {
+        if self.bit_count.is_multiple_of(16) {
+            self.fill(16);
+            self.consume(16);
+        } else {
+            self.align();
+        }
+
+        let byte_position = self.position - (self.bit_count / 8);
+        self.reset(byte_position);
+        byte_position
+    }
+
+    /// Resumes reading the bitstream at the specified byte offset
+    fn reset(&mut self, position: usize) {
+        self.position = position;
+        self.buffer = 0;
+        self.bit_count = 0;
+    }
+}
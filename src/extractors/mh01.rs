@@ -1,4 +1,5 @@
 use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::locked::LockedContent;
 use crate::structures::mh01::parse_mh01_header;
 
 /// Defines the internal extractor function for carving out MH01 firmware images
@@ -12,6 +13,7 @@ use crate::structures::mh01::parse_mh01_header;
 /// match mh01_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -81,6 +83,13 @@ pub fn extract_mh01_image(
                             mh01_header.encrypted_data_offset,
                             mh01_header.encrypted_data_size,
                         );
+
+                        result.locked.push(LockedContent::new(
+                            format!(
+                                "encrypted firmware data, carved to {ENCRYPTED_DATA_FILE_NAME}"
+                            ),
+                            format!("MH01 firmware decryption key (IV carved to {IV_FILE_NAME})"),
+                        ));
                     }
                 }
             // No extraction requested, just return success
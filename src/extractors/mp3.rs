@@ -0,0 +1,64 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::mp3::{parse_id3_header, parse_mp3_stream};
+
+/// Defines the internal extractor function for carving MP3 files
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::mp3::mp3_extractor;
+///
+/// match mp3_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn mp3_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_mp3_file),
+        do_not_recurse: true,
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for carving ID3 tagged MP3 files to disk
+pub fn extract_mp3_file(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTFILE_NAME: &str = "audio.mp3";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(id3_header) = parse_id3_header(&file_data[offset..]) {
+        // Audio frames immediately follow the ID3 tag
+        if let Some(stream_data) = file_data.get(offset + id3_header.size..) {
+            if let Ok(mp3_stream) = parse_mp3_stream(stream_data) {
+                result.size = Some(id3_header.size + mp3_stream.size);
+                result.success = true;
+
+                if output_directory.is_some() {
+                    let chroot = Chroot::new(output_directory);
+                    result.success =
+                        chroot.carve_file(OUTFILE_NAME, file_data, offset, result.size.unwrap());
+                }
+            }
+        }
+    }
+
+    result
+}
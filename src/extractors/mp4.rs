@@ -0,0 +1,60 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::mp4::parse_mp4_file;
+
+/// Defines the internal extractor function for carving ISO base media (MP4) files
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::mp4::mp4_extractor;
+///
+/// match mp4_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn mp4_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_mp4_file),
+        do_not_recurse: true,
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for carving ISO base media (MP4) files to disk
+pub fn extract_mp4_file(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTFILE_NAME: &str = "video.mp4";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(mp4_data) = file_data.get(offset..) {
+        if let Ok(mp4_file) = parse_mp4_file(mp4_data) {
+            result.size = Some(mp4_file.size);
+            result.success = true;
+
+            if output_directory.is_some() {
+                let chroot = Chroot::new(output_directory);
+                result.success = chroot.carve_file(OUTFILE_NAME, file_data, offset, mp4_file.size);
+            }
+        }
+    }
+
+    result
+}
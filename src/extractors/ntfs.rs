@@ -0,0 +1,581 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::extractors::lznt1::lznt1_decompress;
+use crate::locked::LockedContent;
+use crate::structures::ntfs::{
+    NTFS_ATTRIBUTE_DATA, NTFS_ATTRIBUTE_FILE_NAME, NTFS_ATTRIBUTE_REPARSE_POINT,
+    NTFS_FIRST_USER_RECORD, NTFS_ROOT_DIRECTORY_RECORD, NTFSAttribute, NTFSDataRun, NTFSFileRecord,
+    parse_ntfs_file_name, parse_ntfs_file_record, parse_ntfs_header, parse_ntfs_symlink,
+};
+use log::{debug, warn};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Defines the internal extractor function for extracting NTFS file systems
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::ntfs::ntfs_extractor;
+///
+/// match ntfs_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn ntfs_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_ntfs),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for NTFS file systems.
+///
+/// The file system is read-only; files are located by reading every record in the MFT, rather than by walking the
+/// directory indexes. File system metadata files, such as $MFT, and alternate data streams are not extracted.
+/// Encrypted (EFS) files can not be decrypted, and are reported as locked content.
+pub fn extract_ntfs(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    // MFT records are protected by fixups every 512 bytes, and are at most 64KB
+    const MIN_RECORD_SIZE: usize = 512;
+    const MAX_RECORD_SIZE: usize = 64 * 1024;
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(ntfs_data) = file_data.get(offset..) {
+        if let Ok(ntfs_header) = parse_ntfs_header(ntfs_data) {
+            // The reported sector count does not include the NTFS boot sector itself
+            let total_size = ntfs_header
+                .sector_size
+                .saturating_mul(ntfs_header.sector_count.saturating_add(1));
+
+            // Only the available data is used if the file system is truncated
+            let image_end = std::cmp::min(total_size, ntfs_data.len());
+
+            if ntfs_header.cluster_size == 0
+                || !ntfs_header.mft_record_size.is_power_of_two()
+                || !(MIN_RECORD_SIZE..=MAX_RECORD_SIZE).contains(&ntfs_header.mft_record_size)
+            {
+                return result;
+            }
+
+            let mut ntfs_image = NtfsImage {
+                data: &ntfs_data[..image_end],
+                cluster_size: ntfs_header.cluster_size,
+                ..Default::default()
+            };
+
+            if ntfs_image.data.len() < total_size {
+                warn!(
+                    "NTFS file system is truncated to {} of {} bytes",
+                    ntfs_image.data.len(),
+                    total_size
+                );
+            }
+
+            if ntfs_image.load_mft(ntfs_header.mft_offset, ntfs_header.mft_record_size)
+                && ntfs_image.has_user_files()
+            {
+                if output_directory.is_none() {
+                    result.success = true;
+                } else {
+                    let chroot = Chroot::new(output_directory);
+                    let mut visited_directories = HashSet::from([NTFS_ROOT_DIRECTORY_RECORD]);
+                    let mut encrypted_files: Vec<String> = vec![];
+
+                    let file_count = ntfs_image.extract_directory(
+                        &chroot,
+                        NTFS_ROOT_DIRECTORY_RECORD,
+                        "",
+                        &mut visited_directories,
+                        &mut encrypted_files,
+                    );
+
+                    if !encrypted_files.is_empty() {
+                        result.locked.push(LockedContent::encrypted_entries(
+                            "NTFS",
+                            &encrypted_files,
+                            "EFS private key of the file owner",
+                        ));
+                    }
+
+                    result.success = file_count > 0;
+                }
+
+                if result.success {
+                    result.size = Some(ntfs_image.data.len());
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// A file or directory name in an NTFS directory
+#[derive(Debug, Default, Clone)]
+struct NtfsFile {
+    name: String,
+    record: usize,
+}
+
+/// Provides read-only access to the files of an NTFS file system
+#[derive(Debug, Default)]
+struct NtfsImage<'a> {
+    data: &'a [u8],
+    cluster_size: usize,
+    /// In use base records, keyed by record number; the attributes of extension records are merged into their base records
+    records: HashMap<usize, NTFSFileRecord>,
+    /// The files in each directory, keyed by the directory's record number and sorted by name
+    directories: HashMap<usize, BTreeMap<String, NtfsFile>>,
+}
+
+impl NtfsImage<'_> {
+    /// Reads all records from the MFT, and builds the directory tree from their file names; returns false if the root
+    /// directory could not be found
+    fn load_mft(&mut self, mft_offset: usize, record_size: usize) -> bool {
+        // The first MFT record describes the MFT itself
+        let mft_record = match self
+            .data
+            .get(mft_offset..mft_offset.saturating_add(record_size))
+            .map(parse_ntfs_file_record)
+        {
+            Some(Ok(mft_record)) => mft_record,
+            _ => return false,
+        };
+
+        let mut mft_data_attributes = data_attributes(&mft_record.attributes);
+
+        /*
+         * If the MFT is very fragmented, the rest of its data runs are stored in extension records, which are
+         * themselves stored in the MFT; once all records have been read, re-read the MFT if any were found.
+         */
+        loop {
+            self.records = self.read_records(&mft_data_attributes, record_size);
+
+            match self.records.get(&0) {
+                Some(mft_record) => {
+                    let all_mft_data_attributes = data_attributes(&mft_record.attributes);
+
+                    if all_mft_data_attributes.len() <= mft_data_attributes.len() {
+                        break;
+                    }
+
+                    mft_data_attributes = all_mft_data_attributes;
+                }
+                None => break,
+            }
+        }
+
+        match self.records.get(&NTFS_ROOT_DIRECTORY_RECORD) {
+            Some(root_record) if root_record.directory => (),
+            _ => return false,
+        }
+
+        self.build_directories();
+
+        true
+    }
+
+    /// Reads and parses all in use records from the MFT
+    fn read_records(
+        &self,
+        mft_data_attributes: &[NTFSAttribute],
+        record_size: usize,
+    ) -> HashMap<usize, NTFSFileRecord> {
+        let mut records: HashMap<usize, NTFSFileRecord> = HashMap::new();
+        let mut extension_records: Vec<NTFSFileRecord> = vec![];
+
+        let mft_data = self.attribute_data(mft_data_attributes);
+
+        for (record_number, record_data) in mft_data.chunks_exact(record_size).enumerate() {
+            match parse_ntfs_file_record(record_data) {
+                Err(_) => debug!("Invalid NTFS MFT record #{record_number}"),
+                Ok(record) => {
+                    if !record.in_use {
+                        continue;
+                    }
+
+                    match record.base_record {
+                        None => {
+                            records.insert(record_number, record);
+                        }
+                        Some(_) => extension_records.push(record),
+                    }
+                }
+            }
+        }
+
+        for extension_record in extension_records {
+            if let Some(base_record_number) = extension_record.base_record {
+                if let Some(base_record) = records.get_mut(&base_record_number) {
+                    base_record.attributes.extend(extension_record.attributes);
+                }
+            }
+        }
+
+        records
+    }
+
+    /// Adds each record's file names to its parent directory
+    fn build_directories(&mut self) {
+        for (record_number, record) in self.records.iter() {
+            for attribute in &record.attributes {
+                if attribute.attribute_type != NTFS_ATTRIBUTE_FILE_NAME {
+                    continue;
+                }
+
+                let file_name = match parse_ntfs_file_name(&attribute.value) {
+                    Err(_) => continue,
+                    Ok(file_name) => file_name,
+                };
+
+                // DOS names are duplicates of a file's long name, and the root directory is its own parent
+                if file_name.dos_name || *record_number == file_name.parent_record {
+                    continue;
+                }
+
+                // Names linking to a parent record that has since been re-used are stale
+                match self.records.get(&file_name.parent_record) {
+                    Some(parent)
+                        if parent.directory && parent.sequence == file_name.parent_sequence =>
+                    {
+                        self.directories
+                            .entry(file_name.parent_record)
+                            .or_default()
+                            .insert(
+                                file_name.name.clone(),
+                                NtfsFile {
+                                    name: file_name.name,
+                                    record: *record_number,
+                                },
+                            );
+                    }
+                    _ => debug!(
+                        "NTFS file {} in record #{} has no valid parent directory",
+                        file_name.name, record_number
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Returns true if the root directory contains anything other than file system metadata files
+    fn has_user_files(&self) -> bool {
+        match self.directories.get(&NTFS_ROOT_DIRECTORY_RECORD) {
+            None => false,
+            Some(files) => files
+                .values()
+                .any(|file| file.record >= NTFS_FIRST_USER_RECORD),
+        }
+    }
+
+    /// Reads the data of an attribute, which may be split across multiple extents; data beyond the end of the
+    /// available data is not returned
+    fn attribute_data(&self, extents: &[NTFSAttribute]) -> Vec<u8> {
+        let first_extent = match extents.first() {
+            None => return vec![],
+            Some(first_extent) => first_extent,
+        };
+
+        if !first_extent.non_resident {
+            return first_extent.value.clone();
+        }
+
+        // Sparse files may claim to be larger than the file system; they are limited to the size of the file system
+        let data_size = std::cmp::min(first_extent.data_size, self.data.len());
+
+        let data_runs: Vec<NTFSDataRun> = extents
+            .iter()
+            .flat_map(|extent| extent.data_runs.iter().cloned())
+            .collect();
+
+        let mut data = match first_extent.compressed && first_extent.compression_unit > 0 {
+            true => self.read_compressed_runs(&data_runs, first_extent.compression_unit, data_size),
+            false => self.read_runs(&data_runs, data_size),
+        };
+
+        data.truncate(data_size);
+
+        // Data beyond the initialized size reads as zeros
+        if data.len() > first_extent.initialized_size {
+            data[first_extent.initialized_size..].fill(0);
+        }
+
+        data
+    }
+
+    /// Returns the data of the specified cluster, if available
+    fn read_cluster(&self, lcn: usize) -> Option<&[u8]> {
+        let cluster_offset = lcn.checked_mul(self.cluster_size)?;
+        self.data
+            .get(cluster_offset..cluster_offset.checked_add(self.cluster_size)?)
+    }
+
+    /// Reads up to `size` bytes from a list of uncompressed data runs
+    fn read_runs(&self, data_runs: &[NTFSDataRun], size: usize) -> Vec<u8> {
+        let mut data: Vec<u8> = vec![];
+
+        for data_run in data_runs {
+            if data.len() >= size {
+                break;
+            }
+
+            let run_size = std::cmp::min(
+                data_run.cluster_count.saturating_mul(self.cluster_size),
+                size - data.len(),
+            );
+
+            match data_run.lcn {
+                None => data.resize(data.len() + run_size, 0),
+                Some(lcn) => {
+                    let run_data = lcn
+                        .checked_mul(self.cluster_size)
+                        .and_then(|run_offset| self.data.get(run_offset..))
+                        .unwrap_or_default();
+
+                    let available_size = std::cmp::min(run_size, run_data.len());
+                    data.extend_from_slice(&run_data[..available_size]);
+
+                    // A partial run is the end of the available data
+                    if available_size < run_size {
+                        break;
+                    }
+                }
+            }
+        }
+
+        data
+    }
+
+    /// Reads up to `size` bytes from a list of data runs that are compressed in units of 2^`compression_unit` clusters
+    fn read_compressed_runs(
+        &self,
+        data_runs: &[NTFSDataRun],
+        compression_unit: usize,
+        size: usize,
+    ) -> Vec<u8> {
+        const MAX_COMPRESSION_UNIT: usize = 16;
+
+        if compression_unit > MAX_COMPRESSION_UNIT {
+            return vec![];
+        }
+
+        let unit_clusters: usize = 1 << compression_unit;
+        let unit_size = unit_clusters * self.cluster_size;
+
+        // Map each virtual cluster in the file to its logical cluster, or None if it is sparse
+        let max_clusters = size.div_ceil(unit_size) * unit_clusters;
+        let mut clusters: Vec<Option<usize>> = vec![];
+
+        for data_run in data_runs {
+            let cluster_count =
+                std::cmp::min(data_run.cluster_count, max_clusters - clusters.len());
+
+            clusters.extend((0..cluster_count).map(|i| data_run.lcn.map(|lcn| lcn + i)));
+        }
+
+        let mut data: Vec<u8> = vec![];
+
+        /*
+         * Units whose clusters are all allocated are stored uncompressed, units whose clusters are all sparse read as
+         * zeros, and the allocated clusters of any other unit contain its compressed data, followed by sparse clusters.
+         */
+        for unit in clusters.chunks(unit_clusters) {
+            let allocated_clusters: Vec<usize> = unit.iter().flatten().copied().collect();
+
+            let mut unit_data: Vec<u8> = vec![];
+
+            for lcn in &allocated_clusters {
+                match self.read_cluster(*lcn) {
+                    None => break,
+                    Some(cluster_data) => unit_data.extend_from_slice(cluster_data),
+                }
+            }
+
+            if allocated_clusters.is_empty() {
+                unit_data.resize(unit_size, 0);
+            } else if allocated_clusters.len() < unit.len() {
+                match lznt1_decompress(&unit_data, unit_size) {
+                    None => {
+                        warn!("Failed to decompress NTFS compression unit");
+                        break;
+                    }
+                    Some(decompressed_data) => unit_data = decompressed_data,
+                }
+            } else if unit_data.len() < unit_size {
+                // The rest of the unit's data is not available
+                data.extend_from_slice(&unit_data);
+                break;
+            }
+
+            data.extend_from_slice(&unit_data);
+        }
+
+        data
+    }
+
+    /// Extracts the files of a directory, and all of its sub-directories; returns the number of extracted files
+    fn extract_directory(
+        &self,
+        chroot: &Chroot,
+        directory_record: usize,
+        parent_directory: &str,
+        visited_directories: &mut HashSet<usize>,
+        encrypted_files: &mut Vec<String>,
+    ) -> usize {
+        let mut file_count: usize = 0;
+
+        let files = match self.directories.get(&directory_record) {
+            None => return file_count,
+            Some(files) => files,
+        };
+
+        for file in files.values() {
+            // File system metadata files are not extracted, nor are the contents of the $Extend metadata directory
+            if file.record < NTFS_FIRST_USER_RECORD {
+                continue;
+            }
+
+            let record = &self.records[&file.record];
+            let file_path = chroot.safe_path_join(parent_directory, &file.name);
+
+            // Symlinks and junctions are reparse points; other types of reparse points are extracted as regular files
+            if let Some(reparse_point) = record
+                .attributes
+                .iter()
+                .find(|attribute| attribute.attribute_type == NTFS_ATTRIBUTE_REPARSE_POINT)
+            {
+                if let Ok(symlink) =
+                    parse_ntfs_symlink(&self.attribute_data(std::slice::from_ref(reparse_point)))
+                {
+                    if chroot.create_symlink(
+                        &file_path,
+                        symlink_target(&symlink.target, symlink.relative),
+                    ) {
+                        file_count += 1;
+                    } else {
+                        warn!("Failed to extract NTFS symlink {file_path}");
+                    }
+
+                    continue;
+                }
+            }
+
+            if record.directory {
+                // Directories linked more than once could cause infinite recursion
+                if !visited_directories.insert(file.record) {
+                    warn!("NTFS directory {file_path} has already been extracted");
+                    continue;
+                }
+
+                if !chroot.create_directory(&file_path) {
+                    warn!("Failed to extract NTFS directory {file_path}");
+                    continue;
+                }
+
+                file_count += 1;
+                file_count += self.extract_directory(
+                    chroot,
+                    file.record,
+                    &file_path,
+                    visited_directories,
+                    encrypted_files,
+                );
+            } else {
+                // Only the default, unnamed, data stream is extracted
+                let data_extents = data_attributes(&record.attributes);
+
+                if data_extents.iter().any(|extent| extent.encrypted) {
+                    warn!("NTFS file {file_path} is encrypted");
+
+                    // Report the file's path within the file system, rather than its path on disk
+                    encrypted_files.push(
+                        file_path
+                            .strip_prefix(&chroot.chroot_directory)
+                            .unwrap_or(&file_path)
+                            .trim_start_matches(std::path::MAIN_SEPARATOR)
+                            .to_string(),
+                    );
+                    continue;
+                }
+
+                let file_data = self.attribute_data(&data_extents);
+
+                if let Some(first_extent) = data_extents.first() {
+                    if file_data.len() < first_extent.data_size {
+                        warn!(
+                            "Only {} of {} bytes of NTFS file {} are available",
+                            file_data.len(),
+                            first_extent.data_size,
+                            file_path
+                        );
+                    }
+                }
+
+                if chroot.create_file(&file_path, &file_data) {
+                    file_count += 1;
+                } else {
+                    warn!("Failed to extract NTFS file {file_path}");
+                }
+            }
+        }
+
+        file_count
+    }
+}
+
+/// Returns the extents of a record's unnamed $DATA attribute, ordered by their first virtual cluster number
+fn data_attributes(attributes: &[NTFSAttribute]) -> Vec<NTFSAttribute> {
+    let mut data_extents: Vec<NTFSAttribute> = attributes
+        .iter()
+        .filter(|attribute| {
+            attribute.attribute_type == NTFS_ATTRIBUTE_DATA && attribute.name.is_empty()
+        })
+        .cloned()
+        .collect();
+
+    data_extents.sort_by_key(|extent| extent.start_vcn);
+
+    data_extents
+}
+
+/// Converts a Windows symlink target to a path within the extracted file system; absolute targets, including any
+/// NT namespace prefix and drive letter, are made relative to the root of the file system
+fn symlink_target(target: &str, relative: bool) -> String {
+    const NT_NAMESPACE_PREFIX: &str = "\\??\\";
+
+    let mut target = target.strip_prefix(NT_NAMESPACE_PREFIX).unwrap_or(target);
+
+    if !relative {
+        let mut characters = target.chars();
+
+        if let (Some(drive_letter), Some(':')) = (characters.next(), characters.next()) {
+            if drive_letter.is_ascii_alphabetic() {
+                target = &target[2..];
+            }
+        }
+    }
+
+    let target = target.replace('\\', "/");
+
+    match relative || target.starts_with('/') {
+        true => target,
+        false => format!("/{target}"),
+    }
+}
@@ -0,0 +1,198 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::nvram::{
+    ConfigEntry, parse_broadcom_nvram_header, parse_key_value_block, parse_routerboot_config,
+    parse_uboot_environment,
+};
+use log::error;
+
+/// Defines the internal extractor function for Broadcom NVRAM blocks
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::nvram::broadcom_nvram_extractor;
+///
+/// match broadcom_nvram_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn broadcom_nvram_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_broadcom_nvram),
+        ..Default::default()
+    }
+}
+
+/// Defines the internal extractor function for U-Boot environments
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::nvram::uboot_env_extractor;
+///
+/// match uboot_env_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn uboot_env_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_uboot_env),
+        ..Default::default()
+    }
+}
+
+/// Defines the internal extractor function for MikroTik RouterBOOT configuration TLVs
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::nvram::routerboot_config_extractor;
+///
+/// match routerboot_config_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn routerboot_config_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_routerboot_config),
+        ..Default::default()
+    }
+}
+
+/// Validates and extracts the key/value pairs from a Broadcom NVRAM block
+pub fn extract_broadcom_nvram(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTFILE_NAME: &str = "nvram.json";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(nvram_data) = file_data.get(offset..) {
+        if let Ok(nvram_header) = parse_broadcom_nvram_header(nvram_data) {
+            if let Some(vars_data) =
+                nvram_data.get(nvram_header.header_size..nvram_header.total_size)
+            {
+                if let Ok(vars) = parse_key_value_block(vars_data) {
+                    result.size = Some(nvram_header.total_size);
+                    result.success =
+                        write_config_entries(output_directory, OUTFILE_NAME, &vars.entries);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Validates and extracts the key/value pairs from a U-Boot environment.
+/// The provided offset must be the start of the environment's CRC header.
+pub fn extract_uboot_env(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTFILE_NAME: &str = "uboot_env.json";
+
+    // The CRC header is 4 bytes, plus a 1 byte flags field for redundant environments
+    const HEADER_SIZES: [usize; 2] = [4, 5];
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    for header_size in HEADER_SIZES {
+        if let Ok(uboot_env) = parse_uboot_environment(file_data, offset + header_size) {
+            if uboot_env.offset == offset {
+                result.size = Some(uboot_env.size);
+                result.success =
+                    write_config_entries(output_directory, OUTFILE_NAME, &uboot_env.entries);
+                break;
+            }
+        }
+    }
+
+    result
+}
+
+/// Validates and extracts the key/value pairs from a MikroTik RouterBOOT configuration TLV block
+pub fn extract_routerboot_config(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTFILE_NAME: &str = "routerboot.json";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(config_data) = file_data.get(offset..) {
+        if let Ok(config) = parse_routerboot_config(config_data) {
+            result.size = Some(config.size);
+            result.success = write_config_entries(output_directory, OUTFILE_NAME, &config.entries);
+        }
+    }
+
+    result
+}
+
+/// Writes parsed configuration entries to disk as JSON; does nothing and returns true if no output directory was specified
+fn write_config_entries(
+    output_directory: Option<&str>,
+    file_name: &str,
+    entries: &[ConfigEntry],
+) -> bool {
+    if output_directory.is_none() {
+        return true;
+    }
+
+    let chroot = Chroot::new(output_directory);
+
+    match serde_json::to_string_pretty(entries) {
+        Err(e) => {
+            error!("Failed to convert configuration entries to JSON: {e}");
+            false
+        }
+        Ok(json) => chroot.create_file(file_name, json.as_bytes()),
+    }
+}
@@ -0,0 +1,63 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::openwrt::parse_sysupgrade_tar;
+
+/// Defines the internal extractor for OpenWrt sysupgrade images
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::openwrt::sysupgrade_extractor;
+///
+/// match sysupgrade_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn sysupgrade_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_sysupgrade_images),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for the kernel, rootfs and control files of an OpenWrt sysupgrade tar archive
+pub fn extract_sysupgrade_images(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(sysupgrade_image) = parse_sysupgrade_tar(&file_data[offset..]) {
+        result.size = Some(sysupgrade_image.size);
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+
+            for entry in &sysupgrade_image.entries {
+                result.success =
+                    chroot.carve_file(&entry.name, file_data, offset + entry.offset, entry.size);
+
+                if !result.success {
+                    break;
+                }
+            }
+        }
+    }
+
+    result
+}
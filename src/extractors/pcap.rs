@@ -1,6 +1,5 @@
-use crate::common::is_offset_safe;
 use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
-use crate::structures::pcap::{parse_pcapng_block, parse_pcapng_section_block};
+use crate::structures::pcap::{parse_pcap_capture, parse_pcapng_capture};
 
 /// Defines the internal extractor function for extracting pcap-ng files
 ///
@@ -13,6 +12,7 @@ use crate::structures::pcap::{parse_pcapng_block, parse_pcapng_section_block};
 /// match pcapng_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -41,56 +41,77 @@ pub fn pcapng_carver(
     // Output file name
     const OUTPUT_FILE_NAME: &str = "capture.pcapng";
 
-    // Pcap-NG files must have at least two blocks: a section header block and an interface description block
-    const MIN_BLOCK_COUNT: usize = 2;
-
     // Return value
     let mut result = ExtractionResult {
         ..Default::default()
     };
 
-    // All pcap-ng files start with a section header; parse it
-    if let Ok(section_header) = parse_pcapng_section_block(&file_data[offset..]) {
-        let mut block_count: usize = 1;
-        let available_data = file_data.len() - offset;
-        let mut next_offset = offset + section_header.block_size;
-        let mut previous_offset = None;
+    // Walk all the blocks in the pcap-ng file to determine its size
+    if let Ok(capture) = parse_pcapng_capture(&file_data[offset..]) {
+        // Everything looks OK
+        result.size = Some(capture.size);
+        result.success = true;
 
-        // Loop through all blocks in the pcap-ng file
-        while is_offset_safe(available_data, next_offset, previous_offset) {
-            match file_data.get(next_offset..) {
-                None => {
-                    break;
-                }
-                Some(block_data) => {
-                    // Parse the next block header
-                    match parse_pcapng_block(block_data, &section_header.endianness) {
-                        Err(_) => {
-                            break;
-                        }
-                        Ok(block_header) => {
-                            // This block looks valid, go to the next one
-                            block_count += 1;
-                            previous_offset = Some(next_offset);
-                            next_offset += block_header.block_size;
-                        }
-                    }
-                }
-            }
+        // Do extraction if requested
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+            result.success = chroot.carve_file(OUTPUT_FILE_NAME, file_data, offset, capture.size);
         }
+    }
+
+    result
+}
+
+/// Defines the internal extractor function for extracting libpcap files
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::pcap::pcap_extractor;
+///
+/// match pcap_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn pcap_extractor() -> Extractor {
+    Extractor {
+        do_not_recurse: true,
+        utility: ExtractorType::Internal(pcap_carver),
+        ..Default::default()
+    }
+}
+
+/// Carves a libpcap file to disk
+pub fn pcap_carver(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTPUT_FILE_NAME: &str = "capture.pcap";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
 
-        // Must have processed the minimum number of blocks
-        if block_count >= MIN_BLOCK_COUNT {
-            // Everything looks OK
-            result.size = Some(next_offset - offset);
-            result.success = true;
+    if let Ok(capture) = parse_pcap_capture(&file_data[offset..]) {
+        result.size = Some(capture.size);
+        result.success = true;
 
-            // Do extraction if requested
-            if output_directory.is_some() {
-                let chroot = Chroot::new(output_directory);
-                result.success =
-                    chroot.carve_file(OUTPUT_FILE_NAME, file_data, offset, result.size.unwrap());
-            }
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+            result.success = chroot.carve_file(OUTPUT_FILE_NAME, file_data, offset, capture.size);
         }
     }
 
@@ -0,0 +1,160 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::qualcomm::{
+    MBNHeader, find_certificate_chain, parse_mbn_header, parse_qualcomm_elf, parse_sbl_header,
+};
+
+/// Defines the internal extractor for Qualcomm MBN and SBL images
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::qualcomm::mbn_extractor;
+///
+/// match mbn_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn mbn_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_mbn),
+        ..Default::default()
+    }
+}
+
+/// Defines the internal extractor for Qualcomm signed ELF images
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::qualcomm::qualcomm_elf_extractor;
+///
+/// match qualcomm_elf_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn qualcomm_elf_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_qualcomm_elf),
+        ..Default::default()
+    }
+}
+
+/// Parses either a legacy MBN header or an SBL header
+pub fn parse_mbn_or_sbl_header(header_data: &[u8]) -> Option<MBNHeader> {
+    parse_mbn_header(header_data)
+        .or_else(|_| parse_sbl_header(header_data))
+        .ok()
+}
+
+/// Internal extractor for the code and certificate chain of Qualcomm MBN and SBL images
+pub fn extract_mbn(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(mbn_header) = parse_mbn_or_sbl_header(&file_data[offset..]) {
+        if let Some(image_data) = file_data.get(offset..offset + mbn_header.total_size()) {
+            result.size = Some(mbn_header.total_size());
+            result.success = true;
+
+            if output_directory.is_some() {
+                let chroot = Chroot::new(output_directory);
+
+                result.success =
+                    chroot.carve_file(
+                        "code.bin",
+                        image_data,
+                        mbn_header.header_size,
+                        mbn_header.code_size,
+                    ) && carve_certificates(&chroot, &image_data[mbn_header.cert_chain_offset()..]);
+            }
+        }
+    }
+
+    result
+}
+
+/// Internal extractor for the code segments and certificate chain of Qualcomm signed ELF images
+pub fn extract_qualcomm_elf(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(image) = parse_qualcomm_elf(&file_data[offset..]) {
+        result.size = Some(image.size);
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+            let image_data = &file_data[offset..offset + image.size];
+
+            for (i, segment) in image.code_segments.iter().enumerate() {
+                result.success = chroot.carve_file(
+                    format!("segment_{i}.bin"),
+                    image_data,
+                    segment.offset,
+                    segment.file_size,
+                );
+
+                if !result.success {
+                    return result;
+                }
+            }
+
+            let hash_segment_data = &image_data[image.hash_segment.offset
+                ..image.hash_segment.offset + image.hash_segment.file_size];
+
+            result.success = carve_certificates(&chroot, hash_segment_data);
+        }
+    }
+
+    result
+}
+
+/// Carves each certificate in the first certificate chain found in the data to a DER file
+fn carve_certificates(chroot: &Chroot, data: &[u8]) -> bool {
+    for (i, certificate) in find_certificate_chain(data).iter().enumerate() {
+        if !chroot.carve_file(
+            format!("cert_{i}.der"),
+            data,
+            certificate.offset,
+            certificate.size,
+        ) {
+            return false;
+        }
+    }
+
+    true
+}
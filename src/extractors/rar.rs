@@ -1,4 +1,8 @@
+use crate::common::crc32;
 use crate::extractors;
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::rar::{RarFileEntry, parse_rar_archive};
+use std::path::Path;
 
 /// Describes how to run the unrar utility to extract RAR archives
 ///
@@ -11,6 +15,7 @@ use crate::extractors;
 /// match rar_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -36,3 +41,110 @@ pub fn rar_extractor() -> extractors::common::Extractor {
         ..Default::default()
     }
 }
+
+/// Defines the internal extractor function for RAR archives whose entries are all stored without compression
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::rar::rar_store_extractor;
+///
+/// match rar_store_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn rar_store_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_rar_stored),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for unencrypted, non-solid RARv4 and RARv5 archives that contain only stored entries.
+///
+/// Archives with compressed, encrypted or split entries are not supported, and should be extracted with unrar.
+pub fn extract_rar_stored(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(rar_data) = file_data.get(offset..) {
+        if let Ok(archive) = parse_rar_archive(rar_data) {
+            if archive.is_stored() {
+                let chroot = Chroot::new(output_directory);
+
+                for entry in &archive.entries {
+                    let entry_data = match entry
+                        .data_offset
+                        .checked_add(entry.packed_size)
+                        .and_then(|data_end| rar_data.get(entry.data_offset..data_end))
+                    {
+                        None => return result,
+                        Some(entry_data) => entry_data,
+                    };
+
+                    if let Some(expected_crc) = entry.crc {
+                        if crc32(entry_data) != expected_crc {
+                            return result;
+                        }
+                    }
+
+                    if output_directory.is_some() && !extract_entry(&chroot, entry, entry_data) {
+                        return result;
+                    }
+                }
+
+                result.size = Some(archive.size);
+                result.success = true;
+            }
+        }
+    }
+
+    result
+}
+
+/// Writes a single stored entry to disk
+fn extract_entry(chroot: &Chroot, entry: &RarFileEntry, entry_data: &[u8]) -> bool {
+    // Archives created on Windows use backslashes as the path separator
+    let entry_path = entry.name.replace('\\', "/");
+    let file_path = chroot.safe_path_join("", &entry_path);
+
+    // Entries are not guaranteed to be preceded by their parent directory entries
+    if let Some(parent_directory) = Path::new(&entry_path).parent() {
+        if !parent_directory.as_os_str().is_empty() {
+            chroot.create_directory(parent_directory.display().to_string());
+        }
+    }
+
+    if entry.directory {
+        return chroot.create_directory(&file_path);
+    }
+
+    if entry.symlink {
+        // RARv4 archives store the symlink target as the entry data
+        let link_target = match &entry.link_target {
+            Some(link_target) => link_target.clone(),
+            None => String::from_utf8_lossy(entry_data).to_string(),
+        };
+
+        return chroot.create_symlink(&file_path, link_target);
+    }
+
+    chroot.create_file(&file_path, entry_data)
+}
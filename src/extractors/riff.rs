@@ -12,6 +12,7 @@ use crate::structures::riff::parse_riff_header;
 /// match riff_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -38,8 +39,6 @@ pub fn extract_riff_image(
     output_directory: Option<&str>,
 ) -> ExtractionResult {
     const OUTFILE_NAME: &str = "image.riff";
-    const WAV_OUTFILE_NAME: &str = "video.wav";
-    const WAV_TYPE: &str = "WAVE";
 
     let mut result = ExtractionResult {
         ..Default::default()
@@ -52,10 +51,12 @@ pub fn extract_riff_image(
         if output_directory.is_some() {
             let chroot = Chroot::new(output_directory);
 
-            let file_path: String = if riff_header.chunk_type == WAV_TYPE {
-                WAV_OUTFILE_NAME.to_string()
-            } else {
-                OUTFILE_NAME.to_string()
+            // Use a file name appropriate for the type of media contained in the RIFF file
+            let file_path: String = match riff_header.chunk_type.as_str() {
+                "WAVE" => "audio.wav".to_string(),
+                "AVI" => "video.avi".to_string(),
+                "WEBP" => "image.webp".to_string(),
+                _ => OUTFILE_NAME.to_string(),
             };
 
             result.success = chroot.carve_file(file_path, file_data, offset, result.size.unwrap());
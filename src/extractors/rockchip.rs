@@ -0,0 +1,267 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::rockchip::{parse_rkaf_header, parse_rkboot_header, parse_rkfw_header};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Defines the internal extractor for Rockchip RKFW firmware
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::rockchip::rkfw_extractor;
+///
+/// match rkfw_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn rkfw_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_rkfw),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for the boot loader and RKAF firmware package of Rockchip RKFW firmware
+pub fn extract_rkfw(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const LOADER_FILE_NAME: &str = "loader.bin";
+    const IMAGE_FILE_NAME: &str = "firmware.img";
+    const MD5_SIZE: usize = 32;
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(rkfw_header) = parse_rkfw_header(&file_data[offset..]) {
+        let loader_start = offset + rkfw_header.loader_offset;
+        let image_start = offset + rkfw_header.image_offset;
+
+        // The boot loader and firmware package must be present, and have valid headers
+        if let (Some(loader_data), Some(image_data)) = (
+            file_data.get(loader_start..loader_start + rkfw_header.loader_size),
+            file_data.get(image_start..image_start + rkfw_header.image_size),
+        ) {
+            if parse_rkboot_header(loader_data).is_ok() && parse_rkaf_header(image_data).is_ok() {
+                let mut total_size = rkfw_header.total_size();
+
+                // The firmware is usually followed by the ASCII MD5 hash of the firmware
+                if file_data
+                    .get(offset + total_size..offset + total_size + MD5_SIZE)
+                    .is_some_and(|md5| md5.iter().all(|b| b.is_ascii_hexdigit()))
+                {
+                    total_size += MD5_SIZE;
+                }
+
+                result.size = Some(total_size);
+                result.success = true;
+
+                if output_directory.is_some() {
+                    let chroot = Chroot::new(output_directory);
+
+                    result.success = chroot.carve_file(
+                        LOADER_FILE_NAME,
+                        file_data,
+                        loader_start,
+                        rkfw_header.loader_size,
+                    ) && chroot.carve_file(
+                        IMAGE_FILE_NAME,
+                        file_data,
+                        image_start,
+                        rkfw_header.image_size,
+                    );
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Defines the internal extractor for Rockchip boot loaders
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::rockchip::rkboot_extractor;
+///
+/// match rkboot_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn rkboot_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_rkboot),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for the DRAM initialization, USB download and boot loader entries of Rockchip boot loaders
+pub fn extract_rkboot(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(boot_header) = parse_rkboot_header(&file_data[offset..]) {
+        // All entries must be present
+        if file_data.len() - offset >= boot_header.total_size {
+            result.size = Some(boot_header.total_size);
+            result.success = true;
+
+            if output_directory.is_some() {
+                let chroot = Chroot::new(output_directory);
+                let mut file_names: HashSet<String> = HashSet::new();
+
+                for (i, entry) in boot_header.entries.iter().enumerate() {
+                    // The same file is often used for multiple entries; entry names may also be empty
+                    let mut file_name = format!("{}.bin", safe_file_name(&entry.name));
+
+                    if entry.name.is_empty() || file_names.contains(&file_name) {
+                        file_name = format!("entry_{i}.bin");
+                    }
+
+                    result.success =
+                        chroot.carve_file(&file_name, file_data, offset + entry.offset, entry.size);
+
+                    if !result.success {
+                        break;
+                    }
+
+                    file_names.insert(file_name);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Defines the internal extractor for Rockchip RKAF firmware packages
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::rockchip::rkaf_extractor;
+///
+/// match rkaf_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn rkaf_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_rkaf),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for the partition images in Rockchip RKAF firmware packages
+pub fn extract_rkaf(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    // Partition file names that do not refer to a partition image
+    const PSEUDO_FILE_NAMES: [&str; 2] = ["SELF", "RESERVED"];
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(rkaf_header) = parse_rkaf_header(&file_data[offset..]) {
+        // The entire package must be present
+        if file_data.len() - offset >= rkaf_header.total_size {
+            result.size = Some(rkaf_header.total_size);
+            result.success = true;
+
+            if output_directory.is_some() {
+                let chroot = Chroot::new(output_directory);
+                let mut file_names: HashSet<String> = HashSet::new();
+
+                for (i, partition) in rkaf_header.partitions.iter().enumerate() {
+                    if partition.size == 0
+                        || PSEUDO_FILE_NAMES.contains(&partition.file_name.as_str())
+                    {
+                        continue;
+                    }
+
+                    // Partition images are named after their source file, e.g., "Image/boot.img" is carved to "boot.img"
+                    let mut file_name = Path::new(&partition.file_name)
+                        .file_name()
+                        .map(|file_name| safe_file_name(&file_name.to_string_lossy()))
+                        .unwrap_or_default();
+
+                    if file_name.is_empty() || file_names.contains(&file_name) {
+                        file_name = format!("partition_{i}.img");
+                    }
+
+                    result.success = chroot.carve_file(
+                        &file_name,
+                        file_data,
+                        offset + partition.offset,
+                        partition.size,
+                    );
+
+                    if !result.success {
+                        break;
+                    }
+
+                    file_names.insert(file_name);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Replaces characters that are not safe for use in file names
+fn safe_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.is_ascii_alphanumeric() || "._-".contains(c) {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
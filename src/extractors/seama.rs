@@ -0,0 +1,77 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::seama::parse_seama_header;
+
+/// Defines the internal extractor for SEAMA firmware images
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::seama::seama_extractor;
+///
+/// match seama_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn seama_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_seama_payload),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for the payload of a SEAMA firmware image; if the header contains an MD5 digest, the payload
+/// must match it
+pub fn extract_seama_payload(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTPUT_FILE_NAME: &str = "payload.bin";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(seama_header) = parse_seama_header(&file_data[offset..]) {
+        let payload_start = offset + seama_header.header_size;
+        let payload_end = payload_start + seama_header.data_size;
+
+        // Headers without a payload have nothing to extract
+        if seama_header.data_size > 0 {
+            if let Some(payload) = file_data.get(payload_start..payload_end) {
+                let digest_valid = seama_header
+                    .digest
+                    .is_none_or(|digest| md5::compute(payload).0 == digest);
+
+                if digest_valid {
+                    result.size = Some(seama_header.header_size + seama_header.data_size);
+                    result.success = true;
+
+                    if output_directory.is_some() {
+                        let chroot = Chroot::new(output_directory);
+                        result.success = chroot.carve_file(
+                            OUTPUT_FILE_NAME,
+                            file_data,
+                            payload_start,
+                            seama_header.data_size,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
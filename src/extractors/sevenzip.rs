@@ -1,4 +1,11 @@
 use crate::extractors;
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::locked::LockedContent;
+use crate::structures::sevenzip::parse_7z_header;
+use log::{debug, warn};
+use sevenz_rust::{Archive, Password, SevenZArchiveEntry, SevenZMethod, SevenZReader};
+use std::io::{self, Cursor, Read};
+use std::path::Path;
 
 /// Describes how to run the 7z utility, supports multiple file formats
 ///
@@ -11,6 +18,7 @@ use crate::extractors;
 /// match sevenzip_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -35,6 +43,235 @@ pub fn sevenzip_extractor() -> extractors::common::Extractor {
         ],
         // If there is trailing data after the compressed data, extraction will happen but exit code will be 2
         exit_codes: vec![0, 2],
+        reports_progress: true,
+        progress_arguments: vec![
+            "-bsp1".to_string(), // Report progress on stdout
+        ],
         ..Default::default()
     }
 }
+
+/// Defines the internal extractor function for 7-zip archives, which does not require the 7z utility
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::sevenzip::sevenzip_archive_extractor;
+///
+/// match sevenzip_archive_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn sevenzip_archive_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_sevenzip_archive),
+        ..Default::default()
+    }
+}
+
+/// Coders supported by the internal extractor
+const SUPPORTED_CODERS: &[&[u8]] = &[
+    SevenZMethod::ID_COPY,
+    SevenZMethod::ID_LZMA,
+    SevenZMethod::ID_LZMA2,
+    SevenZMethod::ID_BCJ_X86,
+    SevenZMethod::ID_BCJ_ARM,
+    SevenZMethod::ID_BCJ_ARM_THUMB,
+    SevenZMethod::ID_BCJ_PPC,
+    SevenZMethod::ID_BCJ_SPARC,
+    SevenZMethod::ID_DELTA,
+];
+
+/// Returns true if the internal extractor supports all of the coders used by a 7-zip archive.
+///
+/// Only the archive headers are read; no file data is decompressed. Encrypted archives can't be extracted by either
+/// extractor, and are considered supported, so that the internal extractor reports them as locked content.
+pub fn is_internally_extractable(archive_data: &[u8]) -> bool {
+    match Archive::read(
+        &mut Cursor::new(archive_data),
+        archive_data.len() as u64,
+        &[],
+    ) {
+        Err(e) => is_encryption_error(&e),
+        Ok(archive) => archive
+            .folders
+            .iter()
+            .flat_map(|folder| folder.coders.iter())
+            .all(|coder| {
+                let coder_id = coder.decompression_method_id();
+                SUPPORTED_CODERS.contains(&coder_id) || coder_id == SevenZMethod::ID_AES256SHA256
+            }),
+    }
+}
+
+/// Internal extractor for 7-zip archives using the copy, LZMA, LZMA2, BCJ and delta coders; encrypted archives are not
+/// supported, and are reported as locked content
+pub fn extract_sevenzip_archive(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(sevenzip_data) = file_data.get(offset..) {
+        if let Ok(sevenzip_header) = parse_7z_header(sevenzip_data) {
+            let archive_size = sevenzip_header.header_size
+                + sevenzip_header.next_header_offset
+                + sevenzip_header.next_header_size;
+
+            if let Some(archive_data) = sevenzip_data.get(..archive_size) {
+                let chroot = Chroot::new(output_directory);
+                let mut file_count: usize = 0;
+
+                let extraction = SevenZReader::new(
+                    Cursor::new(archive_data),
+                    archive_size as u64,
+                    Password::empty(),
+                )
+                .and_then(|mut archive| {
+                    archive.for_each_entries(|entry, entry_reader| {
+                        // Entry data must always be read, as it is checked against the entry's CRC
+                        let entry_data_size = match output_directory {
+                            None => io::copy(entry_reader, &mut io::sink())?,
+                            Some(_) => extract_entry(&chroot, entry, entry_reader)?,
+                        };
+
+                        if entry_data_size != entry.size() {
+                            return Err(sevenz_rust::Error::other("truncated entry data"));
+                        }
+
+                        file_count += 1;
+                        Ok(true)
+                    })
+                });
+
+                match extraction {
+                    Err(e) if is_encryption_error(&e) => {
+                        result.locked.push(LockedContent::new(
+                            "encrypted 7-zip archive",
+                            "7-zip password",
+                        ));
+                    }
+                    Err(sevenz_rust::Error::UnsupportedCompressionMethod(method)) => {
+                        debug!("7-zip archive uses unsupported coder {method}");
+                    }
+                    Err(e) => warn!("7-zip archive extraction failed: {e}"),
+                    Ok(_) => {
+                        if file_count > 0 {
+                            result.size = Some(archive_size);
+                            result.success = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Returns true if a 7-zip extraction error was caused by encrypted archive headers or entries
+fn is_encryption_error(error: &sevenz_rust::Error) -> bool {
+    match error {
+        sevenz_rust::Error::PasswordRequired | sevenz_rust::Error::MaybeBadPassword(_) => true,
+        // AES decryption support is not built in, so encrypted data is reported as an unsupported method
+        sevenz_rust::Error::UnsupportedCompressionMethod(method) => {
+            method == SevenZMethod::AES256SHA256.name()
+        }
+        _ => false,
+    }
+}
+
+/// Extracts a single 7-zip archive entry, returning the number of bytes of entry data read
+fn extract_entry(
+    chroot: &Chroot,
+    entry: &SevenZArchiveEntry,
+    entry_reader: &mut dyn Read,
+) -> Result<u64, sevenz_rust::Error> {
+    // Size of each chunk of entry data written to disk
+    const CHUNK_SIZE: u64 = 1024 * 1024;
+    // Archives created on Unix systems store the Unix file mode in the upper 16 bits of the file attributes
+    const UNIX_EXTENSION: u32 = 0x8000;
+    const S_IFMT: u32 = 0o170000;
+    const S_IFLNK: u32 = 0o120000;
+
+    // Archives created on Windows use backslashes as the path separator
+    let entry_path = entry.name().replace('\\', "/");
+    let file_path = chroot.safe_path_join("", &entry_path);
+
+    // Entries are not guaranteed to be preceded by their parent directory entries
+    if let Some(parent_directory) = Path::new(&entry_path).parent() {
+        if !parent_directory.as_os_str().is_empty() {
+            chroot.create_directory(parent_directory.display().to_string());
+        }
+    }
+
+    if entry.is_directory() {
+        if !chroot.create_directory(&file_path) {
+            return Err(sevenz_rust::Error::other("failed to create directory"));
+        }
+        return Ok(0);
+    }
+
+    let attributes = entry.windows_attributes();
+
+    if entry.has_windows_attributes
+        && (attributes & UNIX_EXTENSION) != 0
+        && ((attributes >> 16) & S_IFMT) == S_IFLNK
+    {
+        // Symlink entry data is the symlink target
+        let mut link_target: Vec<u8> = vec![];
+        let link_target_size = entry_reader.read_to_end(&mut link_target)? as u64;
+
+        if !chroot.create_symlink(
+            &file_path,
+            String::from_utf8_lossy(&link_target).to_string(),
+        ) {
+            return Err(sevenz_rust::Error::other("failed to create symlink"));
+        }
+
+        return Ok(link_target_size);
+    }
+
+    if !chroot.create_file(&file_path, b"") {
+        return Err(sevenz_rust::Error::other("failed to create file"));
+    }
+
+    // Write out file data a chunk at a time, rather than holding entire files in memory
+    let mut entry_data_size: u64 = 0;
+    let mut chunk: Vec<u8> = vec![];
+
+    loop {
+        chunk.clear();
+
+        let chunk_size = (&mut *entry_reader)
+            .take(CHUNK_SIZE)
+            .read_to_end(&mut chunk)?;
+
+        if chunk_size == 0 {
+            break;
+        }
+
+        if !chroot.append_to_file(&file_path, &chunk) {
+            return Err(sevenz_rust::Error::other("failed to write file data"));
+        }
+
+        entry_data_size += chunk_size as u64;
+    }
+
+    Ok(entry_data_size)
+}
@@ -0,0 +1,94 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::sqlite::{parse_sqlite_header, parse_sqlite_schema};
+
+/// Defines the internal extractor function for carving out SQLite databases
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::sqlite::sqlite_extractor;
+///
+/// match sqlite_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn sqlite_extractor() -> Extractor {
+    Extractor {
+        do_not_recurse: true,
+        utility: ExtractorType::Internal(sqlite_carver),
+        ..Default::default()
+    }
+}
+
+/// Carves a SQLite database, and writes its schema to an adjacent text file
+pub fn sqlite_carver(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTFILE_NAME: &str = "database.sqlite";
+    const SCHEMA_FILE_NAME: &str = "database.sqlite.schema.txt";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    let sqlite_data = &file_data[offset..];
+
+    if let Ok(sqlite_header) = parse_sqlite_header(sqlite_data) {
+        if let Some(page_count) = sqlite_header.page_count {
+            let sqlite_size = page_count * sqlite_header.page_size;
+
+            if sqlite_size <= sqlite_data.len() {
+                result.size = Some(sqlite_size);
+                result.success = true;
+
+                if output_directory.is_some() {
+                    let chroot = Chroot::new(output_directory);
+                    result.success =
+                        chroot.carve_file(OUTFILE_NAME, file_data, offset, sqlite_size);
+
+                    // A damaged schema doesn't prevent the database itself from being examined
+                    if result.success {
+                        if let Ok(schema) =
+                            parse_sqlite_schema(&sqlite_data[..sqlite_size], &sqlite_header)
+                        {
+                            let mut schema_text = "".to_string();
+
+                            for entry in schema {
+                                // Automatically created indexes have no SQL statement
+                                match entry.sql.is_empty() {
+                                    true => schema_text.push_str(&format!(
+                                        "-- {} {} (created automatically)\n\n",
+                                        entry.entry_type, entry.name
+                                    )),
+                                    false => schema_text.push_str(&format!(
+                                        "-- {} {}\n{};\n\n",
+                                        entry.entry_type, entry.name, entry.sql
+                                    )),
+                                }
+                            }
+
+                            result.success =
+                                chroot.create_file(SCHEMA_FILE_NAME, schema_text.as_bytes());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
@@ -1,9 +1,8 @@
 use crate::extractors;
 use std::env;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::fs::{read, File};
 use std::io::{Read};
-use std::process::{Command};
 use log::{warn, debug, error, info};
 
 /// 检查SquashFS文件是否使用LZMA压缩
@@ -70,51 +69,27 @@ fn is_lzma_compressed(file_path: &str) -> Option<bool> {
 /// 返回:
 ///     Option<String>: 7-Zip可执行文件路径，如果未找到则返回None
 fn find_seven_zip() -> Option<String> {
-    // 常见的7-Zip安装路径
-    let common_paths = [
-        "C:\\Program Files\\7-Zip\\7z.exe",
-        "C:\\Program Files (x86)\\7-Zip\\7z.exe",
-        ".\\7z.exe",
-        ".\\7-Zip\\7z.exe",
-    ];
-    
-    // 尝试常见路径
-    for path in &common_paths {
-        if Path::new(path).exists() {
-            debug!("在常见路径找到7-Zip: {}", path);
-            return Some(path.to_string());
-        }
-    }
-    
-    // 尝试在PATH环境变量中查找
-    if let Ok(path_env) = env::var("PATH") {
-        for path in path_env.split(';') {
-            let seven_zip_path = Path::new(path).join("7z.exe");
-            if seven_zip_path.exists() {
-                debug!("在PATH中找到7-Zip: {}", seven_zip_path.display());
-                return Some(seven_zip_path.to_string_lossy().to_string());
-            }
+    // 7-Zip的安装目录; Program Files is not always on drive C:, and on ARM64 Windows the 64-bit directory is ProgramW6432
+    let install_directories = ["ProgramW6432", "ProgramFiles", "ProgramFiles(x86)"]
+        .iter()
+        .filter_map(env::var_os)
+        .map(|directory| Path::new(&directory).join("7-Zip").join("7z.exe"));
+
+    for seven_zip_path in install_directories {
+        if seven_zip_path.exists() {
+            debug!("在常见路径找到7-Zip: {}", seven_zip_path.display());
+            return Some(seven_zip_path.to_string_lossy().to_string());
         }
     }
-    
-    // 尝试在binwalk.exe所在目录查找
-    if let Ok(exe_path) = env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            let seven_zip_path = exe_dir.join("7z.exe");
-            if seven_zip_path.exists() {
-                debug!("在binwalk.exe目录找到7-Zip: {}", seven_zip_path.display());
-                return Some(seven_zip_path.to_string_lossy().to_string());
-            }
-            
-            // 尝试binwalk.exe目录下的7-Zip子目录
-            let seven_zip_dir_path = exe_dir.join("7-Zip").join("7z.exe");
-            if seven_zip_dir_path.exists() {
-                debug!("在binwalk.exe目录的7-Zip子目录找到7-Zip: {}", seven_zip_dir_path.display());
-                return Some(seven_zip_dir_path.to_string_lossy().to_string());
-            }
+
+    // 尝试在当前目录、binwalk.exe所在目录以及PATH环境变量中查找
+    for name in ["7z.exe", "7-Zip\\7z.exe"] {
+        if let Some(seven_zip_path) = extractors::common::find_executable(name) {
+            debug!("找到7-Zip: {}", seven_zip_path.display());
+            return Some(seven_zip_path.to_string_lossy().to_string());
         }
     }
-    
+
     debug!("未找到7-Zip");
     None
 }
@@ -133,7 +108,7 @@ fn get_squashfs_tool() -> String {
         
         // 返回第一个存在的路径，否则返回默认路径
         for path in &potential_paths {
-            if Path::new(path).exists() {
+            if extractors::common::find_executable(path).is_some() {
                 debug!("找到SquashFS工具: {}", path);
                 return path.to_string();
             }
@@ -151,7 +126,7 @@ fn get_squashfs_tool() -> String {
         // Linux/macOS平台使用sasquatch，如果不存在则回退到unsquashfs
         let tools_to_try = ["sasquatch", "unsquashfs"];
         for tool in &tools_to_try {
-            if Command::new("which").arg(tool).output().is_ok() {
+            if extractors::common::find_executable(tool).is_some() {
                 return tool.to_string();
             }
         }
@@ -159,7 +134,7 @@ fn get_squashfs_tool() -> String {
         // 在Linux/macOS上也尝试查找7-Zip作为替代方案
         let seven_zip_names = ["7z", "7za", "7zr"];
         for name in &seven_zip_names {
-            if Command::new("which").arg(name).output().is_ok() {
+            if extractors::common::find_executable(name).is_some() {
                 debug!("使用7-Zip工具 {} 作为squashfs提取的替代方案", name);
                 return name.to_string();
             }
@@ -206,113 +181,16 @@ fn get_mksquashfs_tool() -> String {
 /// 返回:
 ///     bool: 工具是否可用
 fn is_tool_available_on_windows(tool_name: &str) -> bool {
-    // 1. 尝试直接使用工具名作为命令执行
-    match Command::new(tool_name).arg("--help").output() {
-        Ok(output) if output.status.success() || output.status.code() == Some(1) => {
-            // 大多数工具在显示帮助时返回0或1
-            debug!("工具 {} 可以通过命令行直接访问", tool_name);
-            return true;
-        }
-        _ => {}
-    }
-    
-    // 2. 检查相对路径和绝对路径
-    let potential_paths = vec![
-        tool_name.to_string(),
-        format!(".\\{}", tool_name),
-        format!("sqfs_for_win\\{}", tool_name),
-        format!(".\\sqfs_for_win\\{}", tool_name),
-        // 如果不包含.exe后缀，添加.exe再尝试
-        if !tool_name.to_lowercase().ends_with(".exe") {
-            format!("{}.exe", tool_name)
-        } else { "".to_string() },
-        if !tool_name.to_lowercase().ends_with(".exe") {
-            format!(".\\{}.exe", tool_name)
-        } else { "".to_string() },
-        if !tool_name.to_lowercase().ends_with(".exe") {
-            format!("sqfs_for_win\\{}.exe", tool_name)
-        } else { "".to_string() },
-        if !tool_name.to_lowercase().ends_with(".exe") {
-            format!(".\\sqfs_for_win\\{}.exe", tool_name)
-        } else { "".to_string() },
-    ];
-    
-    for path in potential_paths {
-        if path.is_empty() { continue; }
-        
-        if Path::new(&path).exists() {
-            // 验证该文件是否可执行
-            match Command::new(&path).arg("--help").output() {
-                Ok(_) => {
-                    debug!("找到可用的工具: {}", path);
-                    return true;
-                }
-                _ => continue,
-            }
-        }
-    }
-    
-    // 3. 检查PATH环境变量中的所有目录
-    if let Ok(path) = env::var("PATH") {
-        for dir in path.split(";").filter(|d| !d.is_empty()) {
-            let potential_exe_paths = [
-                Path::new(dir).join(tool_name),
-                if !tool_name.to_lowercase().ends_with(".exe") {
-                    Path::new(dir).join(format!("{}.exe", tool_name))
-                } else { PathBuf::new() }
-            ];
-            
-            for exe_path in &potential_exe_paths {
-                if exe_path.exists() {
-                    match Command::new(exe_path).arg("--help").output() {
-                        Ok(_) => {
-                            debug!("在PATH中找到可用的工具: {}", exe_path.display());
-                            return true;
-                        }
-                        _ => continue,
-                    }
-                }
-            }
-        }
-    }
-    
-    // 4. 检查binwalk.exe所在目录
-    if let Ok(exe_path) = env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            let potential_tool_path = exe_dir.join(
-                if tool_name.contains("\\") || tool_name.contains("/") {
-                    PathBuf::from(tool_name)
-                } else {
-                    PathBuf::from(tool_name)
-                }
-            );
-            
-            if potential_tool_path.exists() {
-                match Command::new(&potential_tool_path).arg("--help").output() {
-                    Ok(_) => {
-                        debug!("在binwalk.exe所在目录找到工具: {}", potential_tool_path.display());
-                        return true;
-                    }
-                    _ => {}
-                }
-            }
-            
-            // 也检查exe目录下的sqfs_for_win子目录
-            let sqfs_path = exe_dir.join("sqfs_for_win").join(
-                Path::new(tool_name).file_name().unwrap_or(Path::new(tool_name).as_ref())
-            );
-            if sqfs_path.exists() {
-                match Command::new(&sqfs_path).arg("--help").output() {
-                    Ok(_) => {
-                        debug!("在binwalk.exe目录的sqfs_for_win子目录找到工具: {}", sqfs_path.display());
-                        return true;
-                    }
-                    _ => {}
-                }
-            }
-        }
+    // Tools are looked up without running them; they may also be in the sqfs_for_win directory
+    let sqfs_tool_name = Path::new("sqfs_for_win").join(Path::new(tool_name).file_name().unwrap_or_default());
+
+    if extractors::common::find_executable(tool_name).is_some()
+        || extractors::common::find_executable(&sqfs_tool_name.to_string_lossy()).is_some()
+    {
+        debug!("找到可用的工具: {}", tool_name);
+        return true;
     }
-    
+
     debug!("无法找到可用的工具: {}", tool_name);
     false
 }
@@ -469,6 +347,7 @@ pub fn mksquashfs_creator(source_dir: &str, output_file: &str) -> extractors::co
 /// match squashfs_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -497,6 +376,9 @@ pub fn squashfs_extractor() -> extractors::common::Extractor {
                     arguments: get_squashfs_arguments(false, false, false),
                     // 7-Zip的退出码为0表示成功
                     exit_codes: vec![0],
+                    reports_progress: true,
+                    // Report progress on stdout
+                    progress_arguments: vec!["-bsp1".to_string()],
                     ..Default::default()
                 };
             }
@@ -510,6 +392,8 @@ pub fn squashfs_extractor() -> extractors::common::Extractor {
         arguments: get_squashfs_arguments(false, false, false),
         // 支持unsquashfs和7-Zip的退出码
         exit_codes: vec![0, 2],
+        // The progress bar is drawn by default, except on Windows where it is disabled with -n
+        reports_progress: !cfg!(windows),
         ..Default::default()
     }
 }
@@ -525,6 +409,7 @@ pub fn squashfs_extractor() -> extractors::common::Extractor {
 /// match squashfs_le_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -553,6 +438,9 @@ pub fn squashfs_le_extractor() -> extractors::common::Extractor {
                     arguments: get_squashfs_arguments(true, false, false),
                     // 7-Zip的退出码为0表示成功
                     exit_codes: vec![0],
+                    reports_progress: true,
+                    // Report progress on stdout
+                    progress_arguments: vec!["-bsp1".to_string()],
                     ..Default::default()
                 };
             }
@@ -566,6 +454,8 @@ pub fn squashfs_le_extractor() -> extractors::common::Extractor {
         arguments: get_squashfs_arguments(true, false, false),
         // 支持unsquashfs和7-Zip的退出码
         exit_codes: vec![0, 2],
+        // The progress bar is drawn by default, except on Windows where it is disabled with -n
+        reports_progress: !cfg!(windows),
         ..Default::default()
     }
 }
@@ -581,6 +471,7 @@ pub fn squashfs_le_extractor() -> extractors::common::Extractor {
 /// match squashfs_be_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -609,6 +500,9 @@ pub fn squashfs_be_extractor() -> extractors::common::Extractor {
                     arguments: get_squashfs_arguments(false, true, false),
                     // 7-Zip的退出码为0表示成功
                     exit_codes: vec![0],
+                    reports_progress: true,
+                    // Report progress on stdout
+                    progress_arguments: vec!["-bsp1".to_string()],
                     ..Default::default()
                 };
             }
@@ -622,6 +516,8 @@ pub fn squashfs_be_extractor() -> extractors::common::Extractor {
         arguments: get_squashfs_arguments(false, true, false),
         // 支持unsquashfs和7-Zip的退出码
         exit_codes: vec![0, 2],
+        // The progress bar is drawn by default, except on Windows where it is disabled with -n
+        reports_progress: !cfg!(windows),
         ..Default::default()
     }
 }
@@ -637,6 +533,7 @@ pub fn squashfs_be_extractor() -> extractors::common::Extractor {
 /// match squashfs_v4_be_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -665,6 +562,9 @@ pub fn squashfs_v4_be_extractor() -> extractors::common::Extractor {
                     arguments: get_squashfs_arguments(false, true, true),
                     // 7-Zip的退出码为0表示成功
                     exit_codes: vec![0],
+                    reports_progress: true,
+                    // Report progress on stdout
+                    progress_arguments: vec!["-bsp1".to_string()],
                     ..Default::default()
                 };
             }
@@ -678,6 +578,8 @@ pub fn squashfs_v4_be_extractor() -> extractors::common::Extractor {
         arguments: get_squashfs_arguments(false, true, true),
         // 支持unsquashfs和7-Zip的退出码
         exit_codes: vec![0, 2],
+        // The progress bar is drawn by default, except on Windows where it is disabled with -n
+        reports_progress: !cfg!(windows),
         ..Default::default()
     }
 }
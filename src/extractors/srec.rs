@@ -1,6 +1,8 @@
-use crate::extractors;
+use crate::common::parser_deadline_exceeded;
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::extractors::ihex::{HexRecords, decode_record, next_line};
 
-/// Describes how to run the srec_cat utility to convert Motorola S-records to binary
+/// Describes the internal extractor for converting Motorola S-records to binary
 ///
 /// ```
 /// use std::io::ErrorKind;
@@ -11,6 +13,7 @@ use crate::extractors;
 /// match srec_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -22,17 +25,116 @@ use crate::extractors;
 ///     }
 /// }
 /// ```
-pub fn srec_extractor() -> extractors::common::Extractor {
-    extractors::common::Extractor {
-        utility: extractors::common::ExtractorType::External("srec_cat".to_string()),
-        extension: "hex".to_string(),
-        arguments: vec![
-            "-output".to_string(),
-            "s-record.bin".to_string(),
-            "-binary".to_string(),
-            extractors::common::SOURCE_FILE_PLACEHOLDER.to_string(),
-        ],
-        exit_codes: vec![0],
+pub fn srec_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_srec),
         ..Default::default()
     }
 }
+
+/// Converts Motorola S-records to binary, filling small gaps between records with 0xFF
+pub fn extract_srec(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTPUT_NAME: &str = "s-record";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(hex_records) = parse_srec(&file_data[offset..]) {
+        result.size = Some(hex_records.size);
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+            result.success = hex_records.memory.write(&chroot, OUTPUT_NAME);
+        }
+    }
+
+    result
+}
+
+/// Parses the S-records at the start of the data, up to and including the termination record.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::srec::parse_srec;
+///
+/// let srec = b"S00600004844521B\nS107001001020304DE\nS9030000FC\n";
+///
+/// let hex_records = parse_srec(srec).expect("Failed to parse S-records");
+///
+/// assert_eq!(hex_records.size, srec.len());
+/// assert_eq!(hex_records.memory.segments(), vec![(0x10, vec![1, 2, 3, 4])]);
+/// ```
+pub fn parse_srec(data: &[u8]) -> Option<HexRecords> {
+    const START_CODE: u8 = b'S';
+    // Size of the byte count field
+    const COUNT_SIZE: usize = 1;
+
+    let mut hex_records = HexRecords {
+        ..Default::default()
+    };
+
+    let mut offset: usize = 0;
+
+    while let Some((line, next_offset)) = next_line(data, offset) {
+        if parser_deadline_exceeded() {
+            break;
+        }
+
+        offset = next_offset;
+
+        if line.is_empty() {
+            continue;
+        }
+
+        // The record type is a single decimal digit
+        let record_type = *line.get(1)?;
+        let record = decode_record(&[&line[..1], &line[2..]].concat(), START_CODE)?;
+
+        let address_size: usize = match record_type {
+            b'0' | b'1' | b'5' | b'9' => 2,
+            b'2' | b'6' | b'8' => 3,
+            b'3' | b'7' => 4,
+            _ => return None,
+        };
+
+        // The byte count includes the address, data, and checksum; the checksum is the one's complement of the sum
+        // of the byte count, address, and data
+        if record.len() < COUNT_SIZE + address_size + 1
+            || record.len() != COUNT_SIZE + record[0] as usize
+            || record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) != 0xFF
+        {
+            return None;
+        }
+
+        let address = record[COUNT_SIZE..COUNT_SIZE + address_size]
+            .iter()
+            .fold(0u64, |address, b| (address << 8) | *b as u64);
+        let record_data = &record[COUNT_SIZE + address_size..record.len() - 1];
+
+        match record_type {
+            // Data records
+            b'1' | b'2' | b'3' => {
+                hex_records.address_bits = hex_records.address_bits.max(address_size * 8);
+                hex_records.memory.add(address, record_data);
+            }
+            // Termination records, which specify the start address
+            b'7' | b'8' | b'9' => {
+                hex_records.entry_point = Some(address);
+                hex_records.size = offset;
+                return Some(hex_records);
+            }
+            // Header and record count records
+            _ => (),
+        }
+    }
+
+    // No termination record
+    None
+}
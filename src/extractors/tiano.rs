@@ -0,0 +1,492 @@
+//! Decompressor for the EFI and Tiano compression formats used by UEFI firmware.
+//!
+//! Both formats are LZ77 + Huffman coding schemes, and differ only in the number of bits used to encode match
+//! positions. Compressed data starts with an 8 byte header containing the compressed and decompressed sizes.
+
+use crate::extractors::common::{decompression_limit, report_decompression_limit_reached};
+
+/// Size of the bit buffer, in bits
+const BITBUFSIZ: u32 = 32;
+/// Longest match length
+const MAXMATCH: usize = 256;
+/// Shortest match length
+const THRESHOLD: usize = 3;
+/// Number of symbols in the character and length set
+const NC: usize = 0xFF + MAXMATCH + 2 - THRESHOLD;
+/// Number of bits used to encode the size of the character and length set
+const CBIT: u32 = 9;
+/// Number of bits used to encode the size of the position set, for the Tiano format
+const MAXPBIT: u32 = 5;
+/// Number of bits used to encode the size of the extra set
+const TBIT: u32 = 5;
+/// Maximum number of symbols in the position set
+const MAXNP: usize = (1 << MAXPBIT) - 1;
+/// Number of symbols in the extra set
+const NT: usize = 16 + 3;
+/// Size of the position and extra set tables
+const NPT: usize = if NT > MAXNP { NT } else { MAXNP };
+/// Position bits used by the EFI format
+const EFI_PBIT: u32 = 4;
+
+/// Decompresses EFI compressed data.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::tiano::efi_decompress;
+///
+/// // A single block that repeats the byte 'A'
+/// let compressed = b"\x07\x00\x00\x00\x05\x00\x00\x00\x00\x05\x00\x00\x04\x10\x00";
+///
+/// assert_eq!(efi_decompress(compressed), Some(b"AAAAA".to_vec()));
+/// ```
+pub fn efi_decompress(compressed_data: &[u8]) -> Option<Vec<u8>> {
+    decompress(compressed_data, EFI_PBIT)
+}
+
+/// Decompresses Tiano compressed data.
+pub fn tiano_decompress(compressed_data: &[u8]) -> Option<Vec<u8>> {
+    decompress(compressed_data, MAXPBIT)
+}
+
+/// Decompresses data compressed with the specified number of position bits
+fn decompress(compressed_data: &[u8], pbit: u32) -> Option<Vec<u8>> {
+    const HEADER_SIZE: usize = 8;
+
+    let compressed_size = u32::from_le_bytes(compressed_data.get(0..4)?.try_into().ok()?) as usize;
+    let original_size = u32::from_le_bytes(compressed_data.get(4..8)?.try_into().ok()?) as usize;
+
+    if original_size > decompression_limit() {
+        report_decompression_limit_reached();
+        return None;
+    }
+
+    let source = compressed_data.get(HEADER_SIZE..HEADER_SIZE + compressed_size)?;
+
+    let mut decoder = Decoder {
+        source,
+        in_index: 0,
+        bit_buf: 0,
+        sub_bit_buf: 0,
+        bit_count: 0,
+        block_size: 0,
+        pbit,
+        output: Vec::with_capacity(original_size),
+        original_size,
+        left: [0; 2 * NC - 1],
+        right: [0; 2 * NC - 1],
+        c_len: [0; NC],
+        pt_len: [0; NPT],
+        c_table: [0; 4096],
+        pt_table: [0; 256],
+    };
+
+    decoder.fill_buf(BITBUFSIZ);
+
+    match decoder.decode() {
+        Some(()) => Some(decoder.output),
+        None => None,
+    }
+}
+
+/// Identifies an entry in a Huffman lookup table, or in the left/right trees used for codes longer than the table
+#[derive(Clone, Copy)]
+enum TableEntry {
+    Table(usize),
+    Left(usize),
+    Right(usize),
+}
+
+/// Decompression state
+struct Decoder<'a> {
+    source: &'a [u8],
+    in_index: usize,
+    bit_buf: u32,
+    sub_bit_buf: u32,
+    bit_count: u32,
+    block_size: u16,
+    pbit: u32,
+    output: Vec<u8>,
+    original_size: usize,
+    left: [u16; 2 * NC - 1],
+    right: [u16; 2 * NC - 1],
+    c_len: [u8; NC],
+    pt_len: [u8; NPT],
+    c_table: [u16; 4096],
+    pt_table: [u16; 256],
+}
+
+impl Decoder<'_> {
+    /// Shifts the specified number of bits into the bit buffer; once the source data is exhausted, zeros are shifted in
+    fn fill_buf(&mut self, bit_count: u32) {
+        let mut bit_count = bit_count;
+
+        self.bit_buf = self.bit_buf.checked_shl(bit_count).unwrap_or(0);
+
+        while bit_count > self.bit_count {
+            bit_count -= self.bit_count;
+            self.bit_buf |= self.sub_bit_buf.checked_shl(bit_count).unwrap_or(0);
+
+            self.sub_bit_buf = match self.source.get(self.in_index) {
+                Some(byte) => {
+                    self.in_index += 1;
+                    *byte as u32
+                }
+                None => 0,
+            };
+
+            self.bit_count = 8;
+        }
+
+        self.bit_count -= bit_count;
+        self.bit_buf |= self.sub_bit_buf >> self.bit_count;
+    }
+
+    /// Reads the specified number of bits from the bit buffer
+    fn get_bits(&mut self, bit_count: u32) -> u32 {
+        let bits = self.bit_buf.checked_shr(BITBUFSIZ - bit_count).unwrap_or(0);
+        self.fill_buf(bit_count);
+        bits
+    }
+
+    /// Builds a Huffman lookup table from a list of code lengths.
+    /// Codes longer than the table bits are resolved by walking the left/right trees.
+    fn make_table(&mut self, bit_lengths: &[u8], table_bits: u32, table: TableKind) -> Option<()> {
+        let mut count: [u16; 17] = [0; 17];
+        let mut weight: [u16; 17] = [0; 17];
+        let mut start: [u16; 18] = [0; 18];
+
+        for bit_length in bit_lengths {
+            if *bit_length > 16 {
+                return None;
+            }
+
+            count[*bit_length as usize] += 1;
+        }
+
+        for i in 1..=16 {
+            start[i + 1] = start[i].wrapping_add(count[i].wrapping_shl(16 - i as u32));
+        }
+
+        // The code lengths must describe a complete code
+        if start[17] != 0 {
+            return None;
+        }
+
+        let unused_bits = 16 - table_bits;
+
+        for i in 1..=table_bits as usize {
+            start[i] >>= unused_bits;
+            weight[i] = 1 << (table_bits as usize - i);
+        }
+
+        for (i, w) in weight.iter_mut().enumerate().skip(table_bits as usize + 1) {
+            *w = 1 << (16 - i);
+        }
+
+        let table_size: usize = 1 << table_bits;
+        let first_unused = (start[table_bits as usize + 1] >> unused_bits) as usize;
+
+        if first_unused != 0 {
+            for i in first_unused..table_size {
+                self.set_entry(TableEntry::Table(i), table, 0);
+            }
+        }
+
+        let mut available = bit_lengths.len();
+        let mask: u16 = 1 << (15 - table_bits);
+
+        for (symbol, bit_length) in bit_lengths.iter().enumerate() {
+            let bit_length = *bit_length as usize;
+
+            if bit_length == 0 {
+                continue;
+            }
+
+            let next_code = start[bit_length].wrapping_add(weight[bit_length]);
+
+            if bit_length <= table_bits as usize {
+                if start[bit_length] >= next_code || next_code as usize > table_size {
+                    return None;
+                }
+
+                for i in start[bit_length]..next_code {
+                    self.set_entry(TableEntry::Table(i as usize), table, symbol as u16);
+                }
+            } else {
+                let mut code = start[bit_length];
+                let mut entry = TableEntry::Table((code >> unused_bits) as usize);
+
+                for _ in 0..(bit_length - table_bits as usize) {
+                    let mut node = self.get_entry(entry, table);
+
+                    if node == 0 && available < (2 * NC - 1) {
+                        self.left[available] = 0;
+                        self.right[available] = 0;
+                        self.set_entry(entry, table, available as u16);
+                        node = available as u16;
+                        available += 1;
+                    }
+
+                    if (node as usize) < (2 * NC - 1) {
+                        entry = match code & mask {
+                            0 => TableEntry::Left(node as usize),
+                            _ => TableEntry::Right(node as usize),
+                        };
+                    }
+
+                    code <<= 1;
+                }
+
+                self.set_entry(entry, table, symbol as u16);
+            }
+
+            start[bit_length] = next_code;
+        }
+
+        Some(())
+    }
+
+    fn get_entry(&self, entry: TableEntry, table: TableKind) -> u16 {
+        match entry {
+            TableEntry::Table(i) => match table {
+                TableKind::C => self.c_table[i],
+                TableKind::PT => self.pt_table[i],
+            },
+            TableEntry::Left(i) => self.left[i],
+            TableEntry::Right(i) => self.right[i],
+        }
+    }
+
+    fn set_entry(&mut self, entry: TableEntry, table: TableKind, value: u16) {
+        match entry {
+            TableEntry::Table(i) => match table {
+                TableKind::C => self.c_table[i] = value,
+                TableKind::PT => self.pt_table[i] = value,
+            },
+            TableEntry::Left(i) => self.left[i] = value,
+            TableEntry::Right(i) => self.right[i] = value,
+        }
+    }
+
+    /// Resolves a code whose table entry is a tree node, by walking the tree one bit at a time
+    fn walk_tree(&self, node: u16, symbol_count: usize, table_bits: u32) -> Option<u16> {
+        let mut node = node;
+        let mut mask: u32 = 1 << (BITBUFSIZ - 1 - table_bits);
+
+        while node as usize >= symbol_count {
+            if mask == 0 || node as usize >= 2 * NC - 1 {
+                return None;
+            }
+
+            node = match self.bit_buf & mask {
+                0 => self.left[node as usize],
+                _ => self.right[node as usize],
+            };
+
+            mask >>= 1;
+        }
+
+        Some(node)
+    }
+
+    /// Reads the code lengths of the extra or position set, and builds its lookup table
+    fn read_pt_len(
+        &mut self,
+        symbol_count: usize,
+        bit_count: u32,
+        special: Option<usize>,
+    ) -> Option<()> {
+        let number = self.get_bits(bit_count) as usize;
+
+        if number == 0 {
+            let symbol = self.get_bits(bit_count) as u16;
+
+            if symbol as usize >= symbol_count {
+                return None;
+            }
+
+            self.pt_table = [symbol; 256];
+            self.pt_len[..symbol_count].fill(0);
+            return Some(());
+        }
+
+        if number > symbol_count {
+            return None;
+        }
+
+        let mut index: usize = 0;
+
+        while index < number && index < NPT {
+            let mut bit_length = self.bit_buf >> (BITBUFSIZ - 3);
+
+            if bit_length == 7 {
+                let mut mask: u32 = 1 << (BITBUFSIZ - 1 - 3);
+
+                while mask & self.bit_buf != 0 {
+                    mask >>= 1;
+                    bit_length += 1;
+                }
+            }
+
+            self.fill_buf(if bit_length < 7 { 3 } else { bit_length - 3 });
+            self.pt_len[index] = bit_length as u8;
+            index += 1;
+
+            if Some(index) == special {
+                let zero_count = self.get_bits(2) as usize;
+
+                for _ in 0..zero_count {
+                    if index >= NPT {
+                        break;
+                    }
+
+                    self.pt_len[index] = 0;
+                    index += 1;
+                }
+            }
+        }
+
+        while index < symbol_count && index < NPT {
+            self.pt_len[index] = 0;
+            index += 1;
+        }
+
+        let pt_len = self.pt_len;
+        self.make_table(&pt_len[..symbol_count], 8, TableKind::PT)
+    }
+
+    /// Reads the code lengths of the character and length set, and builds its lookup table
+    fn read_c_len(&mut self) -> Option<()> {
+        let number = self.get_bits(CBIT) as usize;
+
+        if number == 0 {
+            let symbol = self.get_bits(CBIT) as u16;
+
+            if symbol as usize >= NC {
+                return None;
+            }
+
+            self.c_len = [0; NC];
+            self.c_table = [symbol; 4096];
+            return Some(());
+        }
+
+        let mut index: usize = 0;
+
+        while index < number && index < NC {
+            let mut symbol = self.pt_table[(self.bit_buf >> (BITBUFSIZ - 8)) as usize];
+
+            if symbol as usize >= NT {
+                symbol = self.walk_tree(symbol, NT, 8)?;
+            }
+
+            self.fill_buf(self.pt_len[symbol as usize] as u32);
+
+            if symbol <= 2 {
+                let zero_count = match symbol {
+                    0 => 1,
+                    1 => self.get_bits(4) as usize + 3,
+                    _ => self.get_bits(CBIT) as usize + 20,
+                };
+
+                for _ in 0..zero_count {
+                    if index >= NC {
+                        break;
+                    }
+
+                    self.c_len[index] = 0;
+                    index += 1;
+                }
+            } else {
+                self.c_len[index] = (symbol - 2) as u8;
+                index += 1;
+            }
+        }
+
+        self.c_len[index..].fill(0);
+
+        let c_len = self.c_len;
+        self.make_table(&c_len, 12, TableKind::C)
+    }
+
+    /// Decodes the next character or match length
+    fn decode_c(&mut self) -> Option<u16> {
+        if self.block_size == 0 {
+            self.block_size = self.get_bits(16) as u16;
+            self.read_pt_len(NT, TBIT, Some(3))?;
+            self.read_c_len()?;
+            self.read_pt_len(MAXNP, self.pbit, None)?;
+        }
+
+        self.block_size = self.block_size.wrapping_sub(1);
+
+        let mut symbol = self.c_table[(self.bit_buf >> (BITBUFSIZ - 12)) as usize];
+
+        if symbol as usize >= NC {
+            symbol = self.walk_tree(symbol, NC, 12)?;
+        }
+
+        self.fill_buf(self.c_len[symbol as usize] as u32);
+
+        Some(symbol)
+    }
+
+    /// Decodes the next match position
+    fn decode_p(&mut self) -> Option<usize> {
+        let mut symbol = self.pt_table[(self.bit_buf >> (BITBUFSIZ - 8)) as usize];
+
+        if symbol as usize >= MAXNP {
+            symbol = self.walk_tree(symbol, MAXNP, 8)?;
+        }
+
+        self.fill_buf(self.pt_len[symbol as usize] as u32);
+
+        let mut position = symbol as usize;
+
+        if symbol > 1 {
+            position = (1 << (symbol - 1)) + self.get_bits(symbol as u32 - 1) as usize;
+        }
+
+        Some(position)
+    }
+
+    /// Decodes all data, up to the original size
+    fn decode(&mut self) -> Option<()> {
+        const MATCH_LENGTH_BASE: usize = 0x100 - THRESHOLD;
+
+        while self.output.len() < self.original_size {
+            let symbol = self.decode_c()? as usize;
+
+            if symbol < 0x100 {
+                self.output.push(symbol as u8);
+            } else {
+                let match_length = symbol - MATCH_LENGTH_BASE;
+                let distance = self.decode_p()? + 1;
+
+                if distance > self.output.len() {
+                    return None;
+                }
+
+                let match_start = self.output.len() - distance;
+
+                for match_index in match_start..match_start + match_length {
+                    if self.output.len() >= self.original_size {
+                        break;
+                    }
+
+                    self.output.push(self.output[match_index]);
+                }
+            }
+        }
+
+        Some(())
+    }
+}
+
+/// Identifies which lookup table a table entry refers to
+#[derive(Clone, Copy)]
+enum TableKind {
+    C,
+    PT,
+}
@@ -0,0 +1,81 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::tplink::parse_tplink_firmware_header;
+
+/// Defines the internal extractor for TP-Link firmware images
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::tplink::tplink_extractor;
+///
+/// match tplink_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn tplink_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_tplink_regions),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for the bootloader, kernel and rootfs regions of a TP-Link firmware image.
+///
+/// Images are often stripped of the padding after the last region, so the image extends to the end of the last
+/// region if the available data is smaller than the image size reported in the header.
+pub fn extract_tplink_regions(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(tplink_header) = parse_tplink_firmware_header(&file_data[offset..]) {
+        let available_data = file_data.len() - offset;
+
+        if let Some(regions_end) = tplink_header
+            .regions
+            .iter()
+            .map(|region| region.offset + region.size)
+            .max()
+        {
+            if regions_end <= available_data {
+                result.size = Some(std::cmp::min(tplink_header.total_size, available_data));
+                result.success = true;
+
+                if output_directory.is_some() {
+                    let chroot = Chroot::new(output_directory);
+
+                    for region in &tplink_header.regions {
+                        result.success = chroot.carve_file(
+                            format!("{}.bin", region.name),
+                            file_data,
+                            offset + region.offset,
+                            region.size,
+                        );
+
+                        if !result.success {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
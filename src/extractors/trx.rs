@@ -13,6 +13,7 @@ use crate::structures::trx::parse_trx_header;
 /// match trx_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -58,25 +59,20 @@ pub fn extract_trx_partitions(
                     if output_directory.is_some() {
                         let chroot = Chroot::new(output_directory);
 
-                        for i in 0..trx_header.partitions.len() {
-                            let next_partition: usize = i + 1;
-                            let this_partition_relative_offset: usize = trx_header.partitions[i];
-                            let this_partition_absolute_offset: usize =
-                                offset + this_partition_relative_offset;
-                            let mut this_partition_size: usize =
-                                trx_header.total_size - this_partition_relative_offset;
-
-                            if next_partition < trx_header.partitions.len() {
-                                this_partition_size = trx_header.partitions[next_partition]
-                                    - this_partition_relative_offset;
+                        for (i, (partition_offset, partition_size)) in
+                            trx_header.partition_extents().into_iter().enumerate()
+                        {
+                            // A partition that starts at the end of the image is empty
+                            if partition_size == 0 {
+                                continue;
                             }
 
                             let this_partition_file_name = format!("partition_{i}.bin");
                             result.success = chroot.carve_file(
                                 &this_partition_file_name,
                                 file_data,
-                                this_partition_absolute_offset,
-                                this_partition_size,
+                                offset + partition_offset,
+                                partition_size,
                             );
 
                             if !result.success {
@@ -1,6 +1,36 @@
-use crate::extractors;
+use crate::extractors::common::{
+    Chroot, ExtractionResult, Extractor, ExtractorType, SOURCE_FILE_PLACEHOLDER,
+};
+use crate::extractors::lzop::lzo1x_decompress;
+use crate::structures::common;
+use crate::structures::ubi::{
+    UBI_VTBL_RECORD_SIZE, UBIFS_DATA_NODE, UBIFS_DENT_NODE, UBIFS_IDX_NODE, UBIFS_INO_NODE,
+    UBIFS_MST_NODE, UbifsDataNode, UbifsDirentNode, UbifsInodeNode, UbifsNodeHeader,
+    parse_ubi_ec_header, parse_ubi_superblock_header, parse_ubi_volume_header,
+    parse_ubi_volume_table_record, parse_ubifs_data_node, parse_ubifs_dirent_node,
+    parse_ubifs_index_node, parse_ubifs_inode_node, parse_ubifs_master_node,
+    parse_ubifs_node_header,
+};
+use aho_corasick::AhoCorasick;
+use flate2::bufread::DeflateDecoder;
+use log::{debug, warn};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
 
-/// Describes how to run the ubireader_extract_images utility to extract UBI images
+/// File type bits of an inode mode
+const S_IFMT: usize = 0o170000;
+const S_IFSOCK: usize = 0o140000;
+const S_IFLNK: usize = 0o120000;
+const S_IFREG: usize = 0o100000;
+const S_IFBLK: usize = 0o060000;
+const S_IFDIR: usize = 0o040000;
+const S_IFCHR: usize = 0o020000;
+const S_IFIFO: usize = 0o010000;
+
+/// Any of the executable bits of an inode mode
+const S_IXANY: usize = 0o111;
+
+/// Defines the internal extractor function for extracting volumes from UBI images
 ///
 /// ```
 /// use std::io::ErrorKind;
@@ -11,6 +41,7 @@ use crate::extractors;
 /// match ubi_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -22,25 +53,738 @@ use crate::extractors;
 ///     }
 /// }
 /// ```
-pub fn ubi_extractor() -> extractors::common::Extractor {
-    extractors::common::Extractor {
-        utility: extractors::common::ExtractorType::External(
-            "ubireader_extract_images".to_string(),
-        ),
-        extension: "img".to_string(),
-        arguments: vec![extractors::common::SOURCE_FILE_PLACEHOLDER.to_string()],
-        exit_codes: vec![0],
+pub fn ubi_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_ubi_image),
+        fallback: Some(Box::new(ubireader_extractor(
+            "ubireader_extract_images",
+            "img",
+        ))),
+        ..Default::default()
+    }
+}
+
+/// Defines the internal extractor function for extracting files from UBIFS images
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::ubi::ubifs_extractor;
+///
+/// match ubifs_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn ubifs_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_ubifs),
+        fallback: Some(Box::new(ubireader_extractor(
+            "ubireader_extract_files",
+            "ubifs",
+        ))),
         ..Default::default()
     }
 }
 
-/// Describes how to run the ubireader_extract_files utility to extract UBIFS images
-pub fn ubifs_extractor() -> extractors::common::Extractor {
-    extractors::common::Extractor {
-        utility: extractors::common::ExtractorType::External("ubireader_extract_files".to_string()),
-        extension: "ubifs".to_string(),
-        arguments: vec![extractors::common::SOURCE_FILE_PLACEHOLDER.to_string()],
+/// Describes a ubi_reader utility, which is run on images that the internal extractors fail to extract
+fn ubireader_extractor(command: &str, extension: &str) -> Extractor {
+    Extractor {
+        utility: ExtractorType::External(command.to_string()),
+        extension: extension.to_string(),
+        arguments: vec![SOURCE_FILE_PLACEHOLDER.to_string()],
         exit_codes: vec![0],
         ..Default::default()
     }
 }
+
+/// Volume ID of the internal layout volume, which contains the volume table
+const UBI_LAYOUT_VOLUME_ID: usize = 0x7FFFEFFF;
+
+/// Volume types, as reported in volume headers and the volume table
+const UBI_DYNAMIC_VOLUME: usize = 1;
+const UBI_STATIC_VOLUME: usize = 2;
+
+/// A logical erase block mapped to a UBI volume
+#[derive(Debug, Default, Clone)]
+struct UbiLeb {
+    sequence_number: usize,
+    data_start: usize,
+    data_size: usize,
+}
+
+/// The LEBs of each volume in a UBI image
+#[derive(Debug, Default, Clone)]
+struct UbiLayout {
+    peb_size: usize,
+    /// Size of unmapped LEBs, which are assumed to have the same data offset as the first PEB
+    leb_size: usize,
+    peb_count: usize,
+    /// LEBs of each volume, keyed by volume ID and LEB number
+    volumes: HashMap<usize, HashMap<usize, UbiLeb>>,
+}
+
+/// A volume listed in the volume table of a UBI image
+#[derive(Debug, Default, Clone)]
+pub struct UbiVolume {
+    pub volume_id: usize,
+    pub name: String,
+    /// "dynamic", "static", or "unknown"
+    pub volume_type: String,
+    /// Size reserved for the volume, in bytes
+    pub size: usize,
+}
+
+/// Internal extractor for UBI images; each UBI volume is extracted to a separate file
+pub fn extract_ubi_image(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    // Unmapped LEBs in dynamic volumes are considered to be erased
+    const ERASED_BYTE: u8 = 0xFF;
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    let ubi_data = &file_data[offset..];
+
+    let mut layout = match map_ubi_lebs(ubi_data) {
+        Some(layout) => layout,
+        None => return result,
+    };
+
+    let volume_table = read_volume_table(ubi_data, &layout);
+
+    layout.volumes.remove(&UBI_LAYOUT_VOLUME_ID);
+
+    if layout.peb_count == 0 || layout.volumes.is_empty() {
+        return result;
+    }
+
+    result.success = true;
+    result.size = Some(layout.peb_count * layout.peb_size);
+
+    // Do extraction, if an output directory was provided
+    if output_directory.is_some() {
+        let chroot = Chroot::new(output_directory);
+
+        let mut volume_ids: Vec<usize> = layout.volumes.keys().copied().collect();
+        volume_ids.sort();
+
+        // Each volume is carved to its own file, so that UBIFS volumes can be handed off to the UBIFS extractor
+        for volume_id in volume_ids {
+            let volume_lebs = &layout.volumes[&volume_id];
+            let leb_count = volume_lebs.keys().max().map_or(0, |max_lnum| max_lnum + 1);
+
+            let mut volume_data: Vec<u8> = vec![];
+
+            for lnum in 0..leb_count {
+                match volume_lebs.get(&lnum) {
+                    Some(leb) => volume_data.extend_from_slice(
+                        &ubi_data[leb.data_start..leb.data_start + leb.data_size],
+                    ),
+                    None => volume_data.resize(volume_data.len() + layout.leb_size, ERASED_BYTE),
+                }
+            }
+
+            // Volume names may contain path separators
+            let volume_name = match volume_table
+                .iter()
+                .find(|volume| volume.volume_id == volume_id)
+            {
+                Some(volume) => volume.name.replace('/', "_"),
+                None => format!("volume_{volume_id}"),
+            };
+
+            let file_name = match parse_ubi_superblock_header(&volume_data) {
+                Ok(_) => format!("{volume_name}.ubifs"),
+                Err(_) => format!("{volume_name}.img"),
+            };
+
+            if !chroot.create_file(&file_name, &volume_data) {
+                result.success = false;
+            }
+        }
+    }
+
+    result
+}
+
+/// Returns the volumes listed in the volume table of a UBI image, ordered by volume ID
+pub fn ubi_volumes(ubi_data: &[u8]) -> Vec<UbiVolume> {
+    match map_ubi_lebs(ubi_data) {
+        Some(layout) => read_volume_table(ubi_data, &layout),
+        None => vec![],
+    }
+}
+
+/// Maps each PEB in a UBI image to the volume LEB stored in it
+fn map_ubi_lebs(ubi_data: &[u8]) -> Option<UbiLayout> {
+    let peb_size = get_ubi_peb_size(ubi_data)?;
+
+    let mut layout = UbiLayout {
+        peb_size,
+        leb_size: match parse_ubi_ec_header(ubi_data) {
+            Ok(ec_header) if ec_header.data_offset < peb_size => peb_size - ec_header.data_offset,
+            _ => return None,
+        },
+        ..Default::default()
+    };
+
+    // Process each PEB until the end of the UBI image
+    while let Some(peb_data) =
+        ubi_data.get(layout.peb_count * peb_size..(layout.peb_count + 1) * peb_size)
+    {
+        let ec_header = match parse_ubi_ec_header(peb_data) {
+            Err(_) => break,
+            Ok(ec_header) => ec_header,
+        };
+
+        let peb_start = layout.peb_count * peb_size;
+        layout.peb_count += 1;
+
+        // Unused PEBs have no volume header
+        let vid_header = match peb_data
+            .get(ec_header.volume_id_offset..)
+            .map(parse_ubi_volume_header)
+        {
+            Some(Ok(vid_header)) => vid_header,
+            _ => continue,
+        };
+
+        if ec_header.data_offset >= peb_size {
+            continue;
+        }
+
+        let mut leb = UbiLeb {
+            sequence_number: vid_header.sequence_number,
+            data_start: peb_start + ec_header.data_offset,
+            data_size: peb_size - ec_header.data_offset,
+        };
+
+        // Static volumes only contain the amount of data reported in the volume header
+        if vid_header.volume_type == UBI_STATIC_VOLUME {
+            leb.data_size = std::cmp::min(leb.data_size, vid_header.data_size);
+        }
+
+        // If the same LEB was written to multiple PEBs, the one with the highest sequence number wins
+        let volume_lebs = layout.volumes.entry(vid_header.volume_id).or_default();
+        if volume_lebs
+            .get(&vid_header.lnum)
+            .is_none_or(|existing| existing.sequence_number < leb.sequence_number)
+        {
+            volume_lebs.insert(vid_header.lnum, leb);
+        }
+    }
+
+    Some(layout)
+}
+
+/// Reads the volume table from the first LEB of the layout volume
+fn read_volume_table(ubi_data: &[u8], layout: &UbiLayout) -> Vec<UbiVolume> {
+    // Maximum number of volumes in the volume table
+    const UBI_MAX_VOLUMES: usize = 128;
+
+    let mut volume_table: Vec<UbiVolume> = vec![];
+
+    if let Some(vtbl_leb) = layout
+        .volumes
+        .get(&UBI_LAYOUT_VOLUME_ID)
+        .and_then(|layout_volume| layout_volume.get(&0))
+    {
+        for volume_id in 0..UBI_MAX_VOLUMES {
+            let record_start = vtbl_leb.data_start + (volume_id * UBI_VTBL_RECORD_SIZE);
+
+            if let Some(record_data) =
+                ubi_data.get(record_start..record_start + UBI_VTBL_RECORD_SIZE)
+            {
+                if let Ok(record) = parse_ubi_volume_table_record(record_data) {
+                    debug!(
+                        "UBI volume {volume_id}: {}, {} PEBs, type {}",
+                        record.name, record.reserved_pebs, record.volume_type
+                    );
+
+                    volume_table.push(UbiVolume {
+                        volume_id,
+                        name: record.name,
+                        volume_type: match record.volume_type {
+                            UBI_DYNAMIC_VOLUME => "dynamic".to_string(),
+                            UBI_STATIC_VOLUME => "static".to_string(),
+                            _ => "unknown".to_string(),
+                        },
+                        size: record.reserved_pebs * layout.leb_size,
+                    });
+                }
+            }
+        }
+    }
+
+    volume_table
+}
+
+/// Determines the PEB size of a UBI image from the distance between erase count headers
+fn get_ubi_peb_size(ubi_data: &[u8]) -> Option<usize> {
+    let mut peb_size_counts: HashMap<usize, usize> = HashMap::new();
+    let mut previous_ec_offset: Option<usize> = None;
+
+    // Erase count header magic bytes, version is assumed to be 1
+    let grep = AhoCorasick::new(vec![b"UBI#\x01"]).unwrap();
+
+    for magic_match in grep.find_overlapping_iter(ubi_data) {
+        let ec_offset = magic_match.start();
+
+        if parse_ubi_ec_header(&ubi_data[ec_offset..]).is_ok() {
+            // The first EC header must be at the start of the image
+            if previous_ec_offset.is_none() && ec_offset != 0 {
+                return None;
+            }
+
+            if let Some(previous_offset) = previous_ec_offset {
+                *peb_size_counts
+                    .entry(ec_offset - previous_offset)
+                    .or_default() += 1;
+            }
+
+            previous_ec_offset = Some(ec_offset);
+        }
+    }
+
+    // Pick the most common distance between EC headers
+    peb_size_counts
+        .into_iter()
+        .max_by_key(|(peb_size, count)| (*count, *peb_size))
+        .map(|(peb_size, _)| peb_size)
+}
+
+/// All nodes referenced by a UBIFS index
+#[derive(Debug, Default, Clone)]
+struct UbifsImage {
+    inodes: HashMap<usize, UbifsInodeNode>,
+    data: HashMap<usize, Vec<UbifsDataNode>>,
+    dirents: Vec<UbifsDirentNode>,
+}
+
+/// Internal extractor for UBIFS images
+pub fn extract_ubifs(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    // UBIFS files will be extracted to this sub-directory of the output directory
+    const OUTPUT_DIRECTORY_NAME: &str = "ubifs-root";
+    // Only the "simple" key format is defined
+    const UBIFS_SIMPLE_KEY_FORMAT: usize = 0;
+    // Inode number of the root directory
+    const UBIFS_ROOT_INODE: usize = 1;
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    let superblock = match parse_ubi_superblock_header(&file_data[offset..]) {
+        Ok(superblock) => superblock,
+        Err(_) => return result,
+    };
+
+    if superblock.key_format != UBIFS_SIMPLE_KEY_FORMAT || superblock.leb_size == 0 {
+        warn!("Unsupported UBIFS key format {}", superblock.key_format);
+        return result;
+    }
+
+    // The image may have been truncated, e.g., if trailing LEBs were never mapped
+    let image_size = superblock.leb_count * superblock.leb_size;
+    let available_size = std::cmp::min(image_size, file_data.len() - offset);
+    let ubifs_data = &file_data[offset..offset + available_size];
+
+    let ubifs_image = match process_ubifs_index(ubifs_data, superblock.leb_size) {
+        Some(ubifs_image) => ubifs_image,
+        None => return result,
+    };
+
+    // The root directory must have at least one entry
+    if !ubifs_image
+        .dirents
+        .iter()
+        .any(|dirent| dirent.parent_inode == UBIFS_ROOT_INODE)
+    {
+        return result;
+    }
+
+    result.success = true;
+    result.size = Some(image_size);
+
+    // Do extraction, if an output directory was provided
+    if output_directory.is_some() {
+        let chroot = Chroot::new(output_directory);
+        let mut file_count: usize = 0;
+
+        if chroot.create_directory(OUTPUT_DIRECTORY_NAME) {
+            let ubifs_chroot = Chroot::new(Some(&chroot.chrooted_path(OUTPUT_DIRECTORY_NAME)));
+            let mut visited_directories: HashSet<usize> = HashSet::new();
+
+            file_count = extract_ubifs_directory(
+                &ubifs_image,
+                UBIFS_ROOT_INODE,
+                "",
+                &ubifs_chroot,
+                &mut visited_directories,
+            );
+        }
+
+        // If no files were extracted, extraction was a failure
+        if file_count == 0 {
+            result.success = false;
+        }
+    }
+
+    result
+}
+
+/// Locates the latest master node, and walks the UBIFS index to collect all inode, data, and directory entry nodes
+fn process_ubifs_index(ubifs_data: &[u8], leb_size: usize) -> Option<UbifsImage> {
+    // LEBs containing the master node
+    const UBIFS_MASTER_LEBS: [usize; 2] = [1, 2];
+    // Sanity limit on the depth of the index tree
+    const MAX_INDEX_LEVEL: usize = 64;
+
+    let mut ubifs_image = UbifsImage {
+        ..Default::default()
+    };
+
+    // Find the master node with the highest sequence number
+    let mut master_node = None;
+    let mut master_sequence_number: usize = 0;
+
+    for lnum in UBIFS_MASTER_LEBS {
+        if let Some(leb_data) = ubifs_data.get(lnum * leb_size..(lnum + 1) * leb_size) {
+            for (node_offset, node_header) in ubifs_leb_nodes(leb_data) {
+                if node_header.node_type == UBIFS_MST_NODE
+                    && node_header.sequence_number >= master_sequence_number
+                {
+                    if let Ok(master) = parse_ubifs_master_node(&leb_data[node_offset..]) {
+                        master_sequence_number = node_header.sequence_number;
+                        master_node = Some(master);
+                    }
+                }
+            }
+        }
+    }
+
+    let master_node = master_node?;
+
+    // Walk the index, starting at the root index node
+    let mut index_nodes: Vec<(usize, usize, usize)> = vec![(
+        master_node.root_lnum,
+        master_node.root_offset,
+        MAX_INDEX_LEVEL,
+    )];
+    let mut visited_nodes: HashSet<(usize, usize)> = HashSet::new();
+
+    while let Some((lnum, leb_offset, max_level)) = index_nodes.pop() {
+        // Sanity check, no node should be referenced twice, else infinite recursion could ensue
+        if !visited_nodes.insert((lnum, leb_offset)) {
+            continue;
+        }
+
+        let node_start = (lnum * leb_size) + leb_offset;
+
+        let node_data = match ubifs_data.get(node_start..) {
+            Some(node_data) => node_data,
+            None => {
+                warn!("UBIFS node at LEB {lnum}:{leb_offset:#X} is out of bounds");
+                continue;
+            }
+        };
+
+        let node_header = match parse_ubifs_node_header(node_data) {
+            Ok(node_header) => node_header,
+            Err(_) => {
+                warn!("Invalid UBIFS node at LEB {lnum}:{leb_offset:#X}");
+                continue;
+            }
+        };
+
+        let node_data = &node_data[..node_header.size];
+
+        match node_header.node_type {
+            UBIFS_IDX_NODE => {
+                if let Ok(index_node) = parse_ubifs_index_node(node_data) {
+                    // Index levels must decrease as the tree is descended
+                    if index_node.level < max_level {
+                        for (child_lnum, child_offset) in index_node.branches {
+                            index_nodes.push((child_lnum, child_offset, index_node.level));
+                        }
+                    }
+                }
+            }
+            UBIFS_INO_NODE => {
+                if let Ok(inode) = parse_ubifs_inode_node(node_data) {
+                    ubifs_image.inodes.insert(inode.inode, inode);
+                }
+            }
+            UBIFS_DATA_NODE => {
+                if let Ok(data_node) = parse_ubifs_data_node(node_data) {
+                    ubifs_image
+                        .data
+                        .entry(data_node.inode)
+                        .or_default()
+                        .push(data_node);
+                }
+            }
+            UBIFS_DENT_NODE => {
+                if let Ok(dirent) = parse_ubifs_dirent_node(node_data) {
+                    ubifs_image.dirents.push(dirent);
+                }
+            }
+            _ => {
+                debug!(
+                    "Ignoring UBIFS node type {} at LEB {lnum}:{leb_offset:#X}",
+                    node_header.node_type
+                );
+            }
+        }
+    }
+
+    // Sort entries by name for predictable extraction
+    ubifs_image.dirents.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Some(ubifs_image)
+}
+
+/// Returns the offsets and headers of all valid nodes in a UBIFS LEB
+fn ubifs_leb_nodes(leb_data: &[u8]) -> Vec<(usize, UbifsNodeHeader)> {
+    // Nodes are aligned to an 8-byte boundary
+    const NODE_ALIGNMENT: usize = 8;
+
+    let mut nodes = vec![];
+    let mut node_offset: usize = 0;
+
+    while node_offset < leb_data.len() {
+        match parse_ubifs_node_header(&leb_data[node_offset..]) {
+            Err(_) => node_offset += NODE_ALIGNMENT,
+            Ok(node_header) => {
+                let node_size = node_header.size.next_multiple_of(NODE_ALIGNMENT);
+                nodes.push((node_offset, node_header));
+                node_offset += node_size;
+            }
+        }
+    }
+
+    nodes
+}
+
+/// Recursively extracts the contents of a UBIFS directory, returns the number of extracted files/directories
+fn extract_ubifs_directory(
+    ubifs_image: &UbifsImage,
+    directory_inode: usize,
+    directory_path: &str,
+    chroot: &Chroot,
+    visited_directories: &mut HashSet<usize>,
+) -> usize {
+    // Directory entry inode types, used if a file has no inode node
+    const UBIFS_ITYPE_REG: usize = 0;
+    const UBIFS_ITYPE_DIR: usize = 1;
+    const UBIFS_ITYPE_LNK: usize = 2;
+    const UBIFS_ITYPE_BLK: usize = 3;
+    const UBIFS_ITYPE_CHR: usize = 4;
+    const UBIFS_ITYPE_FIFO: usize = 5;
+    const UBIFS_ITYPE_SOCK: usize = 6;
+
+    let mut file_count: usize = 0;
+
+    // Sanity check, a directory should only be processed once, else infinite recursion could ensue
+    if !visited_directories.insert(directory_inode) {
+        return file_count;
+    }
+
+    for dirent in ubifs_image
+        .dirents
+        .iter()
+        .filter(|dirent| dirent.parent_inode == directory_inode)
+    {
+        let file_path = chroot.safe_path_join(directory_path, &dirent.name);
+        let inode = ubifs_image.inodes.get(&dirent.inode);
+
+        // Prefer the file type reported by the inode
+        let file_type = match inode {
+            Some(inode) => inode.mode & S_IFMT,
+            None => match dirent.inode_type {
+                UBIFS_ITYPE_REG => S_IFREG,
+                UBIFS_ITYPE_DIR => S_IFDIR,
+                UBIFS_ITYPE_LNK => S_IFLNK,
+                UBIFS_ITYPE_BLK => S_IFBLK,
+                UBIFS_ITYPE_CHR => S_IFCHR,
+                UBIFS_ITYPE_FIFO => S_IFIFO,
+                UBIFS_ITYPE_SOCK => S_IFSOCK,
+                _ => 0,
+            },
+        };
+
+        let inline_data = inode.map_or(vec![], |inode| inode.data.clone());
+
+        let extraction_success = match file_type {
+            S_IFDIR => chroot.create_directory(&file_path),
+            S_IFREG => chroot.create_file(&file_path, &ubifs_file_data(ubifs_image, dirent.inode)),
+            S_IFLNK => chroot.create_symlink(
+                &file_path,
+                String::from_utf8_lossy(&inline_data).to_string(),
+            ),
+            S_IFCHR | S_IFBLK => {
+                let (major, minor) = ubifs_device_number(&inline_data);
+                if file_type == S_IFCHR {
+                    chroot.create_character_device(&file_path, major, minor)
+                } else {
+                    chroot.create_block_device(&file_path, major, minor)
+                }
+            }
+            S_IFIFO => chroot.create_fifo(&file_path),
+            S_IFSOCK => chroot.create_socket(&file_path),
+            _ => {
+                warn!("Unknown file type for UBIFS file {file_path}");
+                continue;
+            }
+        };
+
+        if extraction_success {
+            file_count += 1;
+
+            if file_type == S_IFDIR {
+                file_count += extract_ubifs_directory(
+                    ubifs_image,
+                    dirent.inode,
+                    &file_path,
+                    chroot,
+                    visited_directories,
+                );
+            } else if file_type == S_IFREG {
+                // Make executable files executable
+                if let Some(inode) = inode {
+                    if (inode.mode & S_IXANY) != 0 {
+                        chroot.make_executable(&file_path);
+                    }
+                }
+            }
+        } else {
+            warn!("Failed to extract UBIFS file {file_path}");
+        }
+    }
+
+    file_count
+}
+
+/// Reconstructs a file's contents from its data nodes
+fn ubifs_file_data(ubifs_image: &UbifsImage, inode_number: usize) -> Vec<u8> {
+    // UBIFS data nodes each contain one block of file data
+    const UBIFS_BLOCK_SIZE: usize = 4096;
+    // Sanity limit on file size, as a corrupt file size field could otherwise exhaust memory
+    const MAX_FILE_SIZE: usize = 1024 * 1024 * 1024;
+
+    let file_size = match ubifs_image.inodes.get(&inode_number) {
+        None => return vec![],
+        Some(inode) => inode.size,
+    };
+
+    if file_size > MAX_FILE_SIZE {
+        warn!("UBIFS inode {inode_number} reports an invalid file size of {file_size} bytes");
+        return vec![];
+    }
+
+    // Holes in the file are not stored
+    let mut contents: Vec<u8> = vec![0; file_size];
+
+    for data_node in ubifs_image.data.get(&inode_number).into_iter().flatten() {
+        let block_offset = data_node.block * UBIFS_BLOCK_SIZE;
+
+        match ubifs_decompress(
+            &data_node.compressed_data,
+            data_node.compression,
+            data_node.size,
+        ) {
+            None => warn!(
+                "Failed to decompress data for UBIFS inode {inode_number} (compression type {})",
+                data_node.compression
+            ),
+            Some(block_data) => {
+                // Copy the block data into the file, ignoring anything past the end of the file
+                if block_offset < contents.len() {
+                    let copy_size = std::cmp::min(block_data.len(), contents.len() - block_offset);
+                    contents[block_offset..block_offset + copy_size]
+                        .copy_from_slice(&block_data[..copy_size]);
+                }
+            }
+        }
+    }
+
+    contents
+}
+
+/// Decompresses UBIFS data node data
+fn ubifs_decompress(
+    compressed_data: &[u8],
+    compression: usize,
+    decompressed_size: usize,
+) -> Option<Vec<u8>> {
+    const UBIFS_COMPR_NONE: usize = 0;
+    const UBIFS_COMPR_LZO: usize = 1;
+    const UBIFS_COMPR_ZLIB: usize = 2;
+    const UBIFS_COMPR_ZSTD: usize = 3;
+
+    let mut decompressed_data: Vec<u8> = vec![];
+
+    match compression {
+        UBIFS_COMPR_NONE => {
+            decompressed_data = compressed_data.get(..decompressed_size)?.to_vec();
+        }
+        UBIFS_COMPR_LZO => {
+            decompressed_data = lzo1x_decompress(compressed_data, decompressed_size)?;
+        }
+        // UBIFS zlib compression is a raw deflate stream
+        UBIFS_COMPR_ZLIB => {
+            DeflateDecoder::new(compressed_data)
+                .take(decompressed_size as u64)
+                .read_to_end(&mut decompressed_data)
+                .ok()?;
+        }
+        UBIFS_COMPR_ZSTD => {
+            let mut compressed_reader = compressed_data;
+            ruzstd::decoding::StreamingDecoder::new(&mut compressed_reader)
+                .ok()?
+                .take(decompressed_size as u64)
+                .read_to_end(&mut decompressed_data)
+                .ok()?;
+        }
+        _ => return None,
+    }
+
+    Some(decompressed_data)
+}
+
+/// Decodes a device file's major and minor numbers from its inline inode data
+fn ubifs_device_number(device_data: &[u8]) -> (usize, usize) {
+    let device_structure = vec![("device", "u32")];
+
+    match common::parse(device_data, &device_structure, "little") {
+        Err(_) => (0, 0),
+        Ok(device_number) => {
+            // Devices numbers are stored in the kernel's "new" encoding
+            let device = device_number["device"];
+            (
+                (device & 0xFFF00) >> 8,
+                (device & 0xFF) | ((device >> 12) & 0xFFF00),
+            )
+        }
+    }
+}
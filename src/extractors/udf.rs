@@ -0,0 +1,388 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::udf::{
+    UDF_EXTENT_CONTINUATION, UDF_EXTENT_RECORDED, UDF_FILE_TYPE_DIRECTORY, UDF_FILE_TYPE_FILE,
+    UDF_FILE_TYPE_SYMLINK, UDF_TAG_FILE_SET_DESCRIPTOR, UDFAllocationType, UDFExtent, UDFFileEntry,
+    UDFLongAD, UDFVolume, parse_udf_allocation_descriptors, parse_udf_file_entry,
+    parse_udf_file_identifier, parse_udf_file_set_descriptor, parse_udf_path_components,
+    parse_udf_tag, parse_udf_volume,
+};
+use log::warn;
+use std::collections::{HashSet, VecDeque};
+
+/// Defines the internal extractor function for extracting UDF file systems
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::udf::udf_extractor;
+///
+/// match udf_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn udf_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_udf),
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for UDF file systems.
+///
+/// Only partitions referenced by type 1 partition maps are supported, which excludes the metadata partitions
+/// used by UDF 2.50 and later, as well as virtual and sparable partitions.
+pub fn extract_udf(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(udf_data) = file_data.get(offset..) {
+        if let Ok(udf_volume) = parse_udf_volume(udf_data) {
+            // Only the available data is used if the file system is truncated
+            let image_end = std::cmp::min(udf_volume.total_size, udf_data.len());
+
+            if image_end < udf_volume.total_size {
+                warn!(
+                    "UDF file system is truncated to {} of {} bytes",
+                    image_end, udf_volume.total_size
+                );
+            }
+
+            let udf_image = UdfImage {
+                data: &udf_data[..image_end],
+                volume: udf_volume,
+            };
+
+            if let Some(root_icb) = udf_image.root_directory() {
+                let root_entries = udf_image.directory_entries(&root_icb);
+
+                if !root_entries.is_empty() {
+                    if output_directory.is_none() {
+                        result.success = true;
+                    } else {
+                        let chroot = Chroot::new(output_directory);
+                        let mut visited_directories =
+                            HashSet::from([(root_icb.partition_reference, root_icb.block)]);
+
+                        let file_count = udf_image.extract_directory(
+                            &chroot,
+                            &root_entries,
+                            "",
+                            &mut visited_directories,
+                        );
+
+                        result.success = file_count > 0;
+                    }
+
+                    if result.success {
+                        result.size = Some(udf_image.data.len());
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// A file, directory or symlink in a UDF directory
+#[derive(Debug, Default, Clone)]
+struct UdfFile {
+    name: String,
+    /// Location of the file's file entry
+    icb: UDFLongAD,
+}
+
+/// Provides read-only access to the logical blocks of a UDF file system
+struct UdfImage<'a> {
+    data: &'a [u8],
+    volume: UDFVolume,
+}
+
+impl UdfImage<'_> {
+    /// Returns the offset of a logical block in the specified partition, if the partition is supported
+    fn block_offset(&self, partition_reference: usize, block: usize) -> Option<usize> {
+        let partition_number = (*self
+            .volume
+            .logical_volume
+            .partition_maps
+            .get(partition_reference)?)?;
+
+        let partition = self
+            .volume
+            .partitions
+            .iter()
+            .find(|partition| partition.number == partition_number)?;
+
+        partition
+            .start
+            .checked_add(block)?
+            .checked_mul(self.volume.sector_size)
+    }
+
+    /// Reads up to `length` bytes starting at the specified logical block; returns None if the block is not available
+    fn read_blocks(
+        &self,
+        partition_reference: usize,
+        block: usize,
+        length: usize,
+    ) -> Option<Vec<u8>> {
+        let data_start = self.block_offset(partition_reference, block)?;
+        let data_end = std::cmp::min(data_start.saturating_add(length), self.data.len());
+        self.data
+            .get(data_start..data_end)
+            .map(|block_data| block_data.to_vec())
+    }
+
+    /// Returns the location of the root directory's file entry, as recorded in the file set descriptor
+    fn root_directory(&self) -> Option<UDFLongAD> {
+        let file_set = &self.volume.logical_volume.file_set;
+        let file_set_data = self.read_blocks(
+            file_set.partition_reference,
+            file_set.block,
+            self.volume.sector_size,
+        )?;
+
+        match parse_udf_tag(&file_set_data, Some(file_set.block)) {
+            Ok(UDF_TAG_FILE_SET_DESCRIPTOR) => parse_udf_file_set_descriptor(&file_set_data).ok(),
+            _ => None,
+        }
+    }
+
+    /// Reads and parses the file entry at the specified location
+    fn file_entry(&self, icb: &UDFLongAD) -> Option<UDFFileEntry> {
+        let entry_data =
+            self.read_blocks(icb.partition_reference, icb.block, self.volume.sector_size)?;
+        parse_udf_file_entry(&entry_data, icb.block).ok()
+    }
+
+    /// Returns the contents of a file; unrecorded extents read as zeros
+    fn file_data(&self, entry: &UDFFileEntry, partition_reference: usize) -> Vec<u8> {
+        // Limits the number of allocation descriptor extents followed for a single file
+        const MAX_CONTINUATIONS: usize = 1024;
+
+        // A file's extents are allocated within the file system, so it can never be larger than the file system
+        let file_size = std::cmp::min(entry.information_length, self.data.len());
+
+        if entry.allocation_type == UDFAllocationType::Embedded {
+            let mut file_data = entry.allocation_descriptors.clone();
+            file_data.truncate(file_size);
+            return file_data;
+        }
+
+        let mut file_data: Vec<u8> = vec![];
+        let mut continuations: usize = 0;
+        let mut extents: VecDeque<UDFExtent> = match parse_udf_allocation_descriptors(
+            &entry.allocation_descriptors,
+            &entry.allocation_type,
+        ) {
+            Err(_) => return file_data,
+            Ok((_ad_size, extents)) => extents.into(),
+        };
+
+        while let Some(extent) = extents.pop_front() {
+            if file_data.len() >= file_size {
+                break;
+            }
+
+            let extent_partition = extent.partition_reference.unwrap_or(partition_reference);
+
+            if extent.extent_type == UDF_EXTENT_CONTINUATION {
+                continuations += 1;
+
+                if continuations > MAX_CONTINUATIONS {
+                    break;
+                }
+
+                // The next allocation descriptors are stored in this extent, following an allocation extent descriptor
+                const ALLOCATION_EXTENT_DESCRIPTOR_SIZE: usize = 24;
+
+                match self
+                    .read_blocks(extent_partition, extent.block, extent.length)
+                    .and_then(|extent_data| {
+                        extent_data.get(ALLOCATION_EXTENT_DESCRIPTOR_SIZE..).map(
+                            |allocation_descriptors| {
+                                parse_udf_allocation_descriptors(
+                                    allocation_descriptors,
+                                    &entry.allocation_type,
+                                )
+                            },
+                        )
+                    }) {
+                    Some(Ok((_ad_size, next_extents))) => extents = next_extents.into(),
+                    _ => break,
+                }
+
+                continue;
+            }
+
+            let extent_length = std::cmp::min(extent.length, file_size - file_data.len());
+
+            if extent.extent_type == UDF_EXTENT_RECORDED {
+                match self.read_blocks(extent_partition, extent.block, extent_length) {
+                    None => break,
+                    Some(extent_data) => {
+                        let complete = extent_data.len() == extent_length;
+                        file_data.extend(extent_data);
+
+                        // Data following a missing extent would be misplaced
+                        if !complete {
+                            break;
+                        }
+                    }
+                }
+            } else {
+                file_data.resize(file_data.len() + extent_length, 0);
+            }
+        }
+
+        file_data.truncate(file_size);
+        file_data
+    }
+
+    /// Parses the file identifiers in a directory; deleted entries and the parent directory entry are excluded
+    fn directory_entries(&self, icb: &UDFLongAD) -> Vec<UdfFile> {
+        let mut files: Vec<UdfFile> = vec![];
+
+        let directory_entry = match self.file_entry(icb) {
+            Some(entry) if entry.file_type == UDF_FILE_TYPE_DIRECTORY => entry,
+            _ => return files,
+        };
+
+        let directory_data = self.file_data(&directory_entry, icb.partition_reference);
+        let mut identifier_offset: usize = 0;
+
+        while identifier_offset < directory_data.len() {
+            let file_identifier =
+                match parse_udf_file_identifier(&directory_data[identifier_offset..]) {
+                    Err(_) => break,
+                    Ok(file_identifier) => file_identifier,
+                };
+
+            identifier_offset += file_identifier.size;
+
+            if file_identifier.deleted || file_identifier.parent {
+                continue;
+            }
+
+            let name = file_identifier.name;
+
+            if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\0']) {
+                continue;
+            }
+
+            files.push(UdfFile {
+                name,
+                icb: file_identifier.icb,
+            });
+        }
+
+        files
+    }
+
+    /// Extracts the entries of a directory, and all of its sub-directories; returns the number of extracted files
+    fn extract_directory(
+        &self,
+        chroot: &Chroot,
+        files: &[UdfFile],
+        parent_directory: &str,
+        visited_directories: &mut HashSet<(usize, usize)>,
+    ) -> usize {
+        let mut file_count: usize = 0;
+
+        for file in files {
+            let file_path = chroot.safe_path_join(parent_directory, &file.name);
+
+            let entry = match self.file_entry(&file.icb) {
+                None => {
+                    warn!("Failed to read UDF file entry for {file_path}");
+                    continue;
+                }
+                Some(entry) => entry,
+            };
+
+            match entry.file_type {
+                UDF_FILE_TYPE_DIRECTORY => {
+                    // Directories linked more than once could cause infinite recursion
+                    if !visited_directories.insert((file.icb.partition_reference, file.icb.block)) {
+                        warn!("UDF directory {file_path} has already been extracted");
+                        continue;
+                    }
+
+                    if !chroot.create_directory(&file_path) {
+                        warn!("Failed to extract UDF directory {file_path}");
+                        continue;
+                    }
+
+                    file_count += 1;
+
+                    let children = self.directory_entries(&file.icb);
+                    file_count +=
+                        self.extract_directory(chroot, &children, &file_path, visited_directories);
+                }
+                UDF_FILE_TYPE_FILE => {
+                    let file_data = self.file_data(&entry, file.icb.partition_reference);
+
+                    if file_data.len() < entry.information_length {
+                        warn!(
+                            "Only {} of {} bytes of UDF file {} are available",
+                            file_data.len(),
+                            entry.information_length,
+                            file_path
+                        );
+                    }
+
+                    if chroot.create_file(&file_path, &file_data) {
+                        file_count += 1;
+                    } else {
+                        warn!("Failed to extract UDF file {file_path}");
+                        continue;
+                    }
+                }
+                UDF_FILE_TYPE_SYMLINK => {
+                    let symlink_data = self.file_data(&entry, file.icb.partition_reference);
+
+                    match parse_udf_path_components(&symlink_data) {
+                        Err(_) => warn!("Failed to parse UDF symlink {file_path}"),
+                        Ok(target) => {
+                            if chroot.create_symlink(&file_path, target) {
+                                file_count += 1;
+                            } else {
+                                warn!("Failed to extract UDF symlink {file_path}");
+                            }
+                        }
+                    }
+
+                    continue;
+                }
+                _ => {
+                    warn!(
+                        "UDF file {} has an unsupported file type: {}",
+                        file_path, entry.file_type
+                    );
+                    continue;
+                }
+            }
+
+            chroot.set_permissions(&file_path, entry.mode as u32);
+        }
+
+        file_count
+    }
+}
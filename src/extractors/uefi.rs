@@ -1,4 +1,17 @@
 use crate::extractors;
+use crate::extractors::common::{
+    Chroot, ExtractionResult, Extractor, ExtractorType, decompression_limit,
+    report_decompression_limit_reached,
+};
+use crate::extractors::tiano::{efi_decompress, tiano_decompress};
+use crate::structures::uefi::{
+    guid_to_string, parse_ffs_file_header, parse_ffs_section_header, parse_uefi_volume_header,
+};
+use liblzma::read::XzDecoder;
+use liblzma::stream::Stream;
+use log::warn;
+use std::collections::HashSet;
+use std::io::Read;
 
 /// Describes how to run the uefi-firmware-parser utility to extract UEFI images
 ///
@@ -11,6 +24,7 @@ use crate::extractors;
 /// match uefi_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -38,5 +52,535 @@ pub fn uefi_extractor() -> extractors::common::Extractor {
          * Recursing into this data would result in double extractions for no good reason.
          */
         do_not_recurse: true,
+        ..Default::default()
     }
 }
+
+/// Defines the internal extractor function for walking the files and sections of UEFI firmware volumes
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::uefi::uefi_volume_extractor;
+///
+/// match uefi_volume_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn uefi_volume_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_uefi_volume),
+        ..Default::default()
+    }
+}
+
+/// Maximum nesting depth of firmware volumes and encapsulation sections
+const MAX_DEPTH: usize = 16;
+
+/// A file in a firmware volume, and the data extracted from its sections
+#[derive(Debug, Default, Clone)]
+struct FFSFile {
+    guid: String,
+    /// Name from the file's user interface section, if it has one
+    name: Option<String>,
+    outputs: Vec<SectionOutput>,
+}
+
+/// Data extracted from a section
+#[derive(Debug, Clone)]
+enum SectionOutput {
+    Data { name: String, data: Vec<u8> },
+    Volume(Vec<FFSFile>),
+}
+
+/// Walks the files in a UEFI firmware volume, writing each file to a directory named after the file, or after its
+/// GUID if it has no name. Leaf sections, such as PE32 executables and dependency expressions, are written to files
+/// within the file's directory; compressed and GUID defined sections are decompressed, and nested firmware volumes
+/// are written to `volume` sub-directories.
+pub fn extract_uefi_volume(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(volume_header) = parse_uefi_volume_header(&file_data[offset..]) {
+        if let Some(volume_data) = file_data.get(offset..offset + volume_header.volume_size) {
+            if let Some(files) = parse_volume(volume_data, 0) {
+                result.size = Some(volume_header.volume_size);
+                result.success = true;
+
+                if output_directory.is_some() {
+                    let chroot = Chroot::new(output_directory);
+                    result.success = write_files(&chroot, "", &files);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Parses all files in a firmware volume; returns None if the volume is invalid, or does not contain a firmware
+/// file system
+fn parse_volume(volume_data: &[u8], depth: usize) -> Option<Vec<FFSFile>> {
+    const FILE_ALIGNMENT: usize = 8;
+    const RAW_FILE_TYPE: usize = 0x01;
+    const PAD_FILE_TYPE: usize = 0xF0;
+
+    if depth > MAX_DEPTH {
+        return None;
+    }
+
+    let volume_header = parse_uefi_volume_header(volume_data).ok()?;
+
+    if !volume_header.is_ffs || volume_header.volume_size > volume_data.len() {
+        return None;
+    }
+
+    let volume_data = &volume_data[..volume_header.volume_size];
+
+    let mut files: Vec<FFSFile> = vec![];
+    let mut file_offset = volume_header.files_offset;
+
+    // Files end at the first erased file header, which marks the start of the volume's free space
+    while let Some(file_data) = volume_data.get(file_offset..) {
+        let file_header = match parse_ffs_file_header(
+            file_data,
+            volume_header.erase_polarity,
+            volume_header.is_ffs3,
+        ) {
+            Err(_) => break,
+            Ok(file_header) => file_header,
+        };
+
+        let file_body = match file_data.get(file_header.header_size..file_header.size) {
+            None => {
+                warn!(
+                    "UEFI file {} at volume offset {:#X} extends beyond the end of the volume",
+                    file_header.guid, file_offset
+                );
+                break;
+            }
+            Some(file_body) => file_body,
+        };
+
+        if file_header.is_valid && file_header.file_type != PAD_FILE_TYPE {
+            let mut file = FFSFile {
+                guid: file_header.guid.clone(),
+                ..Default::default()
+            };
+
+            // Raw files have no sections; neither do files whose sections are malformed
+            if file_header.file_type == RAW_FILE_TYPE
+                || !parse_sections(file_body, depth, &mut file)
+            {
+                file.outputs.push(SectionOutput::Data {
+                    name: "body.bin".to_string(),
+                    data: file_body.to_vec(),
+                });
+            }
+
+            files.push(file);
+        }
+
+        file_offset = (file_offset + file_header.size).next_multiple_of(FILE_ALIGNMENT);
+    }
+
+    Some(files)
+}
+
+/// Parses all sections in a file, or in an encapsulation section; returns false if the sections are malformed
+fn parse_sections(section_data: &[u8], depth: usize, file: &mut FFSFile) -> bool {
+    const SECTION_ALIGNMENT: usize = 4;
+    const MIN_SECTION_SIZE: usize = 4;
+
+    if depth > MAX_DEPTH {
+        return false;
+    }
+
+    let mut section_offset: usize = 0;
+
+    while section_data.len() - section_offset >= MIN_SECTION_SIZE {
+        let section_header = match parse_ffs_section_header(&section_data[section_offset..]) {
+            Err(_) => return false,
+            Ok(section_header) => section_header,
+        };
+
+        match section_data.get(section_offset..section_offset + section_header.size) {
+            None => return false,
+            Some(section) => parse_section(
+                section_header.section_type,
+                section,
+                &section[section_header.header_size..],
+                depth,
+                file,
+            ),
+        }
+
+        section_offset = (section_offset + section_header.size).next_multiple_of(SECTION_ALIGNMENT);
+
+        if section_offset >= section_data.len() {
+            break;
+        }
+    }
+
+    true
+}
+
+/// Parses a single section, adding any data it contains to the file's outputs
+fn parse_section(
+    section_type: usize,
+    section: &[u8],
+    section_body: &[u8],
+    depth: usize,
+    file: &mut FFSFile,
+) {
+    const COMPRESSION_SECTION: usize = 0x01;
+    const GUID_DEFINED_SECTION: usize = 0x02;
+    const DISPOSABLE_SECTION: usize = 0x03;
+    const USER_INTERFACE_SECTION: usize = 0x15;
+    const FIRMWARE_VOLUME_IMAGE_SECTION: usize = 0x17;
+    const FREEFORM_SUBTYPE_GUID_SECTION: usize = 0x18;
+
+    // Compression section header fields, following the common section header
+    const COMPRESSION_TYPE_OFFSET: usize = 4;
+    const COMPRESSED_DATA_OFFSET: usize = 5;
+    const NOT_COMPRESSED: u8 = 0;
+    const STANDARD_COMPRESSION: u8 = 1;
+
+    const GUID_SIZE: usize = 16;
+
+    let mut add_output = |name: &str, data: &[u8]| {
+        file.outputs.push(SectionOutput::Data {
+            name: name.to_string(),
+            data: data.to_vec(),
+        });
+    };
+
+    match section_type {
+        COMPRESSION_SECTION => {
+            let decompressed = match section_body.get(COMPRESSED_DATA_OFFSET..) {
+                None => None,
+                Some(compressed_data) => match section_body[COMPRESSION_TYPE_OFFSET] {
+                    NOT_COMPRESSED => Some(compressed_data.to_vec()),
+                    // Some vendors use Tiano compression in standard compression sections
+                    STANDARD_COMPRESSION => efi_decompress(compressed_data)
+                        .or_else(|| tiano_decompress(compressed_data)),
+                    _ => None,
+                },
+            };
+
+            match decompressed {
+                None => {
+                    warn!(
+                        "Failed to decompress UEFI compressed section in file {}",
+                        file.guid
+                    );
+                    add_output("compressed.bin", section_body);
+                }
+                Some(decompressed_data) => {
+                    parse_encapsulated_sections(&decompressed_data, depth, file);
+                }
+            }
+        }
+        GUID_DEFINED_SECTION => parse_guid_defined_section(section, section_body, depth, file),
+        DISPOSABLE_SECTION => parse_encapsulated_sections(section_body, depth, file),
+        USER_INTERFACE_SECTION => {
+            file.name = Some(ucs2_to_string(section_body));
+        }
+        FIRMWARE_VOLUME_IMAGE_SECTION => match parse_volume(section_body, depth + 1) {
+            None => add_output("volume.bin", section_body),
+            Some(files) => file.outputs.push(SectionOutput::Volume(files)),
+        },
+        FREEFORM_SUBTYPE_GUID_SECTION => add_output(
+            "freeform.bin",
+            section_body.get(GUID_SIZE..).unwrap_or_default(),
+        ),
+        _ => add_output(&section_file_name(section_type), section_body),
+    }
+}
+
+/// Parses a GUID defined section, decoding its contents if the GUID identifies a known encoding
+fn parse_guid_defined_section(
+    section: &[u8],
+    section_body: &[u8],
+    depth: usize,
+    file: &mut FFSFile,
+) {
+    const GUID_SIZE: usize = 16;
+    const DATA_OFFSET_START: usize = 16;
+    const ATTRIBUTES_START: usize = 18;
+    const GUID_DEFINED_HEADER_SIZE: usize = 20;
+    const PROCESSING_REQUIRED: u16 = 0x01;
+
+    if section_body.len() < GUID_DEFINED_HEADER_SIZE {
+        return;
+    }
+
+    let guid = guid_to_string(&section_body[..GUID_SIZE]);
+    let data_offset = u16::from_le_bytes([
+        section_body[DATA_OFFSET_START],
+        section_body[DATA_OFFSET_START + 1],
+    ]) as usize;
+    let attributes = u16::from_le_bytes([
+        section_body[ATTRIBUTES_START],
+        section_body[ATTRIBUTES_START + 1],
+    ]);
+
+    // The data offset is relative to the start of the section
+    let encoded_data = section.get(data_offset..).unwrap_or_default();
+
+    let decoded = match guid.as_str() {
+        // LZMA
+        "EE4E5898-3914-4259-9D6E-DC7BD79403CF" => lzma_decompress(encoded_data),
+        // LZMA, with x86 branch conversion
+        "D42AE6BD-1352-4BFB-909A-CA72A6EAE889" => lzma_decompress(encoded_data).map(|mut data| {
+            x86_convert(&mut data);
+            data
+        }),
+        // Tiano
+        "A31280AD-481E-41B6-95E8-127F4C984779" => tiano_decompress(encoded_data),
+        // Unencoded data may be processed as is, e.g. CRC32 sections
+        _ if (attributes & PROCESSING_REQUIRED) == 0 => Some(encoded_data.to_vec()),
+        _ => None,
+    };
+
+    match decoded {
+        None => {
+            warn!(
+                "Unable to decode UEFI GUID defined section {guid} in file {}",
+                file.guid
+            );
+            file.outputs.push(SectionOutput::Data {
+                name: format!("guid_defined_{guid}.bin"),
+                data: encoded_data.to_vec(),
+            });
+        }
+        Some(decoded_data) => parse_encapsulated_sections(&decoded_data, depth, file),
+    }
+}
+
+/// Parses the sections contained in an encapsulation section; if they are malformed, the data is kept as is
+fn parse_encapsulated_sections(section_data: &[u8], depth: usize, file: &mut FFSFile) {
+    if !parse_sections(section_data, depth + 1, file) {
+        file.outputs.push(SectionOutput::Data {
+            name: "encapsulated.bin".to_string(),
+            data: section_data.to_vec(),
+        });
+    }
+}
+
+/// Returns the output file name for a leaf section
+fn section_file_name(section_type: usize) -> String {
+    match section_type {
+        0x10 => "pe32.efi".to_string(),
+        0x11 => "pic.bin".to_string(),
+        0x12 => "te.efi".to_string(),
+        0x13 => "dxe_depex.bin".to_string(),
+        0x14 => "version.bin".to_string(),
+        0x16 => "compatibility16.bin".to_string(),
+        0x19 => "raw.bin".to_string(),
+        0x1B => "pei_depex.bin".to_string(),
+        0x1C => "mm_depex.bin".to_string(),
+        _ => format!("section_{section_type:02X}.bin"),
+    }
+}
+
+/// Decompresses UEFI LZMA data, which has a standard LZMA header
+fn lzma_decompress(compressed_data: &[u8]) -> Option<Vec<u8>> {
+    const MEM_LIMIT: u64 = 1024 * 1024 * 1024;
+
+    let decoder = Stream::new_lzma_decoder(MEM_LIMIT).ok()?;
+    let mut decompressed_data: Vec<u8> = vec![];
+    let max_decompressed_size = decompression_limit();
+
+    // Decompress one byte more than the limit, to tell if the limit was reached
+    XzDecoder::new_stream(compressed_data, decoder)
+        .take(max_decompressed_size as u64 + 1)
+        .read_to_end(&mut decompressed_data)
+        .ok()?;
+
+    if decompressed_data.len() > max_decompressed_size {
+        report_decompression_limit_reached();
+        decompressed_data.truncate(max_decompressed_size);
+    }
+
+    Some(decompressed_data)
+}
+
+/// Reverses the x86 branch conversion applied to executable code before it was LZMA compressed, converting the
+/// absolute addresses of CALL and JMP instructions back to relative addresses
+fn x86_convert(data: &mut [u8]) {
+    const INSTRUCTION_SIZE: usize = 5;
+
+    // True if the byte is the most significant byte of a 32-bit address that is within 16MB of 0
+    let is_ms_byte = |b: u8| b == 0 || b == 0xFF;
+
+    if data.len() < INSTRUCTION_SIZE {
+        return;
+    }
+
+    let limit = data.len() - (INSTRUCTION_SIZE - 1);
+    let mut mask: u32 = 0;
+    let mut position: usize = 0;
+
+    loop {
+        let mut p = position;
+
+        while p < limit && (data[p] & 0xFE) != 0xE8 {
+            p += 1;
+        }
+
+        let distance = p - position;
+        position = p;
+
+        if p >= limit {
+            break;
+        }
+
+        if distance > 2 {
+            mask = 0;
+        } else {
+            mask >>= distance;
+
+            if mask != 0
+                && (mask > 4 || mask == 3 || is_ms_byte(data[p + (mask as usize >> 1) + 1]))
+            {
+                mask = (mask >> 1) | 4;
+                position += 1;
+                continue;
+            }
+        }
+
+        if is_ms_byte(data[p + 4]) {
+            let mut value =
+                u32::from_le_bytes([data[p + 1], data[p + 2], data[p + 3], data[p + 4]]);
+            let current = (position + INSTRUCTION_SIZE) as u32;
+
+            position += INSTRUCTION_SIZE;
+            value = value.wrapping_sub(current);
+
+            if mask != 0 {
+                let shift = (mask & 6) << 2;
+
+                if is_ms_byte((value >> shift) as u8) {
+                    value ^= (0x100u32 << shift).wrapping_sub(1);
+                    value = value.wrapping_sub(current);
+                }
+
+                mask = 0;
+            }
+
+            data[p + 1] = value as u8;
+            data[p + 2] = (value >> 8) as u8;
+            data[p + 3] = (value >> 16) as u8;
+            data[p + 4] = 0u8.wrapping_sub(((value >> 24) & 1) as u8);
+        } else {
+            mask = (mask >> 1) | 4;
+            position += 1;
+        }
+    }
+}
+
+/// Converts a null terminated UCS-2 string to a String
+fn ucs2_to_string(data: &[u8]) -> String {
+    let characters: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|c| *c != 0)
+        .collect();
+
+    String::from_utf16_lossy(&characters)
+}
+
+/// Writes each file to its own directory
+fn write_files(chroot: &Chroot, directory: &str, files: &[FFSFile]) -> bool {
+    let mut used_names: HashSet<String> = HashSet::new();
+
+    for file in files {
+        let file_directory = chroot.safe_path_join(
+            directory,
+            unique_name(&mut used_names, file.name.as_ref().unwrap_or(&file.guid)),
+        );
+
+        if !chroot.create_directory(&file_directory)
+            || !write_outputs(chroot, &file_directory, &file.outputs)
+        {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Writes a file's section data to the file's directory
+fn write_outputs(chroot: &Chroot, directory: &str, outputs: &[SectionOutput]) -> bool {
+    let mut used_names: HashSet<String> = HashSet::new();
+
+    for output in outputs {
+        match output {
+            SectionOutput::Data { name, data } => {
+                let file_path =
+                    chroot.safe_path_join(directory, unique_name(&mut used_names, name));
+
+                if !chroot.create_file(&file_path, data) {
+                    return false;
+                }
+            }
+            SectionOutput::Volume(files) => {
+                let volume_directory =
+                    chroot.safe_path_join(directory, unique_name(&mut used_names, "volume"));
+
+                if !chroot.create_directory(&volume_directory)
+                    || !write_files(chroot, &volume_directory, files)
+                {
+                    return false;
+                }
+            }
+        }
+    }
+
+    true
+}
+
+/// Returns a file name that is safe to use and has not already been used, by appending a number to the name if needed
+fn unique_name(used_names: &mut HashSet<String>, name: &str) -> String {
+    let safe_name: String = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() || "._- ".contains(c) {
+            true => c,
+            false => '_',
+        })
+        .collect();
+
+    let (stem, extension) = match safe_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem.to_string(), format!(".{extension}")),
+        _ => (safe_name.clone(), "".to_string()),
+    };
+
+    let mut unique = safe_name;
+    let mut count: usize = 1;
+
+    while used_names.contains(&unique) {
+        unique = format!("{stem}_{count}{extension}");
+        count += 1;
+    }
+
+    used_names.insert(unique.clone());
+    unique
+}
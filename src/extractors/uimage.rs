@@ -1,6 +1,15 @@
 use crate::common::crc32;
-use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::extractors::bzip2::bzip2_decompressor;
+use crate::extractors::common::{
+    Chroot, ExtractionResult, Extractor, ExtractorType, InternalExtractor,
+};
+use crate::extractors::gzip::gzip_decompress;
+use crate::extractors::lz4::lz4_decompress;
+use crate::extractors::lzma::lzma_decompress;
+use crate::extractors::lzop::lzop_decompress;
+use crate::extractors::zstd::zstd_decompress;
 use crate::structures::uimage::parse_uimage_header;
+use log::warn;
 
 /// Describes the internal extractor for carving uImage files to disk
 ///
@@ -13,6 +22,7 @@ use crate::structures::uimage::parse_uimage_header;
 /// match uimage_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -31,6 +41,7 @@ pub fn uimage_extractor() -> Extractor {
     }
 }
 
+/// Carves the uImage payload to a file named after the image, and decompresses compressed payloads to `decompressed.bin`
 pub fn extract_uimage(
     file_data: &[u8],
     offset: usize,
@@ -77,6 +88,16 @@ pub fn extract_uimage(
                     let output_file = format!("{file_base_name}.{OUTPUT_FILE_EXT}");
 
                     result.success = chroot.create_file(&output_file, image_data);
+
+                    // The carved payload is still useful if it fails to decompress, so this does not affect the result
+                    if result.success {
+                        decompress_payload(
+                            &file_data[..image_data_end],
+                            image_data_start,
+                            &uimage_header.compression_type,
+                            output_directory,
+                        );
+                    }
                 }
             }
         }
@@ -84,3 +105,28 @@ pub fn extract_uimage(
 
     result
 }
+
+/// Decompresses the uImage payload with the internal decompressor for its compression type
+fn decompress_payload(
+    file_data: &[u8],
+    offset: usize,
+    compression_type: &str,
+    output_directory: Option<&str>,
+) {
+    let decompressor: InternalExtractor = match compression_type {
+        "gzip" => gzip_decompress,
+        "bzip2" => bzip2_decompressor,
+        "lzma" => lzma_decompress,
+        // U-Boot expects LZO compressed payloads to be in the lzop file format
+        "lzo" => lzop_decompress,
+        "lz4" => lz4_decompress,
+        "zstd" => zstd_decompress,
+        _ => return,
+    };
+
+    if !decompressor(file_data, offset, output_directory).success {
+        warn!(
+            "Failed to decompress {compression_type} compressed uImage data at offset {offset:#X}"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::wasm::parse_wasm_module;
+
+/// Defines the internal extractor function for carving WebAssembly modules
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::wasm::wasm_extractor;
+///
+/// match wasm_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn wasm_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_wasm_module),
+        do_not_recurse: true,
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for carving WebAssembly modules to disk
+pub fn extract_wasm_module(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTFILE_NAME: &str = "module.wasm";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(wasm_module) = parse_wasm_module(&file_data[offset..]) {
+        result.size = Some(wasm_module.size);
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+            result.success = chroot.carve_file(OUTFILE_NAME, file_data, offset, wasm_module.size);
+        }
+    }
+
+    result
+}
@@ -0,0 +1,202 @@
+//! Support for extractors implemented as sandboxed WebAssembly modules.
+//!
+//! WebAssembly extractors are loaded and executed with [wasmi](https://crates.io/crates/wasmi); no host functions
+//! are provided to the module, so extractors have no access to the file system, network, or any other host resources.
+//! Execution time and memory usage are also limited.
+//!
+//! WebAssembly extractor modules must export the following:
+//!
+//! - `memory`: The module's linear memory
+//! - `alloc(size: i32) -> i32`: Allocates `size` bytes of memory for the input data, returning a pointer to the allocated buffer
+//! - `extract(ptr: i32, len: i32) -> i64`: Extracts the input data located at `ptr`; returns a pointer to the output buffer
+//!   in the upper 32 bits, and the size of the output buffer in the lower 32 bits. A negative return value indicates failure.
+//!
+//! The output buffer is a list of extracted files, each encoded as:
+//!
+//! - File name length, u32 little endian
+//! - File name, UTF-8; may include sub-directories
+//! - File data length, u32 little endian
+//! - File data
+use crate::extractors::common::{Chroot, ExtractionResult};
+use log::{debug, error};
+use std::fs;
+use std::path::Path;
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// Maximum number of WebAssembly instructions an extractor may execute (approximately)
+const MAX_FUEL: u64 = 50_000_000_000;
+
+/// Maximum size of an extractor's linear memory
+const MAX_MEMORY_SIZE: usize = 1024 * 1024 * 1024;
+
+/// Returned when execution of a WebAssembly extractor fails
+#[derive(Debug, Default, Clone)]
+pub struct WasmExtractorError {
+    pub message: String,
+}
+
+impl WasmExtractorError {
+    fn new(message: impl ToString) -> Self {
+        WasmExtractorError {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Describes a file extracted by a WebAssembly extractor
+#[derive(Debug, Default, Clone)]
+pub struct WasmExtractedFile {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// Runs the WebAssembly extractor module located at `module_path` against the provided data,
+/// and writes the extracted files to the output directory.
+pub fn execute_wasm_extractor(
+    module_path: &str,
+    extractor_data: &[u8],
+    output_directory: &str,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    match run_wasm_extractor(module_path, extractor_data) {
+        Err(e) => {
+            error!("WebAssembly extractor {module_path} failed: {}", e.message);
+        }
+        Ok(extracted_files) => {
+            let chroot = Chroot::new(Some(output_directory));
+
+            result.success = true;
+
+            for extracted_file in extracted_files {
+                debug!(
+                    "WebAssembly extractor {module_path} extracted {} bytes to {}",
+                    extracted_file.data.len(),
+                    extracted_file.name
+                );
+
+                // Create any parent directories for this file
+                if let Some(parent_directory) = Path::new(&extracted_file.name).parent() {
+                    if !parent_directory.as_os_str().is_empty() {
+                        chroot.create_directory(parent_directory.display().to_string());
+                    }
+                }
+
+                if !chroot.create_file(&extracted_file.name, &extracted_file.data) {
+                    result.success = false;
+                    break;
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Loads and executes a WebAssembly extractor module, returning the list of files it extracted
+pub fn run_wasm_extractor(
+    module_path: &str,
+    extractor_data: &[u8],
+) -> Result<Vec<WasmExtractedFile>, WasmExtractorError> {
+    let wasm_module = fs::read(module_path).map_err(WasmExtractorError::new)?;
+
+    let input_size = i32::try_from(extractor_data.len())
+        .map_err(|_| WasmExtractorError::new("input data is too large"))?;
+
+    let mut config = Config::default();
+    config.consume_fuel(true);
+
+    let engine = Engine::new(&config);
+    let module = Module::new(&engine, &wasm_module).map_err(WasmExtractorError::new)?;
+
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_MEMORY_SIZE)
+        .build();
+
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits: &mut StoreLimits| limits);
+    store.set_fuel(MAX_FUEL).map_err(WasmExtractorError::new)?;
+
+    // No host functions are defined; modules that import anything will fail to instantiate
+    let linker = <Linker<StoreLimits>>::new(&engine);
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .and_then(|instance| instance.start(&mut store))
+        .map_err(WasmExtractorError::new)?;
+
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or(WasmExtractorError::new("module does not export its memory"))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "alloc")
+        .map_err(WasmExtractorError::new)?;
+    let extract = instance
+        .get_typed_func::<(i32, i32), i64>(&store, "extract")
+        .map_err(WasmExtractorError::new)?;
+
+    // Copy the input data into the module's memory
+    let input_ptr = alloc
+        .call(&mut store, input_size)
+        .map_err(WasmExtractorError::new)?;
+    memory
+        .write(&mut store, input_ptr as u32 as usize, extractor_data)
+        .map_err(WasmExtractorError::new)?;
+
+    let output = extract
+        .call(&mut store, (input_ptr, input_size))
+        .map_err(WasmExtractorError::new)?;
+
+    if output < 0 {
+        return Err(WasmExtractorError::new(format!(
+            "extraction failed with error code {output}"
+        )));
+    }
+
+    let output_ptr = (output >> 32) as usize;
+    let output_size = (output & 0xFFFFFFFF) as usize;
+
+    let output_data = memory
+        .data(&store)
+        .get(output_ptr..output_ptr + output_size)
+        .ok_or(WasmExtractorError::new("output buffer is out of bounds"))?;
+
+    parse_wasm_output(output_data)
+}
+
+/// Parses the list of extracted files returned by a WebAssembly extractor
+fn parse_wasm_output(output_data: &[u8]) -> Result<Vec<WasmExtractedFile>, WasmExtractorError> {
+    const LENGTH_SIZE: usize = 4;
+
+    let mut extracted_files: Vec<WasmExtractedFile> = vec![];
+    let mut offset: usize = 0;
+
+    // Reads a length-prefixed field from the output buffer, advancing the offset
+    let next_field = |offset: &mut usize| -> Result<&[u8], WasmExtractorError> {
+        let invalid_output = || WasmExtractorError::new("output buffer is malformed");
+
+        let length_bytes = output_data
+            .get(*offset..*offset + LENGTH_SIZE)
+            .ok_or_else(invalid_output)?;
+        let length = u32::from_le_bytes(length_bytes.try_into().unwrap()) as usize;
+
+        let field_start = *offset + LENGTH_SIZE;
+        let field = output_data
+            .get(field_start..field_start + length)
+            .ok_or_else(invalid_output)?;
+
+        *offset = field_start + length;
+        Ok(field)
+    };
+
+    while offset < output_data.len() {
+        let name = String::from_utf8(next_field(&mut offset)?.to_vec())
+            .map_err(|_| WasmExtractorError::new("extracted file name is not valid UTF-8"))?;
+        let data = next_field(&mut offset)?.to_vec();
+
+        extracted_files.push(WasmExtractedFile { name, data });
+    }
+
+    Ok(extracted_files)
+}
@@ -0,0 +1,58 @@
+use crate::extractors::common::{Chroot, ExtractionResult, Extractor, ExtractorType};
+use crate::structures::webasset::parse_minified_script;
+
+/// Defines the internal extractor function for carving minified JavaScript
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::webasset::minified_js_extractor;
+///
+/// match minified_js_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn minified_js_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_minified_js),
+        do_not_recurse: true,
+        ..Default::default()
+    }
+}
+
+/// Internal extractor for carving minified JavaScript to disk; the offset must be the start of the script
+pub fn extract_minified_js(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    const OUTFILE_NAME: &str = "script.js";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Ok(script) = parse_minified_script(&file_data[offset..], 0) {
+        result.size = Some(script.size);
+        result.success = true;
+
+        if output_directory.is_some() {
+            let chroot = Chroot::new(output_directory);
+            result.success = chroot.carve_file(OUTFILE_NAME, file_data, offset, script.size);
+        }
+    }
+
+    result
+}
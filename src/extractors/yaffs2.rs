@@ -1,6 +1,73 @@
-use crate::extractors;
+use crate::extractors::common::{
+    Chroot, ExtractionResult, Extractor, ExtractorType, SOURCE_FILE_PLACEHOLDER,
+};
+use crate::signatures::yaffs::{get_page_size, get_spare_size};
+use crate::structures::yaffs::{
+    YAFFSFileHeader, YAFFSObject, YAFFSPackedTags, parse_yaffs_file_header, parse_yaffs_obj_header,
+    parse_yaffs_packed_tags,
+};
+use log::warn;
+use std::collections::{HashMap, HashSet};
 
-/// Describes how to run the unyaffs utility to extract YAFFS2 file systems
+/// YAFFS object ID of the root directory
+const ROOT_OBJ_ID: usize = 1;
+
+/// YAFFS object types
+const YAFFS_OBJECT_TYPE_FILE: usize = 1;
+const YAFFS_OBJECT_TYPE_SYMLINK: usize = 2;
+const YAFFS_OBJECT_TYPE_DIRECTORY: usize = 3;
+const YAFFS_OBJECT_TYPE_HARDLINK: usize = 4;
+const YAFFS_OBJECT_TYPE_SPECIAL: usize = 5;
+
+/// File type bits of an inode mode
+const S_IFMT: usize = 0o170000;
+const S_IFSOCK: usize = 0o140000;
+const S_IFBLK: usize = 0o060000;
+const S_IFCHR: usize = 0o020000;
+const S_IFIFO: usize = 0o010000;
+
+/// Any of the executable bits of an inode mode
+const S_IXANY: usize = 0o111;
+
+/// Object header info (mode, size, etc) starts this many bytes into the object header
+const FILE_INFO_OFFSET: usize = 268;
+
+/// The latest version of a YAFFS object header
+#[derive(Debug, Default, Clone)]
+struct YAFFSObjectEntry {
+    sequence_number: usize,
+    object: YAFFSObject,
+    info: YAFFSFileHeader,
+}
+
+/// The latest version of a YAFFS data chunk
+#[derive(Debug, Default, Clone)]
+struct YAFFSDataChunk {
+    sequence_number: usize,
+    data_offset: usize,
+    byte_count: usize,
+}
+
+/// All objects and data chunks found in a YAFFS image
+#[derive(Debug, Default, Clone)]
+struct YAFFSImage {
+    size: usize,
+    page_size: usize,
+    objects: HashMap<usize, YAFFSObjectEntry>,
+    chunks: HashMap<(usize, usize), YAFFSDataChunk>,
+}
+
+/// Describes the NAND geometry used by a YAFFS image
+#[derive(Debug, Default, Clone)]
+struct YAFFSLayout {
+    page_size: usize,
+    spare_size: usize,
+    /// Offset of the packed tags inside the spare data, which depends on the ECC layout
+    tags_offset: usize,
+    endianness: String,
+}
+
+/// Defines the internal extractor function for extracting YAFFS2 file systems
 ///
 /// ```
 /// use std::io::ErrorKind;
@@ -11,6 +78,7 @@ use crate::extractors;
 /// match yaffs2_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -22,15 +90,344 @@ use crate::extractors;
 ///     }
 /// }
 /// ```
-pub fn yaffs2_extractor() -> extractors::common::Extractor {
-    extractors::common::Extractor {
-        utility: extractors::common::ExtractorType::External("unyaffs".to_string()),
+pub fn yaffs2_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(extract_yaffs2),
+        fallback: Some(Box::new(unyaffs_extractor())),
+        ..Default::default()
+    }
+}
+
+/// Describes the unyaffs utility, which is run on YAFFS2 images that the internal extractor fails to extract
+fn unyaffs_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::External("unyaffs".to_string()),
         extension: "img".to_string(),
         arguments: vec![
-            extractors::common::SOURCE_FILE_PLACEHOLDER.to_string(),
+            SOURCE_FILE_PLACEHOLDER.to_string(),
             "yaffs-root".to_string(),
         ],
         exit_codes: vec![0],
         ..Default::default()
     }
 }
+
+/// Internal YAFFS2 extractor; page size, spare size, and the location of the tags in the spare data are auto-detected
+pub fn extract_yaffs2(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    // YAFFS files will be extracted to this sub-directory of the output directory
+    const OUTPUT_DIRECTORY_NAME: &str = "yaffs-root";
+
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    if let Some(yaffs_layout) = detect_yaffs_layout(&file_data[offset..]) {
+        let yaffs_image = process_yaffs_chunks(file_data, offset, &yaffs_layout);
+
+        // The root directory must have at least one entry
+        if yaffs_image
+            .objects
+            .iter()
+            .any(|(obj_id, entry)| *obj_id != ROOT_OBJ_ID && entry.object.parent_id == ROOT_OBJ_ID)
+        {
+            result.success = true;
+            result.size = Some(yaffs_image.size);
+
+            // Do extraction, if an output directory was provided
+            if output_directory.is_some() {
+                let chroot = Chroot::new(output_directory);
+                let mut file_count: usize = 0;
+
+                if chroot.create_directory(OUTPUT_DIRECTORY_NAME) {
+                    let yaffs_chroot =
+                        Chroot::new(Some(&chroot.chrooted_path(OUTPUT_DIRECTORY_NAME)));
+                    let mut visited_directories: HashSet<usize> = HashSet::new();
+
+                    file_count = extract_yaffs_directory(
+                        file_data,
+                        &yaffs_image,
+                        ROOT_OBJ_ID,
+                        "",
+                        &yaffs_chroot,
+                        &mut visited_directories,
+                    );
+                }
+
+                // If no files were extracted, extraction was a failure
+                if file_count == 0 {
+                    result.success = false;
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Detects the page size, spare size, endianness, and tags location of a YAFFS image
+fn detect_yaffs_layout(yaffs_data: &[u8]) -> Option<YAFFSLayout> {
+    // Good blocks have a bad block marker of 0xFFFF at the start of the spare data, depending on the ECC layout
+    const BAD_BLOCK_MARKER: &[u8] = b"\xFF\xFF";
+    const BIG_ENDIAN_FIRST_BYTE: u8 = 0;
+
+    let endianness = match yaffs_data.first() {
+        Some(&BIG_ENDIAN_FIRST_BYTE) => "big",
+        _ => "little",
+    };
+
+    let page_size = get_page_size(yaffs_data).ok()?;
+    let spare_size = get_spare_size(yaffs_data, page_size, endianness).ok()?;
+
+    let spare_data = yaffs_data.get(page_size..page_size + spare_size)?;
+
+    // Tags are stored at the beginning of the spare data, or after the bad block marker, if present
+    let tags_offset = match spare_data.starts_with(BAD_BLOCK_MARKER) {
+        true => BAD_BLOCK_MARKER.len(),
+        false => 0,
+    };
+
+    // The first chunk should be an object header
+    match parse_yaffs_packed_tags(&spare_data[tags_offset..], endianness) {
+        Ok(tags) if tags.chunk_id == 0 => Some(YAFFSLayout {
+            page_size,
+            spare_size,
+            tags_offset,
+            endianness: endianness.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+/// Walks all chunks in the YAFFS image, collecting the latest version of each object header and data chunk
+fn process_yaffs_chunks(file_data: &[u8], offset: usize, layout: &YAFFSLayout) -> YAFFSImage {
+    let mut yaffs_image = YAFFSImage {
+        page_size: layout.page_size,
+        ..Default::default()
+    };
+
+    let chunk_size = layout.page_size + layout.spare_size;
+    let mut chunk_offset = offset;
+
+    while let Some(chunk_data) = file_data.get(chunk_offset..chunk_offset + chunk_size) {
+        let page_data = &chunk_data[..layout.page_size];
+        let tags_data = &chunk_data[layout.page_size + layout.tags_offset..];
+
+        match parse_yaffs_packed_tags(tags_data, &layout.endianness) {
+            Err(_) => {
+                // Erased chunks may be interspersed throughout the image; anything else is the end of the image
+                if !chunk_data.iter().all(|b| *b == 0xFF) {
+                    break;
+                }
+            }
+            Ok(tags) => {
+                if !process_yaffs_chunk(&mut yaffs_image, &tags, page_data, chunk_offset, layout) {
+                    break;
+                }
+
+                yaffs_image.size = chunk_offset + chunk_size - offset;
+            }
+        }
+
+        chunk_offset += chunk_size;
+    }
+
+    yaffs_image
+}
+
+/// Records a single object header or data chunk; returns false if the chunk is invalid
+fn process_yaffs_chunk(
+    yaffs_image: &mut YAFFSImage,
+    tags: &YAFFSPackedTags,
+    page_data: &[u8],
+    chunk_offset: usize,
+    layout: &YAFFSLayout,
+) -> bool {
+    if tags.chunk_id == 0 {
+        let object = match parse_yaffs_obj_header(page_data, &layout.endianness) {
+            Ok(object) => object,
+            Err(_) => return false,
+        };
+
+        let info = match parse_yaffs_file_header(&page_data[FILE_INFO_OFFSET..], &layout.endianness)
+        {
+            Ok(info) => info,
+            Err(_) => return false,
+        };
+
+        // Chunks with higher sequence numbers were written later; later chunks in the same block were also written later
+        let is_latest = match yaffs_image.objects.get(&tags.obj_id) {
+            Some(existing_entry) => tags.sequence_number >= existing_entry.sequence_number,
+            None => true,
+        };
+
+        if is_latest {
+            yaffs_image.objects.insert(
+                tags.obj_id,
+                YAFFSObjectEntry {
+                    sequence_number: tags.sequence_number,
+                    object,
+                    info,
+                },
+            );
+        }
+    } else {
+        if tags.byte_count > layout.page_size {
+            return false;
+        }
+
+        let chunk_key = (tags.obj_id, tags.chunk_id);
+
+        let is_latest = match yaffs_image.chunks.get(&chunk_key) {
+            Some(existing_chunk) => tags.sequence_number >= existing_chunk.sequence_number,
+            None => true,
+        };
+
+        if is_latest {
+            yaffs_image.chunks.insert(
+                chunk_key,
+                YAFFSDataChunk {
+                    sequence_number: tags.sequence_number,
+                    data_offset: chunk_offset,
+                    byte_count: tags.byte_count,
+                },
+            );
+        }
+    }
+
+    true
+}
+
+/// Recursively extracts the contents of a YAFFS directory, returns the number of extracted files/directories
+fn extract_yaffs_directory(
+    file_data: &[u8],
+    yaffs_image: &YAFFSImage,
+    directory_id: usize,
+    directory_path: &str,
+    chroot: &Chroot,
+    visited_directories: &mut HashSet<usize>,
+) -> usize {
+    let mut file_count: usize = 0;
+
+    // Sanity check, a directory should only be processed once, else infinite recursion could ensue
+    if !visited_directories.insert(directory_id) {
+        return file_count;
+    }
+
+    for (obj_id, entry) in yaffs_image.objects.iter() {
+        if entry.object.parent_id != directory_id || *obj_id == directory_id {
+            continue;
+        }
+
+        let file_path = chroot.safe_path_join(directory_path, &entry.object.name);
+
+        // Hard links are extracted as copies of the file they link to
+        let mut target_id = *obj_id;
+        let mut target_entry = entry;
+        if entry.object.obj_type == YAFFS_OBJECT_TYPE_HARDLINK {
+            match yaffs_image.objects.get(&entry.info.equiv_id) {
+                Some(equiv_entry) => {
+                    target_id = entry.info.equiv_id;
+                    target_entry = equiv_entry;
+                }
+                None => {
+                    warn!("YAFFS hard link {file_path} points to a non-existent object");
+                    continue;
+                }
+            }
+        }
+
+        let extraction_success = match target_entry.object.obj_type {
+            YAFFS_OBJECT_TYPE_DIRECTORY => chroot.create_directory(&file_path),
+            YAFFS_OBJECT_TYPE_FILE => {
+                let file_contents = yaffs_file_data(
+                    file_data,
+                    yaffs_image,
+                    target_id,
+                    target_entry.info.file_size,
+                );
+                chroot.create_file(&file_path, &file_contents)
+            }
+            YAFFS_OBJECT_TYPE_SYMLINK => {
+                chroot.create_symlink(&file_path, &target_entry.info.alias)
+            }
+            YAFFS_OBJECT_TYPE_SPECIAL => {
+                let rdev = target_entry.info.rdev;
+                let major = (rdev >> 8) & 0xFFF;
+                let minor = (rdev & 0xFF) | ((rdev >> 12) & 0xFFF00);
+
+                match target_entry.info.mode & S_IFMT {
+                    S_IFCHR => chroot.create_character_device(&file_path, major, minor),
+                    S_IFBLK => chroot.create_block_device(&file_path, major, minor),
+                    S_IFIFO => chroot.create_fifo(&file_path),
+                    S_IFSOCK => chroot.create_socket(&file_path),
+                    _ => {
+                        warn!("Unknown special file type for YAFFS file {file_path}");
+                        continue;
+                    }
+                }
+            }
+            _ => {
+                warn!("Unknown object type for YAFFS file {file_path}");
+                continue;
+            }
+        };
+
+        if extraction_success {
+            file_count += 1;
+
+            if target_entry.object.obj_type == YAFFS_OBJECT_TYPE_DIRECTORY {
+                file_count += extract_yaffs_directory(
+                    file_data,
+                    yaffs_image,
+                    target_id,
+                    &file_path,
+                    chroot,
+                    visited_directories,
+                );
+            } else if target_entry.object.obj_type == YAFFS_OBJECT_TYPE_FILE
+                && (target_entry.info.mode & S_IXANY) != 0
+            {
+                // Make executable files executable
+                chroot.make_executable(&file_path);
+            }
+        } else {
+            warn!("Failed to extract YAFFS file {file_path}");
+        }
+    }
+
+    file_count
+}
+
+/// Reconstructs a file's contents from its data chunks
+fn yaffs_file_data(
+    file_data: &[u8],
+    yaffs_image: &YAFFSImage,
+    obj_id: usize,
+    file_size: usize,
+) -> Vec<u8> {
+    // Sanity limit on file size, as a corrupt file size field could otherwise exhaust memory
+    const MAX_FILE_SIZE: usize = 1024 * 1024 * 1024;
+
+    let file_size = std::cmp::min(file_size, MAX_FILE_SIZE);
+    let mut file_contents: Vec<u8> = vec![0; file_size];
+
+    // Each data chunk holds up to one page of file data; data chunk IDs start at 1, and missing chunks are holes in the file
+    for chunk_index in 0..file_size.div_ceil(yaffs_image.page_size) {
+        if let Some(chunk) = yaffs_image.chunks.get(&(obj_id, chunk_index + 1)) {
+            let file_offset = chunk_index * yaffs_image.page_size;
+            let copy_size = std::cmp::min(chunk.byte_count, file_size - file_offset);
+
+            if let Some(chunk_data) =
+                file_data.get(chunk.data_offset..chunk.data_offset + copy_size)
+            {
+                file_contents[file_offset..file_offset + copy_size].copy_from_slice(chunk_data);
+            }
+        }
+    }
+
+    file_contents
+}
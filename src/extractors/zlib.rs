@@ -1,5 +1,6 @@
 use crate::extractors::common::{ExtractionResult, Extractor, ExtractorType};
 use crate::extractors::inflate;
+use crate::structures::zlib::{ZLIB_HEADER_SIZE, parse_zlib_header};
 
 /// Size of the checksum that follows the ZLIB deflate data stream
 pub const CHECKSUM_SIZE: usize = 4;
@@ -15,6 +16,7 @@ pub const CHECKSUM_SIZE: usize = 4;
 /// match zlib_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -39,31 +41,109 @@ pub fn zlib_decompress(
     offset: usize,
     output_directory: Option<&str>,
 ) -> ExtractionResult {
-    // Size of the zlib header
-    const HEADER_SIZE: usize = 2;
-
     let mut exresult = ExtractionResult {
         ..Default::default()
     };
 
-    // Do the decompression, ignoring the ZLIB header
-    let inflate_result =
-        inflate::inflate_decompressor(file_data, offset + HEADER_SIZE, output_directory);
+    // Validate the ZLIB header before attempting decompression
+    if let Some(zlib_data) = file_data.get(offset..) {
+        if parse_zlib_header(zlib_data).is_ok() {
+            // Do the decompression, ignoring the ZLIB header
+            let inflate_result = inflate::inflate_decompressor(
+                file_data,
+                offset + ZLIB_HEADER_SIZE,
+                output_directory,
+            );
+
+            // Check that the data decompressed OK
+            if inflate_result.success {
+                // Calculate the ZLIB checksum offsets
+                let checksum_start = offset + ZLIB_HEADER_SIZE + inflate_result.size;
+                let checksum_end = checksum_start + CHECKSUM_SIZE;
+
+                // Get the ZLIB checksum
+                if let Some(adler32_checksum_bytes) = file_data.get(checksum_start..checksum_end) {
+                    let reported_checksum =
+                        u32::from_be_bytes(adler32_checksum_bytes.try_into().unwrap());
+
+                    // Make sure the checksum matches; the reported size includes the header and checksum, for accurate carving
+                    if reported_checksum == inflate_result.adler32 {
+                        exresult.success = true;
+                        exresult.size =
+                            Some(ZLIB_HEADER_SIZE + inflate_result.size + CHECKSUM_SIZE);
+                    }
+                }
+            }
+        }
+    }
+
+    exresult
+}
+
+/// Defines the internal extractor function for decompressing zlib streams found by a brute force scan
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::process::Command;
+/// use binwalk::extractors::common::ExtractorType;
+/// use binwalk::extractors::zlib::zlib_brute_extractor;
+///
+/// match zlib_brute_extractor().utility {
+///     ExtractorType::None => panic!("Invalid extractor type of None"),
+///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
+///     ExtractorType::External(cmd) => {
+///         if let Err(e) = Command::new(&cmd).output() {
+///             if e.kind() == ErrorKind::NotFound {
+///                 panic!("External extractor '{}' not found", cmd);
+///             } else {
+///                 panic!("Failed to execute external extractor '{}': {}", cmd, e);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn zlib_brute_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(zlib_partial_decompress),
+        ..Default::default()
+    }
+}
 
-    // Check that the data decompressed OK
-    if inflate_result.success {
-        // Calculate the ZLIB checksum offsets
-        let checksum_start = offset + HEADER_SIZE + inflate_result.size;
-        let checksum_end = checksum_start + CHECKSUM_SIZE;
+/// Internal extractor for decompressing ZLIB data that may be truncated or corrupt; everything that can be
+/// decompressed is kept, and the checksum is not required
+pub fn zlib_partial_decompress(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut exresult = ExtractionResult {
+        ..Default::default()
+    };
 
-        // Get the ZLIB checksum
-        if let Some(adler32_checksum_bytes) = file_data.get(checksum_start..checksum_end) {
-            let reported_checksum = u32::from_be_bytes(adler32_checksum_bytes.try_into().unwrap());
+    if let Some(zlib_data) = file_data.get(offset..) {
+        if parse_zlib_header(zlib_data).is_ok() {
+            let inflate_result = inflate::inflate_partial_decompressor(
+                file_data,
+                offset + ZLIB_HEADER_SIZE,
+                output_directory,
+            );
 
-            // Make sure the checksum matches
-            if reported_checksum == inflate_result.adler32 {
+            if inflate_result.success {
                 exresult.success = true;
-                exresult.size = Some(HEADER_SIZE + inflate_result.size + CHECKSUM_SIZE);
+                exresult.size = Some(ZLIB_HEADER_SIZE + inflate_result.size);
+
+                // Include the checksum in the reported size if the stream is complete
+                let checksum_start = offset + exresult.size.unwrap();
+                if let Some(adler32_checksum_bytes) =
+                    file_data.get(checksum_start..checksum_start + CHECKSUM_SIZE)
+                {
+                    if u32::from_be_bytes(adler32_checksum_bytes.try_into().unwrap())
+                        == inflate_result.adler32
+                    {
+                        exresult.size = Some(exresult.size.unwrap() + CHECKSUM_SIZE);
+                    }
+                }
             }
         }
     }
@@ -1,6 +1,12 @@
-use crate::extractors;
+use crate::common::{is_offset_safe, parser_deadline_exceeded};
+use crate::extractors::common::{
+    Chroot, ExtractionResult, Extractor, ExtractorType, SOURCE_FILE_PLACEHOLDER,
+};
+use crate::structures::zstd::{ZSTD_MAGIC, parse_skippable_frame};
+use ruzstd::decoding::StreamingDecoder;
+use std::io::Read;
 
-/// Describes how to run the zstd utility to extract ZSTD compressed files
+/// Defines the internal extractor function for decompressing ZSTD data
 ///
 /// ```
 /// use std::io::ErrorKind;
@@ -11,6 +17,7 @@ use crate::extractors;
 /// match zstd_extractor().utility {
 ///     ExtractorType::None => panic!("Invalid extractor type of None"),
 ///     ExtractorType::Internal(func) => println!("Internal extractor OK: {:?}", func),
+///     ExtractorType::Wasm(module) => println!("WebAssembly extractor OK: {}", module),
 ///     ExtractorType::External(cmd) => {
 ///         if let Err(e) = Command::new(&cmd).output() {
 ///             if e.kind() == ErrorKind::NotFound {
@@ -22,17 +29,153 @@ use crate::extractors;
 ///     }
 /// }
 /// ```
-pub fn zstd_extractor() -> extractors::common::Extractor {
-    extractors::common::Extractor {
-        utility: extractors::common::ExtractorType::External("zstd".to_string()),
+pub fn zstd_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::Internal(zstd_decompress),
+        fallback: Some(Box::new(zstd_utility_extractor())),
+        ..Default::default()
+    }
+}
+
+/// Describes the zstd utility, which is run on ZSTD data that the internal extractor fails to decompress
+fn zstd_utility_extractor() -> Extractor {
+    Extractor {
+        utility: ExtractorType::External("zstd".to_string()),
         extension: "zst".to_string(),
         arguments: vec![
             "-k".to_string(), // Don't delete input files (we do this ourselves)
             "-f".to_string(), // Force overwrite if output file, for some reason, exists (disables y/n prompts)
             "-d".to_string(), // Perform a decompression
-            extractors::common::SOURCE_FILE_PLACEHOLDER.to_string(),
+            SOURCE_FILE_PLACEHOLDER.to_string(),
         ],
         exit_codes: vec![0],
         ..Default::default()
     }
 }
+
+/// Internal extractor for decompressing ZSTD data; concatenated frames are decompressed to a single file,
+/// and skippable frames are ignored.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::zstd::zstd_decompress;
+///
+/// // "Hello, ZSTD! " repeated 16 times, compressed by the zstd utility, with a content checksum
+/// let frame: &[u8] = &[
+///     0x28, 0xb5, 0x2f, 0xfd, 0x04, 0x58, 0xa5, 0x00, 0x00, 0x68, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x2c,
+///     0x20, 0x5a, 0x53, 0x54, 0x44, 0x21, 0x20, 0x01, 0x00, 0x40, 0xa0, 0xe6, 0x17, 0xc2, 0x7a, 0xe0,
+///     0x7d,
+/// ];
+/// // A skippable frame with 4 bytes of user data
+/// let skippable_frame: &[u8] = &[0x50, 0x2a, 0x4d, 0x18, 0x04, 0x00, 0x00, 0x00, 0xde, 0xad, 0xbe, 0xef];
+///
+/// let zstd_data = [frame, skippable_frame, frame].concat();
+///
+/// let output_directory = std::env::temp_dir()
+///     .join("binwalk_zstd_doctest")
+///     .display()
+///     .to_string();
+/// # std::fs::remove_dir_all(&output_directory).ok();
+///
+/// let result = zstd_decompress(&zstd_data, 0, Some(&output_directory));
+///
+/// assert_eq!(result.success, true);
+/// assert_eq!(result.size, Some(zstd_data.len()));
+///
+/// let decompressed_file = std::path::Path::new(&output_directory).join("decompressed.bin");
+/// assert_eq!(std::fs::read(decompressed_file).unwrap(), "Hello, ZSTD! ".repeat(32).as_bytes());
+/// # std::fs::remove_dir_all(&output_directory).ok();
+/// ```
+pub fn zstd_decompress(
+    file_data: &[u8],
+    offset: usize,
+    output_directory: Option<&str>,
+) -> ExtractionResult {
+    let mut result = ExtractionResult {
+        ..Default::default()
+    };
+
+    let mut frame_count: usize = 0;
+    let mut next_frame_offset = offset;
+    let mut previous_frame_offset = None;
+
+    while is_offset_safe(file_data.len(), next_frame_offset, previous_frame_offset) {
+        let frame_data = &file_data[next_frame_offset..];
+
+        let frame_size = if let Ok(skippable_frame_size) = parse_skippable_frame(frame_data) {
+            // Skippable frames contain user data, not compressed data
+            if skippable_frame_size > frame_data.len() {
+                break;
+            }
+            skippable_frame_size
+        } else if frame_data.starts_with(ZSTD_MAGIC) {
+            match decompress_frame(frame_data, output_directory) {
+                None => break,
+                Some(zstd_frame_size) => {
+                    frame_count += 1;
+                    zstd_frame_size
+                }
+            }
+        } else {
+            break;
+        };
+
+        previous_frame_offset = Some(next_frame_offset);
+        next_frame_offset += frame_size;
+    }
+
+    // At least one ZSTD frame must have been decompressed
+    if frame_count > 0 {
+        result.success = true;
+        result.size = Some(next_frame_offset - offset);
+    }
+
+    result
+}
+
+/// Decompresses a single ZSTD frame, returning the size of the compressed frame
+fn decompress_frame(frame_data: &[u8], output_directory: Option<&str>) -> Option<usize> {
+    // Size of decompression buffer
+    const BLOCK_SIZE: usize = 8192;
+    // Output file for decompressed data
+    const OUTPUT_FILE_NAME: &str = "decompressed.bin";
+
+    let mut frame_reader = frame_data;
+    let mut decompressed_buffer = [0; BLOCK_SIZE];
+
+    let mut decompressor = StreamingDecoder::new(&mut frame_reader).ok()?;
+
+    loop {
+        if parser_deadline_exceeded() {
+            return None;
+        }
+
+        match decompressor.read(&mut decompressed_buffer) {
+            // Decompression error; this is not a valid frame
+            Err(_) => return None,
+            // End of the frame
+            Ok(0) => break,
+            Ok(n) => {
+                // If extraction was requested, write the decompressed data to the output file
+                if output_directory.is_some() {
+                    let chroot = Chroot::new(output_directory);
+                    if !chroot.append_to_file(OUTPUT_FILE_NAME, &decompressed_buffer[..n]) {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+
+    // If the frame includes a checksum, it must match the decompressed data
+    let frame_decoder = decompressor.into_frame_decoder();
+    if let Some(expected_checksum) = frame_decoder.get_checksum_from_data() {
+        if frame_decoder.get_calculated_checksum() != Some(expected_checksum) {
+            return None;
+        }
+    }
+
+    // The frame reader has been advanced past all consumed frame data
+    Some(frame_data.len() - frame_reader.len())
+}
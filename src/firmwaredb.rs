@@ -0,0 +1,276 @@
+//! Identification of known firmware releases, by looking up image and partition hashes in a user-maintained database.
+//!
+//! The database is a CSV file with a header row, listing the MD5 or SHA256 hash of each known firmware image or
+//! partition, along with the release it belongs to:
+//!
+//! ```csv
+//! hash,vendor,model,version
+//! 3a7bd3e2360a3d29eea436fcfb7e44c735d117c42d1c1835420b6b9942dd4f1b,Acme,WR-1000,1.2.3
+//! ```
+//!
+//! When built with the `sqlite` feature, the database may instead be a SQLite file containing a `firmware` table with
+//! the same columns.
+use crate::signatures::common::SignatureResult;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Returned when a firmware database can not be loaded
+#[derive(Debug, Default, Clone)]
+pub struct FirmwareDbError {
+    pub message: String,
+}
+
+/// A known firmware release
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FirmwareRelease {
+    pub vendor: String,
+    pub model: String,
+    pub version: String,
+}
+
+/// A database entry, as read from the CSV or SQLite database
+#[derive(Debug, Clone, Deserialize)]
+struct FirmwareEntry {
+    hash: String,
+    vendor: String,
+    model: String,
+    version: String,
+}
+
+/// Data whose hash matched a known firmware release
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirmwareMatch {
+    /// File offset of the matching data
+    pub offset: usize,
+    /// Size of the matching data; this is the size of the file if the entire image matched
+    pub size: usize,
+    /// Hash of the matching data, as listed in the database
+    pub hash: String,
+    /// Name of the signature whose data matched, or "image" if the entire image matched
+    pub name: String,
+    pub release: FirmwareRelease,
+}
+
+/// A set of known firmware releases, indexed by hash
+#[derive(Debug, Clone, Default)]
+pub struct FirmwareDatabase {
+    md5: HashMap<String, FirmwareRelease>,
+    sha256: HashMap<String, FirmwareRelease>,
+}
+
+impl FirmwareDatabase {
+    pub fn is_empty(&self) -> bool {
+        self.md5.is_empty() && self.sha256.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.md5.len() + self.sha256.len()
+    }
+
+    /// Adds a release to the database; hashes that are not valid MD5 or SHA256 hashes are ignored
+    fn add(&mut self, entry: FirmwareEntry) -> bool {
+        const MD5_HASH_LENGTH: usize = 32;
+        const SHA256_HASH_LENGTH: usize = 64;
+
+        let hash = entry.hash.trim().to_lowercase();
+
+        if !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return false;
+        }
+
+        let table = match hash.len() {
+            MD5_HASH_LENGTH => &mut self.md5,
+            SHA256_HASH_LENGTH => &mut self.sha256,
+            _ => return false,
+        };
+
+        table.insert(
+            hash,
+            FirmwareRelease {
+                vendor: entry.vendor.trim().to_string(),
+                model: entry.model.trim().to_string(),
+                version: entry.version.trim().to_string(),
+            },
+        );
+
+        true
+    }
+
+    /// Looks up the hash of the entire file, and of the data of each signature result, in the database
+    pub fn identify(&self, file_data: &[u8], file_map: &[SignatureResult]) -> Vec<FirmwareMatch> {
+        const IMAGE_NAME: &str = "image";
+
+        let mut matches: Vec<FirmwareMatch> = vec![];
+
+        if self.is_empty() {
+            return matches;
+        }
+
+        if let Some(firmware_match) = self.lookup(file_data, 0, IMAGE_NAME) {
+            matches.push(firmware_match);
+        }
+
+        for result in file_map {
+            // The entire image has already been checked
+            if result.size == 0 || result.size == file_data.len() {
+                continue;
+            }
+
+            if let Some(partition_data) = file_data.get(result.offset..result.offset + result.size)
+            {
+                if let Some(firmware_match) =
+                    self.lookup(partition_data, result.offset, &result.name)
+                {
+                    matches.push(firmware_match);
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// Looks up the hash of the data in the database
+    fn lookup(&self, data: &[u8], offset: usize, name: &str) -> Option<FirmwareMatch> {
+        let mut hashes: Vec<(String, &HashMap<String, FirmwareRelease>)> = vec![];
+
+        // Only calculate hashes of the types that are in the database
+        if !self.sha256.is_empty() {
+            let mut hasher = Sha256::new();
+            hasher.update(data);
+            hashes.push((format!("{:x}", hasher.finalize()), &self.sha256));
+        }
+
+        if !self.md5.is_empty() {
+            hashes.push((format!("{:x}", md5::compute(data)), &self.md5));
+        }
+
+        for (hash, table) in hashes {
+            if let Some(release) = table.get(&hash) {
+                debug!(
+                    "Data at offset {offset:#X} matches {} {} version {}",
+                    release.vendor, release.model, release.version
+                );
+
+                return Some(FirmwareMatch {
+                    offset,
+                    size: data.len(),
+                    hash,
+                    name: name.to_string(),
+                    release: release.clone(),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/// Loads a firmware database from a CSV file, or from a SQLite file if built with the `sqlite` feature.
+///
+/// Entries with invalid hashes are logged and skipped.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::firmwaredb::load_firmware_database;
+///
+/// let database_path = std::env::temp_dir().join("binwalk_firmwaredb_example.csv");
+///
+/// std::fs::write(
+///     &database_path,
+///     "hash,vendor,model,version\n5d41402abc4b2a76b9719d911017c592,Acme,WR-1000,1.2.3\n",
+/// )
+/// .expect("Failed to write firmware database");
+///
+/// let database = load_firmware_database(&database_path.to_string_lossy()).expect("Failed to load firmware database");
+/// let matches = database.identify(b"hello", &[]);
+///
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!(matches[0].release.model, "WR-1000");
+/// ```
+pub fn load_firmware_database(database_path: &str) -> Result<FirmwareDatabase, FirmwareDbError> {
+    let mut database = FirmwareDatabase::default();
+
+    let extension = Path::new(database_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let entries = match extension.as_str() {
+        "db" | "sqlite" | "sqlite3" => read_sqlite_entries(database_path)?,
+        _ => read_csv_entries(database_path)?,
+    };
+
+    for entry in entries {
+        let hash = entry.hash.clone();
+
+        if !database.add(entry) {
+            warn!("Skipping firmware database entry with invalid hash '{hash}'");
+        }
+    }
+
+    Ok(database)
+}
+
+/// Reads all entries from a CSV firmware database
+fn read_csv_entries(database_path: &str) -> Result<Vec<FirmwareEntry>, FirmwareDbError> {
+    let db_error = |e: csv::Error| FirmwareDbError {
+        message: format!("Failed to read firmware database '{database_path}': {e}"),
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::Headers)
+        .comment(Some(b'#'))
+        .from_path(database_path)
+        .map_err(db_error)?;
+
+    reader
+        .deserialize()
+        .collect::<Result<Vec<FirmwareEntry>, csv::Error>>()
+        .map_err(db_error)
+}
+
+/// Reads all entries from the firmware table of a SQLite firmware database
+#[cfg(feature = "sqlite")]
+fn read_sqlite_entries(database_path: &str) -> Result<Vec<FirmwareEntry>, FirmwareDbError> {
+    use rusqlite::{Connection, OpenFlags};
+
+    let db_error = |e: rusqlite::Error| FirmwareDbError {
+        message: format!("Failed to read firmware database '{database_path}': {e}"),
+    };
+
+    let connection = Connection::open_with_flags(database_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(db_error)?;
+
+    let mut statement = connection
+        .prepare("SELECT hash, vendor, model, version FROM firmware")
+        .map_err(db_error)?;
+
+    let rows = statement
+        .query_map([], |row| {
+            Ok(FirmwareEntry {
+                hash: row.get(0)?,
+                vendor: row.get(1)?,
+                model: row.get(2)?,
+                version: row.get(3)?,
+            })
+        })
+        .map_err(db_error)?;
+
+    rows.collect::<Result<Vec<FirmwareEntry>, rusqlite::Error>>()
+        .map_err(db_error)
+}
+
+/// Reads all entries from the firmware table of a SQLite firmware database
+#[cfg(not(feature = "sqlite"))]
+fn read_sqlite_entries(database_path: &str) -> Result<Vec<FirmwareEntry>, FirmwareDbError> {
+    Err(FirmwareDbError {
+        message: format!(
+            "Can not read firmware database '{database_path}': SQLite support requires the 'sqlite' feature"
+        ),
+    })
+}
@@ -0,0 +1,263 @@
+//! Context triggered piecewise hashing (CTPH), compatible in format with ssdeep / spamsum.
+
+/// Maximum length of each hash signature
+const SPAMSUM_LENGTH: usize = 64;
+/// Smallest block size used when splitting data into pieces
+const MIN_BLOCKSIZE: usize = 3;
+/// Size of the rolling hash window
+const ROLLING_WINDOW: usize = 7;
+const HASH_PRIME: u32 = 0x01000193;
+const HASH_INIT: u32 = 0x28021967;
+/// Signatures must have a substring of at least this length in common to be considered similar
+pub const COMMON_SUBSTRING_LENGTH: usize = ROLLING_WINDOW;
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Rolling hash used to identify piece boundaries
+#[derive(Debug, Default, Clone)]
+struct RollingHash {
+    window: [u32; ROLLING_WINDOW],
+    h1: u32,
+    h2: u32,
+    h3: u32,
+    n: usize,
+}
+
+impl RollingHash {
+    fn update(&mut self, c: u8) -> u32 {
+        let c = c as u32;
+
+        self.h2 = self.h2.wrapping_sub(self.h1);
+        self.h2 = self
+            .h2
+            .wrapping_add((ROLLING_WINDOW as u32).wrapping_mul(c));
+
+        self.h1 = self.h1.wrapping_add(c);
+        self.h1 = self.h1.wrapping_sub(self.window[self.n % ROLLING_WINDOW]);
+
+        self.window[self.n % ROLLING_WINDOW] = c;
+        self.n += 1;
+
+        self.h3 = (self.h3 << 5) ^ c;
+
+        self.h1.wrapping_add(self.h2).wrapping_add(self.h3)
+    }
+}
+
+/// Generates a fuzzy hash of the provided data, in the form "blocksize:signature1:signature2".
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::fuzzyhash::fuzzy_hash;
+///
+/// let hash = fuzzy_hash(b"AAAABBBBCCCCDDDD");
+///
+/// assert_eq!(hash.split(':').count(), 3);
+/// ```
+pub fn fuzzy_hash(data: &[u8]) -> String {
+    let mut block_size = MIN_BLOCKSIZE;
+
+    // Choose a block size that is expected to produce a signature of roughly SPAMSUM_LENGTH characters
+    while block_size * SPAMSUM_LENGTH < data.len() {
+        block_size *= 2;
+    }
+
+    loop {
+        let (signature1, signature2) = piecewise_hash(data, block_size);
+
+        // If the signature is too short, try again with a smaller block size
+        if signature1.len() < SPAMSUM_LENGTH / 2 && block_size > MIN_BLOCKSIZE {
+            block_size /= 2;
+            continue;
+        }
+
+        return format!("{block_size}:{signature1}:{signature2}");
+    }
+}
+
+/// Generates signatures for the given block size, and for twice the given block size
+fn piecewise_hash(data: &[u8], block_size: usize) -> (String, String) {
+    let mut rolling_hash = RollingHash::default();
+    let mut signature1: Vec<u8> = vec![];
+    let mut signature2: Vec<u8> = vec![];
+    let mut hash1: u32 = HASH_INIT;
+    let mut hash2: u32 = HASH_INIT;
+
+    for byte in data {
+        hash1 = hash1.wrapping_mul(HASH_PRIME) ^ (*byte as u32);
+        hash2 = hash2.wrapping_mul(HASH_PRIME) ^ (*byte as u32);
+
+        let rolling_sum = rolling_hash.update(*byte) as usize;
+
+        // The last signature character covers any remaining data, so it is not reset once the signature is full
+        if rolling_sum % block_size == block_size - 1 && signature1.len() < SPAMSUM_LENGTH - 1 {
+            signature1.push(BASE64[(hash1 as usize) % BASE64.len()]);
+            hash1 = HASH_INIT;
+        }
+
+        if rolling_sum % (block_size * 2) == (block_size * 2) - 1
+            && signature2.len() < (SPAMSUM_LENGTH / 2) - 1
+        {
+            signature2.push(BASE64[(hash2 as usize) % BASE64.len()]);
+            hash2 = HASH_INIT;
+        }
+    }
+
+    if !data.is_empty() {
+        signature1.push(BASE64[(hash1 as usize) % BASE64.len()]);
+        signature2.push(BASE64[(hash2 as usize) % BASE64.len()]);
+    }
+
+    (
+        String::from_utf8_lossy(&signature1).to_string(),
+        String::from_utf8_lossy(&signature2).to_string(),
+    )
+}
+
+/// Stores the components of a fuzzy hash string
+#[derive(Debug, Default, Clone)]
+pub struct FuzzyHash {
+    pub block_size: usize,
+    pub signature1: String,
+    pub signature2: String,
+}
+
+/// Parses a fuzzy hash string, as returned by fuzzy_hash
+pub fn parse_fuzzy_hash(hash: &str) -> Option<FuzzyHash> {
+    let mut parts = hash.splitn(3, ':');
+
+    let block_size = parts.next()?.parse::<usize>().ok()?;
+    let signature1 = parts.next()?;
+    let signature2 = parts.next()?;
+
+    Some(FuzzyHash {
+        block_size,
+        // Long runs of the same character carry little information and are truncated before comparison
+        signature1: eliminate_sequences(signature1),
+        signature2: eliminate_sequences(signature2),
+    })
+}
+
+/// Compares two fuzzy hashes, returning a similarity score from 0 (no similarity) to 100 (identical).
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::fuzzyhash::{fuzzy_compare, fuzzy_hash};
+///
+/// let data: Vec<u8> = (0..8192).map(|i: u32| (i.wrapping_mul(2654435761) >> 24) as u8).collect();
+/// let mut modified_data = data.clone();
+/// modified_data[4096] ^= 0xFF;
+///
+/// assert_eq!(fuzzy_compare(&fuzzy_hash(&data), &fuzzy_hash(&data)), 100);
+/// assert!(fuzzy_compare(&fuzzy_hash(&data), &fuzzy_hash(&modified_data)) > 50);
+/// ```
+pub fn fuzzy_compare(hash1: &str, hash2: &str) -> usize {
+    match (parse_fuzzy_hash(hash1), parse_fuzzy_hash(hash2)) {
+        (Some(hash1), Some(hash2)) => fuzzy_compare_parsed(&hash1, &hash2),
+        _ => 0,
+    }
+}
+
+/// Compares two parsed fuzzy hashes, returning a similarity score from 0 to 100
+pub fn fuzzy_compare_parsed(hash1: &FuzzyHash, hash2: &FuzzyHash) -> usize {
+    // Signatures can only be compared if they were generated with the same block size
+    if hash1.block_size == hash2.block_size {
+        if hash1.signature1 == hash2.signature1 {
+            return 100;
+        }
+
+        std::cmp::max(
+            score_signatures(&hash1.signature1, &hash2.signature1, hash1.block_size),
+            score_signatures(&hash1.signature2, &hash2.signature2, hash1.block_size * 2),
+        )
+    } else if hash1.block_size == hash2.block_size * 2 {
+        score_signatures(&hash1.signature1, &hash2.signature2, hash1.block_size)
+    } else if hash2.block_size == hash1.block_size * 2 {
+        score_signatures(&hash1.signature2, &hash2.signature1, hash2.block_size)
+    } else {
+        0
+    }
+}
+
+/// Scores the similarity of two signatures generated with the same block size
+fn score_signatures(signature1: &str, signature2: &str, block_size: usize) -> usize {
+    let s1 = signature1.as_bytes();
+    let s2 = signature2.as_bytes();
+
+    if s1.len() > SPAMSUM_LENGTH || s2.len() > SPAMSUM_LENGTH {
+        return 0;
+    }
+
+    // Signatures that have no substrings in common are not considered similar
+    if !s1.windows(COMMON_SUBSTRING_LENGTH).any(|window| {
+        s2.windows(COMMON_SUBSTRING_LENGTH)
+            .any(|other| window == other)
+    }) {
+        return 0;
+    }
+
+    // Scale the edit distance to a score of 0-100, where 100 is a perfect match
+    let distance = edit_distance(s1, s2) * SPAMSUM_LENGTH / (s1.len() + s2.len());
+    let distance = (100 * distance) / SPAMSUM_LENGTH;
+
+    if distance >= 100 {
+        return 0;
+    }
+
+    let mut score = 100 - distance;
+
+    // Don't exaggerate the match size for small block sizes
+    let max_block_size = ((99 + ROLLING_WINDOW) / ROLLING_WINDOW) * MIN_BLOCKSIZE;
+    if block_size < max_block_size {
+        score = std::cmp::min(
+            score,
+            (block_size / MIN_BLOCKSIZE) * std::cmp::min(s1.len(), s2.len()),
+        );
+    }
+
+    score
+}
+
+/// Weighted Levenshtein distance; replacements count as an insertion plus a deletion
+fn edit_distance(s1: &[u8], s2: &[u8]) -> usize {
+    const INSERT_COST: usize = 1;
+    const REMOVE_COST: usize = 1;
+    const REPLACE_COST: usize = 2;
+
+    let mut previous_row: Vec<usize> = (0..=s2.len()).map(|i| i * INSERT_COST).collect();
+
+    for (i, c1) in s1.iter().enumerate() {
+        let mut current_row: Vec<usize> = vec![(i + 1) * REMOVE_COST];
+
+        for (j, c2) in s2.iter().enumerate() {
+            let replace = previous_row[j] + if c1 == c2 { 0 } else { REPLACE_COST };
+            let remove = previous_row[j + 1] + REMOVE_COST;
+            let insert = current_row[j] + INSERT_COST;
+            current_row.push(std::cmp::min(replace, std::cmp::min(remove, insert)));
+        }
+
+        previous_row = current_row;
+    }
+
+    previous_row[s2.len()]
+}
+
+/// Truncates runs of more than three identical characters down to three characters
+fn eliminate_sequences(signature: &str) -> String {
+    const MAX_SEQUENCE_LENGTH: usize = 3;
+
+    let mut result: Vec<u8> = vec![];
+
+    for c in signature.bytes() {
+        if result.len() < MAX_SEQUENCE_LENGTH
+            || !result[result.len() - MAX_SEQUENCE_LENGTH..]
+                .iter()
+                .all(|previous| *previous == c)
+        {
+            result.push(c);
+        }
+    }
+
+    String::from_utf8_lossy(&result).to_string()
+}
@@ -0,0 +1,714 @@
+//! Serves scan, event stream and artifact requests over gRPC, for integration with gRPC-based analysis orchestration.
+//!
+//! Scans are submitted with SubmitScan, and run in the background; the file to scan is either uploaded with the
+//! request, or is a file within the server's `--root` directory. Each scan extracts to its own sub-directory of the
+//! extraction directory, and is analyzed with the same options and resource limits as the server's command line. A
+//! scan's events are the same JSON objects that are written in `--stream` mode, and can be streamed with StreamEvents
+//! while the scan is running, or after it has completed. Extracted files are downloaded with FetchArtifact.
+//!
+//! Clients authenticate with the bearer token in the server's `--token-file`. Completed scans, and their extracted
+//! files, are deleted once `--scan-ttl` has elapsed, or to make room for new scans once `--max-scans` are held.
+//!
+//! The service and its messages are generated from proto/binwalk.proto.
+
+use crate::cliparser::CliArgs;
+use crate::extractors::common::get_extracted_files;
+use crate::json::{self, StreamEvent};
+use crate::limits::ResourceLimits;
+use crate::signatures::common::Signature;
+use crate::{WorkerOptions, WorkerTarget, blockdev, spawn_worker};
+use log::{debug, error, info, warn};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time;
+use threadpool::ThreadPool;
+use tokio::sync::{mpsc, watch};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::service::Interceptor;
+use tonic::{Request, Response, Status, Streaming};
+
+tonic::include_proto!("binwalk");
+
+/// Size of the chunks that artifacts are downloaded in
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Number of events or chunks that are queued for a client before waiting for the client to read them
+const QUEUE_SIZE: usize = 64;
+
+/// How often scans are checked for expiry
+const EVICTION_INTERVAL: time::Duration = time::Duration::from_secs(60);
+
+/// Name given to uploaded files that are not named in the scan request
+const DEFAULT_UPLOAD_NAME: &str = "upload.bin";
+
+/// Returned when the gRPC server can not be started
+#[derive(Debug, Default, Clone)]
+pub struct GrpcError {
+    pub message: String,
+}
+
+/// Options of the gRPC server itself
+#[derive(Debug, Default, Clone)]
+pub struct ServerOptions {
+    /// Address to listen on
+    pub listen: String,
+    /// File containing the bearer token that clients must present
+    pub token_file: String,
+    /// Directory that scans of files on the server are restricted to; if None, files must be uploaded
+    pub root: Option<String>,
+    /// Maximum number of scans to hold
+    pub max_scans: usize,
+    /// Completed scans are deleted after this amount of time
+    pub scan_ttl: time::Duration,
+    /// Each scan extracts to a sub-directory of this directory, named after the scan ID
+    pub extraction_directory: String,
+}
+
+/// Analysis options and resource limits applied to every scan, from the server's command line
+#[derive(Debug, Clone)]
+pub struct ScanConfiguration {
+    pub cliargs: Arc<CliArgs>,
+    pub resource_limits: ResourceLimits,
+    pub plugin_signatures: Vec<Signature>,
+    pub worker_options: WorkerOptions,
+    /// Number of files analyzed at once, across all scans
+    pub worker_count: usize,
+}
+
+/// Events of a scan, and when it completed
+#[derive(Debug, Default, Clone)]
+struct ScanProgress {
+    events: Vec<ScanEvent>,
+    completed: Option<time::Instant>,
+    /// Set if the scan could not be run
+    error: Option<String>,
+}
+
+/// A submitted scan
+#[derive(Debug)]
+struct Scan {
+    extraction_directory: PathBuf,
+    progress: watch::Sender<ScanProgress>,
+}
+
+impl Scan {
+    /// Marks the scan as complete
+    fn complete(&self, error: Option<String>) {
+        self.progress.send_modify(|progress| {
+            progress.error = error;
+            progress.completed = Some(time::Instant::now());
+        });
+    }
+
+    /// Returns the time at which the scan completed, if it has
+    fn completed(&self) -> Option<time::Instant> {
+        self.progress.borrow().completed
+    }
+
+    /// Deletes the scan's extraction directory
+    fn delete(&self) {
+        if let Err(e) = fs::remove_dir_all(&self.extraction_directory) {
+            warn!(
+                "Failed to delete scan directory {}: {}",
+                self.extraction_directory.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Implements the binwalk gRPC service
+#[derive(Debug, Clone)]
+struct BinwalkService {
+    extraction_directory: PathBuf,
+    /// Canonical path of the directory that scans of files on the server are restricted to
+    root: Option<PathBuf>,
+    max_scans: usize,
+    scan_ttl: time::Duration,
+    configuration: Arc<ScanConfiguration>,
+    /// Analyzes the files of all scans
+    workers: Arc<Mutex<ThreadPool>>,
+    /// Submitted scans, keyed by scan ID
+    scans: Arc<Mutex<HashMap<String, Arc<Scan>>>>,
+}
+
+impl BinwalkService {
+    /// Returns the scan with the specified ID, if any
+    fn scan(&self, scan_id: &str) -> Option<Arc<Scan>> {
+        self.scans
+            .lock()
+            .expect("Scan list lock was poisoned")
+            .get(scan_id)
+            .cloned()
+    }
+
+    /// Deletes expired scans, and the oldest completed scans while more than `max_count` scans are held.
+    /// Running scans are never deleted. Returns the number of scans still held.
+    fn evict_scans(&self, max_count: usize) -> usize {
+        let mut scans = self.scans.lock().expect("Scan list lock was poisoned");
+
+        let mut completed_scans: Vec<(time::Instant, String)> = scans
+            .iter()
+            .filter_map(|(scan_id, scan)| Some((scan.completed()?, scan_id.clone())))
+            .collect();
+
+        // Oldest first
+        completed_scans.sort();
+
+        for (completed, scan_id) in completed_scans {
+            if completed.elapsed() < self.scan_ttl && scans.len() <= max_count {
+                continue;
+            }
+
+            if let Some(scan) = scans.remove(&scan_id) {
+                info!("Deleting scan {scan_id}");
+                scan.delete();
+            }
+        }
+
+        scans.len()
+    }
+
+    /// Writes the data uploaded with a scan request to the scan's extraction directory, and returns its path
+    async fn receive_upload(
+        &self,
+        first_request: ScanRequest,
+        requests: &mut Streaming<ScanRequest>,
+        extraction_directory: &Path,
+    ) -> Result<PathBuf, Status> {
+        // Only the file name is used, so the upload can't be written outside of the extraction directory
+        let file_name = Path::new(&first_request.file_name)
+            .file_name()
+            .map(|file_name| file_name.to_os_string())
+            .unwrap_or(DEFAULT_UPLOAD_NAME.into());
+
+        let upload_path = extraction_directory.join(file_name);
+
+        let mut upload_file = fs::File::create(&upload_path)
+            .map_err(|e| Status::internal(format!("Failed to create upload file: {e}")))?;
+
+        let max_file_size = self.configuration.resource_limits.max_file_size;
+        let mut upload_size: u64 = 0;
+        let mut data = first_request.data;
+
+        loop {
+            upload_size += data.len() as u64;
+
+            if max_file_size.is_some_and(|max_file_size| upload_size > max_file_size) {
+                return Err(Status::resource_exhausted(
+                    "Uploaded file exceeds the file size limit",
+                ));
+            }
+
+            upload_file
+                .write_all(&data)
+                .map_err(|e| Status::internal(format!("Failed to write upload file: {e}")))?;
+
+            match requests.message().await? {
+                None => break,
+                Some(request) => data = request.data,
+            }
+        }
+
+        Ok(upload_path)
+    }
+}
+
+#[tonic::async_trait]
+impl binwalk_server::Binwalk for BinwalkService {
+    async fn submit_scan(
+        &self,
+        request: Request<Streaming<ScanRequest>>,
+    ) -> Result<Response<ScanHandle>, Status> {
+        let mut requests = request.into_inner();
+
+        let scan_request = match requests.message().await? {
+            None => return Err(Status::invalid_argument("Empty scan request")),
+            Some(scan_request) => scan_request,
+        };
+
+        // Make room for the new scan
+        if self.evict_scans(self.max_scans.saturating_sub(1)) >= self.max_scans {
+            return Err(Status::resource_exhausted(
+                "Too many running scans, try again later",
+            ));
+        }
+
+        let scan_id = uuid::Uuid::new_v4().to_string();
+
+        let scan = Arc::new(Scan {
+            extraction_directory: self.extraction_directory.join(&scan_id),
+            progress: watch::Sender::new(ScanProgress::default()),
+        });
+
+        if let Err(e) = fs::create_dir_all(&scan.extraction_directory) {
+            return Err(Status::internal(format!(
+                "Failed to create scan directory: {e}"
+            )));
+        }
+
+        let extract = scan_request.extract;
+        let recursive = scan_request.recursive;
+
+        let target_file = match (scan_request.file_path.is_empty(), &self.root) {
+            (true, _) => {
+                self.receive_upload(scan_request, &mut requests, &scan.extraction_directory)
+                    .await
+            }
+            (false, None) => Err(Status::permission_denied(
+                "Scans of files on the server are not allowed; upload the file instead",
+            )),
+            (false, Some(root)) => match resolve_file_path(root, &scan_request.file_path) {
+                None => Err(Status::not_found(format!(
+                    "No such file: {}",
+                    scan_request.file_path
+                ))),
+                Some(file_path) => Ok(file_path),
+            },
+        };
+
+        let target_file = match target_file {
+            Err(status) => {
+                scan.delete();
+                return Err(status);
+            }
+            Ok(target_file) => target_file.display().to_string(),
+        };
+
+        self.scans
+            .lock()
+            .expect("Scan list lock was poisoned")
+            .insert(scan_id.clone(), scan.clone());
+
+        info!("Scan {scan_id} submitted for {target_file}");
+
+        let configuration = self.configuration.clone();
+        let workers = self
+            .workers
+            .lock()
+            .expect("Worker pool lock was poisoned")
+            .clone();
+
+        tokio::task::spawn_blocking(move || {
+            run_scan(
+                &configuration,
+                &workers,
+                &scan,
+                target_file,
+                extract,
+                recursive,
+            )
+        });
+
+        Ok(Response::new(ScanHandle { scan_id }))
+    }
+
+    type StreamEventsStream = ReceiverStream<Result<ScanEvent, Status>>;
+
+    async fn stream_events(
+        &self,
+        request: Request<ScanHandle>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let scan_id = &request.get_ref().scan_id;
+
+        let scan = match self.scan(scan_id) {
+            None => return Err(Status::not_found(format!("No such scan: {scan_id}"))),
+            Some(scan) => scan,
+        };
+
+        let mut progress = scan.progress.subscribe();
+        let (event_tx, event_rx) = mpsc::channel(QUEUE_SIZE);
+
+        tokio::spawn(async move {
+            let mut sent_count: usize = 0;
+
+            loop {
+                let (events, complete, scan_error) = {
+                    let progress = progress.borrow_and_update();
+                    (
+                        progress.events[sent_count..].to_vec(),
+                        progress.completed.is_some(),
+                        progress.error.clone(),
+                    )
+                };
+
+                sent_count += events.len();
+
+                for event in events {
+                    // The client has gone away
+                    if event_tx.send(Ok(event)).await.is_err() {
+                        return;
+                    }
+                }
+
+                if complete {
+                    if let Some(scan_error) = scan_error {
+                        let _ = event_tx.send(Err(Status::internal(scan_error))).await;
+                    }
+                    return;
+                }
+
+                // The scan has been deleted
+                if progress.changed().await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(event_rx)))
+    }
+
+    type FetchArtifactStream = ReceiverStream<Result<ArtifactChunk, Status>>;
+
+    async fn fetch_artifact(
+        &self,
+        request: Request<ArtifactRequest>,
+    ) -> Result<Response<Self::FetchArtifactStream>, Status> {
+        let artifact_request = request.into_inner();
+
+        let scan = match self.scan(&artifact_request.scan_id) {
+            None => {
+                return Err(Status::not_found(format!(
+                    "No such scan: {}",
+                    artifact_request.scan_id
+                )));
+            }
+            Some(scan) => scan,
+        };
+
+        // Never serve files outside of the scan's extraction directory, even via symlinks
+        let artifact_path = match fs::canonicalize(&scan.extraction_directory).and_then(|root| {
+            let path = fs::canonicalize(root.join(&artifact_request.path))?;
+            Ok((root, path))
+        }) {
+            Ok((root, path)) if path.starts_with(&root) && path.is_file() => path,
+            _ => {
+                return Err(Status::not_found(format!(
+                    "No such artifact: {}",
+                    artifact_request.path
+                )));
+            }
+        };
+
+        let (chunk_tx, chunk_rx) = mpsc::channel(QUEUE_SIZE);
+
+        tokio::task::spawn_blocking(move || {
+            let mut file = match fs::File::open(&artifact_path) {
+                Err(e) => {
+                    let _ = chunk_tx.blocking_send(Err(Status::internal(format!(
+                        "Failed to open {}: {}",
+                        artifact_path.display(),
+                        e
+                    ))));
+                    return;
+                }
+                Ok(file) => file,
+            };
+
+            let mut chunk = vec![0; CHUNK_SIZE];
+
+            loop {
+                let chunk_result = match file.read(&mut chunk) {
+                    Err(e) => Err(Status::internal(format!(
+                        "Failed to read {}: {}",
+                        artifact_path.display(),
+                        e
+                    ))),
+                    Ok(0) => return,
+                    Ok(chunk_size) => Ok(ArtifactChunk {
+                        data: chunk[..chunk_size].to_vec(),
+                    }),
+                };
+
+                let is_error = chunk_result.is_err();
+
+                // Stop if the client has gone away, or the file can't be read
+                if chunk_tx.blocking_send(chunk_result).is_err() || is_error {
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(chunk_rx)))
+    }
+}
+
+/// Runs a submitted scan with the server's analysis options, publishing the events of each analyzed file as soon as
+/// the file has been analyzed
+fn run_scan(
+    configuration: &ScanConfiguration,
+    workers: &ThreadPool,
+    scan: &Scan,
+    target_file: String,
+    extract: bool,
+    recursive: bool,
+) {
+    let resource_limits = &configuration.resource_limits;
+
+    let mut binwalker = match crate::configure_binwalk(
+        &configuration.cliargs,
+        resource_limits,
+        Some(target_file.clone()),
+        Some(scan.extraction_directory.display().to_string()),
+        configuration.plugin_signatures.clone(),
+    ) {
+        Err(e) => {
+            error!("Failed to scan {}: {}", target_file, e.message);
+            scan.complete(Some(e.message));
+            return;
+        }
+        Ok(binwalker) => binwalker,
+    };
+
+    // There is nobody to answer an external extractor's prompt
+    binwalker.external_options.non_interactive = true;
+
+    // Results are published as scan events, rather than streamed to stdout
+    let worker_options = WorkerOptions {
+        extract,
+        carve: false,
+        stream: false,
+        ..configuration.worker_options.clone()
+    };
+
+    let (worker_tx, worker_rx) = std::sync::mpsc::channel();
+    let mut pending_count: usize = 0;
+    let mut extracted_size: u64 = 0;
+
+    // Returns false if the file was not queued for analysis
+    let queue_file = |file_path: String| {
+        // Don't load files that exceed the file size limit into memory
+        if resource_limits.max_file_size.is_some_and(|max_file_size| {
+            blockdev::file_size(&file_path).is_ok_and(|file_size| file_size > max_file_size)
+        }) {
+            warn!("Skipping {file_path}, which exceeds the file size limit");
+            return false;
+        }
+
+        debug!("Queuing {file_path} for analysis");
+
+        spawn_worker(
+            workers,
+            binwalker.clone(),
+            WorkerTarget {
+                file_path,
+                stdin: false,
+                data: None,
+                selection: None,
+            },
+            worker_options.clone(),
+            worker_tx.clone(),
+        );
+
+        true
+    };
+
+    if queue_file(binwalker.base_target_file.clone()) {
+        pending_count += 1;
+    }
+
+    while pending_count > 0 {
+        let results = match worker_rx.recv() {
+            Err(_) => break,
+            Ok(results) => results,
+        };
+
+        pending_count -= 1;
+
+        let events: Vec<ScanEvent> = json::analysis_events(&results)
+            .iter()
+            .filter_map(scan_event)
+            .collect();
+
+        scan.progress
+            .send_modify(|progress| progress.events.extend(events));
+
+        if !recursive {
+            continue;
+        }
+
+        for extraction_result in results.extractions.values() {
+            if !extraction_result.success || extraction_result.do_not_recurse {
+                continue;
+            }
+
+            for file_path in get_extracted_files(&extraction_result.output_directory) {
+                extracted_size += fs::metadata(&file_path)
+                    .map(|metadata| metadata.len())
+                    .unwrap_or_default();
+
+                // Once the extracted size limit has been reached, stop analyzing extracted files
+                if resource_limits
+                    .max_extracted_size
+                    .is_some_and(|max_extracted_size| extracted_size > max_extracted_size)
+                {
+                    warn!("Extracted size limit reached, extracted files will not be analyzed");
+                    break;
+                }
+
+                if queue_file(file_path) {
+                    pending_count += 1;
+                }
+            }
+        }
+    }
+
+    scan.complete(None);
+}
+
+/// Resolves a file path from a scan request to a file within the server's root directory
+fn resolve_file_path(root: &Path, file_path: &str) -> Option<PathBuf> {
+    // Canonicalizing resolves '..' and symlinks, so the file can't escape the root directory
+    match fs::canonicalize(root.join(file_path)) {
+        Ok(path) if path.starts_with(root) && path.is_file() => Some(path),
+        _ => None,
+    }
+}
+
+/// Converts a stream event to a scan event
+fn scan_event(event: &StreamEvent) -> Option<ScanEvent> {
+    match serde_json::to_value(event) {
+        Err(e) => {
+            error!("Failed to convert stream event to JSON: {e}");
+            None
+        }
+        Ok(event_json) => Some(ScanEvent {
+            event: event_json["event"].as_str().unwrap_or_default().to_string(),
+            json: event_json.to_string(),
+        }),
+    }
+}
+
+/// Rejects requests that don't present the server's bearer token
+#[derive(Debug, Clone)]
+struct Authenticator {
+    expected_authorization: String,
+}
+
+impl Interceptor for Authenticator {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        match request.metadata().get("authorization") {
+            Some(authorization)
+                if constant_time_eq(
+                    authorization.as_bytes(),
+                    self.expected_authorization.as_bytes(),
+                ) =>
+            {
+                Ok(request)
+            }
+            _ => Err(Status::unauthenticated("Invalid or missing bearer token")),
+        }
+    }
+}
+
+/// Returns true if two byte strings are equal, taking the same amount of time no matter where they differ
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Serves the binwalk gRPC service until the process is terminated
+pub fn serve(options: ServerOptions, configuration: ScanConfiguration) -> Result<(), GrpcError> {
+    let address: SocketAddr = match options.listen.parse() {
+        Err(e) => {
+            return Err(GrpcError {
+                message: format!("Invalid listen address '{}': {}", options.listen, e),
+            });
+        }
+        Ok(address) => address,
+    };
+
+    let token = match fs::read_to_string(&options.token_file) {
+        Err(e) => {
+            return Err(GrpcError {
+                message: format!("Failed to read token file '{}': {}", options.token_file, e),
+            });
+        }
+        Ok(token) => token.trim().to_string(),
+    };
+
+    if token.is_empty() {
+        return Err(GrpcError {
+            message: format!("Token file '{}' is empty", options.token_file),
+        });
+    }
+
+    let root = match &options.root {
+        None => None,
+        Some(root) => match fs::canonicalize(root) {
+            Ok(root) if root.is_dir() => Some(root),
+            _ => {
+                return Err(GrpcError {
+                    message: format!("Invalid root directory '{root}'"),
+                });
+            }
+        },
+    };
+
+    let extraction_directory = match std::path::absolute(&options.extraction_directory) {
+        Err(e) => {
+            return Err(GrpcError {
+                message: format!(
+                    "Invalid extraction directory '{}': {}",
+                    options.extraction_directory, e
+                ),
+            });
+        }
+        Ok(extraction_directory) => extraction_directory,
+    };
+
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Err(e) => {
+            return Err(GrpcError {
+                message: format!("Failed to initialize gRPC runtime: {e}"),
+            });
+        }
+        Ok(runtime) => runtime,
+    };
+
+    let service = BinwalkService {
+        extraction_directory,
+        root,
+        max_scans: options.max_scans,
+        scan_ttl: options.scan_ttl,
+        workers: Arc::new(Mutex::new(ThreadPool::new(configuration.worker_count))),
+        configuration: Arc::new(configuration),
+        scans: Arc::new(Mutex::new(HashMap::new())),
+    };
+
+    // Expired scans are deleted even if no new scans are submitted
+    let eviction_service = service.clone();
+
+    runtime.spawn(async move {
+        let mut interval = tokio::time::interval(EVICTION_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            eviction_service.evict_scans(eviction_service.max_scans);
+        }
+    });
+
+    info!("Serving gRPC requests on {address}");
+
+    let server = tonic::transport::Server::builder()
+        .add_service(binwalk_server::BinwalkServer::with_interceptor(
+            service,
+            Authenticator {
+                expected_authorization: format!("Bearer {token}"),
+            },
+        ))
+        .serve(address);
+
+    match runtime.block_on(server) {
+        Err(e) => Err(GrpcError {
+            message: format!("gRPC server failed: {e}"),
+        }),
+        Ok(_) => Ok(()),
+    }
+}
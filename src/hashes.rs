@@ -0,0 +1,255 @@
+//! Hashing of analyzed files, carved regions and extracted files.
+//!
+//! The MD5, SHA1 and SHA256 hashes of each analyzed file, of the data of each signature identified in it, and of every
+//! file extracted from it, are included in the analysis results, and can be written to a hashes manifest in the
+//! extraction directory, so that extracted artifacts can later be shown to be unaltered.
+
+use crate::common::relative_path;
+use crate::extractors::common::ExtractionResult;
+use crate::signatures::common::SignatureResult;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Name of the hashes manifest file written to the extraction directory
+pub const HASHES_FILE_NAME: &str = "hashes.json";
+
+/// Returned when the hashes manifest can't be written
+#[derive(Debug, Default, Clone)]
+pub struct HashesError {
+    pub message: String,
+}
+
+/// MD5, SHA1 and SHA256 hashes of some data, as lower case hex strings
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hashes {
+    pub md5: String,
+    pub sha1: String,
+    pub sha256: String,
+}
+
+/// Hashes of the data of an identified signature
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RegionHashes {
+    /// The SignatureResult.id of the signature
+    pub signature_id: String,
+    /// The SignatureResult.name of the signature
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+    #[serde(flatten)]
+    pub hashes: Hashes,
+}
+
+/// Hashes of an extracted file
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FileHashes {
+    /// The SignatureResult.id of the signature that the file was extracted from
+    pub signature_id: String,
+    /// Path to the file, relative to the extraction directory, using '/' as the path separator
+    pub path: String,
+    pub size: u64,
+    #[serde(flatten)]
+    pub hashes: Hashes,
+}
+
+/// Hashes of an analyzed file, and of the artifacts carved and extracted from it
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArtifactHashes {
+    /// Hashes of the analyzed file's data
+    pub file: Hashes,
+    /// Hashes of the data of each identified signature that has a known size
+    pub regions: Vec<RegionHashes>,
+    /// Hashes of each extracted file
+    pub files: Vec<FileHashes>,
+}
+
+/// Returns the MD5, SHA1 and SHA256 hashes of the data
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::hashes::hash_data;
+///
+/// let hashes = hash_data(b"abc");
+///
+/// assert_eq!(hashes.md5, "900150983cd24fb0d6963f7d28e17f72");
+/// assert_eq!(hashes.sha1, "a9993e364706816aba3e25717850c26c9cd0d89d");
+/// assert_eq!(
+///     hashes.sha256,
+///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+/// );
+/// ```
+pub fn hash_data(data: &[u8]) -> Hashes {
+    Hashes {
+        md5: format!("{:x}", md5::compute(data)),
+        sha1: format!("{:x}", Sha1::digest(data)),
+        sha256: format!("{:x}", Sha256::digest(data)),
+    }
+}
+
+/// Returns the MD5, SHA1 and SHA256 hashes of a file's contents, reading the file a block at a time
+pub fn hash_file(file_path: impl AsRef<Path>) -> Result<Hashes, io::Error> {
+    const BLOCK_SIZE: usize = 1024 * 1024;
+
+    let mut md5_context = md5::Context::new();
+    let mut sha1_hasher = Sha1::new();
+    let mut sha256_hasher = Sha256::new();
+
+    let mut file = fs::File::open(file_path)?;
+    let mut block = vec![0; BLOCK_SIZE];
+
+    loop {
+        let block_size = io::Read::read(&mut file, &mut block)?;
+
+        if block_size == 0 {
+            break;
+        }
+
+        md5_context.consume(&block[..block_size]);
+        sha1_hasher.update(&block[..block_size]);
+        sha256_hasher.update(&block[..block_size]);
+    }
+
+    Ok(Hashes {
+        md5: format!("{:x}", md5_context.finalize()),
+        sha1: format!("{:x}", sha1_hasher.finalize()),
+        sha256: format!("{:x}", sha256_hasher.finalize()),
+    })
+}
+
+/// Hashes an analyzed file's data, the data of each signature identified in it, and each file extracted from it.
+/// Extracted file paths are recorded relative to `extraction_directory`.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::hashes::{hash_artifacts, hash_data};
+/// use binwalk::signatures::common::SignatureResult;
+/// use std::collections::HashMap;
+///
+/// let file_data = b"headerABCDtrailer";
+/// let file_map = vec![SignatureResult {
+///     id: "example".to_string(),
+///     offset: 6,
+///     size: 4,
+///     ..Default::default()
+/// }];
+///
+/// let hashes = hash_artifacts(file_data, &file_map, &HashMap::new(), "extractions");
+///
+/// assert_eq!(hashes.file, hash_data(file_data));
+/// assert_eq!(hashes.regions.len(), 1);
+/// assert_eq!(hashes.regions[0].hashes, hash_data(b"ABCD"));
+/// assert!(hashes.files.is_empty());
+/// ```
+pub fn hash_artifacts(
+    file_data: &[u8],
+    file_map: &[SignatureResult],
+    extractions: &HashMap<String, ExtractionResult>,
+    extraction_directory: &str,
+) -> ArtifactHashes {
+    let mut artifact_hashes = ArtifactHashes {
+        file: hash_data(file_data),
+        ..Default::default()
+    };
+
+    for signature in file_map {
+        // Signatures of an unknown size don't identify a region of data
+        if signature.size == 0 {
+            continue;
+        }
+
+        if let Some(region_data) =
+            file_data.get(signature.offset..signature.offset + signature.size)
+        {
+            artifact_hashes.regions.push(RegionHashes {
+                signature_id: signature.id.clone(),
+                name: signature.name.clone(),
+                offset: signature.offset,
+                size: signature.size,
+                hashes: hash_data(region_data),
+            });
+        }
+    }
+
+    for signature in file_map {
+        if let Some(extraction) = extractions.get(&signature.id) {
+            if !extraction.success || extraction.output_directory.is_empty() {
+                continue;
+            }
+
+            for entry in WalkDir::new(&extraction.output_directory)
+                .sort_by_file_name()
+                .into_iter()
+                .flatten()
+            {
+                // Symlinks are not followed; they may point outside of the extraction directory
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+
+                let file_hashes = entry
+                    .metadata()
+                    .map_err(io::Error::from)
+                    .and_then(|metadata| {
+                        Ok(FileHashes {
+                            signature_id: signature.id.clone(),
+                            path: relative_path(entry.path(), extraction_directory),
+                            size: metadata.len(),
+                            hashes: hash_file(entry.path())?,
+                        })
+                    });
+
+                match file_hashes {
+                    Err(e) => warn!("Failed to hash {}: {}", entry.path().display(), e),
+                    Ok(file_hashes) => artifact_hashes.files.push(file_hashes),
+                }
+            }
+        }
+    }
+
+    artifact_hashes
+}
+
+/// Writes the hashes of all analyzed files, keyed by file path, to the hashes manifest in the extraction directory.
+/// Returns the path to the manifest file.
+pub fn write_hashes(
+    extraction_directory: &str,
+    hashes: &BTreeMap<String, ArtifactHashes>,
+) -> Result<String, HashesError> {
+    let hashes_path = Path::new(extraction_directory)
+        .join(HASHES_FILE_NAME)
+        .display()
+        .to_string();
+
+    let hashes_json = match serde_json::to_string_pretty(hashes) {
+        Err(e) => {
+            return Err(HashesError {
+                message: format!("Failed to serialize hashes: {e}"),
+            });
+        }
+        Ok(json) => json,
+    };
+
+    if let Err(e) = fs::write(&hashes_path, hashes_json) {
+        return Err(HashesError {
+            message: format!("Failed to write hashes manifest {hashes_path}: {e}"),
+        });
+    }
+
+    info!(
+        "Wrote hashes of {} analyzed files to {}",
+        hashes.len(),
+        hashes_path
+    );
+
+    Ok(hashes_path)
+}
@@ -0,0 +1,278 @@
+//! Heuristic findings: suspicious, but unconfirmed, features of analyzed data.
+//!
+//! Heuristic findings are reported as `SignatureResult`s with `heuristic` set, and are kept out of the file map so that
+//! exploratory guesses never pollute the list of validated signatures. Signature parsers report heuristic findings of
+//! their own (e.g., a truncated LZMA stream); the heuristics in this module analyze the file data directly.
+
+use crate::padding;
+use crate::signatures::common::{CONFIDENCE_LOW, SignatureResult};
+use entropy::shannon_entropy;
+
+/// Names of all heuristics; heuristic findings reported by signature parsers are named after their signature
+pub const HEURISTICS: &[&str] = &["classify", "entropy_cliff", "lzma"];
+
+/// Size of the blocks that entropy is measured over
+pub const ENTROPY_BLOCK_SIZE: usize = 4096;
+
+/// Blocks with entropy, in bits per byte, at or below this are considered low entropy
+const LOW_ENTROPY: f32 = 5.0;
+
+/// Blocks with entropy, in bits per byte, at or above this are considered high entropy (compressed or encrypted)
+const HIGH_ENTROPY: f32 = 7.5;
+
+/// Human readable description of entropy cliffs
+pub const ENTROPY_CLIFF_DESCRIPTION: &str = "Entropy cliff";
+
+/// Human readable description of classified data
+pub const CLASSIFY_DESCRIPTION: &str = "Unidentified data";
+
+/// High entropy regions smaller than this are too small to classify reliably
+pub const MIN_CLASSIFY_SIZE: usize = 4096;
+
+/// Chi-square critical value for 255 degrees of freedom at p = 0.001; uniformly random data rarely exceeds this
+const CHI_SQUARE_CRITICAL_VALUE: f64 = 330.5;
+
+/// Byte statistics used to classify high entropy data
+#[derive(Debug, Default, Clone)]
+pub struct ByteStatistics {
+    /// Shannon entropy, in bits per byte
+    pub entropy: f32,
+    /// Chi-square statistic of the byte distribution, against a uniform distribution
+    pub chi_square: f64,
+    /// Arithmetic mean of all byte values
+    pub mean: f64,
+}
+
+impl ByteStatistics {
+    /// Calculates byte statistics for the data
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use binwalk::heuristics::ByteStatistics;
+    ///
+    /// let statistics = ByteStatistics::new(&(0..=255).collect::<Vec<u8>>().repeat(16));
+    ///
+    /// assert_eq!(statistics.chi_square, 0.0);
+    /// assert_eq!(statistics.mean, 127.5);
+    /// ```
+    pub fn new(data: &[u8]) -> Self {
+        let mut counts = [0_usize; 256];
+
+        for byte in data {
+            counts[*byte as usize] += 1;
+        }
+
+        let expected = data.len() as f64 / counts.len() as f64;
+        let total: usize = counts
+            .iter()
+            .enumerate()
+            .map(|(value, count)| value * count)
+            .sum();
+
+        ByteStatistics {
+            entropy: shannon_entropy(data),
+            chi_square: counts
+                .iter()
+                .map(|count| (*count as f64 - expected).powi(2) / expected)
+                .sum(),
+            mean: total as f64 / data.len() as f64,
+        }
+    }
+
+    /// Encrypted data is indistinguishable from uniformly random data: its byte values are evenly distributed, and
+    /// average out to the middle of the byte range. Compressed data has high entropy, but is measurably less uniform.
+    pub fn is_uniform(&self, data_size: usize) -> bool {
+        // Mean and standard deviation of uniformly distributed byte values
+        const UNIFORM_MEAN: f64 = 127.5;
+        const UNIFORM_STDDEV: f64 = 73.9;
+
+        // Allow the mean to stray by three standard errors
+        let max_mean_error = 3.0 * UNIFORM_STDDEV / (data_size as f64).sqrt();
+
+        self.chi_square <= CHI_SQUARE_CRITICAL_VALUE
+            && (self.mean - UNIFORM_MEAN).abs() <= max_mean_error
+    }
+}
+
+/// Finds abrupt changes between low and high entropy data that are not explained by any of the provided signatures.
+///
+/// Such cliffs often mark the start or end of compressed or encrypted data that no signature identified. Entropy is
+/// measured over `ENTROPY_BLOCK_SIZE` blocks, so reported offsets are block aligned; one block of mixed data between
+/// the low and high entropy blocks is tolerated, as the data rarely starts or ends on a block boundary.
+pub fn entropy_cliffs(file_data: &[u8], signatures: &[SignatureResult]) -> Vec<SignatureResult> {
+    let mut cliffs: Vec<SignatureResult> = vec![];
+
+    // A short trailing block has artificially low entropy, so ignore it
+    let entropies: Vec<f32> = file_data
+        .chunks_exact(ENTROPY_BLOCK_SIZE)
+        .map(shannon_entropy)
+        .collect();
+
+    // Index and entropy of the last block that was either low or high entropy
+    let mut previous: Option<(usize, f32)> = None;
+
+    for (i, entropy) in entropies.iter().enumerate() {
+        let is_low = *entropy <= LOW_ENTROPY;
+        let is_high = *entropy >= HIGH_ENTROPY;
+
+        if !is_low && !is_high {
+            continue;
+        }
+
+        if let Some((previous_index, previous_entropy)) = previous {
+            let previous_low = previous_entropy <= LOW_ENTROPY;
+
+            if previous_low == is_high && i - previous_index <= 2 {
+                // The cliff is at the end of the last block that was low or high entropy
+                let offset = (previous_index + 1) * ENTROPY_BLOCK_SIZE;
+
+                if !is_explained(offset, signatures) {
+                    let change = match is_high {
+                        true => "rises",
+                        false => "falls",
+                    };
+
+                    let meaning = match is_high {
+                        true => "start",
+                        false => "end",
+                    };
+
+                    cliffs.push(SignatureResult {
+                        offset,
+                        name: "entropy_cliff".to_string(),
+                        description: format!(
+                            "{ENTROPY_CLIFF_DESCRIPTION}, entropy {change} from {previous_entropy:.2} to {entropy:.2} bits per byte, possible {meaning} of compressed or encrypted data"
+                        ),
+                        confidence: CONFIDENCE_LOW,
+                        heuristic: true,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        previous = Some((i, *entropy));
+    }
+
+    cliffs
+}
+
+/// Returns true if the offset is within a block of the start or end of a signature, or inside of a signature's data
+fn is_explained(offset: usize, signatures: &[SignatureResult]) -> bool {
+    signatures.iter().any(|signature| {
+        offset + ENTROPY_BLOCK_SIZE >= signature.offset
+            && offset <= signature.offset + signature.size + ENTROPY_BLOCK_SIZE
+    })
+}
+
+/// Classifies the regions of the data that are not part of any signature as likely encrypted, likely compressed, or
+/// padding.
+///
+/// High entropy regions are classified by the uniformity of their byte distribution (see `ByteStatistics`); padding is
+/// found with `padding::find_padding`. Regions of data that are none of these, such as code or text, are not reported.
+pub fn classify_regions(file_data: &[u8], file_map: &[SignatureResult]) -> Vec<SignatureResult> {
+    let mut verdicts: Vec<SignatureResult> = vec![];
+    let padding = padding::find_padding(file_data, file_map);
+
+    for region in &padding {
+        verdicts.push(SignatureResult {
+            offset: region.offset,
+            size: region.size,
+            name: "classify".to_string(),
+            description: format!(
+                "{}, padding, size: {} bytes",
+                CLASSIFY_DESCRIPTION, region.size
+            ),
+            confidence: CONFIDENCE_LOW,
+            heuristic: true,
+            ..Default::default()
+        });
+    }
+
+    // Classify the data between any padding in each unidentified region
+    for (start, end) in padding::unidentified_regions(file_data.len(), file_map) {
+        let mut offset = start;
+
+        for region in padding
+            .iter()
+            .filter(|region| region.offset >= start && region.offset < end)
+        {
+            verdicts.extend(classify_high_entropy(file_data, offset, region.offset));
+            offset = region.offset + region.size;
+        }
+
+        verdicts.extend(classify_high_entropy(file_data, offset, end));
+    }
+
+    verdicts.sort_by_key(|verdict| verdict.offset);
+    verdicts
+}
+
+/// Finds runs of high entropy blocks between the start and end offsets, and classifies each as encrypted or compressed
+fn classify_high_entropy(file_data: &[u8], start: usize, end: usize) -> Vec<SignatureResult> {
+    let mut verdicts: Vec<SignatureResult> = vec![];
+    let mut runs: Vec<(usize, usize)> = vec![];
+    let mut run_start: Option<usize> = None;
+    let mut offset = start;
+
+    while offset < end {
+        let block_end = std::cmp::min(offset + ENTROPY_BLOCK_SIZE, end);
+
+        // A short trailing block has artificially low entropy; it is part of any run that it ends
+        let is_high = match block_end - offset < ENTROPY_BLOCK_SIZE && run_start.is_some() {
+            true => true,
+            false => shannon_entropy(&file_data[offset..block_end]) >= HIGH_ENTROPY,
+        };
+
+        match (is_high, run_start) {
+            (true, None) => run_start = Some(offset),
+            (false, Some(run_offset)) => {
+                runs.push((run_offset, offset));
+                run_start = None;
+            }
+            _ => (),
+        }
+
+        offset = block_end;
+    }
+
+    if let Some(run_offset) = run_start {
+        runs.push((run_offset, end));
+    }
+
+    for (run_start, run_end) in runs {
+        let size = run_end - run_start;
+
+        if size < MIN_CLASSIFY_SIZE {
+            continue;
+        }
+
+        let statistics = ByteStatistics::new(&file_data[run_start..run_end]);
+
+        let verdict = match statistics.is_uniform(size) {
+            true => "likely encrypted",
+            false => "likely compressed",
+        };
+
+        verdicts.push(SignatureResult {
+            offset: run_start,
+            size,
+            name: "classify".to_string(),
+            description: format!(
+                "{}, {}, size: {} bytes, entropy: {:.3} bits per byte, chi-square: {:.1}, mean byte value: {:.2}",
+                CLASSIFY_DESCRIPTION,
+                verdict,
+                size,
+                statistics.entropy,
+                statistics.chi_square,
+                statistics.mean
+            ),
+            confidence: CONFIDENCE_LOW,
+            heuristic: true,
+            ..Default::default()
+        });
+    }
+
+    verdicts
+}
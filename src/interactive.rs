@@ -0,0 +1,152 @@
+//! Interactive selection of the signatures to extract from the target file, requested with `--interactive`.
+//!
+//! After the target file has been scanned, each signature result is listed with an index, and the user enters
+//! the indices of the signatures to extract, e.g. `1,3,5-7`. Enter `all` to extract everything, or nothing to
+//! skip extraction of the target file entirely.
+use crate::binwalk::{AnalysisResults, Binwalk};
+use crate::locked;
+use crate::signatures::common::SignatureResult;
+use log::{info, warn};
+use std::io;
+use std::io::{BufRead, Write};
+
+/// Returned when the user's selection can not be parsed
+#[derive(Debug, Default, Clone)]
+pub struct SelectionError {
+    pub message: String,
+}
+
+/// Signature results of the target file, and the subset of those results that the user selected for extraction
+#[derive(Debug, Default, Clone)]
+pub struct Selection {
+    pub file_map: Vec<SignatureResult>,
+    pub selected: Vec<SignatureResult>,
+}
+
+/// Parses a selection of 1-based indices, such as `1,3,5-7`, into a sorted list of 0-based indices
+pub fn parse_selection(input: &str, count: usize) -> Result<Vec<usize>, SelectionError> {
+    const SELECT_ALL: &str = "all";
+    const SELECT_NONE: &str = "none";
+
+    let input = input.trim();
+
+    if input.is_empty() || input.eq_ignore_ascii_case(SELECT_NONE) {
+        return Ok(vec![]);
+    }
+
+    if input.eq_ignore_ascii_case(SELECT_ALL) {
+        return Ok((0..count).collect());
+    }
+
+    let parse_index = |index: &str| -> Result<usize, SelectionError> {
+        match index.trim().parse::<usize>() {
+            Ok(index) if index >= 1 && index <= count => Ok(index - 1),
+            _ => Err(SelectionError {
+                message: format!("'{}' is not an index between 1 and {}", index.trim(), count),
+            }),
+        }
+    };
+
+    let mut indices: Vec<usize> = Vec::new();
+
+    for item in input.split(|c: char| c == ',' || c.is_whitespace()) {
+        if item.is_empty() {
+            continue;
+        }
+
+        match item.split_once('-') {
+            None => indices.push(parse_index(item)?),
+            Some((first, last)) => {
+                let first = parse_index(first)?;
+                let last = parse_index(last)?;
+
+                if first > last {
+                    return Err(SelectionError {
+                        message: format!("'{item}' is not an ascending range"),
+                    });
+                }
+
+                indices.extend(first..=last);
+            }
+        }
+    }
+
+    indices.sort();
+    indices.dedup();
+
+    Ok(indices)
+}
+
+/// Prompts the user until a valid selection is entered; if there is no more input, nothing is selected
+pub fn prompt_selection(file_map: &[SignatureResult]) -> Selection {
+    let mut selection = Selection {
+        file_map: file_map.to_vec(),
+        ..Default::default()
+    };
+
+    if file_map.is_empty() {
+        return selection;
+    }
+
+    let stdin = io::stdin();
+
+    loop {
+        print!(
+            "Select signatures to extract by index or range (1-{}), 'all', or nothing to skip extraction: ",
+            file_map.len()
+        );
+        let _ = io::stdout().flush();
+
+        let mut input = String::new();
+
+        match stdin.lock().read_line(&mut input) {
+            Err(e) => {
+                warn!("Failed to read selection: {e}");
+                return selection;
+            }
+            Ok(0) => {
+                println!();
+                warn!("No selection was entered, nothing will be extracted");
+                return selection;
+            }
+            Ok(_) => match parse_selection(&input, file_map.len()) {
+                Err(e) => println!("Invalid selection: {}", e.message),
+                Ok(indices) => {
+                    selection.selected = indices
+                        .into_iter()
+                        .map(|index| file_map[index].clone())
+                        .collect();
+
+                    info!(
+                        "Selected {} of {} signatures for extraction",
+                        selection.selected.len(),
+                        file_map.len()
+                    );
+
+                    return selection;
+                }
+            },
+        }
+    }
+}
+
+/// Extracts the selected signatures from the target file data, which has already been scanned
+pub fn extract_selection(
+    bw: &Binwalk,
+    file_data: &[u8],
+    file_path: &str,
+    selection: Selection,
+) -> AnalysisResults {
+    let mut results = AnalysisResults {
+        file_path: file_path.to_string(),
+        file_map: selection.file_map,
+        ..Default::default()
+    };
+
+    if !selection.selected.is_empty() {
+        results.extractions = bw.extract(file_data, file_path, &selection.selected);
+        results.locked = locked::collect(&results.file_map, &results.extractions);
+    }
+
+    results
+}
@@ -6,8 +6,22 @@ use std::io::Seek;
 use std::io::Write;
 
 use crate::binwalk::AnalysisResults;
+use crate::changelog::Changelog;
+use crate::cluster::ClusterResults;
+use crate::credentials::CredentialSummary;
+use crate::diff::FirmwareDiff;
 use crate::display;
 use crate::entropy::FileEntropy;
+use crate::extractors::common::{ExtractionProgress, ExtractionResult};
+use crate::firmwaredb::FirmwareMatch;
+use crate::locked::LockedContent;
+use crate::manifest::VerificationResults;
+use crate::network::NetworkSummary;
+use crate::patches::PatchFinding;
+use crate::policy::{PolicyResults, PolicyViolation};
+use crate::secrets::SecretsResults;
+use crate::signatures::common::SignatureResult;
+use crate::toolchain::ToolchainSummary;
 
 const STDOUT: &str = "-";
 const JSON_LIST_START: &str = "[\n";
@@ -17,7 +31,130 @@ const JSON_LIST_SEP: &str = ",\n";
 #[derive(Debug, Serialize, Deserialize)]
 pub enum JSONType {
     Entropy(FileEntropy),
-    Analysis(AnalysisResults),
+    Analysis(Box<AnalysisResults>),
+    Toolchain(ToolchainSummary),
+    Network(NetworkSummary),
+    Credentials(CredentialSummary),
+    Cluster(ClusterResults),
+    Secrets(SecretsResults),
+    Verification(VerificationResults),
+    Changelog(Changelog),
+    Diff(FirmwareDiff),
+    Policy(PolicyResults),
+}
+
+/// Events emitted in stream mode, one JSON object per line
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum StreamEvent {
+    /// A signature was identified in a file
+    Match {
+        file_path: String,
+        result: SignatureResult,
+    },
+    /// A suspicious, but unconfirmed, heuristic finding was reported for a file
+    Heuristic {
+        file_path: String,
+        result: SignatureResult,
+    },
+    /// Extraction of an identified signature completed
+    Extraction {
+        file_path: String,
+        signature_id: String,
+        signature_name: String,
+        offset: usize,
+        result: ExtractionResult,
+    },
+    /// An external extractor reported its progress
+    ExtractionProgress {
+        file_path: String,
+        progress: ExtractionProgress,
+    },
+    /// Encrypted content could not be opened during extraction
+    Locked {
+        file_path: String,
+        locked: LockedContent,
+    },
+    /// A patch signature matched a file
+    Finding {
+        file_path: String,
+        finding: PatchFinding,
+    },
+    /// A file, or one of its partitions, matched a known firmware release
+    KnownFirmware {
+        file_path: String,
+        firmware: FirmwareMatch,
+    },
+    /// A file violated a policy rule
+    PolicyViolation {
+        file_path: String,
+        violation: PolicyViolation,
+    },
+    /// Analysis of a file is complete
+    FileComplete {
+        file_path: String,
+        match_count: usize,
+    },
+    /// A resource limit was reached, and analysis results are incomplete
+    LimitReached { limit: String, message: String },
+    /// All files have been evaluated against the policy
+    PolicyComplete {
+        passed: bool,
+        violation_count: usize,
+    },
+}
+
+/// Returns the stream events for all signature and extraction results of a file that has already been analyzed
+pub fn analysis_events(results: &AnalysisResults) -> Vec<StreamEvent> {
+    let mut events: Vec<StreamEvent> = vec![];
+
+    for signature in &results.file_map {
+        events.push(StreamEvent::Match {
+            file_path: results.file_path.clone(),
+            result: signature.clone(),
+        });
+    }
+
+    for signature in &results.file_map {
+        if let Some(extraction_result) = results.extractions.get(&signature.id) {
+            events.push(StreamEvent::Extraction {
+                file_path: results.file_path.clone(),
+                signature_id: signature.id.clone(),
+                signature_name: signature.name.clone(),
+                offset: signature.offset,
+                result: extraction_result.clone(),
+            });
+        }
+    }
+
+    for locked in &results.locked {
+        events.push(StreamEvent::Locked {
+            file_path: results.file_path.clone(),
+            locked: locked.clone(),
+        });
+    }
+
+    events.push(StreamEvent::FileComplete {
+        file_path: results.file_path.clone(),
+        match_count: results.file_map.len(),
+    });
+
+    events
+}
+
+/// Writes a stream event to stdout as a single JSON Line
+pub fn stream_event(event: &StreamEvent) {
+    match serde_json::to_string(event) {
+        Err(e) => error!("Failed to convert stream event to JSON: {e}"),
+        Ok(json) => {
+            // Lock stdout so that events from different worker threads are never interleaved
+            let mut stdout = io::stdout().lock();
+
+            if let Err(e) = writeln!(stdout, "{json}").and_then(|_| stdout.flush()) {
+                error!("Failed to write stream event to stdout: {e}");
+            }
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -17,9 +17,28 @@
 //! }
 //! ```
 mod binwalk;
+pub mod blockdev;
+pub mod bruteforce;
+pub mod changelog;
 pub mod common;
+pub mod credentials;
+pub mod encoded;
 pub mod extractors;
+pub mod filetype;
+pub mod firmwaredb;
+pub mod fuzzyhash;
+pub mod hashes;
+pub mod heuristics;
+pub mod locked;
+pub mod network;
 mod magic;
+pub mod padding;
+pub mod patches;
+pub mod policy;
+pub mod provenance;
+pub mod report;
 pub mod signatures;
 pub mod structures;
+pub mod toolchain;
+pub mod transforms;
 pub use binwalk::{AnalysisResults, Binwalk, BinwalkError};
@@ -0,0 +1,44 @@
+//! Resource limits for running in constrained environments, such as CI containers.
+use std::time;
+
+/// Limits on the resources used during analysis; a value of None means no limit
+#[derive(Debug, Default, Clone)]
+pub struct ResourceLimits {
+    /// Maximum number of worker threads
+    pub max_threads: Option<usize>,
+    /// Files larger than this are not loaded into memory for analysis
+    pub max_file_size: Option<u64>,
+    /// Once this many bytes have been extracted, no more extracted files are queued for analysis
+    pub max_extracted_size: Option<u64>,
+    /// Once this much time has elapsed, no more files are queued for analysis
+    pub max_run_time: Option<time::Duration>,
+    /// External extraction utilities still running after this amount of time are killed
+    pub extractor_timeout: Option<time::Duration>,
+    /// File data held in memory by all worker threads is kept within this many bytes;
+    /// files larger than this are analyzed a window at a time
+    pub max_memory: Option<u64>,
+    /// Signature parsers still running after this amount of time are aborted
+    pub parser_timeout: Option<time::Duration>,
+}
+
+impl ResourceLimits {
+    /// Conservative limits used by the --ci profile
+    pub fn ci() -> ResourceLimits {
+        const MAX_THREADS: usize = 4;
+        const MAX_FILE_SIZE: u64 = 1024 * 1024 * 1024;
+        const MAX_EXTRACTED_SIZE: u64 = 8 * 1024 * 1024 * 1024;
+        const MAX_RUN_TIME_SECONDS: u64 = 60 * 60;
+        const EXTRACTOR_TIMEOUT_SECONDS: u64 = 10 * 60;
+        const PARSER_TIMEOUT_SECONDS: u64 = 30;
+
+        ResourceLimits {
+            max_threads: Some(MAX_THREADS),
+            max_file_size: Some(MAX_FILE_SIZE),
+            max_extracted_size: Some(MAX_EXTRACTED_SIZE),
+            max_run_time: Some(time::Duration::from_secs(MAX_RUN_TIME_SECONDS)),
+            extractor_timeout: Some(time::Duration::from_secs(EXTRACTOR_TIMEOUT_SECONDS)),
+            max_memory: None,
+            parser_timeout: Some(time::Duration::from_secs(PARSER_TIMEOUT_SECONDS)),
+        }
+    }
+}
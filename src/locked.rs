@@ -0,0 +1,140 @@
+//! Reporting of encrypted content that could not be opened during extraction.
+//!
+//! Signature parsers report encrypted containers they identify, such as LUKS volumes or password protected
+//! archives, in [`SignatureResult::locked`]; such content is only locked if the signature's data could not be extracted.
+//! Extractors report encrypted content they find but can not decrypt, such as encrypted archive entries, in
+//! [`ExtractionResult::locked`].
+
+use crate::extractors::common::ExtractionResult;
+use crate::signatures::common::SignatureResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Describes encrypted content that could not be opened, and the key material that would unlock it
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
+pub struct LockedContent {
+    /// Name of the signature whose data contains the locked content; auto-populated by [`collect`]
+    #[serde(default)]
+    pub signature_name: String,
+    /// Offset of the signature whose data contains the locked content; auto-populated by [`collect`]
+    #[serde(default)]
+    pub offset: usize,
+    /// What could not be opened, e.g., "encrypted ZIP entries: config.bin"
+    pub description: String,
+    /// The key material that would unlock the content, e.g., "ZIP password (ZipCrypto)"
+    pub key_material: String,
+}
+
+impl LockedContent {
+    /// Describes locked content; the signature name and offset are populated by [`collect`]
+    pub fn new(description: impl Into<String>, key_material: impl Into<String>) -> LockedContent {
+        LockedContent {
+            description: description.into(),
+            key_material: key_material.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Describes the encrypted entries of an archive; only the first few entry names are listed
+    pub fn encrypted_entries(
+        archive_type: &str,
+        entry_names: &[String],
+        key_material: impl Into<String>,
+    ) -> LockedContent {
+        const MAX_LISTED_ENTRIES: usize = 8;
+
+        let mut description = format!(
+            "{} encrypted {} entries: {}",
+            entry_names.len(),
+            archive_type,
+            entry_names
+                .iter()
+                .take(MAX_LISTED_ENTRIES)
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+
+        if entry_names.len() > MAX_LISTED_ENTRIES {
+            description = format!(
+                "{}, and {} more",
+                description,
+                entry_names.len() - MAX_LISTED_ENTRIES
+            );
+        }
+
+        LockedContent::new(description, key_material)
+    }
+}
+
+/// Returns all encrypted content that remained locked after extraction, in file map order.
+///
+/// Encrypted content identified by a signature parser is locked unless the signature was successfully extracted;
+/// encrypted content reported by an extractor could not be decrypted by that extractor, and is always locked.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::extractors::common::ExtractionResult;
+/// use binwalk::locked::{LockedContent, collect};
+/// use binwalk::signatures::common::SignatureResult;
+/// use std::collections::HashMap;
+///
+/// let signature = SignatureResult {
+///     id: "1".to_string(),
+///     name: "luks".to_string(),
+///     offset: 0x1000,
+///     locked: vec![LockedContent::new("LUKS encrypted volume", "LUKS passphrase")],
+///     ..Default::default()
+/// };
+///
+/// // LUKS volumes have no extractor
+/// let locked = collect(&[signature], &HashMap::new());
+///
+/// assert_eq!(locked.len(), 1);
+/// assert_eq!(locked[0].signature_name, "luks");
+/// assert_eq!(locked[0].offset, 0x1000);
+///
+/// // Nothing is locked if the signature was extracted
+/// let extracted_signature = SignatureResult {
+///     id: "2".to_string(),
+///     locked: vec![LockedContent::new("OpenSSL encrypted data", "passphrase")],
+///     ..Default::default()
+/// };
+/// let extractions = HashMap::from([(
+///     "2".to_string(),
+///     ExtractionResult {
+///         success: true,
+///         ..Default::default()
+///     },
+/// )]);
+///
+/// assert!(collect(&[extracted_signature], &extractions).is_empty());
+/// ```
+pub fn collect(
+    file_map: &[SignatureResult],
+    extractions: &HashMap<String, ExtractionResult>,
+) -> Vec<LockedContent> {
+    let mut locked_content: Vec<LockedContent> = vec![];
+
+    for signature in file_map {
+        let extraction = extractions.get(&signature.id);
+        let mut signature_locked_content: Vec<LockedContent> = vec![];
+
+        if !extraction.is_some_and(|extraction_result| extraction_result.success) {
+            signature_locked_content.extend(signature.locked.iter().cloned());
+        }
+
+        if let Some(extraction_result) = extraction {
+            signature_locked_content.extend(extraction_result.locked.iter().cloned());
+        }
+
+        for mut locked in signature_locked_content {
+            locked.signature_name = signature.name.clone();
+            locked.offset = signature.offset;
+            locked_content.push(locked);
+        }
+    }
+
+    locked_content
+}
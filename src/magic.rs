@@ -35,7 +35,7 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::sevenzip::sevenzip_magic(),
             parser: signatures::sevenzip::sevenzip_parser,
             description: signatures::sevenzip::DESCRIPTION.to_string(),
-            extractor: Some(extractors::sevenzip::sevenzip_extractor()),
+            extractor: Some(extractors::sevenzip::sevenzip_archive_extractor()),
         },
         // xz
         signatures::common::Signature {
@@ -168,7 +168,7 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::cpio::cpio_magic(),
             parser: signatures::cpio::cpio_parser,
             description: signatures::cpio::DESCRIPTION.to_string(),
-            extractor: Some(extractors::sevenzip::sevenzip_extractor()),
+            extractor: Some(extractors::cpio::cpio_extractor()),
         },
         // iso9660 primary volume
         signatures::common::Signature {
@@ -181,6 +181,17 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             description: signatures::iso9660::DESCRIPTION.to_string(),
             extractor: Some(extractors::iso9660::iso9660_extractor()),
         },
+        // UDF file system
+        signatures::common::Signature {
+            name: "udf".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::udf::udf_magic(),
+            parser: signatures::udf::udf_parser,
+            description: signatures::udf::DESCRIPTION.to_string(),
+            extractor: Some(extractors::udf::udf_extractor()),
+        },
         // linux kernel
         signatures::common::Signature {
             name: "linux_kernel".to_string(),
@@ -256,7 +267,7 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::uefi::uefi_volume_magic(),
             parser: signatures::uefi::uefi_volume_parser,
             description: signatures::uefi::VOLUME_DESCRIPTION.to_string(),
-            extractor: Some(extractors::uefi::uefi_extractor()),
+            extractor: Some(extractors::uefi::uefi_volume_extractor()),
         },
         // UEFI capsule image
         signatures::common::Signature {
@@ -289,7 +300,7 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::elf::elf_magic(),
             parser: signatures::elf::elf_parser,
             description: signatures::elf::DESCRIPTION.to_string(),
-            extractor: None,
+            extractor: Some(extractors::elf::elf_extractor()),
         },
         // CramFS
         signatures::common::Signature {
@@ -300,7 +311,7 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::cramfs::cramfs_magic(),
             parser: signatures::cramfs::cramfs_parser,
             description: signatures::cramfs::DESCRIPTION.to_string(),
-            extractor: Some(extractors::sevenzip::sevenzip_extractor()),
+            extractor: Some(extractors::cramfs::cramfs_extractor()),
         },
         // QNX IFS
         // TODO: The signature and extractor are untested. Need a sample IFS image.
@@ -334,7 +345,7 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::ext::ext_magic(),
             parser: signatures::ext::ext_parser,
             description: signatures::ext::DESCRIPTION.to_string(),
-            extractor: Some(extractors::tsk::tsk_extractor()),
+            extractor: Some(extractors::ext::ext_extractor()),
         },
         // CAB archive
         signatures::common::Signature {
@@ -345,7 +356,7 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::cab::cab_magic(),
             parser: signatures::cab::cab_parser,
             description: signatures::cab::DESCRIPTION.to_string(),
-            extractor: Some(extractors::cab::cab_extractor()),
+            extractor: Some(extractors::cab::cab_internal_extractor()),
         },
         // JFFS2
         signatures::common::Signature {
@@ -405,8 +416,8 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
         // zlib
         signatures::common::Signature {
             name: "zlib".to_string(),
-            // The magic bytes for this signature are only 2 bytes, only match on the beginning of a file
-            short: true,
+            // The magic bytes are only 2 bytes, but the header check and adler32 checksum validate zlib streams found mid-file
+            short: false,
             magic_offset: 0,
             always_display: false,
             magic: signatures::zlib::zlib_magic(),
@@ -459,6 +470,61 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             description: signatures::pem::PEM_PRIVATE_KEY_DESCRIPTION.to_string(),
             extractor: Some(extractors::pem::pem_key_extractor()),
         },
+        // x509 certificates
+        signatures::common::Signature {
+            name: "x509_certificate".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::x509::x509_magic(),
+            parser: signatures::x509::x509_parser,
+            description: signatures::x509::DESCRIPTION.to_string(),
+            extractor: Some(extractors::der::der_extractor()),
+        },
+        // pkcs#7 containers
+        signatures::common::Signature {
+            name: "pkcs7".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::pkcs::pkcs7_magic(),
+            parser: signatures::pkcs::pkcs7_parser,
+            description: signatures::pkcs::PKCS7_DESCRIPTION.to_string(),
+            extractor: Some(extractors::der::der_extractor()),
+        },
+        // pkcs#12 key stores
+        signatures::common::Signature {
+            name: "pkcs12".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: true,
+            magic: signatures::pkcs::pkcs12_magic(),
+            parser: signatures::pkcs::pkcs12_parser,
+            description: signatures::pkcs::PKCS12_DESCRIPTION.to_string(),
+            extractor: Some(extractors::der::der_extractor()),
+        },
+        // pkcs#1 rsa private keys
+        signatures::common::Signature {
+            name: "rsa_private_key".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: true,
+            magic: signatures::pkcs::rsa_private_key_magic(),
+            parser: signatures::pkcs::rsa_private_key_parser,
+            description: signatures::pkcs::RSA_PRIVATE_KEY_DESCRIPTION.to_string(),
+            extractor: Some(extractors::der::der_extractor()),
+        },
+        // pkcs#1 rsa public keys
+        signatures::common::Signature {
+            name: "rsa_public_key".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: true,
+            magic: signatures::pkcs::rsa_public_key_magic(),
+            parser: signatures::pkcs::rsa_public_key_parser,
+            description: signatures::pkcs::RSA_PUBLIC_KEY_DESCRIPTION.to_string(),
+            extractor: Some(extractors::der::der_extractor()),
+        },
         // netgear chk
         signatures::common::Signature {
             name: "chk".to_string(),
@@ -468,7 +534,18 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::chk::chk_magic(),
             parser: signatures::chk::chk_parser,
             description: signatures::chk::DESCRIPTION.to_string(),
-            extractor: None,
+            extractor: Some(extractors::chk::chk_extractor()),
+        },
+        // netgear dni
+        signatures::common::Signature {
+            name: "dni".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::dni::dni_magic(),
+            parser: signatures::dni::dni_parser,
+            description: signatures::dni::DESCRIPTION.to_string(),
+            extractor: Some(extractors::dni::dni_extractor()),
         },
         // trx
         signatures::common::Signature {
@@ -503,6 +580,28 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             description: signatures::srec::SREC_SHORT_DESCRIPTION.to_string(),
             extractor: Some(extractors::srec::srec_extractor()),
         },
+        // Intel HEX
+        signatures::common::Signature {
+            name: "ihex".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::ihex::ihex_magic(),
+            parser: signatures::ihex::ihex_parser,
+            description: signatures::ihex::IHEX_DESCRIPTION.to_string(),
+            extractor: Some(extractors::ihex::ihex_extractor()),
+        },
+        // Intel HEX (generic)
+        signatures::common::Signature {
+            name: "ihex_generic".to_string(),
+            short: true,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::ihex::ihex_short_magic(),
+            parser: signatures::ihex::ihex_parser,
+            description: signatures::ihex::IHEX_SHORT_DESCRIPTION.to_string(),
+            extractor: Some(extractors::ihex::ihex_extractor()),
+        },
         // Android sparse
         signatures::common::Signature {
             name: "android_sparse".to_string(),
@@ -558,6 +657,138 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             description: signatures::cfe::DESCRIPTION.to_string(),
             extractor: None,
         },
+        // Broadcom image tag
+        signatures::common::Signature {
+            name: "bcm_imagetag".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: true,
+            magic: signatures::bcm_imagetag::bcm_imagetag_magic(),
+            parser: signatures::bcm_imagetag::bcm_imagetag_parser,
+            description: signatures::bcm_imagetag::DESCRIPTION.to_string(),
+            extractor: Some(extractors::bcm_imagetag::bcm_imagetag_extractor()),
+        },
+        // Qualcomm MBN image
+        signatures::common::Signature {
+            name: "qcom_mbn".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::qualcomm::mbn_magic(),
+            parser: signatures::qualcomm::mbn_parser,
+            description: signatures::qualcomm::MBN_DESCRIPTION.to_string(),
+            extractor: Some(extractors::qualcomm::mbn_extractor()),
+        },
+        // Qualcomm SBL image
+        signatures::common::Signature {
+            name: "qcom_sbl".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::qualcomm::sbl_magic(),
+            parser: signatures::qualcomm::mbn_parser,
+            description: signatures::qualcomm::SBL_DESCRIPTION.to_string(),
+            extractor: Some(extractors::qualcomm::mbn_extractor()),
+        },
+        // Qualcomm signed ELF image
+        signatures::common::Signature {
+            name: "qcom_elf".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::qualcomm::qualcomm_elf_magic(),
+            parser: signatures::qualcomm::qualcomm_elf_parser,
+            description: signatures::qualcomm::ELF_DESCRIPTION.to_string(),
+            extractor: Some(extractors::qualcomm::qualcomm_elf_extractor()),
+        },
+        // Mediatek image header
+        signatures::common::Signature {
+            name: "mtk_image".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::mediatek::mtk_image_magic(),
+            parser: signatures::mediatek::mtk_image_parser,
+            description: signatures::mediatek::IMAGE_DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // Mediatek preloader
+        signatures::common::Signature {
+            name: "mtk_preloader".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::mediatek::mtk_preloader_magic(),
+            parser: signatures::mediatek::mtk_preloader_parser,
+            description: signatures::mediatek::PRELOADER_DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // Mediatek BootROM header
+        signatures::common::Signature {
+            name: "mtk_brom".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::mediatek::mtk_brom_magic(),
+            parser: signatures::mediatek::mtk_brom_parser,
+            description: signatures::mediatek::BROM_DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // Mediatek preloader EMI settings
+        signatures::common::Signature {
+            name: "mtk_bloader_info".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: true,
+            magic: signatures::mediatek::mtk_bloader_info_magic(),
+            parser: signatures::mediatek::mtk_bloader_info_parser,
+            description: signatures::mediatek::BLOADER_INFO_DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // Rockchip RKFW firmware
+        signatures::common::Signature {
+            name: "rockchip_rkfw".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: true,
+            magic: signatures::rockchip::rkfw_magic(),
+            parser: signatures::rockchip::rkfw_parser,
+            description: signatures::rockchip::RKFW_DESCRIPTION.to_string(),
+            extractor: Some(extractors::rockchip::rkfw_extractor()),
+        },
+        // Rockchip boot loader
+        signatures::common::Signature {
+            name: "rockchip_boot".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::rockchip::rkboot_magic(),
+            parser: signatures::rockchip::rkboot_parser,
+            description: signatures::rockchip::RKBOOT_DESCRIPTION.to_string(),
+            extractor: Some(extractors::rockchip::rkboot_extractor()),
+        },
+        // Rockchip RKAF firmware package
+        signatures::common::Signature {
+            name: "rockchip_rkaf".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::rockchip::rkaf_magic(),
+            parser: signatures::rockchip::rkaf_parser,
+            description: signatures::rockchip::RKAF_DESCRIPTION.to_string(),
+            extractor: Some(extractors::rockchip::rkaf_extractor()),
+        },
+        // Allwinner eGON boot image
+        signatures::common::Signature {
+            name: "allwinner_egon".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::allwinner::egon_magic(),
+            parser: signatures::allwinner::egon_parser,
+            description: signatures::allwinner::DESCRIPTION.to_string(),
+            extractor: None,
+        },
         // SEAMA firmware header
         signatures::common::Signature {
             name: "seama".to_string(),
@@ -567,18 +798,29 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::seama::seama_magic(),
             parser: signatures::seama::seama_parser,
             description: signatures::seama::DESCRIPTION.to_string(),
-            extractor: None,
+            extractor: Some(extractors::seama::seama_extractor()),
         },
-        // compress'd
+        // OpenWrt sysupgrade image
         signatures::common::Signature {
-            name: "compressd".to_string(),
-            short: true,
+            name: "openwrt_sysupgrade".to_string(),
+            short: false,
             magic_offset: 0,
             always_display: false,
-            magic: signatures::compressd::compressd_magic(),
-            parser: signatures::compressd::compressd_parser,
-            description: signatures::compressd::DESCRIPTION.to_string(),
-            extractor: Some(extractors::sevenzip::sevenzip_extractor()),
+            magic: signatures::openwrt::sysupgrade_magic(),
+            parser: signatures::openwrt::sysupgrade_parser,
+            description: signatures::openwrt::SYSUPGRADE_DESCRIPTION.to_string(),
+            extractor: Some(extractors::openwrt::sysupgrade_extractor()),
+        },
+        // OpenWrt fwtool metadata and signature blocks
+        signatures::common::Signature {
+            name: "openwrt_metadata".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::openwrt::fwimage_magic(),
+            parser: signatures::openwrt::fwimage_parser,
+            description: signatures::openwrt::METADATA_DESCRIPTION.to_string(),
+            extractor: None,
         },
         // rar archive
         signatures::common::Signature {
@@ -668,6 +910,28 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             description: signatures::ecos::EXCEPTION_HANDLER_DESCRIPTION.to_string(),
             extractor: None,
         },
+        // ecos redboot version
+        signatures::common::Signature {
+            name: "redboot".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: true,
+            magic: signatures::ecos::redboot_magic(),
+            parser: signatures::ecos::redboot_parser,
+            description: signatures::ecos::REDBOOT_DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // threadx version banner
+        signatures::common::Signature {
+            name: "threadx".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: true,
+            magic: signatures::threadx::threadx_magic(),
+            parser: signatures::threadx::threadx_parser,
+            description: signatures::threadx::DESCRIPTION.to_string(),
+            extractor: None,
+        },
         // dmg
         signatures::common::Signature {
             name: "dmg".to_string(),
@@ -690,6 +954,28 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             description: signatures::riff::DESCRIPTION.to_string(),
             extractor: Some(extractors::riff::riff_extractor()),
         },
+        // mp4
+        signatures::common::Signature {
+            name: "mp4".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::mp4::mp4_magic(),
+            parser: signatures::mp4::mp4_parser,
+            description: signatures::mp4::DESCRIPTION.to_string(),
+            extractor: Some(extractors::mp4::mp4_extractor()),
+        },
+        // mp3
+        signatures::common::Signature {
+            name: "mp3".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::mp3::mp3_magic(),
+            parser: signatures::mp3::mp3_parser,
+            description: signatures::mp3::DESCRIPTION.to_string(),
+            extractor: Some(extractors::mp3::mp3_extractor()),
+        },
         // openssl
         signatures::common::Signature {
             name: "openssl".to_string(),
@@ -732,7 +1018,7 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::tplink::tplink_magic(),
             parser: signatures::tplink::tplink_parser,
             description: signatures::tplink::DESCRIPTION.to_string(),
-            extractor: None,
+            extractor: Some(extractors::tplink::tplink_extractor()),
         },
         // HP PJL
         signatures::common::Signature {
@@ -789,6 +1075,17 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             description: signatures::pcap::PCAPNG_DESCRIPTION.to_string(),
             extractor: Some(extractors::pcap::pcapng_extractor()),
         },
+        // pcap
+        signatures::common::Signature {
+            name: "pcap".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::pcap::pcap_magic(),
+            parser: signatures::pcap::pcap_parser,
+            description: signatures::pcap::PCAP_DESCRIPTION.to_string(),
+            extractor: Some(extractors::pcap::pcap_extractor()),
+        },
         // RSA encrypted data
         signatures::common::Signature {
             name: "rsa".to_string(),
@@ -842,7 +1139,18 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::fat::fat_magic(),
             parser: signatures::fat::fat_parser,
             description: signatures::fat::DESCRIPTION.to_string(),
-            extractor: Some(extractors::tsk::tsk_extractor()),
+            extractor: Some(extractors::fat::fat_extractor()),
+        },
+        // exFAT
+        signatures::common::Signature {
+            name: "exfat".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::exfat::exfat_magic(),
+            parser: signatures::exfat::exfat_parser,
+            description: signatures::exfat::DESCRIPTION.to_string(),
+            extractor: Some(extractors::exfat::exfat_extractor()),
         },
         // EFI GPT
         signatures::common::Signature {
@@ -853,7 +1161,7 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::efigpt::efigpt_magic(),
             parser: signatures::efigpt::efigpt_parser,
             description: signatures::efigpt::DESCRIPTION.to_string(),
-            extractor: Some(extractors::sevenzip::sevenzip_extractor()),
+            extractor: Some(extractors::efigpt::efigpt_extractor()),
         },
         // RTK firmware header
         signatures::common::Signature {
@@ -932,6 +1240,17 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             description: signatures::luks::DESCRIPTION.to_string(),
             extractor: None,
         },
+        // dm-verity
+        signatures::common::Signature {
+            name: "dmverity".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::dmverity::dmverity_magic(),
+            parser: signatures::dmverity::dmverity_parser,
+            description: signatures::dmverity::DESCRIPTION.to_string(),
+            extractor: None,
+        },
         // TP-Link RTOS
         signatures::common::Signature {
             name: "tplink_rtos".to_string(),
@@ -974,7 +1293,7 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::ntfs::ntfs_magic(),
             parser: signatures::ntfs::ntfs_parser,
             description: signatures::ntfs::DESCRIPTION.to_string(),
-            extractor: Some(extractors::tsk::tsk_extractor()),
+            extractor: Some(extractors::ntfs::ntfs_extractor()),
         },
         // APFS
         signatures::common::Signature {
@@ -1086,17 +1405,6 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             description: signatures::shrs::DESCRIPTION.to_string(),
             extractor: Some(extractors::encfw::encfw_extractor()),
         },
-        // PKCS DER hashes
-        signatures::common::Signature {
-            name: "pkcs_der_hash".to_string(),
-            short: false,
-            magic_offset: 0,
-            always_display: false,
-            magic: signatures::pkcs_der::der_hash_magic(),
-            parser: signatures::pkcs_der::der_hash_parser,
-            description: signatures::pkcs_der::DESCRIPTION.to_string(),
-            extractor: None,
-        },
         // LogFS
         signatures::common::Signature {
             name: "logfs".to_string(),
@@ -1128,7 +1436,18 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             magic: signatures::android_bootimg::android_bootimg_magic(),
             parser: signatures::android_bootimg::android_bootimg_parser,
             description: signatures::android_bootimg::DESCRIPTION.to_string(),
-            extractor: None,
+            extractor: Some(extractors::android_bootimg::android_bootimg_extractor()),
+        },
+        // Android vendor boot image
+        signatures::common::Signature {
+            name: "android_vendor_bootimg".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::android_bootimg::android_vendor_bootimg_magic(),
+            parser: signatures::android_bootimg::android_vendor_bootimg_parser,
+            description: signatures::android_bootimg::VENDOR_DESCRIPTION.to_string(),
+            extractor: Some(extractors::android_bootimg::android_vendor_bootimg_extractor()),
         },
         // uboot
         signatures::common::Signature {
@@ -1229,6 +1548,239 @@ pub fn patterns() -> Vec<signatures::common::Signature> {
             description: signatures::hashes::MD5_DESCRIPTION.to_string(),
             extractor: None,
         },
+        // Broadcom NVRAM
+        signatures::common::Signature {
+            name: "bcm_nvram".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::nvram::broadcom_nvram_magic(),
+            parser: signatures::nvram::broadcom_nvram_parser,
+            description: signatures::nvram::BROADCOM_NVRAM_DESCRIPTION.to_string(),
+            extractor: Some(extractors::nvram::broadcom_nvram_extractor()),
+        },
+        // U-Boot environment
+        signatures::common::Signature {
+            name: "uboot_env".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::nvram::uboot_env_magic(),
+            parser: signatures::nvram::uboot_env_parser,
+            description: signatures::nvram::UBOOT_ENV_DESCRIPTION.to_string(),
+            extractor: Some(extractors::nvram::uboot_env_extractor()),
+        },
+        // MikroTik RouterBOOT config
+        signatures::common::Signature {
+            name: "routerboot".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::nvram::routerboot_magic(),
+            parser: signatures::nvram::routerboot_parser,
+            description: signatures::nvram::ROUTERBOOT_DESCRIPTION.to_string(),
+            extractor: Some(extractors::nvram::routerboot_config_extractor()),
+        },
+        // XOR obfuscated XML configuration
+        signatures::common::Signature {
+            name: "xor_config".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::config::xor_config_magic(),
+            parser: signatures::config::xor_config_parser,
+            description: signatures::config::DESCRIPTION.to_string(),
+            extractor: Some(extractors::config::xor_config_extractor()),
+        },
+        // TrueType / OpenType font
+        signatures::common::Signature {
+            name: "ttf".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::font::sfnt_magic(),
+            parser: signatures::font::sfnt_parser,
+            description: signatures::font::SFNT_DESCRIPTION.to_string(),
+            extractor: Some(extractors::font::sfnt_extractor()),
+        },
+        // WOFF web font
+        signatures::common::Signature {
+            name: "woff".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::font::woff_magic(),
+            parser: signatures::font::woff_parser,
+            description: signatures::font::WOFF_DESCRIPTION.to_string(),
+            extractor: Some(extractors::font::woff_extractor()),
+        },
+        // WebAssembly module
+        signatures::common::Signature {
+            name: "wasm".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::wasm::wasm_magic(),
+            parser: signatures::wasm::wasm_parser,
+            description: signatures::wasm::DESCRIPTION.to_string(),
+            extractor: Some(extractors::wasm::wasm_extractor()),
+        },
+        // Minified JavaScript
+        signatures::common::Signature {
+            name: "minified_js".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::webasset::minified_js_magic(),
+            parser: signatures::webasset::minified_js_parser,
+            description: signatures::webasset::DESCRIPTION.to_string(),
+            extractor: Some(extractors::webasset::minified_js_extractor()),
+        },
+        // Lua bytecode
+        signatures::common::Signature {
+            name: "luac".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::script::lua_magic(),
+            parser: signatures::script::lua_parser,
+            description: signatures::script::LUA_DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // Python bytecode
+        signatures::common::Signature {
+            name: "pyc".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::script::pyc_magic(),
+            parser: signatures::script::pyc_parser,
+            description: signatures::script::PYC_DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // Shebang script
+        signatures::common::Signature {
+            name: "shebang_script".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::script::shebang_magic(),
+            parser: signatures::script::shebang_parser,
+            description: signatures::script::SHEBANG_DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // Java class file
+        signatures::common::Signature {
+            name: "java_class".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::java::java_class_magic(),
+            parser: signatures::java::java_class_parser,
+            description: signatures::java::DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // Mach-O binary
+        signatures::common::Signature {
+            name: "macho".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::macho::macho_magic(),
+            parser: signatures::macho::macho_parser,
+            description: signatures::macho::DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // Mach-O universal binary
+        signatures::common::Signature {
+            name: "macho_universal".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::macho::macho_universal_magic(),
+            parser: signatures::macho::macho_universal_parser,
+            description: signatures::macho::UNIVERSAL_DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // Android DEX
+        signatures::common::Signature {
+            name: "dex".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::dex::dex_magic(),
+            parser: signatures::dex::dex_parser,
+            description: signatures::dex::DEX_DESCRIPTION.to_string(),
+            extractor: Some(extractors::dex::dex_extractor()),
+        },
+        // Android ODEX
+        signatures::common::Signature {
+            name: "odex".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::dex::odex_magic(),
+            parser: signatures::dex::odex_parser,
+            description: signatures::dex::ODEX_DESCRIPTION.to_string(),
+            extractor: Some(extractors::dex::dex_extractor()),
+        },
+        // Android VDEX
+        signatures::common::Signature {
+            name: "vdex".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::dex::vdex_magic(),
+            parser: signatures::dex::vdex_parser,
+            description: signatures::dex::VDEX_DESCRIPTION.to_string(),
+            extractor: Some(extractors::dex::dex_extractor()),
+        },
+        // Android ART image
+        signatures::common::Signature {
+            name: "art".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::dex::art_magic(),
+            parser: signatures::dex::art_parser,
+            description: signatures::dex::ART_DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // SQLite database
+        signatures::common::Signature {
+            name: "sqlite".to_string(),
+            short: false,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::sqlite::sqlite_magic(),
+            parser: signatures::sqlite::sqlite_parser,
+            description: signatures::sqlite::DESCRIPTION.to_string(),
+            extractor: Some(extractors::sqlite::sqlite_extractor()),
+        },
+        // Protocol buffers
+        signatures::common::Signature {
+            name: "protobuf".to_string(),
+            // There are no magic bytes for protobuf data, only match on the beginning of a file
+            short: true,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::protobuf::protobuf_magic(),
+            parser: signatures::protobuf::protobuf_parser,
+            description: signatures::protobuf::DESCRIPTION.to_string(),
+            extractor: None,
+        },
+        // FlatBuffers
+        signatures::common::Signature {
+            name: "flatbuffers".to_string(),
+            // There are no magic bytes for FlatBuffers data, only match on the beginning of a file
+            short: true,
+            magic_offset: 0,
+            always_display: false,
+            magic: signatures::flatbuffers::flatbuffers_magic(),
+            parser: signatures::flatbuffers::flatbuffers_parser,
+            description: signatures::flatbuffers::DESCRIPTION.to_string(),
+            extractor: None,
+        },
     ];
 
     binary_signatures
@@ -1,32 +1,61 @@
 use binwalk::AnalysisResults;
-use log::{debug, error, info};
-use std::collections::VecDeque;
+use log::{debug, error, info, warn};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::panic;
 use std::process;
 use std::process::ExitCode;
-use std::sync::mpsc;
+use std::sync::{Arc, mpsc};
 use std::thread;
 use std::time;
 use threadpool::ThreadPool;
 
 mod binwalk;
+mod blockdev;
+mod bruteforce;
+mod changelog;
 mod cliparser;
+mod cluster;
 mod common;
+mod credentials;
+mod diff;
 mod display;
+mod encoded;
 mod entropy;
 mod extractors;
+mod filetype;
+mod firmwaredb;
+mod fuzzyhash;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod hashes;
+mod heuristics;
+mod interactive;
 mod json;
+mod limits;
+mod locked;
 mod magic;
+mod manifest;
+mod mount;
+mod network;
+mod padding;
+mod patches;
+mod policy;
+mod presets;
+mod provenance;
+mod report;
+#[cfg(feature = "s3")]
+mod s3;
+mod secrets;
+mod serve;
 mod signatures;
 mod structures;
+mod toolchain;
+mod transforms;
 
 fn main() -> ExitCode {
     // File name used when reading from stdin
     const STDIN: &str = "stdin";
 
-    // Only use one thread if unable to auto-detect available core info
-    const DEFAULT_WORKER_COUNT: usize = 1;
-
     // Number of seconds to wait before printing debug progress info
     const PROGRESS_INTERVAL: u64 = 30;
 
@@ -50,15 +79,196 @@ fn main() -> ExitCode {
     let run_time = time::Instant::now();
     let mut last_progress_interval = time::Instant::now();
 
+    // Toolchain components identified across all analyzed files, if toolchain analysis was requested
+    let mut toolchain_summary = toolchain::ToolchainSummary::default();
+
+    // Network indicators identified across all analyzed files, if network indicator analysis was requested
+    let mut network_summary = network::NetworkSummary::default();
+
+    // Credentials identified across all analyzed files, if credential analysis was requested
+    let mut credential_summary = credentials::CredentialSummary::default();
+
+    // Total size of all extracted files, tracked only when there is a limit on it
+    let mut extracted_size: u64 = 0;
+
+    // Set if analysis was cut short by a resource limit
+    let mut limit_reached: bool = false;
+
+    // Set once a resource limit has been reached that prevents any more files from being analyzed
+    let mut stop_queuing: bool = false;
+
+    // External extraction utilities used, recorded only when a manifest was requested
+    let mut extraction_tools: BTreeSet<String> = BTreeSet::new();
+
+    // Hashes of the data of each extracted signature, recorded only when a manifest was requested
+    let mut extracted_data: BTreeSet<String> = BTreeSet::new();
+
+    // Hashes of each analyzed file and its artifacts, keyed by file path, recorded only when a hashes manifest was requested
+    let mut artifact_hashes: BTreeMap<String, hashes::ArtifactHashes> = BTreeMap::new();
+
+    // Extractions performed on each analyzed file, recorded only when a provenance tree was requested
+    let mut provenance_tree = provenance::ProvenanceTree::new();
+
+    // Amount of file data each worker thread may hold in memory, keyed by the file being analyzed
+    let mut worker_memory: HashMap<String, u64> = HashMap::new();
+
     // Initialize logging
     env_logger::init();
 
     // Process command line arguments
     let mut cliargs = cliparser::parse();
 
-    // If --list was specified, just display a list of signatures and return
-    if cliargs.list {
-        display::print_signature_list(cliargs.quiet, &magic::patterns());
+    // Resource limits are only enforced in CI mode, or when explicitly requested
+    let mut resource_limits = match cliargs.ci {
+        true => limits::ResourceLimits::ci(),
+        false => limits::ResourceLimits::default(),
+    };
+
+    if let Some(max_memory_mb) = cliargs.max_memory {
+        resource_limits.max_memory = Some(max_memory_mb * 1024 * 1024);
+    }
+
+    if let Some(max_extract_size_mb) = cliargs.max_extract_size {
+        resource_limits.max_extracted_size = Some(max_extract_size_mb * 1024 * 1024);
+    }
+
+    if let Some(parser_timeout_ms) = cliargs.parser_timeout {
+        resource_limits.parser_timeout = Some(time::Duration::from_millis(parser_timeout_ms));
+    }
+
+    // Load the signature bundle; a bundle file takes the place of the embedded bundle
+    let signature_bundle = match &cliargs.signatures {
+        None => signatures::bundle::load_embedded_bundle(),
+        Some(bundle_file) => signatures::bundle::load_bundle(bundle_file),
+    };
+
+    let mut plugin_signatures = match signature_bundle {
+        Err(e) => {
+            error!("{}", e.message);
+            return ExitCode::FAILURE;
+        }
+        Ok(bundle) => {
+            info!(
+                "Loaded {} signature bundle version {} ({} signatures, {} disabled)",
+                match bundle.embedded {
+                    true => "embedded",
+                    false => "external",
+                },
+                bundle.version,
+                bundle.signatures.len(),
+                bundle.disabled.len()
+            );
+
+            // Explicitly included signatures take precedence over those disabled by the bundle
+            if cliargs.include.is_none() && !bundle.disabled.is_empty() {
+                cliargs
+                    .exclude
+                    .get_or_insert_with(Vec::new)
+                    .extend(bundle.disabled);
+            }
+
+            bundle.signatures
+        }
+    };
+
+    // Load any user-defined signatures
+    if let Some(plugin_directory) = &cliargs.plugins {
+        match signatures::plugin::load_plugins(plugin_directory) {
+            Err(e) => {
+                error!("{}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(plugins) => plugin_signatures.extend(plugins),
+        }
+    }
+
+    // Load any user-defined patch signatures
+    let patch_signatures = match &cliargs.patches {
+        None => Arc::new(patches::PatchSignatures::default()),
+        Some(patch_directory) => match patches::load_patch_signatures(patch_directory) {
+            Err(e) => {
+                error!("{}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(patch_signatures) => {
+                info!("Loaded {} patch signatures", patch_signatures.len());
+                Arc::new(patch_signatures)
+            }
+        },
+    };
+
+    // Load the known firmware database, if specified
+    let firmware_database = match &cliargs.firmware_db {
+        None => Arc::new(firmwaredb::FirmwareDatabase::default()),
+        Some(database_path) => match firmwaredb::load_firmware_database(database_path) {
+            Err(e) => {
+                error!("{}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(firmware_database) => {
+                info!("Loaded {} known firmware hashes", firmware_database.len());
+                Arc::new(firmware_database)
+            }
+        },
+    };
+
+    // Load the release gating policy, if specified
+    let mut policy_evaluation = match &cliargs.policy {
+        None => None,
+        Some(policy_file) => match policy::load_policy(policy_file) {
+            Err(e) => {
+                error!("{}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(policy_evaluation) => {
+                info!(
+                    "Loaded {} policy rules",
+                    policy_evaluation.results.rule_count
+                );
+                Some(policy_evaluation)
+            }
+        },
+    };
+
+    // Mountable file systems are collected for the mount script, if one was requested
+    let mut mount_script = cliargs
+        .mount_script
+        .as_ref()
+        .map(|_| mount::MountScript::default());
+
+    // Load the file hashes of the previous version, if only changed files should be kept
+    let mut differential = match &cliargs.previous {
+        None => None,
+        Some(manifest_path) => match manifest::DifferentialExtraction::new(manifest_path) {
+            Err(e) => {
+                error!("{}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(differential) => Some(differential),
+        },
+    };
+
+    // --list is shorthand for the list command's default output format
+    let list_format = match &cliargs.command {
+        Some(cliparser::Command::List { format }) => Some(*format),
+        _ if cliargs.list => Some(cliparser::ListFormat::Text),
+        _ => None,
+    };
+
+    // If a list of signatures was requested, just display the list and return
+    if let Some(format) = list_format {
+        let mut signature_list = magic::patterns();
+        signature_list.extend(plugin_signatures);
+
+        match format {
+            cliparser::ListFormat::Text => {
+                display::print_signature_list(cliargs.quiet, &signature_list)
+            }
+            cliparser::ListFormat::Markdown => {
+                display::print_capabilities_markdown(cliargs.quiet, &signature_list)
+            }
+        }
+
         return ExitCode::SUCCESS;
     }
 
@@ -67,7 +277,228 @@ fn main() -> ExitCode {
         cliargs.file_name = Some(STDIN.to_string());
     }
 
-    let mut json_logger = json::JsonLogger::new(cliargs.log);
+    // Target file data that has already been read into memory
+    let mut target_data: Option<Vec<u8>> = None;
+
+    // Make sure the upload URL is valid before doing any analysis
+    #[cfg(feature = "s3")]
+    if let Some(upload_url) = &cliargs.upload {
+        if let Err(e) = s3::parse_s3_url(upload_url) {
+            error!("{}", e.message);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    // Objects in object storage are downloaded into memory; as with stdin, the object's file name is used as the target file name
+    #[cfg(feature = "s3")]
+    if let Some(url) = cliargs.file_name.clone() {
+        if s3::is_s3_url(&url) {
+            match s3::download(&url) {
+                Err(e) => {
+                    error!("{}", e.message);
+                    return ExitCode::FAILURE;
+                }
+                Ok((file_name, data)) => {
+                    cliargs.file_name = Some(file_name);
+                    target_data = Some(data);
+                }
+            }
+        }
+    }
+
+    let mut json_logger = json::JsonLogger::new(cliargs.log.clone());
+
+    // If binary clustering was requested, cluster the binaries in the specified results directory and return
+    if let Some(cliparser::Command::Cluster {
+        results_directory,
+        threshold,
+    }) = &cliargs.command
+    {
+        match cluster::cluster(results_directory, *threshold as usize) {
+            Err(e) => {
+                error!("Binary clustering failed: {}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(cluster_results) => {
+                json_logger.log(json::JSONType::Cluster(cluster_results.clone()));
+                json_logger.close();
+
+                display::print_cluster_results(cliargs.quiet, cliargs.verbose, &cluster_results);
+            }
+        }
+
+        return ExitCode::SUCCESS;
+    }
+
+    // If secret correlation was requested, find the secrets shared between the images in the results directory and return
+    if let Some(cliparser::Command::Secrets { results_directory }) = &cliargs.command {
+        match secrets::correlate(results_directory) {
+            Err(e) => {
+                error!("Secret correlation failed: {}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(secrets_results) => {
+                json_logger.log(json::JSONType::Secrets(secrets_results.clone()));
+                json_logger.close();
+
+                display::print_shared_secrets(cliargs.quiet, &secrets_results);
+            }
+        }
+
+        return ExitCode::SUCCESS;
+    }
+
+    // If manifest verification was requested, verify the extracted files against the manifest and return
+    if let Some(cliparser::Command::Verify { manifest }) = &cliargs.command {
+        match manifest::verify(
+            manifest,
+            cliargs.internal_only || cfg!(feature = "internal-only"),
+        ) {
+            Err(e) => {
+                error!("Manifest verification failed: {}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(verification_results) => {
+                json_logger.log(json::JSONType::Verification(verification_results.clone()));
+                json_logger.close();
+
+                display::print_verification_results(cliargs.quiet, &verification_results);
+
+                if !verification_results.is_ok() {
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+
+        return ExitCode::SUCCESS;
+    }
+
+    // If a changelog was requested, compare the two firmware images and return
+    if let Some(cliparser::Command::Changelog {
+        old_image,
+        new_image,
+        report,
+    }) = &cliargs.command
+    {
+        match changelog::changelog(old_image, new_image, &cliargs.directory) {
+            Err(e) => {
+                error!("Changelog generation failed: {}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(changelog) => {
+                json_logger.log(json::JSONType::Changelog(changelog.clone()));
+                json_logger.close();
+
+                match report {
+                    report::ReportFormat::Text => {
+                        display::print_changelog(cliargs.quiet, &changelog)
+                    }
+                    report::ReportFormat::Markdown => {
+                        display::print_changelog_markdown(cliargs.quiet, &changelog)
+                    }
+                    report::ReportFormat::Html => {
+                        display::print_changelog_html(cliargs.quiet, &changelog)
+                    }
+                }
+            }
+        }
+
+        return ExitCode::SUCCESS;
+    }
+
+    // If a firmware diff was requested, compare the two firmware images and return
+    if let Some(cliparser::Command::Diff {
+        old_image,
+        new_image,
+    }) = &cliargs.command
+    {
+        match diff::diff(old_image, new_image, &cliargs.directory) {
+            Err(e) => {
+                error!("Firmware diff failed: {}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(firmware_diff) => {
+                json_logger.log(json::JSONType::Diff(firmware_diff.clone()));
+                json_logger.close();
+                display::print_diff(cliargs.quiet, &firmware_diff);
+            }
+        }
+
+        return ExitCode::SUCCESS;
+    }
+
+    // If the gRPC service was requested, serve scan requests until terminated
+    #[cfg(feature = "grpc")]
+    if let Some(cliparser::Command::Grpc {
+        listen,
+        token_file,
+        root,
+        max_scans,
+        scan_ttl,
+    }) = &cliargs.command
+    {
+        let server_options = grpc::ServerOptions {
+            listen: listen.clone(),
+            token_file: token_file.clone(),
+            root: root.clone(),
+            max_scans: *max_scans,
+            scan_ttl: time::Duration::from_secs(*scan_ttl),
+            extraction_directory: cliargs.directory.clone(),
+        };
+
+        let scan_configuration = grpc::ScanConfiguration {
+            worker_options: worker_options(
+                &cliargs,
+                &resource_limits,
+                patch_signatures,
+                firmware_database,
+            ),
+            worker_count: worker_count(&cliargs, &resource_limits),
+            cliargs: Arc::new(cliargs.clone()),
+            resource_limits: resource_limits.clone(),
+            plugin_signatures,
+        };
+
+        if let Err(e) = grpc::serve(server_options, scan_configuration) {
+            error!("{}", e.message);
+            return ExitCode::FAILURE;
+        }
+
+        return ExitCode::SUCCESS;
+    }
+
+    // If previous analysis results were requested, display or serve them and return
+    if let Some(cliparser::Command::Open {
+        results,
+        serve,
+        listen,
+    }) = &cliargs.command
+    {
+        let analysis_results = match serve::load_results(results) {
+            Err(e) => {
+                error!("{}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(analysis_results) => analysis_results,
+        };
+
+        if *serve {
+            if let Err(e) = serve::serve(analysis_results, listen) {
+                error!("{}", e.message);
+                return ExitCode::FAILURE;
+            }
+        } else {
+            for results in &analysis_results {
+                display::print_analysis_results(
+                    cliargs.quiet,
+                    !results.extractions.is_empty(),
+                    results,
+                );
+            }
+        }
+
+        return ExitCode::SUCCESS;
+    }
 
     // If entropy analysis was requested, generate the entropy graph and return
     if cliargs.entropy {
@@ -90,17 +521,22 @@ fn main() -> ExitCode {
 
     // If extraction or data carving was requested, we need to initialize the output directory
     if cliargs.extract || cliargs.carve {
-        output_directory = Some(cliargs.directory);
+        output_directory = Some(cliargs.directory.clone());
     }
 
+    // Analysis results are collected for the report, if one was requested
+    let mut scan_report = cliargs
+        .report
+        .as_ref()
+        .map(|_| report::ScanReport::new(cliargs.file_name.as_deref().unwrap_or_default()));
+
     // Initialize binwalk
-    let binwalker = match binwalk::Binwalk::configure(
-        cliargs.file_name,
+    let mut binwalker = match configure_binwalk(
+        &cliargs,
+        &resource_limits,
+        cliargs.file_name.clone(),
         output_directory,
-        cliargs.include,
-        cliargs.exclude,
-        None,
-        cliargs.search_all,
+        plugin_signatures,
     ) {
         Err(e) => {
             error!("Binwalk initialization failed: {}", e.message);
@@ -109,18 +545,21 @@ fn main() -> ExitCode {
         Ok(bw) => bw,
     };
 
-    // If the user specified --threads, honor that request; else, auto-detect available parallelism
-    let available_workers = cliargs.threads.unwrap_or_else(|| {
-        // Get CPU core info
-        match thread::available_parallelism() {
-            // In case of error use the default
-            Err(e) => {
-                error!("Failed to retrieve CPU core info: {e}");
-                DEFAULT_WORKER_COUNT
-            }
-            Ok(coreinfo) => coreinfo.get(),
-        }
-    });
+    // Hash the data of extracted signatures to record it in the manifest, and to skip data unchanged from the previous version
+    binwalker.hash_extracted_data = cliargs.manifest || differential.is_some();
+    binwalker.unchanged_data = differential
+        .as_ref()
+        .map(|differential| differential.previous_data.clone())
+        .unwrap_or_default();
+
+    // Report the progress of long running external extractors, so that extraction doesn't appear to hang
+    binwalker.external_options.progress = match (cliargs.stream, cliargs.quiet) {
+        (true, _) => Some(stream_extraction_progress),
+        (false, false) => Some(display::print_extraction_progress),
+        (false, true) => None,
+    };
+
+    let available_workers = worker_count(&cliargs, &resource_limits);
 
     // Sanity check the number of available worker threads
     if available_workers < 1 {
@@ -132,6 +571,13 @@ fn main() -> ExitCode {
     let workers = ThreadPool::new(available_workers);
     let (worker_tx, worker_rx) = mpsc::channel();
 
+    let worker_options = worker_options(
+        &cliargs,
+        &resource_limits,
+        patch_signatures,
+        firmware_database,
+    );
+
     /*
      * Set a custom panic handler.
      * This ensures that when any thread panics, the default panic handler will be invoked
@@ -148,6 +594,59 @@ fn main() -> ExitCode {
         binwalker.base_target_file
     );
 
+    // Dump artifacts are cleaned up before the target file is scanned, so its data must be read up front
+    if let Some(transform_names) = &cliargs.transform {
+        let mut file_data = match target_data.take() {
+            Some(data) => data,
+            None => match common::read_input(&binwalker.base_target_file, cliargs.stdin) {
+                Err(e) => {
+                    error!("Failed to read {}: {}", binwalker.base_target_file, e);
+                    return ExitCode::FAILURE;
+                }
+                Ok(data) => data,
+            },
+        };
+
+        for transform_name in transform_names {
+            if let Some(transformed_data) = transforms::apply(transform_name, &file_data) {
+                info!(
+                    "Applied {} transform: {} bytes in, {} bytes out",
+                    transform_name,
+                    file_data.len(),
+                    transformed_data.len()
+                );
+                file_data = transformed_data;
+            }
+        }
+
+        target_data = Some(file_data);
+    }
+
+    // In interactive mode, the target file is scanned up front so that the user can select which signatures to extract
+    let mut interactive_selection: Option<interactive::Selection> = None;
+
+    if cliargs.interactive {
+        let file_data = match target_data.take() {
+            Some(data) => data,
+            None => match common::read_input(&binwalker.base_target_file, false) {
+                Err(e) => {
+                    error!("Failed to read {}: {}", binwalker.base_target_file, e);
+                    return ExitCode::FAILURE;
+                }
+                Ok(data) => data,
+            },
+        };
+
+        let file_map = binwalker.scan_bytes(&file_data);
+
+        if !file_map.is_empty() {
+            display::print_signature_selection(&binwalker.base_target_file, &file_map);
+        }
+
+        interactive_selection = Some(interactive::prompt_selection(&file_map));
+        target_data = Some(file_data);
+    }
+
     // Queue the initial file path
     target_files.insert(target_files.len(), binwalker.base_target_file.clone());
 
@@ -156,6 +655,24 @@ fn main() -> ExitCode {
      * Loop until all pending thread jobs are complete and there are no more files in the queue.
      */
     while !target_files.is_empty() || workers.active_count() > 0 {
+        // Once the run time limit has been reached, stop analyzing new files
+        if let Some(max_run_time) = resource_limits.max_run_time {
+            if !stop_queuing && run_time.elapsed() >= max_run_time {
+                report_limit_reached(
+                    cliargs.stream,
+                    "run_time",
+                    &format!(
+                        "Run time limit of {} seconds reached, skipping {} queued files",
+                        max_run_time.as_secs(),
+                        target_files.len()
+                    ),
+                );
+                target_files.clear();
+                stop_queuing = true;
+                limit_reached = true;
+            }
+        }
+
         // If there are files waiting to be analyzed and there is at least one free thread in the pool
         if !target_files.is_empty() && workers.active_count() < workers.max_count() {
             // Get the next file path from the target_files queue
@@ -163,16 +680,64 @@ fn main() -> ExitCode {
                 .pop_front()
                 .expect("Failed to retrieve next file from the queue");
 
-            // Spawn a new worker for the new file
-            spawn_worker(
-                &workers,
-                binwalker.clone(),
-                target_file,
-                cliargs.stdin && file_count == 0,
-                cliargs.extract,
-                cliargs.carve,
-                worker_tx.clone(),
-            );
+            // Don't load files that exceed the file size limit into memory
+            if let Some(max_file_size) = resource_limits.max_file_size {
+                if let Ok(file_size) = blockdev::file_size(&target_file) {
+                    if file_size > max_file_size {
+                        report_limit_reached(
+                            cliargs.stream,
+                            "file_size",
+                            &format!(
+                                "Skipping {} ({} bytes), which exceeds the file size limit of {} bytes",
+                                target_file, file_size, max_file_size
+                            ),
+                        );
+                        limit_reached = true;
+                        continue;
+                    }
+                }
+            }
+
+            // Don't start analyzing a file until there is room for its data within the memory budget
+            let mut memory_available: bool = true;
+
+            if let Some(max_memory) = resource_limits.max_memory {
+                let file_size = match &target_data {
+                    Some(data) => data.len() as u64,
+                    None => blockdev::file_size(&target_file).unwrap_or(0),
+                };
+
+                // Files larger than the memory budget are analyzed in windows no larger than the memory budget
+                let file_memory = std::cmp::min(file_size, max_memory);
+
+                if workers.active_count() > 0
+                    && worker_memory.values().sum::<u64>() + file_memory > max_memory
+                {
+                    memory_available = false;
+                } else {
+                    worker_memory.insert(target_file.clone(), file_memory);
+                }
+            }
+
+            if memory_available {
+                // Spawn a new worker for the new file
+                spawn_worker(
+                    &workers,
+                    binwalker.clone(),
+                    WorkerTarget {
+                        file_path: target_file,
+                        stdin: cliargs.stdin && file_count == 0,
+                        data: target_data.take(),
+                        selection: interactive_selection.take(),
+                    },
+                    worker_options.clone(),
+                    worker_tx.clone(),
+                );
+            } else {
+                // Wait for a worker thread to finish before trying this file again
+                target_files.push_front(target_file);
+                thread::sleep(time::Duration::from_millis(1));
+            }
         }
 
         // Don't spin CPU cycles if there is no backlog of files to analyze
@@ -197,11 +762,75 @@ fn main() -> ExitCode {
             // Keep a tally of how many files have been analyzed
             file_count += 1;
 
+            // The worker thread is done with this file's data
+            worker_memory.remove(&results.file_path);
+
             // Log analysis results to JSON file
-            json_logger.log(json::JSONType::Analysis(results.clone()));
+            json_logger.log(json::JSONType::Analysis(Box::new(results.clone())));
+
+            if let Some(scan_report) = &mut scan_report {
+                scan_report.files.push(results.clone());
+            }
+
+            // Aggregate toolchain results for all analyzed files
+            toolchain_summary.add(&results.toolchain);
+
+            // Aggregate network indicators for all analyzed files
+            network_summary.add(&results.file_path, &results.network);
+
+            // Aggregate credentials for all analyzed files
+            credential_summary.add(&results.file_path, &results.credentials);
+
+            // Evaluate the policy against this file's results; data read from stdin can not be read again
+            if let Some(policy_evaluation) = &mut policy_evaluation {
+                let violations =
+                    policy_evaluation.evaluate_file(&results, !(cliargs.stdin && file_count == 1));
+
+                if cliargs.stream {
+                    for violation in violations {
+                        json::stream_event(&json::StreamEvent::PolicyViolation {
+                            file_path: violation.file_path.clone(),
+                            violation,
+                        });
+                    }
+                }
+            }
+
+            // Collect the mountable file systems found in this file
+            if let Some(mount_script) = &mut mount_script {
+                mount_script.add(&results, !(cliargs.stdin && file_count == 1));
+            }
+
+            if cliargs.manifest {
+                extraction_tools.extend(manifest::external_tools(&binwalker, &results));
+                extracted_data.extend(
+                    results
+                        .extractions
+                        .values()
+                        .filter(|extraction| extraction.success || extraction.unchanged)
+                        .filter_map(|extraction| extraction.sha256.clone()),
+                );
+            }
+
+            if cliargs.hashes_manifest {
+                artifact_hashes.insert(results.file_path.clone(), results.hashes.clone());
+            }
+
+            if cliargs.provenance {
+                provenance_tree.add(&results);
+            }
 
             // Nothing found? Nothing else to do for this file.
-            if results.file_map.is_empty() {
+            if results.file_map.is_empty()
+                && results.toolchain.is_empty()
+                && results.network.is_empty()
+                && results.credentials.is_empty()
+                && results.findings.is_empty()
+                && results.firmware.is_empty()
+                && results.padding.is_empty()
+                && results.heuristics.is_empty()
+                && results.skipped_analyses.is_empty()
+            {
                 debug!("Found no results for file {}", results.file_path);
                 continue;
             }
@@ -211,8 +840,52 @@ fn main() -> ExitCode {
                 display::print_analysis_results(cliargs.quiet, cliargs.extract, &results);
             }
 
+            // Remove extracted files that are unchanged from the previous version, so that they are not analyzed again
+            if let Some(differential) = &mut differential {
+                for extraction_result in results.extractions.values() {
+                    if extraction_result.unchanged {
+                        differential.skipped_count += 1;
+                        differential.skipped_size +=
+                            extraction_result.size.unwrap_or_default() as u64;
+                        continue;
+                    }
+
+                    differential.prune(
+                        &binwalker.base_output_directory,
+                        &extraction_result.output_directory,
+                    );
+                }
+            }
+
+            // Keep a tally of the total size of extracted files
+            if let Some(max_extracted_size) = resource_limits.max_extracted_size {
+                for extraction_result in results.extractions.values() {
+                    for file_path in
+                        extractors::common::get_extracted_files(&extraction_result.output_directory)
+                    {
+                        if let Ok(metadata) = std::fs::metadata(&file_path) {
+                            extracted_size += metadata.len();
+                        }
+                    }
+                }
+
+                // Once the extracted size limit has been reached, stop analyzing extracted files
+                if extracted_size > max_extracted_size && !stop_queuing {
+                    report_limit_reached(
+                        cliargs.stream,
+                        "extracted_size",
+                        &format!(
+                            "Extracted size limit of {max_extracted_size} bytes reached, extracted files will not be analyzed"
+                        ),
+                    );
+                    target_files.clear();
+                    stop_queuing = true;
+                    limit_reached = true;
+                }
+            }
+
             // If running recursively, add extraction results to list of files to analyze
-            if cliargs.matryoshka {
+            if cliargs.matryoshka && !stop_queuing {
                 for (_signature_id, extraction_result) in results.extractions.into_iter() {
                     if !extraction_result.do_not_recurse {
                         for file_path in extractors::common::get_extracted_files(
@@ -227,8 +900,134 @@ fn main() -> ExitCode {
         }
     }
 
+    // Display and log the aggregated toolchain results
+    if cliargs.toolchain {
+        if let Some(scan_report) = &mut scan_report {
+            scan_report.toolchain = Some(toolchain_summary.clone());
+        }
+
+        display::print_toolchain_summary(cliargs.quiet, &toolchain_summary);
+        json_logger.log(json::JSONType::Toolchain(toolchain_summary));
+    }
+
+    // Display and log the aggregated network indicators
+    if cliargs.network {
+        if let Some(scan_report) = &mut scan_report {
+            scan_report.network = Some(network_summary.clone());
+        }
+
+        display::print_network_summary(cliargs.quiet, &network_summary);
+        json_logger.log(json::JSONType::Network(network_summary));
+    }
+
+    // Display and log the aggregated credentials, and write them to a report alongside the extracted files
+    if cliargs.credentials {
+        if let Some(scan_report) = &mut scan_report {
+            scan_report.credentials = Some(credential_summary.clone());
+        }
+
+        display::print_credential_summary(cliargs.quiet, &credential_summary);
+
+        if cliargs.carve || cliargs.extract {
+            if let Err(e) = credential_summary.write_report(&binwalker.base_output_directory) {
+                error!("{}", e.message);
+            }
+        }
+
+        json_logger.log(json::JSONType::Credentials(credential_summary));
+    }
+
+    // Display and log the policy results
+    let mut policy_passed: bool = true;
+
+    if let Some(policy_evaluation) = policy_evaluation {
+        let policy_results = policy_evaluation.results;
+        policy_passed = policy_results.passed();
+
+        if cliargs.stream {
+            json::stream_event(&json::StreamEvent::PolicyComplete {
+                passed: policy_passed,
+                violation_count: policy_results.violations.len(),
+            });
+        }
+
+        if let Some(scan_report) = &mut scan_report {
+            scan_report.policy = Some(policy_results.clone());
+        }
+
+        display::print_policy_results(cliargs.quiet, &policy_results);
+        json_logger.log(json::JSONType::Policy(policy_results));
+    }
+
     json_logger.close();
 
+    if let Some(differential) = &differential {
+        display::print_differential_summary(cliargs.quiet, differential);
+    }
+
+    // Record the hashes of all extracted files, if requested
+    if cliargs.manifest && (cliargs.carve || cliargs.extract) {
+        let unchanged = differential
+            .as_ref()
+            .map(|differential| differential.unchanged.as_slice())
+            .unwrap_or_default();
+
+        if let Err(e) = manifest::write_manifest(
+            &binwalker,
+            &extraction_tools,
+            &extracted_data,
+            unchanged,
+        ) {
+            error!("{}", e.message);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    // Record the hashes of all analyzed files and their artifacts, if requested
+    if cliargs.hashes_manifest && (cliargs.carve || cliargs.extract) {
+        if let Err(e) = hashes::write_hashes(&binwalker.base_output_directory, &artifact_hashes) {
+            error!("{}", e.message);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    // Record where each extracted file came from, if requested
+    if cliargs.provenance {
+        if let Err(e) = provenance_tree.write(
+            &binwalker.base_target_file,
+            &binwalker.base_output_directory,
+        ) {
+            error!("{}", e.message);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    // Write the mount script, if requested
+    if let (Some(mount_script), Some(script_path)) = (&mount_script, &cliargs.mount_script) {
+        match mount_script.write(script_path) {
+            Err(e) => {
+                error!("{}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(mount_count) => info!("Wrote {mount_count} mount commands to {script_path}"),
+        }
+    }
+
+    // Render and write the report, if requested
+    if let (Some(scan_report), Some(report_file)) = (&mut scan_report, &cliargs.report) {
+        scan_report.file_count = file_count;
+        scan_report.run_time = (run_time.elapsed().as_secs_f64() * 1000.0).round() / 1000.0;
+
+        if let Err(e) =
+            report::write_report(scan_report, report_file, cliargs.report_template.as_deref())
+        {
+            error!("{}", e.message);
+            return ExitCode::FAILURE;
+        }
+
+        info!("Wrote report to {report_file}");
+    }
+
     // If BINWALK_RM_SYMLINK env var was set, delete the base_target_file symlink
     if (cliargs.carve || cliargs.extract) && std::env::var(BINWALK_RM_SYMLINK).is_ok() {
         if let Err(e) = std::fs::remove_file(&binwalker.base_target_file) {
@@ -239,6 +1038,29 @@ fn main() -> ExitCode {
         }
     }
 
+    // Upload extraction results and the JSON log file to object storage, if requested
+    #[cfg(feature = "s3")]
+    if let Some(upload_url) = &cliargs.upload {
+        let output_directory = match cliargs.carve || cliargs.extract {
+            true => Some(binwalker.base_output_directory.as_str()),
+            false => None,
+        };
+
+        // JSON logs written to stdout can't be uploaded
+        let log_file = json_logger
+            .json_file
+            .as_deref()
+            .filter(|log_file| *log_file != "-");
+
+        match s3::upload_results(upload_url, output_directory, log_file) {
+            Err(e) => {
+                error!("{}", e.message);
+                return ExitCode::FAILURE;
+            }
+            Ok(upload_count) => info!("Uploaded {upload_count} files to {upload_url}"),
+        }
+    }
+
     // All done, show some basic statistics
     display::print_stats(
         cliargs.quiet,
@@ -248,9 +1070,34 @@ fn main() -> ExitCode {
         binwalker.pattern_count,
     );
 
+    // Incomplete analysis, or a failed policy check, is a failure
+    if limit_reached || !policy_passed {
+        return ExitCode::FAILURE;
+    }
+
     ExitCode::SUCCESS
 }
 
+/// Reports the progress of an external extractor in stream mode
+fn stream_extraction_progress(progress: &extractors::common::ExtractionProgress) {
+    json::stream_event(&json::StreamEvent::ExtractionProgress {
+        file_path: progress.file_path.clone(),
+        progress: progress.clone(),
+    });
+}
+
+/// Logs that a resource limit was reached, and reports it in stream mode
+fn report_limit_reached(stream: bool, limit: &str, message: &str) {
+    error!("{message}");
+
+    if stream {
+        json::stream_event(&json::StreamEvent::LimitReached {
+            limit: limit.to_string(),
+            message: message.to_string(),
+        });
+    }
+}
+
 /// Returns true if the specified results should be displayed to screen
 fn should_display(results: &AnalysisResults, file_count: usize, verbose: bool) -> bool {
     let mut display_results: bool = false;
@@ -260,7 +1107,15 @@ fn should_display(results: &AnalysisResults, file_count: usize, verbose: bool) -
      * contain signatures that we always want displayed, or which contain extractable signatures.
      * This can be overridden with the --verbose command line flag.
      */
-    if file_count == 1 || verbose || !results.extractions.is_empty() {
+    if file_count == 1
+        || verbose
+        || !results.extractions.is_empty()
+        || !results.findings.is_empty()
+        || !results.firmware.is_empty()
+        || !results.locked.is_empty()
+        || !results.heuristics.is_empty()
+        || !results.skipped_analyses.is_empty()
+    {
         display_results = true;
     } else {
         for signature in &results.file_map {
@@ -274,28 +1129,366 @@ fn should_display(results: &AnalysisResults, file_count: usize, verbose: bool) -
     display_results
 }
 
+/// Configures binwalk to analyze a target file with the analysis options and resource limits from the command line
+fn configure_binwalk(
+    cliargs: &cliparser::CliArgs,
+    resource_limits: &limits::ResourceLimits,
+    file_name: Option<String>,
+    output_directory: Option<String>,
+    plugin_signatures: Vec<signatures::common::Signature>,
+) -> Result<binwalk::Binwalk, binwalk::BinwalkError> {
+    let mut binwalker = binwalk::Binwalk::configure(
+        file_name,
+        output_directory,
+        cliargs.include.clone(),
+        cliargs.exclude.clone(),
+        Some(plugin_signatures),
+        cliargs.search_all,
+    )?;
+
+    // In CI mode, external extractors must never wait on a prompt
+    binwalker.external_options.non_interactive = cliargs.ci;
+    // Portable builds never run external utilities
+    binwalker.external_options.internal_only =
+        cliargs.internal_only || cfg!(feature = "internal-only");
+    binwalker.external_options.timeout = resource_limits.extractor_timeout;
+    binwalker.external_options.max_extract_size = resource_limits
+        .max_extracted_size
+        .map(|max_extracted_size| max_extracted_size as usize);
+    binwalker.parser_timeout = resource_limits.parser_timeout;
+    binwalker.only_types = cliargs.only_types.clone().unwrap_or_default();
+    binwalker.zlib_brute_force = cliargs.zlib_brute.map(|min_decompressed_size| {
+        min_decompressed_size.unwrap_or(bruteforce::DEFAULT_MIN_ZLIB_DECOMPRESSED_SIZE)
+    });
+    binwalker.lzma_brute_force = cliargs.lzma_brute.map(|min_decompressed_size| {
+        min_decompressed_size.unwrap_or(bruteforce::DEFAULT_MIN_LZMA_DECOMPRESSED_SIZE)
+    });
+    binwalker.scan_encoded = cliargs.encoded;
+
+    Ok(binwalker)
+}
+
+/// Returns the number of worker threads to analyze files with
+fn worker_count(cliargs: &cliparser::CliArgs, resource_limits: &limits::ResourceLimits) -> usize {
+    // Only use one thread if unable to auto-detect available core info
+    const DEFAULT_WORKER_COUNT: usize = 1;
+
+    // If the user specified --threads, honor that request; else, auto-detect available parallelism
+    cliargs.threads.unwrap_or_else(|| {
+        // Get CPU core info
+        let detected_workers = match thread::available_parallelism() {
+            // In case of error use the default
+            Err(e) => {
+                error!("Failed to retrieve CPU core info: {e}");
+                DEFAULT_WORKER_COUNT
+            }
+            Ok(coreinfo) => coreinfo.get(),
+        };
+
+        match resource_limits.max_threads {
+            None => detected_workers,
+            Some(max_threads) => std::cmp::min(detected_workers, max_threads),
+        }
+    })
+}
+
+/// Returns the analysis settings for worker threads, from the command line
+fn worker_options(
+    cliargs: &cliparser::CliArgs,
+    resource_limits: &limits::ResourceLimits,
+    patch_signatures: Arc<patches::PatchSignatures>,
+    firmware_database: Arc<firmwaredb::FirmwareDatabase>,
+) -> WorkerOptions {
+    WorkerOptions {
+        max_memory: resource_limits.max_memory,
+        extract: cliargs.extract,
+        carve: cliargs.carve,
+        toolchain: cliargs.toolchain,
+        network: cliargs.network,
+        credentials: cliargs.credentials,
+        padding: cliargs.padding,
+        hashes: cliargs.hashes,
+        heuristics: cliargs.heuristics.clone(),
+        patch_signatures,
+        firmware_database,
+        stream: cliargs.stream,
+    }
+}
+
+/// A file to be analyzed by a worker thread
+struct WorkerTarget {
+    file_path: String,
+    /// True if the file's data is read from stdin
+    stdin: bool,
+    /// The file's data, if it has already been read into memory
+    data: Option<Vec<u8>>,
+    /// Interactively selected signatures to extract; these have already been scanned
+    selection: Option<interactive::Selection>,
+}
+
+/// Analysis settings shared by all worker threads
+#[derive(Debug, Default, Clone)]
+struct WorkerOptions {
+    /// Files larger than this many bytes are analyzed a window of at most this many bytes at a time
+    max_memory: Option<u64>,
+    extract: bool,
+    carve: bool,
+    toolchain: bool,
+    network: bool,
+    credentials: bool,
+    padding: bool,
+    hashes: bool,
+    /// Heuristics to report; None if no heuristics were requested, empty if all were
+    heuristics: Option<Vec<String>>,
+    patch_signatures: Arc<patches::PatchSignatures>,
+    firmware_database: Arc<firmwaredb::FirmwareDatabase>,
+    stream: bool,
+}
+
 /// Spawn a worker thread to analyze a file
 fn spawn_worker(
     pool: &ThreadPool,
     bw: binwalk::Binwalk,
-    target_file: String,
-    stdin: bool,
-    do_extraction: bool,
-    do_carve: bool,
+    target: WorkerTarget,
+    options: WorkerOptions,
     worker_tx: mpsc::Sender<AnalysisResults>,
 ) {
+    let WorkerTarget {
+        file_path: target_file,
+        stdin,
+        data: target_data,
+        selection,
+    } = target;
+
+    let WorkerOptions {
+        max_memory,
+        extract: do_extraction,
+        carve: do_carve,
+        toolchain: do_toolchain,
+        network: do_network,
+        credentials: do_credentials,
+        padding: do_padding,
+        hashes: do_hashes,
+        heuristics,
+        patch_signatures,
+        firmware_database,
+        stream,
+    } = options;
+
     pool.execute(move || {
-        // Read in file data
-        let file_data = match common::read_input(&target_file, stdin) {
-            Err(_) => {
-                error!("Failed to read {target_file} data");
-                b"".to_vec()
+        // When only scanning stdin, there is no need to hold all the data in memory; stream it instead, if not already read
+        if stdin
+            && target_data.is_none()
+            && !do_extraction
+            && !do_carve
+            && !do_toolchain
+            && !do_network
+            && !do_credentials
+            && !do_padding
+            && !do_hashes
+            && heuristics.is_none()
+            && patch_signatures.is_empty()
+            && firmware_database.is_empty()
+        {
+            let results = AnalysisResults {
+                file_map: match bw.scan_reader(std::io::stdin().lock()) {
+                    Err(e) => {
+                        error!("Failed to read data from stdin: {e}");
+                        vec![]
+                    }
+                    Ok(file_map) => file_map,
+                },
+                file_path: target_file.clone(),
+                ..Default::default()
+            };
+
+            if stream {
+                for signature in &results.file_map {
+                    json::stream_event(&json::StreamEvent::Match {
+                        file_path: target_file.clone(),
+                        result: signature.clone(),
+                    });
+                }
+                stream_file_complete(&results);
             }
-            Ok(data) => data,
+
+            if let Err(e) = worker_tx.send(results) {
+                panic!(
+                    "Worker thread for {target_file} failed to send results back to main thread: {e}"
+                );
+            }
+
+            return;
+        }
+
+        // Files larger than the memory budget are never read into memory in their entirety
+        if let Some(max_memory) = max_memory {
+            let file_size = blockdev::file_size(&target_file).unwrap_or(0);
+
+            if !stdin && target_data.is_none() && file_size > max_memory {
+                let mut results =
+                    bw.analyze_windowed(&target_file, do_extraction, max_memory as usize);
+
+                // These analyses need all of the file's data in memory; record that they were skipped, so that the
+                // results are known to be incomplete
+                for (requested, analysis) in [
+                    (do_carve, "carve"),
+                    (do_toolchain, "toolchain"),
+                    (do_network, "network"),
+                    (do_credentials, "credentials"),
+                    (do_padding, "padding"),
+                    (heuristics.is_some(), "heuristics"),
+                    (!patch_signatures.is_empty(), "patches"),
+                    (!firmware_database.is_empty(), "firmware"),
+                    (do_hashes, "hashes"),
+                ] {
+                    if requested {
+                        results.skipped_analyses.push(analysis.to_string());
+                    }
+                }
+
+                if !results.skipped_analyses.is_empty() {
+                    warn!(
+                        "{} is larger than the memory budget; skipped analyses: {}",
+                        target_file,
+                        results.skipped_analyses.join(", ")
+                    );
+                }
+
+                if stream {
+                    stream_results(&results);
+                }
+
+                if let Err(e) = worker_tx.send(results) {
+                    panic!(
+                        "Worker thread for {target_file} failed to send results back to main thread: {e}"
+                    );
+                }
+
+                return;
+            }
+        }
+
+        // Read in file data, unless it has already been read into memory
+        let file_data = match target_data {
+            Some(data) => data,
+            None => match common::read_input(&target_file, stdin) {
+                Err(_) => {
+                    error!("Failed to read {target_file} data");
+                    b"".to_vec()
+                }
+                Ok(data) => data,
+            },
+        };
+
+        // Analyze target file, with extraction, if specified; interactively selected signatures have already been scanned
+        let mut results = match selection {
+            Some(selection) => {
+                interactive::extract_selection(&bw, &file_data, &target_file, selection)
+            }
+            None => match stream {
+                true => analyze_and_stream(&bw, &file_data, &target_file, do_extraction),
+                false => bw.analyze_buf(&file_data, &target_file, do_extraction),
+            },
         };
 
-        // Analyze target file, with extraction, if specified
-        let results = bw.analyze_buf(&file_data, &target_file, do_extraction);
+        // Fingerprint the compiler toolchain used to build the file, if requested
+        if do_toolchain {
+            results.toolchain = toolchain::fingerprint(&file_data);
+        }
+
+        // Identify network indicators compiled into executables, if requested
+        if do_network {
+            results.network = network::find_indicators(&file_data);
+        }
+
+        // Identify private keys, password hashes and other credentials, if requested
+        if do_credentials {
+            results.credentials = credentials::find_credentials(&target_file, &file_data);
+        }
+
+        // Identify known firmware releases, if a firmware database was loaded
+        if !firmware_database.is_empty() {
+            results.firmware = firmware_database.identify(&file_data, &results.file_map);
+
+            if stream {
+                for firmware_match in &results.firmware {
+                    json::stream_event(&json::StreamEvent::KnownFirmware {
+                        file_path: target_file.clone(),
+                        firmware: firmware_match.clone(),
+                    });
+                }
+            }
+        }
+
+        // Detect padding between identified signatures, and how much of the file was accounted for, if requested
+        if do_padding {
+            results.padding = padding::find_padding(&file_data, &results.file_map);
+            results.coverage =
+                padding::coverage(file_data.len(), &results.file_map, &results.padding);
+        }
+
+        // Report heuristic findings only if requested, and only those of the requested heuristics (all, if none were named)
+        match &heuristics {
+            None => results.heuristics.clear(),
+            Some(names) => {
+                let is_requested =
+                    |heuristic: &str| names.is_empty() || names.iter().any(|name| name == heuristic);
+
+                if is_requested("entropy_cliff") {
+                    // Cliffs at the start of other heuristic findings are explained by them
+                    let explained: Vec<signatures::common::SignatureResult> = results
+                        .file_map
+                        .iter()
+                        .chain(results.heuristics.iter())
+                        .cloned()
+                        .collect();
+
+                    results
+                        .heuristics
+                        .extend(heuristics::entropy_cliffs(&file_data, &explained));
+                }
+
+                // Classify data that no signature identified as likely encrypted, likely compressed, or padding
+                if is_requested("classify") {
+                    results.heuristics.extend(heuristics::classify_regions(
+                        &file_data,
+                        &results.file_map,
+                    ));
+                }
+
+                results.heuristics.sort_by_key(|heuristic| heuristic.offset);
+
+                if !names.is_empty() {
+                    results
+                        .heuristics
+                        .retain(|heuristic| names.contains(&heuristic.name));
+                }
+
+                if stream {
+                    for heuristic in &results.heuristics {
+                        json::stream_event(&json::StreamEvent::Heuristic {
+                            file_path: target_file.clone(),
+                            result: heuristic.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        // Check for known vulnerable or patched code, if any patch signatures were loaded
+        if !patch_signatures.is_empty() {
+            results.findings = patch_signatures.evaluate(&target_file, &file_data);
+
+            if stream {
+                for finding in &results.findings {
+                    json::stream_event(&json::StreamEvent::Finding {
+                        file_path: target_file.clone(),
+                        finding: finding.clone(),
+                    });
+                }
+            }
+        }
 
         // If data carving was requested as part of extraction, carve analysis results to disk
         if do_carve {
@@ -303,6 +1496,20 @@ fn spawn_worker(
             info!("Carved {carve_count} data blocks to disk from {target_file}");
         }
 
+        // Hash the file, and the data carved and extracted from it, if requested
+        if do_hashes {
+            results.hashes = hashes::hash_artifacts(
+                &file_data,
+                &results.file_map,
+                &results.extractions,
+                &bw.base_output_directory,
+            );
+        }
+
+        if stream {
+            stream_file_complete(&results);
+        }
+
         // Report file results back to main thread
         if let Err(e) = worker_tx.send(results) {
             panic!(
@@ -312,6 +1519,79 @@ fn spawn_worker(
     });
 }
 
+/// Streams all signature and extraction results for a file that has already been analyzed
+fn stream_results(results: &AnalysisResults) {
+    for event in json::analysis_events(results) {
+        json::stream_event(&event);
+    }
+}
+
+/// Streams each piece of encrypted content that could not be opened during extraction
+fn stream_locked_content(results: &AnalysisResults) {
+    for locked in &results.locked {
+        json::stream_event(&json::StreamEvent::Locked {
+            file_path: results.file_path.clone(),
+            locked: locked.clone(),
+        });
+    }
+}
+
+/// Analyze a data buffer, streaming each signature and extraction result to stdout as soon as it is available
+fn analyze_and_stream(
+    bw: &binwalk::Binwalk,
+    file_data: &[u8],
+    file_path: &str,
+    do_extraction: bool,
+) -> AnalysisResults {
+    let (file_map, heuristics) = bw.scan_with_heuristics(file_data);
+
+    let mut results = AnalysisResults {
+        file_path: file_path.to_string(),
+        file_map,
+        heuristics,
+        ..Default::default()
+    };
+
+    for signature in &results.file_map {
+        json::stream_event(&json::StreamEvent::Match {
+            file_path: file_path.to_string(),
+            result: signature.clone(),
+        });
+    }
+
+    if do_extraction {
+        // Extract one signature at a time, so that each extraction result can be reported as soon as it completes
+        for signature in &results.file_map {
+            for (signature_id, extraction_result) in
+                bw.extract(file_data, file_path, &vec![signature.clone()])
+            {
+                json::stream_event(&json::StreamEvent::Extraction {
+                    file_path: file_path.to_string(),
+                    signature_id: signature_id.clone(),
+                    signature_name: signature.name.clone(),
+                    offset: signature.offset,
+                    result: extraction_result.clone(),
+                });
+
+                results.extractions.insert(signature_id, extraction_result);
+            }
+        }
+
+        results.locked = locked::collect(&results.file_map, &results.extractions);
+        stream_locked_content(&results);
+    }
+
+    results
+}
+
+/// Signals the end of a file's results in stream mode
+fn stream_file_complete(results: &AnalysisResults) {
+    json::stream_event(&json::StreamEvent::FileComplete {
+        file_path: results.file_path.clone(),
+        match_count: results.file_map.len(),
+    });
+}
+
 /// Carve signatures identified during analysis to separate files on disk.
 /// Returns the number of carved files created.
 /// Note that unknown blocks of file data are also carved to disk, so the number of files
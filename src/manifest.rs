@@ -0,0 +1,628 @@
+//! Records the SHA256 hashes of extracted files, and verifies extracted files against a recorded manifest.
+//!
+//! A manifest from a previous firmware version can also be used to only extract the data whose contents have changed
+//! since that version, selected with `--previous`. Signatures whose data is unchanged are not extracted at all, and
+//! files extracted from changed data are only kept if their contents changed as well.
+//!
+//! Manifests also record the environment used for the scan (binwalk version, signature set and external
+//! tool versions), so that differing results between environments can be explained.
+
+use crate::binwalk::{AnalysisResults, Binwalk};
+use crate::changelog::normalize_path;
+use crate::extractors::common::{ExtractorType, find_executable, wait_with_timeout};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+/// Name of the manifest file written to the extraction directory
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Returned when a manifest can't be created or read
+#[derive(Debug, Default, Clone)]
+pub struct ManifestError {
+    pub message: String,
+}
+
+impl ManifestError {
+    pub fn new(message: &str) -> Self {
+        ManifestError {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// An extracted file recorded in a manifest
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path to the file, relative to the directory containing the manifest, using '/' as the path separator
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    /// True if the file was identical to a file extracted from the previous version, and was removed
+    #[serde(default)]
+    pub unchanged: bool,
+}
+
+/// The hashes of all files extracted from a target file, and the environment they were extracted in
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Manifest {
+    /// Name of the analyzed file
+    pub target_file: String,
+    /// SHA256 hash of the analyzed file
+    pub target_sha256: String,
+    /// Version of binwalk used for the scan
+    pub binwalk_version: String,
+    /// SHA256 hash of the signature definitions used for the scan, including any plugin signatures
+    pub signature_set_hash: String,
+    /// Versions of the external extraction utilities used for the scan, keyed by utility name
+    pub tools: BTreeMap<String, String>,
+    pub files: Vec<ManifestEntry>,
+    /// SHA256 hashes of the data of each extracted signature of a known size
+    pub extracted_data: Vec<String>,
+}
+
+/// Results of verifying extracted files against a manifest
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct VerificationResults {
+    /// Path to the manifest file
+    pub manifest: String,
+    /// Number of files whose hashes match the manifest
+    pub verified: usize,
+    /// Files whose hashes do not match the manifest
+    pub modified: Vec<String>,
+    /// Files listed in the manifest that no longer exist
+    pub missing: Vec<String>,
+    /// Files that exist, but are not listed in the manifest
+    pub added: Vec<String>,
+    /// Differences between the environment recorded in the manifest and the current environment;
+    /// these don't fail verification, but may explain differing extraction results
+    pub environment_differences: Vec<String>,
+}
+
+impl VerificationResults {
+    /// Returns true if all files match the manifest
+    pub fn is_ok(&self) -> bool {
+        self.modified.is_empty() && self.missing.is_empty() && self.added.is_empty()
+    }
+}
+
+/// Hashes all extracted files in the extraction directory, and writes the manifest to the extraction directory.
+/// `tools` are the external extraction utilities that were used, `extracted_data` are the hashes of the data of each
+/// extracted signature, and `unchanged` are the extracted files that were removed because they were unchanged from the
+/// previous version. Returns the path to the manifest file.
+pub fn write_manifest(
+    binwalker: &Binwalk,
+    tools: &BTreeSet<String>,
+    extracted_data: &BTreeSet<String>,
+    unchanged: &[ManifestEntry],
+) -> Result<String, ManifestError> {
+    let target_file = &binwalker.base_target_file;
+    let extraction_directory = &binwalker.base_output_directory;
+
+    let manifest_path = Path::new(extraction_directory)
+        .join(MANIFEST_FILE_NAME)
+        .display()
+        .to_string();
+
+    let mut manifest = Manifest {
+        target_file: Path::new(target_file)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        // The target file may not exist on disk, such as when reading from stdin
+        target_sha256: match sha256_file(target_file) {
+            Err(e) => {
+                warn!("Failed to hash {target_file}: {e}");
+                "".to_string()
+            }
+            Ok(hash) => hash,
+        },
+        binwalk_version: env!("CARGO_PKG_VERSION").to_string(),
+        signature_set_hash: signature_set_hash(binwalker),
+        tools: tools
+            .iter()
+            .map(|tool| {
+                (
+                    tool.clone(),
+                    tool_version(tool, binwalker.external_options.internal_only),
+                )
+            })
+            .collect(),
+        extracted_data: extracted_data.iter().cloned().collect(),
+        ..Default::default()
+    };
+
+    for relative_path in find_files(extraction_directory) {
+        let file_path = Path::new(extraction_directory).join(&relative_path);
+
+        let entry = fs::metadata(&file_path).and_then(|metadata| {
+            Ok(ManifestEntry {
+                sha256: sha256_file(&file_path)?,
+                size: metadata.len(),
+                path: relative_path,
+                unchanged: false,
+            })
+        });
+
+        match entry {
+            Err(e) => {
+                return Err(ManifestError::new(&format!(
+                    "Failed to hash {}: {}",
+                    file_path.display(),
+                    e
+                )));
+            }
+            Ok(entry) => manifest.files.push(entry),
+        }
+    }
+
+    // Unchanged files are recorded as well, so that this manifest can be used to extract the next version
+    manifest.files.extend(unchanged.iter().cloned());
+    manifest.files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let manifest_json = match serde_json::to_string_pretty(&manifest) {
+        Err(e) => {
+            return Err(ManifestError::new(&format!(
+                "Failed to serialize manifest: {e}"
+            )));
+        }
+        Ok(json) => json,
+    };
+
+    if let Err(e) = fs::write(&manifest_path, manifest_json) {
+        return Err(ManifestError::new(&format!(
+            "Failed to write manifest {manifest_path}: {e}"
+        )));
+    }
+
+    info!(
+        "Wrote hashes of {} extracted files to {}",
+        manifest.files.len(),
+        manifest_path
+    );
+
+    Ok(manifest_path)
+}
+
+/// Re-hashes the files listed in a manifest, reporting any that have been modified, removed, or added since the
+/// manifest was written. File paths are relative to the directory containing the manifest.
+/// In internal-only mode, the versions of the external utilities listed in the manifest are not checked.
+pub fn verify(
+    manifest_path: &str,
+    internal_only: bool,
+) -> Result<VerificationResults, ManifestError> {
+    let manifest = read_manifest(manifest_path)?;
+
+    let base_directory = Path::new(manifest_path)
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .display()
+        .to_string();
+
+    let mut results = VerificationResults {
+        manifest: manifest_path.to_string(),
+        ..Default::default()
+    };
+
+    let mut manifest_files: HashSet<String> = HashSet::new();
+
+    for entry in &manifest.files {
+        manifest_files.insert(entry.path.clone());
+
+        // Unchanged files were removed during extraction
+        if entry.unchanged {
+            continue;
+        }
+
+        let file_path = Path::new(&base_directory).join(&entry.path);
+
+        if !file_path.is_file() {
+            debug!("{} is missing", entry.path);
+            results.missing.push(entry.path.clone());
+            continue;
+        }
+
+        match sha256_file(&file_path) {
+            Ok(hash) if hash == entry.sha256 => results.verified += 1,
+            Ok(_) => {
+                debug!("{} has been modified", entry.path);
+                results.modified.push(entry.path.clone());
+            }
+            Err(e) => {
+                error!("Failed to hash {}: {}", file_path.display(), e);
+                results.modified.push(entry.path.clone());
+            }
+        }
+    }
+
+    for relative_path in find_files(&base_directory) {
+        if !manifest_files.contains(&relative_path) {
+            debug!("{relative_path} is not in the manifest");
+            results.added.push(relative_path);
+        }
+    }
+
+    results.environment_differences = environment_differences(&manifest, internal_only);
+
+    Ok(results)
+}
+
+/// Reads and parses a manifest file
+fn read_manifest(manifest_path: &str) -> Result<Manifest, ManifestError> {
+    match fs::read(manifest_path) {
+        Err(e) => Err(ManifestError::new(&format!(
+            "Failed to read manifest {manifest_path}: {e}"
+        ))),
+        Ok(manifest_data) => match serde_json::from_slice(&manifest_data) {
+            Err(e) => Err(ManifestError::new(&format!(
+                "Failed to parse manifest {manifest_path}: {e}"
+            ))),
+            Ok(manifest) => Ok(manifest),
+        },
+    }
+}
+
+/// Removes extracted files whose contents are identical to files extracted from a previous version, as recorded in
+/// the previous version's manifest. Files are compared by both their normalized path (see `changelog::normalize_path`)
+/// and their hash, so that identical content at different locations, e.g. empty or common files, is kept; empty
+/// files are always kept.
+///
+/// The data of signatures extracted from the previous version is not extracted at all;
+/// see `Binwalk.unchanged_data`.
+#[derive(Debug, Default, Clone)]
+pub struct DifferentialExtraction {
+    /// Normalized paths and SHA256 hashes of the files extracted from the previous version
+    pub previous_files: HashSet<(String, String)>,
+    /// SHA256 hashes of the signature data extracted from the previous version
+    pub previous_data: HashSet<String>,
+    /// Removed files, with paths relative to the extraction directory
+    pub unchanged: Vec<ManifestEntry>,
+    /// Total size of the removed files
+    pub unchanged_size: u64,
+    /// Number of extracted files that were kept
+    pub changed_count: usize,
+    /// Number of signatures that were not extracted because their data was unchanged
+    pub skipped_count: usize,
+    /// Total size of the data that was not extracted
+    pub skipped_size: u64,
+}
+
+impl DifferentialExtraction {
+    /// Loads the file hashes from a previous version's manifest
+    pub fn new(manifest_path: &str) -> Result<Self, ManifestError> {
+        let manifest = read_manifest(manifest_path)?;
+
+        info!(
+            "Loaded hashes of {} files and {} signatures extracted from previous version {}",
+            manifest.files.len(),
+            manifest.extracted_data.len(),
+            manifest.target_file
+        );
+
+        Ok(DifferentialExtraction {
+            previous_files: manifest
+                .files
+                .into_iter()
+                .filter(|entry| entry.size > 0)
+                .map(|entry| (normalize_path(Path::new(&entry.path)), entry.sha256))
+                .collect(),
+            previous_data: manifest.extracted_data.into_iter().collect(),
+            ..Default::default()
+        })
+    }
+
+    /// Removes the files in an extractor's output directory that are unchanged from the previous version.
+    /// Directories left empty are removed as well.
+    pub fn prune(&mut self, extraction_directory: &str, output_directory: &str) {
+        for relative_path in find_files(output_directory) {
+            let file_path = Path::new(output_directory).join(&relative_path);
+
+            let size = fs::metadata(&file_path)
+                .map(|metadata| metadata.len())
+                .unwrap_or_default();
+
+            // Empty files are identical to every other empty file, and are never considered unchanged
+            if size == 0 {
+                self.changed_count += 1;
+                continue;
+            }
+
+            let hash = match sha256_file(&file_path) {
+                Err(e) => {
+                    warn!("Failed to hash {}: {}", file_path.display(), e);
+                    continue;
+                }
+                Ok(hash) => hash,
+            };
+
+            // Record the path relative to the extraction directory, as the manifest does
+            let manifest_path = match file_path.strip_prefix(extraction_directory) {
+                Err(_) => relative_path,
+                Ok(path) => path
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            };
+
+            if !self
+                .previous_files
+                .contains(&(normalize_path(Path::new(&manifest_path)), hash.clone()))
+            {
+                self.changed_count += 1;
+                continue;
+            }
+
+            if let Err(e) = fs::remove_file(&file_path) {
+                warn!(
+                    "Failed to remove unchanged file {}: {}",
+                    file_path.display(),
+                    e
+                );
+                continue;
+            }
+
+            debug!("Removed unchanged file {}", file_path.display());
+
+            self.unchanged.push(ManifestEntry {
+                path: manifest_path,
+                size,
+                sha256: hash,
+                unchanged: true,
+            });
+            self.unchanged_size += size;
+        }
+
+        // Deepest directories are visited first, so nested empty directories are all removed; non-empty
+        // directories fail to be removed, and are left as-is
+        for entry in WalkDir::new(output_directory)
+            .min_depth(1)
+            .contents_first(true)
+            .into_iter()
+            .flatten()
+        {
+            if entry.file_type().is_dir() {
+                let _ = fs::remove_dir(entry.path());
+            }
+        }
+    }
+}
+
+/// Returns the external extraction utilities used to extract the signatures in the analysis results
+pub fn external_tools(binwalker: &Binwalk, results: &AnalysisResults) -> BTreeSet<String> {
+    let mut tools: BTreeSet<String> = BTreeSet::new();
+
+    for signature in &results.file_map {
+        if !results.extractions.contains_key(&signature.id) {
+            continue;
+        }
+
+        // As when extracting, a signature's preferred extractor takes precedence over the default extractor
+        let extractor = match &signature.preferred_extractor {
+            Some(preferred_extractor) => Some(preferred_extractor.clone()),
+            None => binwalker
+                .extractor_lookup_table
+                .get(&signature.name)
+                .cloned()
+                .flatten(),
+        };
+
+        if let Some(extractor) = extractor {
+            if let ExtractorType::External(command) = extractor.utility {
+                tools.insert(command);
+            }
+
+            // The fallback extractor is only run if the extractor failed; extraction results name the utility that ran
+            if let Some(ExtractorType::External(command)) =
+                extractor.fallback.map(|fallback| fallback.utility)
+            {
+                if results.extractions[&signature.id].extractor == command {
+                    tools.insert(command);
+                }
+            }
+        }
+    }
+
+    tools
+}
+
+/// Returns the SHA256 hash of all enabled signature definitions; this changes if signatures are added, removed,
+/// or modified, including with plugins or the --include and --exclude options.
+pub fn signature_set_hash(binwalker: &Binwalk) -> String {
+    // Some signatures' magic patterns are generated in no particular order, so each pattern is described on its
+    // own line, and the lines are sorted before hashing
+    let mut signature_lines: BTreeSet<String> = BTreeSet::new();
+
+    let pattern_signatures = (0..binwalker.patterns.len()).filter_map(|i| {
+        binwalker
+            .pattern_signature_table
+            .get(&i)
+            .map(|signature| (&binwalker.patterns[i], signature))
+    });
+    let short_signatures = binwalker.short_signatures.iter().flat_map(|signature| {
+        signature
+            .magic
+            .iter()
+            .map(move |pattern| (pattern, signature))
+    });
+
+    for (pattern, signature) in pattern_signatures.chain(short_signatures) {
+        // Internal extractor function addresses differ between builds, and are covered by the binwalk version
+        let extractor = match &signature.extractor {
+            None => "none".to_string(),
+            Some(extractor) => match &extractor.utility {
+                ExtractorType::None => "none".to_string(),
+                ExtractorType::Internal(_) => "internal".to_string(),
+                ExtractorType::Wasm(module) => format!("wasm:{module}"),
+                ExtractorType::External(command) => {
+                    format!("external:{} {}", command, extractor.arguments.join(" "))
+                }
+            },
+        };
+
+        signature_lines.insert(format!(
+            "{}\0{}\0{}\0{}\0{}\0{}\n",
+            signature.name,
+            signature.description,
+            signature.short,
+            signature.magic_offset,
+            hex::encode(pattern),
+            extractor
+        ));
+    }
+
+    let mut hasher = Sha256::new();
+
+    for line in &signature_lines {
+        hasher.update(line.as_bytes());
+    }
+
+    format!("{:x}", hasher.finalize())
+}
+
+/// Returns the version reported by an external utility, or "unknown" if it could not be determined.
+/// There is no standard version argument, so several are tried; the first line of output containing
+/// a digit is assumed to contain the version. Utilities are never run in internal-only mode.
+fn tool_version(command: &str, internal_only: bool) -> String {
+    const VERSION_ARGUMENTS: [&str; 3] = ["--version", "-version", "-V"];
+    const UNKNOWN_VERSION: &str = "unknown";
+    // Utilities that don't exit within this time are killed, and are not run again
+    const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+    if internal_only {
+        return UNKNOWN_VERSION.to_string();
+    }
+
+    let executable = match find_executable(command) {
+        None => return UNKNOWN_VERSION.to_string(),
+        Some(executable) => executable,
+    };
+
+    for argument in VERSION_ARGUMENTS {
+        let mut child = match process::Command::new(&executable)
+            .arg(argument)
+            .stdin(process::Stdio::null())
+            .stdout(process::Stdio::piped())
+            .stderr(process::Stdio::piped())
+            .spawn()
+        {
+            Err(_) => break,
+            Ok(child) => child,
+        };
+
+        let start_time = Instant::now();
+
+        if wait_with_timeout(&mut child, VERSION_TIMEOUT).is_err()
+            || start_time.elapsed() >= VERSION_TIMEOUT
+        {
+            break;
+        }
+
+        if let Ok(output) = child.wait_with_output() {
+            let output_text = format!(
+                "{}\n{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+
+            if let Some(version_line) = output_text
+                .lines()
+                .map(|line| line.trim())
+                .find(|line| line.chars().any(|c| c.is_ascii_digit()))
+            {
+                return version_line.to_string();
+            }
+        }
+    }
+
+    UNKNOWN_VERSION.to_string()
+}
+
+/// Compares the environment recorded in a manifest to the current environment
+fn environment_differences(manifest: &Manifest, internal_only: bool) -> Vec<String> {
+    let mut differences: Vec<String> = vec![];
+
+    let binwalk_version = env!("CARGO_PKG_VERSION");
+
+    if manifest.binwalk_version != binwalk_version {
+        differences.push(format!(
+            "binwalk version {} was used, this is version {}",
+            manifest.binwalk_version, binwalk_version
+        ));
+    }
+
+    // Plugins and signature filters aren't known here, so this is compared against the default signature set
+    if manifest.signature_set_hash != signature_set_hash(&Binwalk::new()) {
+        differences.push(
+            "Signature set differs from the default signature set; plugins or --include/--exclude may have been used"
+                .to_string(),
+        );
+    }
+
+    // External utilities can't be run to query their versions in internal-only mode
+    if internal_only {
+        return differences;
+    }
+
+    for (tool, manifest_version) in &manifest.tools {
+        let version = tool_version(tool, internal_only);
+
+        if *manifest_version != version {
+            differences.push(format!(
+                "{tool} version '{manifest_version}' was used, this system has version '{version}'"
+            ));
+        }
+    }
+
+    differences
+}
+
+/// Returns the paths, relative to the specified directory, of all regular files in the directory, sorted by name.
+/// Symlinks, and the manifest file itself, are excluded.
+fn find_files(directory: &str) -> Vec<String> {
+    let mut files: Vec<String> = vec![];
+
+    for entry in WalkDir::new(directory)
+        .sort_by_file_name()
+        .into_iter()
+        .flatten()
+    {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        if let Ok(relative_path) = entry.path().strip_prefix(directory) {
+            // Manifest paths always use '/' as the path separator
+            let relative_path = relative_path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            if relative_path != MANIFEST_FILE_NAME {
+                files.push(relative_path);
+            }
+        }
+    }
+
+    files
+}
+
+/// Returns the SHA256 hash of a file's contents, reading the file a block at a time
+fn sha256_file(file_path: impl AsRef<Path>) -> Result<String, io::Error> {
+    let mut hasher = Sha256::new();
+    let mut file = fs::File::open(file_path)?;
+
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -0,0 +1,269 @@
+//! Writes a helper script that mounts the file systems identified during analysis, selected with `--mount-script`.
+//!
+//! File systems are mounted directly from the analyzed files at their identified offsets, without extracting them.
+//! Scripts ending in `.bat` or `.cmd` are written as Windows batch files that mount each file system with OSFMount;
+//! all other scripts are written as shell scripts that mount each file system through a read-only Linux loop device.
+//! Only file systems that the target operating system can mount are included.
+
+use crate::binwalk::AnalysisResults;
+use log::{debug, warn};
+use std::fs;
+use std::path::Path;
+
+/// Returned when a mount script can not be written
+#[derive(Debug, Default, Clone)]
+pub struct MountScriptError {
+    pub message: String,
+}
+
+impl MountScriptError {
+    pub fn new(message: &str) -> Self {
+        MountScriptError {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Signature names of mountable file systems, their Linux file system type, and if Windows can mount them
+const FILE_SYSTEMS: [(&str, &str, bool); 10] = [
+    ("squashfs", "squashfs", false),
+    ("cramfs", "cramfs", false),
+    ("romfs", "romfs", false),
+    // The ext4 driver mounts ext2 and ext3 file systems as well
+    ("ext", "ext4", false),
+    ("btrfs", "btrfs", false),
+    ("iso9660", "iso9660", true),
+    ("udf", "udf", true),
+    ("fat", "vfat", true),
+    ("exfat", "exfat", true),
+    ("ntfs", "ntfs", true),
+];
+
+/// Script formats
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ScriptFormat {
+    /// Linux shell script using loop devices
+    #[default]
+    Shell,
+    /// Windows batch file using OSFMount
+    Batch,
+}
+
+/// A file system that can be mounted
+#[derive(Debug, Default, Clone)]
+struct MountEntry {
+    /// Absolute path to the file containing the file system
+    file_path: String,
+    offset: usize,
+    /// Size of the file system, if known
+    size: usize,
+    /// Linux file system type
+    fs_type: String,
+    windows_mountable: bool,
+    description: String,
+}
+
+/// Collects the mountable file systems found in all analyzed files
+#[derive(Debug, Default, Clone)]
+pub struct MountScript {
+    entries: Vec<MountEntry>,
+}
+
+impl MountScript {
+    /// Adds the mountable file systems found in a file's analysis results.
+    /// Files that do not exist on disk, such as data read from stdin, can not be mounted and are ignored.
+    pub fn add(&mut self, results: &AnalysisResults, readable: bool) {
+        if !readable {
+            return;
+        }
+
+        // Extracted files may be deleted or the extraction directory moved; reference the file by its absolute path,
+        // which also resolves the symlink to the target file in the extraction directory
+        let file_path = match fs::canonicalize(&results.file_path) {
+            Err(e) => {
+                warn!(
+                    "Failed to resolve {} for the mount script: {}",
+                    results.file_path, e
+                );
+                return;
+            }
+            Ok(path) => path.display().to_string(),
+        };
+
+        for signature in &results.file_map {
+            if let Some((_, fs_type, windows_mountable)) = FILE_SYSTEMS
+                .iter()
+                .find(|(name, _, _)| *name == signature.name)
+            {
+                debug!(
+                    "Adding {} file system at {:#X} in {} to the mount script",
+                    fs_type, signature.offset, file_path
+                );
+
+                self.entries.push(MountEntry {
+                    file_path: file_path.clone(),
+                    offset: signature.offset,
+                    size: signature.size,
+                    fs_type: fs_type.to_string(),
+                    windows_mountable: *windows_mountable,
+                    description: signature.description.clone(),
+                });
+            }
+        }
+    }
+
+    /// Writes the mount script; the script format is determined by the file extension.
+    /// Returns the number of file systems in the script.
+    pub fn write(&self, script_path: &str) -> Result<usize, MountScriptError> {
+        let format = match Path::new(script_path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("bat") | Some("cmd") => ScriptFormat::Batch,
+            _ => ScriptFormat::Shell,
+        };
+
+        let entries: Vec<&MountEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| format == ScriptFormat::Shell || entry.windows_mountable)
+            .collect();
+
+        let script = match format {
+            ScriptFormat::Shell => shell_script(&entries),
+            ScriptFormat::Batch => batch_script(&entries),
+        };
+
+        if let Err(e) = fs::write(script_path, script) {
+            return Err(MountScriptError::new(&format!(
+                "Failed to write mount script {script_path}: {e}"
+            )));
+        }
+
+        // Shell scripts are made executable for convenience; failure to do so is not fatal
+        #[cfg(unix)]
+        if format == ScriptFormat::Shell {
+            use std::os::unix::fs::PermissionsExt;
+
+            if let Err(e) = fs::set_permissions(script_path, fs::Permissions::from_mode(0o755)) {
+                warn!("Failed to make mount script {script_path} executable: {e}");
+            }
+        }
+
+        Ok(entries.len())
+    }
+}
+
+/// Generates a shell script that mounts each file system read-only at `$MOUNT_ROOT/<file name>_<offset>`
+fn shell_script(entries: &[&MountEntry]) -> String {
+    let mut script = vec![
+        "#!/bin/sh".to_string(),
+        "# File systems identified by binwalk; must be run as root.".to_string(),
+        "# Set MOUNT_ROOT to change where file systems are mounted.".to_string(),
+        "set -e".to_string(),
+        "".to_string(),
+        "MOUNT_ROOT=\"${MOUNT_ROOT:-/mnt/binwalk}\"".to_string(),
+    ];
+
+    for entry in entries {
+        let mount_point = format!("\"$MOUNT_ROOT\"/{}", shell_quote(&mount_point_name(entry)));
+
+        let mut options = format!("ro,loop,offset={}", entry.offset);
+        let mut losetup_options = format!("--offset {}", entry.offset);
+
+        if entry.size > 0 {
+            options.push_str(&format!(",sizelimit={}", entry.size));
+            losetup_options.push_str(&format!(" --sizelimit {}", entry.size));
+        }
+
+        script.push("".to_string());
+        script.push(format!(
+            "# {:#X}: {}",
+            entry.offset,
+            comment(&entry.description)
+        ));
+        script.push(format!(
+            "# Loop device only: losetup --find --show --read-only {} {}",
+            losetup_options,
+            shell_quote(&entry.file_path)
+        ));
+        script.push(format!("mkdir -p {mount_point}"));
+        script.push(format!(
+            "mount -t {} -o {} {} {}",
+            entry.fs_type,
+            options,
+            shell_quote(&entry.file_path),
+            mount_point
+        ));
+    }
+
+    script.push("".to_string());
+    script.join("\n")
+}
+
+/// Generates a batch file that mounts each file system read-only on the next free drive letter with OSFMount
+fn batch_script(entries: &[&MountEntry]) -> String {
+    let mut script = vec![
+        "@echo off".to_string(),
+        "REM File systems identified by binwalk; must be run as administrator.".to_string(),
+        "REM Set OSFMOUNT to the path of OSFMount.com if it is not in the PATH.".to_string(),
+        "if not defined OSFMOUNT set OSFMOUNT=OSFMount.com".to_string(),
+    ];
+
+    for entry in entries {
+        let mut parameters = format!(
+            "-a -t file -f \"{}\" -b {}",
+            batch_escape(&entry.file_path),
+            entry.offset
+        );
+
+        if entry.size > 0 {
+            parameters.push_str(&format!(" -s {}", entry.size));
+        }
+
+        script.push("".to_string());
+        script.push(format!(
+            "REM {:#X}: {}",
+            entry.offset,
+            batch_escape(&comment(&entry.description))
+        ));
+        script.push(format!("\"%OSFMOUNT%\" {parameters} -o ro -m #:"));
+    }
+
+    script.push("".to_string());
+    script.join("\r\n")
+}
+
+/// Mount point names are derived from the file name and file system offset, e.g., "rootfs.bin_0x1C0000"
+fn mount_point_name(entry: &MountEntry) -> String {
+    let file_name = Path::new(&entry.file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let safe_name: String = file_name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() || "-_.".contains(c) {
+            true => c,
+            false => '_',
+        })
+        .collect();
+
+    format!("{}_{:#X}", safe_name, entry.offset)
+}
+
+/// Signature descriptions may span multiple lines; comments must not
+fn comment(description: &str) -> String {
+    description.replace(['\r', '\n'], " ")
+}
+
+/// Quotes a string for use as a single shell word
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Escapes characters that are expanded in batch files
+fn batch_escape(text: &str) -> String {
+    text.replace('%', "%%")
+}
@@ -0,0 +1,192 @@
+//! Padding detection and coverage statistics.
+//!
+//! Padding regions are runs of erased flash (0xFF), zeros, or a short repeating vendor pad pattern that lie outside of
+//! any identified signature. Reporting them as single regions keeps them out of the way when reviewing results, and
+//! lets analysis coverage account for data that is known to be filler.
+
+use crate::signatures::common::SignatureResult;
+use serde::{Deserialize, Serialize};
+
+/// Runs of repeating data shorter than this are not considered padding
+pub const MIN_PADDING_SIZE: usize = 256;
+
+/// Longest repeating pattern, in bytes, that is considered padding
+pub const MAX_PATTERN_SIZE: usize = 16;
+
+/// A region of padding
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PaddingRegion {
+    /// File offset where the padding starts
+    pub offset: usize,
+    /// Size of the padding, in bytes
+    pub size: usize,
+    /// The byte pattern that is repeated throughout the padding
+    pub pattern: Vec<u8>,
+}
+
+impl PaddingRegion {
+    /// Human readable description of the padding
+    pub fn description(&self) -> String {
+        let kind = match self.pattern.as_slice() {
+            [0x00] => "null bytes".to_string(),
+            [0xFF] => "erased flash (0xFF)".to_string(),
+            pattern => {
+                let hex: Vec<String> = pattern.iter().map(|b| format!("{b:02X}")).collect();
+                format!("repeating pattern: {}", hex.join(" "))
+            }
+        };
+
+        format!("Padding, {}, size: {} bytes", kind, self.size)
+    }
+}
+
+/// How much of a file's data was accounted for during analysis
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Coverage {
+    /// Size of the file, in bytes
+    pub file_size: usize,
+    /// Number of bytes within identified signatures
+    pub identified_size: usize,
+    /// Number of bytes of padding outside of identified signatures
+    pub padding_size: usize,
+}
+
+impl Coverage {
+    /// Number of bytes that are neither identified nor padding
+    pub fn unknown_size(&self) -> usize {
+        self.file_size - self.identified_size - self.padding_size
+    }
+
+    /// Returns the percentage of the file that a number of bytes represents
+    pub fn percent(&self, size: usize) -> f64 {
+        match self.file_size {
+            0 => 0.0,
+            file_size => (size as f64 / file_size as f64) * 100.0,
+        }
+    }
+}
+
+/// Finds padding in the regions of the data that are not part of any signature result.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::padding::find_padding;
+///
+/// let data = [b"HEADER".to_vec(), vec![0xFF; 1024], b"\xDE\xAD".repeat(512), b"TRAILER".to_vec()].concat();
+///
+/// let padding = find_padding(&data, &[]);
+///
+/// assert_eq!(padding.len(), 2);
+/// assert_eq!((padding[0].offset, padding[0].size, padding[0].pattern.clone()), (6, 1024, vec![0xFF]));
+/// assert_eq!((padding[1].offset, padding[1].size, padding[1].pattern.clone()), (1030, 1024, vec![0xDE, 0xAD]));
+/// ```
+pub fn find_padding(data: &[u8], file_map: &[SignatureResult]) -> Vec<PaddingRegion> {
+    let mut padding: Vec<PaddingRegion> = vec![];
+
+    for (start, end) in unidentified_regions(data.len(), file_map) {
+        let mut offset = start;
+
+        while offset + MIN_PADDING_SIZE <= end {
+            match find_repeating_pattern(&data[offset..end]) {
+                Some((pattern_size, size)) => {
+                    padding.push(PaddingRegion {
+                        offset,
+                        size,
+                        pattern: data[offset..offset + pattern_size].to_vec(),
+                    });
+                    offset += size;
+                }
+                None => offset += 1,
+            }
+        }
+    }
+
+    padding
+}
+
+/// Calculates how much of the file was identified by signature results, or found to be padding.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::padding::{coverage, find_padding};
+///
+/// let data = [vec![0; 512], b"unknown data".to_vec()].concat();
+///
+/// let file_coverage = coverage(data.len(), &[], &find_padding(&data, &[]));
+///
+/// assert_eq!(file_coverage.padding_size, 512);
+/// assert_eq!(file_coverage.unknown_size(), 12);
+/// ```
+pub fn coverage(
+    file_size: usize,
+    file_map: &[SignatureResult],
+    padding: &[PaddingRegion],
+) -> Coverage {
+    let unidentified_size: usize = unidentified_regions(file_size, file_map)
+        .iter()
+        .map(|(start, end)| end - start)
+        .sum();
+
+    Coverage {
+        file_size,
+        identified_size: file_size - unidentified_size,
+        padding_size: padding.iter().map(|region| region.size).sum(),
+    }
+}
+
+/// Returns the start and end offsets of each region of the file that is not part of a signature result
+pub fn unidentified_regions(file_size: usize, file_map: &[SignatureResult]) -> Vec<(usize, usize)> {
+    let mut regions: Vec<(usize, usize)> = vec![];
+    let mut identified: Vec<(usize, usize)> = file_map
+        .iter()
+        .map(|result| {
+            (
+                result.offset.min(file_size),
+                result.offset.saturating_add(result.size).min(file_size),
+            )
+        })
+        .collect();
+
+    identified.sort();
+
+    let mut last_end: usize = 0;
+
+    for (start, end) in identified {
+        if start > last_end {
+            regions.push((last_end, start));
+        }
+
+        last_end = last_end.max(end);
+    }
+
+    if last_end < file_size {
+        regions.push((last_end, file_size));
+    }
+
+    regions
+}
+
+/// Looks for the shortest pattern that repeats for at least MIN_PADDING_SIZE bytes from the start of the data.
+/// Returns the size of the pattern and the number of bytes over which it repeats.
+fn find_repeating_pattern(data: &[u8]) -> Option<(usize, usize)> {
+    for pattern_size in 1..=MAX_PATTERN_SIZE {
+        if data.len() < pattern_size {
+            break;
+        }
+
+        let repeat_size = pattern_size
+            + data[pattern_size..]
+                .iter()
+                .zip(data)
+                .take_while(|(a, b)| a == b)
+                .count();
+
+        if repeat_size >= MIN_PADDING_SIZE {
+            return Some((pattern_size, repeat_size));
+        }
+    }
+
+    None
+}
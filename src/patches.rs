@@ -0,0 +1,405 @@
+//! Patch-level detection for known vulnerabilities, using user-supplied byte pattern "patch signatures".
+//!
+//! Each file in the patch signature directory defines a single patch signature. For example, `CVE-2023-1234.toml`:
+//!
+//! ```toml
+//! name = "CVE-2023-1234"
+//! description = "libfoo heap overflow in parse_header"
+//! # Optional: only evaluate files with these names
+//! file_names = ["libfoo.so", "libfoo.so.1"]
+//! # Hex encoded byte patterns; '??' matches any byte
+//! vulnerable = ["8b4508 ?? 83f810 7f"]
+//! fixed = ["8b4508 ?? 83f810 0f87"]
+//! ```
+//!
+//! A file is reported as vulnerable if only vulnerable patterns match, fixed if only fixed patterns match,
+//! or inconclusive if both match.
+use aho_corasick::AhoCorasick;
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Returned when patch signatures can not be loaded
+#[derive(Debug, Default, Clone)]
+pub struct PatchError {
+    pub message: String,
+}
+
+/// A patch signature definition, as read from a patch signature file
+#[derive(Debug, Clone, Deserialize)]
+struct PatchDefinition {
+    /// Unique name for the patch signature, typically a CVE ID
+    name: String,
+    /// Human readable description of the vulnerability
+    #[serde(default)]
+    description: String,
+    /// If not empty, only files with one of these names are evaluated
+    #[serde(default)]
+    file_names: Vec<String>,
+    /// Hex encoded byte patterns indicating vulnerable code
+    #[serde(default)]
+    vulnerable: Vec<String>,
+    /// Hex encoded byte patterns indicating patched code
+    #[serde(default)]
+    fixed: Vec<String>,
+}
+
+/// A byte pattern; None matches any byte
+#[derive(Debug, Clone)]
+struct BytePattern {
+    bytes: Vec<Option<u8>>,
+    /// Offset of the longest run of literal bytes in the pattern, used to quickly locate candidate matches
+    anchor_start: usize,
+    anchor_size: usize,
+}
+
+impl BytePattern {
+    /// Returns true if this pattern matches the data at the specified offset
+    fn matches(&self, data: &[u8], offset: usize) -> bool {
+        match data.get(offset..offset + self.bytes.len()) {
+            None => false,
+            Some(candidate) => self
+                .bytes
+                .iter()
+                .zip(candidate)
+                .all(|(expected, actual)| expected.is_none_or(|value| value == *actual)),
+        }
+    }
+
+    fn anchor(&self) -> Vec<u8> {
+        self.bytes[self.anchor_start..self.anchor_start + self.anchor_size]
+            .iter()
+            .map(|byte| byte.unwrap_or_default())
+            .collect()
+    }
+}
+
+/// A validated patch signature
+#[derive(Debug, Clone)]
+struct PatchSignature {
+    name: String,
+    description: String,
+    file_names: Vec<String>,
+    vulnerable: Vec<BytePattern>,
+    fixed: Vec<BytePattern>,
+}
+
+/// Patch status of a file, as determined by a patch signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatchStatus {
+    Vulnerable,
+    Fixed,
+    Inconclusive,
+}
+
+/// Result of a patch signature that matched a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchFinding {
+    pub name: String,
+    pub description: String,
+    pub status: PatchStatus,
+    /// File offsets of all matching vulnerable patterns
+    pub vulnerable_offsets: Vec<usize>,
+    /// File offsets of all matching fixed patterns
+    pub fixed_offsets: Vec<usize>,
+}
+
+/// A set of loaded patch signatures
+#[derive(Debug, Clone, Default)]
+pub struct PatchSignatures {
+    signatures: Vec<PatchSignature>,
+    /// Searches for the anchors of all patterns at once
+    anchor_search: Option<AhoCorasick>,
+    /// Maps anchor search pattern IDs to (signature index, is fixed pattern, pattern index)
+    anchor_table: Vec<(usize, bool, usize)>,
+}
+
+impl PatchSignatures {
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    /// Evaluates all applicable patch signatures against a file's data
+    pub fn evaluate(&self, file_path: &str, file_data: &[u8]) -> Vec<PatchFinding> {
+        let mut findings: Vec<PatchFinding> = vec![];
+
+        let anchor_search = match &self.anchor_search {
+            None => return findings,
+            Some(anchor_search) => anchor_search,
+        };
+
+        let file_name = Path::new(file_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        // Signatures restricted to specific file names do not apply to other files
+        let applicable: Vec<bool> = self
+            .signatures
+            .iter()
+            .map(|signature| {
+                signature.file_names.is_empty() || signature.file_names.contains(&file_name)
+            })
+            .collect();
+
+        if !applicable.contains(&true) {
+            return findings;
+        }
+
+        let mut vulnerable_offsets: Vec<Vec<usize>> = vec![vec![]; self.signatures.len()];
+        let mut fixed_offsets: Vec<Vec<usize>> = vec![vec![]; self.signatures.len()];
+
+        for anchor_match in anchor_search.find_overlapping_iter(file_data) {
+            let (signature_index, is_fixed, pattern_index) =
+                self.anchor_table[anchor_match.pattern().as_usize()];
+
+            if !applicable[signature_index] {
+                continue;
+            }
+
+            let signature = &self.signatures[signature_index];
+            let pattern = match is_fixed {
+                true => &signature.fixed[pattern_index],
+                false => &signature.vulnerable[pattern_index],
+            };
+
+            // Anchors preceded by wildcard bytes may be found too close to the start of the file
+            if anchor_match.start() < pattern.anchor_start {
+                continue;
+            }
+
+            let pattern_offset = anchor_match.start() - pattern.anchor_start;
+
+            if pattern.matches(file_data, pattern_offset) {
+                debug!(
+                    "Patch signature {} pattern matched {file_path} at offset {pattern_offset:#X}",
+                    signature.name
+                );
+
+                match is_fixed {
+                    true => fixed_offsets[signature_index].push(pattern_offset),
+                    false => vulnerable_offsets[signature_index].push(pattern_offset),
+                }
+            }
+        }
+
+        for (signature_index, signature) in self.signatures.iter().enumerate() {
+            let mut vulnerable = vulnerable_offsets[signature_index].clone();
+            let mut fixed = fixed_offsets[signature_index].clone();
+
+            let status = match (vulnerable.is_empty(), fixed.is_empty()) {
+                (true, true) => continue,
+                (false, true) => PatchStatus::Vulnerable,
+                (true, false) => PatchStatus::Fixed,
+                (false, false) => PatchStatus::Inconclusive,
+            };
+
+            vulnerable.sort();
+            vulnerable.dedup();
+            fixed.sort();
+            fixed.dedup();
+
+            findings.push(PatchFinding {
+                name: signature.name.clone(),
+                description: signature.description.clone(),
+                status,
+                vulnerable_offsets: vulnerable,
+                fixed_offsets: fixed,
+            });
+        }
+
+        findings
+    }
+}
+
+/// Loads all `.toml` and `.json` patch signature definitions from the specified directory.
+///
+/// Invalid patch signature files are logged and skipped.
+pub fn load_patch_signatures(patch_directory: &str) -> Result<PatchSignatures, PatchError> {
+    let mut patch_signatures = PatchSignatures::default();
+
+    let mut patch_files: Vec<_> = match fs::read_dir(patch_directory) {
+        Err(e) => {
+            return Err(PatchError {
+                message: format!(
+                    "Failed to read patch signature directory '{patch_directory}': {e}"
+                ),
+            });
+        }
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect(),
+    };
+
+    // Load patch signatures in a predictable order
+    patch_files.sort();
+
+    for patch_file in patch_files {
+        match load_patch_file(&patch_file) {
+            Err(e) => {
+                error!(
+                    "Failed to load patch signature {}: {}",
+                    patch_file.display(),
+                    e.message
+                );
+            }
+            Ok(None) => {
+                debug!("Skipping non-patch signature file {}", patch_file.display());
+            }
+            Ok(Some(signature)) => {
+                debug!(
+                    "Loaded patch signature {} from {}",
+                    signature.name,
+                    patch_file.display()
+                );
+                patch_signatures.signatures.push(signature);
+            }
+        }
+    }
+
+    let mut anchors: Vec<Vec<u8>> = vec![];
+
+    for (signature_index, signature) in patch_signatures.signatures.iter().enumerate() {
+        for (is_fixed, patterns) in [(false, &signature.vulnerable), (true, &signature.fixed)] {
+            for (pattern_index, pattern) in patterns.iter().enumerate() {
+                anchors.push(pattern.anchor());
+                patch_signatures
+                    .anchor_table
+                    .push((signature_index, is_fixed, pattern_index));
+            }
+        }
+    }
+
+    if !anchors.is_empty() {
+        match AhoCorasick::new(anchors) {
+            Err(e) => {
+                return Err(PatchError {
+                    message: format!("Failed to build patch signature search: {e}"),
+                });
+            }
+            Ok(anchor_search) => patch_signatures.anchor_search = Some(anchor_search),
+        }
+    }
+
+    Ok(patch_signatures)
+}
+
+/// Loads and validates a single patch signature file; returns None if the file is not a patch signature file
+fn load_patch_file(patch_file: &Path) -> Result<Option<PatchSignature>, PatchError> {
+    let extension = patch_file
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+
+    if !patch_file.is_file() || !["toml", "json"].contains(&extension) {
+        return Ok(None);
+    }
+
+    let patch_data = fs::read_to_string(patch_file).map_err(|e| PatchError {
+        message: e.to_string(),
+    })?;
+
+    let definition: PatchDefinition = match extension {
+        "toml" => toml::from_str(&patch_data).map_err(|e| PatchError {
+            message: e.to_string(),
+        })?,
+        _ => serde_json::from_str(&patch_data).map_err(|e| PatchError {
+            message: e.to_string(),
+        })?,
+    };
+
+    if definition.name.trim().is_empty() {
+        return Err(PatchError {
+            message: "patch signature name is empty".to_string(),
+        });
+    }
+
+    if definition.vulnerable.is_empty() && definition.fixed.is_empty() {
+        return Err(PatchError {
+            message: "no vulnerable or fixed patterns defined".to_string(),
+        });
+    }
+
+    let mut signature = PatchSignature {
+        name: definition.name,
+        description: definition.description,
+        file_names: definition.file_names,
+        vulnerable: vec![],
+        fixed: vec![],
+    };
+
+    for pattern in &definition.vulnerable {
+        signature.vulnerable.push(parse_byte_pattern(pattern)?);
+    }
+
+    for pattern in &definition.fixed {
+        signature.fixed.push(parse_byte_pattern(pattern)?);
+    }
+
+    Ok(Some(signature))
+}
+
+/// Parses a hex encoded byte pattern, where '??' matches any byte; whitespace is ignored
+fn parse_byte_pattern(pattern: &str) -> Result<BytePattern, PatchError> {
+    // Patterns must contain at least this many consecutive literal bytes
+    const MIN_ANCHOR_SIZE: usize = 2;
+    const WILDCARD: &str = "??";
+
+    let invalid_pattern = || PatchError {
+        message: format!("invalid byte pattern '{pattern}'"),
+    };
+
+    let hex_string: String = pattern.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if hex_string.is_empty() || !hex_string.len().is_multiple_of(2) || !hex_string.is_ascii() {
+        return Err(invalid_pattern());
+    }
+
+    let mut bytes: Vec<Option<u8>> = vec![];
+
+    for i in (0..hex_string.len()).step_by(2) {
+        let hex_byte = &hex_string[i..i + 2];
+
+        if hex_byte == WILDCARD {
+            bytes.push(None);
+        } else {
+            bytes.push(Some(
+                u8::from_str_radix(hex_byte, 16).map_err(|_| invalid_pattern())?,
+            ));
+        }
+    }
+
+    // Find the longest run of literal bytes
+    let mut anchor_start: usize = 0;
+    let mut anchor_size: usize = 0;
+    let mut run_start: usize = 0;
+
+    for (i, byte) in bytes.iter().enumerate() {
+        if byte.is_none() {
+            run_start = i + 1;
+        } else if (i + 1 - run_start) > anchor_size {
+            anchor_start = run_start;
+            anchor_size = i + 1 - run_start;
+        }
+    }
+
+    if anchor_size < MIN_ANCHOR_SIZE {
+        return Err(PatchError {
+            message: format!(
+                "byte pattern '{pattern}' must contain at least {MIN_ANCHOR_SIZE} consecutive non-wildcard bytes"
+            ),
+        });
+    }
+
+    Ok(BytePattern {
+        bytes,
+        anchor_start,
+        anchor_size,
+    })
+}
@@ -0,0 +1,399 @@
+//! Release gating policies, selected with `--policy`.
+//!
+//! A policy is a TOML file of declarative rules that are evaluated against the analysis results of the target file
+//! and all of its extracted files. Each rule specifies exactly one condition; if any rule with a severity at or above
+//! the policy's `fail_on` severity is violated, the policy check fails and binwalk exits with a failure status:
+//!
+//! ```toml
+//! fail_on = "high"
+//!
+//! [[rules]]
+//! name = "private-key"
+//! description = "Private keys must not be shipped in firmware"
+//! severity = "critical"
+//! signature = "pem_private_key"
+//!
+//! [[rules]]
+//! name = "telnetd"
+//! severity = "high"
+//! file = "telnetd"
+//!
+//! [[rules]]
+//! name = "outdated-openssl"
+//! severity = "medium"
+//! component = "OpenSSL"
+//! older_than = "1.1.1"
+//!
+//! [[rules]]
+//! name = "known-vulnerabilities"
+//! severity = "critical"
+//! vulnerable = "*"
+//! ```
+//!
+//! Component rules identify software versions in the data of each analyzed file, so they are most useful when
+//! recursively extracting the target file; vulnerability rules require patch signatures to be loaded with `--patches`.
+use crate::binwalk::AnalysisResults;
+use crate::changelog::harvest_components;
+use crate::common::read_file;
+use crate::extractors::common::get_extracted_files;
+use crate::patches::PatchStatus;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Patch signature name that matches any patch signature in vulnerability rules
+const ANY_PATCH: &str = "*";
+
+/// Returned when a policy can not be loaded
+#[derive(Debug, Default, Clone)]
+pub struct PolicyError {
+    pub message: String,
+}
+
+/// Severity of a policy rule
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    #[default]
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// A single policy rule; exactly one of `signature`, `file`, `component` or `vulnerable` must be specified
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyRule {
+    pub name: String,
+    /// Human readable description of the rule
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Violated if a signature with this name is found
+    pub signature: Option<String>,
+    /// Only signatures whose description contains this text violate a signature rule
+    pub description_contains: Option<String>,
+    /// Violated if a file with this name is analyzed or extracted
+    pub file: Option<String>,
+    /// Violated if this software component is found; see also `older_than`
+    pub component: Option<String>,
+    /// Only versions of the component older than this version violate a component rule
+    pub older_than: Option<String>,
+    /// Violated if this patch signature, or any patch signature if "*", finds vulnerable code
+    pub vulnerable: Option<String>,
+}
+
+impl PolicyRule {
+    /// Validates that the rule specifies exactly one condition, and only the options that apply to it
+    fn validate(&self) -> Result<(), PolicyError> {
+        let invalid_rule = |reason: &str| PolicyError {
+            message: format!("Invalid policy rule '{}': {}", self.name, reason),
+        };
+
+        let condition_count = [
+            self.signature.is_some(),
+            self.file.is_some(),
+            self.component.is_some(),
+            self.vulnerable.is_some(),
+        ]
+        .iter()
+        .filter(|condition| **condition)
+        .count();
+
+        if condition_count != 1 {
+            return Err(invalid_rule(
+                "exactly one of signature, file, component or vulnerable must be specified",
+            ));
+        }
+
+        if self.description_contains.is_some() && self.signature.is_none() {
+            return Err(invalid_rule(
+                "description_contains can only be specified with signature",
+            ));
+        }
+
+        if self.older_than.is_some() && self.component.is_none() {
+            return Err(invalid_rule(
+                "older_than can only be specified with component",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// A set of release gating rules
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    /// Violations of rules with this severity or higher fail the policy check
+    #[serde(default)]
+    pub fail_on: Severity,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+impl Policy {
+    /// Returns true if any rule requires the software components in each file to be identified
+    fn has_component_rules(&self) -> bool {
+        self.rules.iter().any(|rule| rule.component.is_some())
+    }
+}
+
+/// A policy rule that was violated
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PolicyViolation {
+    pub rule: String,
+    pub description: String,
+    pub severity: Severity,
+    /// Path to the file that violated the rule
+    pub file_path: String,
+    /// What was found that violated the rule, e.g., the description of a matching signature
+    pub evidence: String,
+}
+
+/// Results of evaluating a policy against all analyzed files
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PolicyResults {
+    /// Path to the policy file
+    pub policy: String,
+    pub fail_on: Severity,
+    pub rule_count: usize,
+    pub violations: Vec<PolicyViolation>,
+}
+
+impl PolicyResults {
+    /// Returns true if no rule at or above the fail_on severity was violated
+    pub fn passed(&self) -> bool {
+        self.violations
+            .iter()
+            .all(|violation| violation.severity < self.fail_on)
+    }
+}
+
+/// Evaluates a policy against analysis results as they become available
+#[derive(Debug, Default, Clone)]
+pub struct PolicyEvaluation {
+    policy: Policy,
+    /// Files that file rules have already been evaluated against
+    checked_files: HashSet<String>,
+    pub results: PolicyResults,
+}
+
+impl PolicyEvaluation {
+    /// Evaluates the policy against a file's analysis results, returning any new violations.
+    /// The file's data is only needed if the policy has component rules.
+    fn evaluate(
+        &mut self,
+        results: &AnalysisResults,
+        file_data: Option<&[u8]>,
+    ) -> Vec<PolicyViolation> {
+        let mut violations: Vec<PolicyViolation> = vec![];
+
+        // Extracted files are checked against file rules even if they will not be analyzed themselves
+        let mut file_paths: Vec<String> = vec![results.file_path.clone()];
+
+        for extraction_result in results.extractions.values() {
+            file_paths.extend(get_extracted_files(&extraction_result.output_directory));
+        }
+
+        file_paths.retain(|file_path| self.checked_files.insert(file_path.clone()));
+
+        let mut components: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+        if let Some(file_data) = file_data {
+            harvest_components(file_data, &mut components);
+        }
+
+        for rule in &self.policy.rules {
+            let mut violation = |file_path: &str, evidence: String| {
+                violations.push(PolicyViolation {
+                    rule: rule.name.clone(),
+                    description: rule.description.clone(),
+                    severity: rule.severity,
+                    file_path: file_path.to_string(),
+                    evidence,
+                })
+            };
+
+            if let Some(signature_name) = &rule.signature {
+                for signature in &results.file_map {
+                    if signature.name == *signature_name
+                        && rule
+                            .description_contains
+                            .as_ref()
+                            .is_none_or(|text| signature.description.contains(text))
+                    {
+                        violation(
+                            &results.file_path,
+                            format!("{:#X}: {}", signature.offset, signature.description),
+                        );
+                    }
+                }
+            }
+
+            if let Some(file_name) = &rule.file {
+                for file_path in &file_paths {
+                    if Path::new(file_path)
+                        .file_name()
+                        .is_some_and(|name| name.to_string_lossy() == *file_name)
+                    {
+                        violation(file_path, format!("file {file_name} is present"));
+                    }
+                }
+            }
+
+            if let Some(component_name) = &rule.component {
+                if let Some(versions) = components.get(component_name) {
+                    for version in versions {
+                        let violates = match &rule.older_than {
+                            None => true,
+                            Some(minimum_version) => {
+                                compare_versions(version, minimum_version) == Ordering::Less
+                            }
+                        };
+
+                        if violates {
+                            violation(
+                                &results.file_path,
+                                format!("{component_name} version {version}"),
+                            );
+                        }
+                    }
+                }
+            }
+
+            if let Some(patch_name) = &rule.vulnerable {
+                for finding in &results.findings {
+                    if finding.status == PatchStatus::Vulnerable
+                        && (patch_name == ANY_PATCH || finding.name == *patch_name)
+                    {
+                        violation(
+                            &results.file_path,
+                            format!("{}: {}", finding.name, finding.description),
+                        );
+                    }
+                }
+            }
+        }
+
+        self.results.violations.extend(violations.clone());
+
+        violations
+    }
+
+    /// Evaluates the policy against a file's analysis results, reading the file's data from disk if the policy has
+    /// component rules. Files whose data can not be read, such as stdin, are only checked against the other rules.
+    pub fn evaluate_file(
+        &mut self,
+        results: &AnalysisResults,
+        readable: bool,
+    ) -> Vec<PolicyViolation> {
+        let mut file_data: Option<Vec<u8>> = None;
+
+        if self.policy.has_component_rules() && readable {
+            match read_file(&results.file_path) {
+                Err(e) => warn!(
+                    "Failed to read {} for policy component rules: {}",
+                    results.file_path, e
+                ),
+                Ok(data) => file_data = Some(data),
+            }
+        }
+
+        self.evaluate(results, file_data.as_deref())
+    }
+}
+
+/// Loads and validates a policy file
+pub fn load_policy(policy_file: &str) -> Result<PolicyEvaluation, PolicyError> {
+    let policy_data = fs::read_to_string(policy_file).map_err(|e| PolicyError {
+        message: format!("Failed to read policy file {policy_file}: {e}"),
+    })?;
+
+    let policy: Policy = toml::from_str(&policy_data).map_err(|e| PolicyError {
+        message: format!("Failed to parse policy file {policy_file}: {e}"),
+    })?;
+
+    for rule in &policy.rules {
+        rule.validate()?;
+    }
+
+    Ok(PolicyEvaluation {
+        results: PolicyResults {
+            policy: policy_file.to_string(),
+            fail_on: policy.fail_on,
+            rule_count: policy.rules.len(),
+            ..Default::default()
+        },
+        policy,
+        ..Default::default()
+    })
+}
+
+/// Compares two version strings, e.g., "1.0.2k" and "1.1.1"; numeric parts are compared numerically, and all other
+/// parts are compared lexically. Versions that are equal except for trailing parts, e.g., "1.1" and "1.1.0", are
+/// considered equal.
+fn compare_versions(version1: &str, version2: &str) -> Ordering {
+    let parts1 = version_parts(version1);
+    let parts2 = version_parts(version2);
+
+    for index in 0..parts1.len().max(parts2.len()) {
+        let ordering = match (parts1.get(index), parts2.get(index)) {
+            (Some(part1), Some(part2)) => match (part1.parse::<u64>(), part2.parse::<u64>()) {
+                (Ok(number1), Ok(number2)) => number1.cmp(&number2),
+                _ => part1.cmp(part2),
+            },
+            _ => Ordering::Equal,
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Splits a version string into runs of digits and runs of letters, e.g., "1.0.2k" is split into "1", "0", "2", "k"
+fn version_parts(version: &str) -> Vec<String> {
+    let mut parts: Vec<String> = vec![];
+    let mut last_is_digit: Option<bool> = None;
+
+    for c in version.chars() {
+        if !c.is_ascii_alphanumeric() {
+            last_is_digit = None;
+            continue;
+        }
+
+        match last_is_digit == Some(c.is_ascii_digit()) {
+            true => parts.last_mut().expect("Version part missing").push(c),
+            false => parts.push(c.to_string()),
+        }
+
+        last_is_digit = Some(c.is_ascii_digit());
+    }
+
+    parts
+}
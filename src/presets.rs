@@ -0,0 +1,286 @@
+//! Named presets of command line options, selected with `--preset`.
+//!
+//! The built-in presets are `quick`, `deep`, `forensics` and `portable`. Additional presets, or presets that replace the
+//! built-in presets, can be defined in the config file, which is read from `$XDG_CONFIG_HOME/binwalk/config.toml`
+//! (or `~/.config/binwalk/config.toml`) unless another config file is specified with `--config`. For example:
+//!
+//! ```toml
+//! [presets.router]
+//! description = "Recursively extract router firmware, ignoring images"
+//! extract = true
+//! matryoshka = true
+//! exclude = ["jpeg", "png", "gif", "bmp"]
+//! parser_timeout = 2000
+//! ```
+//!
+//! Options specified on the command line take precedence over the options of the selected preset, and options that
+//! the preset enables can be disabled on the command line with their `--no-*` options, e.g. `--preset deep --no-matryoshka`.
+use crate::cliparser::CliArgs;
+use crate::filetype::FILE_TYPES;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Returned when a preset can not be loaded
+#[derive(Debug, Default, Clone)]
+pub struct PresetError {
+    pub message: String,
+}
+
+/// A named combination of command line options; see cliparser::CliArgs for a description of each option
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    /// Human readable description of the preset
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub extract: bool,
+    #[serde(default)]
+    pub carve: bool,
+    #[serde(default)]
+    pub matryoshka: bool,
+    #[serde(default)]
+    pub search_all: bool,
+    #[serde(default)]
+    pub verbose: bool,
+    #[serde(default)]
+    pub toolchain: bool,
+    #[serde(default)]
+    pub padding: bool,
+    #[serde(default)]
+    pub manifest: bool,
+    #[serde(default)]
+    pub internal_only: bool,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub only_types: Option<Vec<String>>,
+    pub patches: Option<String>,
+    pub firmware_db: Option<String>,
+    pub threads: Option<usize>,
+    /// Megabytes
+    pub max_memory: Option<u64>,
+    /// Milliseconds
+    pub parser_timeout: Option<u64>,
+}
+
+impl Preset {
+    /// Applies the preset's options to the command line arguments, unless they were specified, or disabled, on the command line
+    pub fn apply(&self, args: &mut CliArgs) {
+        args.extract |= self.extract && !args.no_extract;
+        args.carve |= self.carve && !args.no_carve;
+        args.matryoshka |= self.matryoshka && !args.no_matryoshka;
+        args.search_all |= self.search_all && !args.no_search_all;
+        args.verbose |= self.verbose && !args.no_verbose;
+        args.toolchain |= self.toolchain && !args.no_toolchain;
+        args.padding |= self.padding && !args.no_padding;
+        args.manifest |= self.manifest && !args.no_manifest;
+        args.internal_only |= self.internal_only && !args.no_internal_only;
+
+        // Included and excluded signatures are mutually exclusive, so specifying either on the command line overrides both
+        if args.include.is_none() && args.exclude.is_none() {
+            args.include = self.include.clone();
+            args.exclude = self.exclude.clone();
+        }
+
+        args.only_types = args.only_types.take().or(self.only_types.clone());
+        args.patches = args.patches.take().or(self.patches.clone());
+        args.firmware_db = args.firmware_db.take().or(self.firmware_db.clone());
+        args.threads = args.threads.or(self.threads);
+        args.max_memory = args.max_memory.or(self.max_memory);
+        args.parser_timeout = args.parser_timeout.or(self.parser_timeout);
+    }
+
+    /// Validates the options that clap would otherwise validate on the command line
+    fn validate(&self, name: &str) -> Result<(), PresetError> {
+        let invalid_preset = |reason: String| PresetError {
+            message: format!("Invalid preset '{name}': {reason}"),
+        };
+
+        if self.include.is_some() && self.exclude.is_some() {
+            return Err(invalid_preset(
+                "include and exclude can not both be specified".to_string(),
+            ));
+        }
+
+        if let Some(only_types) = &self.only_types {
+            for file_type in only_types {
+                if !FILE_TYPES.contains(&file_type.as_str()) {
+                    return Err(invalid_preset(format!(
+                        "unknown file type '{file_type}' in only_types"
+                    )));
+                }
+            }
+        }
+
+        if self.threads == Some(0) || self.max_memory == Some(0) || self.parser_timeout == Some(0) {
+            return Err(invalid_preset(
+                "threads, max_memory and parser_timeout must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Config file contents
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    presets: BTreeMap<String, Preset>,
+}
+
+/// Returns the built-in presets
+fn builtin_presets() -> BTreeMap<String, Preset> {
+    // Signatures that identify common, low value data, such as media files, hashes and crypto constants
+    const QUICK_EXCLUDED_SIGNATURES: &[&str] = &[
+        "aes_acceleration_table",
+        "aes_forward_table",
+        "aes_rcon",
+        "aes_reverse_table",
+        "aes_sbox",
+        "bmp",
+        "copyright",
+        "crc32",
+        "flatbuffers",
+        "gif",
+        "jpeg",
+        "md5",
+        "minified_js",
+        "mp3",
+        "mp4",
+        "pkcs_der_hash",
+        "png",
+        "protobuf",
+        "riff",
+        "sha256",
+        "svg",
+        "ttf",
+        "woff",
+    ];
+    const QUICK_PARSER_TIMEOUT_MS: u64 = 1000;
+
+    BTreeMap::from([
+        (
+            "quick".to_string(),
+            Preset {
+                description: "Fast scan for firmware containers, file systems and executables, without extraction".to_string(),
+                exclude: Some(
+                    QUICK_EXCLUDED_SIGNATURES
+                        .iter()
+                        .map(|signature| signature.to_string())
+                        .collect(),
+                ),
+                parser_timeout: Some(QUICK_PARSER_TIMEOUT_MS),
+                ..Default::default()
+            },
+        ),
+        (
+            "deep".to_string(),
+            Preset {
+                description: "Recursively extract everything, searching for all signatures at all offsets".to_string(),
+                extract: true,
+                matryoshka: true,
+                search_all: true,
+                ..Default::default()
+            },
+        ),
+        (
+            "forensics".to_string(),
+            Preset {
+                description: "Recursively extract and carve all data, recording the hashes of extracted files and fingerprinting toolchains".to_string(),
+                extract: true,
+                carve: true,
+                matryoshka: true,
+                search_all: true,
+                verbose: true,
+                toolchain: true,
+                manifest: true,
+                ..Default::default()
+            },
+        ),
+        (
+            "portable".to_string(),
+            Preset {
+                description: "Recursively extract everything that the built-in extractors can, without running external utilities".to_string(),
+                extract: true,
+                matryoshka: true,
+                internal_only: true,
+                ..Default::default()
+            },
+        ),
+    ])
+}
+
+/// Returns the path of the default config file, if the user's config directory can be determined
+fn default_config_path() -> Option<PathBuf> {
+    const CONFIG_DIRECTORY: &str = "binwalk";
+    const CONFIG_FILE: &str = "config.toml";
+
+    let env_path = |name: &str| {
+        std::env::var_os(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+
+    let config_home = env_path("XDG_CONFIG_HOME")
+        .or_else(|| env_path("APPDATA"))
+        .or_else(|| env_path("HOME").map(|home| home.join(".config")))?;
+
+    Some(config_home.join(CONFIG_DIRECTORY).join(CONFIG_FILE))
+}
+
+/// Loads the presets defined in the config file; the default config file is optional, but a specified config file is not
+fn load_config_presets(config_file: Option<&str>) -> Result<BTreeMap<String, Preset>, PresetError> {
+    let config_path = match config_file {
+        Some(config_file) => PathBuf::from(config_file),
+        None => match default_config_path() {
+            Some(config_path) if config_path.is_file() => config_path,
+            _ => return Ok(BTreeMap::new()),
+        },
+    };
+
+    let config_data = fs::read_to_string(&config_path).map_err(|e| PresetError {
+        message: format!(
+            "Failed to read config file {}: {}",
+            config_path.display(),
+            e
+        ),
+    })?;
+
+    let config: ConfigFile = toml::from_str(&config_data).map_err(|e| PresetError {
+        message: format!(
+            "Failed to parse config file {}: {}",
+            config_path.display(),
+            e
+        ),
+    })?;
+
+    Ok(config.presets)
+}
+
+/// Returns the named preset; presets defined in the config file take precedence over the built-in presets
+pub fn load_preset(name: &str, config_file: Option<&str>) -> Result<Preset, PresetError> {
+    let mut presets = builtin_presets();
+    presets.extend(load_config_presets(config_file)?);
+
+    match presets.remove(name) {
+        Some(preset) => {
+            preset.validate(name)?;
+            Ok(preset)
+        }
+        None => Err(PresetError {
+            message: format!(
+                "Unknown preset '{}'; available presets are: {}",
+                name,
+                presets
+                    .iter()
+                    .map(|(name, preset)| format!("{} ({})", name, preset.description))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }),
+    }
+}
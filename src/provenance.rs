@@ -0,0 +1,206 @@
+//! Provenance of extracted files.
+//!
+//! The provenance tree records where every extracted file came from: the file it was extracted from, the offset of the
+//! signature in that file, and the extractor that produced it, along with the size and hashes of each file. Files
+//! that were themselves extracted from are the parents of the files extracted from them.
+
+use crate::binwalk::AnalysisResults;
+use crate::common::relative_path;
+use crate::hashes::{Hashes, hash_file};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Name of the provenance file written to the extraction directory
+pub const PROVENANCE_FILE_NAME: &str = "provenance.json";
+
+/// Returned when the provenance tree can't be written
+#[derive(Debug, Default, Clone)]
+pub struct ProvenanceError {
+    pub message: String,
+}
+
+/// A file in the provenance tree
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ProvenanceFile {
+    /// Path to the file, relative to the extraction directory, using '/' as the path separator
+    pub path: String,
+    pub size: u64,
+    #[serde(flatten)]
+    pub hashes: Hashes,
+    /// Extractions performed on this file, in offset order
+    pub extractions: Vec<ProvenanceExtraction>,
+}
+
+/// An extraction performed on a file in the provenance tree
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ProvenanceExtraction {
+    /// Offset of the extracted signature in the parent file
+    pub offset: usize,
+    /// Size of the extracted signature's data, 0 if unknown
+    pub size: usize,
+    /// The SignatureResult.name of the extracted signature
+    pub signature: String,
+    /// The SignatureResult.description of the extracted signature
+    pub description: String,
+    /// Name of the extractor that produced the files
+    pub extractor: String,
+    pub success: bool,
+    /// The files produced by the extractor
+    pub files: Vec<ProvenanceFile>,
+}
+
+/// An extraction, as recorded from analysis results
+#[derive(Debug, Default, Clone)]
+struct RecordedExtraction {
+    extraction: ProvenanceExtraction,
+    output_directory: String,
+}
+
+/// Collects the extractions performed on each analyzed file, and builds the provenance tree from them
+#[derive(Debug, Default, Clone)]
+pub struct ProvenanceTree {
+    /// Extractions performed on each analyzed file, keyed by file path
+    extractions: HashMap<String, Vec<RecordedExtraction>>,
+}
+
+impl ProvenanceTree {
+    pub fn new() -> Self {
+        ProvenanceTree::default()
+    }
+
+    /// Records the extractions performed on an analyzed file
+    pub fn add(&mut self, results: &AnalysisResults) {
+        let mut recorded: Vec<RecordedExtraction> = vec![];
+
+        for signature in &results.file_map {
+            if let Some(extraction) = results.extractions.get(&signature.id) {
+                recorded.push(RecordedExtraction {
+                    extraction: ProvenanceExtraction {
+                        offset: signature.offset,
+                        size: match signature.size {
+                            0 => extraction.size.unwrap_or_default(),
+                            size => size,
+                        },
+                        signature: signature.name.clone(),
+                        description: signature.description.clone(),
+                        extractor: extraction.extractor.clone(),
+                        success: extraction.success,
+                        files: vec![],
+                    },
+                    output_directory: extraction.output_directory.clone(),
+                });
+            }
+        }
+
+        recorded.sort_by_key(|recorded| recorded.extraction.offset);
+
+        self.extractions.insert(results.file_path.clone(), recorded);
+    }
+
+    /// Builds the provenance tree of all files extracted from the target file, and writes it to the extraction
+    /// directory. Returns the path to the provenance file.
+    pub fn write(
+        &self,
+        target_file: &str,
+        extraction_directory: &str,
+    ) -> Result<String, ProvenanceError> {
+        let provenance_path = Path::new(extraction_directory)
+            .join(PROVENANCE_FILE_NAME)
+            .display()
+            .to_string();
+
+        let root = self.build(Path::new(target_file), extraction_directory);
+
+        let provenance_json = match serde_json::to_string_pretty(&root) {
+            Err(e) => {
+                return Err(ProvenanceError {
+                    message: format!("Failed to serialize provenance tree: {e}"),
+                });
+            }
+            Ok(json) => json,
+        };
+
+        if let Err(e) = fs::write(&provenance_path, provenance_json) {
+            return Err(ProvenanceError {
+                message: format!("Failed to write provenance tree {provenance_path}: {e}"),
+            });
+        }
+
+        info!("Wrote provenance tree to {provenance_path}");
+
+        Ok(provenance_path)
+    }
+
+    /// Builds the provenance tree of a file
+    fn build(&self, file_path: &Path, extraction_directory: &str) -> ProvenanceFile {
+        let mut file = ProvenanceFile {
+            path: relative_path(file_path, extraction_directory),
+            ..Default::default()
+        };
+
+        match fs::metadata(file_path)
+            .and_then(|metadata| Ok((metadata.len(), hash_file(file_path)?)))
+        {
+            Err(e) => warn!("Failed to hash {}: {}", file_path.display(), e),
+            Ok((size, hashes)) => {
+                file.size = size;
+                file.hashes = hashes;
+            }
+        }
+
+        if let Some(recorded_extractions) = self.extractions.get(&file_path.display().to_string()) {
+            for recorded in recorded_extractions {
+                let mut extraction = recorded.extraction.clone();
+
+                for extracted_file in self.extracted_files(&recorded.output_directory) {
+                    extraction
+                        .files
+                        .push(self.build(Path::new(&extracted_file), extraction_directory));
+                }
+
+                file.extractions.push(extraction);
+            }
+        }
+
+        file
+    }
+
+    /// Returns the paths of all regular files in an extraction's output directory, sorted by name.
+    /// Files in the output directories of nested extractions belong to the files they were extracted from, and are
+    /// excluded.
+    fn extracted_files(&self, output_directory: &str) -> Vec<String> {
+        let mut files: Vec<String> = vec![];
+
+        if output_directory.is_empty() {
+            return files;
+        }
+
+        let nested_directories: HashSet<&str> = self
+            .extractions
+            .values()
+            .flatten()
+            .map(|recorded| recorded.output_directory.as_str())
+            .filter(|directory| *directory != output_directory)
+            .collect();
+
+        for entry in WalkDir::new(output_directory)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| {
+                !nested_directories.contains(entry.path().display().to_string().as_str())
+            })
+            .flatten()
+        {
+            // Symlinks are not followed; they may point outside of the extraction directory
+            if entry.file_type().is_file() {
+                files.push(entry.path().display().to_string());
+            }
+        }
+
+        files
+    }
+}
@@ -0,0 +1,192 @@
+//! Writes a report of the analysis results, selected with `--report`, from a built-in or user supplied template.
+//!
+//! Templates are Handlebars templates, rendered with the structured ScanReport data, so that reports can be branded and
+//! restructured without modifying binwalk. Values inserted with `{{path.to.value}}` are escaped for the report format,
+//! and `{{hex path.to.value}}` inserts a number in hexadecimal.
+//!
+//! Reports ending in `.html` or `.htm` are HTML, reports ending in `.md` are Markdown, and all other reports are text;
+//! each format has its own built-in template.
+
+use crate::binwalk::AnalysisResults;
+use crate::common::{escape_non_printable, html_text, markdown_text};
+use crate::credentials::CredentialSummary;
+use crate::network::NetworkSummary;
+use crate::policy::PolicyResults;
+use crate::toolchain::ToolchainSummary;
+use clap::ValueEnum;
+use handlebars::{Handlebars, handlebars_helper};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Built-in template for HTML reports
+const HTML_TEMPLATE: &str = include_str!("report/report.html.hbs");
+
+/// Built-in template for Markdown reports
+const MARKDOWN_TEMPLATE: &str = include_str!("report/report.md.hbs");
+
+/// Built-in template for text reports
+const TEXT_TEMPLATE: &str = include_str!("report/report.txt.hbs");
+
+/// Output formats for changelog and analysis reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// Human readable text
+    Text,
+    /// Markdown release notes
+    Markdown,
+    /// Standalone HTML page
+    Html,
+}
+
+/// Returned when a report can not be rendered or written
+#[derive(Debug, Default, Clone)]
+pub struct ReportError {
+    pub message: String,
+}
+
+impl ReportError {
+    pub fn new(message: &str) -> Self {
+        ReportError {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Report data that templates are rendered with
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ScanReport {
+    /// Version of binwalk that generated the report
+    pub binwalk_version: String,
+    /// Date and time the report was generated, in RFC 3339 format
+    pub generated: String,
+    /// Path to the analyzed file
+    pub target_file: String,
+    /// Number of analyzed files, including extracted files
+    pub file_count: usize,
+    /// Analysis run time, in seconds
+    pub run_time: f64,
+    /// Analysis results of each analyzed file
+    pub files: Vec<AnalysisResults>,
+    /// Aggregated toolchain results; only populated if toolchain analysis was requested
+    pub toolchain: Option<ToolchainSummary>,
+    /// Aggregated network indicators; only populated if network indicator analysis was requested
+    pub network: Option<NetworkSummary>,
+    /// Aggregated credentials; only populated if credential analysis was requested
+    pub credentials: Option<CredentialSummary>,
+    /// Policy results; only populated if a policy was evaluated
+    pub policy: Option<PolicyResults>,
+}
+
+impl ScanReport {
+    pub fn new(target_file: &str) -> Self {
+        ScanReport {
+            binwalk_version: env!("CARGO_PKG_VERSION").to_string(),
+            generated: chrono::Local::now().to_rfc3339(),
+            target_file: target_file.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Renders the report with the specified template, or the built-in template for the report format, and writes it to the report file
+pub fn write_report(
+    report: &ScanReport,
+    report_file: &str,
+    template_file: Option<&str>,
+) -> Result<(), ReportError> {
+    let format = report_format(report_file);
+
+    let template = match template_file {
+        None => builtin_template(format).to_string(),
+        Some(template_file) => match fs::read_to_string(template_file) {
+            Err(e) => {
+                return Err(ReportError::new(&format!(
+                    "Failed to read report template {template_file}: {e}"
+                )));
+            }
+            Ok(template) => template,
+        },
+    };
+
+    let rendered = render(&template, report, format)?;
+
+    if let Err(e) = fs::write(report_file, rendered) {
+        return Err(ReportError::new(&format!(
+            "Failed to write report {report_file}: {e}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns the built-in template for the specified report format
+pub fn builtin_template(format: ReportFormat) -> &'static str {
+    match format {
+        ReportFormat::Html => HTML_TEMPLATE,
+        ReportFormat::Markdown => MARKDOWN_TEMPLATE,
+        ReportFormat::Text => TEXT_TEMPLATE,
+    }
+}
+
+/// Determines the report format from the report file extension
+fn report_format(report_file: &str) -> ReportFormat {
+    let extension = Path::new(report_file)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "html" | "htm" => ReportFormat::Html,
+        "md" | "markdown" => ReportFormat::Markdown,
+        _ => ReportFormat::Text,
+    }
+}
+
+/// Renders a template with the report data, escaping values for the specified report format
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::report::{ReportFormat, ScanReport, builtin_template, render};
+///
+/// let report = ScanReport::new("fw<1>*\x1B[2J.bin");
+///
+/// let rendered = |format| render(builtin_template(format), &report, format).unwrap();
+///
+/// assert!(rendered(ReportFormat::Text).contains("fw<1>*\\x1B[2J.bin"));
+/// assert!(rendered(ReportFormat::Markdown).contains("fw\\<1>\\*\\\\x1B\\[2J.bin"));
+/// assert!(rendered(ReportFormat::Html).contains("fw&lt;1&gt;*\\x1B[2J.bin"));
+/// ```
+pub fn render(
+    template: &str,
+    report: &ScanReport,
+    format: ReportFormat,
+) -> Result<String, ReportError> {
+    // Inserts a number in hexadecimal; other values are inserted as-is
+    handlebars_helper!(hex: |value: Value| match value.as_u64() {
+        Some(number) => format!("{number:#X}"),
+        None => match value {
+            Value::String(string) => string,
+            _ => value.to_string(),
+        },
+    });
+
+    let mut handlebars = Handlebars::new();
+
+    handlebars.register_helper("hex", Box::new(hex));
+
+    match format {
+        ReportFormat::Html => handlebars.register_escape_fn(html_text),
+        ReportFormat::Markdown => handlebars.register_escape_fn(markdown_text),
+        ReportFormat::Text => handlebars.register_escape_fn(escape_non_printable),
+    }
+
+    match handlebars.render_template(template, report) {
+        Err(e) => Err(ReportError::new(&format!(
+            "Failed to render report template: {e}"
+        ))),
+        Ok(rendered) => Ok(rendered),
+    }
+}
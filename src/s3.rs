@@ -0,0 +1,276 @@
+//! Reads scan targets from, and uploads results to, S3-compatible object storage.
+//!
+//! Credentials, region and endpoint are loaded from the standard AWS environment variables and config files.
+//! For S3-compatible services, set AWS_ENDPOINT_URL (or AWS_ENDPOINT_URL_S3) to the service's endpoint.
+use aws_sdk_s3::Client;
+use aws_sdk_s3::error::DisplayErrorContext;
+use aws_sdk_s3::primitives::ByteStream;
+use log::{debug, error, info};
+use std::path;
+use tokio::runtime::Runtime;
+use walkdir::WalkDir;
+
+/// URL scheme for objects in S3-compatible storage
+const S3_SCHEME: &str = "s3://";
+
+/// Returned when an object storage operation fails
+#[derive(Debug, Default, Clone)]
+pub struct S3Error {
+    pub message: String,
+}
+
+impl S3Error {
+    fn new(message: &str) -> S3Error {
+        S3Error {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// The location of an object, or a prefix for a group of objects, in a bucket
+#[derive(Debug, Default, Clone)]
+pub struct S3Location {
+    pub bucket: String,
+    pub key: String,
+}
+
+impl S3Location {
+    /// Returns the file name component of the object key
+    pub fn file_name(&self) -> String {
+        match self.key.rsplit('/').next() {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => self.bucket.clone(),
+        }
+    }
+
+    /// Returns a new location for the specified relative path under this location's key prefix
+    fn join(&self, relative_path: &str) -> S3Location {
+        let prefix = self.key.trim_end_matches('/');
+
+        S3Location {
+            bucket: self.bucket.clone(),
+            key: match prefix.is_empty() {
+                true => relative_path.to_string(),
+                false => format!("{prefix}/{relative_path}"),
+            },
+        }
+    }
+}
+
+/// Returns true if the specified path is an s3:// URL
+pub fn is_s3_url(path: &str) -> bool {
+    path.starts_with(S3_SCHEME)
+}
+
+/// Parses an s3://bucket/key URL; the key may be empty when the URL refers to a prefix
+pub fn parse_s3_url(url: &str) -> Result<S3Location, S3Error> {
+    if let Some(bucket_and_key) = url.strip_prefix(S3_SCHEME) {
+        let (bucket, key) = bucket_and_key
+            .split_once('/')
+            .unwrap_or((bucket_and_key, ""));
+
+        if !bucket.is_empty() {
+            return Ok(S3Location {
+                bucket: bucket.to_string(),
+                key: key.to_string(),
+            });
+        }
+    }
+
+    Err(S3Error::new(&format!(
+        "Invalid object storage URL '{url}', expected s3://bucket/key"
+    )))
+}
+
+/// Blocking client for S3-compatible object storage
+pub struct S3Client {
+    runtime: Runtime,
+    client: Client,
+}
+
+impl S3Client {
+    /// Creates a new client, using the credentials and endpoint configured in the environment
+    pub fn new() -> Result<S3Client, S3Error> {
+        // A custom endpoint almost always means an S3-compatible service, most of which require path style requests
+        const ENDPOINT_ENV_VARS: [&str; 2] = ["AWS_ENDPOINT_URL", "AWS_ENDPOINT_URL_S3"];
+
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Err(e) => {
+                return Err(S3Error::new(&format!(
+                    "Failed to initialize object storage runtime: {e}"
+                )));
+            }
+            Ok(runtime) => runtime,
+        };
+
+        let sdk_config = runtime.block_on(aws_config::load_from_env());
+
+        let force_path_style = ENDPOINT_ENV_VARS
+            .iter()
+            .any(|env_var| std::env::var(env_var).is_ok());
+
+        let s3_config = aws_sdk_s3::config::Builder::from(&sdk_config)
+            .force_path_style(force_path_style)
+            .build();
+
+        Ok(S3Client {
+            runtime,
+            client: Client::from_conf(s3_config),
+        })
+    }
+
+    /// Downloads an object into memory
+    pub fn get_object(&self, location: &S3Location) -> Result<Vec<u8>, S3Error> {
+        let object_data = self.runtime.block_on(async {
+            let response = self
+                .client
+                .get_object()
+                .bucket(&location.bucket)
+                .key(&location.key)
+                .send()
+                .await
+                .map_err(|e| DisplayErrorContext(e).to_string())?;
+
+            response.body.collect().await.map_err(|e| e.to_string())
+        });
+
+        match object_data {
+            Err(e) => Err(S3Error::new(&format!(
+                "Failed to download s3://{}/{}: {}",
+                location.bucket, location.key, e
+            ))),
+            Ok(data) => {
+                let data = data.into_bytes().to_vec();
+                debug!(
+                    "Downloaded {} bytes from s3://{}/{}",
+                    data.len(),
+                    location.bucket,
+                    location.key
+                );
+                Ok(data)
+            }
+        }
+    }
+
+    /// Uploads a local file to the specified object location
+    pub fn put_file(&self, file_path: &str, location: &S3Location) -> Result<(), S3Error> {
+        let upload = self.runtime.block_on(async {
+            let body = ByteStream::from_path(file_path)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            self.client
+                .put_object()
+                .bucket(&location.bucket)
+                .key(&location.key)
+                .body(body)
+                .send()
+                .await
+                .map_err(|e| DisplayErrorContext(e).to_string())
+        });
+
+        match upload {
+            Err(e) => Err(S3Error::new(&format!(
+                "Failed to upload {} to s3://{}/{}: {}",
+                file_path, location.bucket, location.key, e
+            ))),
+            Ok(_) => {
+                debug!(
+                    "Uploaded {} to s3://{}/{}",
+                    file_path, location.bucket, location.key
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Uploads all regular files in a local directory under the specified key prefix, preserving relative paths.
+    /// Symlinks are not uploaded, as object storage has no equivalent. Returns the number of files uploaded.
+    pub fn put_directory(&self, directory: &str, prefix: &S3Location) -> Result<usize, S3Error> {
+        let mut upload_count: usize = 0;
+        let mut failure_count: usize = 0;
+
+        for entry in WalkDir::new(directory).into_iter().flatten() {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            if let Ok(relative_path) = entry.path().strip_prefix(directory) {
+                // Object keys always use '/' as the path separator
+                let relative_key = relative_path
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+
+                match self.put_file(
+                    &entry.path().display().to_string(),
+                    &prefix.join(&relative_key),
+                ) {
+                    Err(e) => {
+                        error!("{}", e.message);
+                        failure_count += 1;
+                    }
+                    Ok(_) => upload_count += 1,
+                }
+            }
+        }
+
+        if failure_count > 0 {
+            return Err(S3Error::new(&format!(
+                "Failed to upload {failure_count} files from {directory}"
+            )));
+        }
+
+        info!(
+            "Uploaded {} files from {} to s3://{}/{}",
+            upload_count, directory, prefix.bucket, prefix.key
+        );
+
+        Ok(upload_count)
+    }
+}
+
+/// Downloads the object at the specified s3:// URL, returning the object's file name and data
+pub fn download(url: &str) -> Result<(String, Vec<u8>), S3Error> {
+    let location = parse_s3_url(url)?;
+    let client = S3Client::new()?;
+
+    Ok((location.file_name(), client.get_object(&location)?))
+}
+
+/// Uploads the extraction directory and JSON log file, if any, under the specified s3:// URL prefix
+pub fn upload_results(
+    url: &str,
+    output_directory: Option<&str>,
+    log_file: Option<&str>,
+) -> Result<usize, S3Error> {
+    let prefix = parse_s3_url(url)?;
+    let client = S3Client::new()?;
+    let mut upload_count: usize = 0;
+
+    if let Some(directory) = output_directory {
+        // Extracted files are uploaded under a prefix named after the extraction directory
+        let directory_name = path::Path::new(directory)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        upload_count += client.put_directory(directory, &prefix.join(&directory_name))?;
+    }
+
+    if let Some(log_file_path) = log_file {
+        let log_file_name = path::Path::new(log_file_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        client.put_file(log_file_path, &prefix.join(&log_file_name))?;
+        upload_count += 1;
+    }
+
+    Ok(upload_count)
+}
@@ -0,0 +1,312 @@
+//! Finds private keys, certificates and password hashes that are shared between multiple firmware images.
+//!
+//! Secrets that are reused across devices, such as a private key baked into every device of a product line, can not be
+//! identified from the results of a single firmware image; all extracted files of all images in a batch are searched
+//! for secrets, and each secret is identified by a hash of its decoded contents.
+
+use crate::cluster::image_directories;
+use crate::common::read_file;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use walkdir::WalkDir;
+
+/// Returned when secrets can not be correlated
+#[derive(Debug, Default, Clone)]
+pub struct SecretsError {
+    pub message: String,
+}
+
+impl SecretsError {
+    pub fn new(message: &str) -> Self {
+        SecretsError {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Types of secrets that are correlated between firmware images
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SecretKind {
+    PrivateKey,
+    Certificate,
+    PasswordHash,
+}
+
+/// Location of a secret in a firmware image
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SecretLocation {
+    pub image: String,
+    /// Path to the file containing the secret, relative to the image's extraction directory
+    pub path: String,
+    /// File offset of the secret
+    pub offset: usize,
+}
+
+/// A secret that was found in more than one firmware image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedSecret {
+    pub kind: SecretKind,
+    /// SHA256 hash of the secret's decoded contents
+    pub fingerprint: String,
+    /// Description of the secret, e.g., "RSA PRIVATE KEY", or "password hash for root"
+    pub description: String,
+    /// Names of the firmware images containing the secret
+    pub images: Vec<String>,
+    pub locations: Vec<SecretLocation>,
+}
+
+/// Secret correlation results
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SecretsResults {
+    /// Names of the analyzed firmware images, in order
+    pub images: Vec<String>,
+    /// Number of unique secrets found across all images
+    pub secret_count: usize,
+    /// Secrets found in more than one image, most widely shared first
+    pub shared: Vec<SharedSecret>,
+}
+
+/// A secret found in a single file
+struct Secret {
+    kind: SecretKind,
+    fingerprint: String,
+    description: String,
+    offset: usize,
+}
+
+/// Finds secrets that are shared between the firmware images in the results directory.
+///
+/// As with binary clustering, each sub-directory of `results_directory` is treated as the extraction results of one
+/// firmware image.
+pub fn correlate(results_directory: &str) -> Result<SecretsResults, SecretsError> {
+    let mut results = SecretsResults {
+        ..Default::default()
+    };
+
+    // All secrets found, keyed by (kind, fingerprint)
+    let mut secrets: BTreeMap<(SecretKind, String), (String, BTreeSet<SecretLocation>)> =
+        BTreeMap::new();
+
+    for (image_name, image_directory) in
+        image_directories(results_directory).map_err(|e| SecretsError::new(&e.message))?
+    {
+        for entry in WalkDir::new(&image_directory)
+            .sort_by_file_name()
+            .into_iter()
+            .flatten()
+        {
+            // Don't follow symlinks, only look at regular files
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let file_path = entry.path().display().to_string();
+
+            let file_data = match read_file(&file_path) {
+                Err(e) => {
+                    warn!("Failed to read {file_path}: {e}");
+                    continue;
+                }
+                Ok(file_data) => file_data,
+            };
+
+            let relative_path = entry
+                .path()
+                .strip_prefix(&image_directory)
+                .map(|path| path.display().to_string())
+                .unwrap_or(file_path.clone());
+
+            let file_name = entry.file_name().to_string_lossy();
+
+            let mut file_secrets = find_pem_secrets(&file_data);
+            file_secrets.extend(find_password_hashes(&file_name, &file_data));
+
+            for secret in file_secrets {
+                debug!(
+                    "Found {:?} {} in {}",
+                    secret.kind, secret.fingerprint, file_path
+                );
+
+                secrets
+                    .entry((secret.kind, secret.fingerprint))
+                    .or_insert_with(|| (secret.description, BTreeSet::new()))
+                    .1
+                    .insert(SecretLocation {
+                        image: image_name.clone(),
+                        path: relative_path.clone(),
+                        offset: secret.offset,
+                    });
+            }
+        }
+
+        results.images.push(image_name);
+    }
+
+    if results.images.is_empty() {
+        return Err(SecretsError::new(&format!(
+            "No firmware image extraction results found in {results_directory}"
+        )));
+    }
+
+    results.secret_count = secrets.len();
+
+    for ((kind, fingerprint), (description, locations)) in secrets {
+        let images: BTreeSet<String> = locations
+            .iter()
+            .map(|location| location.image.clone())
+            .collect();
+
+        // Secrets that appear in multiple files of the same image are not reused across devices
+        if images.len() > 1 {
+            results.shared.push(SharedSecret {
+                kind,
+                fingerprint,
+                description,
+                images: images.into_iter().collect(),
+                locations: locations.into_iter().collect(),
+            });
+        }
+    }
+
+    results.shared.sort_by(|a, b| {
+        b.images
+            .len()
+            .cmp(&a.images.len())
+            .then(a.kind.cmp(&b.kind))
+            .then(a.fingerprint.cmp(&b.fingerprint))
+    });
+
+    Ok(results)
+}
+
+/// Finds PEM encoded private keys and certificates in the file data
+fn find_pem_secrets(file_data: &[u8]) -> Vec<Secret> {
+    const BEGIN_MARKER: &[u8] = b"-----BEGIN ";
+    const MARKER_END: &[u8] = b"-----";
+    // Longest PEM label and body that are expected
+    const MAX_LABEL_LENGTH: usize = 64;
+    const MAX_BODY_LENGTH: usize = 64 * 1024;
+
+    let mut secrets: Vec<Secret> = vec![];
+    let mut offset: usize = 0;
+
+    while let Some(begin_offset) = find(&file_data[offset..], BEGIN_MARKER).map(|i| offset + i) {
+        offset = begin_offset + BEGIN_MARKER.len();
+
+        let label_data = &file_data[offset..];
+        let label_end = match find(
+            &label_data[..label_data.len().min(MAX_LABEL_LENGTH)],
+            MARKER_END,
+        ) {
+            Some(label_end) => label_end,
+            None => continue,
+        };
+
+        let label = String::from_utf8_lossy(&label_data[..label_end]).to_string();
+
+        let kind = if label.ends_with("PRIVATE KEY") {
+            SecretKind::PrivateKey
+        } else if label.ends_with("CERTIFICATE") {
+            SecretKind::Certificate
+        } else {
+            continue;
+        };
+
+        let body_start = offset + label_end + MARKER_END.len();
+        let body_data = &file_data[body_start..file_data.len().min(body_start + MAX_BODY_LENGTH)];
+        let end_marker = format!("-----END {label}-----");
+
+        if let Some(body_end) = find(body_data, end_marker.as_bytes()) {
+            if let Some(contents) = pem_contents(&body_data[..body_end]) {
+                secrets.push(Secret {
+                    kind,
+                    fingerprint: sha256(&contents),
+                    description: label,
+                    offset: begin_offset,
+                });
+            }
+
+            offset = body_start + body_end + end_marker.len();
+        }
+    }
+
+    secrets
+}
+
+/// Decodes the base64 body of a PEM block; encapsulated headers, such as those of encrypted keys, are ignored
+fn pem_contents(body: &[u8]) -> Option<Vec<u8>> {
+    let base64_data: String = String::from_utf8_lossy(body)
+        .lines()
+        .filter(|line| !line.contains(':'))
+        .flat_map(|line| line.chars().filter(|c| !c.is_whitespace()))
+        .collect();
+
+    STANDARD
+        .decode(base64_data)
+        .ok()
+        .filter(|contents| !contents.is_empty())
+}
+
+/// Finds password hashes in passwd and shadow files
+fn find_password_hashes(file_name: &str, file_data: &[u8]) -> Vec<Secret> {
+    const PASSWORD_FILES: [&str; 5] = ["passwd", "shadow", "passwd-", "shadow-", "master.passwd"];
+    // Length of a traditional DES crypt hash
+    const DES_HASH_LENGTH: usize = 13;
+
+    let mut secrets: Vec<Secret> = vec![];
+
+    if !PASSWORD_FILES.contains(&file_name) {
+        return secrets;
+    }
+
+    let mut offset: usize = 0;
+
+    for line in file_data.split(|b| *b == b'\n') {
+        let line_offset = offset;
+        offset += line.len() + 1;
+
+        let line = String::from_utf8_lossy(line);
+        let fields: Vec<&str> = line.trim_end().split(':').collect();
+
+        if fields.len() < 2 {
+            continue;
+        }
+
+        let (user, password_hash) = (fields[0], fields[1]);
+
+        // Locked and disabled accounts, and passwords stored in the shadow file, have no hash
+        let is_hash = (password_hash.starts_with('$') && password_hash.len() > DES_HASH_LENGTH)
+            || (password_hash.len() == DES_HASH_LENGTH
+                && password_hash
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '/'));
+
+        if is_hash {
+            secrets.push(Secret {
+                kind: SecretKind::PasswordHash,
+                fingerprint: sha256(password_hash.as_bytes()),
+                description: format!("password hash for {user}"),
+                offset: line_offset,
+            });
+        }
+    }
+
+    secrets
+}
+
+/// Returns the offset of the first occurrence of the needle in the data
+fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Returns the SHA256 hash of the data, as a hex string
+fn sha256(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    format!("{:x}", hasher.finalize())
+}
@@ -0,0 +1,580 @@
+//! Serves the results of a completed analysis over a local HTTP server, for browsing carved regions and extracted files
+//! without re-running extraction.
+//!
+//! Analysis results are read from a JSON log file written with `--log`. File data is read on demand, one page at a time,
+//! so that large firmware images can be browsed without reading them into memory.
+
+use crate::binwalk::AnalysisResults;
+use crate::common::{escape_non_printable, html_text};
+use crate::json::JSONType;
+use log::{debug, error, info, warn};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+/// Number of bytes displayed per hexdump page
+const PAGE_SIZE: usize = 4096;
+
+/// Number of bytes displayed per hexdump line
+const LINE_SIZE: usize = 16;
+
+/// Returned when the results can not be loaded or served
+#[derive(Debug, Default, Clone)]
+pub struct ServeError {
+    pub message: String,
+}
+
+/// A region of a file to display or download
+struct FileRegion {
+    path: PathBuf,
+    /// Offset of the region in the file
+    offset: u64,
+    /// Size of the region; if None, the region extends to the end of the file
+    size: Option<u64>,
+}
+
+/// An HTTP response
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: ResponseBody,
+}
+
+enum ResponseBody {
+    Html(String),
+    /// Raw file data, streamed from disk
+    Region(FileRegion),
+}
+
+impl Response {
+    fn html(title: &str, body: &str) -> Response {
+        Response {
+            status: "200 OK",
+            content_type: "text/html; charset=utf-8",
+            body: ResponseBody::Html(html_page(title, body)),
+        }
+    }
+
+    fn not_found() -> Response {
+        Response {
+            status: "404 Not Found",
+            content_type: "text/html; charset=utf-8",
+            body: ResponseBody::Html(html_page("Not found", "<p>Not found</p>")),
+        }
+    }
+}
+
+/// Loads the analysis results from a JSON log file.
+///
+/// Logs that were not closed, for example because the analysis was interrupted, are also accepted.
+pub fn load_results(results_file: &str) -> Result<Vec<AnalysisResults>, ServeError> {
+    let json_data = fs::read_to_string(results_file).map_err(|e| ServeError {
+        message: format!("Failed to read results file '{results_file}': {e}"),
+    })?;
+
+    let entries: Vec<JSONType> = match serde_json::from_str(&json_data) {
+        Ok(entries) => entries,
+        Err(e) => {
+            // Interrupted analyses leave the JSON list unterminated
+            let terminated_json = format!("{}\n]", json_data.trim_end());
+
+            serde_json::from_str(&terminated_json).map_err(|_| ServeError {
+                message: format!("Failed to parse results file '{results_file}': {e}"),
+            })?
+        }
+    };
+
+    let results: Vec<AnalysisResults> = entries
+        .into_iter()
+        .filter_map(|entry| match entry {
+            JSONType::Analysis(analysis_results) => Some(*analysis_results),
+            _ => None,
+        })
+        .collect();
+
+    if results.is_empty() {
+        return Err(ServeError {
+            message: format!("No analysis results found in '{results_file}'"),
+        });
+    }
+
+    Ok(results)
+}
+
+/// Serves the analysis results on the specified address until interrupted
+pub fn serve(results: Vec<AnalysisResults>, listen_address: &str) -> Result<(), ServeError> {
+    let listener = TcpListener::bind(listen_address).map_err(|e| ServeError {
+        message: format!("Failed to listen on {listen_address}: {e}"),
+    })?;
+
+    let results = Arc::new(results);
+
+    match listener.local_addr() {
+        Ok(address) => info!("Serving analysis results at http://{address}/"),
+        Err(_) => info!("Serving analysis results on {listen_address}"),
+    }
+
+    for stream in listener.incoming() {
+        match stream {
+            Err(e) => warn!("Failed to accept connection: {e}"),
+            Ok(stream) => {
+                let results = results.clone();
+
+                thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, &results) {
+                        debug!("Connection closed: {e}");
+                    }
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a single request from the connection and writes the response
+fn handle_connection(mut stream: TcpStream, results: &[AnalysisResults]) -> io::Result<()> {
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    reader.read_line(&mut request_line)?;
+
+    // Discard the request headers
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut request = request_line.split_whitespace();
+
+    let response = match (request.next(), request.next()) {
+        (Some("GET"), Some(target)) => {
+            debug!("GET {target}");
+            route(results, target)
+        }
+        _ => Response {
+            status: "405 Method Not Allowed",
+            content_type: "text/plain",
+            body: ResponseBody::Html("Method not allowed".to_string()),
+        },
+    };
+
+    write_response(&mut stream, response)
+}
+
+/// Writes an HTTP response to the connection
+fn write_response(stream: &mut TcpStream, response: Response) -> io::Result<()> {
+    match response.body {
+        ResponseBody::Html(html) => {
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                response.status,
+                response.content_type,
+                html.len()
+            )?;
+            stream.write_all(html.as_bytes())?;
+        }
+        ResponseBody::Region(region) => {
+            let mut file = fs::File::open(&region.path)?;
+            let file_size = file.metadata()?.len();
+            let start = region.offset.min(file_size);
+            let size = match region.size {
+                Some(size) => size.min(file_size - start),
+                None => file_size - start,
+            };
+
+            file.seek(SeekFrom::Start(start))?;
+
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                response.status, response.content_type, size
+            )?;
+            io::copy(&mut file.take(size), stream)?;
+        }
+    }
+
+    stream.flush()
+}
+
+/// Returns the response for the requested URL
+///
+/// - `/`: list of analyzed files
+/// - `/file/<file>`: signatures and extractions of an analyzed file
+/// - `/region/<file>/<signature id>`: hexdump of a signature's data in the analyzed file
+/// - `/extracted/<file>/<signature id>/<path>`: extracted directory listing, or hexdump of an extracted file
+///
+/// Appending `?raw` to a region or extracted file URL downloads the data; `?offset=<n>` selects the hexdump page.
+fn route(results: &[AnalysisResults], target: &str) -> Response {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(percent_decode)
+        .collect();
+
+    let raw = query.split('&').any(|parameter| parameter == "raw");
+    let page_offset: u64 = query
+        .split('&')
+        .find_map(|parameter| parameter.strip_prefix("offset="))
+        .and_then(|offset| offset.parse().ok())
+        .unwrap_or(0);
+
+    match segments.first().map(String::as_str) {
+        None => index_page(results),
+        Some("file") => match file_results(results, segments.get(1)) {
+            None => Response::not_found(),
+            Some((file_index, analysis)) => file_page(file_index, analysis),
+        },
+        Some("region") => match file_results(results, segments.get(1)) {
+            None => Response::not_found(),
+            Some((file_index, analysis)) => {
+                let signature = segments.get(2).and_then(|signature_id| {
+                    analysis
+                        .file_map
+                        .iter()
+                        .find(|signature| &signature.id == signature_id)
+                });
+
+                match signature {
+                    None => Response::not_found(),
+                    Some(signature) => {
+                        let region = FileRegion {
+                            path: PathBuf::from(&analysis.file_path),
+                            offset: signature.offset as u64,
+                            size: match signature.size {
+                                0 => None,
+                                size => Some(size as u64),
+                            },
+                        };
+
+                        let title = format!(
+                            "{} @ {:#X}",
+                            display_path(&analysis.file_path),
+                            signature.offset
+                        );
+                        let base_url = format!("/region/{}/{}", file_index, signature.id);
+
+                        region_response(region, raw, &title, &base_url, page_offset)
+                    }
+                }
+            }
+        },
+        Some("extracted") => match file_results(results, segments.get(1)) {
+            None => Response::not_found(),
+            Some((file_index, analysis)) => {
+                let extraction = segments
+                    .get(2)
+                    .and_then(|signature_id| analysis.extractions.get(signature_id));
+
+                match extraction {
+                    None => Response::not_found(),
+                    Some(extraction) => extracted_response(
+                        &extraction.output_directory,
+                        &segments[3..],
+                        &format!("/extracted/{}/{}", file_index, segments[2]),
+                        raw,
+                        page_offset,
+                    ),
+                }
+            }
+        },
+        Some(_) => Response::not_found(),
+    }
+}
+
+/// Returns the analysis results for the file index in the URL
+fn file_results<'a>(
+    results: &'a [AnalysisResults],
+    file_index: Option<&String>,
+) -> Option<(usize, &'a AnalysisResults)> {
+    let file_index: usize = file_index?.parse().ok()?;
+    results
+        .get(file_index)
+        .map(|analysis| (file_index, analysis))
+}
+
+fn index_page(results: &[AnalysisResults]) -> Response {
+    let mut body =
+        String::from("<table><tr><th>File</th><th>Signatures</th><th>Extractions</th></tr>");
+
+    for (file_index, analysis) in results.iter().enumerate() {
+        body.push_str(&format!(
+            "<tr><td><a href=\"/file/{}\">{}</a></td><td>{}</td><td>{}</td></tr>",
+            file_index,
+            html_text(&display_path(&analysis.file_path)),
+            analysis.file_map.len(),
+            analysis.extractions.len()
+        ));
+    }
+
+    body.push_str("</table>");
+
+    Response::html("Analysis results", &body)
+}
+
+fn file_page(file_index: usize, analysis: &AnalysisResults) -> Response {
+    let mut body = String::from(
+        "<p><a href=\"/\">All files</a></p><table><tr><th>Offset</th><th>Size</th><th>Description</th><th>Extraction</th></tr>",
+    );
+
+    for signature in &analysis.file_map {
+        let extraction = match analysis.extractions.get(&signature.id) {
+            None => "".to_string(),
+            Some(extraction) if extraction.success => format!(
+                "<a href=\"/extracted/{}/{}/\">{}</a>",
+                file_index,
+                signature.id,
+                html_text(&extraction.extractor)
+            ),
+            Some(extraction) if extraction.unchanged => "unchanged".to_string(),
+            Some(extraction) => format!("{} failed", html_text(&extraction.extractor)),
+        };
+
+        body.push_str(&format!(
+            "<tr><td><a href=\"/region/{}/{}\">{:#X}</a></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            file_index,
+            signature.id,
+            signature.offset,
+            signature.size,
+            html_text(&signature.description),
+            extraction
+        ));
+    }
+
+    body.push_str("</table>");
+
+    Response::html(&display_path(&analysis.file_path), &body)
+}
+
+/// Returns a directory listing or file hexdump for a path in an extraction output directory
+fn extracted_response(
+    output_directory: &str,
+    path_segments: &[String],
+    base_url: &str,
+    raw: bool,
+    page_offset: u64,
+) -> Response {
+    let root = match fs::canonicalize(output_directory) {
+        Err(_) => return Response::not_found(),
+        Ok(root) => root,
+    };
+
+    let requested_path = path_segments
+        .iter()
+        .fold(root.clone(), |path, segment| path.join(segment));
+
+    // Never serve files outside of the extraction directory, even via symlinks
+    let path = match fs::canonicalize(&requested_path) {
+        Ok(path) if path.starts_with(&root) => path,
+        _ => return Response::not_found(),
+    };
+
+    let relative_url: String = path_segments
+        .iter()
+        .map(|segment| format!("/{}", percent_encode(segment)))
+        .collect();
+    let url = format!("{base_url}{relative_url}");
+    let title = format!("/{}", path_segments.join("/"));
+
+    if path.is_dir() {
+        let mut entries: Vec<(String, bool)> = match fs::read_dir(&path) {
+            Err(e) => {
+                error!("Failed to read directory {}: {e}", path.display());
+                return Response::not_found();
+            }
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| {
+                    (
+                        entry.file_name().to_string_lossy().to_string(),
+                        entry.path().is_dir(),
+                    )
+                })
+                .collect(),
+        };
+
+        entries.sort();
+
+        let mut body = String::from("<ul>");
+
+        if !path_segments.is_empty() {
+            body.push_str(&format!("<li><a href=\"{url}/..\">..</a></li>"));
+        }
+
+        for (name, is_dir) in entries {
+            body.push_str(&format!(
+                "<li><a href=\"{}/{}\">{}{}</a></li>",
+                url,
+                percent_encode(&name),
+                html_text(&name),
+                if is_dir { "/" } else { "" }
+            ));
+        }
+
+        body.push_str("</ul>");
+
+        return Response::html(&title, &body);
+    }
+
+    let region = FileRegion {
+        path,
+        offset: 0,
+        size: None,
+    };
+
+    region_response(region, raw, &title, &url, page_offset)
+}
+
+/// Returns either the raw data of a file region, or a hexdump of one page of it
+fn region_response(
+    region: FileRegion,
+    raw: bool,
+    title: &str,
+    base_url: &str,
+    page_offset: u64,
+) -> Response {
+    if raw {
+        return Response {
+            status: "200 OK",
+            content_type: "application/octet-stream",
+            body: ResponseBody::Region(region),
+        };
+    }
+
+    let page = match read_page(&region, page_offset) {
+        Err(e) => {
+            error!("Failed to read {}: {e}", region.path.display());
+            return Response::not_found();
+        }
+        Ok(page) => page,
+    };
+
+    let mut body = format!("<p><a href=\"{base_url}?raw\">Download</a>");
+
+    if page_offset > 0 {
+        body.push_str(&format!(
+            " | <a href=\"{}?offset={}\">Previous</a>",
+            base_url,
+            page_offset.saturating_sub(PAGE_SIZE as u64)
+        ));
+    }
+
+    if page.len() == PAGE_SIZE {
+        body.push_str(&format!(
+            " | <a href=\"{}?offset={}\">Next</a>",
+            base_url,
+            page_offset + PAGE_SIZE as u64
+        ));
+    }
+
+    body.push_str("</p><pre>");
+    body.push_str(&hexdump(&page, page_offset));
+    body.push_str("</pre>");
+
+    Response::html(title, &body)
+}
+
+/// Reads one hexdump page of a file region; `page_offset` is relative to the start of the region
+fn read_page(region: &FileRegion, page_offset: u64) -> io::Result<Vec<u8>> {
+    let mut page: Vec<u8> = vec![];
+    let mut page_size = PAGE_SIZE as u64;
+
+    if let Some(region_size) = region.size {
+        page_size = page_size.min(region_size.saturating_sub(page_offset));
+    }
+
+    let mut file = fs::File::open(&region.path)?;
+    file.seek(SeekFrom::Start(region.offset + page_offset))?;
+    file.take(page_size).read_to_end(&mut page)?;
+
+    Ok(page)
+}
+
+/// Formats data as an HTML escaped hexdump; offsets are displayed relative to the start of the region
+fn hexdump(data: &[u8], start_offset: u64) -> String {
+    let mut lines: Vec<String> = vec![];
+
+    for (line_index, line) in data.chunks(LINE_SIZE).enumerate() {
+        let hex: Vec<String> = line.iter().map(|b| format!("{b:02X}")).collect();
+        let ascii: String = line
+            .iter()
+            .map(|b| match b.is_ascii_graphic() || *b == b' ' {
+                true => *b as char,
+                false => '.',
+            })
+            .collect();
+
+        lines.push(html_text(&format!(
+            "{:08X}  {:<width$}  |{}|",
+            start_offset + (line_index * LINE_SIZE) as u64,
+            hex.join(" "),
+            ascii,
+            width = LINE_SIZE * 3 - 1
+        )));
+    }
+
+    lines.join("\n")
+}
+
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title>\
+         <style>body {{ font-family: sans-serif; }} td, th {{ padding: 2px 8px; text-align: left; }}</style></head>\n\
+         <body>\n<h2>{0}</h2>\n{1}\n</body>\n</html>\n",
+        html_text(title),
+        body
+    )
+}
+
+/// Returns the file path as displayed to users
+fn display_path(file_path: &str) -> String {
+    escape_non_printable(
+        &Path::new(file_path)
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or(file_path.to_string()),
+    )
+}
+
+/// Percent-encodes a URL path segment
+fn percent_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(
+            |b| match b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+                true => (b as char).to_string(),
+                false => format!("%{b:02X}"),
+            },
+        )
+        .collect()
+}
+
+/// Decodes a percent-encoded URL path segment
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded: Vec<u8> = vec![];
+    let mut i: usize = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(value) = segment
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                decoded.push(value);
+                i += 3;
+                continue;
+            }
+        }
+
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
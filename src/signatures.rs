@@ -106,33 +106,39 @@
 //! }
 //! ```
 pub mod aes;
+pub mod allwinner;
 pub mod android_bootimg;
 pub mod androidsparse;
 pub mod apfs;
 pub mod arcadyan;
 pub mod arj;
 pub mod autel;
+pub mod bcm_imagetag;
 pub mod binhdr;
 pub mod bmp;
 pub mod btrfs;
+pub mod bundle;
 pub mod bzip2;
 pub mod cab;
 pub mod cfe;
 pub mod chk;
 pub mod common;
-pub mod compressd;
+pub mod config;
 pub mod copyright;
 pub mod cpio;
 pub mod cramfs;
 pub mod csman;
 pub mod dahua_zip;
 pub mod deb;
+pub mod dex;
 pub mod dkbs;
 pub mod dlink_tlv;
 pub mod dlke;
 pub mod dlob;
 pub mod dmg;
 pub mod dms;
+pub mod dmverity;
+pub mod dni;
 pub mod dpapi;
 pub mod dtb;
 pub mod dxbc;
@@ -141,13 +147,18 @@ pub mod efigpt;
 pub mod elf;
 pub mod encfw;
 pub mod encrpted_img;
+pub mod exfat;
 pub mod ext;
 pub mod fat;
+pub mod flatbuffers;
+pub mod font;
 pub mod gif;
 pub mod gpg;
 pub mod gzip;
 pub mod hashes;
+pub mod ihex;
 pub mod iso9660;
+pub mod java;
 pub mod jboot;
 pub mod jffs2;
 pub mod jpeg;
@@ -158,11 +169,17 @@ pub mod lz4;
 pub mod lzfse;
 pub mod lzma;
 pub mod lzop;
+pub mod macho;
 pub mod matter_ota;
 pub mod mbr;
+pub mod mediatek;
 pub mod mh01;
+pub mod mp3;
+pub mod mp4;
 pub mod ntfs;
+pub mod nvram;
 pub mod openssl;
+pub mod openwrt;
 pub mod packimg;
 pub mod pcap;
 pub mod pchrom;
@@ -170,30 +187,41 @@ pub mod pdf;
 pub mod pe;
 pub mod pem;
 pub mod pjl;
-pub mod pkcs_der;
+pub mod pkcs;
+pub mod plugin;
 pub mod png;
+pub mod protobuf;
 pub mod qcow;
 pub mod qnx;
+pub mod qualcomm;
 pub mod rar;
 pub mod riff;
+pub mod rockchip;
 pub mod romfs;
 pub mod rsa;
 pub mod rtk;
+pub mod script;
 pub mod seama;
 pub mod sevenzip;
 pub mod shrs;
+pub mod sqlite;
 pub mod squashfs;
 pub mod srec;
 pub mod svg;
 pub mod tarball;
+pub mod threadx;
 pub mod tplink;
 pub mod trx;
 pub mod ubi;
 pub mod uboot;
+pub mod udf;
 pub mod uefi;
 pub mod uimage;
 pub mod vxworks;
+pub mod wasm;
+pub mod webasset;
 pub mod wince;
+pub mod x509;
 pub mod xz;
 pub mod yaffs;
 pub mod zip;
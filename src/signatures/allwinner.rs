@@ -0,0 +1,52 @@
+use crate::common::escape_non_printable;
+use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::structures::allwinner::{EGON_MAGIC_OFFSET, parse_egon_header};
+
+/// Human readable description
+pub const DESCRIPTION: &str = "Allwinner eGON boot image";
+
+/// eGON magic bytes for first and second stage boot loaders
+pub fn egon_magic() -> Vec<Vec<u8>> {
+    vec![b"eGON.BT0".to_vec(), b"eGON.BT1".to_vec()]
+}
+
+/// Validates Allwinner eGON boot images
+pub fn egon_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // The image checksum is validated, so confidence is high
+    let mut result = SignatureResult {
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    if offset >= EGON_MAGIC_OFFSET {
+        result.offset = offset - EGON_MAGIC_OFFSET;
+
+        if let Ok(egon_header) = parse_egon_header(&file_data[result.offset..]) {
+            let boot_loader = match (egon_header.boot_stage.as_str(), egon_header.spl_version) {
+                ("BT0", Some(spl_version)) => {
+                    format!("U-Boot SPL, SPL header version: {spl_version}")
+                }
+                ("BT0", None) => "boot0".to_string(),
+                _ => "boot1".to_string(),
+            };
+
+            result.size = egon_header.size;
+            result.description = format!("{}, {}", result.description, boot_loader);
+
+            if let Some(device_tree) = egon_header.device_tree {
+                result.description = format!(
+                    "{}, device tree: {}",
+                    result.description,
+                    escape_non_printable(&device_tree)
+                );
+            }
+
+            result.description =
+                format!("{}, total size: {} bytes", result.description, result.size);
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
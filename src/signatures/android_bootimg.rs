@@ -1,16 +1,26 @@
 use crate::signatures::common::{
     CONFIDENCE_LOW, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
 };
-use crate::structures::android_bootimg::parse_android_bootimg_header;
+use crate::structures::android_bootimg::{
+    parse_android_bootimg_header, parse_android_vendor_bootimg_header,
+};
 
 /// Human readable description
 pub const DESCRIPTION: &str = "Android boot image";
 
+/// Human readable description
+pub const VENDOR_DESCRIPTION: &str = "Android vendor boot image";
+
 /// Android boot images always start with these bytes
 pub fn android_bootimg_magic() -> Vec<Vec<u8>> {
     vec![b"ANDROID!".to_vec()]
 }
 
+/// Android vendor boot images always start with these bytes
+pub fn android_vendor_bootimg_magic() -> Vec<Vec<u8>> {
+    vec![b"VNDRBOOT".to_vec()]
+}
+
 /// Validates the android boot image header
 pub fn android_bootimg_parser(
     file_data: &[u8],
@@ -29,16 +39,82 @@ pub fn android_bootimg_parser(
             result.confidence = CONFIDENCE_MEDIUM;
         }
 
+        result.size = bootimg_header.total_size;
+
+        // Load addresses are stored in the vendor boot image for header versions 3 and later
+        if bootimg_header.header_version < 3 {
+            result.description = format!(
+                "{}, header version {}, page size: {}, kernel size: {} bytes, kernel load address: {:#X}, ramdisk size: {} bytes, ramdisk load address: {:#X}, total size: {} bytes",
+                result.description,
+                bootimg_header.header_version,
+                bootimg_header.page_size,
+                bootimg_header.kernel_size,
+                bootimg_header.kernel_load_address,
+                bootimg_header.ramdisk_size,
+                bootimg_header.ramdisk_load_address,
+                result.size,
+            );
+        } else {
+            result.description = format!(
+                "{}, header version {}, kernel size: {} bytes, ramdisk size: {} bytes, total size: {} bytes",
+                result.description,
+                bootimg_header.header_version,
+                bootimg_header.kernel_size,
+                bootimg_header.ramdisk_size,
+                result.size,
+            );
+        }
+
+        clamp_truncated_size(file_data, &mut result);
+
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates the android vendor boot image header
+pub fn android_vendor_bootimg_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: VENDOR_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_LOW,
+        ..Default::default()
+    };
+
+    if let Ok(bootimg_header) = parse_android_vendor_bootimg_header(&file_data[offset..]) {
+        if offset == 0 {
+            result.confidence = CONFIDENCE_MEDIUM;
+        }
+
+        result.size = bootimg_header.total_size;
         result.description = format!(
-            "{}, kernel size: {} bytes, kernel load address: {:#X}, ramdisk size: {} bytes, ramdisk load address: {:#X}",
+            "{}, header version {}, page size: {}, kernel load address: {:#X}, vendor ramdisk size: {} bytes, ramdisk load address: {:#X}, total size: {} bytes",
             result.description,
-            bootimg_header.kernel_size,
+            bootimg_header.header_version,
+            bootimg_header.page_size,
             bootimg_header.kernel_load_address,
-            bootimg_header.ramdisk_size,
+            bootimg_header.vendor_ramdisk_size,
             bootimg_header.ramdisk_load_address,
+            result.size,
         );
+
+        clamp_truncated_size(file_data, &mut result);
+
         return Ok(result);
     }
 
     Err(SignatureError)
 }
+
+/// Truncated boot images, such as partial flash dumps, are reported up to the end of the available data
+fn clamp_truncated_size(file_data: &[u8], result: &mut SignatureResult) {
+    if result.size > file_data.len() - result.offset {
+        result.size = file_data.len() - result.offset;
+        result.description = format!("{}, truncated to {} bytes", result.description, result.size);
+    }
+}
@@ -33,14 +33,15 @@ pub fn android_sparse_parser(
                 // Update reported size and description
                 result.size = total_size;
                 result.description = format!(
-                    "{}, version {}.{}, header size: {}, block size: {}, chunk count: {}, total size: {} bytes",
+                    "{}, version {}.{}, header size: {}, block size: {}, chunk count: {}, total size: {} bytes, unsparsed size: {} bytes",
                     result.description,
                     header.major_version,
                     header.minor_version,
                     header.header_size,
                     header.block_size,
                     header.chunk_count,
-                    total_size
+                    total_size,
+                    header.block_count.saturating_mul(header.block_size)
                 );
                 return Ok(result);
             }
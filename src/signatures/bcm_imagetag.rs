@@ -0,0 +1,71 @@
+use crate::common::escape_non_printable;
+use crate::extractors::bcm_imagetag::extract_bcm_imagetag_regions;
+use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::structures::bcm_imagetag::parse_bcm_imagetag;
+
+/// Human readable description
+pub const DESCRIPTION: &str = "Broadcom image tag";
+
+/// Image tags start with the tag version, followed by the vendor string
+pub fn bcm_imagetag_magic() -> Vec<Vec<u8>> {
+    vec![b"Broadcom Corporatio".to_vec()]
+}
+
+/// Parse and validate Broadcom image tags
+pub fn bcm_imagetag_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Offset of the vendor string in the tag
+    const MAGIC_OFFSET: usize = 4;
+
+    // The tag has a CRC, so confidence is high
+    let mut result = SignatureResult {
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    if offset >= MAGIC_OFFSET {
+        result.offset = offset - MAGIC_OFFSET;
+
+        // Do a dry run to validate the tag and make sure the tagged data is present
+        let dry_run = extract_bcm_imagetag_regions(file_data, result.offset, None);
+
+        if dry_run.success {
+            if let Some(total_size) = dry_run.size {
+                if let Ok(image_tag) = parse_bcm_imagetag(&file_data[result.offset..]) {
+                    let endianness = match image_tag.big_endian {
+                        true => "big endian",
+                        false => "little endian",
+                    };
+
+                    result.size = total_size;
+                    result.description = format!(
+                        "{}, version: {}, chip ID: BCM{}, board ID: {}, {}",
+                        result.description,
+                        escape_non_printable(&image_tag.tag_version),
+                        image_tag.chip_id,
+                        escape_non_printable(&image_tag.board_id),
+                        endianness
+                    );
+
+                    for (name, region_offset, region_size) in image_tag.regions() {
+                        result.description = format!(
+                            "{}, {} offset: {:#X}, {} size: {} bytes",
+                            result.description, name, region_offset, name, region_size
+                        );
+                    }
+
+                    result.description = format!(
+                        "{}, kernel address: {:#X}, total size: {} bytes",
+                        result.description, image_tag.kernel_address, result.size
+                    );
+                    return Ok(result);
+                }
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
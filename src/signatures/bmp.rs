@@ -1,5 +1,6 @@
 use crate::extractors::bmp::extract_bmp_image;
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
+use crate::structures::bmp::parse_dib_header;
 
 /// Human readable description
 pub const DESCRIPTION: &str = "BMP image (Bitmap)";
@@ -29,9 +30,20 @@ pub fn bmp_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
     if dry_run.success {
         // Retrieve total file size and report it to the user
         if let Some(total_size) = dry_run.size {
-            result.description = format!("BMP image, total size: {total_size}");
-            result.size = total_size;
-            return Ok(result);
+            // The DIB header immediately follows the 14-byte BMP file header
+            if let Ok(dib_header) = parse_dib_header(&file_data[offset + 14..]) {
+                // No sense in carving a BMP out of a file that is just the BMP itself
+                if offset == 0 && total_size == file_data.len() {
+                    result.extraction_declined = true;
+                }
+
+                result.description = format!(
+                    "BMP image, {}x{} pixels, {} bits per pixel, total size: {} bytes",
+                    dib_header.width, dib_header.height, dib_header.bits_per_pixel, total_size
+                );
+                result.size = total_size;
+                return Ok(result);
+            }
         }
     }
 
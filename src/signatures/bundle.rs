@@ -0,0 +1,144 @@
+//! Versioned signature bundles, loaded at startup.
+//!
+//! A signature bundle is a JSON file that ships data-driven signature definitions separately from the analysis engine.
+//! A default bundle is embedded in the binary; an updated bundle can be loaded at runtime with `--signatures`, so that
+//! support for new formats can be delivered without recompiling:
+//!
+//! ```json
+//! {
+//!     "format": 1,
+//!     "version": "2025.06.1",
+//!     "signatures": [
+//!         {
+//!             "name": "acme_firmware",
+//!             "description": "ACME firmware image",
+//!             "magic": ["41434d4546570100"],
+//!             "size_field": {"offset": 8, "type": "u32", "endianness": "big", "adjust": 16}
+//!         }
+//!     ],
+//!     "disabled": ["dlob"]
+//! }
+//! ```
+//!
+//! Signature definitions use the same schema as plugin signatures (see `signatures::plugin`); WebAssembly extractor
+//! paths are relative to the bundle file. Built-in signatures listed in `disabled` are excluded from analysis.
+use crate::signatures::common::Signature;
+use crate::signatures::plugin::{PluginError, PluginSignature, register_plugin, validate_plugin};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Latest bundle format supported by this build
+pub const BUNDLE_FORMAT: usize = 1;
+
+/// The default signature bundle, embedded at build time
+const EMBEDDED_BUNDLE: &str = include_str!("bundle.json");
+
+/// The embedded bundle, parsed and registered with the plugin signature parser on first use
+static LOADED_EMBEDDED_BUNDLE: OnceLock<Result<SignatureBundle, PluginError>> = OnceLock::new();
+
+/// A signature bundle, as read from a bundle file
+#[derive(Debug, Clone, Deserialize)]
+struct BundleFile {
+    /// Bundle format version; bundles with a newer format than BUNDLE_FORMAT are rejected
+    format: usize,
+    version: String,
+    #[serde(default)]
+    signatures: Vec<PluginSignature>,
+    #[serde(default)]
+    disabled: Vec<String>,
+}
+
+/// A loaded signature bundle
+#[derive(Debug, Default, Clone)]
+pub struct SignatureBundle {
+    /// Bundle version, as reported by the bundle; this versions the signature set, independently of the binwalk version
+    pub version: String,
+    /// True if this is the bundle embedded in the binary
+    pub embedded: bool,
+    /// Signature definitions, suitable for passing to `Binwalk::configure`
+    pub signatures: Vec<Signature>,
+    /// Names of built-in signatures to exclude from analysis
+    pub disabled: Vec<String>,
+}
+
+/// Loads the signature bundle embedded in the binary. The bundle is only parsed once, no matter how many times it is
+/// loaded.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::signatures::bundle::load_embedded_bundle;
+///
+/// let bundle = load_embedded_bundle().unwrap();
+///
+/// assert!(bundle.embedded);
+/// assert!(!bundle.version.is_empty());
+/// assert_ne!(bundle.version, env!("CARGO_PKG_VERSION"));
+/// assert!(!bundle.signatures.is_empty());
+///
+/// let compressd = bundle.signatures.iter().find(|signature| signature.name == "compressd").unwrap();
+/// assert!(compressd.extractor.as_ref().unwrap().reports_progress);
+/// ```
+pub fn load_embedded_bundle() -> Result<SignatureBundle, PluginError> {
+    LOADED_EMBEDDED_BUNDLE
+        .get_or_init(|| {
+            let mut bundle = parse_bundle(EMBEDDED_BUNDLE.as_bytes(), Path::new(""))?;
+            bundle.embedded = true;
+            Ok(bundle)
+        })
+        .clone()
+}
+
+/// Loads a signature bundle from the specified file, in place of the embedded bundle
+pub fn load_bundle(bundle_path: &str) -> Result<SignatureBundle, PluginError> {
+    let bundle_data = fs::read(bundle_path).map_err(|e| PluginError {
+        message: format!("Failed to read signature bundle '{bundle_path}': {e}"),
+    })?;
+
+    let bundle_directory = Path::new(bundle_path).parent().unwrap_or(Path::new(""));
+
+    parse_bundle(&bundle_data, bundle_directory).map_err(|e| PluginError {
+        message: format!("Invalid signature bundle '{bundle_path}': {}", e.message),
+    })
+}
+
+/// Parses and validates a signature bundle, and registers its signatures with the plugin signature parser
+fn parse_bundle(
+    bundle_data: &[u8],
+    bundle_directory: &Path,
+) -> Result<SignatureBundle, PluginError> {
+    let bundle_file: BundleFile = serde_json::from_slice(bundle_data).map_err(|e| PluginError {
+        message: e.to_string(),
+    })?;
+
+    if bundle_file.format == 0 || bundle_file.format > BUNDLE_FORMAT {
+        return Err(PluginError {
+            message: format!(
+                "unsupported bundle format {}, this build supports up to format {}",
+                bundle_file.format, BUNDLE_FORMAT
+            ),
+        });
+    }
+
+    // Validate all definitions before registering any of them, so that a bad bundle is rejected as a whole
+    let mut plugins: Vec<PluginSignature> = vec![];
+
+    for plugin in bundle_file.signatures {
+        let name = plugin.name.clone();
+
+        plugins.push(
+            validate_plugin(plugin, bundle_directory).map_err(|e| PluginError {
+                message: format!("signature '{name}': {}", e.message),
+            })?,
+        );
+    }
+
+    Ok(SignatureBundle {
+        version: bundle_file.version,
+        embedded: false,
+        signatures: plugins.into_iter().map(register_plugin).collect(),
+        disabled: bundle_file.disabled,
+    })
+}
@@ -1,5 +1,9 @@
+use crate::extractors::cab::cab_extractor;
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
-use crate::structures::cab::parse_cab_header;
+use crate::structures::cab::{
+    COMPRESSION_LZX, COMPRESSION_MSZIP, COMPRESSION_NONE, parse_cab_folder, parse_cab_header,
+    parse_cab_layout,
+};
 
 /// Human readable description
 pub const DESCRIPTION: &str = "Microsoft Cabinet archive";
@@ -17,8 +21,7 @@ pub fn cab_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
 
         // Sanity check the reported CAB file size
         if cab_header.total_size <= available_data {
-            // Return success
-            return Ok(SignatureResult {
+            let mut result = SignatureResult {
                 description: format!(
                     "{}, file count: {}, folder count: {}, header size: {}, total size: {} bytes",
                     DESCRIPTION,
@@ -31,9 +34,39 @@ pub fn cab_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
                 size: cab_header.total_size,
                 confidence: CONFIDENCE_MEDIUM,
                 ..Default::default()
-            });
+            };
+
+            // Archives using compression types not supported by the internal extractor are extracted with cabextract
+            if !internally_extractable(&file_data[offset..]) {
+                result.preferred_extractor = Some(cab_extractor());
+            }
+
+            // Return success
+            return Ok(result);
         }
     }
 
     Err(SignatureError)
 }
+
+/// Returns true if all folders in the CAB archive are uncompressed, MSZIP or LZX compressed
+fn internally_extractable(cab_data: &[u8]) -> bool {
+    const FOLDER_ENTRY_SIZE: usize = 8;
+
+    if let Ok(layout) = parse_cab_layout(cab_data) {
+        let folder_entry_size = FOLDER_ENTRY_SIZE + layout.folder_reserve_size;
+
+        return (0..layout.folder_count).all(|i| {
+            match cab_data
+                .get(layout.folders_offset + (i * folder_entry_size)..)
+                .map(parse_cab_folder)
+            {
+                Some(Ok(folder)) => [COMPRESSION_NONE, COMPRESSION_MSZIP, COMPRESSION_LZX]
+                    .contains(&folder.compression),
+                _ => false,
+            }
+        });
+    }
+
+    false
+}
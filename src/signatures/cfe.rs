@@ -11,9 +11,13 @@ pub fn cfe_magic() -> Vec<Vec<u8>> {
 }
 
 /// Validate the CFE signature
-pub fn cfe_parser(_file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+pub fn cfe_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
     // Magic bytes occur this many bytes into the bootloader
     const CFE_MAGIC_OFFSET: usize = 28;
+    // BCM63xx bootloaders store their version here, e.g., "cfe-v" followed by the bytes 1, 0, 38, 6, 4 for "1.0.38-6.4"
+    const CFE_VERSION_OFFSET: usize = 0x570;
+    const CFE_VERSION_MAGIC: &[u8] = b"cfe-v";
+    const CFE_VERSION_SIZE: usize = 5;
 
     // Success result; confidence is set to low by default as little additional validation is performed
     let mut result = SignatureResult {
@@ -32,6 +36,18 @@ pub fn cfe_parser(_file_data: &[u8], offset: usize) -> Result<SignatureResult, S
             result.confidence = CONFIDENCE_MEDIUM;
         }
 
+        let version_start = result.offset + CFE_VERSION_OFFSET;
+        let version_end = version_start + CFE_VERSION_MAGIC.len() + CFE_VERSION_SIZE;
+
+        if let Some(version_data) = file_data.get(version_start..version_end) {
+            if let Some(version) = version_data.strip_prefix(CFE_VERSION_MAGIC) {
+                result.description = format!(
+                    "{}, version: {}.{}.{}-{}.{}",
+                    result.description, version[0], version[1], version[2], version[3], version[4]
+                );
+            }
+        }
+
         return Ok(result);
     }
 
@@ -1,3 +1,4 @@
+use crate::extractors::chk::extract_chk_payloads;
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
 use crate::structures::chk::parse_chk_header;
 
@@ -19,25 +20,37 @@ pub fn chk_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
         ..Default::default()
     };
 
-    // Parse the CHK header
-    if let Ok(chk_header) = parse_chk_header(&file_data[offset..]) {
-        // Calculate reported image size and size of available data
-        let available_data: usize = file_data.len() - offset;
-        let image_total_size: usize =
-            chk_header.header_size + chk_header.kernel_size + chk_header.rootfs_size;
-
-        // Total reported image size should be between the header size and the file size
-        if available_data >= image_total_size && image_total_size > chk_header.header_size {
-            // Report the size of the header and a brief description
-            result.size = chk_header.header_size;
-            result.description = format!(
-                "{}, board ID: {}, header size: {} bytes, data size: {} bytes",
-                result.description,
-                chk_header.board_id,
-                chk_header.header_size,
-                chk_header.kernel_size + chk_header.rootfs_size
-            );
-            return Ok(result);
+    // Do a dry run to validate the header and payload sizes
+    let dry_run = extract_chk_payloads(file_data, offset, None);
+
+    if dry_run.success {
+        if let Some(image_total_size) = dry_run.size {
+            if let Ok(chk_header) = parse_chk_header(&file_data[offset..]) {
+                result.size = image_total_size;
+                result.description =
+                    format!("{}, board ID: {}", result.description, chk_header.board_id);
+
+                let region = match chk_header.region_name() {
+                    Some(region_name) => region_name.to_string(),
+                    None => format!("{:#X}", chk_header.region),
+                };
+
+                result.description = format!("{}, region: {}", result.description, region);
+
+                if !chk_header.version.is_empty() {
+                    result.description =
+                        format!("{}, version: {}", result.description, chk_header.version);
+                }
+
+                result.description = format!(
+                    "{}, header size: {} bytes, kernel size: {} bytes, rootfs size: {} bytes",
+                    result.description,
+                    chk_header.header_size,
+                    chk_header.kernel_size,
+                    chk_header.rootfs_size
+                );
+                return Ok(result);
+            }
         }
     }
 
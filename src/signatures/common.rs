@@ -1,4 +1,5 @@
 use crate::extractors;
+use crate::locked::LockedContent;
 use serde::{Deserialize, Serialize};
 
 /// Some pre-defined confidence levels for SignatureResult structures
@@ -58,6 +59,13 @@ pub struct SignatureResult {
     pub always_display: bool,
     /// Set to true to disable extraction for this particular signature result (default: false)
     pub extraction_declined: bool,
+    /// Encrypted content identified by the signature parser, which is reported as locked if the signature's data is not extracted
+    #[serde(default)]
+    pub locked: Vec<LockedContent>,
+    /// Set to true if this is a suspicious, but unconfirmed, match; heuristic results are reported separately
+    /// from the file map, and are never extracted
+    #[serde(default)]
+    pub heuristic: bool,
     /// Signatures may specify a preferred extractor, which overrides the default extractor specified in the Signature.extractor definition
     #[serde(skip_deserializing, skip_serializing)]
     pub preferred_extractor: Option<extractors::common::Extractor>,
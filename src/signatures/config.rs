@@ -0,0 +1,57 @@
+use crate::extractors::config::extract_xor_config;
+use crate::extractors::inflate::inflate_preview;
+use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
+use crate::structures::config::{XML_DECLARATION, parse_config_text, parse_xor_config};
+
+/// Human readable description
+pub const DESCRIPTION: &str = "XOR obfuscated XML configuration";
+
+/// XML declarations, XOR'd with every possible non-zero single byte key
+pub fn xor_config_magic() -> Vec<Vec<u8>> {
+    (1..=u8::MAX)
+        .map(|key| XML_DECLARATION.iter().map(|b| b ^ key).collect())
+        .collect()
+}
+
+/// Validates XOR obfuscated XML configuration files
+pub fn xor_config_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    let dry_run = extract_xor_config(file_data, offset, None);
+
+    if dry_run.success {
+        if let Some(config_size) = dry_run.size {
+            if let Ok(xor_config) = parse_xor_config(&file_data[offset..]) {
+                result.size = config_size;
+                result.description = format!(
+                    "{}, XOR key: {:#04X}, total size: {} bytes",
+                    result.description, xor_config.key, result.size
+                );
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Router configuration backups are commonly just compressed XML or INI files.
+/// Returns a description of the configuration file contained in the deflate stream at the specified offset, if any.
+pub fn deflated_config_description(file_data: &[u8], deflate_offset: usize) -> String {
+    // Only need enough decompressed data to identify the configuration file format
+    const PREVIEW_SIZE: usize = 256;
+
+    match parse_config_text(&inflate_preview(file_data, deflate_offset, PREVIEW_SIZE)) {
+        Err(_) => "".to_string(),
+        Ok(config_format) => format!(", contains {} configuration", config_format.name()),
+    }
+}
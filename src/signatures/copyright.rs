@@ -1,5 +1,6 @@
 use crate::common::get_cstring;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::signatures::threadx::threadx_magic;
 
 /// Human readable description
 pub const DESCRIPTION: &str = "Copyright text";
@@ -32,8 +33,16 @@ pub fn copyright_parser(
     // Get a NULL terminated string, starting at the "copright" text
     let copyright_string = get_cstring(&file_data[offset..]);
 
+    // ThreadX version banners start with a copyright notice; leave those for the ThreadX signature to report
+    let is_threadx_banner = threadx_magic().iter().any(|magic| {
+        copyright_string
+            .as_bytes()
+            .windows(magic.len())
+            .any(|window| window == magic.as_slice())
+    });
+
     // Make sure we got more than just the "copyright" string
-    if copyright_string.len() > MAGIC_SIZE {
+    if copyright_string.len() > MAGIC_SIZE && !is_threadx_banner {
         result.size = copyright_string.len();
         // Truncate copright text to 100 bytes
         result.description = format!("{}: \"{:.100}\"", result.description, copyright_string);
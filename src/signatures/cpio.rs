@@ -5,9 +5,13 @@ use crate::structures::cpio;
 /// Human readable description
 pub const DESCRIPTION: &str = "CPIO ASCII archive";
 
-/// Magic bytes for CPIO archives with and without CRC's
+/// Magic bytes for newc CPIO archives with and without CRC's, and for odc CPIO archives
 pub fn cpio_magic() -> Vec<Vec<u8>> {
-    vec![b"070701".to_vec(), b"070702".to_vec()]
+    vec![
+        cpio::CPIO_NEWC_MAGIC.to_vec(),
+        cpio::CPIO_NEWC_CRC_MAGIC.to_vec(),
+        cpio::CPIO_ODC_MAGIC.to_vec(),
+    ]
 }
 
 /// Parse and validate CPIO archives
@@ -41,8 +45,11 @@ pub fn cpio_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
                         break;
                     }
                     Ok(cpio_header) => {
-                        // Sanity check the magic bytes
-                        if !cpio_magic().contains(&cpio_header.magic) {
+                        // Sanity check the magic bytes; all entries in the archive should be the same format
+                        if !cpio_magic().contains(&cpio_header.magic)
+                            || file_data.get(offset..offset + cpio_header.magic.len())
+                                != Some(cpio_header.magic.as_slice())
+                        {
                             break;
                         }
 
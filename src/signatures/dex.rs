@@ -0,0 +1,171 @@
+use crate::signatures::common::{
+    CONFIDENCE_HIGH, CONFIDENCE_LOW, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
+};
+use crate::structures::dex::{
+    DexHeader, parse_art_version, parse_dex_header, parse_odex_header, parse_vdex_header,
+};
+
+/// Human readable descriptions
+pub const DEX_DESCRIPTION: &str = "Android DEX file";
+pub const ODEX_DESCRIPTION: &str = "Android ODEX file";
+pub const VDEX_DESCRIPTION: &str = "Android VDEX file";
+pub const ART_DESCRIPTION: &str = "Android ART image";
+
+/// DEX magic bytes, for all DEX versions from Android 1.0 (035) through Android 15 (041)
+pub fn dex_magic() -> Vec<Vec<u8>> {
+    vec![
+        b"dex\n035\x00".to_vec(),
+        b"dex\n037\x00".to_vec(),
+        b"dex\n038\x00".to_vec(),
+        b"dex\n039\x00".to_vec(),
+        b"dex\n040\x00".to_vec(),
+        b"dex\n041\x00".to_vec(),
+    ]
+}
+
+/// Dalvik ODEX magic bytes
+pub fn odex_magic() -> Vec<Vec<u8>> {
+    vec![b"dey\n035\x00".to_vec(), b"dey\n036\x00".to_vec()]
+}
+
+/// VDEX magic bytes; all VDEX versions to date start with a zero
+pub fn vdex_magic() -> Vec<Vec<u8>> {
+    vec![b"vdex0".to_vec()]
+}
+
+/// ART image magic bytes
+pub fn art_magic() -> Vec<Vec<u8>> {
+    vec![b"art\n0".to_vec(), b"art\n1".to_vec()]
+}
+
+/// Validates a DEX file
+pub fn dex_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: DEX_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    if let Ok(dex_header) = parse_dex_header(&file_data[offset..]) {
+        // No sense in carving a DEX file if the entire file is just the DEX file itself
+        if offset == 0 && dex_header.size == file_data.len() {
+            result.extraction_declined = true;
+        }
+
+        result.size = dex_header.size;
+        result.description = format!(
+            "{}, version: {}, {}, total size: {} bytes",
+            result.description,
+            dex_header.version,
+            describe_dex(&dex_header),
+            result.size
+        );
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates an ODEX file
+pub fn odex_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: ODEX_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    if let Ok(odex_header) = parse_odex_header(&file_data[offset..]) {
+        // The embedded DEX file was validated by the ODEX parser; parse it again for its metadata
+        if let Ok(dex_header) = parse_dex_header(&file_data[offset + odex_header.dex_offset..]) {
+            if odex_header.size <= file_data.len() - offset {
+                if offset == 0 && odex_header.size == file_data.len() {
+                    result.extraction_declined = true;
+                }
+
+                result.size = odex_header.size;
+                result.description = format!(
+                    "{}, version: {}, DEX version: {}, {}, total size: {} bytes",
+                    result.description,
+                    odex_header.version,
+                    dex_header.version,
+                    describe_dex(&dex_header),
+                    result.size
+                );
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates a VDEX file
+pub fn vdex_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: VDEX_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    if let Ok(vdex_header) = parse_vdex_header(&file_data[offset..]) {
+        result.description = format!("{}, version: {}", result.description, vdex_header.version);
+
+        if let Some(dex_count) = vdex_header.dex_count {
+            result.description = format!("{}, DEX files: {}", result.description, dex_count);
+        }
+
+        // Older VDEX versions have no section table to determine their size from
+        match vdex_header.size {
+            Some(vdex_size) if vdex_size <= file_data.len() - offset => {
+                if offset == 0 && vdex_size == file_data.len() {
+                    result.extraction_declined = true;
+                }
+
+                result.size = vdex_size;
+                result.confidence = CONFIDENCE_HIGH;
+                result.description =
+                    format!("{}, total size: {} bytes", result.description, result.size);
+            }
+            _ => result.extraction_declined = true,
+        }
+
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates an ART image header
+pub fn art_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: ART_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_LOW,
+        ..Default::default()
+    };
+
+    if let Ok(art_version) = parse_art_version(&file_data[offset..]) {
+        result.description = format!("{}, version: {}", result.description, art_version);
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Describes the number of classes, methods, types and strings defined in a DEX file
+fn describe_dex(dex_header: &DexHeader) -> String {
+    format!(
+        "classes: {}, methods: {}, types: {}, strings: {}",
+        dex_header.class_count,
+        dex_header.method_count,
+        dex_header.type_count,
+        dex_header.string_count
+    )
+}
@@ -1,3 +1,4 @@
+use crate::locked::LockedContent;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
 use crate::structures::jboot::parse_jboot_arm_header;
 
@@ -38,6 +39,10 @@ pub fn dlke_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
                     dlke_signature_header.data_size,
                     dlke_crypt_header.data_size
                 );
+                result.locked.push(LockedContent::new(
+                    "DLK encrypted firmware data",
+                    "D-Link firmware decryption key",
+                ));
                 return Ok(result);
             }
         }
@@ -1,3 +1,4 @@
+use crate::common::parser_deadline_exceeded;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
 use crate::structures::dmg::parse_dmg_footer;
 use aho_corasick::AhoCorasick;
@@ -68,6 +69,11 @@ fn find_xml_property_list(file_data: &[u8]) -> Option<usize> {
     let grep = AhoCorasick::new(vec![XML_SIGNATURE]).unwrap();
 
     for xml_match in grep.find_overlapping_iter(file_data) {
+        // Large files may have many XML matches; stop looking once the parser is out of time
+        if parser_deadline_exceeded() {
+            break;
+        }
+
         let xml_start = xml_match.start();
         let xml_end = xml_start + MIN_XML_LENGTH;
 
@@ -0,0 +1,54 @@
+use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
+use crate::structures::dmverity::parse_verity_superblock;
+
+/// Human readable description
+pub const DESCRIPTION: &str = "dm-verity hash tree superblock";
+
+/// dm-verity superblock magic bytes, followed by the superblock version
+pub fn dmverity_magic() -> Vec<Vec<u8>> {
+    vec![b"verity\x00\x00\x01\x00\x00\x00".to_vec()]
+}
+
+/// Validates dm-verity superblocks
+pub fn dmverity_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        offset,
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    if let Ok(superblock) = parse_verity_superblock(&file_data[offset..]) {
+        let hash_type = match superblock.hash_type {
+            0 => "Chrome OS",
+            _ => "normal",
+        };
+
+        let salt: String = superblock.salt.iter().map(|b| format!("{b:02x}")).collect();
+
+        // The superblock is followed by the hash tree, which may have been truncated from partition dumps
+        result.size = std::cmp::min(superblock.total_size, file_data.len() - offset);
+        result.description = format!(
+            "{}, version: {}, hash type: {}, hash algorithm: {}, data block size: {} bytes, hash block size: {} bytes, data size: {} bytes, hash tree size: {} bytes, salt: {}, UUID: {}",
+            result.description,
+            superblock.version,
+            hash_type,
+            superblock.algorithm,
+            superblock.data_block_size,
+            superblock.hash_block_size,
+            superblock
+                .data_blocks
+                .saturating_mul(superblock.data_block_size),
+            superblock.total_size,
+            match salt.is_empty() {
+                true => "none".to_string(),
+                false => salt,
+            },
+            superblock.uuid
+        );
+
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
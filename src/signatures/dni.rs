@@ -0,0 +1,54 @@
+use crate::extractors::dni::extract_dni_payload;
+use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
+use crate::structures::dni::parse_dni_header;
+
+/// Human readable description
+pub const DESCRIPTION: &str = "DNI firmware header";
+
+/// DNI headers start with the device board ID
+pub fn dni_magic() -> Vec<Vec<u8>> {
+    vec![b"device:".to_vec()]
+}
+
+/// Parse and validate DNI headers
+pub fn dni_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    // Do a dry run to validate the header and image checksum
+    let dry_run = extract_dni_payload(file_data, offset, None);
+
+    if dry_run.success {
+        if let Some(image_total_size) = dry_run.size {
+            if let Ok(dni_header) = parse_dni_header(&file_data[offset..]) {
+                result.size = image_total_size;
+
+                let region = match dni_header.region.is_empty() {
+                    true => "worldwide",
+                    false => &dni_header.region,
+                };
+
+                result.description = format!(
+                    "{}, board ID: {}, region: {}, version: {}",
+                    result.description, dni_header.device, region, dni_header.version
+                );
+
+                if !dni_header.hd_id.is_empty() {
+                    result.description =
+                        format!("{}, hardware ID: {}", result.description, dni_header.hd_id);
+                }
+
+                result.description =
+                    format!("{}, total size: {} bytes", result.description, result.size);
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
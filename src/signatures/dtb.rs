@@ -1,5 +1,6 @@
+use crate::extractors::dtb::extract_dtb;
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
-use crate::structures::dtb::parse_dtb_header;
+use crate::structures::dtb::{parse_dtb_header, parse_dtb_tree};
 
 /// Human readable description
 pub const DESCRIPTION: &str = "Device tree blob (DTB)";
@@ -28,6 +29,26 @@ pub fn dtb_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
         // Sanity check the dt_struct and dt_strings offsets
         if file_data.len() >= dt_struct_end && file_data.len() >= dt_strings_end {
             result.size = dtb_header.total_size;
+
+            // FIT images may store image data after the end of the DTB, which is included in the extractor's reported size
+            let dry_run = extract_dtb(file_data, offset, None);
+
+            if let Some(total_size) = dry_run.size {
+                result.size = total_size;
+            }
+
+            if let Some(dtb_data) = file_data.get(offset..offset + dtb_header.total_size) {
+                if let Ok(root_node) = parse_dtb_tree(&dtb_header, dtb_data) {
+                    if let Some(images_node) = root_node.child("images") {
+                        result.description = format!(
+                            "{}, flattened image tree (FIT), image count: {}",
+                            result.description,
+                            images_node.children.len()
+                        );
+                    }
+                }
+            }
+
             result.description = format!(
                 "{}, version: {}, CPU ID: {}, total size: {} bytes",
                 result.description, dtb_header.version, dtb_header.cpu_id, result.size
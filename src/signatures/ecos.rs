@@ -1,3 +1,4 @@
+use crate::common::get_cstring;
 use crate::signatures::common::{CONFIDENCE_LOW, SignatureError, SignatureResult};
 
 /// Human readable description
@@ -48,3 +49,55 @@ pub fn exception_handler_parser(
     result.description = format!("{}, MIPS {} endian", result.description, endianness);
     Ok(result)
 }
+
+/// Human readable description
+pub const REDBOOT_DESCRIPTION: &str = "eCos RedBoot boot loader";
+
+/// RedBoot version string magic bytes
+pub fn redboot_magic() -> Vec<Vec<u8>> {
+    /*
+     * RedBoot version strings, as printed in the RedBoot banner:
+     *
+     *  Non-certified release, version v3_0 - built 16:55:38, Sep 30 2011
+     *  Red Hat certified release, version 1.94 - built 10:38:44, Jun 11 2003
+     */
+    vec![b"certified release, version ".to_vec()]
+}
+
+/// Validates RedBoot version strings
+pub fn redboot_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Length of the magic signature bytes
+    const MAGIC_SIZE: usize = 27;
+    const BUILD_DELIM: &str = " - built ";
+
+    let mut result = SignatureResult {
+        offset,
+        description: REDBOOT_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_LOW,
+        ..Default::default()
+    };
+
+    if let Some(version_bytes) = file_data.get(offset + MAGIC_SIZE..) {
+        // e.g., "v3_0 - built 16:55:38, Sep 30 2011\n\n"
+        let version_string = get_cstring(version_bytes);
+
+        if let Some((version, build_date)) = version_string.split_once(BUILD_DELIM) {
+            let build_date = build_date.trim_end();
+
+            if !version.is_empty()
+                && !version.contains(char::is_whitespace)
+                && !build_date.is_empty()
+                && !build_date.contains('\n')
+            {
+                result.size = MAGIC_SIZE + version_string.len();
+                result.description = format!(
+                    "{}, version: {}, built: {}",
+                    result.description, version, build_date
+                );
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
@@ -1,3 +1,4 @@
+use crate::common::escape_non_printable;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
 use crate::structures::efigpt::parse_efigpt_header;
 
@@ -38,6 +39,22 @@ pub fn efigpt_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult,
                 } else {
                     result.size = efi_header.total_size;
                 }
+                // Add partition info to the description
+                for partition in &efi_header.partitions {
+                    let partition_name = match partition.name.is_empty() {
+                        true => partition.type_name.clone(),
+                        false => format!("{} ({})", partition.name, partition.type_name),
+                    };
+
+                    result.description = format!(
+                        "{}, partition: {}, offset: {:#X}, size: {} bytes",
+                        result.description,
+                        escape_non_printable(&partition_name),
+                        result.offset + partition.start,
+                        partition.size
+                    );
+                }
+
                 result.description = format!("{}, total size: {}", result.description, result.size);
                 return Ok(result);
             }
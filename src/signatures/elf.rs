@@ -1,5 +1,5 @@
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
-use crate::structures::elf::parse_elf_header;
+use crate::structures::elf::{parse_elf_header, parse_elf_program_info, parse_elf_size};
 
 /// Human readable description
 pub const DESCRIPTION: &str = "ELF binary";
@@ -31,6 +31,56 @@ pub fn elf_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
             elf_header.osabi,
             elf_header.endianness
         );
+
+        // Program headers may be missing or truncated; report whatever can be parsed
+        if let Ok(program_info) = parse_elf_program_info(&file_data[offset..]) {
+            if !program_info.interpreter.is_empty() {
+                result.description = format!(
+                    "{}, interpreter: {}",
+                    result.description, program_info.interpreter
+                );
+            } else if elf_header.exe_type == "executable" && !program_info.dynamic {
+                result.description = format!("{}, statically linked", result.description);
+            }
+
+            if !program_info.abi.is_empty() {
+                result.description = format!("{}, ABI: {}", result.description, program_info.abi);
+            }
+
+            if let Some(api_level) = program_info.android_api {
+                result.description =
+                    format!("{}, Android API level: {}", result.description, api_level);
+            }
+
+            if !program_info.build_id.is_empty() {
+                result.description = format!(
+                    "{}, build ID: {}",
+                    result.description, program_info.build_id
+                );
+            }
+
+            if !program_info.go_build_id.is_empty() {
+                result.description = format!(
+                    "{}, Go build ID: {}",
+                    result.description, program_info.go_build_id
+                );
+            }
+        }
+
+        /*
+         * An ELF at the start of a file is the file itself; leave its size unknown so that any data embedded in it
+         * (e.g., a kernel's initramfs) is still identified, and don't bother carving it. ELFs whose extents are
+         * unknown or truncated can't be carved either.
+         */
+        match parse_elf_size(&file_data[offset..]) {
+            Ok(elf_size) if offset > 0 => {
+                result.size = elf_size;
+                result.description =
+                    format!("{}, total size: {} bytes", result.description, result.size);
+            }
+            _ => result.extraction_declined = true,
+        }
+
         return Ok(result);
     }
 
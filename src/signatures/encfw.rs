@@ -1,3 +1,4 @@
+use crate::locked::LockedContent;
 use crate::signatures::common::{
     CONFIDENCE_LOW, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
 };
@@ -58,6 +59,11 @@ pub fn encfw_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult,
                 encfw_known_firmware()[magic_bytes]
             );
 
+            result.locked.push(LockedContent::new(
+                format!("{} encrypted firmware", encfw_known_firmware()[magic_bytes]),
+                "vendor firmware decryption key",
+            ));
+
             return Ok(result);
         }
     }
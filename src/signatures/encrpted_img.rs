@@ -1,3 +1,4 @@
+use crate::locked::LockedContent;
 use crate::signatures::common::{
     CONFIDENCE_LOW, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
 };
@@ -20,6 +21,10 @@ pub fn encrpted_img_parser(
         offset,
         description: DESCRIPTION.to_string(),
         confidence: CONFIDENCE_MEDIUM,
+        locked: vec![LockedContent::new(
+            "D-Link encrypted firmware image",
+            "D-Link firmware decryption key",
+        )],
         ..Default::default()
     };
 
@@ -0,0 +1,45 @@
+use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::structures::exfat::parse_exfat_header;
+
+/// Human readable description
+pub const DESCRIPTION: &str = "exFAT file system";
+
+/// exFAT boot sectors start with a jump instruction followed by the file system name
+pub fn exfat_magic() -> Vec<Vec<u8>> {
+    vec![b"\xEB\x76\x90EXFAT   ".to_vec()]
+}
+
+/// Validates the exFAT boot sector
+pub fn exfat_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        description: DESCRIPTION.to_string(),
+        offset,
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    let exfat_data = &file_data[offset..];
+
+    if let Ok(exfat_header) = parse_exfat_header(exfat_data) {
+        result.size = exfat_header.total_size;
+        result.description = format!(
+            "{}, cluster size: {} bytes, cluster count: {}, total size: {} bytes",
+            result.description, exfat_header.cluster_size, exfat_header.cluster_count, result.size
+        );
+
+        // Truncated images are reported up to the end of the available data
+        if result.size > exfat_data.len() {
+            result.size = exfat_data.len();
+            result.description =
+                format!("{}, truncated to {} bytes", result.description, result.size);
+        }
+
+        if exfat_header.dirty {
+            result.description = format!("{}, not cleanly unmounted", result.description);
+        }
+
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
@@ -1,3 +1,4 @@
+use crate::extractors::tsk::tsk_extractor;
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
 use crate::structures::ext::parse_ext_header;
 
@@ -49,6 +50,12 @@ pub fn ext_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
                 ext_header.blocks_count,
                 result.size
             );
+
+            // The internal extractor doesn't support all EXT features; fall back to tsk_recover for those that it does not
+            if !ext_header.unsupported_features().is_empty() {
+                result.preferred_extractor = Some(tsk_extractor());
+            }
+
             return Ok(result);
         }
     }
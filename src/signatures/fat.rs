@@ -33,15 +33,17 @@ pub fn fat_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
                 result.size = fat_header.total_size;
 
                 // Include FAT type in the description
-                let mut fat_type_desc: &str = "FAT12/16";
-                if fat_header.is_fat32 {
-                    fat_type_desc = "FAT32";
-                }
-
                 result.description = format!(
-                    "{}, type: {}, total size: {} bytes",
-                    result.description, fat_type_desc, result.size
+                    "{}, type: FAT{}, total size: {} bytes",
+                    result.description, fat_header.fat_type, result.size
                 );
+
+                // Truncated images, such as partial SD card dumps, are reported up to the end of the available data
+                if result.size > fat_data.len() {
+                    result.size = fat_data.len();
+                    result.description =
+                        format!("{}, truncated to {} bytes", result.description, result.size);
+                }
                 return Ok(result);
             }
         }
@@ -0,0 +1,67 @@
+use crate::signatures::common::{CONFIDENCE_LOW, SignatureError, SignatureResult};
+use crate::structures::flatbuffers::parse_flatbuffer;
+
+/// Human readable description
+pub const DESCRIPTION: &str = "FlatBuffers data";
+
+/// FlatBuffers have no magic bytes; buffers start with a small, 4-byte aligned offset to the root table
+pub fn flatbuffers_magic() -> Vec<Vec<u8>> {
+    // Range of expected root table offsets
+    const MIN_ROOT_OFFSET: u32 = 8;
+    const MAX_ROOT_OFFSET: u32 = 0x7C;
+
+    (MIN_ROOT_OFFSET..=MAX_ROOT_OFFSET)
+        .step_by(4)
+        .map(|root_offset| root_offset.to_le_bytes().to_vec())
+        .collect()
+}
+
+/// Heuristically validates FlatBuffers data
+pub fn flatbuffers_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Without a file identifier, require a few fields to weed out false positives
+    const MIN_FIELD_COUNT: usize = 3;
+
+    let mut result = SignatureResult {
+        offset,
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_LOW,
+        ..Default::default()
+    };
+
+    if let Ok(flatbuffer) = parse_flatbuffer(&file_data[offset..]) {
+        if !flatbuffer.identifier.is_empty() || flatbuffer.fields.len() >= MIN_FIELD_COUNT {
+            if !flatbuffer.identifier.is_empty() {
+                result.description = format!(
+                    "{}, identifier: {}",
+                    result.description, flatbuffer.identifier
+                );
+            }
+
+            // Best-effort dump of the root table's field layout
+            let field_descriptions: Vec<String> = flatbuffer
+                .fields
+                .iter()
+                .map(|field| match field.is_reference {
+                    true => format!("{}: reference", field.index),
+                    false => format!("{}: {} bytes", field.index, field.size),
+                })
+                .collect();
+
+            result.description = format!(
+                "{}, root table offset: {:#X}, root table size: {} bytes, {} of {} fields present, structure: {}",
+                result.description,
+                flatbuffer.root_table_offset,
+                flatbuffer.root_table_size,
+                flatbuffer.fields.len(),
+                flatbuffer.vtable_field_count,
+                field_descriptions.join(", ")
+            );
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
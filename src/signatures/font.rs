@@ -0,0 +1,90 @@
+use crate::extractors::font::{extract_sfnt_font, extract_woff_font};
+use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
+use crate::structures::font::{parse_sfnt_font, parse_woff_font};
+
+/// Human readable descriptions
+pub const SFNT_DESCRIPTION: &str = "Font file";
+pub const WOFF_DESCRIPTION: &str = "WOFF web font";
+
+/// TrueType / OpenType magic bytes; includes the high byte of the table count, which is always 0
+pub fn sfnt_magic() -> Vec<Vec<u8>> {
+    vec![
+        b"\x00\x01\x00\x00\x00".to_vec(),
+        b"OTTO\x00".to_vec(),
+        b"true\x00".to_vec(),
+    ]
+}
+
+/// WOFF / WOFF2 magic bytes
+pub fn woff_magic() -> Vec<Vec<u8>> {
+    vec![b"wOFF".to_vec(), b"wOF2".to_vec()]
+}
+
+/// Validates TrueType / OpenType fonts
+pub fn sfnt_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: SFNT_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    let dry_run = extract_sfnt_font(file_data, offset, None);
+
+    if dry_run.success {
+        if let Ok(sfnt_font) = parse_sfnt_font(&file_data[offset..]) {
+            // Font file size must not exceed available data
+            if let Some(font_size) = dry_run.size {
+                if offset + font_size <= file_data.len() {
+                    // No sense in extracting a font if the entire file is just the font itself
+                    if offset == 0 && font_size == file_data.len() {
+                        result.extraction_declined = true;
+                    }
+
+                    result.size = font_size;
+                    result.description = format!(
+                        "{}, {}, {} tables, total size: {} bytes",
+                        result.description, sfnt_font.font_type, sfnt_font.num_tables, result.size
+                    );
+                    return Ok(result);
+                }
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates WOFF / WOFF2 web fonts
+pub fn woff_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: WOFF_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    let dry_run = extract_woff_font(file_data, offset, None);
+
+    if dry_run.success {
+        if let Ok(woff_font) = parse_woff_font(&file_data[offset..]) {
+            if offset + woff_font.size <= file_data.len() {
+                // No sense in extracting a font if the entire file is just the font itself
+                if offset == 0 && woff_font.size == file_data.len() {
+                    result.extraction_declined = true;
+                }
+
+                result.size = woff_font.size;
+                result.description = format!(
+                    "{}, version: {}, {} tables, total size: {} bytes",
+                    result.description, woff_font.version, woff_font.num_tables, result.size
+                );
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
@@ -1,6 +1,8 @@
 use crate::common;
 use crate::extractors::gzip::gzip_decompress;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::signatures::config::deflated_config_description;
+use crate::signatures::webasset::deflated_web_asset_description;
 use crate::structures::gzip::parse_gzip_header;
 
 /// Human readable description
@@ -44,11 +46,13 @@ pub fn gzip_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
                     size: total_size,
                     confidence: CONFIDENCE_HIGH,
                     description: format!(
-                        "{},{} operating system: {}, timestamp: {}, total size: {} bytes",
+                        "{},{} operating system: {}, timestamp: {}{}{}, total size: {} bytes",
                         DESCRIPTION,
                         original_file_name_text,
                         gzip_header.os,
                         common::epoch_to_string(gzip_header.timestamp),
+                        deflated_config_description(file_data, offset + gzip_header.size),
+                        deflated_web_asset_description(file_data, offset + gzip_header.size),
                         total_size,
                     ),
                     ..Default::default()
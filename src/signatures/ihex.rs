@@ -0,0 +1,50 @@
+use crate::extractors::ihex::parse_ihex;
+use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+
+/// Human readable descriptions
+pub const IHEX_DESCRIPTION: &str = "Intel HEX";
+pub const IHEX_SHORT_DESCRIPTION: &str = "Intel HEX (generic)";
+
+/// Generic, short signature for Intel HEX files, should only be matched at the beginning of a file
+pub fn ihex_short_magic() -> Vec<Vec<u8>> {
+    vec![b":".to_vec()]
+}
+
+/// Intel HEX files usually start with an extended linear or segment address record
+pub fn ihex_magic() -> Vec<Vec<u8>> {
+    vec![b":02000004".to_vec(), b":02000002".to_vec()]
+}
+
+/// Validates Intel HEX signatures
+pub fn ihex_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        offset,
+        description: IHEX_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    // Every record is validated by its checksum, and the records must end with an end of file record
+    if let Some(hex_records) = parse_ihex(&file_data[offset..]) {
+        if let Some(load_address) = hex_records.memory.load_address() {
+            result.size = hex_records.size;
+            result.description = format!(
+                "{}, {}-bit addresses, load address: {:#X}, data size: {} bytes, total size: {} bytes",
+                result.description,
+                hex_records.address_bits,
+                load_address,
+                hex_records.memory.data_size(),
+                result.size
+            );
+
+            if let Some(entry_point) = hex_records.entry_point {
+                result.description =
+                    format!("{}, entry point: {:#X}", result.description, entry_point);
+            }
+
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
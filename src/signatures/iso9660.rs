@@ -30,6 +30,16 @@ pub fn iso_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
             result.size = iso_header.image_size;
             result.description =
                 format!("{}, total size: {} bytes", result.description, result.size);
+
+            // Truncated images are reported up to the end of the available data
+            let available_data = file_data.len() - result.offset;
+
+            if result.size > available_data {
+                result.size = available_data;
+                result.description =
+                    format!("{}, truncated to {} bytes", result.description, result.size);
+            }
+
             return Ok(result);
         }
     }
@@ -0,0 +1,46 @@
+use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::structures::java::parse_java_class;
+
+/// Human readable description
+pub const DESCRIPTION: &str = "Java class file";
+
+/// Java class file magic bytes; these are shared with Mach-O universal binaries, which are weeded out by the parser
+pub fn java_class_magic() -> Vec<Vec<u8>> {
+    vec![b"\xCA\xFE\xBA\xBE".to_vec()]
+}
+
+/// Validates Java class files
+pub fn java_class_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    if let Ok(class_header) = parse_java_class(&file_data[offset..]) {
+        result.size = class_header.size;
+
+        let mut java_version = class_header.java_version.clone();
+        if class_header.preview {
+            java_version = format!("{} (preview features enabled)", java_version);
+        }
+
+        result.description = format!(
+            "{}, class: {}, Java version: {}, class file version: {}.{}, total size: {} bytes",
+            result.description,
+            class_header.class_name.replace('/', "."),
+            java_version,
+            class_header.major_version,
+            class_header.minor_version,
+            result.size
+        );
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
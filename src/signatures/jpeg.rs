@@ -4,12 +4,18 @@ use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResu
 /// Human readable description
 pub const DESCRIPTION: &str = "JPEG image";
 
-/// JPEG magic bytes
+/// JPEG magic bytes; the start of image marker, followed by the first marker commonly found in JFIF, EXIF, ICC
+/// profile, Photoshop, Adobe and commented JPEGs, or those without any application markers at all
 pub fn jpeg_magic() -> Vec<Vec<u8>> {
     vec![
         b"\xFF\xD8\xFF\xE0\x00\x10JFIF\x00".to_vec(),
         b"\xFF\xD8\xFF\xE1".to_vec(),
+        b"\xFF\xD8\xFF\xE2".to_vec(),
+        b"\xFF\xD8\xFF\xED".to_vec(),
+        b"\xFF\xD8\xFF\xEE".to_vec(),
+        b"\xFF\xD8\xFF\xFE".to_vec(),
         b"\xFF\xD8\xFF\xDB".to_vec(),
+        b"\xFF\xD8\xFF\xC4".to_vec(),
     ]
 }
 
@@ -1,3 +1,4 @@
+use crate::locked::LockedContent;
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
 use crate::structures::luks::parse_luks_header;
 
@@ -40,8 +41,50 @@ pub fn luks_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
                 luks_header.header_size,
                 luks_header.hashfn
             );
+
+            // LUKS2 ciphers are described in the JSON metadata
+            if !luks_header.cipher_algorithm.is_empty() {
+                result.description = format!(
+                    "{}, cipher algorithm: {}, cipher mode: {}",
+                    result.description, luks_header.cipher_algorithm, luks_header.cipher_mode
+                );
+            }
         }
 
+        if luks_header.key_size > 0 {
+            result.description = format!(
+                "{}, key size: {} bits",
+                result.description,
+                luks_header.key_size * 8
+            );
+        }
+
+        result.description = format!(
+            "{}, key slots: {}",
+            result.description, luks_header.key_slots
+        );
+
+        if luks_header.data_offset > 0 {
+            result.description = format!(
+                "{}, encrypted data offset: {:#X}",
+                result.description,
+                offset + luks_header.data_offset
+            );
+        }
+
+        if !luks_header.label.is_empty() {
+            result.description = format!("{}, label: {}", result.description, luks_header.label);
+        }
+
+        if !luks_header.uuid.is_empty() {
+            result.description = format!("{}, UUID: {}", result.description, luks_header.uuid);
+        }
+
+        result.locked.push(LockedContent::new(
+            format!("LUKS{} encrypted volume", luks_header.version),
+            "LUKS passphrase or key file for one of the volume's key slots",
+        ));
+
         return Ok(result);
     }
 
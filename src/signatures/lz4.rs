@@ -1,13 +1,16 @@
 use crate::common::is_offset_safe;
+use crate::extractors::lz4::lz4_decompress;
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
-use crate::structures::lz4::{parse_lz4_block_header, parse_lz4_file_header};
+use crate::structures::lz4::{
+    LZ4_FRAME_MAGIC, LZ4_LEGACY_MAGIC, parse_lz4_block_header, parse_lz4_file_header,
+};
 
 /// Human readable description
 pub const DESCRIPTION: &str = "LZ4 compressed data";
 
 /// LZ4 files start with these magic bytes
 pub fn lz4_magic() -> Vec<Vec<u8>> {
-    vec![b"\x04\x22\x4D\x18".to_vec()]
+    vec![LZ4_FRAME_MAGIC.to_vec(), LZ4_LEGACY_MAGIC.to_vec()]
 }
 
 /// Validate a LZ4 signature
@@ -22,6 +25,24 @@ pub fn lz4_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
         ..Default::default()
     };
 
+    // The legacy format has no end marker, so the data size is determined by decompressing it
+    if file_data[offset..].starts_with(LZ4_LEGACY_MAGIC) {
+        let dry_run = lz4_decompress(file_data, offset, None);
+
+        if dry_run.success {
+            if let Some(lz4_data_size) = dry_run.size {
+                result.size = lz4_data_size;
+                result.description = format!(
+                    "{}, legacy format, total size: {} bytes",
+                    result.description, result.size
+                );
+                return Ok(result);
+            }
+        }
+
+        return Err(SignatureError);
+    }
+
     // Sanity check the size of available data
     if let Ok(lz4_file_header) = parse_lz4_file_header(&file_data[offset..]) {
         // LZ4 data starts immediately after the LZ4 header
@@ -1,17 +1,17 @@
 use crate::extractors::lzma;
-use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::signatures::common::{CONFIDENCE_HIGH, CONFIDENCE_LOW, SignatureError, SignatureResult};
 use crate::structures::lzma::parse_lzma_header;
 
 /// Human readable description
 pub const DESCRIPTION: &str = "LZMA compressed data";
 
+/// Common LZMA properties
+pub const COMMON_PROPERTIES: [u8; 4] = [0x5D, 0x6E, 0x6D, 0x6C];
+
 /// Builds a list of common LZMA magic bytes (properties + dictionary sizes)
 pub fn lzma_magic() -> Vec<Vec<u8>> {
     let mut magic_signatures: Vec<Vec<u8>> = vec![];
 
-    // Common LZMA properties
-    let supported_properties: Vec<u8> = vec![0x5D, 0x6E, 0x6D, 0x6C];
-
     let supported_dictionary_sizes: Vec<u32> = vec![
         0x10_00_00_00,
         0x20_00_00_00,
@@ -33,7 +33,7 @@ pub fn lzma_magic() -> Vec<Vec<u8>> {
      * for a more generic, but shorter, signature, such as b"\x5d\x00\x00". This results in less validation
      * of false positives, improving analysis times.
      */
-    for property in supported_properties {
+    for property in COMMON_PROPERTIES {
         for dictionary_size in &supported_dictionary_sizes {
             let mut magic: Vec<u8> = vec![property];
             magic.extend(dictionary_size.to_le_bytes().to_vec());
@@ -46,6 +46,9 @@ pub fn lzma_magic() -> Vec<Vec<u8>> {
 
 /// Validate LZMA signatures
 pub fn lzma_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Truncated or corrupt streams must decompress at least this many bytes to be reported as a heuristic match
+    const MIN_PARTIAL_SIZE: usize = 32 * 1024;
+
     // Success return value
     let mut result = SignatureResult {
         offset,
@@ -67,17 +70,39 @@ pub fn lzma_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
         if dry_run.success {
             if let Some(lzma_stream_size) = dry_run.size {
                 result.size = lzma_stream_size;
+
+                let decompressed_size = match lzma_header.decompressed_size_valid {
+                    true => format!("{} bytes", lzma_header.decompressed_size as i64),
+                    false => format!("invalid ({:#X})", lzma_header.decompressed_size),
+                };
+
                 result.description = format!(
-                    "{}, properties: {:#04X}, dictionary size: {} bytes, compressed size: {} bytes, uncompressed size: {} bytes",
+                    "{}, properties: {:#04X}, dictionary size: {} bytes, compressed size: {} bytes, uncompressed size: {}",
                     result.description,
                     lzma_header.properties,
                     lzma_header.dictionary_size,
                     result.size,
-                    lzma_header.decompressed_size as i64
+                    decompressed_size
                 );
                 return Ok(result);
             }
         }
+
+        /*
+         * A stream with a sane header that decompresses a good amount of data before failing is likely truncated
+         * or corrupt LZMA data, rather than a false positive; report it as an unconfirmed, heuristic match.
+         */
+        if lzma_header.decompressed_size_valid
+            && lzma::lzma_decompressed_size(file_data, offset, MIN_PARTIAL_SIZE) >= MIN_PARTIAL_SIZE
+        {
+            result.heuristic = true;
+            result.confidence = CONFIDENCE_LOW;
+            result.description = format!(
+                "Possible {}, properties: {:#04X}, dictionary size: {} bytes, stream is truncated or corrupt",
+                result.description, lzma_header.properties, lzma_header.dictionary_size
+            );
+            return Ok(result);
+        }
     }
 
     Err(SignatureError)
@@ -1,7 +1,7 @@
 use crate::common::is_offset_safe;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
 use crate::structures::lzop::{
-    parse_lzop_block_header, parse_lzop_eof_marker, parse_lzop_file_header,
+    LZOPFileHeader, parse_lzop_block_header, parse_lzop_eof_marker, parse_lzop_file_header,
 };
 
 /// Human readable description
@@ -26,8 +26,7 @@ pub fn lzop_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
     if let Ok(lzop_header) = parse_lzop_file_header(&file_data[offset..]) {
         if let Some(lzop_data) = file_data.get(offset + lzop_header.header_size..) {
             // Get the size of the compressed LZO data
-            if let Ok(data_size) = get_lzo_data_size(lzop_data, lzop_header.block_checksum_present)
-            {
+            if let Ok(data_size) = get_lzo_data_size(lzop_data, &lzop_header) {
                 // Update the total size to include the LZO data
                 result.size = lzop_header.header_size + data_size;
                 result.description =
@@ -43,7 +42,7 @@ pub fn lzop_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
 // Parse the LZO blocks to determine the size of the compressed data, including the terminating EOF marker
 fn get_lzo_data_size(
     lzo_data: &[u8],
-    compressed_checksum_present: bool,
+    lzop_header: &LZOPFileHeader,
 ) -> Result<usize, SignatureError> {
     // Technially LZOP could have one block, but this would seem uncommon
     const MIN_BLOCK_COUNT: usize = 2;
@@ -56,7 +55,7 @@ fn get_lzo_data_size(
     // Loop until we run out of data or an invalid block header is encountered
     while is_offset_safe(available_data, data_size, last_offset) {
         // Parse the next block header
-        match parse_lzop_block_header(&lzo_data[data_size..], compressed_checksum_present) {
+        match parse_lzop_block_header(&lzo_data[data_size..], lzop_header) {
             Err(_) => {
                 break;
             }
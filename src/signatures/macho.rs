@@ -0,0 +1,146 @@
+use crate::signatures::common::{
+    CONFIDENCE_HIGH, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
+};
+use crate::structures::macho::{MachOHeader, parse_macho_header, parse_macho_universal};
+
+/// Human readable descriptions
+pub const DESCRIPTION: &str = "Mach-O binary";
+pub const UNIVERSAL_DESCRIPTION: &str = "Mach-O universal binary";
+
+/// 32 and 64-bit Mach-O magic bytes, in both byte orders
+pub fn macho_magic() -> Vec<Vec<u8>> {
+    vec![
+        b"\xFE\xED\xFA\xCE".to_vec(),
+        b"\xCE\xFA\xED\xFE".to_vec(),
+        b"\xFE\xED\xFA\xCF".to_vec(),
+        b"\xCF\xFA\xED\xFE".to_vec(),
+    ]
+}
+
+/// Mach-O universal binary magic bytes; these are shared with Java class files, which are weeded out by the parser
+pub fn macho_universal_magic() -> Vec<Vec<u8>> {
+    vec![b"\xCA\xFE\xBA\xBE".to_vec(), b"\xCA\xFE\xBA\xBF".to_vec()]
+}
+
+/// Validates a Mach-O binary and describes its load commands
+pub fn macho_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    if let Ok(macho_header) = parse_macho_header(&file_data[offset..]) {
+        let available_data = file_data.len() - offset;
+
+        result.description = format!("{}, {}", result.description, describe(&macho_header));
+
+        // The binary may be truncated; only report a size if all of its data is present
+        if macho_header.size <= available_data {
+            result.size = macho_header.size;
+            result.description =
+                format!("{}, total size: {} bytes", result.description, result.size);
+        }
+
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates a Mach-O universal binary and lists its architectures
+pub fn macho_universal_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: UNIVERSAL_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    if let Ok(universal) = parse_macho_universal(&file_data[offset..]) {
+        let available_data = file_data.len() - offset;
+        let mut architectures: Vec<String> = vec![];
+
+        for architecture in &universal.architectures {
+            // If the architecture's binary is present, it must be a valid Mach-O
+            if let Some(macho_data) = file_data.get(offset + architecture.offset..) {
+                match parse_macho_header(macho_data) {
+                    Err(_) => return Err(SignatureError),
+                    Ok(_) => result.confidence = CONFIDENCE_HIGH,
+                }
+            }
+
+            architectures.push(architecture.cpu_type.clone());
+        }
+
+        result.description = format!(
+            "{}, architectures: {}",
+            result.description,
+            architectures.join(", ")
+        );
+
+        if universal.size <= available_data {
+            result.size = universal.size;
+            result.description =
+                format!("{}, total size: {} bytes", result.description, result.size);
+        }
+
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Describes a Mach-O binary's type, architecture and load commands
+fn describe(macho_header: &MachOHeader) -> String {
+    let mut description = format!(
+        "{}-bit {}, {}, {} endian, {} load commands",
+        macho_header.bits,
+        macho_header.file_type,
+        macho_header.cpu_type,
+        macho_header.endianness,
+        macho_header.load_command_count
+    );
+
+    if !macho_header.dylinker.is_empty() {
+        description = format!("{}, dynamic linker: {}", description, macho_header.dylinker);
+    }
+
+    if !macho_header.install_name.is_empty() {
+        description = format!(
+            "{}, install name: {}",
+            description, macho_header.install_name
+        );
+    }
+
+    if macho_header.library_count > 0 {
+        description = format!(
+            "{}, linked libraries: {}",
+            description, macho_header.library_count
+        );
+    }
+
+    if !macho_header.platform.is_empty() {
+        description = format!("{}, platform: {}", description, macho_header.platform);
+    }
+
+    if !macho_header.uuid.is_empty() {
+        description = format!("{}, UUID: {}", description, macho_header.uuid);
+    }
+
+    if macho_header.signed {
+        description = format!("{}, code signed", description);
+    }
+
+    if macho_header.encrypted {
+        description = format!("{}, encrypted", description);
+    }
+
+    description
+}
@@ -46,8 +46,10 @@ pub fn mbr_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
                         }
 
                         // Add partition info to the description
-                        result.description =
-                            format!("{}, partition: {}", result.description, partition.name);
+                        result.description = format!(
+                            "{}, partition: {}, offset: {:#X}, size: {} bytes",
+                            result.description, partition.name, partition.start, partition.size
+                        );
                     }
 
                     // Add total size to the description
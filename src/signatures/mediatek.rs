@@ -0,0 +1,185 @@
+use crate::common::escape_non_printable;
+use crate::signatures::common::{
+    CONFIDENCE_HIGH, CONFIDENCE_LOW, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
+};
+use crate::structures::mediatek::{
+    parse_bloader_info_version, parse_brom_header, parse_gfh_file_info, parse_mtk_image_header,
+};
+
+/// Human readable description
+pub const IMAGE_DESCRIPTION: &str = "Mediatek image header";
+
+/// Mediatek image header magic bytes
+pub fn mtk_image_magic() -> Vec<Vec<u8>> {
+    vec![b"\x88\x16\x88\x58".to_vec()]
+}
+
+/// Validates Mediatek image headers
+pub fn mtk_image_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        offset,
+        description: IMAGE_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    if let Ok(mtk_header) = parse_mtk_image_header(&file_data[offset..]) {
+        result.size = mtk_header.header_size + mtk_header.data_size;
+
+        // The image payload must be present
+        if file_data.len() >= offset + result.size {
+            result.description = format!(
+                "{}, name: {}, payload offset: {:#X}, payload size: {} bytes",
+                result.description,
+                escape_non_printable(&mtk_header.name),
+                offset + mtk_header.header_size,
+                mtk_header.data_size
+            );
+
+            if let Some(load_address) = mtk_header.load_address {
+                result.description =
+                    format!("{}, load address: {:#X}", result.description, load_address);
+            }
+
+            if mtk_header.more_images {
+                result.description = format!("{}, more images follow", result.description);
+            }
+
+            result.description =
+                format!("{}, total size: {} bytes", result.description, result.size);
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Human readable description
+pub const PRELOADER_DESCRIPTION: &str = "Mediatek preloader";
+
+/// GFH file info header magic bytes: "MMM", version 1, header size 0x38, header type 0, and the "FILE_INFO" ID
+pub fn mtk_preloader_magic() -> Vec<Vec<u8>> {
+    vec![b"MMM\x01\x38\x00\x00\x00FILE_INFO\x00".to_vec()]
+}
+
+/// Validates Mediatek preloaders, which start with a GFH file info header
+pub fn mtk_preloader_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        offset,
+        description: PRELOADER_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    if let Ok(file_info) = parse_gfh_file_info(&file_data[offset..]) {
+        result.size = file_info.file_size;
+
+        if file_data.len() >= offset + result.size {
+            result.description = format!(
+                "{}, version: {}, boot device: {}, load address: {:#X}, code offset: {:#X}, entry point offset: {:#X}, signature size: {} bytes, total size: {} bytes",
+                result.description,
+                file_info.file_version,
+                file_info.flash_device,
+                file_info.load_address,
+                offset + file_info.content_offset,
+                offset + file_info.jump_offset,
+                file_info.signature_size,
+                result.size
+            );
+
+            // Report the version of the EMI settings embedded in the preloader, if any
+            let preloader_data = &file_data[offset..offset + result.size];
+            let bloader_info_magic = &mtk_bloader_info_magic()[0];
+
+            if let Some(info_offset) = preloader_data
+                .windows(bloader_info_magic.len())
+                .position(|window| window == bloader_info_magic.as_slice())
+            {
+                if let Ok(version) = parse_bloader_info_version(&preloader_data[info_offset..]) {
+                    result.description =
+                        format!("{}, EMI settings version: {}", result.description, version);
+                }
+            }
+
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Human readable description
+pub const BROM_DESCRIPTION: &str = "Mediatek BootROM header";
+
+/// BootROM device header names
+pub fn mtk_brom_magic() -> Vec<Vec<u8>> {
+    vec![
+        b"EMMC_BOOT\x00".to_vec(),
+        b"SF_BOOT\x00".to_vec(),
+        b"SDMMC_BOOT\x00".to_vec(),
+    ]
+}
+
+/// Validates Mediatek BootROM device and boot layout headers
+pub fn mtk_brom_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        offset,
+        description: BROM_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    if let Ok(brom_header) = parse_brom_header(&file_data[offset..]) {
+        result.size = brom_header.total_size;
+
+        if file_data.len() >= offset + result.size {
+            result.description = format!(
+                "{}, device: {}, layout: {}, payload offset: {:#X}, payload size: {} bytes, total size: {} bytes",
+                result.description,
+                brom_header.device,
+                brom_header.layout_type,
+                offset + brom_header.payload_offset,
+                brom_header.total_size - brom_header.payload_offset,
+                result.size
+            );
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Human readable description
+pub const BLOADER_INFO_DESCRIPTION: &str = "Mediatek bootloader EMI settings";
+
+/// Preloaders embed their DRAM (EMI) settings after this identifier
+pub fn mtk_bloader_info_magic() -> Vec<Vec<u8>> {
+    vec![b"MTK_BLOADER_INFO_v".to_vec()]
+}
+
+/// Validates Mediatek bootloader EMI settings identifiers
+pub fn mtk_bloader_info_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Little validation is possible, so confidence is low
+    let mut result = SignatureResult {
+        offset,
+        description: BLOADER_INFO_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_LOW,
+        ..Default::default()
+    };
+
+    if let Ok(version) = parse_bloader_info_version(&file_data[offset..]) {
+        result.description = format!("{}, version: {}", result.description, version);
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
@@ -0,0 +1,55 @@
+use crate::extractors::mp3::extract_mp3_file;
+use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
+use crate::structures::mp3::{parse_id3_header, parse_mp3_stream};
+
+/// Human readable description
+pub const DESCRIPTION: &str = "MP3 audio";
+
+/// ID3v2 tag magic bytes, plus the supported major versions
+pub fn mp3_magic() -> Vec<Vec<u8>> {
+    vec![
+        b"ID3\x02\x00".to_vec(),
+        b"ID3\x03\x00".to_vec(),
+        b"ID3\x04\x00".to_vec(),
+    ]
+}
+
+/// Validates ID3 tagged MP3 files
+pub fn mp3_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    let dry_run = extract_mp3_file(file_data, offset, None);
+
+    if dry_run.success {
+        if let Some(mp3_size) = dry_run.size {
+            if let Ok(id3_header) = parse_id3_header(&file_data[offset..]) {
+                if let Ok(mp3_stream) = parse_mp3_stream(&file_data[offset + id3_header.size..]) {
+                    // No sense in extracting audio if the entire file is just the audio itself
+                    if offset == 0 && mp3_size == file_data.len() {
+                        result.extraction_declined = true;
+                    }
+
+                    result.size = mp3_size;
+                    result.description = format!(
+                        "{}, ID3v2.{}, {} Layer III, {} Hz, {} frames, total size: {} bytes",
+                        result.description,
+                        id3_header.version,
+                        mp3_stream.version,
+                        mp3_stream.sample_rate,
+                        mp3_stream.frame_count,
+                        result.size
+                    );
+                    return Ok(result);
+                }
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
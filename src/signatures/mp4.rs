@@ -0,0 +1,48 @@
+use crate::extractors::mp4::extract_mp4_file;
+use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
+use crate::structures::mp4::parse_mp4_file;
+
+/// Human readable description
+pub const DESCRIPTION: &str = "ISO media file";
+
+/// ISO base media files start with an ftyp box; the magic bytes are the box type
+pub fn mp4_magic() -> Vec<Vec<u8>> {
+    vec![b"ftyp".to_vec()]
+}
+
+/// Validates ISO base media (MP4, MOV, 3GP, etc) files
+pub fn mp4_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // The box type is preceeded by the 4-byte box size
+    const MAGIC_OFFSET: usize = 4;
+
+    // Successful return value
+    let mut result = SignatureResult {
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    if offset >= MAGIC_OFFSET {
+        result.offset = offset - MAGIC_OFFSET;
+
+        let dry_run = extract_mp4_file(file_data, result.offset, None);
+
+        if dry_run.success {
+            if let Ok(mp4_file) = parse_mp4_file(&file_data[result.offset..]) {
+                // No sense in extracting a video if the entire file is just the video itself
+                if result.offset == 0 && mp4_file.size == file_data.len() {
+                    result.extraction_declined = true;
+                }
+
+                result.size = mp4_file.size;
+                result.description = format!(
+                    "{}, major brand: {}, total size: {} bytes",
+                    result.description, mp4_file.major_brand, result.size
+                );
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
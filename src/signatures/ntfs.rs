@@ -29,6 +29,14 @@ pub fn ntfs_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
                 "{}, number of sectors: {}, bytes per sector: {}, total size: {} bytes",
                 result.description, ntfs_header.sector_count, ntfs_header.sector_size, result.size
             );
+
+            // Truncated partitions, such as partial disk image dumps, are reported up to the end of the available data
+            if result.size > file_data.len() - offset {
+                result.size = file_data.len() - offset;
+                result.description =
+                    format!("{}, truncated to {} bytes", result.description, result.size);
+            }
+
             return Ok(result);
         }
     }
@@ -0,0 +1,165 @@
+use crate::extractors::nvram::{extract_broadcom_nvram, extract_routerboot_config};
+use crate::signatures::common::{
+    CONFIDENCE_HIGH, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
+};
+use crate::structures::nvram::{
+    ConfigEntry, parse_broadcom_nvram_header, parse_key_value_block, parse_routerboot_config,
+    parse_uboot_environment,
+};
+
+/// Human readable descriptions
+pub const BROADCOM_NVRAM_DESCRIPTION: &str = "Broadcom NVRAM";
+pub const UBOOT_ENV_DESCRIPTION: &str = "U-Boot environment";
+pub const ROUTERBOOT_DESCRIPTION: &str = "MikroTik RouterBOOT hard config";
+
+/// Broadcom NVRAM magic bytes
+pub fn broadcom_nvram_magic() -> Vec<Vec<u8>> {
+    vec![b"FLSH".to_vec()]
+}
+
+/// U-Boot environments have no magic bytes; search for variables that nearly every environment defines
+pub fn uboot_env_magic() -> Vec<Vec<u8>> {
+    vec![
+        b"\x00bootcmd=".to_vec(),
+        b"\x00bootargs=".to_vec(),
+        b"\x00bootdelay=".to_vec(),
+        b"\x00baudrate=".to_vec(),
+    ]
+}
+
+/// RouterBOOT hard_config magic bytes
+pub fn routerboot_magic() -> Vec<Vec<u8>> {
+    vec![b"Hard".to_vec()]
+}
+
+/// Validates Broadcom NVRAM blocks
+pub fn broadcom_nvram_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: BROADCOM_NVRAM_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    let dry_run = extract_broadcom_nvram(file_data, offset, None);
+
+    if dry_run.success {
+        if let Some(nvram_size) = dry_run.size {
+            if let Ok(nvram_header) = parse_broadcom_nvram_header(&file_data[offset..]) {
+                if let Ok(vars) = parse_key_value_block(
+                    &file_data[offset + nvram_header.header_size..offset + nvram_size],
+                ) {
+                    result.size = nvram_size;
+                    result.description = format!(
+                        "{}, version: {}, {} variables{}, total size: {} bytes",
+                        result.description,
+                        nvram_header.version,
+                        vars.entries.len(),
+                        interesting_entries(&vars.entries),
+                        result.size
+                    );
+                    return Ok(result);
+                }
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates U-Boot environments
+pub fn uboot_env_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Magic bytes start with the NULL terminator of the previous string
+    const MAGIC_VAR_OFFSET: usize = 1;
+
+    // Successful return value
+    let mut result = SignatureResult {
+        description: UBOOT_ENV_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    if let Ok(uboot_env) = parse_uboot_environment(file_data, offset + MAGIC_VAR_OFFSET) {
+        let mut redundant = "";
+
+        if uboot_env.redundant {
+            redundant = ", redundant";
+        }
+
+        result.offset = uboot_env.offset;
+        result.size = uboot_env.size;
+        result.description = format!(
+            "{}{}, {} endian CRC, {} variables{}, total size: {} bytes",
+            result.description,
+            redundant,
+            uboot_env.endianness,
+            uboot_env.entries.len(),
+            interesting_entries(&uboot_env.entries),
+            result.size
+        );
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates RouterBOOT hard_config TLV blocks
+pub fn routerboot_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: ROUTERBOOT_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    let dry_run = extract_routerboot_config(file_data, offset, None);
+
+    if dry_run.success {
+        if let Ok(config) = parse_routerboot_config(&file_data[offset..]) {
+            result.size = config.size;
+            result.description = format!(
+                "{}, {} entries{}, total size: {} bytes",
+                result.description,
+                config.entries.len(),
+                interesting_entries(&config.entries),
+                result.size
+            );
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Returns a description string containing the values of commonly interesting configuration keys, if present
+fn interesting_entries(entries: &[ConfigEntry]) -> String {
+    const INTERESTING_KEYS: &[&str] = &[
+        "boardtype",
+        "boardnum",
+        "board_identifier",
+        "product_name",
+        "serial_number",
+        "bootcmd",
+    ];
+
+    let mut description = "".to_string();
+
+    for entry in entries {
+        if INTERESTING_KEYS.contains(&entry.key.as_str()) {
+            description = format!("{}, {}: {:.100}", description, entry.key, entry.value);
+        }
+    }
+
+    description
+}
@@ -1,4 +1,5 @@
 use crate::common::is_printable_ascii;
+use crate::locked::LockedContent;
 use crate::signatures::common::{
     CONFIDENCE_LOW, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
 };
@@ -36,6 +37,10 @@ pub fn openssl_crypt_parser(
 
             result.description =
                 format!("{}, salt: {:#X}", result.description, openssl_header.salt);
+            result.locked.push(LockedContent::new(
+                "OpenSSL encrypted data",
+                "OpenSSL passphrase, or the derived key and IV",
+            ));
             return Ok(result);
         }
     }
@@ -0,0 +1,161 @@
+use crate::common::escape_non_printable;
+use crate::extractors::openwrt::extract_sysupgrade_images;
+use crate::signatures::common::{
+    CONFIDENCE_HIGH, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
+};
+use crate::structures::openwrt::{
+    FWIMAGE_INFO, FWIMAGE_TRAILER_SIZE, parse_fwimage_trailer, parse_sysupgrade_tar,
+};
+use serde_json::Value;
+
+/// Human readable description
+pub const SYSUPGRADE_DESCRIPTION: &str = "OpenWrt sysupgrade image";
+
+/// Sysupgrade images are tar archives whose first entry is the sysupgrade-<board> directory
+pub fn sysupgrade_magic() -> Vec<Vec<u8>> {
+    vec![b"sysupgrade-".to_vec()]
+}
+
+/// Parse and validate OpenWrt sysupgrade tar archives
+pub fn sysupgrade_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Successful return value; takes precedence over the generic tarball signature
+    let mut result = SignatureResult {
+        offset,
+        description: SYSUPGRADE_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    // Do a dry run to validate the archive
+    let dry_run = extract_sysupgrade_images(file_data, offset, None);
+
+    if dry_run.success {
+        if let Some(total_size) = dry_run.size {
+            if let Ok(sysupgrade_image) = parse_sysupgrade_tar(&file_data[offset..]) {
+                result.size = total_size;
+                result.description = format!(
+                    "{}, board: {}",
+                    result.description,
+                    escape_non_printable(&sysupgrade_image.board)
+                );
+
+                for (name, description) in [("kernel", "kernel"), ("root", "rootfs")] {
+                    if let Some(entry) = sysupgrade_image.entry(name) {
+                        result.description = format!(
+                            "{}, {} size: {} bytes",
+                            result.description, description, entry.size
+                        );
+                    }
+                }
+
+                result.description =
+                    format!("{}, total size: {} bytes", result.description, result.size);
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Human readable description
+pub const METADATA_DESCRIPTION: &str = "OpenWrt firmware metadata";
+
+/// Human readable description
+pub const SIGNATURE_DESCRIPTION: &str = "OpenWrt firmware signature";
+
+/// fwtool trailer magic bytes; the trailer is at the end of the metadata or signature block it describes
+pub fn fwimage_magic() -> Vec<Vec<u8>> {
+    vec![b"FWx0".to_vec()]
+}
+
+/// Parse and validate fwtool metadata and signature blocks
+pub fn fwimage_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        description: SIGNATURE_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    if let Ok(trailer) = parse_fwimage_trailer(&file_data[offset..]) {
+        let block_end = offset + FWIMAGE_TRAILER_SIZE;
+
+        // The trailer size includes the block data that precedes it
+        if let Some(block_start) = block_end.checked_sub(trailer.size) {
+            result.offset = block_start;
+            result.size = trailer.size;
+
+            if trailer.trailer_type != FWIMAGE_INFO {
+                result.description = format!(
+                    "{}, total size: {} bytes, CRC32: {:#010X}",
+                    result.description, result.size, trailer.crc32
+                );
+                return Ok(result);
+            }
+
+            // Metadata blocks are JSON objects
+            if let Ok(Value::Object(metadata)) =
+                serde_json::from_slice(&file_data[block_start..offset])
+            {
+                result.confidence = CONFIDENCE_HIGH;
+                result.description = METADATA_DESCRIPTION.to_string();
+
+                if let Some(Value::Array(devices)) = metadata.get("supported_devices") {
+                    let devices: Vec<&str> = devices.iter().filter_map(Value::as_str).collect();
+                    result.description = format!(
+                        "{}, supported devices: {}",
+                        result.description,
+                        escape_non_printable(&devices.join(", "))
+                    );
+                }
+
+                if let Some(compat_version) = metadata.get("compat_version").and_then(Value::as_str)
+                {
+                    result.description = format!(
+                        "{}, compat version: {}",
+                        result.description,
+                        escape_non_printable(compat_version)
+                    );
+                }
+
+                // Build information, e.g., {"dist": "OpenWrt", "version": "23.05.0", "revision": "r23497-6637af95aa", "target": "ath79/generic", "board": "tplink_archer-c7-v2"}
+                if let Some(Value::Object(version)) = metadata.get("version") {
+                    let build: Vec<&str> = ["dist", "version", "revision"]
+                        .iter()
+                        .filter_map(|key| version.get(*key).and_then(Value::as_str))
+                        .collect();
+
+                    if !build.is_empty() {
+                        result.description = format!(
+                            "{}, version: {}",
+                            result.description,
+                            escape_non_printable(&build.join(" "))
+                        );
+                    }
+
+                    for key in ["target", "board"] {
+                        if let Some(value) = version.get(key).and_then(Value::as_str) {
+                            result.description = format!(
+                                "{}, {}: {}",
+                                result.description,
+                                key,
+                                escape_non_printable(value)
+                            );
+                        }
+                    }
+                }
+
+                result.description = format!(
+                    "{}, total size: {} bytes, CRC32: {:#010X}",
+                    result.description, result.size, trailer.crc32
+                );
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
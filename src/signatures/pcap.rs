@@ -1,14 +1,25 @@
-use crate::extractors::pcap::pcapng_carver;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::structures::pcap::{PcapCapture, parse_pcap_capture, parse_pcapng_capture};
 
-/// Human readable description
+/// Human readable descriptions
 pub const PCAPNG_DESCRIPTION: &str = "Pcap-NG capture file";
+pub const PCAP_DESCRIPTION: &str = "Pcap capture file";
 
 /// Pcap-NG files always start with these bytes
 pub fn pcapng_magic() -> Vec<Vec<u8>> {
     vec![b"\x0A\x0D\x0D\x0A".to_vec()]
 }
 
+/// Pcap files start with a microsecond or nanosecond timestamp magic, followed by version 2.4, in either byte order
+pub fn pcap_magic() -> Vec<Vec<u8>> {
+    vec![
+        b"\xD4\xC3\xB2\xA1\x02\x00\x04\x00".to_vec(),
+        b"\xA1\xB2\xC3\xD4\x00\x02\x00\x04".to_vec(),
+        b"\x4D\x3C\xB2\xA1\x02\x00\x04\x00".to_vec(),
+        b"\xA1\xB2\x3C\x4D\x00\x02\x00\x04".to_vec(),
+    ]
+}
+
 /// Parses and validates the Pcap-NG file
 pub fn pcapng_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
     // Successful return value
@@ -19,24 +30,61 @@ pub fn pcapng_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult,
         ..Default::default()
     };
 
-    // Do an extraction dry-run
-    let dry_run = pcapng_carver(file_data, offset, None);
-
-    // If dry-run was successful, this is almost certianly a valid pcap-ng file
-    if dry_run.success {
-        if let Some(pcap_size) = dry_run.size {
-            // If this file is just a pcap file, no need to carve it out to yet another file on disk
-            if offset == 0 && pcap_size == file_data.len() {
-                result.extraction_declined = true;
-            }
-
-            // Return parser results
-            result.size = pcap_size;
-            result.description =
-                format!("{}, total size: {} bytes", result.description, result.size);
-            return Ok(result);
+    // If all the blocks parse, this is almost certianly a valid pcap-ng file
+    if let Ok(capture) = parse_pcapng_capture(&file_data[offset..]) {
+        // If this file is just a pcap file, no need to carve it out to yet another file on disk
+        if offset == 0 && capture.size == file_data.len() {
+            result.extraction_declined = true;
+        }
+
+        // Return parser results
+        result.size = capture.size;
+        result.description = format!("{}, {}", result.description, describe(&capture));
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Parses and validates a libpcap file
+pub fn pcap_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: PCAP_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    if let Ok(capture) = parse_pcap_capture(&file_data[offset..]) {
+        // If this file is just a pcap file, no need to carve it out to yet another file on disk
+        if offset == 0 && capture.size == file_data.len() {
+            result.extraction_declined = true;
         }
+
+        result.size = capture.size;
+        result.description = format!("{}, {}", result.description, describe(&capture));
+        return Ok(result);
     }
 
     Err(SignatureError)
 }
+
+/// Describes the link types and number of packets in a capture
+fn describe(capture: &PcapCapture) -> String {
+    let mut link_types = capture.link_types.clone();
+    link_types.dedup();
+
+    let label = match link_types.len() {
+        1 => "link type",
+        _ => "link types",
+    };
+
+    format!(
+        "{}: {}, packets: {}, total size: {} bytes",
+        label,
+        link_types.join(", "),
+        capture.packet_count,
+        capture.size
+    )
+}
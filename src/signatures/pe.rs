@@ -1,8 +1,12 @@
+use crate::common::epoch_to_string;
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
-use crate::structures::pe::parse_pe_header;
+use crate::structures::pe::{
+    PEHeader, parse_dotnet_header, parse_pe_header, parse_pe_optional_header,
+};
 
-/// Human readable description
+/// Human readable descriptions
 pub const DESCRIPTION: &str = "Windows PE binary";
+pub const DOTNET_DESCRIPTION: &str = ".NET assembly";
 
 /// Common PE file magics
 pub fn pe_magic() -> Vec<Vec<u8>> {
@@ -28,12 +32,67 @@ pub fn pe_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Sig
 
     // Parse the PE header
     if let Ok(pe_header) = parse_pe_header(&file_data[offset..]) {
+        // .NET assemblies are PE files with a CLR runtime header
+        if let Ok(dotnet_header) = parse_dotnet_header(&file_data[offset..], &pe_header) {
+            let code_type = match dotnet_header.il_only {
+                true => "managed code only",
+                false => "mixed mode",
+            };
+
+            result.description = format!(
+                "{}, runtime version: {}, CLR header version: {}, {}, machine type: {}{}",
+                DOTNET_DESCRIPTION,
+                dotnet_header.metadata_version,
+                dotnet_header.runtime_version,
+                code_type,
+                pe_header.machine,
+                image_details(&file_data[offset..], &pe_header)
+            );
+            return Ok(result);
+        }
+
         result.description = format!(
-            "{}, machine type: {}",
-            result.description, pe_header.machine
+            "{}, machine type: {}{}",
+            result.description,
+            pe_header.machine,
+            image_details(&file_data[offset..], &pe_header)
         );
         return Ok(result);
     }
 
     Err(SignatureError)
 }
+
+/// Describes the image format, type, subsystem and compile time of a PE file
+fn image_details(pe_data: &[u8], pe_header: &PEHeader) -> String {
+    const IMAGE_FILE_EXECUTABLE_IMAGE: usize = 0x0002;
+    const IMAGE_FILE_DLL: usize = 0x2000;
+
+    let mut details = "".to_string();
+
+    let image_type = if (pe_header.characteristics & IMAGE_FILE_DLL) != 0 {
+        "DLL"
+    } else if (pe_header.characteristics & IMAGE_FILE_EXECUTABLE_IMAGE) != 0 {
+        "executable"
+    } else {
+        "object"
+    };
+
+    if let Ok(optional_header) = parse_pe_optional_header(pe_data, pe_header) {
+        details = format!(
+            ", {} {}, subsystem: {}",
+            optional_header.format, image_type, optional_header.subsystem
+        );
+    }
+
+    // Some linkers zero the timestamp for reproducible builds
+    if pe_header.timestamp != 0 {
+        details = format!(
+            "{}, compiled: {}",
+            details,
+            epoch_to_string(pe_header.timestamp as u32)
+        );
+    }
+
+    details
+}
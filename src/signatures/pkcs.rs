@@ -0,0 +1,184 @@
+use crate::signatures::common::{
+    CONFIDENCE_HIGH, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
+};
+use crate::signatures::x509::certificate_description;
+use crate::structures::pkcs::{
+    parse_pkcs7, parse_pkcs12, parse_rsa_private_key, parse_rsa_public_key,
+};
+
+/// Human readable descriptions
+pub const PKCS7_DESCRIPTION: &str = "PKCS#7 container, DER encoded";
+pub const PKCS12_DESCRIPTION: &str = "PKCS#12 key store, DER encoded";
+pub const RSA_PRIVATE_KEY_DESCRIPTION: &str = "RSA private key, PKCS#1 DER encoded";
+pub const RSA_PUBLIC_KEY_DESCRIPTION: &str = "RSA public key, PKCS#1 DER encoded";
+
+/// PKCS#7 signedData content type OID, followed by the explicitly tagged content
+pub fn pkcs7_magic() -> Vec<Vec<u8>> {
+    vec![b"\x06\x09\x2A\x86\x48\x86\xF7\x0D\x01\x07\x02\xA0".to_vec()]
+}
+
+/// PKCS#12 PFX version 3, followed by the authenticated safe ContentInfo sequence
+pub fn pkcs12_magic() -> Vec<Vec<u8>> {
+    vec![
+        b"\x02\x01\x03\x30\x82".to_vec(),
+        b"\x02\x01\x03\x30\x83".to_vec(),
+    ]
+}
+
+/// RSA private keys start with version 0, followed by the modulus
+pub fn rsa_private_key_magic() -> Vec<Vec<u8>> {
+    vec![
+        // 1024 bit modulus
+        b"\x02\x01\x00\x02\x81\x81\x00".to_vec(),
+        // 2048 bit modulus
+        b"\x02\x01\x00\x02\x82\x01\x01\x00".to_vec(),
+        // 3072 bit modulus
+        b"\x02\x01\x00\x02\x82\x01\x81\x00".to_vec(),
+        // 4096 bit modulus
+        b"\x02\x01\x00\x02\x82\x02\x01\x00".to_vec(),
+    ]
+}
+
+/// RSA public keys start with the modulus
+pub fn rsa_public_key_magic() -> Vec<Vec<u8>> {
+    vec![
+        // 1024 bit modulus
+        b"\x30\x81\x89\x02\x81\x81\x00".to_vec(),
+        // 2048 bit modulus
+        b"\x30\x82\x01\x0A\x02\x82\x01\x01\x00".to_vec(),
+        // 3072 bit modulus
+        b"\x30\x82\x01\x8A\x02\x82\x01\x81\x00".to_vec(),
+        // 4096 bit modulus
+        b"\x30\x82\x02\x0A\x02\x82\x02\x01\x00".to_vec(),
+    ]
+}
+
+/// Returns the possible start offsets of a DER sequence whose contents start at the magic offset
+fn sequence_starts(offset: usize) -> Vec<usize> {
+    // Sequence headers are 2 to 5 bytes long
+    (2..=5)
+        .rev()
+        .filter_map(|header_size| offset.checked_sub(header_size))
+        .collect()
+}
+
+/// Validates PKCS#7 signedData containers, and describes the certificates they contain
+pub fn pkcs7_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        description: PKCS7_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    for pkcs7_start in sequence_starts(offset) {
+        if let Ok(pkcs7) = parse_pkcs7(&file_data[pkcs7_start..]) {
+            result.offset = pkcs7_start;
+            result.size = pkcs7.size;
+            result.description = format!(
+                "{}, content type: {}, certificates: {}",
+                result.description,
+                pkcs7.content_type,
+                pkcs7.certificates.len()
+            );
+
+            for (i, certificate) in pkcs7.certificates.iter().enumerate() {
+                result.description = format!(
+                    "{}, certificate {}: [{}]",
+                    result.description,
+                    i + 1,
+                    certificate_description(certificate)
+                );
+            }
+
+            result.description =
+                format!("{}, total size: {} bytes", result.description, result.size);
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates PKCS#12 key stores
+pub fn pkcs12_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        description: PKCS12_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    for pkcs12_start in sequence_starts(offset) {
+        if let Ok(pkcs12) = parse_pkcs12(&file_data[pkcs12_start..]) {
+            result.offset = pkcs12_start;
+            result.size = pkcs12.size;
+            result.description = format!(
+                "{}, content type: {}",
+                result.description, pkcs12.content_type
+            );
+
+            result.description = match pkcs12.mac_algorithm {
+                Some(mac_algorithm) => format!(
+                    "{}, MAC algorithm: {}, MAC iterations: {}",
+                    result.description, mac_algorithm, pkcs12.mac_iterations
+                ),
+                None => format!("{}, no MAC", result.description),
+            };
+
+            result.description =
+                format!("{}, total size: {} bytes", result.description, result.size);
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates PKCS#1 RSA private keys
+pub fn rsa_private_key_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        description: RSA_PRIVATE_KEY_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    for key_start in sequence_starts(offset) {
+        if let Ok(rsa_key) = parse_rsa_private_key(&file_data[key_start..]) {
+            result.offset = key_start;
+            result.size = rsa_key.size;
+            result.description = format!(
+                "{}, modulus: {} bits, public exponent: {}, total size: {} bytes",
+                result.description, rsa_key.modulus_bits, rsa_key.public_exponent, result.size
+            );
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates PKCS#1 RSA public keys
+pub fn rsa_public_key_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        offset,
+        description: RSA_PUBLIC_KEY_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    if let Ok(rsa_key) = parse_rsa_public_key(&file_data[offset..]) {
+        result.size = rsa_key.size;
+        result.description = format!(
+            "{}, modulus: {} bits, public exponent: {}, total size: {} bytes",
+            result.description, rsa_key.modulus_bits, rsa_key.public_exponent, result.size
+        );
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
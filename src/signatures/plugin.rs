@@ -0,0 +1,436 @@
+//! Support for user-defined signatures, loaded at runtime from TOML or JSON files.
+//!
+//! Each file in the plugin directory defines a single signature. For example, `acme.toml`:
+//!
+//! ```toml
+//! name = "acme_firmware"
+//! description = "ACME firmware image"
+//! # Magic bytes, hex encoded
+//! magic = ["41434d4546570100"]
+//! confidence = "medium"
+//!
+//! # Optional: the total size of the signature data is read from the header
+//! [size_field]
+//! offset = 8
+//! type = "u32"
+//! endianness = "big"
+//! # Value added to the size field; here, the size field does not include the 16-byte header
+//! adjust = 16
+//!
+//! # Optional: an external utility to extract the data with
+//! [extractor]
+//! command = "acme-unpack"
+//! arguments = ["-x", "%e"]
+//! extension = "bin"
+//! ```
+//!
+//! Alternatively, data may be extracted by a sandboxed WebAssembly module (see `extractors::wasm_plugin`),
+//! specified relative to the plugin directory:
+//!
+//! ```toml
+//! [extractor]
+//! wasm = "acme-unpack.wasm"
+//! ```
+//!
+//! or by one of binwalk's own extractor definitions, by name (see `builtin_extractor`):
+//!
+//! ```toml
+//! [extractor]
+//! builtin = "sevenzip"
+//! ```
+use crate::extractors;
+use crate::extractors::common::{Extractor, ExtractorType};
+use crate::signatures::common::{
+    CONFIDENCE_HIGH, CONFIDENCE_LOW, CONFIDENCE_MEDIUM, Signature, SignatureError, SignatureResult,
+};
+use crate::structures::common;
+use log::{debug, error, warn};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+
+/// All loaded plugin signatures; consulted by the plugin signature parser
+static PLUGIN_SIGNATURES: RwLock<Vec<PluginSignature>> = RwLock::new(Vec::new());
+
+/// Returned when the plugin directory can not be loaded
+#[derive(Debug, Default, Clone)]
+pub struct PluginError {
+    pub message: String,
+}
+
+/// Describes the location and format of a size field in the signature's header
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginSizeField {
+    /// Offset of the size field, relative to the start of the signature
+    pub offset: usize,
+    /// One of "u8", "u16", "u24", "u32", "u64"
+    #[serde(rename = "type", default = "default_size_type")]
+    pub size_type: String,
+    /// One of "little", "big"
+    #[serde(default = "default_endianness")]
+    pub endianness: String,
+    /// Value added to the size field to get the total size of the signature data
+    #[serde(default)]
+    pub adjust: i64,
+}
+
+/// Describes an external extraction utility, or WebAssembly extractor module, for a plugin signature
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginExtractor {
+    #[serde(default)]
+    pub command: String,
+    /// Path to a WebAssembly extractor module; mutually exclusive with `command` and `builtin`
+    #[serde(default)]
+    pub wasm: String,
+    /// Name of a built-in extractor definition; mutually exclusive with `command` and `wasm`
+    #[serde(default)]
+    pub builtin: String,
+    /// Command line arguments; the placeholder `%e` is replaced with the path to the carved data
+    #[serde(default)]
+    pub arguments: Vec<String>,
+    /// File extension expected by the command
+    #[serde(default)]
+    pub extension: String,
+    /// Successful exit codes for the command; defaults to 0
+    #[serde(default)]
+    pub exit_codes: Vec<i32>,
+}
+
+/// A user-defined signature definition, as read from a plugin file
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginSignature {
+    /// Unique name for the signature (no whitespace)
+    pub name: String,
+    /// Human readable description of the signature
+    pub description: String,
+    /// Hex encoded magic bytes
+    pub magic: Vec<String>,
+    /// Offset of the magic bytes from the start of the signature
+    #[serde(default)]
+    pub magic_offset: usize,
+    /// If true, the signature is only matched at the beginning of a file; when searching the entire file, matches
+    /// anywhere else are reported with low confidence
+    #[serde(default)]
+    pub short: bool,
+    #[serde(default)]
+    pub always_display: bool,
+    /// One of "low", "medium", "high"
+    #[serde(default = "default_confidence")]
+    pub confidence: String,
+    /// Fixed size of the signature data, if known
+    pub size: Option<usize>,
+    /// Size field to read the signature data size from, if any; takes precedence over `size`
+    pub size_field: Option<PluginSizeField>,
+    pub extractor: Option<PluginExtractor>,
+    /// Decoded magic bytes
+    #[serde(skip)]
+    magic_bytes: Vec<Vec<u8>>,
+}
+
+fn default_size_type() -> String {
+    "u32".to_string()
+}
+
+fn default_endianness() -> String {
+    "little".to_string()
+}
+
+fn default_confidence() -> String {
+    "medium".to_string()
+}
+
+/// Loads all `.toml` and `.json` signature definitions from the specified directory.
+///
+/// Loaded signatures are returned as `Signature` structures, suitable for passing to `Binwalk::configure`.
+/// Invalid plugin files are logged and skipped.
+pub fn load_plugins(plugin_directory: &str) -> Result<Vec<Signature>, PluginError> {
+    let mut signatures: Vec<Signature> = vec![];
+
+    let mut plugin_files: Vec<_> = match fs::read_dir(plugin_directory) {
+        Err(e) => {
+            return Err(PluginError {
+                message: format!("Failed to read plugin directory '{plugin_directory}': {e}"),
+            });
+        }
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect(),
+    };
+
+    // Load plugins in a predictable order
+    plugin_files.sort();
+
+    for plugin_file in plugin_files {
+        match load_plugin_file(&plugin_file) {
+            Err(e) => {
+                error!(
+                    "Failed to load plugin {}: {}",
+                    plugin_file.display(),
+                    e.message
+                );
+            }
+            Ok(None) => {
+                debug!("Skipping non-plugin file {}", plugin_file.display());
+            }
+            Ok(Some(plugin)) => {
+                debug!(
+                    "Loaded plugin signature {} from {}",
+                    plugin.name,
+                    plugin_file.display()
+                );
+                signatures.push(register_plugin(plugin));
+            }
+        }
+    }
+
+    Ok(signatures)
+}
+
+/// Loads and validates a single plugin file; returns None if the file is not a plugin file
+fn load_plugin_file(plugin_file: &Path) -> Result<Option<PluginSignature>, PluginError> {
+    let extension = plugin_file
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+
+    if !plugin_file.is_file() || !["toml", "json"].contains(&extension) {
+        return Ok(None);
+    }
+
+    let plugin_data = fs::read_to_string(plugin_file).map_err(|e| PluginError {
+        message: e.to_string(),
+    })?;
+
+    let plugin: PluginSignature = match extension {
+        "toml" => toml::from_str(&plugin_data).map_err(|e| PluginError {
+            message: e.to_string(),
+        })?,
+        _ => serde_json::from_str(&plugin_data).map_err(|e| PluginError {
+            message: e.to_string(),
+        })?,
+    };
+
+    let plugin_directory = plugin_file.parent().unwrap_or(Path::new(""));
+
+    validate_plugin(plugin, plugin_directory).map(Some)
+}
+
+/// Validates a plugin definition and decodes its magic bytes; WebAssembly extractor paths are resolved relative to `plugin_directory`
+pub fn validate_plugin(
+    mut plugin: PluginSignature,
+    plugin_directory: &Path,
+) -> Result<PluginSignature, PluginError> {
+    if plugin.name.is_empty() || plugin.name.contains(char::is_whitespace) {
+        return Err(PluginError {
+            message: format!("invalid signature name '{}'", plugin.name),
+        });
+    }
+
+    if !["low", "medium", "high"].contains(&plugin.confidence.as_str()) {
+        return Err(PluginError {
+            message: format!("invalid confidence '{}'", plugin.confidence),
+        });
+    }
+
+    if let Some(size_field) = &plugin.size_field {
+        if !["u8", "u16", "u24", "u32", "u64"].contains(&size_field.size_type.as_str())
+            || !["little", "big"].contains(&size_field.endianness.as_str())
+        {
+            return Err(PluginError {
+                message: "invalid size field type or endianness".to_string(),
+            });
+        }
+    }
+
+    for magic in &plugin.magic {
+        match hex::decode(magic) {
+            Ok(magic_bytes) if !magic_bytes.is_empty() => plugin.magic_bytes.push(magic_bytes),
+            _ => {
+                return Err(PluginError {
+                    message: format!("invalid magic bytes '{magic}'"),
+                });
+            }
+        }
+    }
+
+    if plugin.magic_bytes.is_empty() {
+        return Err(PluginError {
+            message: "no magic bytes defined".to_string(),
+        });
+    }
+
+    if let Some(extractor) = &mut plugin.extractor {
+        let defined_count = [&extractor.command, &extractor.wasm, &extractor.builtin]
+            .iter()
+            .filter(|value| !value.is_empty())
+            .count();
+
+        if defined_count != 1 {
+            return Err(PluginError {
+                message: "extractor must define exactly one of 'command', 'wasm' or 'builtin'"
+                    .to_string(),
+            });
+        }
+
+        if !extractor.builtin.is_empty() && builtin_extractor(&extractor.builtin).is_none() {
+            return Err(PluginError {
+                message: format!("unknown built-in extractor '{}'", extractor.builtin),
+            });
+        }
+
+        // WebAssembly module paths are relative to the plugin directory
+        if !extractor.wasm.is_empty() {
+            let wasm_path = plugin_directory.join(&extractor.wasm);
+
+            if !wasm_path.is_file() {
+                return Err(PluginError {
+                    message: format!("WebAssembly extractor '{}' not found", wasm_path.display()),
+                });
+            }
+
+            extractor.wasm = wasm_path.display().to_string();
+        }
+    }
+
+    Ok(plugin)
+}
+
+/// Returns the built-in extractor definition with the specified name, for use by plugin signatures
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::signatures::plugin::builtin_extractor;
+///
+/// assert!(builtin_extractor("sevenzip").unwrap().reports_progress);
+/// assert!(builtin_extractor("no_such_extractor").is_none());
+/// ```
+pub fn builtin_extractor(name: &str) -> Option<Extractor> {
+    match name {
+        "sevenzip" => Some(extractors::sevenzip::sevenzip_extractor()),
+        _ => None,
+    }
+}
+
+/// Registers a validated plugin definition with the plugin signature parser, and returns its Signature definition.
+/// A definition with the same name and magic bytes as an already registered definition replaces it, so that loading
+/// the same plugins more than once does not register duplicate signatures.
+pub fn register_plugin(plugin: PluginSignature) -> Signature {
+    let signature = plugin_to_signature(&plugin);
+
+    match PLUGIN_SIGNATURES.write() {
+        Err(e) => error!("Failed to register plugin signature {}: {e}", plugin.name),
+        Ok(mut plugins) => {
+            match plugins.iter_mut().find(|registered| {
+                registered.name == plugin.name && registered.magic_bytes == plugin.magic_bytes
+            }) {
+                Some(registered) => *registered = plugin,
+                None => plugins.push(plugin),
+            }
+        }
+    }
+
+    signature
+}
+
+/// Converts a plugin definition into a Signature definition
+fn plugin_to_signature(plugin: &PluginSignature) -> Signature {
+    let extractor = plugin.extractor.as_ref().map(|plugin_extractor| {
+        match builtin_extractor(&plugin_extractor.builtin) {
+            Some(extractor) => extractor,
+            None => Extractor {
+                utility: match plugin_extractor.wasm.is_empty() {
+                    true => ExtractorType::External(plugin_extractor.command.clone()),
+                    false => ExtractorType::Wasm(plugin_extractor.wasm.clone()),
+                },
+                extension: plugin_extractor.extension.clone(),
+                arguments: plugin_extractor.arguments.clone(),
+                exit_codes: match plugin_extractor.exit_codes.is_empty() {
+                    true => vec![0],
+                    false => plugin_extractor.exit_codes.clone(),
+                },
+                ..Default::default()
+            },
+        }
+    });
+
+    Signature {
+        name: plugin.name.clone(),
+        short: plugin.short,
+        magic: plugin.magic_bytes.clone(),
+        magic_offset: plugin.magic_offset,
+        description: plugin.description.clone(),
+        always_display: plugin.always_display,
+        parser: plugin_parser,
+        extractor,
+    }
+}
+
+/// Generic parser for all plugin signatures
+pub fn plugin_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let plugins = PLUGIN_SIGNATURES.read().map_err(|_| SignatureError)?;
+
+    // Identify which plugin's magic bytes are at this offset; first loaded, first served
+    let plugin = plugins
+        .iter()
+        .find(|plugin| {
+            offset >= plugin.magic_offset
+                && plugin
+                    .magic_bytes
+                    .iter()
+                    .any(|magic| file_data[offset..].starts_with(magic))
+        })
+        .ok_or(SignatureError)?;
+
+    let mut result = SignatureResult {
+        offset: offset - plugin.magic_offset,
+        description: plugin.description.clone(),
+        confidence: match plugin.confidence.as_str() {
+            "high" => CONFIDENCE_HIGH,
+            "medium" => CONFIDENCE_MEDIUM,
+            _ => CONFIDENCE_LOW,
+        },
+        ..Default::default()
+    };
+
+    // Short magic bytes are only meaningful at the start of a file
+    if plugin.short && result.offset != 0 {
+        result.confidence = CONFIDENCE_LOW;
+    }
+
+    if let Some(size_field) = &plugin.size_field {
+        let size_structure = vec![("size", size_field.size_type.as_str())];
+        let size_data = file_data
+            .get(result.offset + size_field.offset..)
+            .ok_or(SignatureError)?;
+
+        let size_value = common::parse(size_data, &size_structure, &size_field.endianness)
+            .map_err(|_| SignatureError)?;
+
+        match usize::try_from(size_value["size"] as i64 + size_field.adjust) {
+            Ok(size) if size > 0 => result.size = size,
+            _ => {
+                warn!(
+                    "Plugin signature {} at offset {:#X} reported an invalid size",
+                    plugin.name, result.offset
+                );
+                return Err(SignatureError);
+            }
+        }
+    } else if let Some(size) = plugin.size {
+        result.size = size;
+    }
+
+    if result.size > 0 {
+        // Signature data must fit within the available file data
+        if result.offset + result.size > file_data.len() {
+            return Err(SignatureError);
+        }
+
+        result.description = format!("{}, total size: {} bytes", result.description, result.size);
+    }
+
+    Ok(result)
+}
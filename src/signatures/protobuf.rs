@@ -0,0 +1,103 @@
+use crate::common::is_text;
+use crate::signatures::common::{CONFIDENCE_LOW, SignatureError, SignatureResult};
+use crate::structures::protobuf::{
+    ProtobufField, WIRE_TYPE_I32, WIRE_TYPE_I64, WIRE_TYPE_VARINT, parse_protobuf_message,
+};
+
+/// Human readable description
+pub const DESCRIPTION: &str = "Protocol Buffers data";
+
+/// Protocol buffers have no magic bytes; messages typically start with field number 1, as either a varint or length delimited field
+pub fn protobuf_magic() -> Vec<Vec<u8>> {
+    vec![b"\x08".to_vec(), b"\x0A".to_vec()]
+}
+
+/// Heuristically validates serialized protocol buffer data
+pub fn protobuf_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Protobuf data has no magic bytes, so be conservative about what is reported
+    const MIN_MESSAGE_SIZE: usize = 16;
+    const MIN_FIELD_COUNT: usize = 3;
+    // Max number of fields to include in the structure dump
+    const MAX_DISPLAYED_FIELDS: usize = 16;
+
+    let mut result = SignatureResult {
+        offset,
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_LOW,
+        ..Default::default()
+    };
+
+    if let Ok(message) = parse_protobuf_message(&file_data[offset..]) {
+        let message_end = offset + message.size;
+        let message_data = &file_data[offset..message_end];
+        let trailing_data = &file_data[message_end..];
+
+        // The message must be followed by either EOF or padding, and plain text is not protobuf data
+        if message.size >= MIN_MESSAGE_SIZE
+            && message.fields.len() >= MIN_FIELD_COUNT
+            && (trailing_data.iter().all(|b| *b == 0) || trailing_data.iter().all(|b| *b == 0xFF))
+            && !message_data.iter().all(|b| is_text(*b))
+        {
+            let mut displayed_fields: usize = 0;
+
+            result.size = message.size;
+            result.description = format!(
+                "{}, {} top-level fields, structure: {}, total size: {} bytes",
+                result.description,
+                message.fields.len(),
+                protobuf_structure(&message.fields, MAX_DISPLAYED_FIELDS, &mut displayed_fields),
+                result.size
+            );
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Returns a best-effort, human readable dump of the structure of protobuf fields
+fn protobuf_structure(
+    fields: &[ProtobufField],
+    max_fields: usize,
+    displayed_fields: &mut usize,
+) -> String {
+    // Truncate long strings in the structure dump
+    const MAX_STRING_LENGTH: usize = 24;
+
+    let mut field_descriptions: Vec<String> = vec![];
+
+    for field in fields {
+        if *displayed_fields >= max_fields {
+            field_descriptions.push("...".to_string());
+            break;
+        }
+
+        *displayed_fields += 1;
+
+        let field_description = match field.wire_type {
+            WIRE_TYPE_VARINT => format!("varint {}", field.value),
+            WIRE_TYPE_I64 => format!("fixed64 {:#X}", field.value),
+            WIRE_TYPE_I32 => format!("fixed32 {:#X}", field.value),
+            _ => {
+                if let Some(text) = &field.text {
+                    let mut display_text: String = text.chars().take(MAX_STRING_LENGTH).collect();
+                    if display_text.len() < text.len() {
+                        display_text.push_str("...");
+                    }
+                    format!("string {:?}", display_text)
+                } else if !field.fields.is_empty() {
+                    format!(
+                        "message {{{}}}",
+                        protobuf_structure(&field.fields, max_fields, displayed_fields)
+                    )
+                } else {
+                    format!("bytes ({})", field.size)
+                }
+            }
+        };
+
+        field_descriptions.push(format!("{}: {}", field.field_number, field_description));
+    }
+
+    field_descriptions.join(", ")
+}
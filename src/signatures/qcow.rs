@@ -1,3 +1,4 @@
+use crate::locked::LockedContent;
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
 use crate::structures::qcow::parse_qcow_header;
 
@@ -24,6 +25,20 @@ pub fn qcow_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
             1 << qcow_header.cluster_block_bits,
             qcow_header.encryption_method
         );
+
+        // Encrypted images use either the legacy AES-CBC scheme, or a LUKS header
+        match qcow_header.encryption_method.as_str() {
+            "None" => (),
+            "LUKS" => result.locked.push(LockedContent::new(
+                "LUKS encrypted QCOW disk image",
+                "LUKS passphrase for the image's key slots",
+            )),
+            _ => result.locked.push(LockedContent::new(
+                "AES encrypted QCOW disk image",
+                "QCOW image passphrase",
+            )),
+        }
+
         return Ok(result);
     };
 
@@ -0,0 +1,172 @@
+use crate::common::escape_non_printable;
+use crate::extractors::qualcomm::{extract_mbn, extract_qualcomm_elf, parse_mbn_or_sbl_header};
+use crate::signatures::common::{
+    CONFIDENCE_HIGH, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
+};
+use crate::structures::qualcomm::{
+    MAX_IMAGE_ID, find_certificate_chain, image_id_name, parse_qualcomm_elf,
+};
+
+/// Human readable description
+pub const MBN_DESCRIPTION: &str = "Qualcomm MBN image";
+
+/// Legacy MBN headers start with the image ID, followed by header version 3
+pub fn mbn_magic() -> Vec<Vec<u8>> {
+    (1..=MAX_IMAGE_ID as u8)
+        .map(|image_id| vec![image_id, 0, 0, 0, 3, 0, 0, 0])
+        .collect()
+}
+
+/// Human readable description
+pub const SBL_DESCRIPTION: &str = "Qualcomm SBL image";
+
+/// SBL headers start with a codeword and magic number
+pub fn sbl_magic() -> Vec<Vec<u8>> {
+    vec![b"\xD1\xDC\x4B\x84\x34\x10\xD7\x73".to_vec()]
+}
+
+/// Validates Qualcomm MBN and SBL headers
+pub fn mbn_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        offset,
+        description: MBN_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    // Do a dry run to make sure the entire image is present
+    let dry_run = extract_mbn(file_data, offset, None);
+
+    if dry_run.success {
+        if let Some(total_size) = dry_run.size {
+            if let Some(mbn_header) = parse_mbn_or_sbl_header(&file_data[offset..]) {
+                if file_data[offset..].starts_with(&sbl_magic()[0]) {
+                    result.description = SBL_DESCRIPTION.to_string();
+                    result.confidence = CONFIDENCE_HIGH;
+                }
+
+                result.size = total_size;
+                result.description = format!(
+                    "{}, image ID: {} ({}), load address: {:#X}, code offset: {:#X}, code size: {} bytes, signature size: {} bytes",
+                    result.description,
+                    mbn_header.image_id,
+                    image_id_name(mbn_header.image_id),
+                    mbn_header.load_address,
+                    offset + mbn_header.header_size,
+                    mbn_header.code_size,
+                    mbn_header.signature_size
+                );
+
+                let cert_chain_start = offset + mbn_header.cert_chain_offset();
+                let cert_chain_data =
+                    &file_data[cert_chain_start..cert_chain_start + mbn_header.cert_chain_size];
+
+                result.description = format!(
+                    "{}{}, total size: {} bytes",
+                    result.description,
+                    describe_certificate_chain(cert_chain_data),
+                    result.size
+                );
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Human readable description
+pub const ELF_DESCRIPTION: &str = "Qualcomm signed ELF image";
+
+/// Signed images are ELF files
+pub fn qualcomm_elf_magic() -> Vec<Vec<u8>> {
+    vec![b"\x7FELF".to_vec()]
+}
+
+/// Validates ELF files with a Qualcomm hash segment; takes precedence over the generic ELF signature
+pub fn qualcomm_elf_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        offset,
+        description: ELF_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    // Do a dry run to validate the image
+    let dry_run = extract_qualcomm_elf(file_data, offset, None);
+
+    if !dry_run.success {
+        return Err(SignatureError);
+    }
+
+    if let Ok(image) = parse_qualcomm_elf(&file_data[offset..]) {
+        result.size = image.size;
+
+        if let Some(image_id) = image.image_id {
+            result.description = format!(
+                "{}, image ID: {} ({})",
+                result.description,
+                image_id,
+                image_id_name(image_id)
+            );
+        }
+
+        let segment_list: Vec<String> = image
+            .code_segments
+            .iter()
+            .map(|segment| {
+                format!(
+                    "{:#X} ({} bytes, address: {:#X})",
+                    offset + segment.offset,
+                    segment.file_size,
+                    segment.physical_address
+                )
+            })
+            .collect();
+
+        let hash_segment_start = offset + image.hash_segment.offset;
+        let hash_segment_data =
+            &file_data[hash_segment_start..hash_segment_start + image.hash_segment.file_size];
+
+        result.description = format!(
+            "{}, hash segment version: {}, hash segment offset: {:#X}, code segments: {}{}, total size: {} bytes",
+            result.description,
+            image.hash_version,
+            hash_segment_start,
+            segment_list.join(", "),
+            describe_certificate_chain(hash_segment_data),
+            result.size
+        );
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Describes the certificate chain in the provided data, if any, from the attestation certificate to the root
+fn describe_certificate_chain(data: &[u8]) -> String {
+    let certificates = find_certificate_chain(data);
+
+    if certificates.is_empty() {
+        return ", unsigned".to_string();
+    }
+
+    let common_names: Vec<String> = certificates
+        .iter()
+        .map(|certificate| escape_non_printable(&certificate.common_name))
+        .collect();
+
+    let mut description = format!(", certificate chain: {}", common_names.join(" -> "));
+
+    if let Some(sw_id) = certificates
+        .iter()
+        .find_map(|certificate| certificate.sw_id.clone())
+    {
+        description = format!("{description}, SW ID: {sw_id}");
+    }
+
+    description
+}
@@ -1,5 +1,9 @@
-use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
-use crate::structures::rar::parse_rar_archive_header;
+use crate::extractors::rar::rar_store_extractor;
+use crate::locked::LockedContent;
+use crate::signatures::common::{
+    CONFIDENCE_HIGH, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
+};
+use crate::structures::rar::{parse_rar_archive, parse_rar_archive_header};
 use aho_corasick::AhoCorasick;
 use std::collections::HashMap;
 
@@ -22,6 +26,48 @@ pub fn rar_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
 
     let mut extra_description: String = "".to_string();
 
+    // Walk all of the archive's headers; this fails for archives with encrypted headers
+    if let Ok(archive) = parse_rar_archive(&file_data[offset..]) {
+        result.size = archive.size;
+        result.confidence = CONFIDENCE_HIGH;
+
+        if archive.solid {
+            extra_description = ", solid".to_string();
+        }
+
+        let encrypted_entries: Vec<String> = archive
+            .entries
+            .iter()
+            .filter(|entry| entry.encrypted)
+            .map(|entry| entry.name.clone())
+            .collect();
+
+        // Encrypted entries can only be extracted with the archive password
+        if !encrypted_entries.is_empty() {
+            extra_description = format!("{extra_description}, encrypted");
+            result.locked.push(LockedContent::encrypted_entries(
+                "RAR",
+                &encrypted_entries,
+                "RAR password",
+            ));
+        }
+
+        // Archives containing only stored entries are extracted internally; anything else requires unrar
+        if archive.is_stored() {
+            result.preferred_extractor = Some(rar_store_extractor());
+        }
+
+        result.description = format!(
+            "{}, version: {}, {} entries{}, total size: {} bytes",
+            result.description,
+            archive.version,
+            archive.entries.len(),
+            extra_description,
+            result.size
+        );
+        return Ok(result);
+    }
+
     // Parse the archive header
     if let Ok(rar_header) = parse_rar_archive_header(&file_data[offset..]) {
         // Try to locate the RAR end-of-file marker
@@ -2,7 +2,7 @@ use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResu
 use crate::structures::riff::parse_riff_header;
 
 /// Human readable description
-pub const DESCRIPTION: &str = "RIFF image";
+pub const DESCRIPTION: &str = "RIFF file";
 
 /// RIFF file magic bytes
 pub fn riff_magic() -> Vec<Vec<u8>> {
@@ -26,10 +26,18 @@ pub fn riff_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
             result.extraction_declined = true;
         }
 
+        // Identify common RIFF media types
+        let media_type = match riff_header.chunk_type.as_str() {
+            "WAVE" => " (audio)",
+            "AVI" => " (video)",
+            "WEBP" => " (image)",
+            _ => "",
+        };
+
         result.size = riff_header.size;
         result.description = format!(
-            "{}, encoding type: {}, total size: {} bytes",
-            result.description, riff_header.chunk_type, result.size
+            "{}, encoding type: {}{}, total size: {} bytes",
+            result.description, riff_header.chunk_type, media_type, result.size
         );
         return Ok(result);
     }
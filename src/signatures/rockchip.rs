@@ -0,0 +1,146 @@
+use crate::common::escape_non_printable;
+use crate::extractors::rockchip::{extract_rkaf, extract_rkboot, extract_rkfw};
+use crate::signatures::common::{
+    CONFIDENCE_HIGH, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
+};
+use crate::structures::rockchip::{parse_rkaf_header, parse_rkboot_header, parse_rkfw_header};
+
+/// Human readable description
+pub const RKFW_DESCRIPTION: &str = "Rockchip RKFW firmware";
+
+/// RKFW magic bytes, followed by the header size
+pub fn rkfw_magic() -> Vec<Vec<u8>> {
+    vec![b"RKFW\x66\x00".to_vec()]
+}
+
+/// Validates Rockchip RKFW firmware headers
+pub fn rkfw_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // The boot loader and firmware package headers are validated too, so confidence is high
+    let mut result = SignatureResult {
+        offset,
+        description: RKFW_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    // Do a dry run to validate the boot loader and firmware package
+    let dry_run = extract_rkfw(file_data, offset, None);
+
+    if dry_run.success {
+        if let Some(total_size) = dry_run.size {
+            if let Ok(rkfw_header) = parse_rkfw_header(&file_data[offset..]) {
+                result.size = total_size;
+                result.description = format!(
+                    "{}, version: {}, release date: {}, chip: {:#X}, boot loader offset: {:#X}, boot loader size: {} bytes, firmware package offset: {:#X}, firmware package size: {} bytes, total size: {} bytes",
+                    result.description,
+                    rkfw_header.version,
+                    rkfw_header.release_date,
+                    rkfw_header.chip,
+                    offset + rkfw_header.loader_offset,
+                    rkfw_header.loader_size,
+                    offset + rkfw_header.image_offset,
+                    rkfw_header.image_size,
+                    result.size
+                );
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Human readable description
+pub const RKBOOT_DESCRIPTION: &str = "Rockchip boot loader";
+
+/// Boot loader magic bytes, followed by the header size
+pub fn rkboot_magic() -> Vec<Vec<u8>> {
+    vec![b"BOOT\x66\x00".to_vec()]
+}
+
+/// Validates Rockchip boot loader headers
+pub fn rkboot_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        offset,
+        description: RKBOOT_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    // Do a dry run to make sure all entries are present
+    let dry_run = extract_rkboot(file_data, offset, None);
+
+    if dry_run.success {
+        if let Some(total_size) = dry_run.size {
+            if let Ok(boot_header) = parse_rkboot_header(&file_data[offset..]) {
+                let entry_names: Vec<String> = boot_header
+                    .entries
+                    .iter()
+                    .map(|entry| escape_non_printable(&entry.name))
+                    .collect();
+
+                result.size = total_size;
+                result.description = format!(
+                    "{}, version: {}, release date: {}, chip: {:#X}, entries: {}, total size: {} bytes",
+                    result.description,
+                    boot_header.version,
+                    boot_header.release_date,
+                    boot_header.chip,
+                    entry_names.join(", "),
+                    result.size
+                );
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Human readable description
+pub const RKAF_DESCRIPTION: &str = "Rockchip RKAF firmware package";
+
+/// RKAF magic bytes
+pub fn rkaf_magic() -> Vec<Vec<u8>> {
+    vec![b"RKAF".to_vec()]
+}
+
+/// Validates Rockchip RKAF firmware package headers
+pub fn rkaf_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        offset,
+        description: RKAF_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    // Do a dry run to make sure the entire package is present
+    let dry_run = extract_rkaf(file_data, offset, None);
+
+    if dry_run.success {
+        if let Some(total_size) = dry_run.size {
+            if let Ok(rkaf_header) = parse_rkaf_header(&file_data[offset..]) {
+                let partition_names: Vec<String> = rkaf_header
+                    .partitions
+                    .iter()
+                    .filter(|partition| partition.size > 0)
+                    .map(|partition| escape_non_printable(&partition.name))
+                    .collect();
+
+                result.size = total_size;
+                result.description = format!(
+                    "{}, model: {}, manufacturer: {}, version: {}, partitions: {}, total size: {} bytes",
+                    result.description,
+                    escape_non_printable(&rkaf_header.model),
+                    escape_non_printable(&rkaf_header.manufacturer),
+                    rkaf_header.version,
+                    partition_names.join(", "),
+                    result.size
+                );
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
@@ -0,0 +1,124 @@
+use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
+use crate::structures::script::{
+    parse_lua_bytecode_header, parse_pyc_header, parse_shebang_script, pyc_release_magic_numbers,
+};
+
+/// Human readable descriptions
+pub const LUA_DESCRIPTION: &str = "Lua bytecode";
+pub const PYC_DESCRIPTION: &str = "Python bytecode";
+pub const SHEBANG_DESCRIPTION: &str = "Script";
+
+/// Lua bytecode magic bytes
+pub fn lua_magic() -> Vec<Vec<u8>> {
+    vec![
+        b"\x1BLua\x51\x00".to_vec(),
+        b"\x1BLua\x52\x00".to_vec(),
+        b"\x1BLua\x53\x00".to_vec(),
+        b"\x1BLua\x54\x00".to_vec(),
+    ]
+}
+
+/// Python bytecode magic bytes; only the magic numbers of final Python releases are searched for
+pub fn pyc_magic() -> Vec<Vec<u8>> {
+    pyc_release_magic_numbers()
+        .iter()
+        .map(|magic| {
+            let mut magic_bytes = magic.to_le_bytes().to_vec();
+            magic_bytes.extend_from_slice(b"\r\n");
+            magic_bytes
+        })
+        .collect()
+}
+
+/// Shebang script magic bytes
+pub fn shebang_magic() -> Vec<Vec<u8>> {
+    vec![b"#!/".to_vec()]
+}
+
+/// Validates Lua bytecode headers
+pub fn lua_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: LUA_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    if let Ok(lua_header) = parse_lua_bytecode_header(&file_data[offset..]) {
+        result.description = format!(
+            "{}, Lua version: {}, {} endian, integer size: {}, number size: {}",
+            result.description,
+            lua_header.version,
+            lua_header.endianness,
+            lua_header.integer_size,
+            lua_header.number_size
+        );
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates compiled Python file headers
+pub fn pyc_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: PYC_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    if let Ok(pyc_header) = parse_pyc_header(&file_data[offset..]) {
+        let validation = match pyc_header.hash_based {
+            true => "hash-based",
+            false => "timestamp-based",
+        };
+
+        result.description = format!(
+            "{}, Python version: {}, {}",
+            result.description, pyc_header.version, validation
+        );
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Validates shebang lines at the start of scripts
+pub fn shebang_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: SHEBANG_DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    if let Ok(script) = parse_shebang_script(&file_data[offset..]) {
+        result.size = script.size;
+
+        let mut language = script.language.clone();
+        if !script.version.is_empty() {
+            language = format!("{} {}", language, script.version);
+        }
+
+        // For scripts invoked via env, note which program env runs
+        let mut interpreter = script.interpreter.clone();
+        if !interpreter.ends_with(&format!("/{}", script.interpreter_name)) {
+            interpreter = format!("{} {}", interpreter, script.interpreter_name);
+        }
+
+        result.description = format!(
+            "{} {}, interpreter: {}, total size: {} bytes",
+            language,
+            result.description.to_lowercase(),
+            interpreter,
+            result.size
+        );
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
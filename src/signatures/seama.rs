@@ -1,9 +1,14 @@
-use crate::signatures::common::{CONFIDENCE_LOW, SignatureError, SignatureResult};
+use crate::common::escape_non_printable;
+use crate::extractors::seama::extract_seama_payload;
+use crate::signatures::common::{CONFIDENCE_HIGH, CONFIDENCE_LOW, SignatureError, SignatureResult};
 use crate::structures::seama::parse_seama_header;
 
 /// Human readable description
 pub const DESCRIPTION: &str = "SEAMA firmware header";
 
+/// Human readable description of SEAMA headers that seal another SEAMA image
+pub const SEAL_DESCRIPTION: &str = "SEAMA-SEAL firmware header";
+
 /// SEAMA magic bytes, big and little endian
 pub fn seama_magic() -> Vec<Vec<u8>> {
     vec![
@@ -29,10 +34,45 @@ pub fn seama_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult,
         // Sanity check the reported size
         if file_data.len() >= (offset + total_size) {
             result.size = seama_header.header_size;
+
+            // Do a dry run to validate the payload against the header's MD5 digest, if any
+            if seama_header.data_size > 0 {
+                let dry_run = extract_seama_payload(file_data, offset, None);
+
+                match dry_run.size {
+                    Some(seama_size) if dry_run.success => result.size = seama_size,
+                    _ => return Err(SignatureError),
+                }
+
+                if seama_header.digest.is_some() {
+                    result.confidence = CONFIDENCE_HIGH;
+                }
+            }
+
+            // Sealed images wrap a SEAMA image in a SEAMA header with a signature meta data entry
+            if seama_header.metadata_value("signature").is_some()
+                && parse_seama_header(&file_data[offset + seama_header.header_size..]).is_ok()
+            {
+                result.description = SEAL_DESCRIPTION.to_string();
+            }
+
+            if !seama_header.metadata.is_empty() {
+                result.description = format!(
+                    "{}, metadata: {}",
+                    result.description,
+                    escape_non_printable(&seama_header.metadata.join(", "))
+                );
+            }
+
             result.description = format!(
                 "{}, header size: {} bytes, data size: {} bytes",
                 result.description, seama_header.header_size, seama_header.data_size
             );
+
+            if seama_header.digest.is_some() && seama_header.data_size > 0 {
+                result.description = format!("{}, MD5: valid", result.description);
+            }
+
             return Ok(result);
         }
     }
@@ -1,4 +1,5 @@
 use crate::common::crc32;
+use crate::extractors::sevenzip::{is_internally_extractable, sevenzip_extractor};
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
 use crate::structures::sevenzip::parse_7z_header;
 
@@ -30,8 +31,7 @@ pub fn sevenzip_parser(file_data: &[u8], offset: usize) -> Result<SignatureResul
                     + sevenzip_header.next_header_offset
                     + sevenzip_header.next_header_size;
 
-                // Report signature result
-                return Ok(SignatureResult {
+                let mut result = SignatureResult {
                     offset,
                     size: total_size,
                     confidence: CONFIDENCE_HIGH,
@@ -43,7 +43,19 @@ pub fn sevenzip_parser(file_data: &[u8], offset: usize) -> Result<SignatureResul
                         total_size
                     ),
                     ..Default::default()
-                });
+                };
+
+                // The 7z utility supports more coders than the internal extractor, and may be able to at least partially extract malformed archives
+                if !is_internally_extractable(&file_data[offset..offset + total_size]) {
+                    result.preferred_extractor = Some(sevenzip_extractor());
+                    result.description = format!(
+                        "{}, coders not supported by the internal extractor, or malformed data",
+                        result.description
+                    );
+                }
+
+                // Report signature result
+                return Ok(result);
             }
         }
     }
@@ -1,3 +1,4 @@
+use crate::locked::LockedContent;
 use crate::signatures::common::{
     CONFIDENCE_LOW, CONFIDENCE_MEDIUM, SignatureError, SignatureResult,
 };
@@ -22,15 +23,19 @@ pub fn shrs_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
     };
 
     if let Ok(shrs_header) = parse_shrs_header(&file_data[offset..]) {
+        let iv = hex::encode(shrs_header.iv);
+
         result.size = shrs_header.header_size + shrs_header.data_size;
         result.description = format!(
             "{}, header size: {} bytes, encrypted data size: {} bytes, IV: {}",
-            result.description,
-            shrs_header.header_size,
-            shrs_header.data_size,
-            hex::encode(shrs_header.iv),
+            result.description, shrs_header.header_size, shrs_header.data_size, iv,
         );
 
+        result.locked.push(LockedContent::new(
+            "SHRS encrypted firmware data",
+            format!("SHRS firmware AES key (IV: {iv})"),
+        ));
+
         if offset == 0 {
             result.confidence = CONFIDENCE_MEDIUM;
         }
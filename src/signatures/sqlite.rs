@@ -0,0 +1,61 @@
+use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::structures::sqlite::{parse_sqlite_header, parse_sqlite_schema};
+
+/// Human readable description
+pub const DESCRIPTION: &str = "SQLite database";
+
+/// SQLite database magic bytes
+pub fn sqlite_magic() -> Vec<Vec<u8>> {
+    vec![b"SQLite format 3\x00".to_vec()]
+}
+
+/// Validates a SQLite database header
+pub fn sqlite_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    if let Ok(sqlite_header) = parse_sqlite_header(&file_data[offset..]) {
+        result.description = format!(
+            "{}, SQLite version: {}, page size: {}, text encoding: {}",
+            result.description,
+            sqlite_header.sqlite_version,
+            sqlite_header.page_size,
+            sqlite_header.text_encoding
+        );
+
+        if sqlite_header.wal_mode {
+            result.description = format!("{}, WAL mode", result.description);
+        }
+
+        if let Ok(schema) = parse_sqlite_schema(&file_data[offset..], &sqlite_header) {
+            let table_count = schema
+                .iter()
+                .filter(|entry| entry.entry_type == "table")
+                .count();
+            result.description = format!("{}, tables: {}", result.description, table_count);
+        }
+
+        // Older versions of SQLite did not maintain the page count; without it, the database size is unknown
+        match sqlite_header.page_count {
+            Some(page_count)
+                if page_count * sqlite_header.page_size <= file_data.len() - offset =>
+            {
+                result.size = page_count * sqlite_header.page_size;
+                result.description = format!(
+                    "{}, pages: {}, total size: {} bytes",
+                    result.description, page_count, result.size
+                );
+            }
+            _ => result.extraction_declined = true,
+        }
+
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
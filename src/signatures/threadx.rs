@@ -0,0 +1,90 @@
+use crate::common::get_cstring;
+use crate::signatures::common::{CONFIDENCE_LOW, SignatureError, SignatureResult};
+
+/// Human readable description
+pub const DESCRIPTION: &str = "ThreadX RTOS";
+
+/// ThreadX version banners start with a copyright notice
+pub const BANNER_START: &[u8] = b"Copyright";
+
+/// ThreadX version banner magic, as found in the _tx_version_id string compiled into every ThreadX kernel
+pub fn threadx_magic() -> Vec<Vec<u8>> {
+    /*
+     * Examples:
+     *
+     *  Copyright (c) 1996-2018 Express Logic Inc. * ThreadX Cortex-M4/GNU Version G5.8 SN: 3333-333-3333 *
+     *  Copyright (c) 2024 Microsoft Corporation. * ThreadX Cortex-M4/GNU Version 6.4.1 *
+     */
+    vec![b"* ThreadX ".to_vec(), b"*  ThreadX ".to_vec()]
+}
+
+/// Validates ThreadX version banners
+pub fn threadx_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // The copyright notice is not much longer than this
+    const MAX_COPYRIGHT_SIZE: usize = 128;
+    const VERSION_DELIM: &str = " Version ";
+
+    let mut result = SignatureResult {
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_LOW,
+        ..Default::default()
+    };
+
+    // Find the start of the copyright notice that precedes the magic bytes
+    let search_start = offset.saturating_sub(MAX_COPYRIGHT_SIZE);
+
+    if let Some(banner_start) = file_data[search_start..offset]
+        .windows(BANNER_START.len())
+        .rposition(|window| window == BANNER_START)
+        .map(|position| search_start + position)
+    {
+        let banner = get_cstring(&file_data[banner_start..]);
+
+        // The banner must be a single string that contains both the copyright notice and the magic bytes
+        if banner_start + banner.len() > offset {
+            let (copyright, kernel) = banner.split_at(offset - banner_start);
+
+            // e.g., "ThreadX Cortex-M4/GNU Version 6.4.1 *"
+            let kernel = kernel.trim_start_matches(['*', ' ']);
+
+            if let Some((port, version)) = kernel
+                .strip_prefix("ThreadX ")
+                .and_then(|kernel| kernel.split_once(VERSION_DELIM))
+            {
+                // The version is followed by the serial number, if any, and a closing asterisk
+                let version = version
+                    .split([' ', '*'])
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+
+                if !port.is_empty() && !version.is_empty() {
+                    // The vendor name follows the copyright year, e.g., "Copyright (c) 1996-2018 Express Logic Inc. "
+                    let vendor = copyright
+                        .rsplit(|c: char| c.is_ascii_digit() || c == ')')
+                        .next()
+                        .unwrap_or_default()
+                        .split(". ")
+                        .next()
+                        .unwrap_or_default()
+                        .trim_matches(['*', ' ', '.']);
+
+                    result.offset = banner_start;
+                    result.size = banner.len();
+                    result.description = format!(
+                        "{}, port: {}, version: {}",
+                        result.description, port, version
+                    );
+
+                    if !vendor.is_empty() {
+                        result.description = format!("{}, vendor: {}", result.description, vendor);
+                    }
+
+                    return Ok(result);
+                }
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
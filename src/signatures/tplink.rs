@@ -1,12 +1,18 @@
+use crate::extractors::tplink::extract_tplink_regions;
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
-use crate::structures::tplink::{parse_tplink_header, parse_tplink_rtos_header};
+use crate::structures::tplink::{parse_tplink_firmware_header, parse_tplink_rtos_header};
 
 /// Human readable description
 pub const DESCRIPTION: &str = "TP-Link firmware header";
 
-/// TP-Link firmware headers start with these bytes
+/// TP-Link firmware headers start with the header version, followed by the vendor name (version 1) or firmware version string (versions 2 and 3)
 pub fn tplink_magic() -> Vec<Vec<u8>> {
-    vec![b"\x01\x00\x00\x00TP-LINK Technologies\x00\x00\x00\x00ver. 1.0".to_vec()]
+    vec![
+        b"\x01\x00\x00\x00TP-LINK Technologies\x00\x00\x00\x00ver. 1.0".to_vec(),
+        b"\x00\x00\x00\x01TP-LINK Technologies\x00\x00\x00\x00ver. 1.0".to_vec(),
+        b"\x00\x00\x00\x02ver. ".to_vec(),
+        b"\x00\x00\x00\x03ver. ".to_vec(),
+    ]
 }
 
 /// Validates the TP-Link header
@@ -20,17 +26,60 @@ pub fn tplink_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult,
     };
 
     // Parse the header
-    if let Ok(tplink_header) = parse_tplink_header(&file_data[offset..]) {
-        // Fill in size and description
+    if let Ok(tplink_header) = parse_tplink_firmware_header(&file_data[offset..]) {
+        // Do a dry run to validate the flash layout against the available data
+        let dry_run = extract_tplink_regions(file_data, offset, None);
+
         result.size = tplink_header.header_size;
         result.description = format!(
-            "{}, kernel load address: {:#X}, kernel entry point: {:#X}, header size: {} bytes",
+            "{}, header version: {}, hardware ID: {:#010X}, hardware revision: {}",
             result.description,
-            tplink_header.kernel_load_address,
-            tplink_header.kernel_entry_point,
-            tplink_header.header_size
+            tplink_header.header_version,
+            tplink_header.hardware_id,
+            tplink_header.hardware_revision
         );
 
+        if tplink_header.region != 0 {
+            result.description =
+                format!("{}, region: {}", result.description, tplink_header.region);
+        }
+
+        if !tplink_header.firmware_version.is_empty() {
+            result.description = format!(
+                "{}, firmware version: {}",
+                result.description, tplink_header.firmware_version
+            );
+        }
+
+        result.description = format!(
+            "{}, kernel load address: {:#X}, kernel entry point: {:#X}",
+            result.description, tplink_header.kernel_load_address, tplink_header.kernel_entry_point
+        );
+
+        match (dry_run.success, dry_run.size) {
+            (true, Some(image_size)) => {
+                result.size = image_size;
+
+                for region in &tplink_header.regions {
+                    result.description = format!(
+                        "{}, {} offset: {:#X}, {} size: {} bytes",
+                        result.description, region.name, region.offset, region.name, region.size
+                    );
+                }
+
+                result.description =
+                    format!("{}, total size: {} bytes", result.description, result.size);
+            }
+            // Without a valid flash layout, only the header can be reported
+            _ => {
+                result.extraction_declined = true;
+                result.description = format!(
+                    "{}, header size: {} bytes",
+                    result.description, tplink_header.header_size
+                );
+            }
+        }
+
         return Ok(result);
     }
 
@@ -29,13 +29,27 @@ pub fn trx_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
             if let Ok(trx_header) = parse_trx_header(&file_data[offset..]) {
                 result.size = trx_total_size;
                 result.description = format!(
-                    "{}, version {}, partition count: {}, header size: {} bytes, total size: {} bytes",
+                    "{}, version {}, partition count: {}, header size: {} bytes",
                     result.description,
                     trx_header.version,
                     trx_header.partitions.len(),
                     trx_header.header_size,
-                    result.size
                 );
+
+                for (i, (partition_offset, partition_size)) in
+                    trx_header.partition_extents().into_iter().enumerate()
+                {
+                    result.description = format!(
+                        "{}, partition {}: offset {:#X}, size {} bytes",
+                        result.description,
+                        i,
+                        offset + partition_offset,
+                        partition_size
+                    );
+                }
+
+                result.description =
+                    format!("{}, total size: {} bytes", result.description, result.size);
                 return Ok(result);
             }
         }
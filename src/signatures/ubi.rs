@@ -1,3 +1,5 @@
+use crate::common::escape_non_printable;
+use crate::extractors::ubi::ubi_volumes;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
 use crate::structures::ubi::{
     parse_ubi_ec_header, parse_ubi_superblock_header, parse_ubi_volume_header,
@@ -60,10 +62,26 @@ pub fn ubi_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
             // Get the size of the UBI image
             if let Ok(image_size) = get_ubi_image_size(&file_data[offset..]) {
                 result.size = image_size;
-                result.description = format!(
-                    "{}, version: {}, image size: {} bytes",
-                    result.description, ubi_header.version, result.size
-                );
+                result.description =
+                    format!("{}, version: {}", result.description, ubi_header.version);
+
+                // Add volume info from the volume table to the description
+                let ubi_data = file_data
+                    .get(offset..offset + result.size)
+                    .unwrap_or(&file_data[offset..]);
+
+                for volume in ubi_volumes(ubi_data) {
+                    result.description = format!(
+                        "{}, volume: {}, type: {}, size: {} bytes",
+                        result.description,
+                        escape_non_printable(&volume.name),
+                        volume.volume_type,
+                        volume.size
+                    );
+                }
+
+                result.description =
+                    format!("{}, image size: {} bytes", result.description, result.size);
                 return Ok(result);
             }
         }
@@ -0,0 +1,58 @@
+use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::structures::udf::{UDF_VOLUME_RECOGNITION_OFFSET, parse_udf_volume};
+
+/// Human readable description
+pub const DESCRIPTION: &str = "UDF file system";
+
+/// UDF volume recognition sequences start with a beginning extended area descriptor
+pub fn udf_magic() -> Vec<Vec<u8>> {
+    vec![b"\x00BEA01\x01\x00".to_vec()]
+}
+
+/// Validates UDF volumes
+pub fn udf_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    let mut result = SignatureResult {
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    // The volume recognition sequence starts 32KB into the image; UDF images that are also ISO9660 images
+    // have their ISO9660 volume descriptors first, and are reported as ISO9660 images.
+    if offset >= UDF_VOLUME_RECOGNITION_OFFSET {
+        result.offset = offset - UDF_VOLUME_RECOGNITION_OFFSET;
+
+        let udf_data = &file_data[result.offset..];
+
+        if let Ok(udf_volume) = parse_udf_volume(udf_data) {
+            result.size = udf_volume.total_size;
+
+            if !udf_volume.logical_volume.volume_identifier.is_empty() {
+                result.description = format!(
+                    "{}, volume name: \"{}\"",
+                    result.description, udf_volume.logical_volume.volume_identifier
+                );
+            }
+
+            result.description = format!(
+                "{}, UDF version: {:x}.{:02x}, block size: {} bytes, total size: {} bytes",
+                result.description,
+                udf_volume.logical_volume.udf_revision >> 8,
+                udf_volume.logical_volume.udf_revision & 0xFF,
+                udf_volume.sector_size,
+                result.size
+            );
+
+            // Truncated images are reported up to the end of the available data
+            if result.size > udf_data.len() {
+                result.size = udf_data.len();
+                result.description =
+                    format!("{}, truncated to {} bytes", result.description, result.size);
+            }
+
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
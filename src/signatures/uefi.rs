@@ -44,10 +44,17 @@ pub fn uefi_volume_parser(
         if let Ok(uefi_volume_header) = parse_uefi_volume_header(&file_data[result.offset..]) {
             // Make sure the volume size is sane
             if file_data.len() >= (result.offset + uefi_volume_header.volume_size) {
+                // Identify the volume's file system by name if it is known, else by its GUID
+                let file_system = match uefi_volume_header.file_system.is_empty() {
+                    true => uefi_volume_header.file_system_guid.clone(),
+                    false => uefi_volume_header.file_system.clone(),
+                };
+
                 result.size = uefi_volume_header.volume_size;
                 result.description = format!(
-                    "{}, header CRC: {:#X}, header size: {} bytes, total size: {} bytes",
+                    "{}, file system: {}, header CRC: {:#X}, header size: {} bytes, total size: {} bytes",
                     result.description,
+                    file_system,
                     uefi_volume_header.header_crc as u32,
                     uefi_volume_header.header_size,
                     uefi_volume_header.volume_size
@@ -0,0 +1,70 @@
+use crate::extractors::wasm::extract_wasm_module;
+use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::structures::wasm::parse_wasm_module;
+
+/// Human readable description
+pub const DESCRIPTION: &str = "WebAssembly module";
+
+/// Maximum number of export names to list in the signature description
+const MAX_REPORTED_EXPORTS: usize = 16;
+
+/// WebAssembly magic bytes, plus version 1
+pub fn wasm_magic() -> Vec<Vec<u8>> {
+    vec![b"\x00asm\x01\x00\x00\x00".to_vec()]
+}
+
+/// Validates WebAssembly modules
+pub fn wasm_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        offset,
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    let dry_run = extract_wasm_module(file_data, offset, None);
+
+    if dry_run.success {
+        if let Ok(wasm_module) = parse_wasm_module(&file_data[offset..]) {
+            // No sense in extracting a module if the entire file is just the module itself
+            if offset == 0 && wasm_module.size == file_data.len() {
+                result.extraction_declined = true;
+            }
+
+            // Export names identify what a module does, but some modules export a great many things
+            let mut exports = wasm_module.export_count.to_string();
+
+            if !wasm_module.export_names.is_empty() {
+                let mut export_names: Vec<String> = wasm_module
+                    .export_names
+                    .iter()
+                    .take(MAX_REPORTED_EXPORTS)
+                    .cloned()
+                    .collect();
+
+                if wasm_module.export_names.len() > MAX_REPORTED_EXPORTS {
+                    export_names.push("...".to_string());
+                }
+
+                exports = format!("{} ({})", exports, export_names.join(", "));
+            }
+
+            result.size = wasm_module.size;
+            result.description = format!(
+                "{}, version: {}, {} sections ({}), imports: {}, exports: {}, functions: {}, total size: {} bytes",
+                result.description,
+                wasm_module.version,
+                wasm_module.section_count,
+                wasm_module.section_names.join(", "),
+                wasm_module.import_count,
+                exports,
+                wasm_module.function_count,
+                result.size
+            );
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
@@ -0,0 +1,62 @@
+use crate::extractors::inflate::inflate_preview;
+use crate::extractors::webasset::extract_minified_js;
+use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
+use crate::structures::webasset::{parse_minified_script, parse_web_asset_text};
+
+/// Human readable description
+pub const DESCRIPTION: &str = "Minified JavaScript";
+
+/// Code patterns commonly found at the start of minified JavaScript bundles
+pub fn minified_js_magic() -> Vec<Vec<u8>> {
+    vec![
+        b"!function(".to_vec(),
+        b"(window.webpackJsonp".to_vec(),
+        b"(self.webpackChunk".to_vec(),
+        b"\"use strict\";".to_vec(),
+    ]
+}
+
+/// Validates minified JavaScript
+pub fn minified_js_parser(
+    file_data: &[u8],
+    offset: usize,
+) -> Result<SignatureResult, SignatureError> {
+    // Successful return value
+    let mut result = SignatureResult {
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_MEDIUM,
+        ..Default::default()
+    };
+
+    // The magic bytes may be preceeded by other script data, such as license comments
+    if let Ok(script) = parse_minified_script(file_data, offset) {
+        let dry_run = extract_minified_js(file_data, script.offset, None);
+
+        if dry_run.success {
+            // No sense in extracting a script if the entire file is just the script itself
+            if script.offset == 0 && script.size == file_data.len() {
+                result.extraction_declined = true;
+            }
+
+            result.offset = script.offset;
+            result.size = script.size;
+            result.description =
+                format!("{}, total size: {} bytes", result.description, result.size);
+            return Ok(result);
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Web UI assets are frequently stored gzip compressed.
+/// Returns a description of the web asset contained in the deflate stream at the specified offset, if any.
+pub fn deflated_web_asset_description(file_data: &[u8], deflate_offset: usize) -> String {
+    // Only need enough decompressed data to identify the type of web asset
+    const PREVIEW_SIZE: usize = 256;
+
+    match parse_web_asset_text(&inflate_preview(file_data, deflate_offset, PREVIEW_SIZE)) {
+        Err(_) => "".to_string(),
+        Ok(asset_type) => format!(", contains {} web asset", asset_type.name()),
+    }
+}
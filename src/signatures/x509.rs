@@ -0,0 +1,66 @@
+use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::structures::x509::{X509Certificate, parse_x509_certificate};
+
+/// Human readable description
+pub const DESCRIPTION: &str = "X.509 certificate, DER encoded";
+
+/// Version 3 certificates start with an explicitly tagged version number, followed by the serial number
+pub fn x509_magic() -> Vec<Vec<u8>> {
+    vec![b"\xA0\x03\x02\x01\x02\x02".to_vec()]
+}
+
+/// Validates DER encoded X.509 certificates
+pub fn x509_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
+    // The magic bytes follow the certificate and TBSCertificate sequence headers, which are 3 or 4 bytes each
+    const MIN_MAGIC_OFFSET: usize = 6;
+    const MAX_MAGIC_OFFSET: usize = 8;
+
+    let mut result = SignatureResult {
+        description: DESCRIPTION.to_string(),
+        confidence: CONFIDENCE_HIGH,
+        ..Default::default()
+    };
+
+    for magic_offset in (MIN_MAGIC_OFFSET..=MAX_MAGIC_OFFSET).rev() {
+        if let Some(certificate_start) = offset.checked_sub(magic_offset) {
+            if let Ok(certificate) = parse_x509_certificate(&file_data[certificate_start..]) {
+                result.offset = certificate_start;
+                result.size = certificate.size;
+                result.description = format!(
+                    "{}, {}, total size: {} bytes",
+                    result.description,
+                    certificate_description(&certificate),
+                    result.size
+                );
+
+                if result.offset == 0 && result.size == file_data.len() {
+                    result.extraction_declined = true;
+                }
+
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(SignatureError)
+}
+
+/// Describes a certificate's subject, issuer, validity period and keys
+pub fn certificate_description(certificate: &X509Certificate) -> String {
+    let issuer = match certificate.is_self_signed() {
+        true => "self-signed".to_string(),
+        false => format!("issuer: {}", certificate.issuer),
+    };
+
+    format!(
+        "version: {}, subject: {}, {}, valid from {} to {}, serial number: 0x{}, signature algorithm: {}, public key: {}",
+        certificate.version,
+        certificate.subject,
+        issuer,
+        certificate.not_before,
+        certificate.not_after,
+        certificate.serial,
+        certificate.signature_algorithm,
+        certificate.public_key
+    )
+}
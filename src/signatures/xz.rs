@@ -2,14 +2,14 @@ use crate::common::is_offset_safe;
 use crate::extractors::lzma::lzma_decompress;
 use crate::extractors::sevenzip::sevenzip_extractor;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
-use crate::structures::xz::parse_xz_header;
+use crate::structures::xz::{XZ_MAGIC, parse_xz_header};
 
 /// Human readable description
 pub const DESCRIPTION: &str = "XZ compressed data";
 
 /// XZ magic bytes
 pub fn xz_magic() -> Vec<Vec<u8>> {
-    vec![b"\xFD\x37\x7a\x58\x5a\x00".to_vec()]
+    vec![XZ_MAGIC.to_vec()]
 }
 
 /// Validates XZ signatures
@@ -25,7 +25,7 @@ pub fn xz_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Sig
     let mut next_offset = offset;
     let mut previous_offset = None;
     let mut stream_header_count = 0;
-    let available_data = file_data.len() - offset;
+    let available_data = file_data.len();
 
     // XZ streams can be concatenated together, need to process them all to determine the size of an XZ file
     while is_offset_safe(available_data, next_offset, previous_offset) {
@@ -1,4 +1,5 @@
 use crate::common::is_offset_safe;
+use crate::extractors::yaffs2::extract_yaffs2;
 use crate::signatures::common::{CONFIDENCE_MEDIUM, SignatureError, SignatureResult};
 use crate::structures::yaffs::{parse_yaffs_file_header, parse_yaffs_obj_header};
 
@@ -48,9 +49,17 @@ pub fn yaffs_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult,
             // Deterine the chunk size
             if let Ok(spare_size) = get_spare_size(&file_data[offset..], page_size, endianness) {
                 // Get the total image size
-                if let Ok(image_size) =
+                if let Ok(mut image_size) =
                     get_image_size(&file_data[offset..], page_size, spare_size, endianness)
                 {
+                    // Object headers and file data are not necessarily contiguous; the extractor walks every chunk in the image
+                    let dry_run = extract_yaffs2(file_data, offset, None);
+                    if dry_run.success {
+                        if let Some(extracted_size) = dry_run.size {
+                            image_size = std::cmp::max(image_size, extracted_size);
+                        }
+                    }
+
                     result.size = image_size;
                     result.description = format!(
                         "{}, {} endian, page size: {}, spare size: {}, image size: {} bytes",
@@ -66,7 +75,7 @@ pub fn yaffs_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult,
 }
 
 /// Returns the detected page size used by the YAFFS image
-fn get_page_size(file_data: &[u8]) -> Result<usize, SignatureError> {
+pub fn get_page_size(file_data: &[u8]) -> Result<usize, SignatureError> {
     // Spare area is expected to start with these bytes, depending on endianess and ECC settings (YAFFS2 only)
     let spare_magics: Vec<Vec<u8>> = vec![
         b"\x00\x00\x10\x00".to_vec(),
@@ -100,7 +109,7 @@ fn get_page_size(file_data: &[u8]) -> Result<usize, SignatureError> {
 }
 
 /// Returns the detected spare size of the YAFFS image
-fn get_spare_size(
+pub fn get_spare_size(
     file_data: &[u8],
     page_size: usize,
     endianness: &str,
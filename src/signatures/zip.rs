@@ -1,7 +1,11 @@
 use crate::common::is_offset_safe;
+use crate::locked::LockedContent;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
-use crate::structures::zip::{parse_eocd_header, parse_zip_header};
+use crate::structures::java::{JarManifest, parse_jar_manifest};
+use crate::structures::zip::{parse_central_directory_entry, parse_eocd_header, parse_zip_header};
 use aho_corasick::AhoCorasick;
+use flate2::read::DeflateDecoder;
+use std::io::Read;
 
 /// Human readable description
 pub const DESCRIPTION: &str = "ZIP archive";
@@ -27,6 +31,46 @@ pub fn zip_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
         match find_zip_eof(file_data, offset) {
             Ok(zip_info) => {
                 result.size = zip_info.eof - offset;
+
+                // JAR files are ZIP archives with a Java manifest
+                if let Ok(jar_manifest) = find_jar_manifest(file_data, offset, &zip_info) {
+                    result.description = format!("{}, Java archive (JAR)", result.description);
+
+                    if !jar_manifest.created_by.is_empty() {
+                        result.description = format!(
+                            "{}, created by: {}",
+                            result.description, jar_manifest.created_by
+                        );
+                    }
+
+                    if !jar_manifest.build_jdk.is_empty() {
+                        result.description = format!(
+                            "{}, build JDK: {}",
+                            result.description, jar_manifest.build_jdk
+                        );
+                    }
+
+                    if !jar_manifest.main_class.is_empty() {
+                        result.description = format!(
+                            "{}, main class: {}",
+                            result.description, jar_manifest.main_class
+                        );
+                    }
+                }
+
+                // Java class files and Android DEX files are the code in JARs and APKs
+                let (class_count, dex_count) = count_code_entries(file_data, &zip_info);
+
+                if class_count > 0 {
+                    result.description =
+                        format!("{}, class files: {}", result.description, class_count);
+                }
+
+                if dex_count > 0 {
+                    result.description =
+                        format!("{}, DEX files: {}", result.description, dex_count);
+                }
+
                 result.description = format!(
                     "{}, version: {}.{}, file count: {}, total size: {} bytes",
                     result.description,
@@ -35,6 +79,12 @@ pub fn zip_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
                     zip_info.file_count,
                     result.size
                 );
+
+                // Encrypted entries can only be extracted with the archive password
+                if let Some(locked) = find_encrypted_entries(file_data, &zip_info) {
+                    result.description = format!("{}, encrypted", result.description);
+                    result.locked.push(locked);
+                }
             }
             // If the ZIP file is corrupted and no EOCD header exists, attempt to parse all the individual ZIP file headers
             Err(_) => {
@@ -80,6 +130,8 @@ pub fn zip_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, Si
 pub struct ZipEOCDInfo {
     pub eof: usize,
     pub file_count: usize,
+    /// Offset of the central directory in the file data
+    pub central_directory_offset: usize,
 }
 
 /// Need to grep the rest of the file data to locate the end-of-central-directory header, which tells us where the ZIP file ends.
@@ -98,10 +150,16 @@ pub fn find_zip_eof(file_data: &[u8], offset: usize) -> Result<ZipEOCDInfo, Sign
         // Parse the end-of-central-directory header
         if let Some(eocd_data) = file_data.get(eocd_start..) {
             if let Ok(eocd_header) = parse_eocd_header(eocd_data) {
-                return Ok(ZipEOCDInfo {
-                    eof: eocd_start + eocd_header.size,
-                    file_count: eocd_header.file_count,
-                });
+                // The central directory immediately precedes the EOCD header
+                if let Some(central_directory_offset) =
+                    eocd_start.checked_sub(eocd_header.central_directory_size)
+                {
+                    return Ok(ZipEOCDInfo {
+                        eof: eocd_start + eocd_header.size,
+                        file_count: eocd_header.file_count,
+                        central_directory_offset,
+                    });
+                }
             }
         }
     }
@@ -109,3 +167,134 @@ pub fn find_zip_eof(file_data: &[u8], offset: usize) -> Result<ZipEOCDInfo, Sign
     // No valid EOCD record found :(
     Err(SignatureError)
 }
+
+/// Searches the ZIP central directory for encrypted entries, returning a description of them if any are found
+fn find_encrypted_entries(file_data: &[u8], zip_info: &ZipEOCDInfo) -> Option<LockedContent> {
+    // WinZip AES encrypted entries report this compression method
+    const COMPRESSION_AES: usize = 99;
+
+    let mut encrypted_entries: Vec<String> = vec![];
+    let mut aes_encrypted: bool = false;
+    let mut entry_offset = zip_info.central_directory_offset;
+
+    for _ in 0..zip_info.file_count {
+        match file_data
+            .get(entry_offset..)
+            .and_then(|entry_data| parse_central_directory_entry(entry_data).ok())
+        {
+            None => break,
+            Some(entry) => {
+                entry_offset += entry.size;
+
+                if entry.encrypted {
+                    aes_encrypted |= entry.compression == COMPRESSION_AES;
+                    encrypted_entries.push(entry.file_name);
+                }
+            }
+        }
+    }
+
+    if encrypted_entries.is_empty() {
+        return None;
+    }
+
+    let key_material = match aes_encrypted {
+        true => "ZIP password (WinZip AES)",
+        false => "ZIP password (ZipCrypto)",
+    };
+
+    Some(LockedContent::encrypted_entries(
+        "ZIP",
+        &encrypted_entries,
+        key_material,
+    ))
+}
+
+/// Counts the Java class files and Android DEX files listed in the ZIP central directory
+fn count_code_entries(file_data: &[u8], zip_info: &ZipEOCDInfo) -> (usize, usize) {
+    let mut class_count: usize = 0;
+    let mut dex_count: usize = 0;
+    let mut entry_offset = zip_info.central_directory_offset;
+
+    for _ in 0..zip_info.file_count {
+        match file_data
+            .get(entry_offset..)
+            .and_then(|entry_data| parse_central_directory_entry(entry_data).ok())
+        {
+            None => break,
+            Some(entry) => {
+                entry_offset += entry.size;
+
+                if entry.file_name.ends_with(".class") {
+                    class_count += 1;
+                } else if entry.file_name.ends_with(".dex") {
+                    dex_count += 1;
+                }
+            }
+        }
+    }
+
+    (class_count, dex_count)
+}
+
+/// Searches the ZIP central directory for a Java manifest, and parses it if found.
+/// Returns an error if the ZIP archive does not contain a manifest.
+pub fn find_jar_manifest(
+    file_data: &[u8],
+    offset: usize,
+    zip_info: &ZipEOCDInfo,
+) -> Result<JarManifest, SignatureError> {
+    const MANIFEST_PATH: &str = "META-INF/MANIFEST.MF";
+    const COMPRESSION_STORED: usize = 0;
+    const COMPRESSION_DEFLATE: usize = 8;
+    const MAX_MANIFEST_SIZE: u64 = 64 * 1024;
+
+    let mut entry_offset = zip_info.central_directory_offset;
+
+    for _ in 0..zip_info.file_count {
+        let entry_data = file_data.get(entry_offset..).ok_or(SignatureError)?;
+        let entry = parse_central_directory_entry(entry_data).map_err(|_| SignatureError)?;
+        entry_offset += entry.size;
+
+        if entry.file_name != MANIFEST_PATH {
+            continue;
+        }
+
+        // The manifest is definitely present; if its contents can't be read, just report an empty manifest
+        let mut manifest = JarManifest {
+            ..Default::default()
+        };
+
+        let local_header_offset = offset + entry.local_header_offset;
+
+        if let Some(local_header_data) = file_data.get(local_header_offset..) {
+            if let Ok(local_header) = parse_zip_header(local_header_data) {
+                let manifest_start = local_header_offset + local_header.header_size;
+                let manifest_end = manifest_start + entry.compressed_size;
+
+                if let Some(manifest_data) = file_data.get(manifest_start..manifest_end) {
+                    let mut manifest_text: Vec<u8> = vec![];
+
+                    match entry.compression {
+                        COMPRESSION_STORED => manifest_text.extend_from_slice(manifest_data),
+                        COMPRESSION_DEFLATE => {
+                            let decoder = DeflateDecoder::new(manifest_data);
+                            let _ = decoder
+                                .take(MAX_MANIFEST_SIZE)
+                                .read_to_end(&mut manifest_text);
+                        }
+                        _ => (),
+                    }
+
+                    if let Ok(parsed_manifest) = parse_jar_manifest(&manifest_text) {
+                        manifest = parsed_manifest;
+                    }
+                }
+            }
+        }
+
+        return Ok(manifest);
+    }
+
+    Err(SignatureError)
+}
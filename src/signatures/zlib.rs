@@ -1,5 +1,7 @@
 use crate::extractors::zlib::zlib_decompress;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
+use crate::signatures::config::deflated_config_description;
+use crate::structures::zlib::{ZLIB_HEADER_SIZE, parse_zlib_header};
 
 /// Human readable description
 pub const DESCRIPTION: &str = "Zlib compressed file";
@@ -7,6 +9,7 @@ pub const DESCRIPTION: &str = "Zlib compressed file";
 /// Zlib magic bytes
 pub fn zlib_magic() -> Vec<Vec<u8>> {
     vec![
+        b"\x78\x01".to_vec(),
         b"\x78\x9c".to_vec(),
         b"\x78\xDA".to_vec(),
         b"\x78\x5E".to_vec(),
@@ -22,16 +25,25 @@ pub fn zlib_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
         ..Default::default()
     };
 
-    // Decompress the zlib; no output directory specified, dry run only.
-    let decompression_dry_run = zlib_decompress(file_data, offset, None);
+    // Cheap header sanity checks first; zlib streams may be found anywhere in a file
+    if let Ok(zlib_header) = parse_zlib_header(&file_data[offset..]) {
+        // Decompress the zlib; no output directory specified, dry run only.
+        let decompression_dry_run = zlib_decompress(file_data, offset, None);
 
-    // If the decompression dry run was a success, this signature is almost certianly valid
-    if decompression_dry_run.success {
-        if let Some(zlib_file_size) = decompression_dry_run.size {
-            result.size = zlib_file_size;
-            result.description =
-                format!("{}, total size: {} bytes", result.description, result.size);
-            return Ok(result);
+        // If the decompression dry run was a success, this signature is almost certianly valid
+        if decompression_dry_run.success {
+            if let Some(zlib_file_size) = decompression_dry_run.size {
+                result.size = zlib_file_size;
+                result.description = format!(
+                    "{}, compression level: {}, window size: {} bytes{}, total size: {} bytes",
+                    result.description,
+                    zlib_header.compression_level,
+                    zlib_header.window_size,
+                    deflated_config_description(file_data, offset + ZLIB_HEADER_SIZE),
+                    result.size
+                );
+                return Ok(result);
+            }
         }
     }
 
@@ -1,20 +1,26 @@
 use crate::common::is_offset_safe;
 use crate::signatures::common::{CONFIDENCE_HIGH, SignatureError, SignatureResult};
-use crate::structures::zstd::{parse_block_header, parse_zstd_header};
+use crate::structures::zstd::{
+    ZSTD_MAGIC, ZSTD_SKIPPABLE_FRAME_MAGIC, parse_block_header, parse_skippable_frame,
+    parse_zstd_header,
+};
 
 /// Human readable description
 pub const DESCRIPTION: &str = "ZSTD compressed data";
 
-/// ZSTD magic bytes
+/// ZSTD magic bytes; ZSTD data may also begin with a skippable frame
 pub fn zstd_magic() -> Vec<Vec<u8>> {
-    vec![b"\x28\xb5\x2f\xfd".to_vec()]
+    let mut magic_signatures: Vec<Vec<u8>> = vec![ZSTD_MAGIC.to_vec()];
+
+    for i in 0..16 {
+        magic_signatures.push((ZSTD_SKIPPABLE_FRAME_MAGIC + i).to_le_bytes().to_vec());
+    }
+
+    magic_signatures
 }
 
 /// Validate a ZSTD signature
 pub fn zstd_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, SignatureError> {
-    // Size of checksum value at EOF
-    const EOF_CHECKSUM_SIZE: usize = 4;
-
     // More or less arbitrarily chosen
     const MIN_BLOCK_COUNT: usize = 2;
 
@@ -25,10 +31,74 @@ pub fn zstd_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
         ..Default::default()
     };
 
+    let mut frame_count: usize = 0;
+    let mut block_count: usize = 0;
+    let mut skippable_frame_count: usize = 0;
+    let mut next_frame_offset = offset;
+    let mut previous_frame_offset = None;
+
+    // ZSTD frames, and skippable frames, may be concatenated; process them all to determine the total size
+    while is_offset_safe(file_data.len(), next_frame_offset, previous_frame_offset) {
+        // Skippable frames have no blocks
+        let (frame_size, frame_block_count) =
+            match parse_skippable_frame(&file_data[next_frame_offset..]) {
+                Ok(skippable_frame_size) => (skippable_frame_size, None),
+                Err(_) => match zstd_frame_size(file_data, next_frame_offset) {
+                    None => break,
+                    Some((zstd_frame_size, zstd_block_count)) => {
+                        (zstd_frame_size, Some(zstd_block_count))
+                    }
+                },
+            };
+
+        // Frame data must be in bounds
+        if next_frame_offset + frame_size > file_data.len() {
+            break;
+        }
+
+        match frame_block_count {
+            None => skippable_frame_count += 1,
+            Some(frame_block_count) => {
+                frame_count += 1;
+                block_count += frame_block_count;
+            }
+        }
+
+        previous_frame_offset = Some(next_frame_offset);
+        next_frame_offset += frame_size;
+        result.size = next_frame_offset - offset;
+    }
+
+    // There must be at least one ZSTD frame, and we want to have validated more than one block (or skippable frame)
+    if frame_count > 0 && (block_count + skippable_frame_count) >= MIN_BLOCK_COUNT {
+        if frame_count > 1 || skippable_frame_count > 0 {
+            result.description = format!(
+                "{}, frames: {}, skippable frames: {}",
+                result.description, frame_count, skippable_frame_count
+            );
+        }
+
+        result.description = format!("{}, total size: {} bytes", result.description, result.size);
+        return Ok(result);
+    }
+
+    Err(SignatureError)
+}
+
+/// Returns the size and block count of the ZSTD frame at the specified offset
+fn zstd_frame_size(file_data: &[u8], offset: usize) -> Option<(usize, usize)> {
+    // Size of checksum value at EOF
+    const EOF_CHECKSUM_SIZE: usize = 4;
+
     let available_data = file_data.len();
 
-    // Parse the ZSTD header; this should be safe as the ZSTD magic bytes wouldn't have matched at this offset if nothing was there...
+    // Parse the ZSTD header
     if let Ok(zstd_header) = parse_zstd_header(&file_data[offset..]) {
+        // Skippable frame magic bytes pass the frame header validation; the ZSTD magic must be present
+        if !file_data[offset..].starts_with(ZSTD_MAGIC) {
+            return None;
+        }
+
         /*
          * The first block header starts immediately after the ZSTD header, BUT there may be optional header fields present.
          * Must parse the frame header descriptor bit fields to determine total size of the header.
@@ -89,29 +159,20 @@ pub fn zstd_parser(file_data: &[u8], offset: usize) -> Result<SignatureResult, S
 
                     // Was this the last block?
                     if block_header.last_block {
-                        // Update the total size, which is the difference between the end of the last block and the start of the ZSTD header
-                        result.size = next_block_header_start - offset;
+                        // The frame size is the difference between the end of the last block and the start of the ZSTD header
+                        let mut frame_size = next_block_header_start - offset;
 
                         // If a checksum is included at the end of the block stream, add the checksum size to the total size
                         if zstd_header.content_checksum_present {
-                            result.size += EOF_CHECKSUM_SIZE;
+                            frame_size += EOF_CHECKSUM_SIZE;
                         }
 
-                        // Make sure we've processed more than one block; if so, return Ok, else break and return Err below
-                        if block_count >= MIN_BLOCK_COUNT {
-                            result.description = format!(
-                                "{}, total size: {} bytes",
-                                result.description, result.size
-                            );
-                            return Ok(result);
-                        } else {
-                            break;
-                        }
+                        return Some((frame_size, block_count));
                     }
                 }
             }
         }
     }
 
-    Err(SignatureError)
+    None
 }
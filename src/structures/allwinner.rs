@@ -0,0 +1,110 @@
+use crate::common::get_cstring;
+use crate::structures::common::{self, StructureError};
+
+/// Offset of the eGON magic bytes, which follow the jump instruction
+pub const EGON_MAGIC_OFFSET: usize = 4;
+
+/// Stores info about an Allwinner eGON boot image header
+#[derive(Debug, Default, Clone)]
+pub struct EGONHeader {
+    /// Boot stage, "BT0" for the first stage boot loader (boot0 or U-Boot SPL) and "BT1" for the second stage
+    pub boot_stage: String,
+    /// Version of the U-Boot SPL header, if this is a U-Boot SPL image
+    pub spl_version: Option<usize>,
+    /// Name of the device tree U-Boot was built for, if any
+    pub device_tree: Option<String>,
+    /// Size of the boot image, including the header
+    pub size: usize,
+}
+
+/// Parse and validate an Allwinner eGON boot image header
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::allwinner::parse_egon_header;
+///
+/// let mut image = b"\x06\x00\x00\xEAeGON.BT0\x12\xF6\xAD\xC7\x40\x00\x00\x00".to_vec();
+/// image.resize(0x40, 0);
+///
+/// let egon_header = parse_egon_header(&image).unwrap();
+///
+/// assert_eq!(egon_header.boot_stage, "BT0");
+/// assert_eq!(egon_header.size, 0x40);
+/// assert_eq!(egon_header.spl_version, None);
+/// ```
+pub fn parse_egon_header(image_data: &[u8]) -> Result<EGONHeader, StructureError> {
+    // The checksum is calculated with this value in place of the checksum field
+    const CHECKSUM_STAMP: u32 = 0x5F0A6C39;
+    const CHECKSUM_OFFSET: usize = 12;
+    const MIN_SIZE: usize = 0x20;
+    // "SPL", as a little endian u24
+    const SPL_SIGNATURE: usize = 0x4C5053;
+    // The device tree name offset was added in version 2 of the SPL header
+    const SPL_DEVICE_TREE_VERSION: usize = 2;
+
+    let header_structure = vec![
+        ("jump_instruction", "u32"),
+        ("magic_1", "u32"),
+        ("magic_2", "u32"),
+        ("checksum", "u32"),
+        ("length", "u32"),
+        ("spl_signature", "u24"),
+        ("spl_version", "u8"),
+        ("fel_script_address", "u32"),
+        ("fel_uenv_length", "u32"),
+        ("dt_name_offset", "u32"),
+    ];
+
+    let header = common::parse(image_data, &header_structure, "little")?;
+
+    // The checksum covers the entire image, which must be a multiple of 4 bytes
+    if header["length"] < MIN_SIZE || header["length"] % 4 != 0 {
+        return Err(StructureError);
+    }
+
+    let boot_stage = match image_data.get(EGON_MAGIC_OFFSET..EGON_MAGIC_OFFSET + 8) {
+        Some(b"eGON.BT0") => "BT0",
+        Some(b"eGON.BT1") => "BT1",
+        _ => return Err(StructureError),
+    };
+
+    let image = image_data.get(..header["length"]).ok_or(StructureError)?;
+
+    let checksum = image
+        .chunks_exact(4)
+        .enumerate()
+        .fold(0_u32, |checksum, (i, word)| {
+            let word = match i * 4 {
+                CHECKSUM_OFFSET => CHECKSUM_STAMP,
+                _ => u32::from_le_bytes([word[0], word[1], word[2], word[3]]),
+            };
+            checksum.wrapping_add(word)
+        });
+
+    if checksum as usize != header["checksum"] {
+        return Err(StructureError);
+    }
+
+    let mut egon_header = EGONHeader {
+        boot_stage: boot_stage.to_string(),
+        size: header["length"],
+        ..Default::default()
+    };
+
+    if header["spl_signature"] == SPL_SIGNATURE {
+        egon_header.spl_version = Some(header["spl_version"]);
+
+        if header["spl_version"] >= SPL_DEVICE_TREE_VERSION && header["dt_name_offset"] != 0 {
+            if let Some(dt_name_data) = image.get(header["dt_name_offset"]..) {
+                let device_tree = get_cstring(dt_name_data);
+
+                if !device_tree.is_empty() {
+                    egon_header.device_tree = Some(device_tree);
+                }
+            }
+        }
+    }
+
+    Ok(egon_header)
+}
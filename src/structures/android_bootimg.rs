@@ -1,35 +1,480 @@
+use crate::common::get_cstring;
 use crate::structures::common::{self, StructureError};
+use serde::{Deserialize, Serialize};
+
+/// A component stored in an Android boot or vendor boot image, such as the kernel or ramdisk
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AndroidBootImageComponent {
+    /// Name of the component, used as its extracted file name
+    pub name: String,
+    /// Offset of the component, relative to the start of the boot image
+    pub offset: usize,
+    pub size: usize,
+}
 
 /// Struct to store Android boot image header info
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct AndroidBootImageHeader {
+    pub header_version: usize,
+    pub page_size: usize,
     pub kernel_size: usize,
     pub ramdisk_size: usize,
     pub kernel_load_address: usize,
     pub ramdisk_load_address: usize,
+    pub second_load_address: usize,
+    pub tags_load_address: usize,
+    pub dtb_load_address: usize,
+    pub os_version: usize,
+    pub name: String,
+    pub cmdline: String,
+    /// The image's components, in the order they are stored
+    pub components: Vec<AndroidBootImageComponent>,
+    /// Size of the boot image, including all components and padding
+    pub total_size: usize,
 }
 
-/// Parses an Android boot image header
+/// Parses an Android boot image header, versions 0 through 4
 pub fn parse_android_bootimg_header(
     bootimg_data: &[u8],
 ) -> Result<AndroidBootImageHeader, StructureError> {
+    // Versions 3 and later always use 4K pages
+    const V3_PAGE_SIZE: usize = 4096;
+    const V3_HEADER_SIZE: usize = 1580;
+    const V4_HEADER_SIZE: usize = 1584;
+
+    // The header_version field is at the same offset in all header versions
+    let version_structure = vec![
+        ("magic", "u64"),
+        ("unknown1", "u64"),
+        ("unknown2", "u64"),
+        ("unknown3", "u64"),
+        ("unknown4", "u64"),
+        ("header_version", "u32"),
+    ];
+
+    let version_header = common::parse(bootimg_data, &version_structure, "little")?;
+
+    match version_header["header_version"] {
+        3 | 4 => {
+            let bootimg_structure = vec![
+                ("magic", "u64"),
+                ("kernel_size", "u32"),
+                ("ramdisk_size", "u32"),
+                ("os_version", "u32"),
+                ("header_size", "u32"),
+                ("reserved1", "u64"),
+                ("reserved2", "u64"),
+                ("header_version", "u32"),
+            ];
+
+            let bootimg_header = common::parse(bootimg_data, &bootimg_structure, "little")?;
+
+            let mut header = AndroidBootImageHeader {
+                header_version: bootimg_header["header_version"],
+                page_size: V3_PAGE_SIZE,
+                kernel_size: bootimg_header["kernel_size"],
+                ramdisk_size: bootimg_header["ramdisk_size"],
+                os_version: bootimg_header["os_version"],
+                cmdline: bootimg_string(bootimg_data, common::size(&bootimg_structure), 1536)?,
+                ..Default::default()
+            };
+
+            let mut signature_size: usize = 0;
+
+            if header.header_version == 4 {
+                if bootimg_header["header_size"] != V4_HEADER_SIZE {
+                    return Err(StructureError);
+                }
+
+                signature_size = bootimg_u32(bootimg_data, V3_HEADER_SIZE)?;
+            } else if bootimg_header["header_size"] != V3_HEADER_SIZE {
+                return Err(StructureError);
+            }
+
+            let sections = [
+                ("kernel", header.kernel_size),
+                ("ramdisk", header.ramdisk_size),
+                ("boot_signature", signature_size),
+            ];
+
+            add_components(
+                &mut header.components,
+                &mut header.total_size,
+                bootimg_header["header_size"],
+                header.page_size,
+                &sections,
+            )?;
+
+            Ok(header)
+        }
+
+        version => {
+            // Page sizes are typically 2K or 4K, but up to 64K is allowed
+            const MIN_PAGE_SIZE: usize = 2048;
+            const MAX_PAGE_SIZE: usize = 0x10000;
+
+            // Offsets of the command line fields
+            const CMDLINE_OFFSET: usize = 64;
+            const CMDLINE_SIZE: usize = 512;
+            const EXTRA_CMDLINE_OFFSET: usize = 608;
+            const EXTRA_CMDLINE_SIZE: usize = 1024;
+
+            // Offsets of the fields added in versions 1 and 2
+            const RECOVERY_DTBO_SIZE_OFFSET: usize = 1632;
+            const DTB_OFFSET: usize = 1648;
+
+            let bootimg_structure = vec![
+                ("magic", "u64"),
+                ("kernel_size", "u32"),
+                ("kernel_load_addr", "u32"),
+                ("ramdisk_size", "u32"),
+                ("ramdisk_load_addr", "u32"),
+                ("second_size", "u32"),
+                ("second_load_addr", "u32"),
+                ("tags_addr", "u32"),
+                ("page_size", "u32"),
+                ("header_version", "u32"),
+                ("os_version", "u32"),
+            ];
+
+            // Version 2 header fields
+            let dtb_structure = vec![("dtb_size", "u32"), ("dtb_load_addr", "u64")];
+
+            let bootimg_header = common::parse(bootimg_data, &bootimg_structure, "little")?;
+
+            let page_size = bootimg_header["page_size"];
+
+            if !page_size.is_power_of_two() || !(MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&page_size)
+            {
+                return Err(StructureError);
+            }
+
+            let mut header = AndroidBootImageHeader {
+                header_version: version,
+                page_size,
+                kernel_size: bootimg_header["kernel_size"],
+                kernel_load_address: bootimg_header["kernel_load_addr"],
+                ramdisk_size: bootimg_header["ramdisk_size"],
+                ramdisk_load_address: bootimg_header["ramdisk_load_addr"],
+                second_load_address: bootimg_header["second_load_addr"],
+                tags_load_address: bootimg_header["tags_addr"],
+                os_version: bootimg_header["os_version"],
+                name: bootimg_string(bootimg_data, common::size(&bootimg_structure), 16)?,
+                cmdline: bootimg_string(bootimg_data, CMDLINE_OFFSET, CMDLINE_SIZE)?
+                    + &bootimg_string(bootimg_data, EXTRA_CMDLINE_OFFSET, EXTRA_CMDLINE_SIZE)?,
+                ..Default::default()
+            };
+
+            let mut recovery_dtbo_size: usize = 0;
+            let mut dtb_size: usize = 0;
+
+            // Version 0 headers from some vendors store the size of a device tree image in the header_version field
+            let mut dt_size: usize = 0;
+
+            match version {
+                0 => (),
+                1 => {
+                    recovery_dtbo_size = bootimg_u32(bootimg_data, RECOVERY_DTBO_SIZE_OFFSET)?;
+                }
+                2 => {
+                    recovery_dtbo_size = bootimg_u32(bootimg_data, RECOVERY_DTBO_SIZE_OFFSET)?;
+
+                    let dtb_header = common::parse(
+                        bootimg_data.get(DTB_OFFSET..).ok_or(StructureError)?,
+                        &dtb_structure,
+                        "little",
+                    )?;
+
+                    dtb_size = dtb_header["dtb_size"];
+                    header.dtb_load_address = dtb_header["dtb_load_addr"];
+                }
+                _ => {
+                    header.header_version = 0;
+                    dt_size = version;
+                }
+            }
+
+            let sections = [
+                ("kernel", header.kernel_size),
+                ("ramdisk", header.ramdisk_size),
+                ("second", bootimg_header["second_size"]),
+                ("dt", dt_size),
+                ("recovery_dtbo", recovery_dtbo_size),
+                ("dtb", dtb_size),
+            ];
+
+            // The header is never larger than the minimum page size
+            add_components(
+                &mut header.components,
+                &mut header.total_size,
+                MIN_PAGE_SIZE,
+                header.page_size,
+                &sections,
+            )?;
+
+            Ok(header)
+        }
+    }
+}
+
+/// Struct to store Android vendor boot image header info
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AndroidVendorBootImageHeader {
+    pub header_version: usize,
+    pub page_size: usize,
+    pub kernel_load_address: usize,
+    pub ramdisk_load_address: usize,
+    pub vendor_ramdisk_size: usize,
+    pub tags_load_address: usize,
+    pub dtb_load_address: usize,
+    pub name: String,
+    pub cmdline: String,
+    /// The image's components, in the order they are stored
+    pub components: Vec<AndroidBootImageComponent>,
+    /// The individual vendor ramdisks, as listed in the vendor ramdisk table (version 4 only)
+    pub vendor_ramdisks: Vec<AndroidVendorRamdisk>,
+    /// Size of the vendor boot image, including all components and padding
+    pub total_size: usize,
+}
+
+/// Struct to store info about a vendor ramdisk listed in a vendor boot image's vendor ramdisk table
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AndroidVendorRamdisk {
+    pub name: String,
+    pub ramdisk_type: usize,
+    /// Offset of the ramdisk, relative to the start of the vendor ramdisk section
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// Parses an Android vendor boot image header, versions 3 and 4
+pub fn parse_android_vendor_bootimg_header(
+    bootimg_data: &[u8],
+) -> Result<AndroidVendorBootImageHeader, StructureError> {
+    const V3_HEADER_SIZE: usize = 2112;
+    const V4_HEADER_SIZE: usize = 2128;
+
+    const CMDLINE_SIZE: usize = 2048;
+    const NAME_SIZE: usize = 16;
+
+    // Page sizes are typically 2K or 4K, but up to 64K is allowed
+    const MIN_PAGE_SIZE: usize = 2048;
+    const MAX_PAGE_SIZE: usize = 0x10000;
+
+    // The ramdisk table entry structure may grow in future versions, but must be at least this big
+    const MIN_TABLE_ENTRY_SIZE: usize = 108;
+
+    const RAMDISK_NAME_OFFSET: usize = 12;
+    const RAMDISK_NAME_SIZE: usize = 32;
+
     let bootimg_structure = vec![
         ("magic", "u64"),
-        ("kernel_size", "u32"),
+        ("header_version", "u32"),
+        ("page_size", "u32"),
         ("kernel_load_addr", "u32"),
-        ("ramdisk_size", "u32"),
         ("ramdisk_load_addr", "u32"),
+        ("vendor_ramdisk_size", "u32"),
+    ];
+
+    // These fields follow the command line and name fields
+    let bootimg_structure2 = vec![
+        ("header_size", "u32"),
+        ("dtb_size", "u32"),
+        ("dtb_load_addr", "u64"),
+    ];
+
+    let v4_structure = vec![
+        ("vendor_ramdisk_table_size", "u32"),
+        ("vendor_ramdisk_table_entry_count", "u32"),
+        ("vendor_ramdisk_table_entry_size", "u32"),
+        ("bootconfig_size", "u32"),
+    ];
+
+    let ramdisk_entry_structure = vec![
+        ("ramdisk_size", "u32"),
+        ("ramdisk_offset", "u32"),
+        ("ramdisk_type", "u32"),
+    ];
+
+    let bootimg_header = common::parse(bootimg_data, &bootimg_structure, "little")?;
+
+    let page_size = bootimg_header["page_size"];
+
+    if !page_size.is_power_of_two() || !(MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&page_size) {
+        return Err(StructureError);
+    }
+
+    let cmdline_offset = common::size(&bootimg_structure);
+    let tags_offset = cmdline_offset + CMDLINE_SIZE;
+    let name_offset = tags_offset + std::mem::size_of::<u32>();
+    let bootimg_header2_offset = name_offset + NAME_SIZE;
+
+    let bootimg_header2 = common::parse(
+        bootimg_data
+            .get(bootimg_header2_offset..)
+            .ok_or(StructureError)?,
+        &bootimg_structure2,
+        "little",
+    )?;
+
+    let mut header = AndroidVendorBootImageHeader {
+        header_version: bootimg_header["header_version"],
+        page_size,
+        kernel_load_address: bootimg_header["kernel_load_addr"],
+        ramdisk_load_address: bootimg_header["ramdisk_load_addr"],
+        vendor_ramdisk_size: bootimg_header["vendor_ramdisk_size"],
+        tags_load_address: bootimg_u32(bootimg_data, tags_offset)?,
+        dtb_load_address: bootimg_header2["dtb_load_addr"],
+        name: bootimg_string(bootimg_data, name_offset, NAME_SIZE)?,
+        cmdline: bootimg_string(bootimg_data, cmdline_offset, CMDLINE_SIZE)?,
+        ..Default::default()
+    };
+
+    let mut ramdisk_table_size: usize = 0;
+    let mut bootconfig_size: usize = 0;
+
+    match header.header_version {
+        3 => {
+            if bootimg_header2["header_size"] != V3_HEADER_SIZE {
+                return Err(StructureError);
+            }
+        }
+        4 => {
+            if bootimg_header2["header_size"] != V4_HEADER_SIZE {
+                return Err(StructureError);
+            }
+
+            let v4_header = common::parse(
+                bootimg_data.get(V3_HEADER_SIZE..).ok_or(StructureError)?,
+                &v4_structure,
+                "little",
+            )?;
+
+            ramdisk_table_size = v4_header["vendor_ramdisk_table_size"];
+            bootconfig_size = v4_header["bootconfig_size"];
+
+            let entry_count = v4_header["vendor_ramdisk_table_entry_count"];
+            let entry_size = v4_header["vendor_ramdisk_table_entry_size"];
+
+            if entry_size < MIN_TABLE_ENTRY_SIZE
+                || entry_count.checked_mul(entry_size) != Some(ramdisk_table_size)
+            {
+                return Err(StructureError);
+            }
+
+            // The ramdisk table follows the header, vendor ramdisks and DTB, each of which are padded to a page boundary
+            let table_offset = align(V4_HEADER_SIZE, page_size)?
+                .checked_add(align(header.vendor_ramdisk_size, page_size)?)
+                .and_then(|offset| {
+                    offset.checked_add(align(bootimg_header2["dtb_size"], page_size).ok()?)
+                })
+                .ok_or(StructureError)?;
+
+            for i in 0..entry_count {
+                let entry_offset = table_offset + (i * entry_size);
+
+                let entry = common::parse(
+                    bootimg_data.get(entry_offset..).ok_or(StructureError)?,
+                    &ramdisk_entry_structure,
+                    "little",
+                )?;
+
+                let ramdisk = AndroidVendorRamdisk {
+                    name: bootimg_string(
+                        bootimg_data,
+                        entry_offset + RAMDISK_NAME_OFFSET,
+                        RAMDISK_NAME_SIZE,
+                    )?,
+                    ramdisk_type: entry["ramdisk_type"],
+                    offset: entry["ramdisk_offset"],
+                    size: entry["ramdisk_size"],
+                };
+
+                // Each vendor ramdisk must lie within the vendor ramdisk section
+                if ramdisk.offset + ramdisk.size > header.vendor_ramdisk_size {
+                    return Err(StructureError);
+                }
+
+                header.vendor_ramdisks.push(ramdisk);
+            }
+        }
+        _ => {
+            return Err(StructureError);
+        }
+    }
+
+    let sections = [
+        ("vendor_ramdisk", header.vendor_ramdisk_size),
+        ("dtb", bootimg_header2["dtb_size"]),
+        ("vendor_ramdisk_table", ramdisk_table_size),
+        ("bootconfig", bootconfig_size),
     ];
 
-    // Parse the header
-    if let Ok(bootimg_header) = common::parse(bootimg_data, &bootimg_structure, "little") {
-        return Ok(AndroidBootImageHeader {
-            kernel_size: bootimg_header["kernel_size"],
-            kernel_load_address: bootimg_header["kernel_load_addr"],
-            ramdisk_size: bootimg_header["ramdisk_size"],
-            ramdisk_load_address: bootimg_header["ramdisk_load_addr"],
+    add_components(
+        &mut header.components,
+        &mut header.total_size,
+        bootimg_header2["header_size"],
+        page_size,
+        &sections,
+    )?;
+
+    Ok(header)
+}
+
+/// Adds the non-empty sections to a list of components. Each section, as well as the header that precedes them,
+/// occupies a whole number of pages. Updates `total_size` to the end of the last section.
+fn add_components(
+    components: &mut Vec<AndroidBootImageComponent>,
+    total_size: &mut usize,
+    header_size: usize,
+    page_size: usize,
+    sections: &[(&str, usize)],
+) -> Result<(), StructureError> {
+    let mut next_offset: usize = align(header_size, page_size)?;
+
+    for (name, size) in sections {
+        if *size == 0 {
+            continue;
+        }
+
+        components.push(AndroidBootImageComponent {
+            name: name.to_string(),
+            offset: next_offset,
+            size: *size,
         });
+
+        next_offset = next_offset
+            .checked_add(align(*size, page_size)?)
+            .ok_or(StructureError)?;
     }
 
-    Err(StructureError)
+    *total_size = next_offset;
+
+    Ok(())
+}
+
+/// Rounds size up to the next multiple of page_size
+fn align(size: usize, page_size: usize) -> Result<usize, StructureError> {
+    size.checked_next_multiple_of(page_size)
+        .ok_or(StructureError)
+}
+
+/// Reads a little endian u32 value from the specified offset
+fn bootimg_u32(bootimg_data: &[u8], offset: usize) -> Result<usize, StructureError> {
+    match bootimg_data.get(offset..offset + 4) {
+        None => Err(StructureError),
+        Some(bytes) => Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as usize),
+    }
+}
+
+/// Reads a NULL terminated string from a fixed size field
+fn bootimg_string(
+    bootimg_data: &[u8],
+    offset: usize,
+    size: usize,
+) -> Result<String, StructureError> {
+    match bootimg_data.get(offset..offset + size) {
+        None => Err(StructureError),
+        Some(string_data) => Ok(get_cstring(string_data)),
+    }
 }
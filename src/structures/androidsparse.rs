@@ -7,6 +7,7 @@ pub struct AndroidSparseHeader {
     pub minor_version: usize,
     pub header_size: usize,
     pub block_size: usize,
+    pub block_count: usize,
     pub chunk_count: usize,
 }
 
@@ -46,6 +47,7 @@ pub fn parse_android_sparse_header(
             && header["minor_version"] == MINOR_VERSION
             && header["header_size"] == expected_header_size
             && header["chunk_header_size"] == CHUNK_HEADER_SIZE
+            && header["block_size"] != 0
             && (header["block_size"] % BLOCK_ALIGNMENT) == 0
         {
             return Ok(AndroidSparseHeader {
@@ -53,6 +55,7 @@ pub fn parse_android_sparse_header(
                 minor_version: header["minor_version"],
                 header_size: header["header_size"],
                 block_size: header["block_size"],
+                block_count: header["block_count"],
                 chunk_count: header["total_chunks"],
             });
         }
@@ -97,8 +100,8 @@ pub fn parse_android_sparse_chunk_header(
 
     // Parse the header
     if let Ok(chunk_header) = common::parse(chunk_data, &chunk_structure, "little") {
-        // Make sure the reserved field is zero
-        if chunk_header["reserved"] == 0 {
+        // Make sure the reserved field is zero, and the reported chunk size includes the chunk header
+        if chunk_header["reserved"] == 0 && chunk_header["total_size"] >= chonker.header_size {
             // Populate the structure values
             chonker.block_count = chunk_header["output_block_count"];
             chonker.data_size = chunk_header["total_size"] - chonker.header_size;
@@ -0,0 +1,128 @@
+use crate::common::{crc32, get_cstring};
+use crate::structures::common::{self, StructureError};
+
+/// Size of a Broadcom image tag
+pub const IMAGETAG_SIZE: usize = 256;
+
+/// Stores info about a Broadcom image tag (bcmImageTag)
+#[derive(Debug, Default, Clone)]
+pub struct BCMImageTag {
+    pub tag_version: String,
+    /// Chip ID, e.g., "6348"
+    pub chip_id: String,
+    pub board_id: String,
+    pub big_endian: bool,
+    /// Size of the CFE bootloader included in the image; 0 if the image does not include a bootloader
+    pub cfe_size: usize,
+    /// Flash address of the kernel and rootfs
+    pub flash_image_address: usize,
+    /// Combined size of the kernel and rootfs
+    pub flash_image_size: usize,
+    pub kernel_address: usize,
+    pub kernel_size: usize,
+}
+
+impl BCMImageTag {
+    /// Total size of the tagged image, including the tag
+    pub fn total_size(&self) -> usize {
+        IMAGETAG_SIZE + self.cfe_size + self.flash_image_size
+    }
+
+    /// Returns the name, offset and size of the bootloader, kernel and rootfs regions, relative to the start of the tag.
+    ///
+    /// The tag is followed by the bootloader, if any, then the flash image. The flash image is either a rootfs followed
+    /// by the kernel (Broadcom images), or a kernel followed by the rootfs (OpenWrt images).
+    pub fn regions(&self) -> Vec<(&'static str, usize, usize)> {
+        let mut regions: Vec<(&'static str, usize, usize)> = vec![];
+        let flash_image_offset = IMAGETAG_SIZE + self.cfe_size;
+        let kernel_offset = self.kernel_address - self.flash_image_address;
+
+        if self.cfe_size > 0 {
+            regions.push(("cfe", IMAGETAG_SIZE, self.cfe_size));
+        }
+
+        if kernel_offset > 0 {
+            regions.push(("rootfs", flash_image_offset, kernel_offset));
+        }
+
+        regions.push((
+            "kernel",
+            flash_image_offset + kernel_offset,
+            self.kernel_size,
+        ));
+
+        let kernel_end = kernel_offset + self.kernel_size;
+
+        if kernel_offset == 0 && kernel_end < self.flash_image_size {
+            regions.push((
+                "rootfs",
+                flash_image_offset + kernel_end,
+                self.flash_image_size - kernel_end,
+            ));
+        }
+
+        regions
+    }
+}
+
+/// Parse a Broadcom image tag
+pub fn parse_bcm_imagetag(tag_data: &[u8]) -> Result<BCMImageTag, StructureError> {
+    // Tag fields are NULL terminated ASCII strings; numeric fields are decimal
+    const TAG_VERSION: (usize, usize) = (0, 4);
+    const CHIP_ID: (usize, usize) = (38, 44);
+    const BOARD_ID: (usize, usize) = (44, 60);
+    const BIG_ENDIAN: (usize, usize) = (60, 62);
+    const CFE_LENGTH: (usize, usize) = (84, 94);
+    const FLASH_IMAGE_ADDRESS: (usize, usize) = (94, 106);
+    const FLASH_IMAGE_LENGTH: (usize, usize) = (106, 116);
+    const KERNEL_ADDRESS: (usize, usize) = (116, 128);
+    const KERNEL_LENGTH: (usize, usize) = (128, 138);
+    // The header CRC covers the tag, up to the header CRC field
+    const HEADER_CRC_OFFSET: usize = 236;
+
+    let crc_structure = vec![("header_crc", "u32")];
+
+    let tag = tag_data.get(..IMAGETAG_SIZE).ok_or(StructureError)?;
+    let field = |(start, end): (usize, usize)| get_cstring(&tag[start..end]);
+    let number = |location: (usize, usize)| -> Result<usize, StructureError> {
+        field(location)
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| StructureError)
+    };
+
+    let crc_field = common::parse(&tag[HEADER_CRC_OFFSET..], &crc_structure, "big")?;
+
+    // Header CRC is stored without the final XOR
+    if (crc32(&tag[..HEADER_CRC_OFFSET]) ^ 0xFFFFFFFF) as usize != crc_field["header_crc"] {
+        return Err(StructureError);
+    }
+
+    let image_tag = BCMImageTag {
+        tag_version: field(TAG_VERSION),
+        chip_id: field(CHIP_ID),
+        board_id: field(BOARD_ID),
+        big_endian: field(BIG_ENDIAN) == "1",
+        cfe_size: number(CFE_LENGTH).unwrap_or(0),
+        flash_image_address: number(FLASH_IMAGE_ADDRESS)?,
+        flash_image_size: number(FLASH_IMAGE_LENGTH)?,
+        kernel_address: number(KERNEL_ADDRESS)?,
+        kernel_size: number(KERNEL_LENGTH)?,
+    };
+
+    // The chip ID is a hex number, e.g., "6348" or "63268"
+    if image_tag.chip_id.is_empty() || !image_tag.chip_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(StructureError);
+    }
+
+    // The kernel must be inside the flash image
+    if image_tag.kernel_size == 0
+        || image_tag.kernel_address < image_tag.flash_image_address
+        || image_tag.kernel_address - image_tag.flash_image_address + image_tag.kernel_size
+            > image_tag.flash_image_size
+    {
+        return Err(StructureError);
+    }
+
+    Ok(image_tag)
+}
@@ -49,21 +49,134 @@ pub fn parse_bmp_file_header(bmp_data: &[u8]) -> Result<BMPFileHeader, Structure
     Err(StructureError)
 }
 
-// https://learn.microsoft.com/en-us/windows/win32/api/wingdi/ns-wingdi-bitmapv5header
-// "The number of bytes required by the structure. Applications should use this member to determine which bitmap information header structure is being used."
-pub fn get_dib_header_size(bmp_data: &[u8]) -> Result<usize, StructureError> {
+/// Stores info about a BMP image's DIB (bitmap information) header
+#[derive(Debug, Default, Clone)]
+pub struct DIBHeader {
+    pub header_size: usize,
+    pub width: usize,
+    pub height: usize,
+    pub bits_per_pixel: usize,
+    /// Size of the bitmap's pixel data, if known
+    pub image_data_size: Option<usize>,
+}
+
+/// Parses the DIB header that follows the BMP file header
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::bmp::parse_dib_header;
+///
+/// // BITMAPINFOHEADER for a 3x2, 24-bit, uncompressed image
+/// let mut dib = b"\x28\x00\x00\x00\x03\x00\x00\x00\x02\x00\x00\x00\x01\x00\x18\x00".to_vec();
+/// dib.extend([0; 24]);
+///
+/// let dib_header = parse_dib_header(&dib).unwrap();
+///
+/// assert_eq!(dib_header.width, 3);
+/// assert_eq!(dib_header.height, 2);
+/// // Each row of pixels is padded to a multiple of 4 bytes
+/// assert_eq!(dib_header.image_data_size, Some(24));
+/// ```
+pub fn parse_dib_header(bmp_data: &[u8]) -> Result<DIBHeader, StructureError> {
+    // https://learn.microsoft.com/en-us/windows/win32/api/wingdi/ns-wingdi-bitmapv5header
+    // "The number of bytes required by the structure. Applications should use this member to determine which bitmap information header structure is being used."
+    const CORE_HEADER_SIZE: usize = 12;
     let valid_header_sizes = [
-        12,  // BITMAPCOREHEADER
-        40,  // BITMAPINFOHEADER
-        108, // BITMAPV4HEADER
-        124,
+        CORE_HEADER_SIZE, // BITMAPCOREHEADER
+        40,               // BITMAPINFOHEADER
+        52,               // BITMAPV2INFOHEADER
+        56,               // BITMAPV3INFOHEADER
+        64,               // OS22XBITMAPHEADER
+        108,              // BITMAPV4HEADER
+        124,              // BITMAPV5HEADER
+    ];
+
+    const VALID_PLANES: usize = 1;
+    const VALID_BITS_PER_PIXEL: [usize; 8] = [1, 2, 4, 8, 16, 24, 32, 64];
+
+    // BI_RGB, BI_BITFIELDS and BI_ALPHABITFIELDS pixel data is not compressed
+    const UNCOMPRESSED: [usize; 3] = [0, 3, 6];
+
+    let core_header_structure = vec![
+        ("size", "u32"),
+        ("width", "u16"),
+        ("height", "u16"),
+        ("planes", "u16"),
+        ("bit_count", "u16"),
     ];
 
-    let header_size = u32::from_le_bytes(bmp_data[..4].try_into().unwrap());
+    let info_header_structure = vec![
+        ("size", "u32"),
+        ("width", "u32"),
+        ("height", "u32"),
+        ("planes", "u16"),
+        ("bit_count", "u16"),
+        ("compression", "u32"),
+        ("size_image", "u32"),
+    ];
+
+    let header_size = common::parse(bmp_data, &vec![("size", "u32")], "little")?["size"];
+
+    if !valid_header_sizes.contains(&header_size) || bmp_data.len() < header_size {
+        return Err(StructureError);
+    }
+
+    let mut compression: usize = 0;
+    let mut size_image: usize = 0;
+    let width: usize;
+    let height: usize;
+    let planes: usize;
+    let bits_per_pixel: usize;
+
+    if header_size == CORE_HEADER_SIZE {
+        let dib_header = common::parse(bmp_data, &core_header_structure, "little")?;
+        width = dib_header["width"];
+        height = dib_header["height"];
+        planes = dib_header["planes"];
+        bits_per_pixel = dib_header["bit_count"];
+    } else {
+        let dib_header = common::parse(bmp_data, &info_header_structure, "little")?;
+
+        // Width and height are signed; a negative height indicates a top-down bitmap
+        width = dib_header["width"];
+        height = (dib_header["height"] as u32 as i32).unsigned_abs() as usize;
+        planes = dib_header["planes"];
+        bits_per_pixel = dib_header["bit_count"];
+        compression = dib_header["compression"];
+        size_image = dib_header["size_image"];
 
-    if !valid_header_sizes.contains(&header_size) {
+        if (width as u32 as i32) <= 0 {
+            return Err(StructureError);
+        }
+    }
+
+    if width == 0
+        || height == 0
+        || planes != VALID_PLANES
+        || !VALID_BITS_PER_PIXEL.contains(&bits_per_pixel)
+    {
         return Err(StructureError);
     }
 
-    Ok(header_size as usize)
+    let image_data_size = if UNCOMPRESSED.contains(&compression) {
+        // Each row of pixels is padded to a multiple of 4 bytes
+        width
+            .checked_mul(bits_per_pixel)
+            .map(|row_bits| row_bits.div_ceil(32) * 4)
+            .and_then(|row_size| row_size.checked_mul(height))
+    } else if size_image > 0 {
+        // Compressed pixel data must specify its size
+        Some(size_image)
+    } else {
+        None
+    };
+
+    Ok(DIBHeader {
+        header_size,
+        width,
+        height,
+        bits_per_pixel,
+        image_data_size,
+    })
 }
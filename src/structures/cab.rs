@@ -108,3 +108,240 @@ pub fn parse_cab_header(header_data: &[u8]) -> Result<CabinetHeader, StructureEr
 
     Err(StructureError)
 }
+
+/// Stores info on the layout of a CAB archive, needed to locate its folders, files and data blocks
+#[derive(Debug, Default, Clone)]
+pub struct CabinetLayout {
+    /// Offset of the first folder entry, following the header and any reserved or cabinet set data
+    pub folders_offset: usize,
+    pub first_file_offset: usize,
+    pub folder_count: usize,
+    pub file_count: usize,
+    /// Size of the reserved data in each folder entry
+    pub folder_reserve_size: usize,
+    /// Size of the reserved data in each data block header
+    pub data_reserve_size: usize,
+}
+
+/// Stores info on a CAB folder; a folder is a single compressed stream, containing the data of one or more files
+#[derive(Debug, Default, Clone)]
+pub struct CabinetFolder {
+    /// Offset of the folder's first data block, relative to the start of the archive
+    pub data_offset: usize,
+    pub data_block_count: usize,
+    /// One of the COMPRESSION_* constants
+    pub compression: usize,
+    /// LZX window size, in bits; only valid for LZX compressed folders
+    pub lzx_window_bits: usize,
+}
+
+/// Stores info on a file in a CAB archive
+#[derive(Debug, Default, Clone)]
+pub struct CabinetFile {
+    pub name: String,
+    pub size: usize,
+    /// Offset of the file's data in the uncompressed folder data
+    pub folder_offset: usize,
+    pub folder_index: usize,
+    /// File data begins in a previous cabinet, or continues in the next cabinet of a cabinet set
+    pub split: bool,
+}
+
+/// Stores info on a CAB data block
+#[derive(Debug, Default, Clone)]
+pub struct CabinetDataBlock {
+    /// Zero if the block has no checksum
+    pub checksum: u32,
+    pub compressed_size: usize,
+    pub uncompressed_size: usize,
+    pub header_size: usize,
+}
+
+/// Folder compression types
+pub const COMPRESSION_NONE: usize = 0;
+pub const COMPRESSION_MSZIP: usize = 1;
+pub const COMPRESSION_LZX: usize = 3;
+
+/// Parses the CAB header fields needed to locate the archive's folders and files
+pub fn parse_cab_layout(cab_data: &[u8]) -> Result<CabinetLayout, StructureError> {
+    const HEADER_SIZE: usize = 36;
+    const RESERVE_SIZES_LEN: usize = 4;
+
+    const FLAG_PREV_CABINET: usize = 1;
+    const FLAG_NEXT_CABINET: usize = 2;
+    const FLAG_RESERVE_PRESENT: usize = 4;
+
+    let cab_header_structure = vec![
+        ("magic", "u32"),
+        ("reserved1", "u32"),
+        ("size", "u32"),
+        ("reserved2", "u32"),
+        ("first_file_offset", "u32"),
+        ("reserved3", "u32"),
+        ("minor_version", "u8"),
+        ("major_version", "u8"),
+        ("folder_count", "u16"),
+        ("file_count", "u16"),
+        ("flags", "u16"),
+        ("id", "u16"),
+        ("set_number", "u16"),
+    ];
+
+    let reserve_sizes_structure = vec![
+        ("header_reserve_size", "u16"),
+        ("folder_reserve_size", "u8"),
+        ("data_reserve_size", "u8"),
+    ];
+
+    let cab_header = common::parse(cab_data, &cab_header_structure, "little")?;
+
+    let mut layout = CabinetLayout {
+        folders_offset: HEADER_SIZE,
+        first_file_offset: cab_header["first_file_offset"],
+        folder_count: cab_header["folder_count"],
+        file_count: cab_header["file_count"],
+        ..Default::default()
+    };
+
+    if (cab_header["flags"] & FLAG_RESERVE_PRESENT) != 0 {
+        let reserve_sizes = common::parse(
+            cab_data.get(HEADER_SIZE..).ok_or(StructureError)?,
+            &reserve_sizes_structure,
+            "little",
+        )?;
+
+        layout.folder_reserve_size = reserve_sizes["folder_reserve_size"];
+        layout.data_reserve_size = reserve_sizes["data_reserve_size"];
+        layout.folders_offset += RESERVE_SIZES_LEN + reserve_sizes["header_reserve_size"];
+    }
+
+    // Cabinets in a cabinet set are followed by the cabinet and disk names of the previous and/or next cabinet
+    for flag in [FLAG_PREV_CABINET, FLAG_NEXT_CABINET] {
+        if (cab_header["flags"] & flag) != 0 {
+            for _ in 0..2 {
+                let (_, string_size) = parse_cab_string(
+                    cab_data
+                        .get(layout.folders_offset..)
+                        .ok_or(StructureError)?,
+                )?;
+                layout.folders_offset += string_size;
+            }
+        }
+    }
+
+    Ok(layout)
+}
+
+/// Parses a CAB folder entry
+pub fn parse_cab_folder(folder_data: &[u8]) -> Result<CabinetFolder, StructureError> {
+    const COMPRESSION_TYPE_MASK: usize = 0x000F;
+    const LZX_WINDOW_SHIFT: usize = 8;
+    const LZX_WINDOW_MASK: usize = 0x1F;
+
+    let folder_structure = vec![
+        ("data_offset", "u32"),
+        ("data_block_count", "u16"),
+        ("compression_type", "u16"),
+    ];
+
+    let folder = common::parse(folder_data, &folder_structure, "little")?;
+
+    Ok(CabinetFolder {
+        data_offset: folder["data_offset"],
+        data_block_count: folder["data_block_count"],
+        compression: folder["compression_type"] & COMPRESSION_TYPE_MASK,
+        lzx_window_bits: (folder["compression_type"] >> LZX_WINDOW_SHIFT) & LZX_WINDOW_MASK,
+    })
+}
+
+/// Parses a CAB file entry, returning the file info and the size of the file entry
+pub fn parse_cab_file(file_data: &[u8]) -> Result<(CabinetFile, usize), StructureError> {
+    const FILE_ENTRY_SIZE: usize = 16;
+    // Special folder indexes for files split across cabinets
+    const CONTINUED_FROM_PREV: usize = 0xFFFD;
+
+    let file_structure = vec![
+        ("size", "u32"),
+        ("folder_offset", "u32"),
+        ("folder_index", "u16"),
+        ("date", "u16"),
+        ("time", "u16"),
+        ("attributes", "u16"),
+    ];
+
+    let file_entry = common::parse(file_data, &file_structure, "little")?;
+    let (name, name_size) =
+        parse_cab_string(file_data.get(FILE_ENTRY_SIZE..).ok_or(StructureError)?)?;
+
+    Ok((
+        CabinetFile {
+            name,
+            size: file_entry["size"],
+            folder_offset: file_entry["folder_offset"],
+            folder_index: file_entry["folder_index"],
+            split: file_entry["folder_index"] >= CONTINUED_FROM_PREV,
+        },
+        FILE_ENTRY_SIZE + name_size,
+    ))
+}
+
+/// Parses a CAB data block header
+pub fn parse_cab_data_block(
+    block_data: &[u8],
+    data_reserve_size: usize,
+) -> Result<CabinetDataBlock, StructureError> {
+    const BLOCK_HEADER_SIZE: usize = 8;
+
+    let block_structure = vec![
+        ("checksum", "u32"),
+        ("compressed_size", "u16"),
+        ("uncompressed_size", "u16"),
+    ];
+
+    let block_header = common::parse(block_data, &block_structure, "little")?;
+
+    Ok(CabinetDataBlock {
+        checksum: block_header["checksum"] as u32,
+        compressed_size: block_header["compressed_size"],
+        uncompressed_size: block_header["uncompressed_size"],
+        header_size: BLOCK_HEADER_SIZE + data_reserve_size,
+    })
+}
+
+/// Calculates the checksum of a CAB data block, over the block's data and its size fields
+pub fn cab_data_block_checksum(block: &CabinetDataBlock, data: &[u8]) -> u32 {
+    let mut size_fields: Vec<u8> = vec![];
+    size_fields.extend_from_slice(&(block.compressed_size as u16).to_le_bytes());
+    size_fields.extend_from_slice(&(block.uncompressed_size as u16).to_le_bytes());
+
+    cab_checksum(&size_fields, cab_checksum(data, 0))
+}
+
+/// XORs data into a CAB checksum 4 bytes at a time; any trailing bytes are combined in big endian order
+fn cab_checksum(data: &[u8], seed: u32) -> u32 {
+    let mut checksum = seed;
+    let chunks = data.chunks_exact(4);
+    let remainder = chunks.remainder();
+
+    for chunk in chunks {
+        checksum ^= u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+
+    let mut trailing_bytes: u32 = 0;
+    for byte in remainder {
+        trailing_bytes = (trailing_bytes << 8) | (*byte as u32);
+    }
+
+    checksum ^ trailing_bytes
+}
+
+/// Parses a NULL terminated CAB string, returning the string and its size, including the NULL terminator
+fn parse_cab_string(string_data: &[u8]) -> Result<(String, usize), StructureError> {
+    match string_data.iter().position(|byte| *byte == 0) {
+        None => Err(StructureError),
+        Some(null_index) => Ok((
+            String::from_utf8_lossy(&string_data[..null_index]).to_string(),
+            null_index + 1,
+        )),
+    }
+}
@@ -8,6 +8,20 @@ pub struct CHKHeader {
     pub kernel_size: usize,
     pub rootfs_size: usize,
     pub board_id: String,
+    pub region: usize,
+    /// Firmware and UI version, e.g., "V1.0.2.28_1.1.54"; empty if not set
+    pub version: String,
+}
+
+impl CHKHeader {
+    /// Returns the name of the header's region code, if known
+    pub fn region_name(&self) -> Option<&str> {
+        match self.region {
+            1 => Some("worldwide"),
+            2 => Some("North America"),
+            _ => None,
+        }
+    }
 }
 
 /// Parse a CHK firmware header
@@ -18,11 +32,18 @@ pub fn parse_chk_header(header_data: &[u8]) -> Result<CHKHeader, StructureError>
     let chk_header_structure = vec![
         ("magic", "u32"),
         ("header_size", "u32"),
-        ("unknown", "u64"),
+        ("region", "u8"),
+        ("version_major", "u8"),
+        ("version_minor", "u8"),
+        ("version_release", "u8"),
+        ("version_build", "u8"),
+        ("ui_version_major", "u8"),
+        ("ui_version_minor", "u8"),
+        ("ui_version_build", "u8"),
         ("kernel_checksum", "u32"),
         ("rootfs_checksum", "u32"),
-        ("rootfs_size", "u32"),
         ("kernel_size", "u32"),
+        ("rootfs_size", "u32"),
         ("image_checksum", "u32"),
         ("header_checksum", "u32"),
         // Board ID string follows
@@ -46,11 +67,39 @@ pub fn parse_chk_header(header_data: &[u8]) -> Result<CHKHeader, StructureError>
 
                 // We expect that there must be a valid board ID string
                 if !board_id_string.is_empty() {
+                    let version_fields = [
+                        "version_major",
+                        "version_minor",
+                        "version_release",
+                        "version_build",
+                        "ui_version_major",
+                        "ui_version_minor",
+                        "ui_version_build",
+                    ]
+                    .map(|field| chk_header[field]);
+
+                    // Images built with generic tools leave the version fields zeroed
+                    let version = match version_fields.iter().all(|field| *field == 0) {
+                        true => "".to_string(),
+                        false => format!(
+                            "V{}.{}.{}.{}_{}.{}.{}",
+                            version_fields[0],
+                            version_fields[1],
+                            version_fields[2],
+                            version_fields[3],
+                            version_fields[4],
+                            version_fields[5],
+                            version_fields[6]
+                        ),
+                    };
+
                     return Ok(CHKHeader {
                         board_id: board_id_string.clone(),
                         header_size: chk_header["header_size"],
                         kernel_size: chk_header["kernel_size"],
                         rootfs_size: chk_header["rootfs_size"],
+                        region: chk_header["region"],
+                        version,
                     });
                 }
             }
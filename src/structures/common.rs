@@ -1,3 +1,4 @@
+use crate::common::parser_deadline_exceeded;
 use log::error;
 use std::collections::HashMap;
 
@@ -63,6 +64,11 @@ pub fn parse(
     let mut offset: usize = 0;
     let mut parsed_structure = HashMap::new();
 
+    // Parsers that have run past their time budget fail at their next structure
+    if parser_deadline_exceeded() {
+        return Err(StructureError);
+    }
+
     // Get the size of the defined structure
     let structure_size = size(structure);
 
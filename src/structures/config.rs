@@ -0,0 +1,125 @@
+use crate::common::is_text;
+use crate::structures::common::StructureError;
+
+/// XML configuration files start with an XML declaration
+pub const XML_DECLARATION: &[u8] = b"<?xml ";
+
+/// Supported plain text configuration file formats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Xml,
+    Ini,
+}
+
+impl ConfigFormat {
+    /// Human readable name of the configuration format
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigFormat::Xml => "XML",
+            ConfigFormat::Ini => "INI",
+        }
+    }
+}
+
+/// Identifies the format of plain text configuration data, such as that found inside compressed router configuration backups.
+/// The provided data need only be the first few hundred bytes of the configuration file.
+pub fn parse_config_text(config_data: &[u8]) -> Result<ConfigFormat, StructureError> {
+    // UTF-8 byte order mark, which some configuration exports include
+    const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+    let text_data = config_data.strip_prefix(UTF8_BOM).unwrap_or(config_data);
+
+    // Configuration files are plain text
+    if text_data.is_empty() || !text_data.iter().all(|b| is_text(*b)) {
+        return Err(StructureError);
+    }
+
+    let text = String::from_utf8_lossy(text_data);
+
+    // Only the first non-empty line of the file is needed to determine its format
+    let first_line = text
+        .lines()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())
+        .ok_or(StructureError)?;
+
+    if first_line.as_bytes().starts_with(XML_DECLARATION) {
+        return Ok(ConfigFormat::Xml);
+    }
+
+    // INI files start with a [section] header, or with key=value pairs
+    if (first_line.starts_with('[') && first_line.ends_with(']') && first_line.len() > 2)
+        || is_ini_key_value(first_line)
+    {
+        return Ok(ConfigFormat::Ini);
+    }
+
+    Err(StructureError)
+}
+
+/// Returns true if the line looks like an INI key=value pair
+fn is_ini_key_value(line: &str) -> bool {
+    match line.split_once('=') {
+        None => false,
+        Some((key, _value)) => {
+            let key = key.trim();
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c))
+        }
+    }
+}
+
+/// Stores info about an XOR obfuscated XML configuration file
+#[derive(Debug, Default, Clone)]
+pub struct XORConfig {
+    pub key: u8,
+    pub size: usize,
+}
+
+/// Parses an XML configuration file that has been obfuscated with a single byte XOR key
+pub fn parse_xor_config(config_data: &[u8]) -> Result<XORConfig, StructureError> {
+    // Anything smaller than this is unlikely to be a configuration file
+    const MIN_CONFIG_SIZE: usize = 64;
+    const XML_TAG_END: u8 = b'>';
+
+    // The key can be recovered from the first byte of the XML declaration
+    let key = config_data.first().ok_or(StructureError)? ^ XML_DECLARATION[0];
+
+    // A key of zero means this is plain text, not obfuscated
+    if key == 0 {
+        return Err(StructureError);
+    }
+
+    // The obfuscated data ends when the decoded data is no longer text
+    let text_size = config_data
+        .iter()
+        .position(|b| !is_text(b ^ key))
+        .unwrap_or(config_data.len());
+
+    // XML data ends with a closing tag, though may be followed by some whitespace
+    let config_size = config_data[0..text_size]
+        .iter()
+        .rposition(|b| (b ^ key) == XML_TAG_END)
+        .ok_or(StructureError)?
+        + 1;
+
+    if config_size < MIN_CONFIG_SIZE {
+        return Err(StructureError);
+    }
+
+    let decoded_header: Vec<u8> = config_data[0..XML_DECLARATION.len()]
+        .iter()
+        .map(|b| b ^ key)
+        .collect();
+
+    if decoded_header != XML_DECLARATION {
+        return Err(StructureError);
+    }
+
+    Ok(XORConfig {
+        key,
+        size: config_size,
+    })
+}
@@ -1,70 +1,124 @@
 use crate::structures::common::StructureError;
 
-/// Expected minimum size of a CPIO entry header
+/// Expected minimum size of a "new" (newc) CPIO entry header
 pub const CPIO_HEADER_SIZE: usize = 110;
 
+/// Size of an "old" portable (odc) CPIO entry header
+pub const CPIO_ODC_HEADER_SIZE: usize = 76;
+
+/// CPIO header magic bytes
+pub const CPIO_NEWC_MAGIC: &[u8] = b"070701";
+pub const CPIO_NEWC_CRC_MAGIC: &[u8] = b"070702";
+pub const CPIO_ODC_MAGIC: &[u8] = b"070707";
+
 /// Storage struct for CPIO entry header info
 #[derive(Debug, Clone, Default)]
 pub struct CPIOEntryHeader {
     pub magic: Vec<u8>,
+    /// Size of the file data, including any padding
     pub data_size: usize,
     pub file_name: String,
+    /// Size of the header and file name, including any padding
     pub header_size: usize,
+    /// Size of the file data, not including padding
+    pub file_size: usize,
+    pub mode: usize,
+    pub inode: usize,
+    pub nlink: usize,
+    pub rdev_major: usize,
+    pub rdev_minor: usize,
 }
 
-/// Parses a CPIO entry header
+/// Parses a CPIO entry header; supports newc (with and without CRC) and odc formats
 pub fn parse_cpio_entry_header(cpio_data: &[u8]) -> Result<CPIOEntryHeader, StructureError> {
-    // Some expected constants
-    const NULL_BYTE_SIZE: usize = 1;
-    const CPIO_MAGIC_START: usize = 0;
-    const CPIO_MAGIC_END: usize = 6;
+    const CPIO_MAGIC_SIZE: usize = 6;
+
+    match cpio_data.get(..CPIO_MAGIC_SIZE) {
+        Some(CPIO_NEWC_MAGIC) | Some(CPIO_NEWC_CRC_MAGIC) => parse_newc_entry_header(cpio_data),
+        Some(CPIO_ODC_MAGIC) => parse_odc_entry_header(cpio_data),
+        _ => Err(StructureError),
+    }
+}
+
+/// Parses a newc CPIO entry header; all fields are 8-character ASCII hex strings
+fn parse_newc_entry_header(cpio_data: &[u8]) -> Result<CPIOEntryHeader, StructureError> {
+    const HEX: u32 = 16;
+    const FIELD_SIZE: usize = 8;
+    const INODE_START: usize = 6;
+    const MODE_START: usize = 14;
+    const NLINK_START: usize = 38;
     const FILE_SIZE_START: usize = 54;
-    const FILE_SIZE_END: usize = 62;
+    const RDEV_MAJOR_START: usize = 78;
+    const RDEV_MINOR_START: usize = 86;
     const FILE_NAME_SIZE_START: usize = 94;
-    const FILE_NAME_SIZE_END: usize = 102;
-
-    let available_data: usize = cpio_data.len();
-
-    // TODO: If file mode parsing is added, internal extractor would be pretty easy to implement...
-    if available_data > CPIO_HEADER_SIZE {
-        // Grab the CPIO header magic bytes
-        let header_magic = cpio_data[CPIO_MAGIC_START..CPIO_MAGIC_END].to_vec();
-
-        // Get the ASCII hex string representing the file's data size
-        if let Ok(file_data_size_str) =
-            String::from_utf8(cpio_data[FILE_SIZE_START..FILE_SIZE_END].to_vec())
-        {
-            // Convert the file data size from ASCII hex to an integer
-            if let Ok(file_data_size) = usize::from_str_radix(&file_data_size_str, 16) {
-                // Get the ASCII hex string representing the file name's size
-                if let Ok(file_name_size_str) =
-                    String::from_utf8(cpio_data[FILE_NAME_SIZE_START..FILE_NAME_SIZE_END].to_vec())
-                {
-                    // Convert the file name size from ASCII hex to an integer
-                    if let Ok(file_name_size) = usize::from_str_radix(&file_name_size_str, 16) {
-                        // The file name immediately follows the fixed-length header data.
-                        let file_name_start: usize = CPIO_HEADER_SIZE;
-                        let file_name_end: usize =
-                            file_name_start + file_name_size - NULL_BYTE_SIZE;
-
-                        // Get the file name
-                        if let Some(file_name_raw_bytes) =
-                            cpio_data.get(file_name_start..file_name_end)
-                        {
-                            if let Ok(file_name) = String::from_utf8(file_name_raw_bytes.to_vec()) {
-                                let header_total_size = CPIO_HEADER_SIZE + file_name_size;
-
-                                return Ok(CPIOEntryHeader {
-                                    magic: header_magic.clone(),
-                                    file_name: file_name.clone(),
-                                    data_size: file_data_size + byte_padding(file_data_size),
-                                    header_size: header_total_size
-                                        + byte_padding(header_total_size),
-                                });
-                            }
-                        }
-                    }
-                }
+
+    let file_size = ascii_field(cpio_data, FILE_SIZE_START, FIELD_SIZE, HEX)?;
+    let file_name_size = ascii_field(cpio_data, FILE_NAME_SIZE_START, FIELD_SIZE, HEX)?;
+
+    // The file name immediately follows the fixed-length header data
+    let file_name = entry_file_name(cpio_data, CPIO_HEADER_SIZE, file_name_size)?;
+
+    // The header (including the file name) and the file data are each padded to a 4-byte boundary
+    let header_total_size = CPIO_HEADER_SIZE + file_name_size;
+
+    Ok(CPIOEntryHeader {
+        magic: cpio_data[..INODE_START].to_vec(),
+        file_name,
+        data_size: file_size + byte_padding(file_size),
+        header_size: header_total_size + byte_padding(header_total_size),
+        file_size,
+        mode: ascii_field(cpio_data, MODE_START, FIELD_SIZE, HEX)?,
+        inode: ascii_field(cpio_data, INODE_START, FIELD_SIZE, HEX)?,
+        nlink: ascii_field(cpio_data, NLINK_START, FIELD_SIZE, HEX)?,
+        rdev_major: ascii_field(cpio_data, RDEV_MAJOR_START, FIELD_SIZE, HEX)?,
+        rdev_minor: ascii_field(cpio_data, RDEV_MINOR_START, FIELD_SIZE, HEX)?,
+    })
+}
+
+/// Parses an odc CPIO entry header; all fields are ASCII octal strings, and there is no padding
+fn parse_odc_entry_header(cpio_data: &[u8]) -> Result<CPIOEntryHeader, StructureError> {
+    const OCTAL: u32 = 8;
+    const FIELD_SIZE: usize = 6;
+    const LONG_FIELD_SIZE: usize = 11;
+    const INODE_START: usize = 12;
+    const MODE_START: usize = 18;
+    const NLINK_START: usize = 36;
+    const RDEV_START: usize = 42;
+    const FILE_NAME_SIZE_START: usize = 59;
+    const FILE_SIZE_START: usize = 65;
+
+    let file_size = ascii_field(cpio_data, FILE_SIZE_START, LONG_FIELD_SIZE, OCTAL)?;
+    let file_name_size = ascii_field(cpio_data, FILE_NAME_SIZE_START, FIELD_SIZE, OCTAL)?;
+    let file_name = entry_file_name(cpio_data, CPIO_ODC_HEADER_SIZE, file_name_size)?;
+
+    // The device number is a 16-bit value, with the major number in the upper 8 bits
+    let rdev = ascii_field(cpio_data, RDEV_START, FIELD_SIZE, OCTAL)?;
+
+    Ok(CPIOEntryHeader {
+        magic: cpio_data[..FIELD_SIZE].to_vec(),
+        file_name,
+        data_size: file_size,
+        header_size: CPIO_ODC_HEADER_SIZE + file_name_size,
+        file_size,
+        mode: ascii_field(cpio_data, MODE_START, FIELD_SIZE, OCTAL)?,
+        inode: ascii_field(cpio_data, INODE_START, FIELD_SIZE, OCTAL)?,
+        nlink: ascii_field(cpio_data, NLINK_START, FIELD_SIZE, OCTAL)?,
+        rdev_major: (rdev >> 8) & 0xFF,
+        rdev_minor: rdev & 0xFF,
+    })
+}
+
+/// Converts an ASCII number field in a CPIO header to an integer
+fn ascii_field(
+    cpio_data: &[u8],
+    start: usize,
+    size: usize,
+    radix: u32,
+) -> Result<usize, StructureError> {
+    if let Some(field_bytes) = cpio_data.get(start..start + size) {
+        if let Ok(field_str) = std::str::from_utf8(field_bytes) {
+            if let Ok(value) = usize::from_str_radix(field_str, radix) {
+                return Ok(value);
             }
         }
     }
@@ -72,6 +126,31 @@ pub fn parse_cpio_entry_header(cpio_data: &[u8]) -> Result<CPIOEntryHeader, Stru
     Err(StructureError)
 }
 
+/// Gets the NULL-terminated file name that follows a CPIO header
+fn entry_file_name(
+    cpio_data: &[u8],
+    header_size: usize,
+    file_name_size: usize,
+) -> Result<String, StructureError> {
+    const NULL_BYTE_SIZE: usize = 1;
+
+    // The file name size includes the NULL terminator
+    if file_name_size < NULL_BYTE_SIZE {
+        return Err(StructureError);
+    }
+
+    let file_name_start: usize = header_size;
+    let file_name_end: usize = file_name_start + file_name_size - NULL_BYTE_SIZE;
+
+    if let Some(file_name_raw_bytes) = cpio_data.get(file_name_start..file_name_end) {
+        if let Ok(file_name) = String::from_utf8(file_name_raw_bytes.to_vec()) {
+            return Ok(file_name);
+        }
+    }
+
+    Err(StructureError)
+}
+
 /// File data and CPIO headers are padded to 4-byte boundaries
 fn byte_padding(n: usize) -> usize {
     let modulus: usize = n % 4;
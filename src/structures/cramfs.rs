@@ -6,6 +6,7 @@ pub struct CramFSHeader {
     pub size: usize,
     pub checksum: u32,
     pub file_count: usize,
+    pub flags: usize,
     pub endianness: String,
 }
 
@@ -72,6 +73,7 @@ pub fn parse_cramfs_header(cramfs_data: &[u8]) -> Result<CramFSHeader, Structure
                 cramfs_info.size = cramfs_header["size"];
                 cramfs_info.checksum = cramfs_header["checksum"] as u32;
                 cramfs_info.file_count = cramfs_header["file_count"];
+                cramfs_info.flags = cramfs_header["flags"];
 
                 return Ok(cramfs_info);
             }
@@ -80,3 +82,62 @@ pub fn parse_cramfs_header(cramfs_data: &[u8]) -> Result<CramFSHeader, Structure
 
     Err(StructureError)
 }
+
+/// Offset of the root directory inode, relative to the start of the CramFS image
+pub const CRAMFS_ROOT_INODE_OFFSET: usize = 64;
+
+/// Size of a CramFS inode structure
+pub const CRAMFS_INODE_SIZE: usize = 12;
+
+/// Struct to store info about a CramFS inode
+#[derive(Default, Debug, Clone)]
+pub struct CramFSInode {
+    pub mode: usize,
+    /// File size; for device files this is the device number
+    pub size: usize,
+    /// Length of the file name that follows the inode, in bytes
+    pub name_length: usize,
+    /// Offset of the file data, relative to the start of the CramFS image
+    pub data_offset: usize,
+}
+
+/// Parses a CramFS inode
+pub fn parse_cramfs_inode(
+    inode_data: &[u8],
+    endianness: &str,
+) -> Result<CramFSInode, StructureError> {
+    // Names and data offsets are stored as a count of 4-byte words
+    const WORD_SIZE: usize = 4;
+
+    let cramfs_inode_structure = vec![
+        ("mode_uid", "u32"),
+        ("size_gid", "u32"),
+        ("namelen_offset", "u32"),
+    ];
+
+    let inode = common::parse(inode_data, &cramfs_inode_structure, endianness)?;
+
+    /*
+     * The inode fields are C bit fields, so their layout depends on the endianness of the host that created the image:
+     *
+     *   mode: 16 bits, uid: 16 bits
+     *   size: 24 bits, gid: 8 bits
+     *   namelen: 6 bits, offset: 26 bits
+     */
+    let cramfs_inode = match endianness {
+        "big" => CramFSInode {
+            mode: inode["mode_uid"] >> 16,
+            size: inode["size_gid"] >> 8,
+            name_length: (inode["namelen_offset"] >> 26) * WORD_SIZE,
+            data_offset: (inode["namelen_offset"] & 0x3FFFFFF) * WORD_SIZE,
+        },
+        _ => CramFSInode {
+            mode: inode["mode_uid"] & 0xFFFF,
+            size: inode["size_gid"] & 0xFFFFFF,
+            name_length: (inode["namelen_offset"] & 0x3F) * WORD_SIZE,
+            data_offset: (inode["namelen_offset"] >> 6) * WORD_SIZE,
+        },
+    };
+
+    Ok(cramfs_inode)
+}
@@ -0,0 +1,261 @@
+use crate::structures::common::{self, StructureError};
+use adler32::RollingAdler32;
+
+/// Stores info about a Dalvik executable (DEX) file
+#[derive(Debug, Default, Clone)]
+pub struct DexHeader {
+    /// DEX format version, e.g., "035"
+    pub version: String,
+    pub size: usize,
+    pub string_count: usize,
+    pub type_count: usize,
+    pub method_count: usize,
+    pub class_count: usize,
+}
+
+/// Parses a DEX file header, validating its checksum
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::dex::parse_dex_header;
+///
+/// let mut dex = b"dex\n035\x00\x00\x00\x00\x00".to_vec();
+/// dex.extend([0; 20]);
+/// dex.extend(b"\x70\x00\x00\x00\x70\x00\x00\x00\x78\x56\x34\x12");
+/// dex.extend([0; 0x70 - 0x2C]);
+///
+/// // Adler-32 checksum of everything after the checksum field
+/// dex[8..12].copy_from_slice(&0x915001F5_u32.to_le_bytes());
+///
+/// let dex_header = parse_dex_header(&dex).unwrap();
+///
+/// assert_eq!(dex_header.version, "035");
+/// assert_eq!(dex_header.size, 0x70);
+/// ```
+pub fn parse_dex_header(dex_data: &[u8]) -> Result<DexHeader, StructureError> {
+    const DEX_MAGIC: &[u8] = b"dex\n";
+    const ENDIAN_CONSTANT: usize = 0x12345678;
+    const MIN_HEADER_SIZE: usize = 0x70;
+    // The checksum covers everything after the magic and the checksum itself
+    const CHECKSUM_START: usize = 12;
+
+    let dex_structure = vec![
+        ("magic", "u32"),
+        ("version", "u32"),
+        ("checksum", "u32"),
+        ("signature_1", "u64"),
+        ("signature_2", "u64"),
+        ("signature_3", "u32"),
+        ("file_size", "u32"),
+        ("header_size", "u32"),
+        ("endian_tag", "u32"),
+        ("link_size", "u32"),
+        ("link_offset", "u32"),
+        ("map_offset", "u32"),
+        ("string_ids_size", "u32"),
+        ("string_ids_offset", "u32"),
+        ("type_ids_size", "u32"),
+        ("type_ids_offset", "u32"),
+        ("proto_ids_size", "u32"),
+        ("proto_ids_offset", "u32"),
+        ("field_ids_size", "u32"),
+        ("field_ids_offset", "u32"),
+        ("method_ids_size", "u32"),
+        ("method_ids_offset", "u32"),
+        ("class_defs_size", "u32"),
+        ("class_defs_offset", "u32"),
+        ("data_size", "u32"),
+        ("data_offset", "u32"),
+    ];
+
+    if !dex_data.starts_with(DEX_MAGIC) {
+        return Err(StructureError);
+    }
+
+    let version = parse_version(dex_data.get(DEX_MAGIC.len()..).ok_or(StructureError)?)?;
+    let dex_header = common::parse(dex_data, &dex_structure, "little")?;
+
+    if dex_header["endian_tag"] != ENDIAN_CONSTANT
+        || dex_header["header_size"] < MIN_HEADER_SIZE
+        || dex_header["file_size"] < dex_header["header_size"]
+    {
+        return Err(StructureError);
+    }
+
+    let checksum_data = dex_data
+        .get(CHECKSUM_START..dex_header["file_size"])
+        .ok_or(StructureError)?;
+
+    if RollingAdler32::from_buffer(checksum_data).hash() as usize != dex_header["checksum"] {
+        return Err(StructureError);
+    }
+
+    Ok(DexHeader {
+        version,
+        size: dex_header["file_size"],
+        string_count: dex_header["string_ids_size"],
+        type_count: dex_header["type_ids_size"],
+        method_count: dex_header["method_ids_size"],
+        class_count: dex_header["class_defs_size"],
+    })
+}
+
+/// Stores info about an optimized DEX (ODEX) file
+#[derive(Debug, Default, Clone)]
+pub struct OdexHeader {
+    pub version: String,
+    /// Offset of the embedded DEX file, relative to the start of the ODEX file
+    pub dex_offset: usize,
+    pub size: usize,
+}
+
+/// Parses a Dalvik optimized DEX (ODEX) file header
+pub fn parse_odex_header(odex_data: &[u8]) -> Result<OdexHeader, StructureError> {
+    const ODEX_MAGIC: &[u8] = b"dey\n";
+
+    let odex_structure = vec![
+        ("magic", "u32"),
+        ("version", "u32"),
+        ("dex_offset", "u32"),
+        ("dex_length", "u32"),
+        ("deps_offset", "u32"),
+        ("deps_length", "u32"),
+        ("opt_offset", "u32"),
+        ("opt_length", "u32"),
+        ("flags", "u32"),
+        ("checksum", "u32"),
+    ];
+
+    if !odex_data.starts_with(ODEX_MAGIC) {
+        return Err(StructureError);
+    }
+
+    let version = parse_version(odex_data.get(ODEX_MAGIC.len()..).ok_or(StructureError)?)?;
+    let odex_header = common::parse(odex_data, &odex_structure, "little")?;
+
+    // The DEX file, its dependencies and the optimized data follow the ODEX header, in that order
+    if odex_header["dex_offset"] < common::size(&odex_structure)
+        || odex_header["deps_offset"] < odex_header["dex_offset"] + odex_header["dex_length"]
+        || odex_header["opt_offset"] < odex_header["deps_offset"] + odex_header["deps_length"]
+    {
+        return Err(StructureError);
+    }
+
+    // The embedded DEX file must be valid
+    parse_dex_header(
+        odex_data
+            .get(odex_header["dex_offset"]..)
+            .ok_or(StructureError)?,
+    )?;
+
+    Ok(OdexHeader {
+        version,
+        dex_offset: odex_header["dex_offset"],
+        size: odex_header["opt_offset"] + odex_header["opt_length"],
+    })
+}
+
+/// Stores info about an ART verified DEX (VDEX) file
+#[derive(Debug, Default, Clone)]
+pub struct VdexHeader {
+    pub version: String,
+    /// Number of DEX files the VDEX file describes, if known
+    pub dex_count: Option<usize>,
+    /// Size of the VDEX file, if known
+    pub size: Option<usize>,
+}
+
+/// Parses an ART VDEX file header; the section layout is only known for VDEX version 027 and later
+pub fn parse_vdex_header(vdex_data: &[u8]) -> Result<VdexHeader, StructureError> {
+    const VDEX_MAGIC: &[u8] = b"vdex";
+    const SECTIONED_VERSION: usize = 27;
+    const SECTION_COUNT: usize = 4;
+    // Each DEX file has a 4-byte checksum in the checksum section
+    const CHECKSUM_SECTION: usize = 0;
+    const DEX_CHECKSUM_SIZE: usize = 4;
+
+    let vdex_structure = vec![
+        ("magic", "u32"),
+        ("version", "u32"),
+        ("section_count", "u32"),
+    ];
+    let section_structure = vec![("kind", "u32"), ("offset", "u32"), ("size", "u32")];
+
+    if !vdex_data.starts_with(VDEX_MAGIC) {
+        return Err(StructureError);
+    }
+
+    let version = parse_version(vdex_data.get(VDEX_MAGIC.len()..).ok_or(StructureError)?)?;
+
+    let mut vdex_info = VdexHeader {
+        version: version.clone(),
+        ..Default::default()
+    };
+
+    if version.parse::<usize>().map_err(|_| StructureError)? < SECTIONED_VERSION {
+        return Ok(vdex_info);
+    }
+
+    let vdex_header = common::parse(vdex_data, &vdex_structure, "little")?;
+
+    if vdex_header["section_count"] != SECTION_COUNT {
+        return Err(StructureError);
+    }
+
+    let sections_start = common::size(&vdex_structure);
+    let mut vdex_size = sections_start + (SECTION_COUNT * common::size(&section_structure));
+
+    for i in 0..SECTION_COUNT {
+        let section = common::parse(
+            vdex_data
+                .get(sections_start + (i * common::size(&section_structure))..)
+                .ok_or(StructureError)?,
+            &section_structure,
+            "little",
+        )?;
+
+        // Sections are listed in order of their kind
+        if section["kind"] != i {
+            return Err(StructureError);
+        }
+
+        if section["kind"] == CHECKSUM_SECTION {
+            vdex_info.dex_count = Some(section["size"] / DEX_CHECKSUM_SIZE);
+        }
+
+        vdex_size = std::cmp::max(vdex_size, section["offset"] + section["size"]);
+    }
+
+    vdex_info.size = Some(vdex_size);
+
+    Ok(vdex_info)
+}
+
+/// Parses the version of an ART image (boot.art) file header
+pub fn parse_art_version(art_data: &[u8]) -> Result<String, StructureError> {
+    const ART_MAGIC: &[u8] = b"art\n";
+
+    if !art_data.starts_with(ART_MAGIC) {
+        return Err(StructureError);
+    }
+
+    parse_version(art_data.get(ART_MAGIC.len()..).ok_or(StructureError)?)
+}
+
+/// DEX, ODEX, VDEX and ART file versions are three ASCII digits, followed by a NULL byte
+fn parse_version(version_data: &[u8]) -> Result<String, StructureError> {
+    const VERSION_SIZE: usize = 4;
+
+    let version_bytes = version_data.get(..VERSION_SIZE).ok_or(StructureError)?;
+
+    if version_bytes[VERSION_SIZE - 1] != 0
+        || !version_bytes[..VERSION_SIZE - 1]
+            .iter()
+            .all(|byte| byte.is_ascii_digit())
+    {
+        return Err(StructureError);
+    }
+
+    Ok(String::from_utf8_lossy(&version_bytes[..VERSION_SIZE - 1]).to_string())
+}
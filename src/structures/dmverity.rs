@@ -0,0 +1,161 @@
+use crate::common::get_cstring;
+use crate::structures::common::{self, StructureError};
+
+/// Struct to store info about a dm-verity hash device superblock, as created by veritysetup
+#[derive(Debug, Default, Clone)]
+pub struct VeritySuperblock {
+    pub version: usize,
+    /// 0 for the original Chrome OS format, 1 for the normal format
+    pub hash_type: usize,
+    pub uuid: String,
+    /// Hash algorithm, e.g., "sha256"
+    pub algorithm: String,
+    pub data_block_size: usize,
+    pub hash_block_size: usize,
+    /// Number of data blocks protected by the hash tree
+    pub data_blocks: usize,
+    pub salt: Vec<u8>,
+    /// Size of the superblock and hash tree
+    pub total_size: usize,
+}
+
+/// Parses a dm-verity superblock
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::dmverity::parse_verity_superblock;
+///
+/// let mut superblock = b"verity\x00\x00\x01\x00\x00\x00\x01\x00\x00\x00".to_vec();
+/// superblock.extend([0xAB; 16]);
+/// superblock.extend(b"sha256");
+/// superblock.resize(64, 0);
+/// superblock.extend(b"\x00\x10\x00\x00\x00\x10\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00\x20\x00");
+/// superblock.resize(512, 0);
+///
+/// let verity_superblock = parse_verity_superblock(&superblock).unwrap();
+///
+/// assert_eq!(verity_superblock.algorithm, "sha256");
+/// assert_eq!(verity_superblock.data_blocks, 256);
+/// assert_eq!(verity_superblock.salt.len(), 32);
+/// assert_eq!(verity_superblock.total_size, 0x4000);
+/// ```
+pub fn parse_verity_superblock(superblock_data: &[u8]) -> Result<VeritySuperblock, StructureError> {
+    const SUPERBLOCK_SIZE: usize = 512;
+    const UUID_START: usize = 16;
+    const UUID_END: usize = 32;
+    const ALGORITHM_START: usize = 32;
+    const ALGORITHM_END: usize = 64;
+    const BLOCK_INFO_START: usize = 64;
+    const SALT_START: usize = 88;
+    const MAX_SALT_SIZE: usize = 256;
+    const MIN_BLOCK_SIZE: usize = 512;
+    const MAX_BLOCK_SIZE: usize = 512 * 1024;
+
+    let superblock_structure = vec![("magic", "u64"), ("version", "u32"), ("hash_type", "u32")];
+
+    let block_info_structure = vec![
+        ("data_block_size", "u32"),
+        ("hash_block_size", "u32"),
+        ("data_blocks", "u64"),
+        ("salt_size", "u16"),
+    ];
+
+    let superblock_data = superblock_data
+        .get(..SUPERBLOCK_SIZE)
+        .ok_or(StructureError)?;
+    let superblock = common::parse(superblock_data, &superblock_structure, "little")?;
+    let block_info = common::parse(
+        &superblock_data[BLOCK_INFO_START..],
+        &block_info_structure,
+        "little",
+    )?;
+
+    let is_valid_block_size = |block_size: usize| {
+        block_size.is_power_of_two() && (MIN_BLOCK_SIZE..=MAX_BLOCK_SIZE).contains(&block_size)
+    };
+
+    if superblock["version"] != 1
+        || superblock["hash_type"] > 1
+        || block_info["data_blocks"] == 0
+        || block_info["salt_size"] > MAX_SALT_SIZE
+        || !is_valid_block_size(block_info["data_block_size"])
+        || !is_valid_block_size(block_info["hash_block_size"])
+    {
+        return Err(StructureError);
+    }
+
+    let algorithm = get_cstring(&superblock_data[ALGORITHM_START..ALGORITHM_END]);
+    let digest_size = digest_size(&algorithm).ok_or(StructureError)?;
+
+    Ok(VeritySuperblock {
+        version: superblock["version"],
+        hash_type: superblock["hash_type"],
+        uuid: format_uuid(&superblock_data[UUID_START..UUID_END]),
+        algorithm,
+        data_block_size: block_info["data_block_size"],
+        hash_block_size: block_info["hash_block_size"],
+        data_blocks: block_info["data_blocks"],
+        salt: superblock_data[SALT_START..SALT_START + block_info["salt_size"]].to_vec(),
+        total_size: hash_tree_size(
+            block_info["data_blocks"],
+            block_info["hash_block_size"],
+            digest_size,
+        )
+        .saturating_add(SUPERBLOCK_SIZE.next_multiple_of(block_info["hash_block_size"])),
+    })
+}
+
+/// Returns the size of a dm-verity hash tree, calculated the same way as veritysetup does
+fn hash_tree_size(data_blocks: usize, hash_block_size: usize, digest_size: usize) -> usize {
+    // Each hash block holds a power of two number of hashes
+    let hashes_per_block_bits = (hash_block_size / digest_size).ilog2() as usize;
+
+    let mut levels: usize = 0;
+
+    while hashes_per_block_bits * levels < 64
+        && (data_blocks - 1) >> (hashes_per_block_bits * levels) != 0
+    {
+        levels += 1;
+    }
+
+    let hash_blocks: usize = (0..levels)
+        .map(|level| {
+            let shift = (level + 1) * hashes_per_block_bits;
+            match shift >= 64 {
+                true => 1,
+                false => data_blocks.div_ceil(1 << shift),
+            }
+        })
+        .sum();
+
+    hash_blocks.saturating_mul(hash_block_size)
+}
+
+/// Returns the digest size, in bytes, of hash algorithms supported by dm-verity
+fn digest_size(algorithm: &str) -> Option<usize> {
+    match algorithm {
+        "sha1" => Some(20),
+        "sha224" => Some(28),
+        "sha256" => Some(32),
+        "sha384" => Some(48),
+        "sha512" => Some(64),
+        "blake2s-256" => Some(32),
+        "blake2b-512" => Some(64),
+        _ => None,
+    }
+}
+
+/// Formats a binary UUID in the canonical 8-4-4-4-12 format
+fn format_uuid(uuid_bytes: &[u8]) -> String {
+    let hex: String = uuid_bytes.iter().map(|b| format!("{b:02x}")).collect();
+
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
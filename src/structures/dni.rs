@@ -0,0 +1,80 @@
+use crate::common::get_cstring;
+use crate::structures::common::StructureError;
+
+/// Size of a DNI firmware header; the header is NULL padded text
+pub const DNI_HEADER_SIZE: usize = 128;
+
+/// Storage struct for DNI header info
+#[derive(Debug, Clone, Default)]
+pub struct DNIHeader {
+    /// Board ID of the device the firmware is for
+    pub device: String,
+    pub version: String,
+    /// Region code; empty for worldwide firmware
+    pub region: String,
+    /// Hardware ID, if specified
+    pub hd_id: String,
+}
+
+/// Parse a DNI firmware header, e.g., "device:R7000\nversion:V1.0.0.0\nregion:\n"
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::dni::parse_dni_header;
+///
+/// let mut header = b"device:WNR2000v5\nversion:V1.0.0.42\nregion:NA\nhd_id:29764841+0+128+64\n".to_vec();
+/// header.resize(128, 0);
+///
+/// let dni_header = parse_dni_header(&header).unwrap();
+///
+/// assert_eq!(dni_header.device, "WNR2000v5");
+/// assert_eq!(dni_header.version, "V1.0.0.42");
+/// assert_eq!(dni_header.region, "NA");
+/// assert_eq!(dni_header.hd_id, "29764841+0+128+64");
+/// ```
+pub fn parse_dni_header(header_data: &[u8]) -> Result<DNIHeader, StructureError> {
+    const DEVICE_KEY: &str = "device";
+    const VERSION_KEY: &str = "version";
+    const REGION_KEY: &str = "region";
+    const HD_ID_KEY: &str = "hd_id";
+
+    let mut dni_header = DNIHeader {
+        ..Default::default()
+    };
+
+    if let Some(raw_header) = header_data.get(0..DNI_HEADER_SIZE) {
+        let header_text = get_cstring(raw_header);
+
+        // The rest of the header must be NULL padding
+        if raw_header[header_text.len()..].iter().any(|b| *b != 0) {
+            return Err(StructureError);
+        }
+
+        let mut lines = header_text.lines();
+
+        // The first line must always be the device board ID
+        match lines.next().and_then(|line| line.split_once(':')) {
+            Some((DEVICE_KEY, device)) if !device.is_empty() => {
+                dni_header.device = device.to_string()
+            }
+            _ => return Err(StructureError),
+        }
+
+        for line in lines {
+            match line.split_once(':') {
+                Some((VERSION_KEY, version)) => dni_header.version = version.to_string(),
+                Some((REGION_KEY, region)) => dni_header.region = region.to_string(),
+                Some((HD_ID_KEY, hd_id)) => dni_header.hd_id = hd_id.to_string(),
+                _ => return Err(StructureError),
+            }
+        }
+
+        // Version strings always start with a 'V'
+        if dni_header.version.starts_with('V') {
+            return Ok(dni_header);
+        }
+    }
+
+    Err(StructureError)
+}
@@ -1,4 +1,4 @@
-use crate::common::get_cstring;
+use crate::common::{get_cstring, is_offset_safe};
 use crate::structures::common::{self, StructureError};
 
 /// Struct to store DTB info
@@ -9,6 +9,7 @@ pub struct DTBHeader {
     pub cpu_id: usize,
     pub struct_offset: usize,
     pub strings_offset: usize,
+    pub mem_reservation_offset: usize,
     pub struct_size: usize,
     pub strings_size: usize,
 }
@@ -58,6 +59,7 @@ pub fn parse_dtb_header(dtb_data: &[u8]) -> Result<DTBHeader, StructureError> {
                         cpu_id: dtb_header["cpu_id"],
                         struct_offset: dtb_header["dt_struct_offset"],
                         strings_offset: dtb_header["dt_strings_offset"],
+                        mem_reservation_offset: dtb_header["mem_reservation_block_offset"],
                         struct_size: dtb_header["dt_struct_size"],
                         strings_size: dtb_header["dt_strings_size"],
                     });
@@ -149,6 +151,119 @@ pub fn parse_dtb_node(dtb_header: &DTBHeader, dtb_data: &[u8], node_offset: usiz
     node
 }
 
+/// A node in a parsed device tree, along with all of its properties and child nodes
+#[derive(Debug, Default, Clone)]
+pub struct DTBTreeNode {
+    /// Node name; the root node's name is empty
+    pub name: String,
+    /// Property names and values, in the order they appear in the DTB
+    pub properties: Vec<(String, Vec<u8>)>,
+    pub children: Vec<DTBTreeNode>,
+}
+
+impl DTBTreeNode {
+    /// Returns the value of the named property, if it exists
+    pub fn property(&self, name: &str) -> Option<&[u8]> {
+        self.properties
+            .iter()
+            .find(|(property_name, _)| property_name == name)
+            .map(|(_, value)| value.as_slice())
+    }
+
+    /// Returns the named child node, if it exists
+    pub fn child(&self, name: &str) -> Option<&DTBTreeNode> {
+        self.children.iter().find(|child| child.name == name)
+    }
+}
+
+/// Parses the DTB structure block into a tree of nodes, returning the root node
+pub fn parse_dtb_tree(
+    dtb_header: &DTBHeader,
+    dtb_data: &[u8],
+) -> Result<DTBTreeNode, StructureError> {
+    // Real device trees are only nested a handful of levels deep
+    const MAX_DEPTH: usize = 64;
+
+    // Nodes that have been opened, but not yet closed
+    let mut open_nodes: Vec<DTBTreeNode> = vec![];
+    let mut root_node: Option<DTBTreeNode> = None;
+
+    let mut entry_offset = dtb_header.struct_offset;
+    let mut previous_entry_offset = None;
+    let available_data = dtb_data.len();
+
+    while is_offset_safe(available_data, entry_offset, previous_entry_offset) {
+        let node = parse_dtb_node(dtb_header, dtb_data, entry_offset);
+
+        if node.begin {
+            // There can be only one root node
+            if root_node.is_some() || open_nodes.len() >= MAX_DEPTH {
+                break;
+            }
+
+            open_nodes.push(DTBTreeNode {
+                name: node.name,
+                ..Default::default()
+            });
+        } else if node.end {
+            match open_nodes.pop() {
+                None => break,
+                Some(closed_node) => match open_nodes.last_mut() {
+                    None => root_node = Some(closed_node),
+                    Some(parent_node) => parent_node.children.push(closed_node),
+                },
+            }
+        } else if node.eof {
+            // The structure block is only valid if all nodes were closed before the end marker
+            if open_nodes.is_empty() {
+                if let Some(root_node) = root_node {
+                    return Ok(root_node);
+                }
+            }
+            break;
+        } else if node.property {
+            match open_nodes.last_mut() {
+                None => break,
+                Some(current_node) => current_node.properties.push((node.name, node.data)),
+            }
+        } else if !node.nop {
+            break;
+        }
+
+        previous_entry_offset = Some(entry_offset);
+        entry_offset += node.total_size;
+    }
+
+    Err(StructureError)
+}
+
+/// Parses the DTB memory reservation block, returning the address and size of each reserved memory region
+pub fn parse_dtb_memory_reservations(
+    dtb_header: &DTBHeader,
+    dtb_data: &[u8],
+) -> Result<Vec<(usize, usize)>, StructureError> {
+    let reservation_structure = vec![("address", "u64"), ("size", "u64")];
+
+    let reservation_size = common::size(&reservation_structure);
+    let mut reservations: Vec<(usize, usize)> = vec![];
+    let mut reservation_offset = dtb_header.mem_reservation_offset;
+
+    // The list of reservations is terminated by an entry with an address and size of zero
+    loop {
+        let reservation_data = dtb_data.get(reservation_offset..).ok_or(StructureError)?;
+        let reservation = common::parse(reservation_data, &reservation_structure, "big")?;
+
+        if reservation["address"] == 0 && reservation["size"] == 0 {
+            break;
+        }
+
+        reservations.push((reservation["address"], reservation["size"]));
+        reservation_offset += reservation_size;
+    }
+
+    Ok(reservations)
+}
+
 /// DTB entries must be aligned to 4-byte boundaries
 fn dtb_aligned(len: usize) -> usize {
     const ALIGNMENT: usize = 4;
@@ -1,12 +1,26 @@
 use crate::common::{crc32, is_offset_safe};
 use crate::structures::common::{self, StructureError};
+use crate::structures::uefi::guid_to_string;
 
 const BLOCK_SIZE: usize = 512;
 
+/// Struct to store EFI GPT partition info
+#[derive(Debug, Default, Clone)]
+pub struct GPTPartition {
+    /// Offset of the partition, relative to the start of the disk image
+    pub start: usize,
+    pub size: usize,
+    /// Partition name, from the partition entry
+    pub name: String,
+    /// Descriptive name of the partition type, or the type GUID if the type is not well-known
+    pub type_name: String,
+}
+
 /// Struct to store EFI GPT header info
 #[derive(Debug, Default, Clone)]
 pub struct EFIGPTHeader {
     pub total_size: usize,
+    pub partitions: Vec<GPTPartition>,
 }
 
 /// Parses an EFI GPT header
@@ -72,11 +86,11 @@ pub fn parse_efigpt_header(efi_data: &[u8]) -> Result<EFIGPTHeader, StructureErr
                                 if let Some(partition) = parse_gpt_partition_entry(
                                     &partition_entries_data[next_partition_offset..],
                                 ) {
-                                    // EOF is the end of the farthest away partition
-                                    if partition.start_offset < partition.end_offset
-                                        && partition.end_offset > result.total_size
-                                    {
-                                        result.total_size = partition.end_offset;
+                                    if partition.size > 0 {
+                                        // EOF is the end of the farthest away partition
+                                        result.total_size =
+                                            result.total_size.max(partition.start + partition.size);
+                                        result.partitions.push(partition);
                                     }
                                 }
 
@@ -97,14 +111,12 @@ pub fn parse_efigpt_header(efi_data: &[u8]) -> Result<EFIGPTHeader, StructureErr
     Err(StructureError)
 }
 
-#[derive(Debug, Default, Clone)]
-struct GPTPartitionEntry {
-    pub end_offset: usize,
-    pub start_offset: usize,
-}
-
 /// Parse a GPT partition entry
-fn parse_gpt_partition_entry(entry_data: &[u8]) -> Option<GPTPartitionEntry> {
+fn parse_gpt_partition_entry(entry_data: &[u8]) -> Option<GPTPartition> {
+    const TYPE_GUID_END: usize = 16;
+    const NAME_START: usize = 56;
+    const NAME_END: usize = 128;
+
     let entry_structure = vec![
         ("type_guid_p1", "u64"),
         ("type_guid_p2", "u64"),
@@ -115,23 +127,63 @@ fn parse_gpt_partition_entry(entry_data: &[u8]) -> Option<GPTPartitionEntry> {
         ("attributes", "u64"),
     ];
 
-    let mut result = GPTPartitionEntry {
-        ..Default::default()
-    };
-
     if let Ok(entry_header) = common::parse(entry_data, &entry_structure, "little") {
         // GUID types of NULL can be ignored
         if entry_header["type_guid_p1"] != 0 && entry_header["type_guid_p2"] != 0 {
-            result.start_offset = lba_to_offset(entry_header["starting_lba"]);
-            result.end_offset = lba_to_offset(entry_header["ending_lba"]);
-            return Some(result);
+            let type_guid = guid_to_string(&entry_data[..TYPE_GUID_END]);
+
+            // The partition name is a NULL terminated UTF-16 string
+            let name_characters: Vec<u16> = entry_data
+                .get(NAME_START..NAME_END)
+                .unwrap_or_default()
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .take_while(|c| *c != 0)
+                .collect();
+
+            // The ending LBA is inclusive
+            let start = lba_to_offset(entry_header["starting_lba"]);
+            let end = lba_to_offset(entry_header["ending_lba"].saturating_add(1));
+
+            return Some(GPTPartition {
+                start,
+                size: end.saturating_sub(start),
+                name: String::from_utf16_lossy(&name_characters),
+                type_name: partition_type_name(&type_guid),
+            });
         }
     }
 
     None
 }
 
+/// Returns a descriptive name for well-known partition type GUIDs
+fn partition_type_name(type_guid: &str) -> String {
+    let type_name = match type_guid {
+        "C12A7328-F81F-11D2-BA4B-00A0C93EC93B" => "EFI System",
+        "21686148-6449-6E6F-744E-656564454649" => "BIOS boot",
+        "0FC63DAF-8483-4772-8E79-3D69D8477DE4" => "Linux filesystem",
+        "4F68BCE3-E8CD-4DB1-96E7-FBCAF984B709" => "Linux root (x86-64)",
+        "B921B045-1DF0-41C3-AF44-4C6F280D3FAE" => "Linux root (ARM64)",
+        "933AC7E1-2EB4-4F13-B844-0E14E2AEF915" => "Linux home",
+        "0657FD6D-A4AB-43C4-84E5-0933C84B4F4F" => "Linux swap",
+        "E6D6D379-F507-44C2-A23C-238F2A3DF928" => "Linux LVM",
+        "A19D880F-05FC-4D3B-A006-743F0F84911E" => "Linux RAID",
+        "EBD0A0A2-B9E5-4433-87C0-68B6B72699C7" => "Microsoft basic data",
+        "E3C9E316-0B5C-4DB8-817D-F92DF00215AE" => "Microsoft reserved",
+        "DE94BBA4-06D1-4D40-A16A-BFD50179D6AC" => "Windows recovery",
+        "48465300-0000-11AA-AA11-00306543ECAC" => "Apple HFS+",
+        "7C3457EF-0000-11AA-AA11-00306543ECAC" => "Apple APFS",
+        "FE3A2A5D-4F32-41A7-B725-ACCC3285A309" => "ChromeOS kernel",
+        "3CB8E202-3B7E-47DD-8A3C-7FF2A13CFCEC" => "ChromeOS root",
+        "516E7CB6-6ECF-11D6-8FF8-00022D09712B" => "FreeBSD UFS",
+        _ => type_guid,
+    };
+
+    type_name.to_string()
+}
+
 // Convert LBA to offset
 fn lba_to_offset(lba: usize) -> usize {
-    lba * BLOCK_SIZE
+    lba.saturating_mul(BLOCK_SIZE)
 }
@@ -1,3 +1,4 @@
+use crate::common::get_cstring;
 use crate::structures::common::{self, StructureError};
 use std::collections::HashMap;
 
@@ -317,3 +318,446 @@ pub fn parse_elf_header(elf_data: &[u8]) -> Result<ELFHeader, StructureError> {
 
     Err(StructureError)
 }
+
+/// Stores info about an ELF section
+#[derive(Debug, Default, Clone)]
+pub struct ELFSection {
+    pub name: String,
+    pub section_type: usize,
+    /// Offset of the section data, relative to the start of the ELF file
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// Parses the ELF section header table
+pub fn parse_elf_sections(elf_data: &[u8]) -> Result<Vec<ELFSection>, StructureError> {
+    const ELF_IDENT_STRUCT_SIZE: usize = 16;
+    // Sections of this type have no data in the file
+    const SHT_NOBITS: usize = 8;
+    // Sanity limit on the number of sections
+    const MAX_SECTION_COUNT: usize = 0xFF00;
+
+    let elf_header = parse_elf_header(elf_data)?;
+
+    // Address and offset sizes depend on the ELF class
+    let word_type = match elf_header.class.as_str() {
+        "64" => "u64",
+        _ => "u32",
+    };
+
+    let elf_offsets_structure = vec![
+        ("type", "u16"),
+        ("machine", "u16"),
+        ("version", "u32"),
+        ("entry", word_type),
+        ("phoff", word_type),
+        ("shoff", word_type),
+        ("flags", "u32"),
+        ("ehsize", "u16"),
+        ("phentsize", "u16"),
+        ("phnum", "u16"),
+        ("shentsize", "u16"),
+        ("shnum", "u16"),
+        ("shstrndx", "u16"),
+    ];
+
+    let section_structure = vec![
+        ("name", "u32"),
+        ("type", "u32"),
+        ("flags", word_type),
+        ("addr", word_type),
+        ("offset", word_type),
+        ("size", word_type),
+        ("link", "u32"),
+        ("info", "u32"),
+        ("addralign", word_type),
+        ("entsize", word_type),
+    ];
+
+    let elf_offsets = common::parse(
+        elf_data
+            .get(ELF_IDENT_STRUCT_SIZE..)
+            .ok_or(StructureError)?,
+        &elf_offsets_structure,
+        &elf_header.endianness,
+    )?;
+
+    if elf_offsets["shoff"] == 0
+        || elf_offsets["shoff"] >= elf_data.len()
+        || elf_offsets["shnum"] == 0
+        || elf_offsets["shnum"] > MAX_SECTION_COUNT
+        || elf_offsets["shentsize"] < common::size(&section_structure)
+        || elf_offsets["shstrndx"] >= elf_offsets["shnum"]
+    {
+        return Err(StructureError);
+    }
+
+    let mut raw_sections = vec![];
+
+    for i in 0..elf_offsets["shnum"] {
+        let section_offset = elf_offsets["shoff"] + (i * elf_offsets["shentsize"]);
+        let section_header = common::parse(
+            elf_data.get(section_offset..).ok_or(StructureError)?,
+            &section_structure,
+            &elf_header.endianness,
+        )?;
+        raw_sections.push(section_header);
+    }
+
+    // Section names are stored in the section header string table
+    let string_table = &raw_sections[elf_offsets["shstrndx"]];
+    let string_table_data = elf_data
+        .get(string_table["offset"]..)
+        .and_then(|data| data.get(..string_table["size"]))
+        .ok_or(StructureError)?;
+
+    let mut sections = vec![];
+
+    for raw_section in raw_sections {
+        let mut section = ELFSection {
+            section_type: raw_section["type"],
+            offset: raw_section["offset"],
+            size: raw_section["size"],
+            ..Default::default()
+        };
+
+        if let Some(name_data) = string_table_data.get(raw_section["name"]..) {
+            section.name = get_cstring(name_data);
+        }
+
+        // Sections that have data must be within the bounds of the file
+        if section.section_type != SHT_NOBITS
+            && elf_data
+                .get(section.offset..)
+                .and_then(|data| data.get(..section.size))
+                .is_none()
+        {
+            return Err(StructureError);
+        }
+
+        sections.push(section);
+    }
+
+    Ok(sections)
+}
+
+/// Stores info about an ELF program header (segment)
+#[derive(Debug, Default, Clone)]
+pub struct ELFSegment {
+    pub segment_type: usize,
+    pub flags: usize,
+    /// Offset of the segment data, relative to the start of the ELF file
+    pub offset: usize,
+    /// Size of the segment data in the file
+    pub file_size: usize,
+    pub physical_address: usize,
+}
+
+/// Parses the ELF program header table
+pub fn parse_elf_segments(elf_data: &[u8]) -> Result<Vec<ELFSegment>, StructureError> {
+    const ELF_IDENT_STRUCT_SIZE: usize = 16;
+    // Sanity limit on the number of segments
+    const MAX_SEGMENT_COUNT: usize = 0xFFFF;
+
+    let elf_header = parse_elf_header(elf_data)?;
+
+    let word_type = match elf_header.class.as_str() {
+        "64" => "u64",
+        _ => "u32",
+    };
+
+    let elf_offsets_structure = vec![
+        ("type", "u16"),
+        ("machine", "u16"),
+        ("version", "u32"),
+        ("entry", word_type),
+        ("phoff", word_type),
+        ("shoff", word_type),
+        ("flags", "u32"),
+        ("ehsize", "u16"),
+        ("phentsize", "u16"),
+        ("phnum", "u16"),
+    ];
+
+    // The flags field moved in 64-bit program headers
+    let segment_structure = match elf_header.class.as_str() {
+        "64" => vec![
+            ("type", "u32"),
+            ("flags", "u32"),
+            ("offset", "u64"),
+            ("vaddr", "u64"),
+            ("paddr", "u64"),
+            ("filesz", "u64"),
+            ("memsz", "u64"),
+            ("align", "u64"),
+        ],
+        _ => vec![
+            ("type", "u32"),
+            ("offset", "u32"),
+            ("vaddr", "u32"),
+            ("paddr", "u32"),
+            ("filesz", "u32"),
+            ("memsz", "u32"),
+            ("flags", "u32"),
+            ("align", "u32"),
+        ],
+    };
+
+    let elf_offsets = common::parse(
+        elf_data
+            .get(ELF_IDENT_STRUCT_SIZE..)
+            .ok_or(StructureError)?,
+        &elf_offsets_structure,
+        &elf_header.endianness,
+    )?;
+
+    if elf_offsets["phoff"] == 0
+        || elf_offsets["phoff"] >= elf_data.len()
+        || elf_offsets["phnum"] == 0
+        || elf_offsets["phnum"] > MAX_SEGMENT_COUNT
+        || elf_offsets["phentsize"] < common::size(&segment_structure)
+    {
+        return Err(StructureError);
+    }
+
+    let mut segments = vec![];
+
+    for i in 0..elf_offsets["phnum"] {
+        let segment_offset = elf_offsets["phoff"] + (i * elf_offsets["phentsize"]);
+        let segment_header = common::parse(
+            elf_data.get(segment_offset..).ok_or(StructureError)?,
+            &segment_structure,
+            &elf_header.endianness,
+        )?;
+
+        segments.push(ELFSegment {
+            segment_type: segment_header["type"],
+            flags: segment_header["flags"],
+            offset: segment_header["offset"],
+            file_size: segment_header["filesz"],
+            physical_address: segment_header["paddr"],
+        });
+    }
+
+    Ok(segments)
+}
+
+/// Stores info gleaned from an ELF's program interpreter and note segments
+#[derive(Debug, Default, Clone)]
+pub struct ELFProgramInfo {
+    /// Path to the program interpreter, e.g., "/lib64/ld-linux-x86-64.so.2"
+    pub interpreter: String,
+    /// True if the ELF has a dynamic segment
+    pub dynamic: bool,
+    /// Minimum OS version required, from the GNU ABI tag note, e.g., "Linux 3.2.0"
+    pub abi: String,
+    pub build_id: String,
+    pub go_build_id: String,
+    /// Android API level, from the Android ident note
+    pub android_api: Option<usize>,
+}
+
+/// Parses the ELF's PT_INTERP, PT_DYNAMIC and PT_NOTE segments
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::elf::parse_elf_program_info;
+///
+/// let mut elf = b"\x7FELF\x01\x01\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00".to_vec();
+/// elf.extend(b"\x02\x00\x28\x00\x01\x00\x00\x00\x00\x00\x00\x00\x34\x00\x00\x00");
+/// elf.extend(b"\x00\x00\x00\x00\x00\x00\x00\x00\x34\x00\x20\x00\x01\x00");
+/// elf.extend(b"\x00\x00\x00\x00\x00\x00");
+/// elf.extend(b"\x04\x00\x00\x00\x54\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00");
+/// elf.extend(b"\x14\x00\x00\x00\x14\x00\x00\x00\x04\x00\x00\x00\x04\x00\x00\x00");
+/// elf.extend(b"\x04\x00\x00\x00\x04\x00\x00\x00\x03\x00\x00\x00GNU\x00\xDE\xAD\xBE\xEF");
+///
+/// let program_info = parse_elf_program_info(&elf).unwrap();
+///
+/// assert_eq!(program_info.build_id, "deadbeef");
+/// assert_eq!(program_info.dynamic, false);
+/// ```
+pub fn parse_elf_program_info(elf_data: &[u8]) -> Result<ELFProgramInfo, StructureError> {
+    const PT_DYNAMIC: usize = 2;
+    const PT_INTERP: usize = 3;
+    const PT_NOTE: usize = 4;
+
+    let elf_header = parse_elf_header(elf_data)?;
+    let segments = parse_elf_segments(elf_data)?;
+
+    let mut program_info = ELFProgramInfo {
+        ..Default::default()
+    };
+
+    for segment in segments {
+        let segment_data = elf_data
+            .get(segment.offset..)
+            .and_then(|data| data.get(..segment.file_size));
+
+        match segment.segment_type {
+            PT_DYNAMIC => program_info.dynamic = true,
+            PT_INTERP => {
+                if let Some(interpreter_data) = segment_data {
+                    program_info.interpreter = get_cstring(interpreter_data);
+                }
+            }
+            PT_NOTE => {
+                if let Some(notes_data) = segment_data {
+                    parse_elf_notes(notes_data, &elf_header.endianness, &mut program_info);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(program_info)
+}
+
+/// Parses the notes in a PT_NOTE segment, updating the program info with any known notes
+fn parse_elf_notes(notes_data: &[u8], endianness: &str, program_info: &mut ELFProgramInfo) {
+    const NOTE_ALIGNMENT: usize = 4;
+    const NT_GNU_ABI_TAG: usize = 1;
+    const NT_GNU_BUILD_ID: usize = 3;
+    const NT_GO_BUILD_ID: usize = 4;
+    const NT_ANDROID_IDENT: usize = 1;
+
+    let note_structure = vec![("name_size", "u32"), ("desc_size", "u32"), ("type", "u32")];
+    let abi_tag_structure = vec![
+        ("os", "u32"),
+        ("major", "u32"),
+        ("minor", "u32"),
+        ("patch", "u32"),
+    ];
+    let android_ident_structure = vec![("api_level", "u32")];
+
+    let align = |size: usize| -> usize { size.div_ceil(NOTE_ALIGNMENT) * NOTE_ALIGNMENT };
+
+    let mut offset: usize = 0;
+
+    while let Some(note_data) = notes_data.get(offset..) {
+        let note = match common::parse(note_data, &note_structure, endianness) {
+            Ok(note) => note,
+            Err(_) => break,
+        };
+
+        let name_start = common::size(&note_structure);
+        let desc_start = name_start + align(note["name_size"]);
+        let desc_end = desc_start + note["desc_size"];
+
+        let (name_data, desc) = match (
+            note_data.get(name_start..name_start + note["name_size"]),
+            note_data.get(desc_start..desc_end),
+        ) {
+            (Some(name_data), Some(desc)) => (name_data, desc),
+            _ => break,
+        };
+
+        match (get_cstring(name_data).as_str(), note["type"]) {
+            ("GNU", NT_GNU_ABI_TAG) => {
+                if let Ok(abi_tag) = common::parse(desc, &abi_tag_structure, endianness) {
+                    let os = match abi_tag["os"] {
+                        0 => "Linux",
+                        1 => "GNU Hurd",
+                        2 => "Solaris",
+                        3 => "FreeBSD",
+                        _ => "unknown OS",
+                    };
+                    program_info.abi = format!(
+                        "{} {}.{}.{}",
+                        os, abi_tag["major"], abi_tag["minor"], abi_tag["patch"]
+                    );
+                }
+            }
+            ("GNU", NT_GNU_BUILD_ID) => {
+                program_info.build_id = desc.iter().map(|b| format!("{b:02x}")).collect();
+            }
+            ("Go", NT_GO_BUILD_ID) => {
+                program_info.go_build_id = get_cstring(desc);
+            }
+            ("Android", NT_ANDROID_IDENT) => {
+                if let Ok(ident) = common::parse(desc, &android_ident_structure, endianness) {
+                    program_info.android_api = Some(ident["api_level"]);
+                }
+            }
+            _ => (),
+        }
+
+        offset += desc_start + align(note["desc_size"]);
+    }
+}
+
+/// Calculates the size of an ELF file from the extents of its header tables, segments and sections
+pub fn parse_elf_size(elf_data: &[u8]) -> Result<usize, StructureError> {
+    const ELF_IDENT_STRUCT_SIZE: usize = 16;
+    // Sections of this type have no data in the file
+    const SHT_NOBITS: usize = 8;
+
+    let elf_header = parse_elf_header(elf_data)?;
+
+    let word_type = match elf_header.class.as_str() {
+        "64" => "u64",
+        _ => "u32",
+    };
+
+    let elf_offsets_structure = vec![
+        ("type", "u16"),
+        ("machine", "u16"),
+        ("version", "u32"),
+        ("entry", word_type),
+        ("phoff", word_type),
+        ("shoff", word_type),
+        ("flags", "u32"),
+        ("ehsize", "u16"),
+        ("phentsize", "u16"),
+        ("phnum", "u16"),
+        ("shentsize", "u16"),
+        ("shnum", "u16"),
+        ("shstrndx", "u16"),
+    ];
+
+    let elf_offsets = common::parse(
+        elf_data
+            .get(ELF_IDENT_STRUCT_SIZE..)
+            .ok_or(StructureError)?,
+        &elf_offsets_structure,
+        &elf_header.endianness,
+    )?;
+
+    // Without either header table, the extent of the ELF data is unknown
+    if elf_offsets["phnum"] == 0 && elf_offsets["shnum"] == 0 {
+        return Err(StructureError);
+    }
+
+    let mut elf_size = elf_offsets["ehsize"];
+
+    if elf_offsets["phnum"] > 0 {
+        elf_size = std::cmp::max(
+            elf_size,
+            elf_offsets["phoff"] + (elf_offsets["phnum"] * elf_offsets["phentsize"]),
+        );
+
+        for segment in parse_elf_segments(elf_data)? {
+            elf_size = std::cmp::max(elf_size, segment.offset + segment.file_size);
+        }
+    }
+
+    // The section header table is usually at the very end of the ELF
+    if elf_offsets["shnum"] > 0 {
+        elf_size = std::cmp::max(
+            elf_size,
+            elf_offsets["shoff"] + (elf_offsets["shnum"] * elf_offsets["shentsize"]),
+        );
+
+        for section in parse_elf_sections(elf_data)? {
+            if section.section_type != SHT_NOBITS {
+                elf_size = std::cmp::max(elf_size, section.offset + section.size);
+            }
+        }
+    }
+
+    if elf_size > elf_data.len() {
+        return Err(StructureError);
+    }
+
+    Ok(elf_size)
+}
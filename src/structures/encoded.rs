@@ -0,0 +1,142 @@
+use crate::structures::common::StructureError;
+use base64::Engine;
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+
+/// Runs of encoded text shorter than this, not counting line breaks, are not considered encoded data
+pub const MIN_ENCODED_SIZE: usize = 256;
+
+/// Stores info about a run of base64 or hex encoded text
+#[derive(Debug, Default, Clone)]
+pub struct EncodedData {
+    /// Size of the encoded text, including any line breaks and padding
+    pub size: usize,
+    /// The decoded data
+    pub data: Vec<u8>,
+}
+
+/// Parses and decodes a run of hex encoded text, which may be broken into lines
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::encoded::parse_hex_text;
+///
+/// let hex_text = "1f8b0800".repeat(32) + "\n" + &"deadbeef".repeat(8) + "\nnot hex";
+///
+/// let hex_data = parse_hex_text(hex_text.as_bytes()).unwrap();
+///
+/// assert_eq!(hex_data.size, 256 + 1 + 64);
+/// assert_eq!(hex_data.data.len(), 160);
+/// assert_eq!(hex_data.data[..4], [0x1F, 0x8B, 0x08, 0x00]);
+/// ```
+pub fn parse_hex_text(text: &[u8]) -> Result<EncodedData, StructureError> {
+    let (size, digits) = encoded_text_run(text, |byte| byte.is_ascii_hexdigit());
+
+    if digits.len() < MIN_ENCODED_SIZE {
+        return Err(StructureError);
+    }
+
+    // Each byte is encoded as two hex digits; a trailing odd digit is ignored
+    let data: Vec<u8> = digits
+        .chunks_exact(2)
+        .map(|pair| (hex_digit_value(pair[0]) << 4) | hex_digit_value(pair[1]))
+        .collect();
+
+    Ok(EncodedData { size, data })
+}
+
+/// Parses and decodes a run of base64 encoded text, which may be broken into lines
+///
+/// ## Example
+///
+/// ```
+/// use base64::Engine;
+/// use base64::engine::general_purpose::STANDARD;
+/// use binwalk::structures::encoded::parse_base64_text;
+///
+/// let data: Vec<u8> = (0..=255).collect();
+/// let base64_text = STANDARD.encode(&data) + "\n";
+///
+/// let base64_data = parse_base64_text(base64_text.as_bytes()).unwrap();
+///
+/// assert_eq!(base64_data.size, base64_text.len() - 1);
+/// assert_eq!(base64_data.data, data);
+/// ```
+pub fn parse_base64_text(text: &[u8]) -> Result<EncodedData, StructureError> {
+    const PADDING: u8 = b'=';
+    const MAX_PADDING_SIZE: usize = 2;
+
+    let (mut size, mut symbols) = encoded_text_run(text, |byte| {
+        byte.is_ascii_alphanumeric() || byte == b'+' || byte == b'/'
+    });
+
+    /*
+     * Base64 encoded binary data has upper and lower case letters, and digits, throughout; requiring all three rules out
+     * long identifiers, and hex encoded text, which is made up entirely of valid base64 symbols.
+     */
+    if symbols.len() < MIN_ENCODED_SIZE
+        || !symbols.iter().any(|byte| byte.is_ascii_uppercase())
+        || !symbols.iter().any(|byte| byte.is_ascii_lowercase())
+        || !symbols.iter().any(|byte| byte.is_ascii_digit())
+        || symbols.iter().all(|byte| byte.is_ascii_hexdigit())
+    {
+        return Err(StructureError);
+    }
+
+    // Padding ends the encoded text
+    let padding_size = text[size..]
+        .iter()
+        .take(MAX_PADDING_SIZE)
+        .take_while(|byte| **byte == PADDING)
+        .count();
+    size += padding_size;
+
+    // Every four base64 symbols encode three bytes; a single trailing symbol does not encode any
+    if symbols.len() % 4 == 1 {
+        symbols.pop();
+    }
+
+    let engine = GeneralPurpose::new(
+        &alphabet::STANDARD,
+        GeneralPurposeConfig::new()
+            .with_decode_allow_trailing_bits(true)
+            .with_decode_padding_mode(DecodePaddingMode::RequireNone),
+    );
+
+    match engine.decode(&symbols) {
+        Err(_) => Err(StructureError),
+        Ok(data) => Ok(EncodedData { size, data }),
+    }
+}
+
+/// Returns the size of a run of encoded text, and the encoded symbols in it. The run ends at the first byte that is
+/// neither an encoded symbol nor part of a single line break.
+fn encoded_text_run(text: &[u8], is_symbol: impl Fn(u8) -> bool) -> (usize, Vec<u8>) {
+    let mut size: usize = 0;
+    let mut symbols: Vec<u8> = vec![];
+    let mut line_breaks: usize = 0;
+
+    for (i, byte) in text.iter().enumerate() {
+        if is_symbol(*byte) {
+            symbols.push(*byte);
+            size = i + 1;
+            line_breaks = 0;
+        } else if *byte == b'\n' && line_breaks == 0 {
+            line_breaks += 1;
+        } else if *byte != b'\r' {
+            break;
+        }
+    }
+
+    (size, symbols)
+}
+
+/// Returns the value of an ASCII hex digit
+fn hex_digit_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
@@ -0,0 +1,211 @@
+use crate::structures::common::{self, StructureError};
+
+/// Size of an exFAT directory entry
+pub const EXFAT_DIRECTORY_ENTRY_SIZE: usize = 32;
+
+/// Directory entry type marking the end of a directory
+pub const EXFAT_END_OF_DIRECTORY: u8 = 0x00;
+
+/// Directory entry type of a file or directory entry, which is followed by its stream and name entries
+pub const EXFAT_FILE_ENTRY: u8 = 0x85;
+
+/// Struct to store exFAT boot sector info
+#[derive(Debug, Default, Clone)]
+pub struct EXFATHeader {
+    pub total_size: usize,
+    pub cluster_size: usize,
+    /// Offset of the first FAT from the start of the file system
+    pub fat_offset: usize,
+    /// Offset of the first cluster from the start of the file system
+    pub cluster_heap_offset: usize,
+    pub cluster_count: usize,
+    pub root_cluster: usize,
+    /// Set if the file system was not cleanly unmounted
+    pub dirty: bool,
+}
+
+/// Parses an exFAT boot sector
+pub fn parse_exfat_header(exfat_data: &[u8]) -> Result<EXFATHeader, StructureError> {
+    const BOOT_SIGNATURE: usize = 0xAA55;
+    const MIN_BYTES_PER_SECTOR_SHIFT: usize = 9;
+    const MAX_BYTES_PER_SECTOR_SHIFT: usize = 12;
+    // Clusters may be at most 32MB
+    const MAX_CLUSTER_SHIFT: usize = 25;
+    const MIN_FAT_OFFSET: usize = 24;
+    const VOLUME_DIRTY: usize = 0x02;
+    // These bytes correspond to the BIOS parameter block of FAT file systems, and must be zero
+    const MUST_BE_ZERO_START: usize = 11;
+    const MUST_BE_ZERO_END: usize = 64;
+    const BOOT_SIGNATURE_OFFSET: usize = 510;
+
+    let boot_sector_structure = vec![
+        ("partition_offset", "u64"),
+        ("volume_length", "u64"),
+        ("fat_offset", "u32"),
+        ("fat_length", "u32"),
+        ("cluster_heap_offset", "u32"),
+        ("cluster_count", "u32"),
+        ("root_cluster", "u32"),
+        ("serial_number", "u32"),
+        ("revision", "u16"),
+        ("volume_flags", "u16"),
+        ("bytes_per_sector_shift", "u8"),
+        ("sectors_per_cluster_shift", "u8"),
+        ("fat_count", "u8"),
+        ("drive_select", "u8"),
+        ("percent_in_use", "u8"),
+    ];
+
+    let boot_signature_structure = vec![("boot_signature", "u16")];
+
+    let must_be_zero = exfat_data
+        .get(MUST_BE_ZERO_START..MUST_BE_ZERO_END)
+        .ok_or(StructureError)?;
+
+    if must_be_zero.iter().any(|byte| *byte != 0) {
+        return Err(StructureError);
+    }
+
+    let boot_sector = common::parse(
+        &exfat_data[MUST_BE_ZERO_END..],
+        &boot_sector_structure,
+        "little",
+    )?;
+    let boot_signature = common::parse(
+        exfat_data
+            .get(BOOT_SIGNATURE_OFFSET..)
+            .ok_or(StructureError)?,
+        &boot_signature_structure,
+        "little",
+    )?;
+
+    let bytes_per_sector_shift = boot_sector["bytes_per_sector_shift"];
+    let sectors_per_cluster_shift = boot_sector["sectors_per_cluster_shift"];
+
+    if boot_signature["boot_signature"] == BOOT_SIGNATURE
+        && (MIN_BYTES_PER_SECTOR_SHIFT..=MAX_BYTES_PER_SECTOR_SHIFT)
+            .contains(&bytes_per_sector_shift)
+        && bytes_per_sector_shift + sectors_per_cluster_shift <= MAX_CLUSTER_SHIFT
+        && (1..=2).contains(&boot_sector["fat_count"])
+        && boot_sector["fat_offset"] >= MIN_FAT_OFFSET
+        && boot_sector["cluster_heap_offset"]
+            >= boot_sector["fat_offset"] + (boot_sector["fat_length"] * boot_sector["fat_count"])
+        && boot_sector["root_cluster"] >= 2
+        && boot_sector["root_cluster"] < boot_sector["cluster_count"] + 2
+    {
+        let bytes_per_sector = 1 << bytes_per_sector_shift;
+
+        return Ok(EXFATHeader {
+            total_size: boot_sector["volume_length"] * bytes_per_sector,
+            cluster_size: bytes_per_sector << sectors_per_cluster_shift,
+            fat_offset: boot_sector["fat_offset"] * bytes_per_sector,
+            cluster_heap_offset: boot_sector["cluster_heap_offset"] * bytes_per_sector,
+            cluster_count: boot_sector["cluster_count"],
+            root_cluster: boot_sector["root_cluster"],
+            dirty: boot_sector["volume_flags"] & VOLUME_DIRTY != 0,
+        });
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about an exFAT file directory entry
+#[derive(Debug, Default, Clone)]
+pub struct EXFATFileEntry {
+    /// Number of stream and name entries that follow this entry
+    pub secondary_count: usize,
+    pub directory: bool,
+}
+
+/// Parses an exFAT file directory entry
+pub fn parse_exfat_file_entry(entry_data: &[u8]) -> Result<EXFATFileEntry, StructureError> {
+    const DIRECTORY_ATTRIBUTE: usize = 0x10;
+    // Each file has at least a stream entry and one file name entry
+    const MIN_SECONDARY_COUNT: usize = 2;
+
+    let file_entry_structure = vec![
+        ("entry_type", "u8"),
+        ("secondary_count", "u8"),
+        ("set_checksum", "u16"),
+        ("attributes", "u16"),
+    ];
+
+    let file_entry = common::parse(entry_data, &file_entry_structure, "little")?;
+
+    if file_entry["entry_type"] == EXFAT_FILE_ENTRY as usize
+        && file_entry["secondary_count"] >= MIN_SECONDARY_COUNT
+    {
+        return Ok(EXFATFileEntry {
+            secondary_count: file_entry["secondary_count"],
+            directory: file_entry["attributes"] & DIRECTORY_ATTRIBUTE != 0,
+        });
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about an exFAT stream extension directory entry, which describes where a file's data is stored
+#[derive(Debug, Default, Clone)]
+pub struct EXFATStreamEntry {
+    /// Set if the file's clusters are contiguous, in which case they are not recorded in the FAT
+    pub contiguous: bool,
+    pub name_length: usize,
+    /// Data beyond this many bytes has not been written, and reads as zeros
+    pub valid_data_length: usize,
+    pub first_cluster: usize,
+    pub data_length: usize,
+}
+
+/// Parses an exFAT stream extension directory entry
+pub fn parse_exfat_stream_entry(entry_data: &[u8]) -> Result<EXFATStreamEntry, StructureError> {
+    const STREAM_ENTRY: usize = 0xC0;
+    const NO_FAT_CHAIN: usize = 0x02;
+
+    let stream_entry_structure = vec![
+        ("entry_type", "u8"),
+        ("flags", "u8"),
+        ("reserved1", "u8"),
+        ("name_length", "u8"),
+        ("name_hash", "u16"),
+        ("reserved2", "u16"),
+        ("valid_data_length", "u64"),
+        ("reserved3", "u32"),
+        ("first_cluster", "u32"),
+        ("data_length", "u64"),
+    ];
+
+    let stream_entry = common::parse(entry_data, &stream_entry_structure, "little")?;
+
+    if stream_entry["entry_type"] == STREAM_ENTRY
+        && stream_entry["valid_data_length"] <= stream_entry["data_length"]
+    {
+        return Ok(EXFATStreamEntry {
+            contiguous: stream_entry["flags"] & NO_FAT_CHAIN != 0,
+            name_length: stream_entry["name_length"],
+            valid_data_length: stream_entry["valid_data_length"],
+            first_cluster: stream_entry["first_cluster"],
+            data_length: stream_entry["data_length"],
+        });
+    }
+
+    Err(StructureError)
+}
+
+/// Parses an exFAT file name directory entry, returning the UTF-16 characters it contains
+pub fn parse_exfat_name_entry(entry_data: &[u8]) -> Result<Vec<u16>, StructureError> {
+    const NAME_ENTRY: u8 = 0xC1;
+    const NAME_START: usize = 2;
+
+    let entry_data = entry_data
+        .get(..EXFAT_DIRECTORY_ENTRY_SIZE)
+        .ok_or(StructureError)?;
+
+    if entry_data[0] != NAME_ENTRY {
+        return Err(StructureError);
+    }
+
+    Ok(entry_data[NAME_START..]
+        .chunks_exact(2)
+        .map(|character| u16::from_le_bytes([character[0], character[1]]))
+        .collect())
+}
@@ -7,6 +7,24 @@ pub const SUPERBLOCK_SIZE: usize = 1024;
 /// Expected file offset of an EXT superblock
 pub const SUPERBLOCK_OFFSET: usize = 1024;
 
+/// Inode number of the root directory
+pub const ROOT_INODE: usize = 2;
+
+/// Size of the block pointers / extent tree stored inside each inode
+pub const INODE_BLOCK_DATA_SIZE: usize = 60;
+
+/// Incompatible features that change how file data is stored; images using them are not supported by the internal extractor
+const UNSUPPORTED_INCOMPAT_FEATURES: &[(usize, &str)] = &[
+    (0x0001, "compression"),
+    (0x0008, "journal device"),
+    (0x0010, "meta block groups"),
+    (0x8000, "inline data"),
+    (0x10000, "encryption"),
+];
+
+/// Incompatible feature flag indicating 64-bit block numbers
+const INCOMPAT_64BIT: usize = 0x80;
+
 /// Struct to store some useful EXT info
 #[derive(Debug, Default, Clone)]
 pub struct EXTHeader {
@@ -17,12 +35,34 @@ pub struct EXTHeader {
     pub inodes_count: usize,
     pub free_blocks_count: usize,
     pub reserved_blocks_count: usize,
+    pub first_data_block: usize,
+    pub blocks_per_group: usize,
+    pub inodes_per_group: usize,
+    pub inode_size: usize,
+    pub descriptor_size: usize,
+    pub feature_incompat: usize,
+}
+
+impl EXTHeader {
+    /// Returns the names of any features used by this image that the internal extractor does not support
+    pub fn unsupported_features(&self) -> Vec<&'static str> {
+        UNSUPPORTED_INCOMPAT_FEATURES
+            .iter()
+            .filter(|(flag, _name)| self.feature_incompat & flag != 0)
+            .map(|(_flag, name)| *name)
+            .collect()
+    }
 }
 
 /// Partially parses an EXT superblock structure
 pub fn parse_ext_header(ext_data: &[u8]) -> Result<EXTHeader, StructureError> {
     // Max value of the EXT log block size
     const MAX_BLOCK_LOG: usize = 2;
+    // Inode size and descriptor size used by revision 0 images, and images without 64-bit support
+    const DEFAULT_INODE_SIZE: usize = 128;
+    const DEFAULT_DESCRIPTOR_SIZE: usize = 32;
+    // Revision 1 images define the inode size and feature flags in the superblock
+    const DYNAMIC_REV: usize = 1;
 
     // Parital superblock structure, just enough for validation and size calculation
     let ext_superblock_structure = vec![
@@ -53,6 +93,20 @@ pub fn parse_ext_header(ext_data: &[u8]) -> Result<EXTHeader, StructureError> {
         ("resgid", "u16"),
     ];
 
+    // Superblock fields only present in revision 1 images, which immediately follow the partial superblock structure
+    let ext_dynamic_structure = vec![
+        ("first_inode", "u32"),
+        ("inode_size", "u16"),
+        ("block_group_number", "u16"),
+        ("feature_compat", "u32"),
+        ("feature_incompat", "u32"),
+        ("feature_ro_compat", "u32"),
+    ];
+
+    // Group descriptor size, only used if the 64-bit feature is enabled
+    const DESCRIPTOR_SIZE_OFFSET: usize = 0xFE;
+    let descriptor_size_structure = vec![("descriptor_size", "u16")];
+
     let allowed_rev_levels: Vec<usize> = vec![0, 1];
     let allowed_first_data_blocks: Vec<usize> = vec![0, 1];
 
@@ -94,6 +148,37 @@ pub fn parse_ext_header(ext_data: &[u8]) -> Result<EXTHeader, StructureError> {
                                 ext_superblock["reserved_blocks_count"];
                             ext_header.image_size =
                                 ext_header.block_size * ext_superblock["blocks_count"];
+                            ext_header.first_data_block = ext_superblock["first_data_block"];
+                            ext_header.blocks_per_group = ext_superblock["blocks_per_group"];
+                            ext_header.inodes_per_group = ext_superblock["inodes_per_group"];
+                            ext_header.inode_size = DEFAULT_INODE_SIZE;
+                            ext_header.descriptor_size = DEFAULT_DESCRIPTOR_SIZE;
+
+                            if ext_superblock["s_rev_level"] == DYNAMIC_REV {
+                                let dynamic_offset =
+                                    SUPERBLOCK_OFFSET + common::size(&ext_superblock_structure);
+                                let ext_dynamic = common::parse(
+                                    &ext_data[dynamic_offset..],
+                                    &ext_dynamic_structure,
+                                    "little",
+                                )?;
+
+                                ext_header.inode_size = ext_dynamic["inode_size"];
+                                ext_header.feature_incompat = ext_dynamic["feature_incompat"];
+
+                                if ext_header.feature_incompat & INCOMPAT_64BIT != 0 {
+                                    let descriptor_size = common::parse(
+                                        &ext_data[SUPERBLOCK_OFFSET + DESCRIPTOR_SIZE_OFFSET..],
+                                        &descriptor_size_structure,
+                                        "little",
+                                    )?;
+
+                                    ext_header.descriptor_size = std::cmp::max(
+                                        descriptor_size["descriptor_size"],
+                                        DEFAULT_DESCRIPTOR_SIZE,
+                                    );
+                                }
+                            }
 
                             return Ok(ext_header);
                         }
@@ -105,3 +190,273 @@ pub fn parse_ext_header(ext_data: &[u8]) -> Result<EXTHeader, StructureError> {
 
     Err(StructureError)
 }
+
+/// Stores the location of a block group's inode table
+#[derive(Debug, Default, Clone)]
+pub struct EXTGroupDescriptor {
+    pub inode_table_block: usize,
+}
+
+/// Parses an EXT block group descriptor
+pub fn parse_ext_group_descriptor(
+    descriptor_data: &[u8],
+    descriptor_size: usize,
+) -> Result<EXTGroupDescriptor, StructureError> {
+    // 64-bit group descriptors extend the 32-bit descriptor with the high bits of each block number
+    const DESCRIPTOR_64BIT_SIZE: usize = 64;
+
+    let descriptor_structure = vec![
+        ("block_bitmap", "u32"),
+        ("inode_bitmap", "u32"),
+        ("inode_table", "u32"),
+        ("free_blocks_count", "u16"),
+        ("free_inodes_count", "u16"),
+        ("used_dirs_count", "u16"),
+        ("flags", "u16"),
+        ("exclude_bitmap", "u32"),
+        ("block_bitmap_checksum", "u16"),
+        ("inode_bitmap_checksum", "u16"),
+        ("inode_table_unused", "u16"),
+        ("checksum", "u16"),
+    ];
+
+    let descriptor_high_structure = vec![
+        ("block_bitmap_high", "u32"),
+        ("inode_bitmap_high", "u32"),
+        ("inode_table_high", "u32"),
+    ];
+
+    let descriptor = common::parse(descriptor_data, &descriptor_structure, "little")?;
+    let mut inode_table_block = descriptor["inode_table"];
+
+    if descriptor_size >= DESCRIPTOR_64BIT_SIZE {
+        let high_offset = common::size(&descriptor_structure);
+        let descriptor_high = common::parse(
+            descriptor_data.get(high_offset..).ok_or(StructureError)?,
+            &descriptor_high_structure,
+            "little",
+        )?;
+
+        inode_table_block |= descriptor_high["inode_table_high"] << 32;
+    }
+
+    Ok(EXTGroupDescriptor { inode_table_block })
+}
+
+/// Stores info about an EXT inode
+#[derive(Debug, Default, Clone)]
+pub struct EXTInode {
+    pub size: usize,
+    pub permissions: usize,
+    pub directory: bool,
+    pub regular: bool,
+    pub symlink: bool,
+    pub fifo: bool,
+    pub socket: bool,
+    pub block_device: bool,
+    pub character_device: bool,
+    /// File data is mapped by an extent tree, rather than by block pointers
+    pub extents: bool,
+    /// Raw contents of the inode's block pointers / extent tree
+    pub block_data: Vec<u8>,
+}
+
+/// Parses an EXT inode
+pub fn parse_ext_inode(inode_data: &[u8]) -> Result<EXTInode, StructureError> {
+    const FILE_TYPE_MASK: usize = 0xF000;
+    const PERMISSIONS_MASK: usize = 0x0FFF;
+    const EXTENTS_FLAG: usize = 0x80000;
+
+    // File types, from the upper bits of the mode field
+    const FIFO: usize = 0x1000;
+    const CHARACTER_DEVICE: usize = 0x2000;
+    const DIRECTORY: usize = 0x4000;
+    const BLOCK_DEVICE: usize = 0x6000;
+    const REGULAR_FILE: usize = 0x8000;
+    const SYMLINK: usize = 0xA000;
+    const SOCKET: usize = 0xC000;
+
+    let inode_structure = vec![
+        ("mode", "u16"),
+        ("uid", "u16"),
+        ("size_low", "u32"),
+        ("access_time", "u32"),
+        ("change_time", "u32"),
+        ("modification_time", "u32"),
+        ("deletion_time", "u32"),
+        ("gid", "u16"),
+        ("links_count", "u16"),
+        ("blocks_count", "u32"),
+        ("flags", "u32"),
+        ("os_specific", "u32"),
+    ];
+
+    // Fields that follow the inode's block data
+    let inode_tail_structure = vec![
+        ("generation", "u32"),
+        ("file_acl", "u32"),
+        ("size_high", "u32"),
+    ];
+
+    let inode = common::parse(inode_data, &inode_structure, "little")?;
+
+    let block_data_start = common::size(&inode_structure);
+    let block_data_end = block_data_start + INODE_BLOCK_DATA_SIZE;
+
+    let block_data = inode_data
+        .get(block_data_start..block_data_end)
+        .ok_or(StructureError)?;
+    let inode_tail = common::parse(
+        inode_data.get(block_data_end..).ok_or(StructureError)?,
+        &inode_tail_structure,
+        "little",
+    )?;
+
+    let file_type = inode["mode"] & FILE_TYPE_MASK;
+    let mut size = inode["size_low"];
+
+    // The high 32 bits of the size field are only reliably defined for regular files
+    if file_type == REGULAR_FILE {
+        size |= inode_tail["size_high"] << 32;
+    }
+
+    Ok(EXTInode {
+        size,
+        permissions: inode["mode"] & PERMISSIONS_MASK,
+        directory: file_type == DIRECTORY,
+        regular: file_type == REGULAR_FILE,
+        symlink: file_type == SYMLINK,
+        fifo: file_type == FIFO,
+        socket: file_type == SOCKET,
+        block_device: file_type == BLOCK_DEVICE,
+        character_device: file_type == CHARACTER_DEVICE,
+        extents: inode["flags"] & EXTENTS_FLAG != 0,
+        block_data: block_data.to_vec(),
+    })
+}
+
+/// Stores info about an EXT extent tree node header
+#[derive(Debug, Default, Clone)]
+pub struct EXTExtentHeader {
+    pub header_size: usize,
+    pub entry_count: usize,
+    pub depth: usize,
+}
+
+/// Parses an EXT extent tree node header
+pub fn parse_ext_extent_header(extent_data: &[u8]) -> Result<EXTExtentHeader, StructureError> {
+    const EXTENT_MAGIC: usize = 0xF30A;
+
+    let extent_header_structure = vec![
+        ("magic", "u16"),
+        ("entry_count", "u16"),
+        ("max_entries", "u16"),
+        ("depth", "u16"),
+        ("generation", "u32"),
+    ];
+
+    let extent_header = common::parse(extent_data, &extent_header_structure, "little")?;
+
+    if extent_header["magic"] == EXTENT_MAGIC
+        && extent_header["entry_count"] <= extent_header["max_entries"]
+    {
+        return Ok(EXTExtentHeader {
+            header_size: common::size(&extent_header_structure),
+            entry_count: extent_header["entry_count"],
+            depth: extent_header["depth"],
+        });
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about an EXT extent, which maps a run of file blocks to a run of blocks in the image
+#[derive(Debug, Default, Clone)]
+pub struct EXTExtent {
+    pub logical_block: usize,
+    pub block_count: usize,
+    pub physical_block: usize,
+    /// Uninitialized extents are allocated, but read as zeros
+    pub initialized: bool,
+}
+
+/// Parses an EXT extent tree leaf entry
+pub fn parse_ext_extent(extent_data: &[u8]) -> Result<EXTExtent, StructureError> {
+    // Lengths greater than this indicate an uninitialized extent
+    const MAX_INITIALIZED_LENGTH: usize = 0x8000;
+
+    let extent_structure = vec![
+        ("logical_block", "u32"),
+        ("length", "u16"),
+        ("physical_block_high", "u16"),
+        ("physical_block_low", "u32"),
+    ];
+
+    let extent = common::parse(extent_data, &extent_structure, "little")?;
+
+    let mut block_count = extent["length"];
+    let initialized = block_count <= MAX_INITIALIZED_LENGTH;
+
+    if !initialized {
+        block_count -= MAX_INITIALIZED_LENGTH;
+    }
+
+    Ok(EXTExtent {
+        logical_block: extent["logical_block"],
+        block_count,
+        physical_block: (extent["physical_block_high"] << 32) | extent["physical_block_low"],
+        initialized,
+    })
+}
+
+/// Parses an EXT extent tree index entry, returning the block number of the child node it points to
+pub fn parse_ext_extent_index(index_data: &[u8]) -> Result<usize, StructureError> {
+    let index_structure = vec![
+        ("logical_block", "u32"),
+        ("leaf_block_low", "u32"),
+        ("leaf_block_high", "u16"),
+        ("unused", "u16"),
+    ];
+
+    let index = common::parse(index_data, &index_structure, "little")?;
+
+    Ok((index["leaf_block_high"] << 32) | index["leaf_block_low"])
+}
+
+/// Stores info about an EXT directory entry
+#[derive(Debug, Default, Clone)]
+pub struct EXTDirectoryEntry {
+    pub inode: usize,
+    pub entry_size: usize,
+    pub name: String,
+}
+
+/// Parses an EXT directory entry
+pub fn parse_ext_directory_entry(entry_data: &[u8]) -> Result<EXTDirectoryEntry, StructureError> {
+    // Entries are aligned to 4 bytes
+    const ENTRY_ALIGNMENT: usize = 4;
+
+    let entry_structure = vec![
+        ("inode", "u32"),
+        ("entry_size", "u16"),
+        ("name_length", "u8"),
+        ("file_type", "u8"),
+    ];
+
+    let entry = common::parse(entry_data, &entry_structure, "little")?;
+
+    let name_start = common::size(&entry_structure);
+    let name_end = name_start + entry["name_length"];
+
+    if entry["entry_size"] >= name_end && entry["entry_size"] % ENTRY_ALIGNMENT == 0 {
+        if let Some(name_bytes) = entry_data.get(name_start..name_end) {
+            return Ok(EXTDirectoryEntry {
+                inode: entry["inode"],
+                entry_size: entry["entry_size"],
+                name: String::from_utf8_lossy(name_bytes).to_string(),
+            });
+        }
+    }
+
+    Err(StructureError)
+}
@@ -5,6 +5,23 @@ use crate::structures::common::{self, StructureError};
 pub struct FATHeader {
     pub is_fat32: bool,
     pub total_size: usize,
+    /// Size of each FAT entry, in bits: 12, 16 or 32
+    pub fat_type: usize,
+    pub bytes_per_sector: usize,
+    pub sectors_per_cluster: usize,
+    pub reserved_sectors: usize,
+    pub fat_count: usize,
+    /// Number of sectors in each FAT
+    pub fat_size: usize,
+    /// Number of entries in the fixed size root directory; 0 for FAT32
+    pub root_entries_count: usize,
+    /// First cluster of the root directory; FAT32 only
+    pub root_cluster: usize,
+    /// Offset of the FAT12/16 root directory from the start of the file system
+    pub root_directory_offset: usize,
+    /// Offset of the first cluster from the start of the file system
+    pub data_offset: usize,
+    pub cluster_count: usize,
 }
 
 /// Parses a FAT header
@@ -32,6 +49,19 @@ pub fn parse_fat_header(fat_data: &[u8]) -> Result<FATHeader, StructureError> {
         ("total_sectors_32", "u32"),
     ];
 
+    // FAT32 extends the boot sector with these fields
+    let fat32_boot_sector_structure = vec![
+        ("fat_size_32", "u32"),
+        ("extended_flags", "u16"),
+        ("version", "u16"),
+        ("root_cluster", "u32"),
+        ("fs_info_sector", "u16"),
+        ("backup_boot_sector", "u16"),
+    ];
+
+    // FAT12 and FAT16 are distinguished by the number of clusters in the file system
+    const MAX_FAT12_CLUSTERS: usize = 4084;
+
     // First opcode should be jump instruction, either EB or E9
     let valid_opcode1: Vec<usize> = vec![0xEB, 0xE9];
 
@@ -76,6 +106,49 @@ pub fn parse_fat_header(fat_data: &[u8]) -> Result<FATHeader, StructureError> {
                                         * bs_header["bytes_per_sector"];
                                 }
 
+                                result.bytes_per_sector = bs_header["bytes_per_sector"];
+                                result.sectors_per_cluster = bs_header["sectors_per_cluster"];
+                                result.reserved_sectors = bs_header["reserved_sectors"];
+                                result.fat_count = bs_header["fat_count"];
+                                result.root_entries_count = bs_header["root_entries_count_16"];
+                                result.fat_size = bs_header["fat_size_16"];
+
+                                if result.is_fat32 {
+                                    let fat32_header = common::parse(
+                                        &fat_data[common::size(&fat_boot_sector_structure)..],
+                                        &fat32_boot_sector_structure,
+                                        "little",
+                                    )?;
+
+                                    result.fat_size = fat32_header["fat_size_32"];
+                                    result.root_cluster = fat32_header["root_cluster"];
+                                }
+
+                                // The data region follows the reserved sectors, the FATs, and the FAT12/16 root directory
+                                let root_directory_sectors = (result.root_entries_count
+                                    * FAT_DIRECTORY_ENTRY_SIZE)
+                                    .div_ceil(result.bytes_per_sector);
+                                let data_start_sector = result.reserved_sectors
+                                    + (result.fat_count * result.fat_size)
+                                    + root_directory_sectors;
+                                let cluster_count = ((result.total_size / result.bytes_per_sector)
+                                    .saturating_sub(data_start_sector))
+                                    / result.sectors_per_cluster;
+
+                                result.cluster_count = cluster_count;
+                                result.data_offset = data_start_sector * result.bytes_per_sector;
+                                result.root_directory_offset = (result.reserved_sectors
+                                    + (result.fat_count * result.fat_size))
+                                    * result.bytes_per_sector;
+
+                                result.fat_type = if result.is_fat32 {
+                                    32
+                                } else if cluster_count <= MAX_FAT12_CLUSTERS {
+                                    12
+                                } else {
+                                    16
+                                };
+
                                 // If both total_sectors_32 and total_sectors_16 is 0, this is not a valid FAT
                                 if result.total_size > 0 {
                                     return Ok(result);
@@ -90,3 +163,167 @@ pub fn parse_fat_header(fat_data: &[u8]) -> Result<FATHeader, StructureError> {
 
     Err(StructureError)
 }
+
+/// Stores info about a FAT directory entry
+#[derive(Debug, Default, Clone)]
+pub struct FATDirectoryEntry {
+    /// Set for the unused entry that marks the end of a directory
+    pub end_of_directory: bool,
+    pub deleted: bool,
+    pub volume_label: bool,
+    pub directory: bool,
+    pub read_only: bool,
+    /// The 8.3 short name, with the base name and extension joined by a '.'
+    pub short_name: String,
+    /// The raw 11 byte short name, used to validate long name checksums
+    pub raw_short_name: Vec<u8>,
+    pub first_cluster: usize,
+    pub size: usize,
+    /// Set if this entry holds part of a long file name, rather than describing a file
+    pub long_name: bool,
+    /// Position of this long name part in the full name, starting at 1
+    pub long_name_order: usize,
+    /// Set for the long name entry that holds the end of the name; it precedes all other parts of the name
+    pub long_name_last: bool,
+    pub long_name_checksum: u8,
+    /// UTF-16 characters of this part of the long name
+    pub long_name_part: Vec<u16>,
+}
+
+/// Size of a FAT directory entry
+pub const FAT_DIRECTORY_ENTRY_SIZE: usize = 32;
+
+/// Parses a FAT directory entry, which may be either a short name entry or part of a long file name
+pub fn parse_fat_directory_entry(entry_data: &[u8]) -> Result<FATDirectoryEntry, StructureError> {
+    const SHORT_NAME_SIZE: usize = 11;
+    const END_OF_DIRECTORY: u8 = 0x00;
+    const DELETED: u8 = 0xE5;
+    // A first name byte of 0x05 is used to store names that actually start with 0xE5
+    const ESCAPED_DELETED: u8 = 0x05;
+
+    const ATTRIBUTE_READ_ONLY: usize = 0x01;
+    const ATTRIBUTE_VOLUME_LABEL: usize = 0x08;
+    const ATTRIBUTE_DIRECTORY: usize = 0x10;
+    const ATTRIBUTE_LONG_NAME: usize = 0x0F;
+    const ATTRIBUTE_LONG_NAME_MASK: usize = 0x3F;
+
+    // Bits in the reserved field that indicate that the base name and extension are lower case
+    const LOWER_CASE_BASE: usize = 0x08;
+    const LOWER_CASE_EXTENSION: usize = 0x10;
+
+    const LONG_NAME_LAST: usize = 0x40;
+    const LONG_NAME_ORDER_MASK: usize = 0x1F;
+
+    let entry_structure = vec![
+        ("attributes", "u8"),
+        ("nt_reserved", "u8"),
+        ("creation_time_tenths", "u8"),
+        ("creation_time", "u16"),
+        ("creation_date", "u16"),
+        ("access_date", "u16"),
+        ("first_cluster_high", "u16"),
+        ("write_time", "u16"),
+        ("write_date", "u16"),
+        ("first_cluster_low", "u16"),
+        ("size", "u32"),
+    ];
+
+    let long_name_structure = vec![("order", "u8")];
+
+    let mut entry = FATDirectoryEntry {
+        ..Default::default()
+    };
+
+    let entry_data = entry_data
+        .get(..FAT_DIRECTORY_ENTRY_SIZE)
+        .ok_or(StructureError)?;
+    let raw_short_name = &entry_data[..SHORT_NAME_SIZE];
+    let entry_fields = common::parse(&entry_data[SHORT_NAME_SIZE..], &entry_structure, "little")?;
+
+    match raw_short_name[0] {
+        END_OF_DIRECTORY => {
+            entry.end_of_directory = true;
+            return Ok(entry);
+        }
+        DELETED => {
+            entry.deleted = true;
+            return Ok(entry);
+        }
+        _ => (),
+    }
+
+    if entry_fields["attributes"] & ATTRIBUTE_LONG_NAME_MASK == ATTRIBUTE_LONG_NAME {
+        // Long name characters are stored in three separate runs of UTF-16 characters
+        const NAME_RUNS: [(usize, usize); 3] = [(1, 11), (14, 26), (28, 32)];
+
+        let long_name = common::parse(entry_data, &long_name_structure, "little")?;
+
+        entry.long_name = true;
+        entry.long_name_order = long_name["order"] & LONG_NAME_ORDER_MASK;
+        entry.long_name_last = long_name["order"] & LONG_NAME_LAST != 0;
+        entry.long_name_checksum = entry_data[13];
+
+        for (run_start, run_end) in NAME_RUNS {
+            for character in entry_data[run_start..run_end].chunks_exact(2) {
+                entry
+                    .long_name_part
+                    .push(u16::from_le_bytes([character[0], character[1]]));
+            }
+        }
+
+        return Ok(entry);
+    }
+
+    let mut base_name = raw_short_name[..8].to_vec();
+    let mut extension = raw_short_name[8..].to_vec();
+
+    if base_name[0] == ESCAPED_DELETED {
+        base_name[0] = DELETED;
+    }
+
+    if entry_fields["nt_reserved"] & LOWER_CASE_BASE != 0 {
+        base_name.make_ascii_lowercase();
+    }
+
+    if entry_fields["nt_reserved"] & LOWER_CASE_EXTENSION != 0 {
+        extension.make_ascii_lowercase();
+    }
+
+    // Short names are padded with spaces; characters outside of ASCII use an unknown OEM code page
+    let base_name = String::from_utf8_lossy(&base_name).trim_end().to_string();
+    let extension = String::from_utf8_lossy(&extension).trim_end().to_string();
+
+    entry.short_name = match extension.is_empty() {
+        true => base_name,
+        false => format!("{base_name}.{extension}"),
+    };
+
+    entry.raw_short_name = raw_short_name.to_vec();
+    entry.volume_label = entry_fields["attributes"] & ATTRIBUTE_VOLUME_LABEL != 0;
+    entry.directory = entry_fields["attributes"] & ATTRIBUTE_DIRECTORY != 0;
+    entry.read_only = entry_fields["attributes"] & ATTRIBUTE_READ_ONLY != 0;
+    entry.first_cluster =
+        (entry_fields["first_cluster_high"] << 16) | entry_fields["first_cluster_low"];
+    entry.size = entry_fields["size"];
+
+    Ok(entry)
+}
+
+/// Calculates the checksum of a raw 11 byte short name, which is stored in each of its long name entries
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::fat::fat_short_name_checksum;
+///
+/// assert_eq!(fat_short_name_checksum(b"README  TXT"), 0x73);
+/// ```
+pub fn fat_short_name_checksum(raw_short_name: &[u8]) -> u8 {
+    let mut checksum: u8 = 0;
+
+    for byte in raw_short_name {
+        checksum = checksum.rotate_right(1).wrapping_add(*byte);
+    }
+
+    checksum
+}
@@ -0,0 +1,154 @@
+use crate::structures::common::{self, StructureError};
+
+/// Stores info about a field in a FlatBuffers table
+#[derive(Debug, Default, Clone)]
+pub struct FlatBufferField {
+    /// Field index in the table's vtable
+    pub index: usize,
+    /// Offset of the field, relative to the start of the table
+    pub offset: usize,
+    /// Size of the field, inferred from the offset of the next field
+    pub size: usize,
+    /// True if the field looks like an offset to a string, vector, or sub-table
+    pub is_reference: bool,
+}
+
+/// Stores info about a FlatBuffers buffer
+#[derive(Debug, Default, Clone)]
+pub struct FlatBufferInfo {
+    /// Optional 4-character file identifier
+    pub identifier: String,
+    pub root_table_offset: usize,
+    pub root_table_size: usize,
+    /// Total number of fields defined in the root table's vtable
+    pub vtable_field_count: usize,
+    /// Fields that are present in the root table
+    pub fields: Vec<FlatBufferField>,
+}
+
+/// Heuristically parses the root table of a FlatBuffers buffer
+pub fn parse_flatbuffer(flatbuffer_data: &[u8]) -> Result<FlatBufferInfo, StructureError> {
+    const FILE_IDENTIFIER_START: usize = 4;
+    const FILE_IDENTIFIER_END: usize = 8;
+    const VTABLE_HEADER_SIZE: usize = 4;
+    const VTABLE_ENTRY_SIZE: usize = 2;
+    const REFERENCE_SIZE: usize = 4;
+    const MIN_FIELD_OFFSET: usize = 4;
+
+    let root_structure = vec![("root_table_offset", "u32")];
+    let table_structure = vec![("vtable_offset", "u32")];
+    let vtable_structure = vec![("vtable_size", "u16"), ("table_size", "u16")];
+    let vtable_entry_structure = vec![("field_offset", "u16")];
+
+    let root = common::parse(flatbuffer_data, &root_structure, "little")?;
+
+    let mut flatbuffer = FlatBufferInfo {
+        root_table_offset: root["root_table_offset"],
+        ..Default::default()
+    };
+
+    // The root table must be 4-byte aligned, and follow the root offset
+    if flatbuffer.root_table_offset < common::size(&root_structure)
+        || !flatbuffer.root_table_offset.is_multiple_of(4)
+    {
+        return Err(StructureError);
+    }
+
+    // The file identifier is optional; if present, it is expected to be alphanumeric
+    if let Some(identifier_bytes) = flatbuffer_data.get(FILE_IDENTIFIER_START..FILE_IDENTIFIER_END)
+    {
+        if flatbuffer.root_table_offset >= FILE_IDENTIFIER_END
+            && identifier_bytes.iter().all(|b| b.is_ascii_alphanumeric())
+        {
+            flatbuffer.identifier = String::from_utf8_lossy(identifier_bytes).to_string();
+        }
+    }
+
+    // Tables start with a signed offset to their vtable, which is subtracted from the table's offset
+    let table_data = flatbuffer_data
+        .get(flatbuffer.root_table_offset..)
+        .ok_or(StructureError)?;
+    let table = common::parse(table_data, &table_structure, "little")?;
+    let vtable_offset =
+        (flatbuffer.root_table_offset as i64) - (table["vtable_offset"] as i32 as i64);
+
+    if vtable_offset < 0 || (vtable_offset % 2) != 0 {
+        return Err(StructureError);
+    }
+
+    let vtable_data = flatbuffer_data
+        .get(vtable_offset as usize..)
+        .ok_or(StructureError)?;
+    let vtable = common::parse(vtable_data, &vtable_structure, "little")?;
+
+    flatbuffer.root_table_size = vtable["table_size"];
+
+    if vtable["vtable_size"] < VTABLE_HEADER_SIZE
+        || !vtable["vtable_size"].is_multiple_of(VTABLE_ENTRY_SIZE)
+        || vtable_data.len() < vtable["vtable_size"]
+        || flatbuffer.root_table_size < common::size(&table_structure)
+        || table_data.len() < flatbuffer.root_table_size
+    {
+        return Err(StructureError);
+    }
+
+    flatbuffer.vtable_field_count =
+        (vtable["vtable_size"] - VTABLE_HEADER_SIZE) / VTABLE_ENTRY_SIZE;
+
+    for index in 0..flatbuffer.vtable_field_count {
+        let entry_offset = VTABLE_HEADER_SIZE + (index * VTABLE_ENTRY_SIZE);
+        let entry = common::parse(
+            &vtable_data[entry_offset..],
+            &vtable_entry_structure,
+            "little",
+        )?;
+
+        // Fields that are not present have an offset of 0; present fields must lie within the table
+        if entry["field_offset"] != 0 {
+            if entry["field_offset"] < MIN_FIELD_OFFSET
+                || entry["field_offset"] >= flatbuffer.root_table_size
+            {
+                return Err(StructureError);
+            }
+
+            flatbuffer.fields.push(FlatBufferField {
+                index,
+                offset: entry["field_offset"],
+                ..Default::default()
+            });
+        }
+    }
+
+    if flatbuffer.fields.is_empty() {
+        return Err(StructureError);
+    }
+
+    // Infer field sizes from the layout of the fields in the table
+    let mut field_offsets: Vec<usize> =
+        flatbuffer.fields.iter().map(|field| field.offset).collect();
+    field_offsets.push(flatbuffer.root_table_size);
+    field_offsets.sort();
+    field_offsets.dedup();
+
+    for field in flatbuffer.fields.iter_mut() {
+        if let Some(next_offset) = field_offsets.iter().find(|offset| **offset > field.offset) {
+            field.size = next_offset - field.offset;
+        }
+
+        // References are unsigned offsets relative to the location of the field itself
+        if field.size == REFERENCE_SIZE {
+            let field_location = flatbuffer.root_table_offset + field.offset;
+            if let Ok(reference) =
+                common::parse(&table_data[field.offset..], &root_structure, "little")
+            {
+                let target = field_location + reference["root_table_offset"];
+                // Strings, vectors, and tables are all 4-byte aligned
+                field.is_reference = reference["root_table_offset"] >= REFERENCE_SIZE
+                    && target < flatbuffer_data.len()
+                    && target.is_multiple_of(REFERENCE_SIZE);
+            }
+        }
+    }
+
+    Ok(flatbuffer)
+}
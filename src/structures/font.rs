@@ -0,0 +1,128 @@
+use crate::structures::common::{self, StructureError};
+
+/// Stores info about a TrueType / OpenType font
+#[derive(Debug, Default, Clone)]
+pub struct SFNTFont {
+    pub size: usize,
+    pub num_tables: usize,
+    pub font_type: String,
+}
+
+/// Parses a TrueType / OpenType (SFNT) font header and table directory to determine the font size
+pub fn parse_sfnt_font(font_data: &[u8]) -> Result<SFNTFont, StructureError> {
+    const TRUETYPE_VERSION: usize = 0x00010000;
+    const OPENTYPE_VERSION: usize = 0x4F54544F;
+    const APPLE_VERSION: usize = 0x74727565;
+    const TABLE_ENTRY_SIZE: usize = 16;
+    const MAX_TABLES: usize = 64;
+
+    let sfnt_structure = vec![
+        ("version", "u32"),
+        ("num_tables", "u16"),
+        ("search_range", "u16"),
+        ("entry_selector", "u16"),
+        ("range_shift", "u16"),
+    ];
+
+    let table_structure = vec![
+        ("tag", "u32"),
+        ("checksum", "u32"),
+        ("offset", "u32"),
+        ("length", "u32"),
+    ];
+
+    let sfnt_header = common::parse(font_data, &sfnt_structure, "big")?;
+
+    let font_type = match sfnt_header["version"] {
+        TRUETYPE_VERSION | APPLE_VERSION => "TrueType",
+        OPENTYPE_VERSION => "OpenType",
+        _ => return Err(StructureError),
+    };
+
+    let num_tables = sfnt_header["num_tables"];
+    if num_tables == 0 || num_tables > MAX_TABLES {
+        return Err(StructureError);
+    }
+
+    // The search fields are derived from the number of tables; validating them weeds out false positives
+    let entry_selector = num_tables.ilog2() as usize;
+    let search_range = (1 << entry_selector) * TABLE_ENTRY_SIZE;
+    let range_shift = (num_tables * TABLE_ENTRY_SIZE) - search_range;
+
+    if sfnt_header["search_range"] != search_range
+        || sfnt_header["entry_selector"] != entry_selector
+        || sfnt_header["range_shift"] != range_shift
+    {
+        return Err(StructureError);
+    }
+
+    let header_size = common::size(&sfnt_structure);
+    let directory_size = header_size + (num_tables * TABLE_ENTRY_SIZE);
+    let mut font_size = directory_size;
+
+    // The font ends at the end of the last table
+    for i in 0..num_tables {
+        let entry_offset = header_size + (i * TABLE_ENTRY_SIZE);
+        let table_data = font_data.get(entry_offset..).ok_or(StructureError)?;
+        let table_entry = common::parse(table_data, &table_structure, "big")?;
+
+        // Tables must be located after the table directory
+        if table_entry["offset"] < directory_size {
+            return Err(StructureError);
+        }
+
+        font_size = std::cmp::max(font_size, table_entry["offset"] + table_entry["length"]);
+    }
+
+    Ok(SFNTFont {
+        // Tables are padded to a 4-byte boundary
+        size: font_size.next_multiple_of(4),
+        num_tables,
+        font_type: font_type.to_string(),
+    })
+}
+
+/// Stores info about a WOFF / WOFF2 web font
+#[derive(Debug, Default, Clone)]
+pub struct WOFFFont {
+    pub version: usize,
+    pub size: usize,
+    pub num_tables: usize,
+}
+
+/// Parses a WOFF or WOFF2 web font header
+pub fn parse_woff_font(woff_data: &[u8]) -> Result<WOFFFont, StructureError> {
+    const WOFF1_MAGIC: usize = 0x774F4646;
+    const WOFF2_MAGIC: usize = 0x774F4632;
+
+    let woff_structure = vec![
+        ("magic", "u32"),
+        ("flavor", "u32"),
+        ("length", "u32"),
+        ("num_tables", "u16"),
+        ("reserved", "u16"),
+        ("total_sfnt_size", "u32"),
+    ];
+
+    let woff_header = common::parse(woff_data, &woff_structure, "big")?;
+
+    let version = match woff_header["magic"] {
+        WOFF1_MAGIC => 1,
+        WOFF2_MAGIC => 2,
+        _ => return Err(StructureError),
+    };
+
+    // Reserved field must be zero, and the file must be at least large enough to contain its header
+    if woff_header["reserved"] != 0
+        || woff_header["num_tables"] == 0
+        || woff_header["length"] <= common::size(&woff_structure)
+    {
+        return Err(StructureError);
+    }
+
+    Ok(WOFFFont {
+        version,
+        size: woff_header["length"],
+        num_tables: woff_header["num_tables"],
+    })
+}
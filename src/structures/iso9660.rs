@@ -69,3 +69,370 @@ pub fn parse_iso_header(iso_data: &[u8]) -> Result<ISOHeader, StructureError> {
 
     Err(StructureError)
 }
+
+/// Offset of the first volume descriptor from the beginning of the ISO image
+pub const ISO_VOLUME_DESCRIPTORS_OFFSET: usize = 32768;
+
+/// Size of each volume descriptor
+pub const ISO_VOLUME_DESCRIPTOR_SIZE: usize = 2048;
+
+/// Volume descriptor type of the primary volume descriptor
+pub const ISO_PRIMARY_VOLUME_DESCRIPTOR: usize = 1;
+
+/// Volume descriptor type of supplementary volume descriptors, such as the Joliet volume descriptor
+pub const ISO_SUPPLEMENTARY_VOLUME_DESCRIPTOR: usize = 2;
+
+/// Volume descriptor type of the volume descriptor set terminator
+pub const ISO_VOLUME_DESCRIPTOR_TERMINATOR: usize = 255;
+
+/// Stores info about an ISO9660 volume descriptor
+#[derive(Debug, Default, Clone)]
+pub struct ISOVolumeDescriptor {
+    pub descriptor_type: usize,
+    /// Set if this is a Joliet supplementary volume descriptor, whose file names are UCS-2 encoded
+    pub joliet: bool,
+    pub block_size: usize,
+    pub root_directory: ISODirectoryRecord,
+}
+
+/// Parses an ISO9660 volume descriptor; only the primary and supplementary volume descriptors contain a root directory
+pub fn parse_iso_volume_descriptor(
+    descriptor_data: &[u8],
+) -> Result<ISOVolumeDescriptor, StructureError> {
+    const MAGIC: &[u8] = b"CD001\x01";
+    const MAGIC_START: usize = 1;
+    const MAGIC_END: usize = MAGIC_START + MAGIC.len();
+    const ESCAPE_SEQUENCES_START: usize = 88;
+    const ESCAPE_SEQUENCES_END: usize = 120;
+    const BLOCK_SIZE_OFFSET: usize = 128;
+    const ROOT_DIRECTORY_OFFSET: usize = 156;
+    // Joliet escape sequences for UCS-2 levels 1, 2 and 3
+    const JOLIET_ESCAPE_SEQUENCES: [&[u8]; 3] = [b"%/@", b"%/C", b"%/E"];
+
+    let block_size_structure = vec![("block_size_lsb", "u16"), ("block_size_msb", "u16")];
+
+    if descriptor_data.get(MAGIC_START..MAGIC_END) != Some(MAGIC) {
+        return Err(StructureError);
+    }
+
+    let mut descriptor = ISOVolumeDescriptor {
+        descriptor_type: descriptor_data[0] as usize,
+        ..Default::default()
+    };
+
+    if descriptor.descriptor_type == ISO_PRIMARY_VOLUME_DESCRIPTOR
+        || descriptor.descriptor_type == ISO_SUPPLEMENTARY_VOLUME_DESCRIPTOR
+    {
+        let block_size = common::parse(
+            descriptor_data
+                .get(BLOCK_SIZE_OFFSET..)
+                .ok_or(StructureError)?,
+            &block_size_structure,
+            "little",
+        )?;
+
+        descriptor.block_size = block_size["block_size_lsb"];
+
+        // Logical blocks may be no larger than the 2048 byte sectors they are stored in
+        if !descriptor.block_size.is_power_of_two()
+            || descriptor.block_size < 512
+            || descriptor.block_size > ISO_VOLUME_DESCRIPTOR_SIZE
+        {
+            return Err(StructureError);
+        }
+
+        if descriptor.descriptor_type == ISO_SUPPLEMENTARY_VOLUME_DESCRIPTOR {
+            let escape_sequences = &descriptor_data[ESCAPE_SEQUENCES_START..ESCAPE_SEQUENCES_END];
+            descriptor.joliet = JOLIET_ESCAPE_SEQUENCES
+                .iter()
+                .any(|sequence| escape_sequences.starts_with(sequence));
+        }
+
+        descriptor.root_directory = parse_iso_directory_record(
+            descriptor_data
+                .get(ROOT_DIRECTORY_OFFSET..)
+                .ok_or(StructureError)?,
+        )?;
+    }
+
+    Ok(descriptor)
+}
+
+/// Stores info about an ISO9660 directory record
+#[derive(Debug, Default, Clone)]
+pub struct ISODirectoryRecord {
+    /// Size of the directory record, in bytes
+    pub size: usize,
+    /// Logical block where the file data starts
+    pub extent: usize,
+    pub data_length: usize,
+    pub directory: bool,
+    /// Set if the file's data continues in the extent of the next directory record
+    pub multi_extent: bool,
+    /// The raw file identifier; 0x00 and 0x01 identify the current and parent directories respectively
+    pub identifier: Vec<u8>,
+    /// The system use area, which contains any Rock Ridge entries
+    pub system_use: Vec<u8>,
+}
+
+/// Parses an ISO9660 directory record
+pub fn parse_iso_directory_record(
+    record_data: &[u8],
+) -> Result<ISODirectoryRecord, StructureError> {
+    const DIRECTORY_FLAG: usize = 0x02;
+    const MULTI_EXTENT_FLAG: usize = 0x80;
+
+    let record_structure = vec![
+        ("record_length", "u8"),
+        ("extended_attribute_length", "u8"),
+        ("extent_lsb", "u32"),
+        ("extent_msb", "u32"),
+        ("data_length_lsb", "u32"),
+        ("data_length_msb", "u32"),
+        ("recording_date", "u32"),
+        ("recording_time", "u24"),
+        ("flags", "u8"),
+        ("file_unit_size", "u8"),
+        ("interleave_gap_size", "u8"),
+        ("volume_sequence_lsb", "u16"),
+        ("volume_sequence_msb", "u16"),
+        ("identifier_length", "u8"),
+    ];
+
+    let header_size = common::size(&record_structure);
+    let record = common::parse(record_data, &record_structure, "little")?;
+
+    let record_size = record["record_length"];
+    let identifier_end = header_size + record["identifier_length"];
+
+    // Identifiers are padded to an even length
+    let system_use_start = identifier_end + (identifier_end % 2);
+
+    if record["identifier_length"] > 0 && identifier_end <= record_size {
+        if let Some(record_data) = record_data.get(..record_size) {
+            return Ok(ISODirectoryRecord {
+                size: record_size,
+                // Extended attribute records precede the file data
+                extent: record["extent_lsb"] + record["extended_attribute_length"],
+                data_length: record["data_length_lsb"],
+                directory: record["flags"] & DIRECTORY_FLAG != 0,
+                multi_extent: record["flags"] & MULTI_EXTENT_FLAG != 0,
+                identifier: record_data[header_size..identifier_end].to_vec(),
+                system_use: record_data
+                    .get(system_use_start..)
+                    .unwrap_or_default()
+                    .to_vec(),
+            });
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// A System Use Sharing Protocol entry, such as a Rock Ridge entry, from a directory record's system use area
+#[derive(Debug, Default, Clone)]
+pub struct SUSPEntry {
+    /// Two character entry signature, e.g., "NM"
+    pub signature: String,
+    /// Entry data, excluding the entry header
+    pub data: Vec<u8>,
+}
+
+/// Parses all System Use Sharing Protocol entries in a system use area
+pub fn parse_susp_entries(system_use_data: &[u8]) -> Vec<SUSPEntry> {
+    const ENTRY_HEADER_SIZE: usize = 4;
+    const TERMINATOR: &str = "ST";
+
+    let entry_structure = vec![("signature", "u16"), ("length", "u8"), ("version", "u8")];
+
+    let mut entries: Vec<SUSPEntry> = vec![];
+    let mut entry_offset: usize = 0;
+
+    while let Some(entry_data) = system_use_data.get(entry_offset..) {
+        let entry_length = match common::parse(entry_data, &entry_structure, "little") {
+            Err(_) => break,
+            Ok(entry_header) => entry_header["length"],
+        };
+
+        if entry_length < ENTRY_HEADER_SIZE {
+            break;
+        }
+
+        match entry_data.get(..entry_length) {
+            None => break,
+            Some(entry_data) => {
+                let signature = String::from_utf8_lossy(&entry_data[..2]).to_string();
+
+                if signature == TERMINATOR {
+                    break;
+                }
+
+                entries.push(SUSPEntry {
+                    signature,
+                    data: entry_data[ENTRY_HEADER_SIZE..].to_vec(),
+                });
+            }
+        }
+
+        entry_offset += entry_length;
+    }
+
+    entries
+}
+
+/// Location of additional System Use Sharing Protocol entries, as specified by a "CE" entry
+#[derive(Debug, Default, Clone)]
+pub struct SUSPContinuation {
+    pub block: usize,
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// Rock Ridge attributes of a file, accumulated from its System Use Sharing Protocol entries
+#[derive(Debug, Default, Clone)]
+pub struct RockRidgeAttributes {
+    /// Set if the system use area contains a "SP" entry, which indicates the use of the System Use Sharing Protocol
+    pub susp_indicator: bool,
+    pub name: Vec<u8>,
+    /// Set if the last "NM" entry indicated that the name continues in another "NM" entry
+    pub name_continues: bool,
+    pub symlink_components: Vec<Vec<u8>>,
+    /// Set if the last symlink component indicated that it continues in the next symlink component
+    pub symlink_component_continues: bool,
+    pub symlink: bool,
+    /// POSIX file mode
+    pub mode: Option<usize>,
+    /// Set for directories that have been relocated, which are also referenced by a child link
+    pub relocated: bool,
+    /// Logical block of a relocated directory that this entry refers to
+    pub child_link: Option<usize>,
+    pub continuation: Option<SUSPContinuation>,
+}
+
+impl RockRidgeAttributes {
+    /// Returns the symlink target path
+    pub fn symlink_target(&self) -> String {
+        let components: Vec<String> = self
+            .symlink_components
+            .iter()
+            .map(|component| String::from_utf8_lossy(component).to_string())
+            .collect();
+
+        match components.as_slice() {
+            // An empty first component is the root directory
+            [root] if root.is_empty() => "/".to_string(),
+            _ => components.join("/"),
+        }
+    }
+}
+
+/// Updates a file's Rock Ridge attributes with the contents of its System Use Sharing Protocol entries.
+///
+/// A file's entries may span multiple system use areas, so this may be called for each area in turn;
+/// any continuation area specified by the entries is stored in `attributes.continuation`.
+pub fn parse_rock_ridge_entries(entries: &[SUSPEntry], attributes: &mut RockRidgeAttributes) {
+    const SP_CHECK_BYTES: &[u8] = b"\xBE\xEF";
+    const CONTINUE: u8 = 0x01;
+    const CURRENT: u8 = 0x02;
+    const PARENT: u8 = 0x04;
+    const ROOT: u8 = 0x08;
+
+    let both_endian_structure = vec![("value_lsb", "u32"), ("value_msb", "u32")];
+    let continuation_structure = vec![
+        ("block_lsb", "u32"),
+        ("block_msb", "u32"),
+        ("offset_lsb", "u32"),
+        ("offset_msb", "u32"),
+        ("length_lsb", "u32"),
+        ("length_msb", "u32"),
+    ];
+
+    attributes.continuation = None;
+
+    for entry in entries {
+        match entry.signature.as_str() {
+            "SP" => {
+                attributes.susp_indicator = entry.data.starts_with(SP_CHECK_BYTES);
+            }
+            "CE" => {
+                if let Ok(continuation) =
+                    common::parse(&entry.data, &continuation_structure, "little")
+                {
+                    attributes.continuation = Some(SUSPContinuation {
+                        block: continuation["block_lsb"],
+                        offset: continuation["offset_lsb"],
+                        length: continuation["length_lsb"],
+                    });
+                }
+            }
+            "PX" => {
+                if let Ok(mode) = common::parse(&entry.data, &both_endian_structure, "little") {
+                    attributes.mode = Some(mode["value_lsb"]);
+                }
+            }
+            "CL" => {
+                if let Ok(location) = common::parse(&entry.data, &both_endian_structure, "little") {
+                    attributes.child_link = Some(location["value_lsb"]);
+                }
+            }
+            "RE" => {
+                attributes.relocated = true;
+            }
+            "NM" => {
+                if let Some((flags, name)) = entry.data.split_first() {
+                    if !attributes.name_continues {
+                        attributes.name.clear();
+                    }
+
+                    if flags & CURRENT != 0 {
+                        attributes.name = b".".to_vec();
+                    } else if flags & PARENT != 0 {
+                        attributes.name = b"..".to_vec();
+                    } else {
+                        attributes.name.extend_from_slice(name);
+                    }
+
+                    attributes.name_continues = flags & CONTINUE != 0;
+                }
+            }
+            "SL" => {
+                attributes.symlink = true;
+
+                // The first byte is the SL entry's flags, followed by the component records
+                let mut component_offset: usize = 1;
+
+                while let Some(component_header) =
+                    entry.data.get(component_offset..component_offset + 2)
+                {
+                    let component_flags = component_header[0];
+                    let component_start = component_offset + 2;
+                    let component_end = component_start + component_header[1] as usize;
+
+                    let component: Vec<u8> = if component_flags & CURRENT != 0 {
+                        b".".to_vec()
+                    } else if component_flags & PARENT != 0 {
+                        b"..".to_vec()
+                    } else if component_flags & ROOT != 0 {
+                        vec![]
+                    } else {
+                        match entry.data.get(component_start..component_end) {
+                            None => break,
+                            Some(component) => component.to_vec(),
+                        }
+                    };
+
+                    match attributes.symlink_components.last_mut() {
+                        Some(last_component) if attributes.symlink_component_continues => {
+                            last_component.extend(component);
+                        }
+                        _ => attributes.symlink_components.push(component),
+                    }
+
+                    attributes.symlink_component_continues = component_flags & CONTINUE != 0;
+                    component_offset = component_end;
+                }
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use crate::structures::common::{self, StructureError};
+use std::collections::HashMap;
+
+/// Stores info about a Java class file
+#[derive(Debug, Default, Clone)]
+pub struct JavaClassHeader {
+    /// Java SE version that the class file targets, e.g., "1.4" or "17"
+    pub java_version: String,
+    pub major_version: usize,
+    pub minor_version: usize,
+    /// True if the class file uses Java preview features
+    pub preview: bool,
+    /// Fully qualified class name, e.g., "com/example/Main"
+    pub class_name: String,
+    /// Total size of the class file, in bytes
+    pub size: usize,
+}
+
+/// Parses a Java class file
+pub fn parse_java_class(class_data: &[u8]) -> Result<JavaClassHeader, StructureError> {
+    const JAVA_CLASS_MAGIC: usize = 0xCAFEBABE;
+    // Java 1.0/1.1 through Java 26
+    const MIN_MAJOR_VERSION: usize = 45;
+    const MAX_MAJOR_VERSION: usize = 70;
+    // Preview features were introduced in Java 12
+    const PREVIEW_MINOR_VERSION: usize = 0xFFFF;
+    const MIN_PREVIEW_MAJOR_VERSION: usize = 56;
+
+    // Constant pool tags
+    const CONSTANT_UTF8: usize = 1;
+    const CONSTANT_CLASS: usize = 7;
+    const CONSTANT_LONG: usize = 5;
+    const CONSTANT_DOUBLE: usize = 6;
+
+    let class_structure = vec![
+        ("magic", "u32"),
+        ("minor_version", "u16"),
+        ("major_version", "u16"),
+        ("constant_pool_count", "u16"),
+    ];
+
+    let class_header = common::parse(class_data, &class_structure, "big")?;
+
+    if class_header["magic"] != JAVA_CLASS_MAGIC
+        || class_header["major_version"] < MIN_MAJOR_VERSION
+        || class_header["major_version"] > MAX_MAJOR_VERSION
+        || class_header["constant_pool_count"] == 0
+    {
+        return Err(StructureError);
+    }
+
+    let preview = class_header["minor_version"] == PREVIEW_MINOR_VERSION;
+    if preview && class_header["major_version"] < MIN_PREVIEW_MAJOR_VERSION {
+        return Err(StructureError);
+    }
+
+    // Constant pool tags and the size of their associated data; some entries are variable length and handled separately
+    let constant_sizes: HashMap<usize, usize> = HashMap::from([
+        (3, 4),  // Integer
+        (4, 4),  // Float
+        (5, 8),  // Long
+        (6, 8),  // Double
+        (7, 2),  // Class
+        (8, 2),  // String
+        (9, 4),  // Fieldref
+        (10, 4), // Methodref
+        (11, 4), // InterfaceMethodref
+        (12, 4), // NameAndType
+        (15, 3), // MethodHandle
+        (16, 2), // MethodType
+        (17, 4), // Dynamic
+        (18, 4), // InvokeDynamic
+        (19, 2), // Module
+        (20, 2), // Package
+    ]);
+
+    let mut offset: usize = common::size(&class_structure);
+
+    // UTF-8 strings and class name indexes in the constant pool, keyed by their constant pool index
+    let mut utf8_constants: HashMap<usize, String> = HashMap::new();
+    let mut class_constants: HashMap<usize, usize> = HashMap::new();
+
+    // Constant pool indexes start at 1
+    let mut constant_index: usize = 1;
+
+    while constant_index < class_header["constant_pool_count"] {
+        let tag = *class_data.get(offset).ok_or(StructureError)? as usize;
+        offset += 1;
+
+        if tag == CONSTANT_UTF8 {
+            let utf8_structure = vec![("length", "u16")];
+            let utf8_header = common::parse(
+                class_data.get(offset..).ok_or(StructureError)?,
+                &utf8_structure,
+                "big",
+            )?;
+
+            let string_start = offset + common::size(&utf8_structure);
+            let string_end = string_start + utf8_header["length"];
+            let string_data = class_data
+                .get(string_start..string_end)
+                .ok_or(StructureError)?;
+
+            utf8_constants.insert(
+                constant_index,
+                String::from_utf8_lossy(string_data).to_string(),
+            );
+            offset = string_end;
+        } else if let Some(constant_size) = constant_sizes.get(&tag) {
+            if tag == CONSTANT_CLASS {
+                let class_constant_structure = vec![("name_index", "u16")];
+                let class_constant = common::parse(
+                    class_data.get(offset..).ok_or(StructureError)?,
+                    &class_constant_structure,
+                    "big",
+                )?;
+                class_constants.insert(constant_index, class_constant["name_index"]);
+            }
+            offset += constant_size;
+        } else {
+            return Err(StructureError);
+        }
+
+        // Long and double constants occupy two entries in the constant pool
+        if tag == CONSTANT_LONG || tag == CONSTANT_DOUBLE {
+            constant_index += 1;
+        }
+
+        constant_index += 1;
+    }
+
+    let class_info_structure = vec![
+        ("access_flags", "u16"),
+        ("this_class", "u16"),
+        ("super_class", "u16"),
+        ("interfaces_count", "u16"),
+    ];
+
+    let class_info = common::parse(
+        class_data.get(offset..).ok_or(StructureError)?,
+        &class_info_structure,
+        "big",
+    )?;
+
+    // The class name is a UTF-8 constant, referenced by the class constant that this_class points to
+    let class_name = class_constants
+        .get(&class_info["this_class"])
+        .and_then(|name_index| utf8_constants.get(name_index))
+        .ok_or(StructureError)?;
+
+    // Each interface is a 2-byte constant pool index
+    offset += common::size(&class_info_structure) + (class_info["interfaces_count"] * 2);
+
+    // Skip over the fields and methods, which share the same format
+    for _ in 0..2 {
+        let count_structure = vec![("count", "u16")];
+        let member_structure = vec![
+            ("access_flags", "u16"),
+            ("name_index", "u16"),
+            ("descriptor_index", "u16"),
+            ("attributes_count", "u16"),
+        ];
+
+        let member_count = common::parse(
+            class_data.get(offset..).ok_or(StructureError)?,
+            &count_structure,
+            "big",
+        )?;
+        offset += common::size(&count_structure);
+
+        for _ in 0..member_count["count"] {
+            let member = common::parse(
+                class_data.get(offset..).ok_or(StructureError)?,
+                &member_structure,
+                "big",
+            )?;
+            offset += common::size(&member_structure);
+            offset = skip_class_attributes(class_data, offset, member["attributes_count"])?;
+        }
+    }
+
+    // Class attributes are the last thing in the class file
+    let attributes_structure = vec![("attributes_count", "u16")];
+    let class_attributes = common::parse(
+        class_data.get(offset..).ok_or(StructureError)?,
+        &attributes_structure,
+        "big",
+    )?;
+    offset += common::size(&attributes_structure);
+    offset = skip_class_attributes(class_data, offset, class_attributes["attributes_count"])?;
+
+    let java_version = match class_header["major_version"] {
+        45 => "1.1".to_string(),
+        46 => "1.2".to_string(),
+        47 => "1.3".to_string(),
+        48 => "1.4".to_string(),
+        major_version => (major_version - 44).to_string(),
+    };
+
+    Ok(JavaClassHeader {
+        java_version,
+        major_version: class_header["major_version"],
+        minor_version: class_header["minor_version"],
+        preview,
+        class_name: class_name.clone(),
+        size: offset,
+    })
+}
+
+/// Skips over a list of class file attributes, returning the offset of the end of the attribute list
+fn skip_class_attributes(
+    class_data: &[u8],
+    mut offset: usize,
+    attribute_count: usize,
+) -> Result<usize, StructureError> {
+    let attribute_structure = vec![("name_index", "u16"), ("length", "u32")];
+
+    for _ in 0..attribute_count {
+        let attribute = common::parse(
+            class_data.get(offset..).ok_or(StructureError)?,
+            &attribute_structure,
+            "big",
+        )?;
+        offset += common::size(&attribute_structure) + attribute["length"];
+    }
+
+    // Make sure the last attribute's data is actually in bounds
+    if offset > class_data.len() {
+        return Err(StructureError);
+    }
+
+    Ok(offset)
+}
+
+/// Stores info about a JAR manifest
+#[derive(Debug, Default, Clone)]
+pub struct JarManifest {
+    /// Tool (and usually JDK version) that created the JAR, from the Created-By attribute
+    pub created_by: String,
+    /// JDK version used to build the JAR, from the Build-Jdk or Build-Jdk-Spec attributes
+    pub build_jdk: String,
+    pub main_class: String,
+}
+
+/// Parses the main section of a JAR manifest (META-INF/MANIFEST.MF)
+pub fn parse_jar_manifest(manifest_data: &[u8]) -> Result<JarManifest, StructureError> {
+    const MANIFEST_VERSION: &str = "Manifest-Version";
+
+    let manifest_text = String::from_utf8_lossy(manifest_data);
+    let mut manifest = JarManifest {
+        ..Default::default()
+    };
+
+    let mut lines = manifest_text.lines();
+
+    // Manifests must start with the manifest version
+    match lines.next() {
+        Some(line) if line.starts_with(MANIFEST_VERSION) => (),
+        _ => return Err(StructureError),
+    }
+
+    for line in lines {
+        // The main section ends at the first blank line
+        if line.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim().to_string();
+
+            match name.trim() {
+                "Created-By" => manifest.created_by = value,
+                "Build-Jdk" => manifest.build_jdk = value,
+                "Build-Jdk-Spec" if manifest.build_jdk.is_empty() => manifest.build_jdk = value,
+                "Main-Class" => manifest.main_class = value,
+                _ => (),
+            }
+        }
+    }
+
+    Ok(manifest)
+}
@@ -70,3 +70,150 @@ pub fn parse_jffs2_node_header(node_data: &[u8]) -> Result<JFFS2Node, StructureE
 fn jffs2_node_crc(file_data: &[u8]) -> usize {
     (crc32_v2::crc32(0xFFFFFFFF, file_data) ^ 0xFFFFFFFF) as usize
 }
+
+/// JFFS2 directory entry node type
+pub const JFFS2_NODETYPE_DIRENT: u16 = 0xE001;
+
+/// JFFS2 inode node type
+pub const JFFS2_NODETYPE_INODE: u16 = 0xE002;
+
+/// Structure for storing JFFS2 directory entry info
+#[derive(Debug, Default, Clone)]
+pub struct JFFS2Dirent {
+    pub parent_inode: usize,
+    pub version: usize,
+    /// Inode number of the entry; 0 if the entry has been deleted
+    pub inode: usize,
+    pub dirent_type: usize,
+    pub name: String,
+}
+
+/// Parse a JFFS2 directory entry node
+pub fn parse_jffs2_dirent(
+    node_data: &[u8],
+    endianness: &str,
+) -> Result<JFFS2Dirent, StructureError> {
+    // Number of bytes over which the node CRC is calculated
+    const JFFS2_DIRENT_CRC_SIZE: usize = 32;
+
+    let dirent_structure = vec![
+        ("magic", "u16"),
+        ("type", "u16"),
+        ("size", "u32"),
+        ("header_crc", "u32"),
+        ("parent_inode", "u32"),
+        ("version", "u32"),
+        ("inode", "u32"),
+        ("mctime", "u32"),
+        ("name_size", "u8"),
+        ("dirent_type", "u8"),
+        ("unused", "u16"),
+        ("node_crc", "u32"),
+        ("name_crc", "u32"),
+    ];
+
+    let dirent_header_size = common::size(&dirent_structure);
+
+    let dirent = common::parse(node_data, &dirent_structure, endianness)?;
+
+    // Validate the node CRC
+    if jffs2_node_crc(&node_data[0..JFFS2_DIRENT_CRC_SIZE]) != dirent["node_crc"] {
+        return Err(StructureError);
+    }
+
+    let name_end = dirent_header_size + dirent["name_size"];
+
+    if let Some(name_bytes) = node_data.get(dirent_header_size..name_end) {
+        // Validate the name CRC
+        if jffs2_node_crc(name_bytes) == dirent["name_crc"] {
+            if let Ok(name) = String::from_utf8(name_bytes.to_vec()) {
+                return Ok(JFFS2Dirent {
+                    parent_inode: dirent["parent_inode"],
+                    version: dirent["version"],
+                    inode: dirent["inode"],
+                    dirent_type: dirent["dirent_type"],
+                    name,
+                });
+            }
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// Structure for storing JFFS2 inode info
+#[derive(Debug, Default, Clone)]
+pub struct JFFS2Inode {
+    pub inode: usize,
+    pub version: usize,
+    pub mode: usize,
+    /// Size of the file, as of this version of the inode
+    pub file_size: usize,
+    /// Offset in the file where this node's data belongs
+    pub data_offset: usize,
+    pub compressed_size: usize,
+    pub decompressed_size: usize,
+    pub compression: usize,
+    /// Offset of the node data, relative to the start of the node
+    pub header_size: usize,
+}
+
+/// Parse a JFFS2 inode node
+pub fn parse_jffs2_inode(node_data: &[u8], endianness: &str) -> Result<JFFS2Inode, StructureError> {
+    // Number of bytes over which the node CRC is calculated
+    const JFFS2_INODE_CRC_SIZE: usize = 60;
+
+    let inode_structure = vec![
+        ("magic", "u16"),
+        ("type", "u16"),
+        ("size", "u32"),
+        ("header_crc", "u32"),
+        ("inode", "u32"),
+        ("version", "u32"),
+        ("mode", "u32"),
+        ("uid", "u16"),
+        ("gid", "u16"),
+        ("file_size", "u32"),
+        ("atime", "u32"),
+        ("mtime", "u32"),
+        ("ctime", "u32"),
+        ("data_offset", "u32"),
+        ("compressed_size", "u32"),
+        ("decompressed_size", "u32"),
+        ("compression", "u8"),
+        ("user_compression", "u8"),
+        ("flags", "u16"),
+        ("data_crc", "u32"),
+        ("node_crc", "u32"),
+    ];
+
+    let inode_header_size = common::size(&inode_structure);
+
+    let inode = common::parse(node_data, &inode_structure, endianness)?;
+
+    // Validate the node CRC
+    if jffs2_node_crc(&node_data[0..JFFS2_INODE_CRC_SIZE]) != inode["node_crc"] {
+        return Err(StructureError);
+    }
+
+    let data_end = inode_header_size + inode["compressed_size"];
+
+    if let Some(inode_data) = node_data.get(inode_header_size..data_end) {
+        // Validate the data CRC
+        if jffs2_node_crc(inode_data) == inode["data_crc"] {
+            return Ok(JFFS2Inode {
+                inode: inode["inode"],
+                version: inode["version"],
+                mode: inode["mode"],
+                file_size: inode["file_size"],
+                data_offset: inode["data_offset"],
+                compressed_size: inode["compressed_size"],
+                decompressed_size: inode["decompressed_size"],
+                compression: inode["compression"],
+                header_size: inode_header_size,
+            });
+        }
+    }
+
+    Err(StructureError)
+}
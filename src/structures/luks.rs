@@ -1,5 +1,6 @@
 use crate::common::get_cstring;
 use crate::structures::common::{self, StructureError};
+use serde_json::Value;
 
 /// Struct to store some useful LUKS info
 #[derive(Debug, Default, Clone)]
@@ -9,6 +10,16 @@ pub struct LUKSHeader {
     pub hashfn: String,
     pub cipher_mode: String,
     pub cipher_algorithm: String,
+    /// Volume UUID
+    pub uuid: String,
+    /// Volume label; only available in LUKS2
+    pub label: String,
+    /// Size of the volume key, in bytes
+    pub key_size: usize,
+    /// Number of key slots in use
+    pub key_slots: usize,
+    /// Offset of the encrypted data, relative to the start of the header
+    pub data_offset: usize,
 }
 
 /// Partially parses a LUKS header
@@ -67,6 +78,7 @@ pub fn parse_luks_header(luks_data: &[u8]) -> Result<LUKSHeader, StructureError>
                             // Make sure there were valid strings specified for both cipher algo and cipher mode
                             if !luks_hdr_info.cipher_mode.is_empty()
                                 && !luks_hdr_info.cipher_algorithm.is_empty()
+                                && parse_luks1_key_slots(luks_data, &mut luks_hdr_info).is_ok()
                             {
                                 return Ok(luks_hdr_info);
                             }
@@ -80,6 +92,7 @@ pub fn parse_luks_header(luks_data: &[u8]) -> Result<LUKSHeader, StructureError>
                     if luks_hdr_info.header_size > LUKS2_MIN_HEADER_SIZE
                         && luks_hdr_info.header_size < luks_data.len()
                     {
+                        parse_luks2_metadata(luks_data, &mut luks_hdr_info);
                         return Ok(luks_hdr_info);
                     }
                 }
@@ -89,3 +102,97 @@ pub fn parse_luks_header(luks_data: &[u8]) -> Result<LUKSHeader, StructureError>
 
     Err(StructureError)
 }
+
+/// Parses the payload offset, volume key size, UUID, and key slots of a LUKS1 header
+fn parse_luks1_key_slots(
+    luks_data: &[u8],
+    luks_hdr_info: &mut LUKSHeader,
+) -> Result<(), StructureError> {
+    const KEY_INFO_START: usize = 104;
+    const UUID_START: usize = 168;
+    const UUID_END: usize = 208;
+    const KEY_SLOTS_START: usize = 208;
+    const KEY_SLOT_SIZE: usize = 48;
+    const KEY_SLOT_COUNT: usize = 8;
+    const KEY_SLOT_ENABLED: usize = 0x00AC71F3;
+    const KEY_SLOT_DISABLED: usize = 0x0000DEAD;
+    const SECTOR_SIZE: usize = 512;
+
+    let key_info_structure = vec![("payload_offset", "u32"), ("key_bytes", "u32")];
+    let key_slot_structure = vec![("active", "u32"), ("iterations", "u32")];
+
+    let key_info = common::parse(
+        luks_data.get(KEY_INFO_START..).ok_or(StructureError)?,
+        &key_info_structure,
+        "big",
+    )?;
+
+    luks_hdr_info.data_offset = key_info["payload_offset"] * SECTOR_SIZE;
+    luks_hdr_info.key_size = key_info["key_bytes"];
+    luks_hdr_info.uuid = get_cstring(luks_data.get(UUID_START..UUID_END).ok_or(StructureError)?);
+
+    for i in 0..KEY_SLOT_COUNT {
+        let key_slot_start = KEY_SLOTS_START + (i * KEY_SLOT_SIZE);
+        let key_slot = common::parse(
+            luks_data.get(key_slot_start..).ok_or(StructureError)?,
+            &key_slot_structure,
+            "big",
+        )?;
+
+        match key_slot["active"] {
+            KEY_SLOT_ENABLED => luks_hdr_info.key_slots += 1,
+            KEY_SLOT_DISABLED => (),
+            _ => return Err(StructureError),
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses the label and UUID of a LUKS2 binary header, and the cipher, key slots and data offset from its JSON metadata
+fn parse_luks2_metadata(luks_data: &[u8], luks_hdr_info: &mut LUKSHeader) {
+    const LABEL_START: usize = 24;
+    const LABEL_END: usize = 72;
+    const UUID_START: usize = 168;
+    const UUID_END: usize = 208;
+    const JSON_START: usize = 4096;
+
+    luks_hdr_info.label = get_cstring(&luks_data[LABEL_START..LABEL_END]);
+    luks_hdr_info.uuid = get_cstring(&luks_data[UUID_START..UUID_END]);
+
+    // The JSON metadata follows the binary header, and is padded with NULL bytes
+    if let Some(json_data) = luks_data.get(JSON_START..luks_hdr_info.header_size) {
+        if let Ok(metadata) = serde_json::from_str::<Value>(&get_cstring(json_data)) {
+            if let Some(key_slots) = metadata["keyslots"].as_object() {
+                luks_hdr_info.key_slots = key_slots.len();
+                luks_hdr_info.key_size = key_slots
+                    .values()
+                    .filter_map(|key_slot| key_slot["key_size"].as_u64())
+                    .max()
+                    .unwrap_or(0) as usize;
+            }
+
+            // Use the first segment; LUKS2 volumes only have multiple segments while being re-encrypted
+            if let Some(segment) = metadata["segments"]
+                .as_object()
+                .and_then(|segments| segments.values().next())
+            {
+                // e.g., "aes-xts-plain64"
+                if let Some(encryption) = segment["encryption"].as_str() {
+                    let (cipher_algorithm, cipher_mode) =
+                        encryption.split_once('-').unwrap_or((encryption, ""));
+                    luks_hdr_info.cipher_algorithm = cipher_algorithm.to_string();
+                    luks_hdr_info.cipher_mode = cipher_mode.to_string();
+                }
+
+                // Sizes and offsets are stored as strings, to support 64 bit values
+                if let Some(data_offset) = segment["offset"]
+                    .as_str()
+                    .and_then(|offset| offset.parse::<usize>().ok())
+                {
+                    luks_hdr_info.data_offset = data_offset;
+                }
+            }
+        }
+    }
+}
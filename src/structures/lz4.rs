@@ -1,10 +1,17 @@
 use crate::structures::common::{self, StructureError};
 use xxhash_rust;
 
+/// LZ4 frame format magic bytes
+pub const LZ4_FRAME_MAGIC: &[u8] = b"\x04\x22\x4D\x18";
+
+/// LZ4 legacy format magic bytes, as used by compressed Linux kernels
+pub const LZ4_LEGACY_MAGIC: &[u8] = b"\x02\x21\x4C\x18";
+
 /// Struct to store LZ4 file header info
 #[derive(Debug, Default, Clone)]
 pub struct LZ4FileHeader {
     pub header_size: usize,
+    pub max_block_size: usize,
     pub block_checksum_present: bool,
     pub content_checksum_present: bool,
 }
@@ -26,6 +33,10 @@ pub fn parse_lz4_file_header(lz4_data: &[u8]) -> Result<LZ4FileHeader, Structure
     const DICTIONARY_LEN: usize = 4;
     const CONTENT_SIZE_LEN: usize = 8;
 
+    const BD_BLOCK_MAX_SIZE_MASK: usize = 0b01110000;
+    const BD_BLOCK_MAX_SIZE_SHIFT: usize = 4;
+    const MIN_BLOCK_MAX_SIZE_ID: usize = 4;
+
     // Basic LZ4 header; optional fields and header CRC byte follow
     let lz4_structure = vec![("magic", "u32"), ("flags", "u8"), ("bd", "u8")];
 
@@ -36,8 +47,12 @@ pub fn parse_lz4_file_header(lz4_data: &[u8]) -> Result<LZ4FileHeader, Structure
     // Parse the header
     if let Ok(lz4_header) = common::parse(lz4_data, &lz4_structure, "little") {
         // Make sure the reserved bits aren't set
+        let block_max_size_id =
+            (lz4_header["bd"] & BD_BLOCK_MAX_SIZE_MASK) >> BD_BLOCK_MAX_SIZE_SHIFT;
+
         if (lz4_header["flags"] & FLAGS_RESERVED_MASK) == 0
             && (lz4_header["bd"] & BD_RESERVED_MASK) == 0
+            && block_max_size_id >= MIN_BLOCK_MAX_SIZE_ID
         {
             /*
              * Calculate the start and end of data used to calculate the header CRC.
@@ -69,6 +84,8 @@ pub fn parse_lz4_file_header(lz4_data: &[u8]) -> Result<LZ4FileHeader, Structure
                     if *actual_crc == calculated_crc {
                         // Data blocks start immediately after the header checksum byte
                         lz4_hdr_info.header_size = crc_data_end + 1;
+                        // Block max size IDs 4-7 correspond to 64KB, 256KB, 1MB and 4MB
+                        lz4_hdr_info.max_block_size = 1 << (8 + (2 * block_max_size_id));
                         lz4_hdr_info.block_checksum_present =
                             (lz4_header["flags"] & FLAG_BLOCK_CHECKSUM_PRESENT) != 0;
                         lz4_hdr_info.content_checksum_present =
@@ -91,6 +108,8 @@ pub struct LZ4BlockHeader {
     pub header_size: usize,
     pub checksum_size: usize,
     pub last_block: bool,
+    /// If set, the block data is stored uncompressed
+    pub uncompressed: bool,
 }
 
 /// Parse an LZ4 block header
@@ -100,6 +119,7 @@ pub fn parse_lz4_block_header(
 ) -> Result<LZ4BlockHeader, StructureError> {
     // Useful constants
     const SIZE_MASK: u32 = 0x7FFFFFFF;
+    const UNCOMPRESSED_MASK: u32 = 0x80000000;
     const END_MARKER: usize = 0;
     const CHECKSUM_SIZE: usize = 4;
     const BLOCK_STRUCT_SIZE: usize = 4;
@@ -119,13 +139,14 @@ pub fn parse_lz4_block_header(
         // If file size is 0, this is the end of the LZ4 data
         lz4_block.last_block = block_header["block_size"] == END_MARKER;
 
-        // If a checksum is present, it will be an extra 4 bytes at the end of the block
-        if checksum_present {
+        // If a checksum is present, it will be an extra 4 bytes at the end of the block; the end marker has no checksum
+        if checksum_present && !lz4_block.last_block {
             lz4_block.checksum_size = CHECKSUM_SIZE;
         }
 
         // The high bit of the reported block size is not part of the actual block size
         lz4_block.data_size = ((block_header["block_size"] as u32) & SIZE_MASK) as usize;
+        lz4_block.uncompressed = ((block_header["block_size"] as u32) & UNCOMPRESSED_MASK) != 0;
 
         return Ok(lz4_block);
     }
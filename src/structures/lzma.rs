@@ -1,17 +1,25 @@
 use crate::structures::common::{self, StructureError};
 
+/// Size of an LZMA header
+pub const LZMA_HEADER_SIZE: usize = 13;
+
+/// Streamed data has a reported size of -1
+pub const LZMA_STREAM_SIZE: usize = 0xFFFFFFFFFFFFFFFF;
+
 /// Struct to store useful LZMA header data
 #[derive(Debug, Default, Clone)]
 pub struct LZMAHeader {
     pub properties: usize,
     pub dictionary_size: usize,
     pub decompressed_size: usize,
+    /// False if the reported decompressed size is not sane; such streams must be decoded as if their size is unknown
+    pub decompressed_size_valid: bool,
 }
 
 /// Parse an LZMA header
 pub fn parse_lzma_header(lzma_data: &[u8]) -> Result<LZMAHeader, StructureError> {
-    // Streamed data has a reported size of -1
-    const LZMA_STREAM_SIZE: usize = 0xFFFFFFFFFFFFFFFF;
+    // Properties byte encodes (pb * 5 + lp) * 9 + lc, where pb <= 4, lp <= 4, lc <= 8
+    const MAX_PROPERTIES: usize = (4 * 5 + 4) * 9 + 8;
 
     // Some sane min and max values on the reported decompressed data size
     const MIN_SUPPORTED_DECOMPRESSED_SIZE: usize = 256;
@@ -24,25 +32,24 @@ pub fn parse_lzma_header(lzma_data: &[u8]) -> Result<LZMAHeader, StructureError>
         ("null_byte", "u8"),
     ];
 
-    let mut lzma_hdr_info = LZMAHeader {
-        ..Default::default()
-    };
-
     // Parse the lzma header
     if let Ok(lzma_header) = common::parse(lzma_data, &lzma_structure, "little") {
         // Make sure the expected NULL byte is NULL
-        if lzma_header["null_byte"] == 0 {
-            // Sanity check the reported decompressed size
-            if lzma_header["decompressed_size"] >= MIN_SUPPORTED_DECOMPRESSED_SIZE
-                && (lzma_header["decompressed_size"] == LZMA_STREAM_SIZE
-                    || lzma_header["decompressed_size"] <= MAX_SUPPORTED_DECOMPRESSED_SIZE)
-            {
-                lzma_hdr_info.properties = lzma_header["properties"];
-                lzma_hdr_info.dictionary_size = lzma_header["dictionary_size"];
-                lzma_hdr_info.decompressed_size = lzma_header["decompressed_size"];
-
-                return Ok(lzma_hdr_info);
-            }
+        if lzma_header["null_byte"] == 0 && lzma_header["properties"] <= MAX_PROPERTIES {
+            /*
+             * Firmware often contains LZMA streams whose decompressed size field is garbage (or zero);
+             * these are still reported, but the size field can not be trusted.
+             */
+            let decompressed_size_valid = lzma_header["decompressed_size"] == LZMA_STREAM_SIZE
+                || (lzma_header["decompressed_size"] >= MIN_SUPPORTED_DECOMPRESSED_SIZE
+                    && lzma_header["decompressed_size"] <= MAX_SUPPORTED_DECOMPRESSED_SIZE);
+
+            return Ok(LZMAHeader {
+                properties: lzma_header["properties"],
+                dictionary_size: lzma_header["dictionary_size"],
+                decompressed_size: lzma_header["decompressed_size"],
+                decompressed_size_valid,
+            });
         }
     }
 
@@ -7,7 +7,12 @@ const LZO_CHECKSUM_SIZE: usize = 4;
 #[derive(Debug, Default, Clone)]
 pub struct LZOPFileHeader {
     pub header_size: usize,
+    /// Blocks include a checksum of the compressed data
     pub block_checksum_present: bool,
+    /// Blocks include an adler32 checksum of the uncompressed data
+    pub uncompressed_adler32_present: bool,
+    /// Blocks include a CRC32 checksum of the uncompressed data
+    pub uncompressed_crc32_present: bool,
 }
 
 /// Parse an LZOP file header
@@ -21,9 +26,9 @@ pub fn parse_lzop_file_header(lzop_data: &[u8]) -> Result<LZOPFileHeader, Struct
     const FILTER_SIZE: usize = 4;
 
     const FLAG_FILTER: usize = 0x000_00800;
-    //const FLAG_CRC32_D: usize = 0x0000_0100;
+    const FLAG_CRC32_D: usize = 0x0000_0100;
     const FLAG_CRC32_C: usize = 0x0000_0200;
-    //const FLAG_ADLER32_D: usize = 0x0000_0001;
+    const FLAG_ADLER32_D: usize = 0x0000_0001;
     const FLAG_ADLER32_C: usize = 0x0000_0002;
 
     let lzo_structure_p1 = vec![
@@ -78,9 +83,13 @@ pub fn parse_lzop_file_header(lzop_data: &[u8]) -> Result<LZOPFileHeader, Struct
                         lzop_info.header_size =
                             header_p2_end + lzo_header_p2["file_name_length"] + LZO_CHECKSUM_SIZE;
 
-                        // Check if block headers include an optional compressed data checksum field
+                        // Check if block headers include optional compressed and uncompressed data checksum fields
                         lzop_info.block_checksum_present =
-                            (lzo_header_p1["flags"] & FLAG_ADLER32_C & FLAG_CRC32_C) != 0;
+                            (lzo_header_p1["flags"] & (FLAG_ADLER32_C | FLAG_CRC32_C)) != 0;
+                        lzop_info.uncompressed_adler32_present =
+                            (lzo_header_p1["flags"] & FLAG_ADLER32_D) != 0;
+                        lzop_info.uncompressed_crc32_present =
+                            (lzo_header_p1["flags"] & FLAG_CRC32_D) != 0;
 
                         // Sanity check on the calculated header size
                         if lzop_info.header_size <= lzop_data.len() {
@@ -98,33 +107,36 @@ pub fn parse_lzop_file_header(lzop_data: &[u8]) -> Result<LZOPFileHeader, Struct
 /// Struct to store info on LZOP block headers
 #[derive(Debug, Default, Clone)]
 pub struct LZOPBlockHeader {
+    /// Size of the block header, including the uncompressed data checksum, if any
     pub header_size: usize,
     pub compressed_size: usize,
     pub uncompressed_size: usize,
+    /// Size of the compressed data checksum, which sits between the block header and the block data
     pub checksum_size: usize,
+    /// Checksum of the uncompressed data, if present
+    pub uncompressed_checksum: Option<u32>,
+    /// If set, the block data is stored uncompressed
+    pub uncompressed: bool,
 }
 
 /// Parse an LZO block header
 pub fn parse_lzop_block_header(
     lzo_data: &[u8],
-    compressed_checksum_present: bool,
+    lzop_header: &LZOPFileHeader,
 ) -> Result<LZOPBlockHeader, StructureError> {
     // Size constants
-    const BLOCK_HEADER_SIZE: usize = 12;
+    const BLOCK_HEADER_SIZE: usize = 8;
     const MAX_UNCOMPRESSED_BLOCK_SIZE: usize = 64 * 1024 * 1024;
 
-    let block_structure = vec![
-        ("uncompressed_size", "u32"),
-        ("compressed_size", "u32"),
-        ("uncompressed_checksum", "u32"),
-    ];
+    let block_structure = vec![("uncompressed_size", "u32"), ("compressed_size", "u32")];
+    let checksum_structure = vec![("checksum", "u32")];
 
     // Parse the block header
     if let Ok(block_header) = common::parse(lzo_data, &block_structure, "big") {
-        // Basic sanity check on the block header values
+        // Basic sanity check on the block header values; blocks that don't compress are stored uncompressed
         if block_header["compressed_size"] != 0
             && block_header["uncompressed_size"] != 0
-            && block_header["uncompressed_checksum"] != 0
+            && block_header["compressed_size"] <= block_header["uncompressed_size"]
             && block_header["uncompressed_size"] <= MAX_UNCOMPRESSED_BLOCK_SIZE
         {
             let mut block_hdr_info = LZOPBlockHeader {
@@ -134,9 +146,23 @@ pub fn parse_lzop_block_header(
             block_hdr_info.header_size = BLOCK_HEADER_SIZE;
             block_hdr_info.compressed_size = block_header["compressed_size"];
             block_hdr_info.uncompressed_size = block_header["uncompressed_size"];
+            block_hdr_info.uncompressed =
+                block_hdr_info.compressed_size == block_hdr_info.uncompressed_size;
+
+            // Uncompressed data checksum field is optional
+            if lzop_header.uncompressed_adler32_present || lzop_header.uncompressed_crc32_present
+            {
+                let checksum = common::parse(
+                    &lzo_data[BLOCK_HEADER_SIZE..],
+                    &checksum_structure,
+                    "big",
+                )?;
+                block_hdr_info.uncompressed_checksum = Some(checksum["checksum"] as u32);
+                block_hdr_info.header_size += LZO_CHECKSUM_SIZE;
+            }
 
-            // Checksum field is optional
-            if compressed_checksum_present {
+            // Compressed data checksum field is optional, and is omitted for uncompressed blocks
+            if lzop_header.block_checksum_present && !block_hdr_info.uncompressed {
                 block_hdr_info.checksum_size = LZO_CHECKSUM_SIZE;
             }
 
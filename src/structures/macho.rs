@@ -0,0 +1,448 @@
+use crate::common::get_cstring;
+use crate::structures::common::{self, StructureError};
+
+/// Load commands that are parsed
+const LC_SEGMENT: usize = 0x1;
+const LC_LOAD_DYLIB: usize = 0xC;
+const LC_ID_DYLIB: usize = 0xD;
+const LC_LOAD_DYLINKER: usize = 0xE;
+const LC_SEGMENT_64: usize = 0x19;
+const LC_UUID: usize = 0x1B;
+const LC_CODE_SIGNATURE: usize = 0x1D;
+const LC_ENCRYPTION_INFO: usize = 0x21;
+const LC_VERSION_MIN_MACOSX: usize = 0x24;
+const LC_VERSION_MIN_IPHONEOS: usize = 0x25;
+const LC_ENCRYPTION_INFO_64: usize = 0x2C;
+const LC_VERSION_MIN_TVOS: usize = 0x2F;
+const LC_VERSION_MIN_WATCHOS: usize = 0x30;
+const LC_BUILD_VERSION: usize = 0x32;
+const LC_LOAD_WEAK_DYLIB: usize = 0x80000018;
+const LC_REEXPORT_DYLIB: usize = 0x8000001F;
+
+/// Stores info about a Mach-O binary
+#[derive(Debug, Default, Clone)]
+pub struct MachOHeader {
+    /// 32 or 64
+    pub bits: usize,
+    pub endianness: String,
+    pub cpu_type: String,
+    pub file_type: String,
+    pub load_command_count: usize,
+    /// Path to the dynamic linker, e.g., "/usr/lib/dyld"
+    pub dylinker: String,
+    /// Install name of a dynamic library
+    pub install_name: String,
+    /// Number of dynamic libraries that the binary links against
+    pub library_count: usize,
+    pub uuid: String,
+    /// Target platform and minimum OS version, e.g., "macOS 11.0"
+    pub platform: String,
+    /// True if the binary has a code signature
+    pub signed: bool,
+    /// True if the binary has encrypted segments (e.g., App Store DRM)
+    pub encrypted: bool,
+    /// Size of the binary, as described by its segments and code signature
+    pub size: usize,
+}
+
+/// Returns the name of a Mach-O CPU type
+fn cpu_type_name(cpu_type: usize) -> Option<&'static str> {
+    match cpu_type {
+        1 => Some("VAX"),
+        6 => Some("Motorola 68k"),
+        7 => Some("Intel x86"),
+        0x01000007 => Some("Intel x86-64"),
+        10 => Some("Motorola 98k"),
+        11 => Some("HP PA-RISC"),
+        12 => Some("ARM"),
+        0x0100000C => Some("ARM-64"),
+        0x0200000C => Some("ARM64_32"),
+        13 => Some("Motorola 88k"),
+        14 => Some("SPARC"),
+        15 => Some("Intel i860"),
+        18 => Some("PowerPC"),
+        0x01000012 => Some("PowerPC 64-bit"),
+        _ => None,
+    }
+}
+
+/// Parses a Mach-O binary's header and load commands
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::macho::parse_macho_header;
+///
+/// let mut macho = b"\xCF\xFA\xED\xFE\x0C\x00\x00\x01\x00\x00\x00\x00\x02\x00\x00\x00".to_vec();
+/// macho.extend(b"\x01\x00\x00\x00\x18\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00");
+/// macho.extend(b"\x1B\x00\x00\x00\x18\x00\x00\x00");
+/// macho.extend((0..16).collect::<Vec<u8>>());
+///
+/// let macho_header = parse_macho_header(&macho).unwrap();
+///
+/// assert_eq!(macho_header.bits, 64);
+/// assert_eq!(macho_header.cpu_type, "ARM-64");
+/// assert_eq!(macho_header.file_type, "executable");
+/// assert_eq!(macho_header.uuid, "00010203-0405-0607-0809-0A0B0C0D0E0F");
+/// ```
+pub fn parse_macho_header(macho_data: &[u8]) -> Result<MachOHeader, StructureError> {
+    const MACHO_32_MAGIC: usize = 0xFEEDFACE;
+    const MACHO_64_MAGIC: usize = 0xFEEDFACF;
+    const MACHO_64_HEADER_SIZE: usize = 32;
+    const MAX_LOAD_COMMANDS: usize = 0x1000;
+    const MIN_LOAD_COMMAND_SIZE: usize = 8;
+
+    let macho_structure = vec![
+        ("magic", "u32"),
+        ("cpu_type", "u32"),
+        ("cpu_subtype", "u32"),
+        ("file_type", "u32"),
+        ("load_command_count", "u32"),
+        ("load_commands_size", "u32"),
+        ("flags", "u32"),
+    ];
+
+    let load_command_structure = vec![("command", "u32"), ("size", "u32")];
+
+    // Mach-O binaries are in the byte order of the CPU they were built for, as is the magic
+    let mut macho_info = MachOHeader {
+        ..Default::default()
+    };
+
+    let mut header = common::parse(macho_data, &macho_structure, "little")?;
+    macho_info.endianness = "little".to_string();
+
+    if header["magic"] != MACHO_32_MAGIC && header["magic"] != MACHO_64_MAGIC {
+        header = common::parse(macho_data, &macho_structure, "big")?;
+        macho_info.endianness = "big".to_string();
+    }
+
+    let header_size = match header["magic"] {
+        MACHO_32_MAGIC => common::size(&macho_structure),
+        MACHO_64_MAGIC => MACHO_64_HEADER_SIZE,
+        _ => return Err(StructureError),
+    };
+
+    macho_info.bits = match header_size {
+        MACHO_64_HEADER_SIZE => 64,
+        _ => 32,
+    };
+
+    macho_info.cpu_type = cpu_type_name(header["cpu_type"])
+        .ok_or(StructureError)?
+        .to_string();
+
+    macho_info.file_type = match header["file_type"] {
+        1 => "object",
+        2 => "executable",
+        3 => "fixed VM shared library",
+        4 => "core file",
+        5 => "preloaded executable",
+        6 => "dynamic library",
+        7 => "dynamic linker",
+        8 => "bundle",
+        9 => "dynamic library stub",
+        10 => "debug symbols",
+        11 => "kernel extension",
+        12 => "file set",
+        _ => return Err(StructureError),
+    }
+    .to_string();
+
+    if header["load_command_count"] == 0 || header["load_command_count"] > MAX_LOAD_COMMANDS {
+        return Err(StructureError);
+    }
+
+    macho_info.load_command_count = header["load_command_count"];
+
+    let load_commands_end = header_size + header["load_commands_size"];
+    let load_commands = macho_data.get(..load_commands_end).ok_or(StructureError)?;
+
+    let mut command_offset = header_size;
+
+    macho_info.size = load_commands_end;
+
+    for _ in 0..header["load_command_count"] {
+        let command_data = load_commands.get(command_offset..).ok_or(StructureError)?;
+        let load_command = common::parse(
+            command_data,
+            &load_command_structure,
+            &macho_info.endianness,
+        )?;
+
+        // Load commands are at least 8 bytes, and are aligned to 4 bytes
+        if load_command["size"] < MIN_LOAD_COMMAND_SIZE
+            || load_command["size"] % 4 != 0
+            || load_command["size"] > command_data.len()
+        {
+            return Err(StructureError);
+        }
+
+        parse_load_command(
+            &command_data[..load_command["size"]],
+            load_command["command"],
+            &mut macho_info,
+        )?;
+
+        command_offset += load_command["size"];
+    }
+
+    // The load commands must fill the space allotted to them
+    if command_offset != load_commands_end {
+        return Err(StructureError);
+    }
+
+    Ok(macho_info)
+}
+
+/// Parses the load commands that are of interest, updating the Mach-O info
+fn parse_load_command(
+    command_data: &[u8],
+    command: usize,
+    macho_info: &mut MachOHeader,
+) -> Result<(), StructureError> {
+    const UUID_OFFSET: usize = 8;
+    const UUID_SIZE: usize = 16;
+
+    let name_structure = vec![("command", "u32"), ("size", "u32"), ("name_offset", "u32")];
+
+    let segment_structure = vec![
+        ("command", "u32"),
+        ("size", "u32"),
+        ("name_1", "u64"),
+        ("name_2", "u64"),
+        ("vm_address", "u32"),
+        ("vm_size", "u32"),
+        ("file_offset", "u32"),
+        ("file_size", "u32"),
+    ];
+
+    let segment_64_structure = vec![
+        ("command", "u32"),
+        ("size", "u32"),
+        ("name_1", "u64"),
+        ("name_2", "u64"),
+        ("vm_address", "u64"),
+        ("vm_size", "u64"),
+        ("file_offset", "u64"),
+        ("file_size", "u64"),
+    ];
+
+    let linkedit_data_structure = vec![
+        ("command", "u32"),
+        ("size", "u32"),
+        ("data_offset", "u32"),
+        ("data_size", "u32"),
+    ];
+
+    let encryption_info_structure = vec![
+        ("command", "u32"),
+        ("size", "u32"),
+        ("crypt_offset", "u32"),
+        ("crypt_size", "u32"),
+        ("crypt_id", "u32"),
+    ];
+
+    let version_structure = vec![
+        ("command", "u32"),
+        ("size", "u32"),
+        ("platform_or_version", "u32"),
+        ("min_os_version", "u32"),
+    ];
+
+    let endianness = macho_info.endianness.clone();
+
+    // Strings are referenced by their offset from the start of the load command
+    let command_string = |name_offset: usize| -> Result<String, StructureError> {
+        Ok(get_cstring(
+            command_data.get(name_offset..).ok_or(StructureError)?,
+        ))
+    };
+
+    match command {
+        LC_SEGMENT | LC_SEGMENT_64 => {
+            let segment = match command {
+                LC_SEGMENT => common::parse(command_data, &segment_structure, &endianness)?,
+                _ => common::parse(command_data, &segment_64_structure, &endianness)?,
+            };
+            macho_info.size = std::cmp::max(
+                macho_info.size,
+                segment["file_offset"] + segment["file_size"],
+            );
+        }
+        LC_CODE_SIGNATURE => {
+            let signature = common::parse(command_data, &linkedit_data_structure, &endianness)?;
+            macho_info.signed = true;
+            macho_info.size = std::cmp::max(
+                macho_info.size,
+                signature["data_offset"] + signature["data_size"],
+            );
+        }
+        LC_ENCRYPTION_INFO | LC_ENCRYPTION_INFO_64 => {
+            let encryption_info =
+                common::parse(command_data, &encryption_info_structure, &endianness)?;
+            macho_info.encrypted |= encryption_info["crypt_id"] != 0;
+        }
+        LC_LOAD_DYLIB | LC_LOAD_WEAK_DYLIB | LC_REEXPORT_DYLIB => {
+            macho_info.library_count += 1;
+        }
+        LC_ID_DYLIB => {
+            let name = common::parse(command_data, &name_structure, &endianness)?;
+            macho_info.install_name = command_string(name["name_offset"])?;
+        }
+        LC_LOAD_DYLINKER => {
+            let name = common::parse(command_data, &name_structure, &endianness)?;
+            macho_info.dylinker = command_string(name["name_offset"])?;
+        }
+        LC_UUID => {
+            let uuid = command_data
+                .get(UUID_OFFSET..UUID_OFFSET + UUID_SIZE)
+                .ok_or(StructureError)?;
+            let hex: String = uuid.iter().map(|b| format!("{b:02X}")).collect();
+            macho_info.uuid = format!(
+                "{}-{}-{}-{}-{}",
+                &hex[0..8],
+                &hex[8..12],
+                &hex[12..16],
+                &hex[16..20],
+                &hex[20..32]
+            );
+        }
+        LC_VERSION_MIN_MACOSX
+        | LC_VERSION_MIN_IPHONEOS
+        | LC_VERSION_MIN_TVOS
+        | LC_VERSION_MIN_WATCHOS => {
+            let version = common::parse(command_data, &version_structure, &endianness)?;
+            let platform = match command {
+                LC_VERSION_MIN_MACOSX => "macOS",
+                LC_VERSION_MIN_IPHONEOS => "iOS",
+                LC_VERSION_MIN_TVOS => "tvOS",
+                _ => "watchOS",
+            };
+            macho_info.platform = format!(
+                "{} {}",
+                platform,
+                format_version(version["platform_or_version"])
+            );
+        }
+        LC_BUILD_VERSION => {
+            let version = common::parse(command_data, &version_structure, &endianness)?;
+            let platform = match version["platform_or_version"] {
+                1 => "macOS",
+                2 => "iOS",
+                3 => "tvOS",
+                4 => "watchOS",
+                5 => "bridgeOS",
+                6 => "Mac Catalyst",
+                7 => "iOS simulator",
+                8 => "tvOS simulator",
+                9 => "watchOS simulator",
+                10 => "DriverKit",
+                11 => "visionOS",
+                12 => "visionOS simulator",
+                _ => "unknown platform",
+            };
+            macho_info.platform =
+                format!("{} {}", platform, format_version(version["min_os_version"]));
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Formats a Mach-O version number, encoded as xxxx.yy.zz nibbles, e.g., "10.15" or "14.2.1"
+fn format_version(version: usize) -> String {
+    let (major, minor, patch) = (version >> 16, (version >> 8) & 0xFF, version & 0xFF);
+
+    match patch {
+        0 => format!("{major}.{minor}"),
+        _ => format!("{major}.{minor}.{patch}"),
+    }
+}
+
+/// Stores info about an architecture in a Mach-O universal binary
+#[derive(Debug, Default, Clone)]
+pub struct MachOArchitecture {
+    pub cpu_type: String,
+    /// Offset of the architecture's Mach-O binary, relative to the start of the universal binary
+    pub offset: usize,
+}
+
+/// Stores info about a Mach-O universal (fat) binary
+#[derive(Debug, Default, Clone)]
+pub struct MachOUniversal {
+    pub architectures: Vec<MachOArchitecture>,
+    pub size: usize,
+}
+
+/// Parses the header of a Mach-O universal binary
+pub fn parse_macho_universal(universal_data: &[u8]) -> Result<MachOUniversal, StructureError> {
+    const FAT_MAGIC: usize = 0xCAFEBABE;
+    const FAT_64_MAGIC: usize = 0xCAFEBABF;
+    // Java class files share the same magic, followed by a major version of at least 45
+    const MAX_ARCHITECTURES: usize = 32;
+
+    let fat_structure = vec![("magic", "u32"), ("architecture_count", "u32")];
+
+    let fat_arch_structure = vec![
+        ("cpu_type", "u32"),
+        ("cpu_subtype", "u32"),
+        ("offset", "u32"),
+        ("size", "u32"),
+        ("alignment", "u32"),
+    ];
+
+    let fat_arch_64_structure = vec![
+        ("cpu_type", "u32"),
+        ("cpu_subtype", "u32"),
+        ("offset", "u64"),
+        ("size", "u64"),
+        ("alignment", "u32"),
+        ("reserved", "u32"),
+    ];
+
+    let fat_header = common::parse(universal_data, &fat_structure, "big")?;
+
+    let arch_structure = match fat_header["magic"] {
+        FAT_MAGIC => &fat_arch_structure,
+        FAT_64_MAGIC => &fat_arch_64_structure,
+        _ => return Err(StructureError),
+    };
+
+    if fat_header["architecture_count"] == 0 || fat_header["architecture_count"] > MAX_ARCHITECTURES
+    {
+        return Err(StructureError);
+    }
+
+    let mut universal = MachOUniversal {
+        size: common::size(&fat_structure)
+            + (fat_header["architecture_count"] * common::size(arch_structure)),
+        ..Default::default()
+    };
+
+    for i in 0..fat_header["architecture_count"] {
+        let arch_offset = common::size(&fat_structure) + (i * common::size(arch_structure));
+        let arch = common::parse(
+            universal_data.get(arch_offset..).ok_or(StructureError)?,
+            arch_structure,
+            "big",
+        )?;
+
+        // Architectures are aligned to a power of two, and come after the universal header
+        if arch["offset"] < universal.size || arch["size"] == 0 || arch["alignment"] > 31 {
+            return Err(StructureError);
+        }
+
+        universal.architectures.push(MachOArchitecture {
+            cpu_type: cpu_type_name(arch["cpu_type"])
+                .ok_or(StructureError)?
+                .to_string(),
+            offset: arch["offset"],
+        });
+
+        universal.size = std::cmp::max(universal.size, arch["offset"] + arch["size"]);
+    }
+
+    Ok(universal)
+}
@@ -33,14 +33,25 @@ pub fn parse_mbr_image(mbr_data: &[u8]) -> Result<MBRHeader, StructureError> {
         ("lba_size", "u32"),
     ];
 
+    // Extended partitions contain a chain of extended boot records, each describing one logical partition
+    let extended_os_types: Vec<usize> = vec![0x05, 0x0F, 0x85];
+
+    // A GPT protective MBR; the partitions are described by the GPT
+    const GPT_PROTECTIVE_OS_TYPE: usize = 0xEE;
+
     let known_os_types = HashMap::from([
+        (0x01, "FAT12"),
+        (0x04, "FAT16"),
+        (0x06, "FAT16"),
         (0x07, "NTFS_IFS_HPFS_exFAT"),
         (0x0B, "FAT32"),
         (0x0C, "FAT32"),
+        (0x0E, "FAT16"),
         (0x43, "Linux"),
         (0x4D, "QNX Primary Volume"),
         (0x4E, "QNX Secondary Volume"),
         (0x81, "Minix"),
+        (0x82, "Linux swap"),
         (0x83, "Linux"),
         (0x8E, "Linux LVM"),
         (0x96, "ISO-9660"),
@@ -79,6 +90,11 @@ pub fn parse_mbr_image(mbr_data: &[u8]) -> Result<MBRHeader, StructureError> {
                     return Err(StructureError);
                 }
                 Ok(partition_entry) => {
+                    // GPT disks are handled by the EFI GPT signature
+                    if partition_entry["os_type"] == GPT_PROTECTIVE_OS_TYPE {
+                        return Err(StructureError);
+                    }
+
                     // OS type of zero or LBA size of 0 can be ignored
                     if partition_entry["os_type"] != 0 || partition_entry["lba_size"] != 0 {
                         // Validate the reported MBR status value
@@ -105,8 +121,15 @@ pub fn parse_mbr_image(mbr_data: &[u8]) -> Result<MBRHeader, StructureError> {
                             // Some valid MBRs have partitions that start/end out of bounds WRT the disk image.
                             // Not sure why? At any rate, don't include them in the reported partitions.
                             if this_partition_end_offset <= mbr_data.len() {
+                                // Extended partitions are reported as the logical partitions they contain
+                                if extended_os_types.contains(&partition_entry["os_type"]) {
+                                    mbr_header.partitions.extend(parse_logical_partitions(
+                                        mbr_data,
+                                        &this_partition,
+                                        &known_os_types,
+                                    ));
                                 // Don't report the partition where the MBR header resides
-                                if this_partition.start != 0 {
+                                } else if this_partition.start != 0 {
                                     // Add it to the list of partitions
                                     mbr_header.partitions.push(this_partition.clone());
                                 }
@@ -133,3 +156,84 @@ pub fn parse_mbr_image(mbr_data: &[u8]) -> Result<MBRHeader, StructureError> {
 
     Err(StructureError)
 }
+
+/// Walks the chain of extended boot records in an extended partition, returning the logical partitions
+fn parse_logical_partitions(
+    mbr_data: &[u8],
+    extended_partition: &MBRPartition,
+    known_os_types: &HashMap<usize, &str>,
+) -> Vec<MBRPartition> {
+    const BLOCK_SIZE: usize = 512;
+    const PARTITION_TABLE_OFFSET: usize = 446;
+    const BOOT_SIGNATURE_OFFSET: usize = 510;
+    const BOOT_SIGNATURE: &[u8] = b"\x55\xAA";
+    // Guards against extended boot records that point back to themselves
+    const MAX_LOGICAL_PARTITIONS: usize = 128;
+
+    let partition_entry_structure = vec![
+        ("status", "u8"),
+        ("chs_start", "u24"),
+        ("os_type", "u8"),
+        ("chs_end", "u24"),
+        ("lba_start", "u32"),
+        ("lba_size", "u32"),
+    ];
+
+    let partition_entry_size = common::size(&partition_entry_structure);
+    let extended_partition_end = extended_partition.start + extended_partition.size;
+
+    let mut partitions: Vec<MBRPartition> = vec![];
+    let mut ebr_offset = extended_partition.start;
+
+    while partitions.len() < MAX_LOGICAL_PARTITIONS {
+        let ebr_data = match mbr_data.get(ebr_offset..ebr_offset + BLOCK_SIZE) {
+            Some(ebr_data) if ebr_data[BOOT_SIGNATURE_OFFSET..].starts_with(BOOT_SIGNATURE) => {
+                ebr_data
+            }
+            _ => break,
+        };
+
+        // The first entry describes the logical partition, relative to this EBR
+        if let Ok(logical_entry) = common::parse(
+            &ebr_data[PARTITION_TABLE_OFFSET..],
+            &partition_entry_structure,
+            "little",
+        ) {
+            let start = ebr_offset + logical_entry["lba_start"] * BLOCK_SIZE;
+            let size = logical_entry["lba_size"] * BLOCK_SIZE;
+
+            if logical_entry["os_type"] != 0 && size > 0 && start + size <= extended_partition_end {
+                partitions.push(MBRPartition {
+                    start,
+                    size,
+                    name: known_os_types
+                        .get(&logical_entry["os_type"])
+                        .unwrap_or(&"Unknown")
+                        .to_string(),
+                });
+            }
+        }
+
+        // The second entry points to the next EBR, relative to the start of the extended partition
+        match common::parse(
+            &ebr_data[PARTITION_TABLE_OFFSET + partition_entry_size..],
+            &partition_entry_structure,
+            "little",
+        ) {
+            Ok(next_entry) if next_entry["lba_start"] != 0 => {
+                let next_ebr_offset =
+                    extended_partition.start + next_entry["lba_start"] * BLOCK_SIZE;
+
+                // EBRs must be in order, else the chain may loop
+                if next_ebr_offset <= ebr_offset || next_ebr_offset >= extended_partition_end {
+                    break;
+                }
+
+                ebr_offset = next_ebr_offset;
+            }
+            _ => break,
+        }
+    }
+
+    partitions
+}
@@ -0,0 +1,300 @@
+use crate::common::get_cstring;
+use crate::structures::common::{self, StructureError};
+
+/// Default size of a Mediatek image header, and its payload offset
+pub const MTK_IMAGE_HEADER_SIZE: usize = 512;
+
+/// Stores info about a Mediatek image header, as created by Mediatek's mkimage tool
+#[derive(Debug, Default, Clone)]
+pub struct MTKImageHeader {
+    /// Image name, e.g., "KERNEL", "ROOTFS", "lk", "logo"
+    pub name: String,
+    /// Load address, if any
+    pub load_address: Option<usize>,
+    /// Offset of the payload data, relative to the start of the header
+    pub header_size: usize,
+    pub data_size: usize,
+    /// True if another image header follows this image
+    pub more_images: bool,
+}
+
+/// Parse a Mediatek image header
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::mediatek::parse_mtk_image_header;
+///
+/// let mut header = b"\x88\x16\x88\x58\x00\x10\x00\x00KERNEL".to_vec();
+/// header.resize(40, 0);
+/// header.extend(b"\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF");
+/// header.resize(512, 0xFF);
+///
+/// let mtk_header = parse_mtk_image_header(&header).unwrap();
+///
+/// assert_eq!(mtk_header.name, "KERNEL");
+/// assert_eq!(mtk_header.data_size, 0x1000);
+/// assert_eq!(mtk_header.header_size, 512);
+/// assert_eq!(mtk_header.load_address, None);
+/// ```
+pub fn parse_mtk_image_header(header_data: &[u8]) -> Result<MTKImageHeader, StructureError> {
+    const MAGIC: usize = 0x58881688;
+    const EXT_MAGIC: usize = 0x58891689;
+    const NAME_START: usize = 8;
+    const NAME_END: usize = 40;
+    const INFO_START: usize = 40;
+    // Size of the header fields, including the extended header fields
+    const EXT_HEADER_FIELDS_SIZE: usize = 80;
+    const NO_LOAD_ADDRESS: usize = 0xFFFFFFFF;
+
+    let header_structure = vec![("magic", "u32"), ("data_size", "u32")];
+
+    let info_structure = vec![
+        ("load_address", "u32"),
+        ("mode", "u32"),
+        ("ext_magic", "u32"),
+        ("header_size", "u32"),
+        ("header_version", "u32"),
+        ("image_type", "u32"),
+        ("image_list_end", "u32"),
+        ("align_size", "u32"),
+        ("data_size_extend", "u32"),
+        ("load_address_extend", "u32"),
+    ];
+
+    let header = common::parse(header_data, &header_structure, "little")?;
+    let info = common::parse(
+        header_data.get(INFO_START..).ok_or(StructureError)?,
+        &info_structure,
+        "little",
+    )?;
+
+    if header["magic"] != MAGIC {
+        return Err(StructureError);
+    }
+
+    // The image name is a NULL terminated, printable string
+    let name = get_cstring(&header_data[NAME_START..NAME_END]);
+
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_graphic()) {
+        return Err(StructureError);
+    }
+
+    let mut mtk_header = MTKImageHeader {
+        name,
+        header_size: MTK_IMAGE_HEADER_SIZE,
+        data_size: header["data_size"],
+        ..Default::default()
+    };
+
+    if info["load_address"] != NO_LOAD_ADDRESS {
+        mtk_header.load_address = Some(info["load_address"]);
+    }
+
+    // Newer headers may specify a different header size, and 64-bit sizes and addresses
+    if info["ext_magic"] == EXT_MAGIC {
+        if info["header_size"] < EXT_HEADER_FIELDS_SIZE {
+            return Err(StructureError);
+        }
+
+        mtk_header.header_size = info["header_size"];
+        mtk_header.data_size += info["data_size_extend"] << 32;
+        mtk_header.more_images = info["image_list_end"] == 0;
+
+        if let Some(load_address) = mtk_header.load_address {
+            mtk_header.load_address = Some(load_address + (info["load_address_extend"] << 32));
+        }
+    }
+
+    Ok(mtk_header)
+}
+
+/// Size of a Mediatek GFH (generic file header) file info header
+pub const GFH_FILE_INFO_SIZE: usize = 0x38;
+
+/// Stores info about a Mediatek GFH file info header, as found at the start of preloaders
+#[derive(Debug, Default, Clone)]
+pub struct GFHFileInfo {
+    pub file_version: usize,
+    /// Boot device the file was built for, e.g., "eMMC"
+    pub flash_device: String,
+    pub load_address: usize,
+    /// Size of the file, including the GFH headers and signature
+    pub file_size: usize,
+    /// Offset of the code, relative to the start of the file
+    pub content_offset: usize,
+    /// Offset of the entry point, relative to the start of the file
+    pub jump_offset: usize,
+    pub signature_size: usize,
+}
+
+/// Parse a Mediatek GFH file info header
+pub fn parse_gfh_file_info(gfh_data: &[u8]) -> Result<GFHFileInfo, StructureError> {
+    const GFH_MAGIC: usize = 0x4D4D4D;
+    const GFH_TYPE_FILE_INFO: usize = 0;
+    const ID_START: usize = 8;
+    const ID_END: usize = 20;
+    const FILE_INFO_ID: &str = "FILE_INFO";
+
+    let gfh_structure = vec![
+        ("magic", "u24"),
+        ("version", "u8"),
+        ("size", "u16"),
+        ("type", "u16"),
+    ];
+
+    let file_info_structure = vec![
+        ("file_version", "u32"),
+        ("file_type", "u16"),
+        ("flash_device", "u8"),
+        ("signature_type", "u8"),
+        ("load_address", "u32"),
+        ("file_size", "u32"),
+        ("max_size", "u32"),
+        ("content_offset", "u32"),
+        ("signature_size", "u32"),
+        ("jump_offset", "u32"),
+        ("attributes", "u32"),
+    ];
+
+    let gfh = common::parse(gfh_data, &gfh_structure, "little")?;
+
+    if gfh["magic"] != GFH_MAGIC
+        || gfh["size"] != GFH_FILE_INFO_SIZE
+        || gfh["type"] != GFH_TYPE_FILE_INFO
+        || get_cstring(gfh_data.get(ID_START..ID_END).ok_or(StructureError)?) != FILE_INFO_ID
+    {
+        return Err(StructureError);
+    }
+
+    let file_info = common::parse(&gfh_data[ID_END..], &file_info_structure, "little")?;
+
+    // Code and entry point must be inside the file, after the GFH headers
+    if file_info["content_offset"] < GFH_FILE_INFO_SIZE
+        || file_info["content_offset"] >= file_info["file_size"]
+        || file_info["jump_offset"] < file_info["content_offset"]
+        || file_info["jump_offset"] >= file_info["file_size"]
+        || file_info["signature_size"] >= file_info["file_size"]
+        || file_info["file_size"] > file_info["max_size"]
+    {
+        return Err(StructureError);
+    }
+
+    let flash_device = match file_info["flash_device"] {
+        1 => "NOR flash",
+        2..=4 => "NAND flash",
+        5 => "eMMC",
+        6 => "eMMC data region",
+        7 => "serial flash",
+        8 => "XBOOT",
+        _ => "unknown",
+    };
+
+    Ok(GFHFileInfo {
+        file_version: file_info["file_version"],
+        flash_device: flash_device.to_string(),
+        load_address: file_info["load_address"],
+        file_size: file_info["file_size"],
+        content_offset: file_info["content_offset"],
+        jump_offset: file_info["jump_offset"],
+        signature_size: file_info["signature_size"],
+    })
+}
+
+/// Stores info about a Mediatek BootROM device header and boot layout
+#[derive(Debug, Default, Clone)]
+pub struct BROMHeader {
+    /// Boot device name, e.g., "EMMC_BOOT"
+    pub device: String,
+    pub layout_type: String,
+    /// Offset of the boot loader (typically a GFH preloader), relative to the start of the header
+    pub payload_offset: usize,
+    /// Size of the header and boot loader
+    pub total_size: usize,
+}
+
+/// Parse a Mediatek BootROM device header, which is followed by a "BRLYT" boot layout header
+pub fn parse_brom_header(header_data: &[u8]) -> Result<BROMHeader, StructureError> {
+    const DEVICE_NAME_SIZE: usize = 12;
+    const BRLYT_OFFSET: usize = 0x200;
+    const BRLYT_NAME_SIZE: usize = 8;
+    const BRLYT_NAME: &str = "BRLYT";
+    const BRLYT_MAGIC: usize = 0x42424242;
+    // Only the layouts of byte addressed devices are supported; NAND layouts are specified in pages
+    const DEVICE_NAMES: [&str; 3] = ["EMMC_BOOT", "SF_BOOT", "SDMMC_BOOT"];
+
+    let device_structure = vec![("version", "u32"), ("size", "u32")];
+
+    let brlyt_structure = vec![
+        ("version", "u32"),
+        ("header_size", "u32"),
+        ("total_size", "u32"),
+        ("magic", "u32"),
+        ("type", "u32"),
+        ("header_size_2", "u32"),
+        ("total_size_2", "u32"),
+    ];
+
+    let device = get_cstring(header_data.get(..DEVICE_NAME_SIZE).ok_or(StructureError)?);
+
+    if !DEVICE_NAMES.contains(&device.as_str()) {
+        return Err(StructureError);
+    }
+
+    let device_header = common::parse(
+        &header_data[DEVICE_NAME_SIZE..],
+        &device_structure,
+        "little",
+    )?;
+
+    if device_header["size"] > BRLYT_OFFSET {
+        return Err(StructureError);
+    }
+
+    let brlyt_data = header_data.get(BRLYT_OFFSET..).ok_or(StructureError)?;
+
+    if get_cstring(brlyt_data.get(..BRLYT_NAME_SIZE).ok_or(StructureError)?) != BRLYT_NAME {
+        return Err(StructureError);
+    }
+
+    let brlyt = common::parse(&brlyt_data[BRLYT_NAME_SIZE..], &brlyt_structure, "little")?;
+
+    if brlyt["magic"] != BRLYT_MAGIC
+        || brlyt["header_size"] != brlyt["header_size_2"]
+        || brlyt["total_size"] != brlyt["total_size_2"]
+        || brlyt["header_size"] <= BRLYT_OFFSET
+        || brlyt["header_size"] >= brlyt["total_size"]
+    {
+        return Err(StructureError);
+    }
+
+    let layout_type = match brlyt["type"] {
+        0x10002 => "NAND",
+        0x10005 => "eMMC",
+        0x10007 => "NOR",
+        0x10008 => "SD/MMC",
+        0x10009 => "SPI NAND",
+        _ => return Err(StructureError),
+    };
+
+    Ok(BROMHeader {
+        device,
+        layout_type: layout_type.to_string(),
+        payload_offset: brlyt["header_size"],
+        total_size: brlyt["total_size"],
+    })
+}
+
+/// Parse the version number of a "MTK_BLOADER_INFO_v<version>" EMI settings identifier, as embedded in preloaders
+pub fn parse_bloader_info_version(info_data: &[u8]) -> Result<usize, StructureError> {
+    const IDENTIFIER_PREFIX: &str = "MTK_BLOADER_INFO_v";
+    const IDENTIFIER_SIZE: usize = 27;
+
+    let identifier = get_cstring(info_data.get(..IDENTIFIER_SIZE).ok_or(StructureError)?);
+
+    identifier
+        .strip_prefix(IDENTIFIER_PREFIX)
+        .filter(|version| !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()))
+        .and_then(|version| version.parse::<usize>().ok())
+        .ok_or(StructureError)
+}
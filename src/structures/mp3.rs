@@ -0,0 +1,174 @@
+use crate::structures::common::{self, StructureError};
+
+/// Stores info about an ID3v2 tag header
+#[derive(Debug, Default, Clone)]
+pub struct ID3Header {
+    pub version: usize,
+    /// Total size of the ID3 tag, including the header
+    pub size: usize,
+}
+
+/// Parses an ID3v2 tag header
+pub fn parse_id3_header(id3_data: &[u8]) -> Result<ID3Header, StructureError> {
+    const MAGIC_P1: usize = 0x4944;
+    const MAGIC_P2: usize = 0x33;
+    const HEADER_SIZE: usize = 10;
+    const FOOTER_SIZE: usize = 10;
+    const FOOTER_FLAG: usize = 0x10;
+    const SUPPORTED_VERSIONS: [usize; 3] = [2, 3, 4];
+    const SYNCHSAFE_MASK: u32 = 0x80808080;
+
+    let id3_structure = vec![
+        ("magic_p1", "u16"),
+        ("magic_p2", "u8"),
+        ("version", "u8"),
+        ("revision", "u8"),
+        ("flags", "u8"),
+        ("size", "u32"),
+    ];
+
+    let id3_header = common::parse(id3_data, &id3_structure, "big")?;
+
+    // Tag size is a "synchsafe" integer; the high bit of each byte is always 0
+    let synchsafe_size = id3_header["size"] as u32;
+    if id3_header["magic_p1"] != MAGIC_P1
+        || id3_header["magic_p2"] != MAGIC_P2
+        || !SUPPORTED_VERSIONS.contains(&id3_header["version"])
+        || (synchsafe_size & SYNCHSAFE_MASK) != 0
+    {
+        return Err(StructureError);
+    }
+
+    let mut tag_size: usize = (((synchsafe_size & 0x7F000000) >> 3)
+        | ((synchsafe_size & 0x007F0000) >> 2)
+        | ((synchsafe_size & 0x00007F00) >> 1)
+        | (synchsafe_size & 0x0000007F)) as usize;
+
+    tag_size += HEADER_SIZE;
+
+    if (id3_header["flags"] & FOOTER_FLAG) != 0 {
+        tag_size += FOOTER_SIZE;
+    }
+
+    Ok(ID3Header {
+        version: id3_header["version"],
+        size: tag_size,
+    })
+}
+
+/// Stores info about a stream of MPEG Layer III audio frames
+#[derive(Debug, Default, Clone)]
+pub struct MP3Stream {
+    /// Total size of the audio frames, including any trailing ID3v1 tag
+    pub size: usize,
+    pub frame_count: usize,
+    pub version: String,
+    pub sample_rate: usize,
+}
+
+/// Parses consecutive MPEG Layer III audio frames to determine the size of the audio stream
+pub fn parse_mp3_stream(stream_data: &[u8]) -> Result<MP3Stream, StructureError> {
+    // Require at least this many consecutive, consistent frames
+    const MIN_FRAME_COUNT: usize = 2;
+
+    // ID3v1 tags are a fixed size, and are appended to the end of the audio data
+    const ID3V1_MAGIC: &[u8] = b"TAG";
+    const ID3V1_SIZE: usize = 128;
+
+    let mut stream = MP3Stream {
+        ..Default::default()
+    };
+
+    while let Some(frame_data) = stream_data.get(stream.size..) {
+        match parse_mp3_frame_header(frame_data) {
+            Err(_) => break,
+            Ok(frame) => {
+                // All frames in the stream are expected to have the same version and sample rate
+                if stream.frame_count == 0 {
+                    stream.version = frame.version.clone();
+                    stream.sample_rate = frame.sample_rate;
+                } else if frame.version != stream.version || frame.sample_rate != stream.sample_rate
+                {
+                    break;
+                }
+
+                // Don't include truncated frames
+                if frame.size > frame_data.len() {
+                    break;
+                }
+
+                stream.size += frame.size;
+                stream.frame_count += 1;
+            }
+        }
+    }
+
+    if stream.frame_count < MIN_FRAME_COUNT {
+        return Err(StructureError);
+    }
+
+    if let Some(id3v1_data) = stream_data.get(stream.size..stream.size + ID3V1_SIZE) {
+        if id3v1_data.starts_with(ID3V1_MAGIC) {
+            stream.size += ID3V1_SIZE;
+        }
+    }
+
+    Ok(stream)
+}
+
+/// Stores info about an MPEG Layer III audio frame header
+#[derive(Debug, Default, Clone)]
+struct MP3FrameHeader {
+    pub size: usize,
+    pub version: String,
+    pub sample_rate: usize,
+}
+
+/// Parses an MPEG Layer III audio frame header
+fn parse_mp3_frame_header(frame_data: &[u8]) -> Result<MP3FrameHeader, StructureError> {
+    const SYNC_MASK: usize = 0xFFE00000;
+    const LAYER_III: usize = 1;
+
+    // Bit rates, in kbps, indexed by the frame header's bitrate field
+    const MPEG1_BITRATES: [usize; 16] = [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 0,
+    ];
+    const MPEG2_BITRATES: [usize; 16] = [
+        0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160, 0,
+    ];
+
+    let frame_structure = vec![("header", "u32")];
+    let frame_header = common::parse(frame_data, &frame_structure, "big")?;
+    let header = frame_header["header"];
+
+    let version_id = (header >> 19) & 0b11;
+    let layer = (header >> 17) & 0b11;
+    let bitrate_index = (header >> 12) & 0b1111;
+    let sample_rate_index = (header >> 10) & 0b11;
+    let padding = (header >> 9) & 1;
+
+    if (header & SYNC_MASK) != SYNC_MASK || layer != LAYER_III {
+        return Err(StructureError);
+    }
+
+    let (version, bitrates, sample_rates, samples_per_frame) = match version_id {
+        0b11 => ("MPEG-1", MPEG1_BITRATES, [44100, 48000, 32000], 1152),
+        0b10 => ("MPEG-2", MPEG2_BITRATES, [22050, 24000, 16000], 576),
+        0b00 => ("MPEG-2.5", MPEG2_BITRATES, [11025, 12000, 8000], 576),
+        _ => return Err(StructureError),
+    };
+
+    let bitrate = bitrates[bitrate_index] * 1000;
+    let sample_rate = *sample_rates.get(sample_rate_index).ok_or(StructureError)?;
+
+    // Free-format and invalid bitrates are not supported
+    if bitrate == 0 {
+        return Err(StructureError);
+    }
+
+    Ok(MP3FrameHeader {
+        size: (samples_per_frame / 8) * bitrate / sample_rate + padding,
+        version: version.to_string(),
+        sample_rate,
+    })
+}
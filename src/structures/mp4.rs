@@ -0,0 +1,133 @@
+use crate::common::is_offset_safe;
+use crate::structures::common::{self, StructureError};
+
+/// Stores info about an ISO base media (MP4, MOV, 3GP, etc) file
+#[derive(Debug, Default, Clone)]
+pub struct MP4File {
+    pub size: usize,
+    pub major_brand: String,
+}
+
+/// Parses the top-level boxes of an ISO base media file to determine its total size
+pub fn parse_mp4_file(mp4_data: &[u8]) -> Result<MP4File, StructureError> {
+    // The first box must be the file type box
+    const FTYP_BOX_TYPE: &[u8] = b"ftyp";
+
+    // Required boxes: at least one of these must be present in a media file
+    const REQUIRED_BOX_TYPES: [&[u8]; 3] = [b"moov", b"mdat", b"moof"];
+
+    // Major brand immediately follows the ftyp box header
+    const MAJOR_BRAND_START: usize = 8;
+    const MAJOR_BRAND_END: usize = 12;
+
+    let available_data = mp4_data.len();
+    let mut next_box_offset: usize = 0;
+    let mut previous_box_offset = None;
+    let mut box_count: usize = 0;
+    let mut required_box_found: bool = false;
+
+    let mut mp4_file = MP4File {
+        ..Default::default()
+    };
+
+    while is_offset_safe(available_data, next_box_offset, previous_box_offset) {
+        let mp4_box = match parse_mp4_box_header(&mp4_data[next_box_offset..]) {
+            Err(_) => break,
+            Ok(mp4_box) => mp4_box,
+        };
+
+        // Validate and process the initial ftyp box
+        if box_count == 0 {
+            if mp4_box.box_type != FTYP_BOX_TYPE {
+                return Err(StructureError);
+            }
+
+            let major_brand = mp4_data
+                .get(MAJOR_BRAND_START..MAJOR_BRAND_END)
+                .ok_or(StructureError)?;
+
+            mp4_file.major_brand = String::from_utf8_lossy(major_brand).trim().to_string();
+        }
+
+        if REQUIRED_BOX_TYPES.contains(&mp4_box.box_type.as_slice()) {
+            required_box_found = true;
+        }
+
+        // A box size of zero means the box extends to EOF
+        let box_size = match mp4_box.size {
+            0 => available_data - next_box_offset,
+            _ => mp4_box.size,
+        };
+
+        // Don't count boxes that are truncated
+        if next_box_offset + box_size > available_data {
+            break;
+        }
+
+        box_count += 1;
+        previous_box_offset = Some(next_box_offset);
+        next_box_offset += box_size;
+        mp4_file.size = next_box_offset;
+    }
+
+    if box_count > 1 && required_box_found {
+        return Ok(mp4_file);
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about an ISO base media box header
+#[derive(Debug, Default, Clone)]
+struct MP4BoxHeader {
+    /// Total size of the box, including the header; 0 if the box extends to EOF
+    pub size: usize,
+    pub box_type: Vec<u8>,
+}
+
+/// Parses an ISO base media box header
+fn parse_mp4_box_header(box_data: &[u8]) -> Result<MP4BoxHeader, StructureError> {
+    // A size of 1 indicates that the actual size is stored in the 64-bit largesize field
+    const LARGE_SIZE: usize = 1;
+    // A size of 0 indicates that the box extends to EOF
+    const EOF_SIZE: usize = 0;
+
+    const TYPE_START: usize = 4;
+    const TYPE_END: usize = 8;
+
+    let box_structure = vec![("size", "u32"), ("type", "u32")];
+    let large_box_structure = vec![("size", "u32"), ("type", "u32"), ("largesize", "u64")];
+
+    let box_header = common::parse(box_data, &box_structure, "big")?;
+
+    // Box types are four printable ASCII characters
+    let box_type = box_data[TYPE_START..TYPE_END].to_vec();
+    if !box_type
+        .iter()
+        .all(|b| b.is_ascii_alphanumeric() || *b == b' ')
+    {
+        return Err(StructureError);
+    }
+
+    let (box_size, header_size) = match box_header["size"] {
+        EOF_SIZE => (EOF_SIZE, common::size(&box_structure)),
+        LARGE_SIZE => {
+            let large_box_header = common::parse(box_data, &large_box_structure, "big")?;
+            (
+                large_box_header["largesize"],
+                common::size(&large_box_structure),
+            )
+        }
+        _ => (box_header["size"], common::size(&box_structure)),
+    };
+
+    // The box size includes the box header
+    if box_size != EOF_SIZE && box_size < header_size {
+        return Err(StructureError);
+    }
+
+    Ok(MP4BoxHeader {
+        size: box_size,
+        box_type,
+    })
+}
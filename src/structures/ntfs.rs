@@ -1,10 +1,26 @@
 use crate::structures::common::{self, StructureError};
 
+/// Record number of the root directory in the MFT
+pub const NTFS_ROOT_DIRECTORY_RECORD: usize = 5;
+
+/// Records before this one are reserved for file system metadata files, such as $MFT and $Extend
+pub const NTFS_FIRST_USER_RECORD: usize = 16;
+
+/// Attribute types
+pub const NTFS_ATTRIBUTE_FILE_NAME: usize = 0x30;
+pub const NTFS_ATTRIBUTE_DATA: usize = 0x80;
+pub const NTFS_ATTRIBUTE_REPARSE_POINT: usize = 0xC0;
+
 /// Struct to store NTFS info
 #[derive(Debug, Default, Clone)]
 pub struct NTFSPartition {
     pub sector_size: usize,
     pub sector_count: usize,
+    pub cluster_size: usize,
+    /// Offset of the MFT from the start of the partition
+    pub mft_offset: usize,
+    /// Size of each MFT record; 0 if the header specifies an invalid size
+    pub mft_record_size: usize,
 }
 
 /// Parses an NTFS partition header
@@ -26,6 +42,13 @@ pub fn parse_ntfs_header(ntfs_data: &[u8]) -> Result<NTFSPartition, StructureErr
         ("unused5", "u32"),
         ("unknown", "u32"),
         ("sector_count", "u64"),
+        ("mft_cluster", "u64"),
+        ("mft_mirror_cluster", "u64"),
+        ("clusters_per_mft_record", "u8"),
+        ("unused6", "u24"),
+        ("clusters_per_index_record", "u8"),
+        ("unused7", "u24"),
+        ("serial_number", "u64"),
     ];
 
     // Parse the NTFS partition header
@@ -37,12 +60,495 @@ pub fn parse_ntfs_header(ntfs_data: &[u8]) -> Result<NTFSPartition, StructureErr
             && ntfs_header["unused4"] == 0
             && ntfs_header["unused5"] == 0
         {
+            let cluster_size = ntfs_header["bytes_per_sector"]
+                * ntfs_cluster_count(ntfs_header["sectors_per_cluster"]);
+
+            // Positive values are a number of clusters, negative values are the log2 of the size in bytes
+            let mft_record_size = match ntfs_header["clusters_per_mft_record"] {
+                0..=0x7F => ntfs_header["clusters_per_mft_record"] * cluster_size,
+                _ => ntfs_cluster_count(ntfs_header["clusters_per_mft_record"]),
+            };
+
             return Ok(NTFSPartition {
                 sector_count: ntfs_header["sector_count"],
                 sector_size: ntfs_header["bytes_per_sector"],
+                cluster_size,
+                mft_offset: ntfs_header["mft_cluster"].saturating_mul(cluster_size),
+                mft_record_size,
             });
         }
     }
 
     Err(StructureError)
 }
+
+/// Sizes up to 0x80 are stored as-is, larger sizes are stored as a negative log2 of the size; invalid sizes return 0
+fn ntfs_cluster_count(value: usize) -> usize {
+    const MAX_LOG2_SIZE: usize = 31;
+
+    match value {
+        0..=0x80 => value,
+        _ if 0x100 - value <= MAX_LOG2_SIZE => 1 << (0x100 - value),
+        _ => 0,
+    }
+}
+
+/// Struct to store info about an MFT file record
+#[derive(Debug, Default, Clone)]
+pub struct NTFSFileRecord {
+    /// Incremented each time the record is re-used
+    pub sequence: usize,
+    pub in_use: bool,
+    pub directory: bool,
+    /// Record number of the base record that this extension record holds additional attributes for; None for base records
+    pub base_record: Option<usize>,
+    pub attributes: Vec<NTFSAttribute>,
+}
+
+/// Parses an MFT file record, including all of its attributes
+pub fn parse_ntfs_file_record(record_data: &[u8]) -> Result<NTFSFileRecord, StructureError> {
+    const FILE_MAGIC: usize = 0x454C4946;
+    const IN_USE_FLAG: usize = 0x01;
+    const DIRECTORY_FLAG: usize = 0x02;
+    const END_OF_ATTRIBUTES: u32 = 0xFFFFFFFF;
+    // The base record is a file reference; the upper 16 bits are the base record's sequence number
+    const RECORD_NUMBER_MASK: usize = 0xFFFFFFFFFFFF;
+
+    let file_record_structure = vec![
+        ("magic", "u32"),
+        ("update_sequence_offset", "u16"),
+        ("update_sequence_count", "u16"),
+        ("log_sequence_number", "u64"),
+        ("sequence", "u16"),
+        ("link_count", "u16"),
+        ("attributes_offset", "u16"),
+        ("flags", "u16"),
+        ("used_size", "u32"),
+        ("allocated_size", "u32"),
+        ("base_record", "u64"),
+    ];
+
+    let record = apply_ntfs_fixups(record_data)?;
+    let file_record = common::parse(&record, &file_record_structure, "little")?;
+
+    if file_record["magic"] != FILE_MAGIC
+        || file_record["used_size"] > record.len()
+        || file_record["attributes_offset"] < common::size(&file_record_structure)
+    {
+        return Err(StructureError);
+    }
+
+    let mut result = NTFSFileRecord {
+        sequence: file_record["sequence"],
+        in_use: file_record["flags"] & IN_USE_FLAG != 0,
+        directory: file_record["flags"] & DIRECTORY_FLAG != 0,
+        ..Default::default()
+    };
+
+    if file_record["base_record"] != 0 {
+        result.base_record = Some(file_record["base_record"] & RECORD_NUMBER_MASK);
+    }
+
+    // Attributes are stored back to back, up to an end marker
+    let attribute_data = &record[..file_record["used_size"]];
+    let mut attribute_offset = file_record["attributes_offset"];
+
+    loop {
+        let attribute_type = attribute_data
+            .get(attribute_offset..attribute_offset + 4)
+            .ok_or(StructureError)?;
+
+        if u32::from_le_bytes(attribute_type.try_into().unwrap()) == END_OF_ATTRIBUTES {
+            break;
+        }
+
+        let (attribute, attribute_size) =
+            parse_ntfs_attribute(&attribute_data[attribute_offset..])?;
+
+        result.attributes.push(attribute);
+        attribute_offset += attribute_size;
+    }
+
+    Ok(result)
+}
+
+/// Replaces the last two bytes of each 512 byte block of an NTFS record with the original bytes stored in the record's
+/// update sequence array; these bytes are used to detect incomplete writes, and must match the update sequence number
+fn apply_ntfs_fixups(record_data: &[u8]) -> Result<Vec<u8>, StructureError> {
+    const BLOCK_SIZE: usize = 512;
+
+    let record_header_structure = vec![
+        ("magic", "u32"),
+        ("update_sequence_offset", "u16"),
+        ("update_sequence_count", "u16"),
+    ];
+
+    let record_header = common::parse(record_data, &record_header_structure, "little")?;
+
+    // The update sequence number is followed by one entry for each block
+    let block_count = record_header["update_sequence_count"]
+        .checked_sub(1)
+        .ok_or(StructureError)?;
+
+    if block_count * BLOCK_SIZE > record_data.len() {
+        return Err(StructureError);
+    }
+
+    let update_sequence_start = record_header["update_sequence_offset"];
+    let update_sequence_end = update_sequence_start + ((block_count + 1) * 2);
+    let update_sequence = record_data
+        .get(update_sequence_start..update_sequence_end)
+        .ok_or(StructureError)?;
+
+    let mut record = record_data.to_vec();
+
+    for (i, original_bytes) in update_sequence[2..].chunks_exact(2).enumerate() {
+        let block_end = (i + 1) * BLOCK_SIZE;
+
+        if record[block_end - 2..block_end] != update_sequence[..2] {
+            return Err(StructureError);
+        }
+
+        record[block_end - 2..block_end].copy_from_slice(original_bytes);
+    }
+
+    Ok(record)
+}
+
+/// Stores info about an MFT record attribute
+#[derive(Debug, Default, Clone)]
+pub struct NTFSAttribute {
+    pub attribute_type: usize,
+    /// Most attributes, including the default data stream, are unnamed
+    pub name: String,
+    pub compressed: bool,
+    pub encrypted: bool,
+    pub non_resident: bool,
+    /// Value of resident attributes, which is stored within the MFT record itself
+    pub value: Vec<u8>,
+    /// First virtual cluster of a non-resident attribute described by this attribute's data runs
+    pub start_vcn: usize,
+    pub data_runs: Vec<NTFSDataRun>,
+    /// Log2 of the number of clusters in each compression unit of a compressed attribute
+    pub compression_unit: usize,
+    pub data_size: usize,
+    /// Data beyond this many bytes has not been written, and reads as zeros
+    pub initialized_size: usize,
+}
+
+/// Parses an MFT record attribute, returning the attribute and its size
+fn parse_ntfs_attribute(attribute_data: &[u8]) -> Result<(NTFSAttribute, usize), StructureError> {
+    const NON_RESIDENT: usize = 1;
+    const COMPRESSED_FLAG: usize = 0x0001;
+    const ENCRYPTED_FLAG: usize = 0x4000;
+    // Attributes are 8-byte aligned
+    const ALIGNMENT: usize = 8;
+
+    let attribute_header_structure = vec![
+        ("type", "u32"),
+        ("size", "u32"),
+        ("non_resident", "u8"),
+        ("name_length", "u8"),
+        ("name_offset", "u16"),
+        ("flags", "u16"),
+        ("id", "u16"),
+    ];
+
+    let resident_structure = vec![
+        ("value_size", "u32"),
+        ("value_offset", "u16"),
+        ("indexed", "u8"),
+        ("padding", "u8"),
+    ];
+
+    let non_resident_structure = vec![
+        ("start_vcn", "u64"),
+        ("last_vcn", "u64"),
+        ("data_runs_offset", "u16"),
+        ("compression_unit", "u16"),
+        ("padding", "u32"),
+        ("allocated_size", "u64"),
+        ("data_size", "u64"),
+        ("initialized_size", "u64"),
+    ];
+
+    let attribute_header = common::parse(attribute_data, &attribute_header_structure, "little")?;
+    let attribute_size = attribute_header["size"];
+    let header_size = common::size(&attribute_header_structure);
+
+    if attribute_size < header_size || attribute_size % ALIGNMENT != 0 {
+        return Err(StructureError);
+    }
+
+    let attribute_data = attribute_data.get(..attribute_size).ok_or(StructureError)?;
+
+    let name_start = attribute_header["name_offset"];
+    let name_end = name_start + (attribute_header["name_length"] * 2);
+
+    let mut attribute = NTFSAttribute {
+        attribute_type: attribute_header["type"],
+        name: parse_ntfs_name(
+            attribute_data
+                .get(name_start..name_end)
+                .ok_or(StructureError)?,
+        ),
+        compressed: attribute_header["flags"] & COMPRESSED_FLAG != 0,
+        encrypted: attribute_header["flags"] & ENCRYPTED_FLAG != 0,
+        non_resident: attribute_header["non_resident"] == NON_RESIDENT,
+        ..Default::default()
+    };
+
+    if attribute.non_resident {
+        let non_resident = common::parse(
+            &attribute_data[header_size..],
+            &non_resident_structure,
+            "little",
+        )?;
+
+        if non_resident["initialized_size"] > non_resident["data_size"] {
+            return Err(StructureError);
+        }
+
+        attribute.start_vcn = non_resident["start_vcn"];
+        attribute.compression_unit = non_resident["compression_unit"];
+        attribute.data_size = non_resident["data_size"];
+        attribute.initialized_size = non_resident["initialized_size"];
+        attribute.data_runs = parse_ntfs_data_runs(
+            attribute_data
+                .get(non_resident["data_runs_offset"]..)
+                .ok_or(StructureError)?,
+        )?;
+    } else {
+        let resident = common::parse(
+            &attribute_data[header_size..],
+            &resident_structure,
+            "little",
+        )?;
+
+        let value_start = resident["value_offset"];
+        let value_end = value_start + resident["value_size"];
+
+        attribute.value = attribute_data
+            .get(value_start..value_end)
+            .ok_or(StructureError)?
+            .to_vec();
+        attribute.data_size = attribute.value.len();
+        attribute.initialized_size = attribute.value.len();
+    }
+
+    Ok((attribute, attribute_size))
+}
+
+/// Describes a contiguous run of clusters in a non-resident attribute
+#[derive(Debug, Default, Clone)]
+pub struct NTFSDataRun {
+    /// Logical cluster number of the first cluster in the run; None for sparse runs, which read as zeros
+    pub lcn: Option<usize>,
+    pub cluster_count: usize,
+}
+
+/// Parses the data runs of a non-resident attribute
+fn parse_ntfs_data_runs(runs_data: &[u8]) -> Result<Vec<NTFSDataRun>, StructureError> {
+    const MAX_FIELD_SIZE: usize = 8;
+
+    let mut data_runs: Vec<NTFSDataRun> = vec![];
+    let mut run_offset: usize = 0;
+    let mut lcn: i64 = 0;
+
+    loop {
+        // Each run starts with a byte specifying the size of its length and offset fields; a size byte of 0 ends the list
+        let field_sizes = *runs_data.get(run_offset).ok_or(StructureError)? as usize;
+
+        if field_sizes == 0 {
+            break;
+        }
+
+        let length_size = field_sizes & 0x0F;
+        let offset_size = field_sizes >> 4;
+
+        if length_size == 0 || length_size > MAX_FIELD_SIZE || offset_size > MAX_FIELD_SIZE {
+            return Err(StructureError);
+        }
+
+        let length_start = run_offset + 1;
+        let offset_start = length_start + length_size;
+        let run_end = offset_start + offset_size;
+
+        let length_bytes = runs_data
+            .get(length_start..offset_start)
+            .ok_or(StructureError)?;
+        let offset_bytes = runs_data.get(offset_start..run_end).ok_or(StructureError)?;
+
+        let mut cluster_count: u64 = 0;
+        for (i, byte) in length_bytes.iter().enumerate() {
+            cluster_count |= (*byte as u64) << (i * 8);
+        }
+
+        let mut data_run = NTFSDataRun {
+            cluster_count: cluster_count as usize,
+            ..Default::default()
+        };
+
+        // Runs without an offset are sparse; otherwise, the offset is a signed delta from the previous run's LCN
+        if offset_size > 0 {
+            let mut lcn_delta: i64 = 0;
+            for (i, byte) in offset_bytes.iter().enumerate() {
+                lcn_delta |= (*byte as i64) << (i * 8);
+            }
+
+            // Sign extend the delta
+            let unused_bits = (MAX_FIELD_SIZE - offset_size) * 8;
+            lcn_delta = (lcn_delta << unused_bits) >> unused_bits;
+
+            lcn = lcn.checked_add(lcn_delta).ok_or(StructureError)?;
+
+            if lcn < 0 {
+                return Err(StructureError);
+            }
+
+            data_run.lcn = Some(lcn as usize);
+        }
+
+        data_runs.push(data_run);
+        run_offset = run_end;
+    }
+
+    Ok(data_runs)
+}
+
+/// Stores info about a $FILE_NAME attribute, which links an MFT record to a name in its parent directory
+#[derive(Debug, Default, Clone)]
+pub struct NTFSFileName {
+    pub parent_record: usize,
+    /// Sequence number of the parent record at the time the link was created
+    pub parent_sequence: usize,
+    /// Set for DOS 8.3 names, which are additional names for files that also have a long name
+    pub dos_name: bool,
+    pub name: String,
+}
+
+/// Parses the value of a $FILE_NAME attribute
+pub fn parse_ntfs_file_name(file_name_data: &[u8]) -> Result<NTFSFileName, StructureError> {
+    const DOS_NAMESPACE: usize = 2;
+
+    let file_name_structure = vec![
+        ("parent_reference", "u64"),
+        ("creation_time", "u64"),
+        ("modification_time", "u64"),
+        ("mft_modification_time", "u64"),
+        ("access_time", "u64"),
+        ("allocated_size", "u64"),
+        ("data_size", "u64"),
+        ("flags", "u32"),
+        ("reparse_value", "u32"),
+        ("name_length", "u8"),
+        ("namespace", "u8"),
+    ];
+
+    let file_name = common::parse(file_name_data, &file_name_structure, "little")?;
+
+    let name_start = common::size(&file_name_structure);
+    let name_end = name_start + (file_name["name_length"] * 2);
+
+    // File references are a 48-bit record number, followed by a 16-bit sequence number
+    Ok(NTFSFileName {
+        parent_record: file_name["parent_reference"] & 0xFFFFFFFFFFFF,
+        parent_sequence: file_name["parent_reference"] >> 48,
+        dos_name: file_name["namespace"] == DOS_NAMESPACE,
+        name: parse_ntfs_name(
+            file_name_data
+                .get(name_start..name_end)
+                .ok_or(StructureError)?,
+        ),
+    })
+}
+
+/// Stores info about a symbolic link or junction, as described by a $REPARSE_POINT attribute
+#[derive(Debug, Default, Clone)]
+pub struct NTFSSymlink {
+    /// Link target, using '\' as the path separator
+    pub target: String,
+    /// Set if the target is relative to the link's parent directory
+    pub relative: bool,
+}
+
+/// Parses the value of a $REPARSE_POINT attribute; other types of reparse points are not supported
+pub fn parse_ntfs_symlink(reparse_data: &[u8]) -> Result<NTFSSymlink, StructureError> {
+    const MOUNT_POINT_TAG: usize = 0xA0000003;
+    const SYMLINK_TAG: usize = 0xA000000C;
+    const RELATIVE_FLAG: usize = 1;
+
+    let reparse_structure = vec![
+        ("tag", "u32"),
+        ("data_size", "u16"),
+        ("reserved", "u16"),
+        ("substitute_name_offset", "u16"),
+        ("substitute_name_size", "u16"),
+        ("print_name_offset", "u16"),
+        ("print_name_size", "u16"),
+    ];
+
+    let symlink_structure = vec![("flags", "u32")];
+
+    let reparse_header = common::parse(reparse_data, &reparse_structure, "little")?;
+
+    let mut symlink = NTFSSymlink {
+        ..Default::default()
+    };
+
+    // Symlinks have an additional flags field, junctions do not
+    let mut path_buffer_start = common::size(&reparse_structure);
+
+    match reparse_header["tag"] {
+        MOUNT_POINT_TAG => (),
+        SYMLINK_TAG => {
+            let symlink_header = common::parse(
+                &reparse_data[path_buffer_start..],
+                &symlink_structure,
+                "little",
+            )?;
+            symlink.relative = symlink_header["flags"] & RELATIVE_FLAG != 0;
+            path_buffer_start += common::size(&symlink_structure);
+        }
+        _ => return Err(StructureError),
+    }
+
+    let path_name = |offset: usize, size: usize| -> Result<String, StructureError> {
+        let name_start = path_buffer_start + offset;
+
+        Ok(parse_ntfs_name(
+            reparse_data
+                .get(name_start..name_start + size)
+                .ok_or(StructureError)?,
+        ))
+    };
+
+    // The print name is the user-friendly version of the substitute name, without any NT namespace prefix
+    symlink.target = path_name(
+        reparse_header["print_name_offset"],
+        reparse_header["print_name_size"],
+    )?;
+
+    if symlink.target.is_empty() {
+        symlink.target = path_name(
+            reparse_header["substitute_name_offset"],
+            reparse_header["substitute_name_size"],
+        )?;
+    }
+
+    if symlink.target.is_empty() {
+        return Err(StructureError);
+    }
+
+    Ok(symlink)
+}
+
+/// Decodes a UTF-16 name
+fn parse_ntfs_name(name_data: &[u8]) -> String {
+    let characters: Vec<u16> = name_data
+        .chunks_exact(2)
+        .map(|character| u16::from_le_bytes([character[0], character[1]]))
+        .collect();
+
+    String::from_utf16_lossy(&characters)
+}
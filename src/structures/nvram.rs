@@ -0,0 +1,333 @@
+use crate::common::{crc32, is_printable_ascii, parser_deadline_exceeded};
+use crate::structures::common::{self, StructureError};
+use serde::{Deserialize, Serialize};
+
+/// A single key/value pair parsed from an NVRAM or configuration blob
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: String,
+}
+
+/// Stores info about a block of NULL-separated key=value strings
+#[derive(Debug, Default, Clone)]
+pub struct KeyValueBlock {
+    /// Number of bytes consumed, including the terminating double-NULL
+    pub size: usize,
+    pub entries: Vec<ConfigEntry>,
+}
+
+/// Parses a block of NULL-separated key=value strings, terminated by an empty string (i.e., two consecutive NULL bytes).
+pub fn parse_key_value_block(block_data: &[u8]) -> Result<KeyValueBlock, StructureError> {
+    const NULL_BYTE: u8 = 0;
+
+    let mut block = KeyValueBlock {
+        ..Default::default()
+    };
+
+    let mut offset: usize = 0;
+
+    loop {
+        let remaining_data = block_data.get(offset..).ok_or(StructureError)?;
+
+        // Each string must be NULL terminated; running out of data is an error
+        let string_length = remaining_data
+            .iter()
+            .position(|b| *b == NULL_BYTE)
+            .ok_or(StructureError)?;
+
+        // An empty string marks the end of the block
+        if string_length == 0 {
+            block.size = offset + 1;
+            break;
+        }
+
+        block
+            .entries
+            .push(parse_key_value_string(&remaining_data[0..string_length])?);
+
+        offset += string_length + 1;
+    }
+
+    // An empty block is not much of a config block
+    if block.entries.is_empty() {
+        return Err(StructureError);
+    }
+
+    Ok(block)
+}
+
+/// Parses a single key=value string
+fn parse_key_value_string(kv_data: &[u8]) -> Result<ConfigEntry, StructureError> {
+    const DELIMITER: u8 = b'=';
+
+    // Keys and values must be printable
+    if !kv_data
+        .iter()
+        .all(|b| is_printable_ascii(*b) || *b == b'\t')
+    {
+        return Err(StructureError);
+    }
+
+    let delimiter_offset = kv_data
+        .iter()
+        .position(|b| *b == DELIMITER)
+        .ok_or(StructureError)?;
+
+    // Keys are never empty, and never contain whitespace
+    let key = &kv_data[0..delimiter_offset];
+    if key.is_empty() || key.iter().any(|b| b.is_ascii_whitespace()) {
+        return Err(StructureError);
+    }
+
+    Ok(ConfigEntry {
+        key: String::from_utf8_lossy(key).to_string(),
+        value: String::from_utf8_lossy(&kv_data[delimiter_offset + 1..]).to_string(),
+    })
+}
+
+/// Stores info about a Broadcom NVRAM header
+#[derive(Debug, Default, Clone)]
+pub struct BroadcomNVRAMHeader {
+    pub version: usize,
+    pub total_size: usize,
+    pub header_size: usize,
+}
+
+/// Parses a Broadcom NVRAM ("FLSH") header
+pub fn parse_broadcom_nvram_header(
+    nvram_data: &[u8],
+) -> Result<BroadcomNVRAMHeader, StructureError> {
+    const MAX_NVRAM_SIZE: usize = 0x100000;
+
+    let nvram_structure = vec![
+        ("magic", "u32"),
+        ("len", "u32"),
+        ("crc", "u8"),
+        ("version", "u8"),
+        ("sdram_init", "u16"),
+        ("config_refresh", "u32"),
+        ("config_ncdl", "u32"),
+    ];
+
+    let header_size = common::size(&nvram_structure);
+
+    if let Ok(nvram_header) = common::parse(nvram_data, &nvram_structure, "little") {
+        // The reported length includes the header
+        if nvram_header["len"] > header_size && nvram_header["len"] <= MAX_NVRAM_SIZE {
+            return Ok(BroadcomNVRAMHeader {
+                version: nvram_header["version"],
+                total_size: nvram_header["len"],
+                header_size,
+            });
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about a U-Boot environment
+#[derive(Debug, Default, Clone)]
+pub struct UBootEnvironment {
+    /// Offset of the environment's CRC field, relative to the start of the provided data
+    pub offset: usize,
+    /// Total size of the environment, including the CRC and flags header
+    pub size: usize,
+    pub redundant: bool,
+    pub endianness: String,
+    pub entries: Vec<ConfigEntry>,
+}
+
+/// Parses a U-Boot environment. The `vars_offset` argument is the offset, within `env_data`, of a known environment
+/// variable string; the start of the environment (its CRC header) is located by searching backwards from there.
+pub fn parse_uboot_environment(
+    env_data: &[u8],
+    vars_offset: usize,
+) -> Result<UBootEnvironment, StructureError> {
+    // CRC is a u32; redundant environments also have a one byte flags field
+    const CRC_SIZE: usize = 4;
+    const FLAGS_SIZE: usize = 1;
+
+    // U-Boot environment sizes are configured at build time, but are almost always a power of two
+    const MIN_ENV_SIZE: usize = 0x400;
+    const MAX_ENV_SIZE: usize = 0x80000;
+
+    let vars_start = find_key_value_block_start(env_data, vars_offset);
+
+    // The key=value block must be well formed
+    let vars = parse_key_value_block(&env_data[vars_start..])?;
+
+    for header_size in [CRC_SIZE, CRC_SIZE + FLAGS_SIZE] {
+        if vars_start < header_size {
+            continue;
+        }
+
+        let env_start = vars_start - header_size;
+        let crc_bytes: [u8; CRC_SIZE] = env_data[env_start..env_start + CRC_SIZE]
+            .try_into()
+            .map_err(|_| StructureError)?;
+
+        let mut env_size = MIN_ENV_SIZE;
+
+        while env_size <= MAX_ENV_SIZE && !parser_deadline_exceeded() {
+            // The environment must be large enough to hold all of the key=value strings
+            if env_size >= header_size + vars.size {
+                match env_data.get(vars_start..env_start + env_size) {
+                    None => break,
+                    Some(crc_data) => {
+                        let env_crc = crc32(crc_data);
+
+                        for endianness in ["little", "big"] {
+                            let stored_crc = match endianness {
+                                "little" => u32::from_le_bytes(crc_bytes),
+                                _ => u32::from_be_bytes(crc_bytes),
+                            };
+
+                            if stored_crc == env_crc {
+                                return Ok(UBootEnvironment {
+                                    offset: env_start,
+                                    size: env_size,
+                                    redundant: header_size != CRC_SIZE,
+                                    endianness: endianness.to_string(),
+                                    entries: vars.entries.clone(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+
+            env_size *= 2;
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// Given the offset of a key=value string, walks backwards over any preceeding NULL-terminated key=value strings
+/// and returns the offset of the first string in the block.
+fn find_key_value_block_start(data: &[u8], offset: usize) -> usize {
+    // Sanity limit on the length of a single key=value string
+    const MAX_STRING_LENGTH: usize = 4096;
+
+    let mut block_start = offset;
+
+    // The byte before each string in the block is the preceeding string's NULL terminator
+    while block_start > 1 && data[block_start - 1] == 0 {
+        let previous_string_end = block_start - 1;
+        let mut previous_string_start = previous_string_end;
+
+        while previous_string_start > 0
+            && data[previous_string_start - 1] != 0
+            && (previous_string_end - previous_string_start) < MAX_STRING_LENGTH
+        {
+            previous_string_start -= 1;
+        }
+
+        // Stop if the previous string is empty, too long, or is not a key=value string
+        if previous_string_start == previous_string_end
+            || (previous_string_start > 0 && data[previous_string_start - 1] != 0)
+            || parse_key_value_string(&data[previous_string_start..previous_string_end]).is_err()
+        {
+            break;
+        }
+
+        block_start = previous_string_start;
+    }
+
+    block_start
+}
+
+/// Stores info about a MikroTik RouterBOOT configuration TLV block
+#[derive(Debug, Default, Clone)]
+pub struct RouterBootConfig {
+    pub size: usize,
+    pub entries: Vec<ConfigEntry>,
+}
+
+/// Parses a MikroTik RouterBOOT hard_config TLV block
+pub fn parse_routerboot_config(config_data: &[u8]) -> Result<RouterBootConfig, StructureError> {
+    const MAGIC_SIZE: usize = 4;
+    const END_TAG: usize = 0;
+    const MAX_TAG: usize = 0xFF;
+    const MAX_ENTRIES: usize = 256;
+
+    // Each TLV record is a u32 whose low 16 bits are the tag ID, and high 16 bits are the value length
+    let tlv_structure = vec![("tag", "u16"), ("length", "u16")];
+    let tlv_header_size = common::size(&tlv_structure);
+
+    let mut config = RouterBootConfig {
+        ..Default::default()
+    };
+
+    let mut offset: usize = MAGIC_SIZE;
+
+    while config.entries.len() < MAX_ENTRIES {
+        let tlv_data = config_data.get(offset..).ok_or(StructureError)?;
+        let tlv_header = common::parse(tlv_data, &tlv_structure, "little")?;
+
+        if tlv_header["tag"] == END_TAG {
+            config.size = offset + tlv_header_size;
+            break;
+        }
+
+        // Known tag IDs are all fairly small values
+        if tlv_header["tag"] > MAX_TAG {
+            return Err(StructureError);
+        }
+
+        let value_start = tlv_header_size;
+        let value_end = value_start + tlv_header["length"];
+        let value = tlv_data.get(value_start..value_end).ok_or(StructureError)?;
+
+        config.entries.push(ConfigEntry {
+            key: routerboot_tag_name(tlv_header["tag"]),
+            value: routerboot_tag_value(value),
+        });
+
+        offset += value_end;
+    }
+
+    if config.size == 0 || config.entries.is_empty() {
+        return Err(StructureError);
+    }
+
+    Ok(config)
+}
+
+/// Returns a human readable name for known RouterBOOT tag IDs
+fn routerboot_tag_name(tag: usize) -> String {
+    let name = match tag {
+        0x03 => "flash_info",
+        0x04 => "mac_address",
+        0x05 => "board_product_code",
+        0x06 => "bios_version",
+        0x0A => "software_id",
+        0x0B => "serial_number",
+        0x0D => "memory_size",
+        0x0E => "mac_address_count",
+        0x15 => "hw_options",
+        0x16 => "wlan_data",
+        0x17 => "board_identifier",
+        0x21 => "product_name",
+        0x26 => "default_config",
+        _ => "",
+    };
+
+    if name.is_empty() {
+        return format!("tag_{tag:#04X}");
+    }
+
+    name.to_string()
+}
+
+/// RouterBOOT values are either NULL-terminated strings or binary data; binary data is hex encoded
+fn routerboot_tag_value(value: &[u8]) -> String {
+    let string_data: Vec<u8> = value.iter().copied().take_while(|b| *b != 0).collect();
+
+    if !string_data.is_empty() && string_data.iter().all(|b| is_printable_ascii(*b)) {
+        return String::from_utf8_lossy(&string_data).to_string();
+    }
+
+    hex::encode(value)
+}
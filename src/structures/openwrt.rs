@@ -0,0 +1,197 @@
+use crate::common::get_cstring;
+use crate::structures::common::{self, StructureError};
+
+/// Size of an fwtool image trailer
+pub const FWIMAGE_TRAILER_SIZE: usize = 16;
+
+/// fwtool trailer type of a signature block
+pub const FWIMAGE_SIGNATURE: usize = 0;
+
+/// fwtool trailer type of a JSON metadata block
+pub const FWIMAGE_INFO: usize = 1;
+
+/// Storage struct for fwtool image trailers
+#[derive(Debug, Default, Clone)]
+pub struct FWImageTrailer {
+    pub crc32: usize,
+    /// One of FWIMAGE_SIGNATURE or FWIMAGE_INFO
+    pub trailer_type: usize,
+    /// Size of the data block that precedes the trailer, including the trailer
+    pub size: usize,
+}
+
+/// Parse an fwtool image trailer, as appended to OpenWrt images along with their metadata and signatures
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::openwrt::{FWIMAGE_INFO, parse_fwimage_trailer};
+///
+/// let trailer = b"FWx0\x12\x34\x56\x78\x01\x00\x00\x00\x00\x00\x01\x10";
+///
+/// let fwimage_trailer = parse_fwimage_trailer(trailer).unwrap();
+///
+/// assert_eq!(fwimage_trailer.crc32, 0x12345678);
+/// assert_eq!(fwimage_trailer.trailer_type, FWIMAGE_INFO);
+/// assert_eq!(fwimage_trailer.size, 0x110);
+/// ```
+pub fn parse_fwimage_trailer(trailer_data: &[u8]) -> Result<FWImageTrailer, StructureError> {
+    // https://github.com/openwrt/openwrt/blob/main/package/system/fwtool/src/fwimage.h
+    const MAGIC: usize = 0x46577830;
+
+    let trailer_structure = vec![
+        ("magic", "u32"),
+        ("crc32", "u32"),
+        ("type", "u8"),
+        ("padding1", "u8"),
+        ("padding2", "u16"),
+        ("size", "u32"),
+    ];
+
+    if let Ok(trailer) = common::parse(trailer_data, &trailer_structure, "big") {
+        if trailer["magic"] == MAGIC
+            && [FWIMAGE_SIGNATURE, FWIMAGE_INFO].contains(&trailer["type"])
+            && trailer["size"] > FWIMAGE_TRAILER_SIZE
+        {
+            return Ok(FWImageTrailer {
+                crc32: trailer["crc32"],
+                trailer_type: trailer["type"],
+                size: trailer["size"],
+            });
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// A file in an OpenWrt sysupgrade tar archive
+#[derive(Debug, Default, Clone)]
+pub struct SysupgradeEntry {
+    /// File name, without the leading "sysupgrade-<board>/" directory, e.g., "kernel", "root", "CONTROL"
+    pub name: String,
+    /// Offset of the file data, relative to the start of the archive
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// Storage struct for OpenWrt sysupgrade tar archive info
+#[derive(Debug, Default, Clone)]
+pub struct SysupgradeImage {
+    /// Board name, from the archive's top level directory
+    pub board: String,
+    /// Size of the archive, including the terminating NULL blocks, if present
+    pub size: usize,
+    /// Regular files in the archive, in archive order
+    pub entries: Vec<SysupgradeEntry>,
+}
+
+impl SysupgradeImage {
+    /// Returns the archive entry with the specified name
+    pub fn entry(&self, name: &str) -> Option<&SysupgradeEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+}
+
+/// Parse an OpenWrt sysupgrade tar archive, in which all files are stored under a "sysupgrade-<board>/" directory
+pub fn parse_sysupgrade_tar(tar_data: &[u8]) -> Result<SysupgradeImage, StructureError> {
+    const BLOCK_SIZE: usize = 512;
+    const NAME_END: usize = 100;
+    const SIZE_START: usize = 124;
+    const SIZE_END: usize = 136;
+    const CHECKSUM_START: usize = 148;
+    const CHECKSUM_END: usize = 156;
+    const TYPE_OFFSET: usize = 156;
+    const MAGIC_START: usize = 257;
+    const MAGIC_END: usize = 262;
+    const TAR_MAGIC: &[u8] = b"ustar";
+    const DIRECTORY_PREFIX: &str = "sysupgrade-";
+    // Type flags of regular files and directories
+    const REGULAR_FILE_TYPES: [u8; 2] = [b'0', 0];
+    const DIRECTORY_TYPE: u8 = b'5';
+
+    let mut image = SysupgradeImage {
+        ..Default::default()
+    };
+
+    let mut next_header_start: usize = 0;
+
+    while let Some(header) = tar_data.get(next_header_start..next_header_start + BLOCK_SIZE) {
+        // Archives end with NULL blocks
+        if header.iter().all(|b| *b == 0) {
+            image.size = next_header_start;
+
+            // Include the terminating NULL blocks that are present
+            while let Some(null_block) = tar_data.get(image.size..image.size + BLOCK_SIZE) {
+                if null_block.iter().any(|b| *b != 0) {
+                    break;
+                }
+                image.size += BLOCK_SIZE;
+            }
+            break;
+        }
+
+        if &header[MAGIC_START..MAGIC_END] != TAR_MAGIC {
+            return Err(StructureError);
+        }
+
+        // The header checksum is calculated with the checksum field set to spaces
+        let checksum: usize = header
+            .iter()
+            .enumerate()
+            .map(|(i, b)| match (CHECKSUM_START..CHECKSUM_END).contains(&i) {
+                true => b' ' as usize,
+                false => *b as usize,
+            })
+            .sum();
+
+        if tar_octal(&header[CHECKSUM_START..CHECKSUM_END]) != Some(checksum) {
+            return Err(StructureError);
+        }
+
+        let entry_size = tar_octal(&header[SIZE_START..SIZE_END]).ok_or(StructureError)?;
+        let entry_path = get_cstring(&header[..NAME_END]);
+
+        // All files are in the sysupgrade-<board> directory
+        let (directory, file_name) = entry_path.split_once('/').ok_or(StructureError)?;
+        let board = directory
+            .strip_prefix(DIRECTORY_PREFIX)
+            .filter(|board| !board.is_empty())
+            .ok_or(StructureError)?;
+
+        if image.board.is_empty() {
+            image.board = board.to_string();
+        } else if image.board != board {
+            return Err(StructureError);
+        }
+
+        let entry_type = header[TYPE_OFFSET];
+
+        if REGULAR_FILE_TYPES.contains(&entry_type) && !file_name.is_empty() {
+            image.entries.push(SysupgradeEntry {
+                name: file_name.to_string(),
+                offset: next_header_start + BLOCK_SIZE,
+                size: entry_size,
+            });
+        } else if entry_type != DIRECTORY_TYPE {
+            return Err(StructureError);
+        }
+
+        // Entry data is padded to a multiple of the block size
+        next_header_start += BLOCK_SIZE + entry_size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+    }
+
+    // The archive must have been terminated, and every sysupgrade image has a kernel or a rootfs
+    if image.size > 0 && (image.entry("kernel").is_some() || image.entry("root").is_some()) {
+        return Ok(image);
+    }
+
+    Err(StructureError)
+}
+
+/// Converts a NULL or space terminated ASCII octal tar field to a number
+fn tar_octal(octal_field: &[u8]) -> Option<usize> {
+    let octal_string = String::from_utf8_lossy(octal_field);
+    let octal_digits = octal_string.trim_matches(|c: char| c == '\0' || c == ' ');
+
+    usize::from_str_radix(octal_digits, 8).ok()
+}
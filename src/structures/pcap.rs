@@ -1,6 +1,16 @@
+use crate::common::is_offset_safe;
 use crate::structures::common::{self, StructureError};
 use std::collections::HashMap;
 
+/// Stores info about the packets in a pcap or pcap-ng capture file
+#[derive(Debug, Default, Clone)]
+pub struct PcapCapture {
+    pub size: usize,
+    pub packet_count: usize,
+    /// Link types of the captured interfaces, e.g., "Ethernet"
+    pub link_types: Vec<String>,
+}
+
 /// Storage struct for Pcap block info
 #[derive(Debug, Clone, Default)]
 pub struct PcapBlock {
@@ -15,6 +25,8 @@ pub fn parse_pcapng_block(
 ) -> Result<PcapBlock, StructureError> {
     // Reserved bit in block type field
     const BLOCK_TYPE_RESERVED_MASK: usize = 0x80000000;
+    // Blocks are at least a block type, block size, and trailing block size
+    const MIN_BLOCK_SIZE: usize = 12;
 
     let block_header_structure = vec![("block_type", "u32"), ("block_size", "u32")];
 
@@ -33,7 +45,9 @@ pub fn parse_pcapng_block(
         result.block_size = block_header["block_size"];
 
         // Make sure the reserved bit of the block type is not set
-        if (result.block_type & BLOCK_TYPE_RESERVED_MASK) == 0 {
+        if (result.block_type & BLOCK_TYPE_RESERVED_MASK) == 0
+            && result.block_size >= MIN_BLOCK_SIZE
+        {
             // Calculate the block footer offsets
             let block_footer_start = result.block_size - footer_size;
             let block_footer_end = block_footer_start + footer_size;
@@ -100,3 +114,202 @@ pub fn parse_pcapng_section_block(block_data: &[u8]) -> Result<PcapSectionBlock,
 
     Err(StructureError)
 }
+
+/// Parses all the blocks in a pcap-ng capture, counting its packets and identifying the link types of its interfaces
+pub fn parse_pcapng_capture(capture_data: &[u8]) -> Result<PcapCapture, StructureError> {
+    // Pcap-NG files must have at least two blocks: a section header block and an interface description block
+    const MIN_BLOCK_COUNT: usize = 2;
+    const INTERFACE_DESCRIPTION_BLOCK: usize = 1;
+    const OBSOLETE_PACKET_BLOCK: usize = 2;
+    const SIMPLE_PACKET_BLOCK: usize = 3;
+    const ENHANCED_PACKET_BLOCK: usize = 6;
+
+    let interface_structure = vec![
+        ("block_type", "u32"),
+        ("block_size", "u32"),
+        ("link_type", "u16"),
+        ("reserved", "u16"),
+        ("snap_length", "u32"),
+    ];
+
+    // All pcap-ng files start with a section header
+    let section_header = parse_pcapng_section_block(capture_data)?;
+
+    let mut capture = PcapCapture {
+        ..Default::default()
+    };
+
+    let mut block_count: usize = 1;
+    let mut next_offset = section_header.block_size;
+    let mut previous_offset = None;
+
+    while is_offset_safe(capture_data.len(), next_offset, previous_offset) {
+        let block_data = &capture_data[next_offset..];
+
+        match parse_pcapng_block(block_data, &section_header.endianness) {
+            Err(_) => break,
+            Ok(block_header) => {
+                match block_header.block_type {
+                    INTERFACE_DESCRIPTION_BLOCK => {
+                        let interface = common::parse(
+                            block_data,
+                            &interface_structure,
+                            &section_header.endianness,
+                        )?;
+                        capture
+                            .link_types
+                            .push(link_type_name(interface["link_type"]));
+                    }
+                    OBSOLETE_PACKET_BLOCK | SIMPLE_PACKET_BLOCK | ENHANCED_PACKET_BLOCK => {
+                        capture.packet_count += 1;
+                    }
+                    _ => (),
+                }
+
+                block_count += 1;
+                previous_offset = Some(next_offset);
+                next_offset += block_header.block_size;
+            }
+        }
+    }
+
+    if block_count < MIN_BLOCK_COUNT {
+        return Err(StructureError);
+    }
+
+    capture.size = next_offset;
+
+    Ok(capture)
+}
+
+/// Parses a libpcap capture file, counting its packets
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::pcap::parse_pcap_capture;
+///
+/// let mut pcap = b"\xD4\xC3\xB2\xA1\x02\x00\x04\x00\x00\x00\x00\x00\x00\x00\x00\x00".to_vec();
+/// pcap.extend(b"\xFF\xFF\x00\x00\x01\x00\x00\x00");
+/// pcap.extend(b"\x00\x00\x00\x00\x00\x00\x00\x00\x04\x00\x00\x00\x04\x00\x00\x00ABCD");
+///
+/// let capture = parse_pcap_capture(&pcap).unwrap();
+///
+/// assert_eq!(capture.packet_count, 1);
+/// assert_eq!(capture.link_types, vec!["Ethernet"]);
+/// assert_eq!(capture.size, pcap.len());
+/// ```
+pub fn parse_pcap_capture(capture_data: &[u8]) -> Result<PcapCapture, StructureError> {
+    const MICROSECOND_MAGIC: usize = 0xA1B2C3D4;
+    const NANOSECOND_MAGIC: usize = 0xA1B23C4D;
+    const MAJOR_VERSION: usize = 2;
+    const MINOR_VERSION: usize = 4;
+    // The upper bits of the link type field hold FCS info
+    const LINK_TYPE_MASK: usize = 0xFFFF;
+    // Largest packet size that libpcap will read, regardless of the snap length
+    const MAX_PACKET_SIZE: usize = 0x40000;
+
+    let pcap_structure = vec![
+        ("magic", "u32"),
+        ("major_version", "u16"),
+        ("minor_version", "u16"),
+        ("timezone", "u32"),
+        ("sigfigs", "u32"),
+        ("snap_length", "u32"),
+        ("link_type", "u32"),
+    ];
+
+    let record_structure = vec![
+        ("timestamp", "u32"),
+        ("timestamp_fraction", "u32"),
+        ("captured_length", "u32"),
+        ("original_length", "u32"),
+    ];
+
+    // The magic bytes are written in the byte order of the capturing host
+    let mut endianness = "little";
+    let mut pcap_header = common::parse(capture_data, &pcap_structure, endianness)?;
+
+    if pcap_header["magic"] != MICROSECOND_MAGIC && pcap_header["magic"] != NANOSECOND_MAGIC {
+        endianness = "big";
+        pcap_header = common::parse(capture_data, &pcap_structure, endianness)?;
+    }
+
+    let max_timestamp_fraction = match pcap_header["magic"] {
+        MICROSECOND_MAGIC => 1_000_000,
+        NANOSECOND_MAGIC => 1_000_000_000,
+        _ => return Err(StructureError),
+    };
+
+    if pcap_header["major_version"] != MAJOR_VERSION
+        || pcap_header["minor_version"] != MINOR_VERSION
+        || pcap_header["snap_length"] == 0
+    {
+        return Err(StructureError);
+    }
+
+    let max_packet_size = std::cmp::max(pcap_header["snap_length"], MAX_PACKET_SIZE);
+
+    let mut capture = PcapCapture {
+        size: common::size(&pcap_structure),
+        link_types: vec![link_type_name(pcap_header["link_type"] & LINK_TYPE_MASK)],
+        ..Default::default()
+    };
+
+    // Packet records continue until EOF; stop at the first record that is invalid or truncated
+    while let Some(record_data) = capture_data.get(capture.size..) {
+        let record = match common::parse(record_data, &record_structure, endianness) {
+            Err(_) => break,
+            Ok(record) => record,
+        };
+
+        let record_size = common::size(&record_structure) + record["captured_length"];
+
+        if record["timestamp_fraction"] >= max_timestamp_fraction
+            || record["captured_length"] > record["original_length"]
+            || record["captured_length"] > max_packet_size
+            || record_size > record_data.len()
+        {
+            break;
+        }
+
+        capture.packet_count += 1;
+        capture.size += record_size;
+    }
+
+    Ok(capture)
+}
+
+/// Returns the name of a pcap link type
+fn link_type_name(link_type: usize) -> String {
+    let name = match link_type {
+        0 => "BSD loopback",
+        1 => "Ethernet",
+        6 => "IEEE 802.5 Token Ring",
+        8 => "SLIP",
+        9 => "PPP",
+        10 => "FDDI",
+        50 => "PPP in HDLC-like framing",
+        101 => "raw IP",
+        104 => "Cisco HDLC",
+        105 => "IEEE 802.11",
+        113 => "Linux cooked capture",
+        119 => "IEEE 802.11 with Prism header",
+        127 => "IEEE 802.11 with radiotap header",
+        187 => "Bluetooth HCI UART",
+        189 => "Linux USB",
+        195 => "IEEE 802.15.4",
+        201 => "Bluetooth HCI with direction",
+        220 => "Linux USB (memory mapped)",
+        227 => "SocketCAN",
+        228 => "raw IPv4",
+        229 => "raw IPv6",
+        249 => "Linux netlink",
+        251 => "Bluetooth Low Energy link layer",
+        272 => "nRF sniffer for Bluetooth LE",
+        276 => "Linux cooked capture v2",
+        _ => return format!("link type {link_type}"),
+    };
+
+    name.to_string()
+}
@@ -1,9 +1,20 @@
 use crate::structures::common::{self, StructureError};
 use std::collections::HashMap;
 
+/// Optional header magics for PE32 and PE32+ images
+const PE32_MAGIC: usize = 0x10B;
+const PE32_PLUS_MAGIC: usize = 0x20B;
+
 /// Stores info about the PE file
 pub struct PEHeader {
     pub machine: String,
+    pub number_of_sections: usize,
+    /// Link time, as a Unix timestamp
+    pub timestamp: usize,
+    pub characteristics: usize,
+    /// Offset of the optional header, relative to the start of the DOS header
+    pub optional_header_offset: usize,
+    pub optional_header_size: usize,
 }
 
 /// Partially parse a PE header
@@ -121,6 +132,11 @@ pub fn parse_pe_header(pe_data: &[u8]) -> Result<PEHeader, StructureError> {
                         if known_machine_types.contains_key(&pe_header["machine"]) {
                             return Ok(PEHeader {
                                 machine: known_machine_types[&pe_header["machine"]].to_string(),
+                                number_of_sections: pe_header["number_of_sections"],
+                                timestamp: pe_header["timestamp"],
+                                characteristics: pe_header["characteristics"],
+                                optional_header_offset: pe_header_end,
+                                optional_header_size: pe_header["optional_header_size"],
                             });
                         }
                     }
@@ -131,3 +147,225 @@ pub fn parse_pe_header(pe_data: &[u8]) -> Result<PEHeader, StructureError> {
 
     Err(StructureError)
 }
+
+/// Stores info about a PE file's optional header
+#[derive(Debug, Default, Clone)]
+pub struct PEOptionalHeader {
+    /// "PE32" or "PE32+"
+    pub format: String,
+    pub subsystem: String,
+}
+
+/// Parses the PE optional header; the PE header must have already been parsed by parse_pe_header
+pub fn parse_pe_optional_header(
+    pe_data: &[u8],
+    pe_header: &PEHeader,
+) -> Result<PEOptionalHeader, StructureError> {
+    // The subsystem is at the same offset in PE32 and PE32+ optional headers
+    const SUBSYSTEM_OFFSET: usize = 68;
+
+    let optional_header_structure = vec![("magic", "u16")];
+    let subsystem_structure = vec![("subsystem", "u16")];
+
+    if pe_header.optional_header_size < SUBSYSTEM_OFFSET + common::size(&subsystem_structure) {
+        return Err(StructureError);
+    }
+
+    let optional_header_data = pe_data
+        .get(pe_header.optional_header_offset..)
+        .ok_or(StructureError)?;
+    let optional_header =
+        common::parse(optional_header_data, &optional_header_structure, "little")?;
+    let subsystem = common::parse(
+        optional_header_data
+            .get(SUBSYSTEM_OFFSET..)
+            .ok_or(StructureError)?,
+        &subsystem_structure,
+        "little",
+    )?;
+
+    let format = match optional_header["magic"] {
+        PE32_MAGIC => "PE32",
+        PE32_PLUS_MAGIC => "PE32+",
+        _ => return Err(StructureError),
+    };
+
+    let subsystem = match subsystem["subsystem"] {
+        1 => "native",
+        2 => "Windows GUI",
+        3 => "Windows console",
+        5 => "OS/2 console",
+        7 => "POSIX console",
+        8 => "native Win9x driver",
+        9 => "Windows CE GUI",
+        10 => "EFI application",
+        11 => "EFI boot service driver",
+        12 => "EFI runtime driver",
+        13 => "EFI ROM",
+        14 => "Xbox",
+        16 => "Windows boot application",
+        _ => "unknown subsystem",
+    };
+
+    Ok(PEOptionalHeader {
+        format: format.to_string(),
+        subsystem: subsystem.to_string(),
+    })
+}
+
+/// Stores info about a .NET assembly's CLR header and metadata
+#[derive(Debug, Default, Clone)]
+pub struct DotNetHeader {
+    /// Minimum CLR runtime version required, e.g., "2.5"
+    pub runtime_version: String,
+    /// Version of the .NET framework the metadata was built against, e.g., "v4.0.30319"
+    pub metadata_version: String,
+    /// True if the assembly contains only managed (IL) code
+    pub il_only: bool,
+}
+
+/// Parses the CLR runtime header of a .NET assembly; the PE header must have already been parsed by parse_pe_header
+pub fn parse_dotnet_header(
+    pe_data: &[u8],
+    pe_header: &PEHeader,
+) -> Result<DotNetHeader, StructureError> {
+    // Offset of the data directories in the optional header, for PE32 and PE32+ images respectively
+    const PE32_DATA_DIRECTORIES_OFFSET: usize = 96;
+    const PE32_PLUS_DATA_DIRECTORIES_OFFSET: usize = 112;
+    // The CLR runtime header is the 15th data directory
+    const CLR_DATA_DIRECTORY_INDEX: usize = 14;
+    const CLR_HEADER_SIZE: usize = 72;
+    const METADATA_MAGIC: usize = 0x424A5342;
+    const MAX_METADATA_VERSION_LENGTH: usize = 255;
+    const COMIMAGE_FLAGS_ILONLY: usize = 1;
+
+    let optional_header_structure = vec![("magic", "u16")];
+    let data_directory_structure = vec![("virtual_address", "u32"), ("size", "u32")];
+
+    let section_structure = vec![
+        ("name", "u64"),
+        ("virtual_size", "u32"),
+        ("virtual_address", "u32"),
+        ("raw_data_size", "u32"),
+        ("raw_data_offset", "u32"),
+        ("relocations_offset", "u32"),
+        ("line_numbers_offset", "u32"),
+        ("relocations_count", "u16"),
+        ("line_numbers_count", "u16"),
+        ("characteristics", "u32"),
+    ];
+
+    let clr_structure = vec![
+        ("header_size", "u32"),
+        ("major_runtime_version", "u16"),
+        ("minor_runtime_version", "u16"),
+        ("metadata_rva", "u32"),
+        ("metadata_size", "u32"),
+        ("flags", "u32"),
+    ];
+
+    let metadata_structure = vec![
+        ("magic", "u32"),
+        ("major_version", "u16"),
+        ("minor_version", "u16"),
+        ("reserved", "u32"),
+        ("version_length", "u32"),
+    ];
+
+    let optional_header_data = pe_data
+        .get(pe_header.optional_header_offset..)
+        .ok_or(StructureError)?;
+    let optional_header =
+        common::parse(optional_header_data, &optional_header_structure, "little")?;
+
+    let data_directories_offset = match optional_header["magic"] {
+        PE32_MAGIC => PE32_DATA_DIRECTORIES_OFFSET,
+        PE32_PLUS_MAGIC => PE32_PLUS_DATA_DIRECTORIES_OFFSET,
+        _ => return Err(StructureError),
+    };
+
+    // The CLR data directory entry must be within the optional header
+    let clr_directory_start = data_directories_offset
+        + (CLR_DATA_DIRECTORY_INDEX * common::size(&data_directory_structure));
+    let clr_directory_end = clr_directory_start + common::size(&data_directory_structure);
+
+    if clr_directory_end > pe_header.optional_header_size {
+        return Err(StructureError);
+    }
+
+    let clr_directory = common::parse(
+        optional_header_data
+            .get(clr_directory_start..)
+            .ok_or(StructureError)?,
+        &data_directory_structure,
+        "little",
+    )?;
+
+    // Not a .NET assembly
+    if clr_directory["virtual_address"] == 0 || clr_directory["size"] < CLR_HEADER_SIZE {
+        return Err(StructureError);
+    }
+
+    // Parse the section table, which immediately follows the optional header
+    let mut sections: Vec<HashMap<String, usize>> = vec![];
+    let section_table_offset = pe_header.optional_header_offset + pe_header.optional_header_size;
+
+    for i in 0..pe_header.number_of_sections {
+        let section_offset = section_table_offset + (i * common::size(&section_structure));
+        let section_data = pe_data.get(section_offset..).ok_or(StructureError)?;
+        sections.push(common::parse(section_data, &section_structure, "little")?);
+    }
+
+    // Converts a relative virtual address to a file offset
+    let rva_to_offset = |rva: usize| -> Result<usize, StructureError> {
+        for section in &sections {
+            let section_size = std::cmp::max(section["virtual_size"], section["raw_data_size"]);
+            let section_start = section["virtual_address"];
+
+            if rva >= section_start && rva < section_start + section_size {
+                return Ok(rva - section_start + section["raw_data_offset"]);
+            }
+        }
+
+        Err(StructureError)
+    };
+
+    let clr_offset = rva_to_offset(clr_directory["virtual_address"])?;
+    let clr_header = common::parse(
+        pe_data.get(clr_offset..).ok_or(StructureError)?,
+        &clr_structure,
+        "little",
+    )?;
+
+    if clr_header["header_size"] != CLR_HEADER_SIZE {
+        return Err(StructureError);
+    }
+
+    let metadata_offset = rva_to_offset(clr_header["metadata_rva"])?;
+    let metadata_data = pe_data.get(metadata_offset..).ok_or(StructureError)?;
+    let metadata_header = common::parse(metadata_data, &metadata_structure, "little")?;
+
+    if metadata_header["magic"] != METADATA_MAGIC
+        || metadata_header["version_length"] > MAX_METADATA_VERSION_LENGTH
+    {
+        return Err(StructureError);
+    }
+
+    // The metadata version string is NULL padded
+    let version_start = common::size(&metadata_structure);
+    let version_end = version_start + metadata_header["version_length"];
+    let version_data = metadata_data
+        .get(version_start..version_end)
+        .ok_or(StructureError)?;
+
+    Ok(DotNetHeader {
+        runtime_version: format!(
+            "{}.{}",
+            clr_header["major_runtime_version"], clr_header["minor_runtime_version"]
+        ),
+        metadata_version: String::from_utf8_lossy(version_data)
+            .trim_end_matches('\0')
+            .to_string(),
+        il_only: (clr_header["flags"] & COMIMAGE_FLAGS_ILONLY) != 0,
+    })
+}
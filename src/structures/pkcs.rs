@@ -0,0 +1,260 @@
+use crate::structures::common::StructureError;
+use crate::structures::x509::{
+    DER_CONTEXT_0, DER_INTEGER, DER_OCTET_STRING, DER_OID, DER_SEQUENCE, DERElement,
+    X509Certificate, oid_name, parse_der_children, parse_der_element, parse_x509_certificate,
+};
+
+/// Stores info about a PKCS#1 RSA key
+#[derive(Debug, Default, Clone)]
+pub struct RSAKey {
+    /// Size of the DER encoded key
+    pub size: usize,
+    pub modulus_bits: usize,
+    pub public_exponent: usize,
+}
+
+/// Returns the size, in bits, of a DER encoded unsigned integer
+fn integer_bits(integer: &DERElement) -> usize {
+    // Positive integers with the high bit set are prefixed with a NULL byte
+    match integer.contents.iter().position(|b| *b != 0) {
+        None => 0,
+        Some(first) => {
+            ((integer.contents.len() - first) * 8)
+                - integer.contents[first].leading_zeros() as usize
+        }
+    }
+}
+
+/// Parses the modulus and public exponent of an RSA key
+fn parse_rsa_integers(
+    key_data: &[u8],
+    integer_count: usize,
+) -> Result<(DERElement<'_>, Vec<DERElement<'_>>), StructureError> {
+    // Moduli smaller than this are not real keys
+    const MIN_MODULUS_SIZE: usize = 64;
+    // Public exponents larger than 64 bits are not used in practice
+    const MAX_EXPONENT_SIZE: usize = 8;
+
+    let key = parse_der_element(key_data)?;
+
+    if key.tag != DER_SEQUENCE {
+        return Err(StructureError);
+    }
+
+    let integers = parse_der_children(key.contents)?;
+
+    if integers.len() != integer_count || integers.iter().any(|field| field.tag != DER_INTEGER) {
+        return Err(StructureError);
+    }
+
+    // Private keys start with a version number
+    let (modulus, exponent) = match integer_count {
+        2 => (&integers[0], &integers[1]),
+        _ => (&integers[1], &integers[2]),
+    };
+
+    if modulus.contents.len() < MIN_MODULUS_SIZE
+        || exponent.contents.is_empty()
+        || exponent.contents.len() > MAX_EXPONENT_SIZE
+    {
+        return Err(StructureError);
+    }
+
+    Ok((key, integers))
+}
+
+/// Parses a DER encoded PKCS#1 RSAPublicKey
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::pkcs::parse_rsa_public_key;
+///
+/// let mut key = b"\x30\x48\x02\x41\x00\xC1".to_vec();
+/// key.extend([0xAA; 63]);
+/// key.extend(b"\x02\x03\x01\x00\x01");
+///
+/// let rsa_key = parse_rsa_public_key(&key).unwrap();
+///
+/// assert_eq!(rsa_key.size, key.len());
+/// assert_eq!(rsa_key.modulus_bits, 512);
+/// assert_eq!(rsa_key.public_exponent, 65537);
+/// ```
+pub fn parse_rsa_public_key(key_data: &[u8]) -> Result<RSAKey, StructureError> {
+    let (key, integers) = parse_rsa_integers(key_data, 2)?;
+
+    Ok(RSAKey {
+        size: key.encoded.len(),
+        modulus_bits: integer_bits(&integers[0]),
+        public_exponent: integer_value(&integers[1]),
+    })
+}
+
+/// Parses a DER encoded PKCS#1 RSAPrivateKey
+pub fn parse_rsa_private_key(key_data: &[u8]) -> Result<RSAKey, StructureError> {
+    // Version, modulus, public exponent, private exponent, prime 1, prime 2, exponent 1, exponent 2, coefficient
+    const INTEGER_COUNT: usize = 9;
+
+    let (key, integers) = parse_rsa_integers(key_data, INTEGER_COUNT)?;
+
+    // Only two-prime keys (version 0) are supported
+    if integers[0].contents != [0] {
+        return Err(StructureError);
+    }
+
+    Ok(RSAKey {
+        size: key.encoded.len(),
+        modulus_bits: integer_bits(&integers[1]),
+        public_exponent: integer_value(&integers[2]),
+    })
+}
+
+/// Returns the value of a small DER encoded unsigned integer
+fn integer_value(integer: &DERElement) -> usize {
+    integer
+        .contents
+        .iter()
+        .fold(0_usize, |value, b| (value << 8) | *b as usize)
+}
+
+/// Stores info about a PKCS#7 ContentInfo structure
+#[derive(Debug, Default, Clone)]
+pub struct PKCS7 {
+    /// Size of the DER encoded structure
+    pub size: usize,
+    /// e.g., "signedData"
+    pub content_type: String,
+    /// Certificates embedded in signedData content
+    pub certificates: Vec<X509Certificate>,
+}
+
+/// Parses a DER encoded PKCS#7 ContentInfo structure
+pub fn parse_pkcs7(pkcs7_data: &[u8]) -> Result<PKCS7, StructureError> {
+    let (content_info, content_type, content) = parse_content_info(pkcs7_data)?;
+
+    let mut pkcs7 = PKCS7 {
+        size: content_info.encoded.len(),
+        content_type: oid_name(content_type.contents),
+        ..Default::default()
+    };
+
+    if pkcs7.content_type == "signedData" {
+        let content = content.ok_or(StructureError)?;
+        let signed_data = parse_der_element(content.contents)?;
+
+        if signed_data.tag != DER_SEQUENCE {
+            return Err(StructureError);
+        }
+
+        // Version, digest algorithms, content info, certificates (optional), CRLs (optional), signer infos
+        for field in parse_der_children(signed_data.contents)? {
+            if field.tag == DER_CONTEXT_0 {
+                for certificate in parse_der_children(field.contents)? {
+                    pkcs7
+                        .certificates
+                        .push(parse_x509_certificate(certificate.encoded)?);
+                }
+            }
+        }
+    }
+
+    Ok(pkcs7)
+}
+
+/// Parses a ContentInfo structure, returning the ContentInfo, content type, and explicitly tagged content, if any
+fn parse_content_info(
+    content_info_data: &[u8],
+) -> Result<(DERElement<'_>, DERElement<'_>, Option<DERElement<'_>>), StructureError> {
+    // PKCS#7 content types are all in the 1.2.840.113549.1.7 arc
+    const PKCS7_OID_PREFIX: &[u8] = b"\x2A\x86\x48\x86\xF7\x0D\x01\x07";
+
+    let content_info = parse_der_element(content_info_data)?;
+
+    if content_info.tag != DER_SEQUENCE {
+        return Err(StructureError);
+    }
+
+    let mut fields = parse_der_children(content_info.contents)?.into_iter();
+
+    if let Some(content_type) = fields.next() {
+        if content_type.tag == DER_OID
+            && content_type.contents.len() == PKCS7_OID_PREFIX.len() + 1
+            && content_type.contents.starts_with(PKCS7_OID_PREFIX)
+        {
+            match (fields.next(), fields.next()) {
+                (None, None) => return Ok((content_info, content_type, None)),
+                (Some(content), None) if content.tag == DER_CONTEXT_0 => {
+                    return Ok((content_info, content_type, Some(content)));
+                }
+                _ => (),
+            }
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about a PKCS#12 PFX structure
+#[derive(Debug, Default, Clone)]
+pub struct PKCS12 {
+    /// Size of the DER encoded structure
+    pub size: usize,
+    /// Content type of the authenticated safe, e.g., "data"
+    pub content_type: String,
+    /// MAC digest algorithm, if the PFX is password integrity protected
+    pub mac_algorithm: Option<String>,
+    /// Number of MAC key derivation iterations
+    pub mac_iterations: usize,
+}
+
+/// Parses a DER encoded PKCS#12 PFX structure
+pub fn parse_pkcs12(pkcs12_data: &[u8]) -> Result<PKCS12, StructureError> {
+    const PFX_VERSION: &[u8] = &[3];
+    const DEFAULT_ITERATIONS: usize = 1;
+
+    let pfx = parse_der_element(pkcs12_data)?;
+
+    if pfx.tag != DER_SEQUENCE {
+        return Err(StructureError);
+    }
+
+    let fields = parse_der_children(pfx.contents)?;
+
+    // Version, auth safe, MAC data (optional)
+    if !(2..=3).contains(&fields.len())
+        || fields[0].tag != DER_INTEGER
+        || fields[0].contents != PFX_VERSION
+    {
+        return Err(StructureError);
+    }
+
+    let (_, content_type, _) = parse_content_info(fields[1].encoded)?;
+
+    let mut pkcs12 = PKCS12 {
+        size: pfx.encoded.len(),
+        content_type: oid_name(content_type.contents),
+        ..Default::default()
+    };
+
+    if let Some(mac_data) = fields.get(2) {
+        // MAC (DigestInfo), salt, iterations (optional)
+        let mac_data = parse_der_children(mac_data.contents)?;
+        let digest_info = parse_der_children(mac_data.first().ok_or(StructureError)?.contents)?;
+        let algorithm = parse_der_children(digest_info.first().ok_or(StructureError)?.contents)?;
+
+        if digest_info.len() != 2
+            || digest_info[1].tag != DER_OCTET_STRING
+            || algorithm.first().is_none_or(|oid| oid.tag != DER_OID)
+        {
+            return Err(StructureError);
+        }
+
+        pkcs12.mac_algorithm = Some(oid_name(algorithm[0].contents));
+        pkcs12.mac_iterations = match mac_data.get(2) {
+            Some(iterations) if iterations.tag == DER_INTEGER => integer_value(iterations),
+            _ => DEFAULT_ITERATIONS,
+        };
+    }
+
+    Ok(pkcs12)
+}
@@ -0,0 +1,179 @@
+use crate::structures::common::StructureError;
+
+/// Protocol buffer wire types
+pub const WIRE_TYPE_VARINT: usize = 0;
+pub const WIRE_TYPE_I64: usize = 1;
+pub const WIRE_TYPE_LEN: usize = 2;
+pub const WIRE_TYPE_I32: usize = 5;
+
+/// Stores info about a single field in a serialized protocol buffer message
+#[derive(Debug, Default, Clone)]
+pub struct ProtobufField {
+    pub field_number: usize,
+    pub wire_type: usize,
+    /// Size of the field's value, in bytes
+    pub size: usize,
+    /// Integer value of varint and fixed size fields
+    pub value: usize,
+    /// Length delimited fields that contain printable text
+    pub text: Option<String>,
+    /// Length delimited fields that appear to contain an embedded message
+    pub fields: Vec<ProtobufField>,
+}
+
+/// Stores info about a serialized protocol buffer message
+#[derive(Debug, Default, Clone)]
+pub struct ProtobufMessage {
+    pub size: usize,
+    pub fields: Vec<ProtobufField>,
+}
+
+/// Heuristically parses serialized protocol buffer data.
+///
+/// Fields are parsed until something that doesn't look like a valid field is encountered;
+/// the returned message size is the size of all successfully parsed fields.
+pub fn parse_protobuf_message(protobuf_data: &[u8]) -> Result<ProtobufMessage, StructureError> {
+    parse_protobuf_fields(protobuf_data, 0, false)
+}
+
+/// Parses protobuf fields; if exact is true, all of the data must be successfully parsed as fields
+fn parse_protobuf_fields(
+    protobuf_data: &[u8],
+    depth: usize,
+    exact: bool,
+) -> Result<ProtobufMessage, StructureError> {
+    // Limit recursion when parsing embedded messages
+    const MAX_DEPTH: usize = 8;
+    // Field numbers are 29 bits, and 19000-19999 are reserved
+    const MAX_FIELD_NUMBER: usize = 0x1FFFFFFF;
+    const RESERVED_FIELD_NUMBERS: std::ops::RangeInclusive<usize> = 19000..=19999;
+    const FIELD_NUMBER_SHIFT: usize = 3;
+    const WIRE_TYPE_MASK: usize = 0b111;
+
+    let mut message = ProtobufMessage {
+        ..Default::default()
+    };
+
+    if depth > MAX_DEPTH {
+        return Err(StructureError);
+    }
+
+    while message.size < protobuf_data.len() {
+        let field_data = &protobuf_data[message.size..];
+
+        let (tag, tag_size) = match parse_varint(field_data) {
+            Ok(varint) => varint,
+            Err(_) => break,
+        };
+
+        let mut field = ProtobufField {
+            field_number: tag >> FIELD_NUMBER_SHIFT,
+            wire_type: tag & WIRE_TYPE_MASK,
+            ..Default::default()
+        };
+
+        if field.field_number == 0
+            || field.field_number > MAX_FIELD_NUMBER
+            || RESERVED_FIELD_NUMBERS.contains(&field.field_number)
+        {
+            break;
+        }
+
+        let value_data = &field_data[tag_size..];
+
+        // Size of the value's length prefix, if any
+        let mut prefix_size: usize = 0;
+
+        match field.wire_type {
+            WIRE_TYPE_VARINT => match parse_varint(value_data) {
+                Ok((value, value_size)) => {
+                    field.value = value;
+                    field.size = value_size;
+                }
+                Err(_) => break,
+            },
+            WIRE_TYPE_I64 | WIRE_TYPE_I32 => {
+                field.size = match field.wire_type {
+                    WIRE_TYPE_I64 => 8,
+                    _ => 4,
+                };
+
+                match value_data.get(..field.size) {
+                    Some(value_bytes) => {
+                        for (i, byte) in value_bytes.iter().enumerate() {
+                            field.value |= (*byte as usize) << (8 * i);
+                        }
+                    }
+                    None => break,
+                }
+            }
+            WIRE_TYPE_LEN => match parse_varint(value_data) {
+                Ok((length, length_size)) => {
+                    match value_data.get(length_size..length_size + length) {
+                        Some(embedded_data) => {
+                            prefix_size = length_size;
+                            field.size = length;
+
+                            if let Some(text) = protobuf_string(embedded_data) {
+                                field.text = Some(text);
+                            } else if let Ok(embedded_message) =
+                                parse_protobuf_fields(embedded_data, depth + 1, true)
+                            {
+                                field.fields = embedded_message.fields;
+                            }
+                        }
+                        None => break,
+                    }
+                }
+                Err(_) => break,
+            },
+            // Groups are deprecated, and wire types 6 and 7 are invalid
+            _ => break,
+        }
+
+        message.size += tag_size + prefix_size + field.size;
+        message.fields.push(field);
+    }
+
+    if message.fields.is_empty() || (exact && message.size != protobuf_data.len()) {
+        return Err(StructureError);
+    }
+
+    Ok(message)
+}
+
+/// Returns the data as a string, if it appears to be printable UTF-8 text
+fn protobuf_string(string_data: &[u8]) -> Option<String> {
+    match std::str::from_utf8(string_data) {
+        Ok(text)
+            if !text.is_empty()
+                && !text
+                    .chars()
+                    .any(|c| c.is_control() && !c.is_ascii_whitespace()) =>
+        {
+            Some(text.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Parses a protobuf varint; returns the value and the number of bytes it occupied
+fn parse_varint(varint_data: &[u8]) -> Result<(usize, usize), StructureError> {
+    const MAX_VARINT_SIZE: usize = 10;
+    const CONTINUE_BIT: u8 = 0x80;
+    const VALUE_MASK: u8 = 0x7F;
+
+    let mut value: usize = 0;
+
+    for (i, byte) in varint_data.iter().take(MAX_VARINT_SIZE).enumerate() {
+        value |= ((byte & VALUE_MASK) as usize)
+            .checked_shl(7 * i as u32)
+            .unwrap_or(0);
+
+        if (byte & CONTINUE_BIT) == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+
+    Err(StructureError)
+}
@@ -0,0 +1,355 @@
+use crate::common::get_cstring;
+use crate::structures::common::{self, StructureError};
+use crate::structures::elf::{ELFSegment, parse_elf_segments};
+use std::collections::HashMap;
+
+/// Size of a legacy Qualcomm MBN header
+pub const MBN_HEADER_SIZE: usize = 40;
+
+/// Size of a Qualcomm SBL header
+pub const SBL_HEADER_SIZE: usize = 80;
+
+/// Stores info about a Qualcomm MBN or SBL header
+#[derive(Debug, Default, Clone)]
+pub struct MBNHeader {
+    pub image_id: usize,
+    /// Offset of the code, relative to the start of the header
+    pub header_size: usize,
+    pub load_address: usize,
+    pub code_size: usize,
+    pub signature_size: usize,
+    pub cert_chain_size: usize,
+}
+
+impl MBNHeader {
+    /// Total size of the image, including the header
+    pub fn total_size(&self) -> usize {
+        self.header_size + self.code_size + self.signature_size + self.cert_chain_size
+    }
+
+    /// Offset of the certificate chain, relative to the start of the header
+    pub fn cert_chain_offset(&self) -> usize {
+        self.header_size + self.code_size + self.signature_size
+    }
+}
+
+/// Returns the name of a Qualcomm image ID, as used in MBN headers
+pub fn image_id_name(image_id: usize) -> &'static str {
+    match image_id {
+        1 => "OEM SBL",
+        2 => "AMSS",
+        3 => "QCSBL",
+        4 => "hash",
+        5 => "APPSBL",
+        6 => "APPS",
+        7 => "HOSTDL",
+        8 => "DSP1",
+        9 => "FSBL",
+        10 => "DBL",
+        11 => "OSBL",
+        12 => "DSP2",
+        13 => "EHOSTDL",
+        14 => "NANDPRG",
+        15 => "NORPRG",
+        16 => "RAMFS1",
+        17 => "RAMFS2",
+        18 => "ADSP Q5",
+        19 => "APPS kernel",
+        20 => "backup RAMFS",
+        21 => "SBL1",
+        22 => "SBL2",
+        23 => "RPM",
+        24 => "SBL3",
+        25 => "TrustZone",
+        26 => "SSD keys",
+        27 => "GEN",
+        28 => "DSP3",
+        29 => "ACDB",
+        30 => "WDT",
+        31 => "MBA",
+        _ => "unknown",
+    }
+}
+
+/// Highest known image ID
+pub const MAX_IMAGE_ID: usize = 31;
+
+/// Parse a legacy (version 3) Qualcomm MBN header
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::qualcomm::parse_mbn_header;
+///
+/// let header = b"\x05\x00\x00\x00\x03\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x88\
+///                \x00\x13\x00\x00\x00\x10\x00\x00\x00\x10\x00\x88\x00\x01\x00\x00\
+///                \x00\x11\x00\x88\x00\x02\x00\x00";
+///
+/// let mbn_header = parse_mbn_header(header).unwrap();
+///
+/// assert_eq!(mbn_header.image_id, 5);
+/// assert_eq!(mbn_header.load_address, 0x88000000);
+/// assert_eq!(mbn_header.code_size, 0x1000);
+/// assert_eq!(mbn_header.total_size(), 40 + 0x1300);
+/// ```
+pub fn parse_mbn_header(header_data: &[u8]) -> Result<MBNHeader, StructureError> {
+    const VERSION: usize = 3;
+
+    let header_structure = vec![
+        ("image_id", "u32"),
+        ("version", "u32"),
+        ("image_src", "u32"),
+        ("image_dest", "u32"),
+        ("image_size", "u32"),
+        ("code_size", "u32"),
+        ("signature_ptr", "u32"),
+        ("signature_size", "u32"),
+        ("cert_chain_ptr", "u32"),
+        ("cert_chain_size", "u32"),
+    ];
+
+    let header = common::parse(header_data, &header_structure, "little")?;
+
+    if header["version"] != VERSION || header["image_id"] == 0 || header["image_id"] > MAX_IMAGE_ID
+    {
+        return Err(StructureError);
+    }
+
+    validate_layout(&header, MBN_HEADER_SIZE)
+}
+
+/// Parse a Qualcomm SBL header, as used by secondary boot loaders of older chipsets
+pub fn parse_sbl_header(header_data: &[u8]) -> Result<MBNHeader, StructureError> {
+    const CODEWORD: usize = 0x844BDCD1;
+    const MAGIC: usize = 0x73D71034;
+
+    let header_structure = vec![
+        ("codeword", "u32"),
+        ("magic", "u32"),
+        ("image_id", "u32"),
+        ("reserved1", "u32"),
+        ("reserved2", "u32"),
+        ("image_src", "u32"),
+        ("image_dest", "u32"),
+        ("image_size", "u32"),
+        ("code_size", "u32"),
+        ("signature_ptr", "u32"),
+        ("signature_size", "u32"),
+        ("cert_chain_ptr", "u32"),
+        ("cert_chain_size", "u32"),
+    ];
+
+    let header = common::parse(header_data, &header_structure, "little")?;
+
+    if header["codeword"] != CODEWORD || header["magic"] != MAGIC {
+        return Err(StructureError);
+    }
+
+    validate_layout(&header, SBL_HEADER_SIZE)
+}
+
+/// Validates that the code, signature and certificate chain are contiguous, and fill the image
+fn validate_layout(
+    header: &HashMap<String, usize>,
+    header_size: usize,
+) -> Result<MBNHeader, StructureError> {
+    if header["code_size"] == 0
+        || header["image_size"]
+            != header["code_size"] + header["signature_size"] + header["cert_chain_size"]
+        || header["signature_ptr"] != header["image_dest"] + header["code_size"]
+        || header["cert_chain_ptr"] != header["signature_ptr"] + header["signature_size"]
+    {
+        return Err(StructureError);
+    }
+
+    Ok(MBNHeader {
+        image_id: header["image_id"],
+        header_size,
+        load_address: header["image_dest"],
+        code_size: header["code_size"],
+        signature_size: header["signature_size"],
+        cert_chain_size: header["cert_chain_size"],
+    })
+}
+
+/// Stores info about a Qualcomm signed ELF image, which includes a hash segment
+#[derive(Debug, Default, Clone)]
+pub struct QualcommELFImage {
+    /// Loadable segments, other than the hash segment
+    pub code_segments: Vec<ELFSegment>,
+    pub hash_segment: ELFSegment,
+    /// Version of the hash segment header
+    pub hash_version: usize,
+    /// Image ID from the hash segment header, for header versions that include one
+    pub image_id: Option<usize>,
+    /// Total size of the image
+    pub size: usize,
+}
+
+/// Parse an ELF image with a Qualcomm hash segment, as used for boot loaders, TrustZone and firmware images
+pub fn parse_qualcomm_elf(elf_data: &[u8]) -> Result<QualcommELFImage, StructureError> {
+    const PT_LOAD: usize = 1;
+    const PT_NULL: usize = 0;
+    // Qualcomm segment types are stored in bits 24-26 of the program header flags
+    const SEGMENT_TYPE_SHIFT: usize = 24;
+    const SEGMENT_TYPE_MASK: usize = 7;
+    const SEGMENT_TYPE_HASH: usize = 2;
+    const SEGMENT_TYPE_PHDR: usize = 7;
+    // Hash segment header versions 3 and 5 have an image ID field; later versions do not
+    const HASH_VERSIONS: [usize; 5] = [3, 5, 6, 7, 8];
+    const IMAGE_ID_HASH_VERSIONS: [usize; 2] = [3, 5];
+
+    let hash_header_structure = vec![("image_id", "u32"), ("version", "u32")];
+
+    let mut image = QualcommELFImage {
+        ..Default::default()
+    };
+
+    let mut hash_segment: Option<ELFSegment> = None;
+
+    for segment in parse_elf_segments(elf_data)? {
+        // Segments must be within the bounds of the file
+        if elf_data
+            .get(segment.offset..)
+            .and_then(|data| data.get(..segment.file_size))
+            .is_none()
+        {
+            return Err(StructureError);
+        }
+
+        image.size = std::cmp::max(image.size, segment.offset + segment.file_size);
+
+        match (segment.flags >> SEGMENT_TYPE_SHIFT) & SEGMENT_TYPE_MASK {
+            SEGMENT_TYPE_HASH if segment.segment_type == PT_NULL => hash_segment = Some(segment),
+            SEGMENT_TYPE_PHDR => (),
+            _ => {
+                if segment.segment_type == PT_LOAD && segment.file_size > 0 {
+                    image.code_segments.push(segment);
+                }
+            }
+        }
+    }
+
+    image.hash_segment = hash_segment.ok_or(StructureError)?;
+
+    let hash_header = common::parse(
+        &elf_data[image.hash_segment.offset..],
+        &hash_header_structure,
+        "little",
+    )?;
+
+    if !HASH_VERSIONS.contains(&hash_header["version"]) {
+        return Err(StructureError);
+    }
+
+    image.hash_version = hash_header["version"];
+
+    if IMAGE_ID_HASH_VERSIONS.contains(&image.hash_version)
+        && hash_header["image_id"] > 0
+        && hash_header["image_id"] <= MAX_IMAGE_ID
+    {
+        image.image_id = Some(hash_header["image_id"]);
+    }
+
+    Ok(image)
+}
+
+/// Stores info about an X.509 certificate in a certificate chain
+#[derive(Debug, Default, Clone)]
+pub struct Certificate {
+    /// Offset of the certificate, relative to the start of the searched data
+    pub offset: usize,
+    pub size: usize,
+    /// Subject common name, if any
+    pub common_name: String,
+    /// Software ID, from the subject organizational unit of attestation certificates (e.g., "0x0000000000000019")
+    pub sw_id: Option<String>,
+}
+
+/// Finds the first chain of consecutive DER encoded X.509 certificates in the provided data
+pub fn find_certificate_chain(data: &[u8]) -> Vec<Certificate> {
+    let mut certificates: Vec<Certificate> = vec![];
+
+    // Certificates are DER SEQUENCEs with 2-byte lengths, that start with the tbsCertificate SEQUENCE
+    let is_certificate = |offset: usize| -> Option<usize> {
+        let header = data.get(offset..offset + 8)?;
+
+        if header[0] == 0x30 && header[1] == 0x82 && header[4] == 0x30 && header[5] == 0x82 {
+            let size = 4 + ((header[2] as usize) << 8) + header[3] as usize;
+
+            if offset + size <= data.len() {
+                return Some(size);
+            }
+        }
+
+        None
+    };
+
+    let mut offset = match (0..data.len()).find(|offset| is_certificate(*offset).is_some()) {
+        None => return certificates,
+        Some(offset) => offset,
+    };
+
+    while let Some(size) = is_certificate(offset) {
+        let certificate_data = &data[offset..offset + size];
+
+        certificates.push(Certificate {
+            offset,
+            size,
+            common_name: subject_attribute(certificate_data, &[0x55, 0x04, 0x03])
+                .unwrap_or_default(),
+            sw_id: sw_id(certificate_data),
+        });
+
+        offset += size;
+    }
+
+    certificates
+}
+
+/// Returns the value of the last instance of the attribute with the specified OID; for certificates, the subject follows the issuer
+fn subject_attribute(certificate_data: &[u8], oid: &[u8]) -> Option<String> {
+    const OID_TAG: u8 = 0x06;
+
+    let mut value: Option<String> = None;
+
+    for offset in 0..certificate_data.len() {
+        if certificate_data.get(offset) == Some(&OID_TAG)
+            && certificate_data.get(offset + 1) == Some(&(oid.len() as u8))
+            && certificate_data
+                .get(offset + 2..)
+                .is_some_and(|data| data.starts_with(oid))
+        {
+            // The OID is followed by a string tag, length, and the string value
+            let value_start = offset + 2 + oid.len();
+
+            if let Some(length) = certificate_data.get(value_start + 1) {
+                if let Some(value_data) =
+                    certificate_data.get(value_start + 2..value_start + 2 + *length as usize)
+                {
+                    value = Some(get_cstring(value_data));
+                }
+            }
+        }
+    }
+
+    value
+}
+
+/// Returns the software ID from an attestation certificate's organizational units, e.g., "01 0000000000000019 SW_ID"
+fn sw_id(certificate_data: &[u8]) -> Option<String> {
+    const SW_ID: &[u8] = b" SW_ID";
+    const SW_ID_DIGITS: usize = 16;
+
+    let position = certificate_data
+        .windows(SW_ID.len())
+        .position(|window| window == SW_ID)?;
+
+    let digits = certificate_data.get(position.checked_sub(SW_ID_DIGITS)?..position)?;
+
+    if digits.iter().all(|b| b.is_ascii_hexdigit()) {
+        return Some(format!("0x{}", String::from_utf8_lossy(digits)));
+    }
+
+    None
+}
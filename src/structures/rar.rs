@@ -1,3 +1,4 @@
+use crate::common::crc32;
 use crate::structures::common::{self, StructureError};
 use std::collections::HashMap;
 
@@ -27,3 +28,475 @@ pub fn parse_rar_archive_header(rar_data: &[u8]) -> Result<RarArchiveHeader, Str
 
     Err(StructureError)
 }
+
+/// Stores info on a file entry in a RAR archive
+#[derive(Debug, Default, Clone)]
+pub struct RarFileEntry {
+    pub name: String,
+    /// Offset of the entry's packed data, relative to the start of the archive
+    pub data_offset: usize,
+    pub packed_size: usize,
+    pub unpacked_size: usize,
+    /// CRC32 of the unpacked data; optional in RARv5 archives
+    pub crc: Option<u32>,
+    pub directory: bool,
+    /// Data is stored without compression
+    pub stored: bool,
+    pub encrypted: bool,
+    /// Data is compressed using the dictionary of previous entries
+    pub solid: bool,
+    /// Data is split across multiple archive volumes
+    pub split: bool,
+    pub symlink: bool,
+    /// Symlink target; RARv4 archives store the symlink target as the entry data instead
+    pub link_target: Option<String>,
+    /// Hard links, file copies and other references to other entries
+    pub redirected: bool,
+}
+
+impl RarFileEntry {
+    /// Returns true if the entry can be extracted without decompression or decryption
+    pub fn is_stored(&self) -> bool {
+        self.stored
+            && !self.encrypted
+            && !self.solid
+            && !self.split
+            && !self.redirected
+            && self.packed_size == self.unpacked_size
+    }
+}
+
+/// Stores info on the contents of a RAR archive
+#[derive(Debug, Default, Clone)]
+pub struct RarArchive {
+    pub version: usize,
+    /// Size of the archive, up to and including the end of archive header
+    pub size: usize,
+    pub solid: bool,
+    /// Archive is one volume of a multi-volume archive
+    pub volume: bool,
+    pub entries: Vec<RarFileEntry>,
+}
+
+impl RarArchive {
+    /// Returns true if all entries in the archive can be extracted without decompression or decryption
+    pub fn is_stored(&self) -> bool {
+        !self.solid && !self.volume && self.entries.iter().all(|entry| entry.is_stored())
+    }
+}
+
+/// Parses all block headers in a RAR archive, up to the end of archive header.
+///
+/// Archives with encrypted headers can not be listed, and are reported as an error.
+pub fn parse_rar_archive(rar_data: &[u8]) -> Result<RarArchive, StructureError> {
+    let archive_header = parse_rar_archive_header(rar_data)?;
+
+    match archive_header.version {
+        4 => parse_rar4_archive(rar_data),
+        _ => parse_rar5_archive(rar_data),
+    }
+}
+
+/// Walks the blocks of a RARv4 (RAR 1.5 - 4.x) archive
+fn parse_rar4_archive(rar_data: &[u8]) -> Result<RarArchive, StructureError> {
+    const MARKER_BLOCK_SIZE: usize = 7;
+    const BLOCK_HEADER_SIZE: usize = 7;
+    const ADD_SIZE_LEN: usize = 4;
+    const FILE_HEADER_SIZE: usize = 32;
+    const LARGE_SIZE_LEN: usize = 8;
+
+    const MAIN_HEADER: usize = 0x73;
+    const FILE_HEADER: usize = 0x74;
+    const END_OF_ARCHIVE: usize = 0x7B;
+
+    // Block header flags
+    const LONG_BLOCK: usize = 0x8000;
+
+    // Main header flags
+    const MAIN_VOLUME: usize = 0x0001;
+    const MAIN_SOLID: usize = 0x0008;
+    const MAIN_ENCRYPTED_HEADERS: usize = 0x0080;
+
+    // File header flags
+    const FILE_SPLIT_BEFORE: usize = 0x0001;
+    const FILE_SPLIT_AFTER: usize = 0x0002;
+    const FILE_ENCRYPTED: usize = 0x0004;
+    const FILE_SOLID: usize = 0x0010;
+    const FILE_DIRECTORY_MASK: usize = 0x00E0;
+    const FILE_LARGE: usize = 0x0100;
+
+    const METHOD_STORE: usize = 0x30;
+    const HOST_OS_UNIX: usize = 3;
+    const S_IFMT: usize = 0o170000;
+    const S_IFLNK: usize = 0o120000;
+
+    let block_header_structure = vec![
+        ("header_crc", "u16"),
+        ("header_type", "u8"),
+        ("flags", "u16"),
+        ("header_size", "u16"),
+    ];
+
+    let file_header_structure = vec![
+        ("block_header_p1", "u32"),
+        ("block_header_p2", "u24"),
+        ("packed_size", "u32"),
+        ("unpacked_size", "u32"),
+        ("host_os", "u8"),
+        ("file_crc", "u32"),
+        ("timestamp", "u32"),
+        ("unpack_version", "u8"),
+        ("method", "u8"),
+        ("name_size", "u16"),
+        ("attributes", "u32"),
+    ];
+
+    let large_size_structure = vec![("packed_size_high", "u32"), ("unpacked_size_high", "u32")];
+
+    let mut archive = RarArchive {
+        version: 4,
+        ..Default::default()
+    };
+
+    let mut block_offset: usize = MARKER_BLOCK_SIZE;
+
+    while let Some(block_data) = rar_data.get(block_offset..) {
+        let block_header = common::parse(block_data, &block_header_structure, "little")?;
+
+        let header_size = block_header["header_size"];
+        if header_size < BLOCK_HEADER_SIZE {
+            return Err(StructureError);
+        }
+
+        // The header CRC is the low 16 bits of the CRC32 of the header, starting at the header type field
+        let header_bytes = block_data.get(2..header_size).ok_or(StructureError)?;
+        if (crc32(header_bytes) as usize & 0xFFFF) != block_header["header_crc"] {
+            return Err(StructureError);
+        }
+
+        // Long blocks are followed by additional data, the size of which immediately follows the block header
+        let mut data_size: usize = 0;
+        if (block_header["flags"] & LONG_BLOCK) != 0 {
+            let add_size = block_data
+                .get(BLOCK_HEADER_SIZE..BLOCK_HEADER_SIZE + ADD_SIZE_LEN)
+                .ok_or(StructureError)?;
+            data_size = u32::from_le_bytes(add_size.try_into().unwrap()) as usize;
+        }
+
+        match block_header["header_type"] {
+            MAIN_HEADER => {
+                if (block_header["flags"] & MAIN_ENCRYPTED_HEADERS) != 0 {
+                    return Err(StructureError);
+                }
+                archive.solid = (block_header["flags"] & MAIN_SOLID) != 0;
+                archive.volume = (block_header["flags"] & MAIN_VOLUME) != 0;
+            }
+            FILE_HEADER => {
+                // Only the file header itself is parsed; the file name and optional fields follow
+                let file_header_data = block_data.get(..header_size).ok_or(StructureError)?;
+                let file_header =
+                    common::parse(file_header_data, &file_header_structure, "little")?;
+                let flags = block_header["flags"];
+
+                let mut packed_size = file_header["packed_size"];
+                let mut unpacked_size = file_header["unpacked_size"];
+                let mut name_offset = FILE_HEADER_SIZE;
+
+                if (flags & FILE_LARGE) != 0 {
+                    let large_size = common::parse(
+                        file_header_data
+                            .get(FILE_HEADER_SIZE..)
+                            .ok_or(StructureError)?,
+                        &large_size_structure,
+                        "little",
+                    )?;
+                    packed_size |= large_size["packed_size_high"] << 32;
+                    unpacked_size |= large_size["unpacked_size_high"] << 32;
+                    name_offset += LARGE_SIZE_LEN;
+                }
+
+                let name_bytes = file_header_data
+                    .get(name_offset..name_offset + file_header["name_size"])
+                    .ok_or(StructureError)?;
+
+                let symlink = file_header["host_os"] == HOST_OS_UNIX
+                    && (file_header["attributes"] & S_IFMT) == S_IFLNK;
+
+                archive.entries.push(RarFileEntry {
+                    name: rar4_file_name(name_bytes),
+                    data_offset: block_offset + header_size,
+                    packed_size,
+                    unpacked_size,
+                    crc: Some(file_header["file_crc"] as u32),
+                    directory: (flags & FILE_DIRECTORY_MASK) == FILE_DIRECTORY_MASK,
+                    stored: file_header["method"] == METHOD_STORE,
+                    encrypted: (flags & FILE_ENCRYPTED) != 0,
+                    solid: (flags & FILE_SOLID) != 0,
+                    split: (flags & (FILE_SPLIT_BEFORE | FILE_SPLIT_AFTER)) != 0,
+                    symlink,
+                    ..Default::default()
+                });
+
+                data_size = packed_size;
+            }
+            END_OF_ARCHIVE => {
+                archive.size = block_offset + header_size;
+                return Ok(archive);
+            }
+            _ => (),
+        }
+
+        block_offset = block_offset
+            .checked_add(header_size)
+            .and_then(|offset| offset.checked_add(data_size))
+            .ok_or(StructureError)?;
+    }
+
+    Err(StructureError)
+}
+
+/// RARv4 unicode file names are stored as an ASCII name, followed by a NULL byte and the encoded unicode name;
+/// only the ASCII name is used. Names without a NULL byte are UTF-8.
+fn rar4_file_name(name_bytes: &[u8]) -> String {
+    let ascii_name = match name_bytes.iter().position(|byte| *byte == 0) {
+        None => name_bytes,
+        Some(null_index) => &name_bytes[..null_index],
+    };
+
+    String::from_utf8_lossy(ascii_name).to_string()
+}
+
+/// Walks the headers of a RARv5 archive
+fn parse_rar5_archive(rar_data: &[u8]) -> Result<RarArchive, StructureError> {
+    const SIGNATURE_SIZE: usize = 8;
+    const CRC_SIZE: usize = 4;
+    // Headers larger than 2MB are not allowed by the specification
+    const MAX_HEADER_SIZE: usize = 2 * 1024 * 1024;
+
+    const MAIN_HEADER: usize = 1;
+    const FILE_HEADER: usize = 2;
+    const ENCRYPTION_HEADER: usize = 4;
+    const END_OF_ARCHIVE: usize = 5;
+
+    // Common header flags
+    const EXTRA_AREA_PRESENT: usize = 0x0001;
+    const DATA_AREA_PRESENT: usize = 0x0002;
+    const SPLIT_BEFORE: usize = 0x0008;
+    const SPLIT_AFTER: usize = 0x0010;
+
+    // Main header flags
+    const MAIN_VOLUME: usize = 0x0001;
+    const MAIN_SOLID: usize = 0x0004;
+
+    let mut archive = RarArchive {
+        version: 5,
+        ..Default::default()
+    };
+
+    let mut header_offset: usize = SIGNATURE_SIZE;
+
+    while let Some(header_data) = rar_data.get(header_offset..) {
+        let crc_bytes = header_data.get(..CRC_SIZE).ok_or(StructureError)?;
+        let header_crc = u32::from_le_bytes(crc_bytes.try_into().unwrap());
+
+        // Header size does not include the CRC or the header size field itself
+        let mut reader = Rar5Reader::new(header_data, CRC_SIZE);
+        let header_size = reader.vint()?;
+        if header_size == 0 || header_size > MAX_HEADER_SIZE {
+            return Err(StructureError);
+        }
+
+        let header_end = reader.offset + header_size;
+
+        // The header CRC covers the header size field and the header
+        let crc_data = header_data
+            .get(CRC_SIZE..header_end)
+            .ok_or(StructureError)?;
+        if crc32(crc_data) != header_crc {
+            return Err(StructureError);
+        }
+
+        let mut reader = Rar5Reader::new(&header_data[..header_end], reader.offset);
+        let header_type = reader.vint()?;
+        let header_flags = reader.vint()?;
+
+        let mut extra_area_size: usize = 0;
+        if (header_flags & EXTRA_AREA_PRESENT) != 0 {
+            extra_area_size = reader.vint()?;
+        }
+
+        let mut data_size: usize = 0;
+        if (header_flags & DATA_AREA_PRESENT) != 0 {
+            data_size = reader.vint()?;
+        }
+
+        // The extra area is at the end of the header
+        let extra_area_start = header_end
+            .checked_sub(extra_area_size)
+            .ok_or(StructureError)?;
+        if extra_area_start < reader.offset {
+            return Err(StructureError);
+        }
+
+        match header_type {
+            MAIN_HEADER => {
+                let archive_flags = reader.vint()?;
+                archive.solid = (archive_flags & MAIN_SOLID) != 0;
+                archive.volume = (archive_flags & MAIN_VOLUME) != 0;
+            }
+            FILE_HEADER => {
+                let mut entry = parse_rar5_file_header(
+                    &mut reader,
+                    &header_data[extra_area_start..header_end],
+                )?;
+
+                entry.data_offset = header_offset + header_end;
+                entry.packed_size = data_size;
+                entry.split = (header_flags & (SPLIT_BEFORE | SPLIT_AFTER)) != 0;
+
+                archive.entries.push(entry);
+            }
+            ENCRYPTION_HEADER => {
+                // All following headers are encrypted
+                return Err(StructureError);
+            }
+            END_OF_ARCHIVE => {
+                archive.size = header_offset + header_end;
+                return Ok(archive);
+            }
+            _ => (),
+        }
+
+        header_offset = header_offset
+            .checked_add(header_end)
+            .and_then(|offset| offset.checked_add(data_size))
+            .ok_or(StructureError)?;
+    }
+
+    Err(StructureError)
+}
+
+/// Parses the type specific fields and extra area of a RARv5 file header
+fn parse_rar5_file_header(
+    reader: &mut Rar5Reader,
+    extra_area: &[u8],
+) -> Result<RarFileEntry, StructureError> {
+    // File flags
+    const DIRECTORY: usize = 0x0001;
+    const TIME_PRESENT: usize = 0x0002;
+    const CRC_PRESENT: usize = 0x0004;
+
+    // Compression info fields
+    const SOLID: usize = 0x0040;
+    const METHOD_SHIFT: usize = 7;
+    const METHOD_MASK: usize = 0x07;
+    const METHOD_STORE: usize = 0;
+
+    // Extra record types
+    const ENCRYPTION_RECORD: usize = 0x01;
+    const REDIRECTION_RECORD: usize = 0x05;
+
+    // Redirection types
+    const UNIX_SYMLINK: usize = 1;
+    const WINDOWS_SYMLINK: usize = 2;
+
+    let file_flags = reader.vint()?;
+    let unpacked_size = reader.vint()?;
+    let _attributes = reader.vint()?;
+
+    if (file_flags & TIME_PRESENT) != 0 {
+        reader.bytes(4)?;
+    }
+
+    let mut crc: Option<u32> = None;
+    if (file_flags & CRC_PRESENT) != 0 {
+        crc = Some(u32::from_le_bytes(reader.bytes(4)?.try_into().unwrap()));
+    }
+
+    let compression_info = reader.vint()?;
+    let _host_os = reader.vint()?;
+    let name_size = reader.vint()?;
+    let name = String::from_utf8_lossy(reader.bytes(name_size)?).to_string();
+
+    let mut entry = RarFileEntry {
+        name,
+        unpacked_size,
+        crc,
+        directory: (file_flags & DIRECTORY) != 0,
+        stored: ((compression_info >> METHOD_SHIFT) & METHOD_MASK) == METHOD_STORE,
+        solid: (compression_info & SOLID) != 0,
+        ..Default::default()
+    };
+
+    // Each extra record is its size, followed by the record type and record data
+    let mut extra_reader = Rar5Reader::new(extra_area, 0);
+
+    while extra_reader.offset < extra_area.len() {
+        let record_size = extra_reader.vint()?;
+        let record_data = extra_reader.bytes(record_size)?;
+
+        let mut record_reader = Rar5Reader::new(record_data, 0);
+
+        match record_reader.vint()? {
+            ENCRYPTION_RECORD => entry.encrypted = true,
+            REDIRECTION_RECORD => {
+                let redirection_type = record_reader.vint()?;
+                let _redirection_flags = record_reader.vint()?;
+                let target_size = record_reader.vint()?;
+                let target = String::from_utf8_lossy(record_reader.bytes(target_size)?).to_string();
+
+                if redirection_type == UNIX_SYMLINK || redirection_type == WINDOWS_SYMLINK {
+                    entry.symlink = true;
+                    entry.link_target = Some(target);
+                } else {
+                    entry.redirected = true;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(entry)
+}
+
+/// Reads the variable length integers and raw fields that make up RARv5 headers
+struct Rar5Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Rar5Reader<'a> {
+    fn new(data: &'a [u8], offset: usize) -> Rar5Reader<'a> {
+        Rar5Reader { data, offset }
+    }
+
+    /// Reads a variable length integer; each byte holds 7 bits of the value, least significant first,
+    /// and the high bit is set in all but the last byte
+    fn vint(&mut self) -> Result<usize, StructureError> {
+        const MAX_VINT_SIZE: usize = 10;
+        const CONTINUE_BIT: u8 = 0x80;
+        const VALUE_MASK: u8 = 0x7F;
+
+        let mut value: u64 = 0;
+
+        for i in 0..MAX_VINT_SIZE {
+            let byte = *self.data.get(self.offset).ok_or(StructureError)?;
+            self.offset += 1;
+
+            value |= ((byte & VALUE_MASK) as u64) << (7 * i);
+
+            if (byte & CONTINUE_BIT) == 0 {
+                return Ok(value as usize);
+            }
+        }
+
+        Err(StructureError)
+    }
+
+    fn bytes(&mut self, size: usize) -> Result<&'a [u8], StructureError> {
+        let end = self.offset.checked_add(size).ok_or(StructureError)?;
+        let bytes = self.data.get(self.offset..end).ok_or(StructureError)?;
+        self.offset = end;
+        Ok(bytes)
+    }
+}
@@ -15,6 +15,9 @@ pub fn parse_riff_header(riff_data: &[u8]) -> Result<RIFFHeader, StructureError>
 
     const FILE_SIZE_OFFSET: usize = 8;
 
+    // The file size must include at least the chunk type field
+    const MIN_FILE_SIZE: usize = 4;
+
     let riff_structure = vec![
         ("magic", "u32"),
         ("file_size", "u32"),
@@ -24,11 +27,14 @@ pub fn parse_riff_header(riff_data: &[u8]) -> Result<RIFFHeader, StructureError>
     // Parse the riff header
     if let Ok(riff_header) = common::parse(riff_data, &riff_structure, "little") {
         // Sanity check expected magic bytes
-        if riff_header["magic"] == MAGIC {
-            // Get the RIFF type string (e.g., "WAVE")
-            if let Ok(type_string) =
-                String::from_utf8(riff_data[CHUNK_TYPE_START..CHUNK_TYPE_END].to_vec())
+        if riff_header["magic"] == MAGIC && riff_header["file_size"] >= MIN_FILE_SIZE {
+            // Get the RIFF type string (e.g., "WAVE"); these are always alphanumeric ASCII, possibly padded with spaces
+            let chunk_type = &riff_data[CHUNK_TYPE_START..CHUNK_TYPE_END];
+            if chunk_type
+                .iter()
+                .all(|b| b.is_ascii_alphanumeric() || *b == b' ')
             {
+                let type_string = String::from_utf8_lossy(chunk_type);
                 return Ok(RIFFHeader {
                     size: riff_header["file_size"] + FILE_SIZE_OFFSET,
                     chunk_type: type_string.trim().to_string(),
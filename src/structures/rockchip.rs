@@ -0,0 +1,355 @@
+use crate::common::get_cstring;
+use crate::structures::common::{self, StructureError};
+
+/// Size of RKFW and BOOT headers
+const RK_HEADER_SIZE: usize = 0x66;
+
+/// Stores info about a Rockchip RKFW firmware header, as found at the start of update.img files
+#[derive(Debug, Default, Clone)]
+pub struct RKFWHeader {
+    pub version: String,
+    pub release_date: String,
+    pub chip: usize,
+    /// Offset of the boot loader, relative to the start of the header
+    pub loader_offset: usize,
+    pub loader_size: usize,
+    /// Offset of the RKAF firmware package, relative to the start of the header
+    pub image_offset: usize,
+    pub image_size: usize,
+}
+
+impl RKFWHeader {
+    /// Size of the header, boot loader and firmware package; does not include the trailing MD5 checksum, if any
+    pub fn total_size(&self) -> usize {
+        std::cmp::max(
+            self.loader_offset + self.loader_size,
+            self.image_offset + self.image_size,
+        )
+    }
+}
+
+/// Parse a Rockchip RKFW firmware header
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::rockchip::parse_rkfw_header;
+///
+/// let mut header = b"RKFW\x66\x00\x00\x00\x00\x08\x00\x00\x00\x00\xE8\x07\x05\x11\x0C\x00\x00\x41\x00\x00\x00\
+///                    \x66\x00\x00\x00\x00\x10\x00\x00\x66\x10\x00\x00\x00\x00\x01\x00".to_vec();
+/// header.resize(0x66, 0);
+///
+/// let rkfw_header = parse_rkfw_header(&header).unwrap();
+///
+/// assert_eq!(rkfw_header.version, "8.0.0");
+/// assert_eq!(rkfw_header.release_date, "2024-05-17 12:00:00");
+/// assert_eq!(rkfw_header.chip, 0x41);
+/// assert_eq!(rkfw_header.loader_offset, 0x66);
+/// assert_eq!(rkfw_header.image_offset, 0x1066);
+/// assert_eq!(rkfw_header.total_size(), 0x11066);
+/// ```
+pub fn parse_rkfw_header(header_data: &[u8]) -> Result<RKFWHeader, StructureError> {
+    const MAGIC: usize = 0x57464B52;
+
+    let header_structure = vec![
+        ("magic", "u32"),
+        ("header_size", "u16"),
+        ("version", "u32"),
+        ("code", "u32"),
+        ("year", "u16"),
+        ("month", "u8"),
+        ("day", "u8"),
+        ("hour", "u8"),
+        ("minute", "u8"),
+        ("second", "u8"),
+        ("chip", "u32"),
+        ("loader_offset", "u32"),
+        ("loader_size", "u32"),
+        ("image_offset", "u32"),
+        ("image_size", "u32"),
+    ];
+
+    let header = common::parse(header_data, &header_structure, "little")?;
+
+    // The boot loader and firmware package must both follow the header, and must not overlap
+    if header["magic"] != MAGIC
+        || header["header_size"] != RK_HEADER_SIZE
+        || header["loader_size"] == 0
+        || header["image_size"] == 0
+        || header["loader_offset"] < RK_HEADER_SIZE
+        || header["image_offset"] < RK_HEADER_SIZE
+        || (header["loader_offset"] < header["image_offset"] + header["image_size"]
+            && header["image_offset"] < header["loader_offset"] + header["loader_size"])
+    {
+        return Err(StructureError);
+    }
+
+    Ok(RKFWHeader {
+        version: rk_version(header["version"]),
+        release_date: rk_date(&header),
+        chip: header["chip"],
+        loader_offset: header["loader_offset"],
+        loader_size: header["loader_size"],
+        image_offset: header["image_offset"],
+        image_size: header["image_size"],
+    })
+}
+
+/// Stores info about a file in a Rockchip boot loader
+#[derive(Debug, Default, Clone)]
+pub struct RKBootEntry {
+    /// Entry name, e.g., "rk3399_ddr_800MHz", "FlashBoot"
+    pub name: String,
+    /// Offset of the entry data, relative to the start of the boot loader header
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// Stores info about a Rockchip boot loader header, as created by boot_merger
+#[derive(Debug, Default, Clone)]
+pub struct RKBootHeader {
+    pub version: String,
+    pub release_date: String,
+    pub chip: usize,
+    /// DRAM initialization code, USB download code, and boot loader entries
+    pub entries: Vec<RKBootEntry>,
+    /// Size of the header and all entries, including the trailing CRC
+    pub total_size: usize,
+}
+
+/// Parse a Rockchip boot loader header
+pub fn parse_rkboot_header(header_data: &[u8]) -> Result<RKBootHeader, StructureError> {
+    const MAGIC: usize = 0x544F4F42;
+    const ENTRY_SIZE: usize = 0x39;
+    const NAME_SIZE: usize = 40;
+    const CRC_SIZE: usize = 4;
+
+    let header_structure = vec![
+        ("magic", "u32"),
+        ("header_size", "u16"),
+        ("version", "u32"),
+        ("merger_version", "u32"),
+        ("year", "u16"),
+        ("month", "u8"),
+        ("day", "u8"),
+        ("hour", "u8"),
+        ("minute", "u8"),
+        ("second", "u8"),
+        ("chip", "u32"),
+        ("code471_count", "u8"),
+        ("code471_offset", "u32"),
+        ("code471_size", "u8"),
+        ("code472_count", "u8"),
+        ("code472_offset", "u32"),
+        ("code472_size", "u8"),
+        ("loader_count", "u8"),
+        ("loader_offset", "u32"),
+        ("loader_size", "u8"),
+    ];
+
+    let entry_structure = vec![("size", "u8"), ("type", "u32")];
+    let entry_data_structure = vec![("offset", "u32"), ("size", "u32"), ("delay", "u32")];
+
+    let header = common::parse(header_data, &header_structure, "little")?;
+
+    if header["magic"] != MAGIC || header["header_size"] != RK_HEADER_SIZE {
+        return Err(StructureError);
+    }
+
+    let mut boot_header = RKBootHeader {
+        version: rk_version(header["version"]),
+        release_date: rk_date(&header),
+        chip: header["chip"],
+        ..Default::default()
+    };
+
+    for entry_list in ["code471", "code472", "loader"] {
+        let entry_count = header[&format!("{entry_list}_count")];
+        let entry_list_offset = header[&format!("{entry_list}_offset")];
+
+        if entry_count > 0
+            && (header[&format!("{entry_list}_size")] != ENTRY_SIZE
+                || entry_list_offset < RK_HEADER_SIZE)
+        {
+            return Err(StructureError);
+        }
+
+        for i in 0..entry_count {
+            let entry_offset = entry_list_offset + (i * ENTRY_SIZE);
+            let entry_data = header_data.get(entry_offset..).ok_or(StructureError)?;
+            let entry = common::parse(entry_data, &entry_structure, "little")?;
+
+            if entry["size"] != ENTRY_SIZE {
+                return Err(StructureError);
+            }
+
+            let name_start = common::size(&entry_structure);
+            let name_end = name_start + NAME_SIZE;
+            let entry_info = common::parse(
+                entry_data.get(name_end..).ok_or(StructureError)?,
+                &entry_data_structure,
+                "little",
+            )?;
+
+            if entry_info["size"] == 0 || entry_info["offset"] < entry_list_offset {
+                return Err(StructureError);
+            }
+
+            boot_header.entries.push(RKBootEntry {
+                name: utf16_name(&entry_data[name_start..name_end]),
+                offset: entry_info["offset"],
+                size: entry_info["size"],
+            });
+        }
+    }
+
+    boot_header.total_size = boot_header
+        .entries
+        .iter()
+        .map(|entry| entry.offset + entry.size)
+        .max()
+        .ok_or(StructureError)?
+        + CRC_SIZE;
+
+    Ok(boot_header)
+}
+
+/// Stores info about a file in a Rockchip RKAF firmware package
+#[derive(Debug, Default, Clone)]
+pub struct RKAFPartition {
+    /// Partition name, e.g., "boot", "system"
+    pub name: String,
+    /// Path to the partition image in the firmware package source directory, e.g., "Image/boot.img"
+    pub file_name: String,
+    /// Offset of the partition image, relative to the start of the package
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// Stores info about a Rockchip RKAF firmware package
+#[derive(Debug, Default, Clone)]
+pub struct RKAFHeader {
+    pub model: String,
+    pub manufacturer: String,
+    pub version: String,
+    pub partitions: Vec<RKAFPartition>,
+    /// Size of the package, including the trailing CRC
+    pub total_size: usize,
+}
+
+/// Parse a Rockchip RKAF firmware package header, as created by afptool
+pub fn parse_rkaf_header(header_data: &[u8]) -> Result<RKAFHeader, StructureError> {
+    const MAGIC: usize = 0x46414B52;
+    const MODEL_START: usize = 8;
+    const MODEL_END: usize = 0x2A;
+    const MANUFACTURER_START: usize = 0x48;
+    const MANUFACTURER_END: usize = 0x80;
+    const INFO_START: usize = 0x80;
+    const PARTITIONS_START: usize = 0x8C;
+    const PARTITION_SIZE: usize = 112;
+    const PARTITION_NAME_SIZE: usize = 32;
+    const PARTITION_FILE_NAME_SIZE: usize = 60;
+    const MAX_PARTITIONS: usize = 16;
+    const CRC_SIZE: usize = 4;
+
+    let header_structure = vec![("magic", "u32"), ("length", "u32")];
+    let info_structure = vec![
+        ("unknown", "u32"),
+        ("version", "u32"),
+        ("partition_count", "u32"),
+    ];
+    let partition_structure = vec![
+        ("nand_size", "u32"),
+        ("offset", "u32"),
+        ("nand_address", "u32"),
+        ("padded_size", "u32"),
+        ("size", "u32"),
+    ];
+
+    let header = common::parse(header_data, &header_structure, "little")?;
+    let info = common::parse(
+        header_data.get(INFO_START..).ok_or(StructureError)?,
+        &info_structure,
+        "little",
+    )?;
+
+    // The length field does not include the trailing CRC
+    if header["magic"] != MAGIC
+        || info["partition_count"] == 0
+        || info["partition_count"] > MAX_PARTITIONS
+        || header["length"] < PARTITIONS_START + (MAX_PARTITIONS * PARTITION_SIZE)
+    {
+        return Err(StructureError);
+    }
+
+    let mut rkaf_header = RKAFHeader {
+        model: get_cstring(&header_data[MODEL_START..MODEL_END]),
+        manufacturer: get_cstring(&header_data[MANUFACTURER_START..MANUFACTURER_END]),
+        version: rk_version(info["version"]),
+        total_size: header["length"] + CRC_SIZE,
+        ..Default::default()
+    };
+
+    for i in 0..info["partition_count"] {
+        let partition_start = PARTITIONS_START + (i * PARTITION_SIZE);
+        let partition_data = header_data
+            .get(partition_start..partition_start + PARTITION_SIZE)
+            .ok_or(StructureError)?;
+
+        let file_name_start = PARTITION_NAME_SIZE;
+        let file_name_end = file_name_start + PARTITION_FILE_NAME_SIZE;
+        let partition = common::parse(
+            &partition_data[file_name_end..],
+            &partition_structure,
+            "little",
+        )?;
+
+        // Partition images must be inside the package
+        if partition["size"] > 0 && partition["offset"] + partition["size"] > header["length"] {
+            return Err(StructureError);
+        }
+
+        rkaf_header.partitions.push(RKAFPartition {
+            name: get_cstring(&partition_data[..PARTITION_NAME_SIZE]),
+            file_name: get_cstring(&partition_data[file_name_start..file_name_end]),
+            offset: partition["offset"],
+            size: partition["size"],
+        });
+    }
+
+    Ok(rkaf_header)
+}
+
+/// Formats Rockchip version numbers as "major.minor.patch"
+fn rk_version(version: usize) -> String {
+    format!(
+        "{}.{}.{}",
+        (version >> 24) & 0xFF,
+        (version >> 16) & 0xFF,
+        version & 0xFFFF
+    )
+}
+
+/// Formats Rockchip release times as "YYYY-MM-DD hh:mm:ss"
+fn rk_date(header: &std::collections::HashMap<String, usize>) -> String {
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}:{:02}",
+        header["year"],
+        header["month"],
+        header["day"],
+        header["hour"],
+        header["minute"],
+        header["second"]
+    )
+}
+
+/// Decodes a NULL terminated UTF-16LE name
+fn utf16_name(name_data: &[u8]) -> String {
+    let characters: Vec<u16> = name_data
+        .chunks_exact(2)
+        .map(|character| u16::from_le_bytes([character[0], character[1]]))
+        .take_while(|character| *character != 0)
+        .collect();
+
+    String::from_utf16_lossy(&characters)
+}
@@ -0,0 +1,316 @@
+use crate::common::is_text;
+use crate::structures::common::{self, StructureError};
+
+/// Stores info about a Lua bytecode header
+#[derive(Debug, Default, Clone)]
+pub struct LuaBytecodeHeader {
+    pub version: String,
+    pub endianness: String,
+    pub integer_size: usize,
+    pub number_size: usize,
+}
+
+/// Parses a precompiled Lua (luac) bytecode header
+pub fn parse_lua_bytecode_header(lua_data: &[u8]) -> Result<LuaBytecodeHeader, StructureError> {
+    const LUA_MAGIC: &[u8] = b"\x1BLua";
+    const OFFICIAL_FORMAT: usize = 0;
+    const INSTRUCTION_SIZE: usize = 4;
+    const VALID_INT_SIZES: [usize; 2] = [4, 8];
+    // Used to detect conversion errors, present in Lua 5.2 and later
+    const LUAC_DATA: &[u8] = b"\x19\x93\r\n\x1A\n";
+
+    let lua_structure = vec![("magic", "u32"), ("version", "u8"), ("format", "u8")];
+
+    let lua_header = common::parse(lua_data, &lua_structure, "big")?;
+    let header_size = common::size(&lua_structure);
+
+    if !lua_data.starts_with(LUA_MAGIC) || lua_header["format"] != OFFICIAL_FORMAT {
+        return Err(StructureError);
+    }
+
+    let mut header = LuaBytecodeHeader {
+        version: format!(
+            "{}.{}",
+            lua_header["version"] >> 4,
+            lua_header["version"] & 0x0F
+        ),
+        ..Default::default()
+    };
+
+    match lua_header["version"] {
+        // Lua 5.1 and 5.2 report endianness and type sizes immediately after the header
+        0x51 | 0x52 => {
+            let sizes_structure = vec![
+                ("endianness", "u8"),
+                ("int_size", "u8"),
+                ("size_t_size", "u8"),
+                ("instruction_size", "u8"),
+                ("number_size", "u8"),
+                ("integral", "u8"),
+            ];
+
+            let sizes_data = lua_data.get(header_size..).ok_or(StructureError)?;
+            let sizes = common::parse(sizes_data, &sizes_structure, "big")?;
+
+            header.endianness = match sizes["endianness"] {
+                0 => "big".to_string(),
+                1 => "little".to_string(),
+                _ => return Err(StructureError),
+            };
+
+            if !VALID_INT_SIZES.contains(&sizes["int_size"])
+                || !VALID_INT_SIZES.contains(&sizes["size_t_size"])
+                || !VALID_INT_SIZES.contains(&sizes["number_size"])
+                || sizes["instruction_size"] != INSTRUCTION_SIZE
+                || sizes["integral"] > 1
+            {
+                return Err(StructureError);
+            }
+
+            // Lua 5.2 headers end with the LUAC_DATA bytes
+            if lua_header["version"] == 0x52 {
+                let tail_offset = header_size + common::size(&sizes_structure);
+                let tail_data = lua_data.get(tail_offset..).ok_or(StructureError)?;
+
+                if !tail_data.starts_with(LUAC_DATA) {
+                    return Err(StructureError);
+                }
+            }
+
+            header.integer_size = sizes["int_size"];
+            header.number_size = sizes["number_size"];
+        }
+        // Lua 5.3 and 5.4 place the LUAC_DATA bytes immediately after the header, followed by type sizes
+        0x53 | 0x54 => {
+            let sizes_data = lua_data
+                .get(header_size..)
+                .and_then(|data| data.strip_prefix(LUAC_DATA))
+                .ok_or(StructureError)?;
+
+            let sizes_structure = match lua_header["version"] {
+                0x53 => vec![
+                    ("int_size", "u8"),
+                    ("size_t_size", "u8"),
+                    ("instruction_size", "u8"),
+                    ("integer_size", "u8"),
+                    ("number_size", "u8"),
+                ],
+                _ => vec![
+                    ("instruction_size", "u8"),
+                    ("integer_size", "u8"),
+                    ("number_size", "u8"),
+                ],
+            };
+
+            let sizes = common::parse(sizes_data, &sizes_structure, "big")?;
+
+            if sizes["instruction_size"] != INSTRUCTION_SIZE
+                || !VALID_INT_SIZES.contains(&sizes["integer_size"])
+                || !VALID_INT_SIZES.contains(&sizes["number_size"])
+            {
+                return Err(StructureError);
+            }
+
+            // Endianness is inferred from a test integer (0x5678) that follows the type sizes
+            let test_integer_offset = common::size(&sizes_structure);
+            let test_integer_structure = vec![("value", "u16")];
+            let test_integer_data = sizes_data
+                .get(test_integer_offset..)
+                .ok_or(StructureError)?;
+
+            header.endianness =
+                match common::parse(test_integer_data, &test_integer_structure, "little")?["value"]
+                {
+                    0x5678 => "little".to_string(),
+                    _ => "big".to_string(),
+                };
+
+            header.integer_size = sizes["integer_size"];
+            header.number_size = sizes["number_size"];
+        }
+        _ => return Err(StructureError),
+    }
+
+    Ok(header)
+}
+
+/// Stores info about a compiled Python (.pyc) file header
+#[derive(Debug, Default, Clone)]
+pub struct PycHeader {
+    pub version: String,
+    /// True if the pyc file is validated by a source hash rather than a timestamp
+    pub hash_based: bool,
+}
+
+/// Python bytecode magic numbers, and the Python version that they correspond to
+const PYC_VERSIONS: [(usize, usize, usize, &str); 17] = [
+    // (first magic number, last magic number, header size, Python version)
+    (62071, 62131, 8, "2.5"),
+    (62151, 62161, 8, "2.6"),
+    (62171, 62211, 8, "2.7"),
+    (3000, 3131, 8, "3.0"),
+    (3141, 3151, 8, "3.1"),
+    (3160, 3180, 8, "3.2"),
+    (3190, 3230, 12, "3.3"),
+    (3250, 3310, 12, "3.4"),
+    (3320, 3351, 12, "3.5"),
+    (3360, 3379, 12, "3.6"),
+    (3390, 3394, 16, "3.7"),
+    (3400, 3413, 16, "3.8"),
+    (3420, 3425, 16, "3.9"),
+    (3430, 3439, 16, "3.10"),
+    (3450, 3495, 16, "3.11"),
+    (3500, 3531, 16, "3.12"),
+    (3550, 3571, 16, "3.13"),
+];
+
+/// Returns the magic number used by the final release of each supported Python version
+pub fn pyc_release_magic_numbers() -> Vec<u16> {
+    PYC_VERSIONS
+        .iter()
+        .map(|(_, last_magic, _, _)| *last_magic as u16)
+        .collect()
+}
+
+/// Parses a compiled Python (.pyc) file header
+pub fn parse_pyc_header(pyc_data: &[u8]) -> Result<PycHeader, StructureError> {
+    const MAGIC_SUFFIX: usize = 0x0A0D;
+    const HASH_BASED_FLAG: usize = 1;
+    const MAX_FLAGS: usize = 3;
+    // Marshalled code object type, with or without the FLAG_REF bit set
+    const CODE_OBJECT_TYPES: [u8; 2] = [b'c', b'c' | 0x80];
+
+    let pyc_structure = vec![("magic", "u16"), ("magic_suffix", "u16"), ("flags", "u32")];
+
+    let pyc_header = common::parse(pyc_data, &pyc_structure, "little")?;
+
+    if pyc_header["magic_suffix"] != MAGIC_SUFFIX {
+        return Err(StructureError);
+    }
+
+    let (_, _, header_size, version) = PYC_VERSIONS
+        .iter()
+        .find(|(first_magic, last_magic, _, _)| {
+            (*first_magic..=*last_magic).contains(&pyc_header["magic"])
+        })
+        .ok_or(StructureError)?;
+
+    // Python 3.7 and later have a flags field; only the lower two bits are defined
+    let mut hash_based = false;
+    if *header_size == 16 {
+        if pyc_header["flags"] > MAX_FLAGS {
+            return Err(StructureError);
+        }
+        hash_based = (pyc_header["flags"] & HASH_BASED_FLAG) != 0;
+    }
+
+    // The header is followed by a marshalled code object
+    match pyc_data.get(*header_size) {
+        Some(object_type) if CODE_OBJECT_TYPES.contains(object_type) => Ok(PycHeader {
+            version: version.to_string(),
+            hash_based,
+        }),
+        _ => Err(StructureError),
+    }
+}
+
+/// Stores info about a script's shebang line
+#[derive(Debug, Default, Clone)]
+pub struct ShebangScript {
+    /// Full path to the interpreter
+    pub interpreter: String,
+    /// Name of the interpreter, e.g., "python3"; if the interpreter is invoked via env, this is the name of the program env runs
+    pub interpreter_name: String,
+    pub language: String,
+    /// Interpreter version, if specified in the interpreter name (e.g., "3.8" for "python3.8")
+    pub version: String,
+    /// Total size of the script text
+    pub size: usize,
+}
+
+/// Parses a script's shebang ("#!") line, and determines the size of the script text
+pub fn parse_shebang_script(script_data: &[u8]) -> Result<ShebangScript, StructureError> {
+    const SHEBANG: &[u8] = b"#!";
+    const MAX_SHEBANG_LINE_LENGTH: usize = 256;
+    const ENV_PROGRAM: &str = "env";
+
+    // Known interpreter names and the languages they correspond to
+    const INTERPRETERS: [(&str, &str); 16] = [
+        ("sh", "Shell"),
+        ("ash", "Shell"),
+        ("bash", "Shell"),
+        ("dash", "Shell"),
+        ("ksh", "Shell"),
+        ("zsh", "Shell"),
+        ("busybox", "Shell"),
+        ("python", "Python"),
+        ("lua", "Lua"),
+        ("luajit", "Lua"),
+        ("perl", "Perl"),
+        ("php", "PHP"),
+        ("ruby", "Ruby"),
+        ("node", "JavaScript"),
+        ("tclsh", "Tcl"),
+        ("awk", "AWK"),
+    ];
+
+    let line_data = script_data.strip_prefix(SHEBANG).ok_or(StructureError)?;
+
+    let line_length = line_data
+        .iter()
+        .take(MAX_SHEBANG_LINE_LENGTH)
+        .position(|b| *b == b'\n')
+        .ok_or(StructureError)?;
+
+    let line_bytes = &line_data[..line_length];
+
+    if !line_bytes
+        .iter()
+        .all(|b| b.is_ascii_graphic() || *b == b' ' || *b == b'\t' || *b == b'\r')
+    {
+        return Err(StructureError);
+    }
+
+    let line = String::from_utf8_lossy(line_bytes).to_string();
+    let mut words = line.split_whitespace();
+
+    // The interpreter must be specified as an absolute path
+    let interpreter = words.next().ok_or(StructureError)?;
+    if !interpreter.starts_with('/') {
+        return Err(StructureError);
+    }
+
+    let mut interpreter_name = interpreter.rsplit('/').next().unwrap_or_default();
+
+    // Scripts invoked via env specify the interpreter name as an argument to env; skip any env options
+    if interpreter_name == ENV_PROGRAM {
+        interpreter_name = words
+            .find(|word| !word.starts_with('-'))
+            .ok_or(StructureError)?;
+    }
+
+    // Interpreter names may have a version suffix, e.g., "python3.8" or "lua5.1"
+    let base_name = interpreter_name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    let version = &interpreter_name[base_name.len()..];
+
+    let language = match INTERPRETERS.iter().find(|(name, _)| *name == base_name) {
+        Some((_, language)) => language.to_string(),
+        // Unknown interpreters are only accepted if they live in a bin directory, to weed out false positives
+        None if interpreter.contains("bin/") => "Unknown".to_string(),
+        None => return Err(StructureError),
+    };
+
+    // The script continues until the end of the text data
+    let script_size = match script_data.iter().position(|b| !is_text(*b)) {
+        Some(text_size) => text_size,
+        None => script_data.len(),
+    };
+
+    Ok(ShebangScript {
+        interpreter: interpreter.to_string(),
+        interpreter_name: interpreter_name.to_string(),
+        language,
+        version: version.trim_matches('.').to_string(),
+        size: script_size,
+    })
+}
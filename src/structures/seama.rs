@@ -1,52 +1,87 @@
+use crate::common::get_cstring;
 use crate::structures::common::{self, StructureError};
 
+/// SEAMA magic
+const SEAMA_MAGIC: usize = 0x5EA3A417;
+
+/// Size of the MD5 digest that follows the fixed-length portion of the header
+const DIGEST_SIZE: usize = 16;
+
 /// Struct to store SEAMA firmware header data
+#[derive(Debug, Default, Clone)]
 pub struct SeamaHeader {
     pub data_size: usize,
+    /// Size of the header, including the meta data
     pub header_size: usize,
+    /// MD5 digest of the data, if one was specified
+    pub digest: Option<[u8; DIGEST_SIZE]>,
+    /// Meta data strings, e.g., "dev=/dev/mtdblock/1", "type=firmware"
+    pub metadata: Vec<String>,
+}
+
+impl SeamaHeader {
+    /// Returns the value of the meta data entry with the specified key, e.g., "signature"
+    pub fn metadata_value(&self, key: &str) -> Option<&str> {
+        self.metadata.iter().find_map(|entry| {
+            entry
+                .split_once('=')
+                .filter(|(entry_key, _)| *entry_key == key)
+                .map(|(_, value)| value)
+        })
+    }
 }
 
 /// Parse a SEAMA firmware header
 pub fn parse_seama_header(seama_data: &[u8]) -> Result<SeamaHeader, StructureError> {
-    // SEAMA magic
-    const MAGIC: usize = 0x5EA3A417;
-
     let seama_structure = vec![
         ("magic", "u32"),
-        ("description_size", "u32"),
+        ("reserved", "u16"),
+        ("metadata_size", "u16"),
         ("data_size", "u32"),
-        ("unknown1", "u64"),
-        ("unknown2", "u64"),
+        // MD5 digest follows
     ];
 
-    let mut endianness: &str = "little";
-    let available_data = seama_data.len();
-    let header_size: usize = common::size(&seama_structure);
+    let mut endianness: &str = "big";
+    let fixed_header_size: usize = common::size(&seama_structure) + DIGEST_SIZE;
 
-    // Parse the header; try little endian first
+    // Parse the header; try big endian first
     if let Ok(mut seama_header) = common::parse(seama_data, &seama_structure, endianness) {
-        // If the magic bytes don't match, switch to big endian
-        if seama_header["magic"] != MAGIC {
-            endianness = "big";
+        // If the magic bytes don't match, switch to little endian
+        if seama_header["magic"] != SEAMA_MAGIC {
+            endianness = "little";
             match common::parse(seama_data, &seama_structure, endianness) {
                 Err(_) => {
                     return Err(StructureError);
                 }
-                Ok(seama_header_be) => {
-                    seama_header = seama_header_be.clone();
+                Ok(seama_header_le) => {
+                    seama_header = seama_header_le.clone();
                 }
             }
         }
 
         // Sanity check on magic bytes
-        if seama_header["magic"] == MAGIC {
-            let total_header_size = header_size + seama_header["description_size"];
+        if seama_header["magic"] == SEAMA_MAGIC && seama_header["reserved"] == 0 {
+            let total_header_size = fixed_header_size + seama_header["metadata_size"];
+
+            if let Some(header_data) = seama_data.get(..total_header_size) {
+                let digest: [u8; DIGEST_SIZE] = header_data
+                    [fixed_header_size - DIGEST_SIZE..fixed_header_size]
+                    .try_into()
+                    .expect("SEAMA digest size mismatch");
+
+                // Meta data is a list of NULL terminated strings
+                let metadata: Vec<String> = header_data[fixed_header_size..]
+                    .split(|b| *b == 0)
+                    .map(get_cstring)
+                    .filter(|entry| !entry.is_empty())
+                    .collect();
 
-            // Sanity check on total header size
-            if total_header_size >= header_size && available_data >= total_header_size {
                 return Ok(SeamaHeader {
                     data_size: seama_header["data_size"],
                     header_size: total_header_size,
+                    // An unused digest field is zeroed
+                    digest: Some(digest).filter(|digest| digest.iter().any(|b| *b != 0)),
+                    metadata,
                 });
             }
         }
@@ -0,0 +1,453 @@
+use crate::structures::common::{self, StructureError};
+
+/// Stores info about a SQLite database header
+#[derive(Debug, Default, Clone)]
+pub struct SQLiteHeader {
+    pub page_size: usize,
+    /// Number of pages in the database, if the header's page count is valid
+    pub page_count: Option<usize>,
+    /// Usable size of each page, not including any reserved space at the end of the page
+    pub usable_size: usize,
+    pub text_encoding: String,
+    /// Version of the SQLite library that last wrote the database, e.g., "3.45.1"
+    pub sqlite_version: String,
+    /// True if the database is in write-ahead log (WAL) mode
+    pub wal_mode: bool,
+}
+
+/// Parses a SQLite database header
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::sqlite::parse_sqlite_header;
+///
+/// let mut header = b"SQLite format 3\x00\x10\x00\x01\x01\x00\x40\x20\x20".to_vec();
+/// header.extend(b"\x00\x00\x00\x01\x00\x00\x00\x02\x00\x00\x00\x00\x00\x00\x00\x00");
+/// header.extend(b"\x00\x00\x00\x01\x00\x00\x00\x04\x00\x00\x00\x00\x00\x00\x00\x00");
+/// header.extend(b"\x00\x00\x00\x01");
+/// header.extend([0; 32]);
+/// header.extend(b"\x00\x00\x00\x01\x00\x2E\x5B\x33");
+///
+/// let sqlite_header = parse_sqlite_header(&header).unwrap();
+///
+/// assert_eq!(sqlite_header.page_size, 4096);
+/// assert_eq!(sqlite_header.page_count, Some(2));
+/// assert_eq!(sqlite_header.sqlite_version, "3.38.3");
+/// ```
+pub fn parse_sqlite_header(sqlite_data: &[u8]) -> Result<SQLiteHeader, StructureError> {
+    const SQLITE_MAGIC: &[u8] = b"SQLite format 3\x00";
+    const MIN_PAGE_SIZE: usize = 512;
+    const MAX_PAGE_SIZE: usize = 32768;
+    // A page size of 1 indicates a page size of 65536
+    const LARGE_PAGE_SIZE: usize = 65536;
+    const LEGACY_FORMAT: usize = 1;
+    const WAL_FORMAT: usize = 2;
+    const MAX_SCHEMA_FORMAT: usize = 4;
+
+    let sqlite_structure = vec![
+        ("magic_1", "u64"),
+        ("magic_2", "u64"),
+        ("page_size", "u16"),
+        ("write_version", "u8"),
+        ("read_version", "u8"),
+        ("reserved_size", "u8"),
+        ("max_payload_fraction", "u8"),
+        ("min_payload_fraction", "u8"),
+        ("leaf_payload_fraction", "u8"),
+        ("change_counter", "u32"),
+        ("page_count", "u32"),
+        ("freelist_trunk_page", "u32"),
+        ("freelist_page_count", "u32"),
+        ("schema_cookie", "u32"),
+        ("schema_format", "u32"),
+        ("default_cache_size", "u32"),
+        ("largest_root_page", "u32"),
+        ("text_encoding", "u32"),
+        ("user_version", "u32"),
+        ("incremental_vacuum", "u32"),
+        ("application_id", "u32"),
+        ("reserved_1", "u64"),
+        ("reserved_2", "u64"),
+        ("reserved_3", "u32"),
+        ("version_valid_for", "u32"),
+        ("sqlite_version", "u32"),
+    ];
+
+    if !sqlite_data.starts_with(SQLITE_MAGIC) {
+        return Err(StructureError);
+    }
+
+    let sqlite_header = common::parse(sqlite_data, &sqlite_structure, "big")?;
+
+    let page_size = match sqlite_header["page_size"] {
+        1 => LARGE_PAGE_SIZE,
+        size if (MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&size) && size.is_power_of_two() => size,
+        _ => return Err(StructureError),
+    };
+
+    // These values are fixed by the file format
+    if sqlite_header["max_payload_fraction"] != 64
+        || sqlite_header["min_payload_fraction"] != 32
+        || sqlite_header["leaf_payload_fraction"] != 32
+        || sqlite_header["reserved_1"] != 0
+        || sqlite_header["reserved_2"] != 0
+        || sqlite_header["reserved_3"] != 0
+        || sqlite_header["schema_format"] > MAX_SCHEMA_FORMAT
+        || sqlite_header["reserved_size"] >= page_size - MIN_PAGE_SIZE
+    {
+        return Err(StructureError);
+    }
+
+    let wal_mode = match (
+        sqlite_header["write_version"],
+        sqlite_header["read_version"],
+    ) {
+        (LEGACY_FORMAT, LEGACY_FORMAT) => false,
+        (WAL_FORMAT, WAL_FORMAT) => true,
+        _ => return Err(StructureError),
+    };
+
+    // An empty database has no encoding set yet, which defaults to UTF-8
+    let text_encoding = match sqlite_header["text_encoding"] {
+        0 | 1 => "UTF-8",
+        2 => "UTF-16LE",
+        3 => "UTF-16BE",
+        _ => return Err(StructureError),
+    };
+
+    // The page count is only valid if it was written by SQLite 3.7.0 or later, which also updates version_valid_for
+    let page_count = match sqlite_header["page_count"] {
+        0 => None,
+        _ if sqlite_header["version_valid_for"] != sqlite_header["change_counter"] => None,
+        page_count => Some(page_count),
+    };
+
+    // The version number is encoded as X*1000000 + Y*1000 + Z
+    let version = sqlite_header["sqlite_version"];
+
+    Ok(SQLiteHeader {
+        page_size,
+        page_count,
+        usable_size: page_size - sqlite_header["reserved_size"],
+        text_encoding: text_encoding.to_string(),
+        sqlite_version: format!(
+            "{}.{}.{}",
+            version / 1000000,
+            (version / 1000) % 1000,
+            version % 1000
+        ),
+        wal_mode,
+    })
+}
+
+/// Stores info about an entry in the SQLite schema table
+#[derive(Debug, Default, Clone)]
+pub struct SQLiteSchemaEntry {
+    /// One of "table", "index", "view" or "trigger"
+    pub entry_type: String,
+    pub name: String,
+    /// SQL statement that created the entry; empty for automatically created indexes
+    pub sql: String,
+}
+
+/// Parses the schema table, which is rooted at the first page of a SQLite database
+pub fn parse_sqlite_schema(
+    sqlite_data: &[u8],
+    sqlite_header: &SQLiteHeader,
+) -> Result<Vec<SQLiteSchemaEntry>, StructureError> {
+    const SCHEMA_ROOT_PAGE: usize = 1;
+
+    let mut schema: Vec<SQLiteSchemaEntry> = vec![];
+    let mut records: Vec<Vec<Option<String>>> = vec![];
+
+    parse_table_page(
+        sqlite_data,
+        sqlite_header,
+        SCHEMA_ROOT_PAGE,
+        0,
+        &mut records,
+    )?;
+
+    // Schema records are (type, name, tbl_name, rootpage, sql)
+    for record in records {
+        let column = |index: usize| -> String {
+            record
+                .get(index)
+                .cloned()
+                .flatten()
+                .unwrap_or("".to_string())
+        };
+
+        schema.push(SQLiteSchemaEntry {
+            entry_type: column(0),
+            name: column(1),
+            sql: column(4),
+        });
+    }
+
+    Ok(schema)
+}
+
+/// Parses the records in a table b-tree page, descending into child pages of interior pages
+fn parse_table_page(
+    sqlite_data: &[u8],
+    sqlite_header: &SQLiteHeader,
+    page_number: usize,
+    depth: usize,
+    records: &mut Vec<Vec<Option<String>>>,
+) -> Result<(), StructureError> {
+    const MAX_DEPTH: usize = 16;
+    const MAX_RECORDS: usize = 10000;
+    // The first page contains the database header
+    const DATABASE_HEADER_SIZE: usize = 100;
+    const INTERIOR_TABLE_PAGE: usize = 0x05;
+    const LEAF_TABLE_PAGE: usize = 0x0D;
+    const CELL_POINTER_SIZE: usize = 2;
+    const CHILD_POINTER_SIZE: usize = 4;
+
+    let page_header_structure = vec![
+        ("page_type", "u8"),
+        ("first_freeblock", "u16"),
+        ("cell_count", "u16"),
+        ("cell_content_offset", "u16"),
+        ("fragmented_bytes", "u8"),
+    ];
+    let child_pointer_structure = vec![("page_number", "u32")];
+    let cell_pointer_structure = vec![("offset", "u16")];
+
+    if depth > MAX_DEPTH || records.len() > MAX_RECORDS || page_number == 0 {
+        return Err(StructureError);
+    }
+
+    let page_start = (page_number - 1) * sqlite_header.page_size;
+    let page_data = sqlite_data
+        .get(page_start..page_start + sqlite_header.page_size)
+        .ok_or(StructureError)?;
+
+    let page_header_offset = match page_number {
+        1 => DATABASE_HEADER_SIZE,
+        _ => 0,
+    };
+
+    let page_header = common::parse(
+        page_data.get(page_header_offset..).ok_or(StructureError)?,
+        &page_header_structure,
+        "big",
+    )?;
+
+    let mut cell_pointers_offset = page_header_offset + common::size(&page_header_structure);
+
+    // Interior pages have a pointer to their right-most child page at the end of the page header
+    let right_child = match page_header["page_type"] {
+        INTERIOR_TABLE_PAGE => {
+            let child = common::parse(
+                page_data
+                    .get(cell_pointers_offset..)
+                    .ok_or(StructureError)?,
+                &child_pointer_structure,
+                "big",
+            )?;
+            cell_pointers_offset += CHILD_POINTER_SIZE;
+            Some(child["page_number"])
+        }
+        LEAF_TABLE_PAGE => None,
+        _ => return Err(StructureError),
+    };
+
+    for i in 0..page_header["cell_count"] {
+        let cell_pointer = common::parse(
+            page_data
+                .get(cell_pointers_offset + (i * CELL_POINTER_SIZE)..)
+                .ok_or(StructureError)?,
+            &cell_pointer_structure,
+            "big",
+        )?;
+        let cell_data = page_data
+            .get(cell_pointer["offset"]..)
+            .ok_or(StructureError)?;
+
+        match right_child {
+            // Interior cells are a child page number, followed by a rowid
+            Some(_) => {
+                let child = common::parse(cell_data, &child_pointer_structure, "big")?;
+                parse_table_page(
+                    sqlite_data,
+                    sqlite_header,
+                    child["page_number"],
+                    depth + 1,
+                    records,
+                )?;
+            }
+            // Leaf cells are the payload size, the rowid, and the payload
+            None => {
+                let (payload_size, payload_size_length) = parse_varint(cell_data)?;
+                let (_, rowid_length) =
+                    parse_varint(cell_data.get(payload_size_length..).ok_or(StructureError)?)?;
+                let payload = read_payload(
+                    sqlite_data,
+                    sqlite_header,
+                    cell_data
+                        .get(payload_size_length + rowid_length..)
+                        .ok_or(StructureError)?,
+                    payload_size,
+                )?;
+                records.push(parse_record(&payload, &sqlite_header.text_encoding)?);
+            }
+        }
+    }
+
+    if let Some(child_page) = right_child {
+        parse_table_page(sqlite_data, sqlite_header, child_page, depth + 1, records)?;
+    }
+
+    Ok(())
+}
+
+/// Reads a cell's payload, following its overflow page chain if the payload does not fit in the cell
+fn read_payload(
+    sqlite_data: &[u8],
+    sqlite_header: &SQLiteHeader,
+    cell_payload: &[u8],
+    payload_size: usize,
+) -> Result<Vec<u8>, StructureError> {
+    const OVERFLOW_POINTER_SIZE: usize = 4;
+
+    let overflow_pointer_structure = vec![("page_number", "u32")];
+
+    // Maximum and minimum amount of payload stored in a table leaf cell, as defined by the file format
+    let usable_size = sqlite_header.usable_size;
+    let max_local = usable_size - 35;
+    let min_local = ((usable_size - 12) * 32 / 255) - 23;
+
+    if payload_size <= max_local {
+        return Ok(cell_payload
+            .get(..payload_size)
+            .ok_or(StructureError)?
+            .to_vec());
+    }
+
+    let mut local_size = min_local + ((payload_size - min_local) % (usable_size - 4));
+    if local_size > max_local {
+        local_size = min_local;
+    }
+
+    let mut payload = cell_payload
+        .get(..local_size)
+        .ok_or(StructureError)?
+        .to_vec();
+    let mut overflow_page = common::parse(
+        cell_payload.get(local_size..).ok_or(StructureError)?,
+        &overflow_pointer_structure,
+        "big",
+    )?["page_number"];
+
+    // Each overflow page starts with the number of the next overflow page
+    while payload.len() < payload_size && overflow_page != 0 {
+        let page_start = (overflow_page - 1) * sqlite_header.page_size;
+        let page_data = sqlite_data
+            .get(page_start..page_start + usable_size)
+            .ok_or(StructureError)?;
+        let overflow_size = std::cmp::min(
+            payload_size - payload.len(),
+            usable_size - OVERFLOW_POINTER_SIZE,
+        );
+
+        payload.extend_from_slice(
+            &page_data[OVERFLOW_POINTER_SIZE..OVERFLOW_POINTER_SIZE + overflow_size],
+        );
+        overflow_page =
+            common::parse(page_data, &overflow_pointer_structure, "big")?["page_number"];
+    }
+
+    if payload.len() != payload_size {
+        return Err(StructureError);
+    }
+
+    Ok(payload)
+}
+
+/// Parses a record's values; text values are returned as strings, and all other values as None
+fn parse_record(
+    record_data: &[u8],
+    text_encoding: &str,
+) -> Result<Vec<Option<String>>, StructureError> {
+    let mut values: Vec<Option<String>> = vec![];
+
+    // The record header is its size, followed by the serial type of each value
+    let (header_size, mut header_offset) = parse_varint(record_data)?;
+    let mut value_offset = header_size;
+
+    while header_offset < header_size {
+        let (serial_type, serial_type_length) =
+            parse_varint(record_data.get(header_offset..).ok_or(StructureError)?)?;
+        header_offset += serial_type_length;
+
+        let value_size = match serial_type {
+            0 | 8 | 9 => 0,
+            1..=4 => serial_type,
+            5 => 6,
+            6 | 7 => 8,
+            10 | 11 => return Err(StructureError),
+            _ => (serial_type - 12) / 2,
+        };
+
+        let value_data = record_data
+            .get(value_offset..value_offset + value_size)
+            .ok_or(StructureError)?;
+        value_offset += value_size;
+
+        // Odd serial types of 13 and above are text
+        if serial_type >= 13 && serial_type % 2 == 1 {
+            values.push(Some(decode_text(value_data, text_encoding)));
+        } else {
+            values.push(None);
+        }
+    }
+
+    Ok(values)
+}
+
+/// Decodes text in the database's text encoding
+fn decode_text(text_data: &[u8], text_encoding: &str) -> String {
+    let code_units = text_data.chunks_exact(2);
+
+    match text_encoding {
+        "UTF-16LE" => String::from_utf16_lossy(
+            &code_units
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .collect::<Vec<u16>>(),
+        ),
+        "UTF-16BE" => String::from_utf16_lossy(
+            &code_units
+                .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+                .collect::<Vec<u16>>(),
+        ),
+        _ => String::from_utf8_lossy(text_data).to_string(),
+    }
+}
+
+/// Parses a SQLite variable length integer; returns the value and the number of bytes it occupied
+fn parse_varint(varint_data: &[u8]) -> Result<(usize, usize), StructureError> {
+    const MAX_VARINT_SIZE: usize = 9;
+    const CONTINUE_BIT: u8 = 0x80;
+    const VALUE_MASK: u8 = 0x7F;
+
+    let mut value: u64 = 0;
+
+    for (i, byte) in varint_data.iter().take(MAX_VARINT_SIZE).enumerate() {
+        // All 8 bits of the ninth byte are part of the value
+        if i == MAX_VARINT_SIZE - 1 {
+            value = (value << 8) | (*byte as u64);
+            return Ok((value as usize, MAX_VARINT_SIZE));
+        }
+
+        value = (value << 7) | ((byte & VALUE_MASK) as u64);
+
+        if (byte & CONTINUE_BIT) == 0 {
+            return Ok((value as usize, i + 1));
+        }
+    }
+
+    Err(StructureError)
+}
@@ -1,26 +1,69 @@
+use crate::common::get_cstring;
 use crate::structures::common::{self, StructureError};
+use std::collections::HashMap;
+
+/// Total size of a TP-Link firmware header
+const HEADER_SIZE: usize = 0x200;
+
+/// A region of a TP-Link firmware image, as described by the firmware header
+#[derive(Debug, Default, Clone)]
+pub struct TPLinkRegion {
+    /// Region name, one of "bootloader", "kernel" or "rootfs"
+    pub name: &'static str,
+    /// Offset of the region, relative to the start of the firmware header
+    pub offset: usize,
+    pub size: usize,
+}
 
 /// Stores info about a TP-Link firmware header
 #[derive(Debug, Default, Clone)]
 pub struct TPLinkFirmwareHeader {
     pub header_size: usize,
+    /// Header version; version 3 headers have the same layout as version 2 headers
+    pub header_version: usize,
+    pub hardware_id: usize,
+    pub hardware_revision: usize,
+    /// Region code, if set; only present in version 1 headers
+    pub region: usize,
+    /// Firmware version, e.g., "3.16.9"; if no numeric version is set, this is the firmware version string
+    pub firmware_version: String,
     pub kernel_load_address: usize,
     pub kernel_entry_point: usize,
+    /// Total size of the firmware image, including the header; 0 if the flash layout is not valid or does not fit in the data
+    pub total_size: usize,
+    /// Bootloader, kernel and rootfs regions, in the order they are listed in the header; empty if the flash layout is not valid
+    pub regions: Vec<TPLinkRegion>,
+}
+
+/// Parse a TP-Link version 1, 2 or 3 firmware header
+pub fn parse_tplink_firmware_header(
+    tplink_data: &[u8],
+) -> Result<TPLinkFirmwareHeader, StructureError> {
+    // Version 1 headers have a vendor name string where later versions have the firmware version string
+    const V1_VENDOR_NAME: &[u8] = b"TP-LINK";
+    const V1_VENDOR_NAME_OFFSET: usize = 4;
+
+    match tplink_data.get(V1_VENDOR_NAME_OFFSET..) {
+        Some(vendor_name) if vendor_name.starts_with(V1_VENDOR_NAME) => {
+            parse_tplink_header(tplink_data)
+        }
+        _ => parse_tplink_v2_header(tplink_data),
+    }
 }
 
-/// Pase a TP-Link firmware header
+/// Parse a TP-Link version 1 firmware header
 pub fn parse_tplink_header(tplink_data: &[u8]) -> Result<TPLinkFirmwareHeader, StructureError> {
-    // Offset of data structure, after firmware signature
+    // Offset of data structure, after vendor name and firmware version strings
     const STRUCTURE_OFFSET: usize = 0x40;
-
-    // Total size of the firmware header
-    const HEADER_SIZE: usize = 0x200;
+    // Firmware version string location
+    const VERSION_STRING_START: usize = 0x1C;
+    const VERSION_STRING_END: usize = 0x40;
 
     // https://github.com/jtreml/firmware-mod-kit/blob/master/src/tpl-tool/doc/Image_layout
     let tplink_structure = vec![
-        ("product_id", "u32"),
-        ("product_version", "u32"),
-        ("reserved1", "u32"),
+        ("hardware_id", "u32"),
+        ("hardware_revision", "u32"),
+        ("region", "u32"),
         ("image_checksum_p1", "u64"),
         ("image_checksum_p2", "u64"),
         ("reserved2", "u32"),
@@ -42,26 +85,155 @@ pub fn parse_tplink_header(tplink_data: &[u8]) -> Result<TPLinkFirmwareHeader, S
         ("reserved4", "u32"),
     ];
 
-    let mut result = TPLinkFirmwareHeader {
-        header_size: HEADER_SIZE,
-        ..Default::default()
-    };
+    // Sanity check available data
+    if tplink_data.len() >= HEADER_SIZE {
+        if let Some(structure_data) = tplink_data.get(STRUCTURE_OFFSET..) {
+            // Vendor images use big endian fields, but some third party tools write little endian fields;
+            // the byte order whose flash layout is valid and fits in the available data wins, and headers with no
+            // valid flash layout are reported using the little endian interpretation.
+            let mut parsed_headers: Vec<HashMap<String, usize>> = vec![];
+
+            for endianness in ["big", "little"] {
+                if let Ok(tplink_header) =
+                    common::parse(structure_data, &tplink_structure, endianness)
+                {
+                    // Make sure the reserved fields are NULL
+                    if tplink_header["reserved2"] == 0
+                        && tplink_header["reserved3"] == 0
+                        && tplink_header["reserved4"] == 0
+                    {
+                        parsed_headers.push(tplink_header);
+                    }
+                }
+            }
+
+            let layout_header = parsed_headers
+                .iter()
+                .find(|tplink_header| flash_layout(tplink_header, tplink_data.len()).is_some());
+
+            if let Some(tplink_header) = layout_header.or(parsed_headers.last()) {
+                let mut result = TPLinkFirmwareHeader {
+                    header_size: HEADER_SIZE,
+                    header_version: 1,
+                    hardware_id: tplink_header["hardware_id"],
+                    hardware_revision: tplink_header["hardware_revision"],
+                    region: tplink_header["region"],
+                    kernel_load_address: tplink_header["kernel_load_address"],
+                    kernel_entry_point: tplink_header["kernel_entry_point"],
+                    firmware_version: firmware_version(
+                        [
+                            tplink_header["fw_version_major"],
+                            tplink_header["fw_version_minor"],
+                            tplink_header["fw_version_patch"],
+                        ],
+                        &tplink_data[VERSION_STRING_START..VERSION_STRING_END],
+                    ),
+                    ..Default::default()
+                };
+
+                if let Some((total_size, regions)) = flash_layout(tplink_header, tplink_data.len())
+                {
+                    result.total_size = total_size;
+                    result.regions = regions;
+                }
+
+                return Ok(result);
+            }
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// Parse a TP-Link version 2 (or version 3) firmware header
+pub fn parse_tplink_v2_header(tplink_data: &[u8]) -> Result<TPLinkFirmwareHeader, StructureError> {
+    // Offset of data structure, after the firmware version string
+    const STRUCTURE_OFFSET: usize = 0x34;
+    // Firmware version string location
+    const VERSION_STRING_START: usize = 4;
+    const VERSION_STRING_END: usize = 0x34;
+    // Expected values of the magic fields
+    const MAGIC1: usize = 0x55AA;
+    const MAGIC2: usize = 0xA5;
+    const RESERVED3: usize = 0xFFFFFFFF;
+    const VALID_VERSIONS: [usize; 2] = [2, 3];
+
+    // https://github.com/openwrt/firmware-utils/blob/master/src/mktplinkfw2.c
+    let tplink_structure = vec![
+        ("hardware_id", "u32"),
+        ("hardware_revision", "u32"),
+        ("hardware_version_add", "u32"),
+        ("image_checksum_p1", "u64"),
+        ("image_checksum_p2", "u64"),
+        ("reserved2", "u32"),
+        ("kernel_checksum_p1", "u64"),
+        ("kernel_checksum_p2", "u64"),
+        ("reserved3", "u32"),
+        ("kernel_load_address", "u32"),
+        ("kernel_entry_point", "u32"),
+        ("image_length", "u32"),
+        ("kernel_offset", "u32"),
+        ("kernel_length", "u32"),
+        ("rootfs_offset", "u32"),
+        ("rootfs_length", "u32"),
+        ("bootloader_offset", "u32"),
+        ("bootloader_length", "u32"),
+        ("magic1", "u16"),
+        ("sw_version_major", "u8"),
+        ("sw_version_minor", "u8"),
+        ("magic2", "u8"),
+        ("fw_version_major", "u8"),
+        ("fw_version_minor", "u8"),
+        ("fw_version_patch", "u8"),
+    ];
+
+    let version_structure = vec![("header_version", "u32")];
 
     // Sanity check available data
     if tplink_data.len() >= HEADER_SIZE {
         if let Some(structure_data) = tplink_data.get(STRUCTURE_OFFSET..) {
-            // Parse the header
-            if let Ok(tplink_header) = common::parse(structure_data, &tplink_structure, "little") {
-                // Make sure the reserved fields are NULL
-                if tplink_header["reserved1"] == 0
-                    && tplink_header["reserved2"] == 0
-                    && tplink_header["reserved3"] == 0
-                    && tplink_header["reserved4"] == 0
+            // The magic fields identify the byte order
+            for endianness in ["big", "little"] {
+                if let Ok(version_header) =
+                    common::parse(tplink_data, &version_structure, endianness)
                 {
-                    // Unfortunately, most header fields aren't reliably used; these seem to be, so report them
-                    result.kernel_entry_point = tplink_header["kernel_entry_point"];
-                    result.kernel_load_address = tplink_header["kernel_load_address"];
-                    return Ok(result);
+                    if let Ok(tplink_header) =
+                        common::parse(structure_data, &tplink_structure, endianness)
+                    {
+                        if VALID_VERSIONS.contains(&version_header["header_version"])
+                            && tplink_header["magic1"] == MAGIC1
+                            && tplink_header["magic2"] == MAGIC2
+                            && tplink_header["reserved2"] == 0
+                            && tplink_header["reserved3"] == RESERVED3
+                        {
+                            let mut result = TPLinkFirmwareHeader {
+                                header_size: HEADER_SIZE,
+                                header_version: version_header["header_version"],
+                                hardware_id: tplink_header["hardware_id"],
+                                hardware_revision: tplink_header["hardware_revision"],
+                                kernel_load_address: tplink_header["kernel_load_address"],
+                                kernel_entry_point: tplink_header["kernel_entry_point"],
+                                firmware_version: firmware_version(
+                                    [
+                                        tplink_header["fw_version_major"],
+                                        tplink_header["fw_version_minor"],
+                                        tplink_header["fw_version_patch"],
+                                    ],
+                                    &tplink_data[VERSION_STRING_START..VERSION_STRING_END],
+                                ),
+                                ..Default::default()
+                            };
+
+                            if let Some((total_size, regions)) =
+                                flash_layout(&tplink_header, tplink_data.len())
+                            {
+                                result.total_size = total_size;
+                                result.regions = regions;
+                            }
+
+                            return Ok(result);
+                        }
+                    }
                 }
             }
         }
@@ -70,6 +242,71 @@ pub fn parse_tplink_header(tplink_data: &[u8]) -> Result<TPLinkFirmwareHeader, S
     Err(StructureError)
 }
 
+/// Returns the numeric firmware version, or the firmware version string if no numeric version is set
+fn firmware_version(version_fields: [usize; 3], version_string: &[u8]) -> String {
+    if version_fields.iter().any(|field| *field != 0) {
+        return format!(
+            "{}.{}.{}",
+            version_fields[0], version_fields[1], version_fields[2]
+        );
+    }
+
+    get_cstring(version_string)
+}
+
+/// Validates the flash layout described by a TP-Link header, and returns the total image size and the non-empty regions.
+/// Regions must fit in the available data, but the padding after the last region may have been stripped.
+fn flash_layout(
+    tplink_header: &HashMap<String, usize>,
+    available_data: usize,
+) -> Option<(usize, Vec<TPLinkRegion>)> {
+    let total_size = tplink_header["image_length"];
+    let mut regions: Vec<TPLinkRegion> = vec![];
+
+    if total_size <= HEADER_SIZE {
+        return None;
+    }
+
+    for (name, offset_field, size_field) in [
+        ("bootloader", "bootloader_offset", "bootloader_length"),
+        ("kernel", "kernel_offset", "kernel_length"),
+        ("rootfs", "rootfs_offset", "rootfs_length"),
+    ] {
+        let region = TPLinkRegion {
+            name,
+            offset: tplink_header[offset_field],
+            size: tplink_header[size_field],
+        };
+
+        if region.size > 0 {
+            // Regions must follow the header, and be inside the image and the available data
+            if region.offset < HEADER_SIZE
+                || region.offset + region.size > total_size
+                || region.offset + region.size > available_data
+            {
+                return None;
+            }
+
+            // Regions must not overlap
+            if regions.iter().any(|other| {
+                region.offset < other.offset + other.size
+                    && other.offset < region.offset + region.size
+            }) {
+                return None;
+            }
+
+            regions.push(region);
+        }
+    }
+
+    // There must at least be a kernel
+    if !regions.iter().any(|region| region.name == "kernel") {
+        return None;
+    }
+
+    Some((total_size, regions))
+}
+
 /// Stores info about a TP-Link RTOS firmware header
 #[derive(Debug, Default, Clone)]
 pub struct TPLinkRTOSFirmwareHeader {
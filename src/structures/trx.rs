@@ -7,9 +7,29 @@ pub struct TRXHeader {
     pub checksum: usize,
     pub total_size: usize,
     pub header_size: usize,
+    /// Partition offsets, relative to the start of the header
     pub partitions: Vec<usize>,
 }
 
+impl TRXHeader {
+    /// Returns the offset and size of each partition; each partition extends to the next partition, or the end of the image
+    pub fn partition_extents(&self) -> Vec<(usize, usize)> {
+        self.partitions
+            .iter()
+            .enumerate()
+            .map(|(i, partition_offset)| {
+                let partition_end = self
+                    .partitions
+                    .get(i + 1)
+                    .copied()
+                    .unwrap_or(self.total_size);
+
+                (*partition_offset, partition_end - partition_offset)
+            })
+            .collect()
+    }
+}
+
 /// Parse a TRX firmware header
 pub fn parse_trx_header(header_data: &[u8]) -> Result<TRXHeader, StructureError> {
     // TRX comes in two flavors: v1 and v2
@@ -67,6 +87,19 @@ pub fn parse_trx_header(header_data: &[u8]) -> Result<TRXHeader, StructureError>
                         struct_size -= std::mem::size_of::<u32>();
                     }
 
+                    // Partitions must follow the header, in order, and be within the image
+                    let mut previous_partition_offset: usize = struct_size - 1;
+
+                    for partition_offset in &partitions {
+                        if *partition_offset <= previous_partition_offset
+                            || *partition_offset > trx_header["total_size"]
+                        {
+                            return Err(StructureError);
+                        }
+
+                        previous_partition_offset = *partition_offset;
+                    }
+
                     return Ok(TRXHeader {
                         version: trx_header["version"],
                         checksum: trx_header["crc32"],
@@ -6,6 +6,7 @@ use crate::structures::common::{self, StructureError};
 pub struct UbiSuperBlockHeader {
     pub leb_size: usize,
     pub leb_count: usize,
+    pub key_format: usize,
 }
 
 /// Partially parse a UBI superblock header
@@ -68,6 +69,7 @@ pub fn parse_ubi_superblock_header(ubi_data: &[u8]) -> Result<UbiSuperBlockHeade
                             return Ok(UbiSuperBlockHeader {
                                 leb_size: sb_header["leb_size"],
                                 leb_count: sb_header["leb_count"],
+                                key_format: sb_header["key_format"],
                             });
                         }
                     }
@@ -129,9 +131,15 @@ pub fn parse_ubi_ec_header(ubi_data: &[u8]) -> Result<UbiECHeader, StructureErro
     Err(StructureError)
 }
 
-/// Dummy structure indicating a UBI volume header was parsed successfully
+/// Stores info about a UBI volume header
 #[derive(Debug, Default, Clone)]
-pub struct UbiVolumeHeader;
+pub struct UbiVolumeHeader {
+    pub volume_id: usize,
+    pub volume_type: usize,
+    pub lnum: usize,
+    pub data_size: usize,
+    pub sequence_number: usize,
+}
 
 /// Parse a UBI volume header
 pub fn parse_ubi_volume_header(ubi_data: &[u8]) -> Result<UbiVolumeHeader, StructureError> {
@@ -169,7 +177,13 @@ pub fn parse_ubi_volume_header(ubi_data: &[u8]) -> Result<UbiVolumeHeader, Struc
             // Validate the header CRC
             if let Some(crc_data) = ubi_data.get(0..crc_data_size) {
                 if ubi_crc(crc_data) == ubi_vol_header["header_crc"] {
-                    return Ok(UbiVolumeHeader);
+                    return Ok(UbiVolumeHeader {
+                        volume_id: ubi_vol_header["volume_id"],
+                        volume_type: ubi_vol_header["volume_type"],
+                        lnum: ubi_vol_header["logical_erase_block_number"],
+                        data_size: ubi_vol_header["data_size"],
+                        sequence_number: ubi_vol_header["sequence_number"],
+                    });
                 }
             }
         }
@@ -178,6 +192,320 @@ pub fn parse_ubi_volume_header(ubi_data: &[u8]) -> Result<UbiVolumeHeader, Struc
     Err(StructureError)
 }
 
+/// Size of a UBI volume table record
+pub const UBI_VTBL_RECORD_SIZE: usize = 172;
+
+/// Stores info about a UBI volume table record
+#[derive(Debug, Default, Clone)]
+pub struct UbiVolumeTableRecord {
+    pub reserved_pebs: usize,
+    pub volume_type: usize,
+    pub name: String,
+}
+
+/// Parse a UBI volume table record; empty records are reported as errors
+pub fn parse_ubi_volume_table_record(
+    record_data: &[u8],
+) -> Result<UbiVolumeTableRecord, StructureError> {
+    const NAME_OFFSET: usize = 16;
+    const MAX_NAME_LENGTH: usize = 128;
+
+    let vtbl_structure = vec![
+        ("reserved_pebs", "u32"),
+        ("alignment", "u32"),
+        ("data_pad", "u32"),
+        ("volume_type", "u8"),
+        ("update_marker", "u8"),
+        ("name_length", "u16"),
+    ];
+
+    let crc_data_size: usize = UBI_VTBL_RECORD_SIZE - std::mem::size_of::<u32>();
+
+    let record = common::parse(record_data, &vtbl_structure, "big")?;
+    let record_crc = common::parse(
+        record_data.get(crc_data_size..).ok_or(StructureError)?,
+        &vec![("crc", "u32")],
+        "big",
+    )?;
+
+    // Validate the record CRC
+    if ubi_crc(&record_data[0..crc_data_size]) != record_crc["crc"] {
+        return Err(StructureError);
+    }
+
+    if record["reserved_pebs"] == 0 || record["name_length"] > MAX_NAME_LENGTH {
+        return Err(StructureError);
+    }
+
+    let name_bytes = &record_data[NAME_OFFSET..NAME_OFFSET + record["name_length"]];
+
+    Ok(UbiVolumeTableRecord {
+        reserved_pebs: record["reserved_pebs"],
+        volume_type: record["volume_type"],
+        name: String::from_utf8_lossy(name_bytes).to_string(),
+    })
+}
+
+/// UBIFS node types
+pub const UBIFS_INO_NODE: usize = 0;
+pub const UBIFS_DATA_NODE: usize = 1;
+pub const UBIFS_DENT_NODE: usize = 2;
+pub const UBIFS_MST_NODE: usize = 7;
+pub const UBIFS_IDX_NODE: usize = 9;
+
+/// Stores info about a UBIFS node header
+#[derive(Debug, Default, Clone)]
+pub struct UbifsNodeHeader {
+    pub node_type: usize,
+    pub size: usize,
+    pub sequence_number: usize,
+}
+
+/// Parse and validate a UBIFS common node header
+pub fn parse_ubifs_node_header(node_data: &[u8]) -> Result<UbifsNodeHeader, StructureError> {
+    const UBIFS_NODE_MAGIC: usize = 0x06101831;
+    const CRC_START_OFFSET: usize = 8;
+
+    let ubifs_ch_structure = vec![
+        ("magic", "u32"),
+        ("crc", "u32"),
+        ("sequence_number", "u64"),
+        ("length", "u32"),
+        ("node_type", "u8"),
+        ("group_type", "u8"),
+        ("padding", "u16"),
+    ];
+
+    let node_header = common::parse(node_data, &ubifs_ch_structure, "little")?;
+
+    if node_header["magic"] == UBIFS_NODE_MAGIC
+        && node_header["length"] >= common::size(&ubifs_ch_structure)
+    {
+        // The node CRC is calculated over the entire node, except for the magic bytes and CRC field
+        if let Some(crc_data) = node_data.get(CRC_START_OFFSET..node_header["length"]) {
+            if ubi_crc(crc_data) == node_header["crc"] {
+                return Ok(UbifsNodeHeader {
+                    node_type: node_header["node_type"],
+                    size: node_header["length"],
+                    sequence_number: node_header["sequence_number"],
+                });
+            }
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// Stores the location of the root index node, as reported by a UBIFS master node
+#[derive(Debug, Default, Clone)]
+pub struct UbifsMasterNode {
+    pub root_lnum: usize,
+    pub root_offset: usize,
+}
+
+/// Parse a UBIFS master node
+pub fn parse_ubifs_master_node(node_data: &[u8]) -> Result<UbifsMasterNode, StructureError> {
+    const UBIFS_CH_SIZE: usize = 24;
+
+    let master_structure = vec![
+        ("highest_inum", "u64"),
+        ("commit_number", "u64"),
+        ("flags", "u32"),
+        ("log_lnum", "u32"),
+        ("root_lnum", "u32"),
+        ("root_offset", "u32"),
+        ("root_length", "u32"),
+    ];
+
+    let master = common::parse(
+        node_data.get(UBIFS_CH_SIZE..).ok_or(StructureError)?,
+        &master_structure,
+        "little",
+    )?;
+
+    Ok(UbifsMasterNode {
+        root_lnum: master["root_lnum"],
+        root_offset: master["root_offset"],
+    })
+}
+
+/// Stores info about a UBIFS index node
+#[derive(Debug, Default, Clone)]
+pub struct UbifsIndexNode {
+    pub level: usize,
+    /// LEB number and offset of each child node
+    pub branches: Vec<(usize, usize)>,
+}
+
+/// Parse a UBIFS index node; only the "simple" key format (8-byte keys) is supported
+pub fn parse_ubifs_index_node(node_data: &[u8]) -> Result<UbifsIndexNode, StructureError> {
+    const UBIFS_CH_SIZE: usize = 24;
+    const SIMPLE_KEY_SIZE: usize = 8;
+
+    let index_structure = vec![("child_count", "u16"), ("level", "u16")];
+    let branch_structure = vec![("lnum", "u32"), ("offset", "u32"), ("length", "u32")];
+
+    let index_data = node_data.get(UBIFS_CH_SIZE..).ok_or(StructureError)?;
+    let index = common::parse(index_data, &index_structure, "little")?;
+
+    let branch_size = common::size(&branch_structure) + SIMPLE_KEY_SIZE;
+
+    let mut index_node = UbifsIndexNode {
+        level: index["level"],
+        ..Default::default()
+    };
+
+    for i in 0..index["child_count"] {
+        let branch_offset = common::size(&index_structure) + (i * branch_size);
+        let branch_data = index_data.get(branch_offset..).ok_or(StructureError)?;
+        let branch = common::parse(branch_data, &branch_structure, "little")?;
+
+        index_node.branches.push((branch["lnum"], branch["offset"]));
+    }
+
+    Ok(index_node)
+}
+
+/// Stores info about a UBIFS inode node
+#[derive(Debug, Default, Clone)]
+pub struct UbifsInodeNode {
+    pub inode: usize,
+    pub size: usize,
+    pub mode: usize,
+    /// Inline data, such as symlink targets and device numbers
+    pub data: Vec<u8>,
+}
+
+/// Parse a UBIFS inode node
+pub fn parse_ubifs_inode_node(node_data: &[u8]) -> Result<UbifsInodeNode, StructureError> {
+    const UBIFS_CH_SIZE: usize = 24;
+    const UBIFS_INO_NODE_SIZE: usize = 160;
+
+    let inode_structure = vec![
+        ("inode", "u32"),
+        ("key_type", "u32"),
+        ("key_padding1", "u64"),
+        ("creation_sequence_number", "u64"),
+        ("size", "u64"),
+        ("atime", "u64"),
+        ("ctime", "u64"),
+        ("mtime", "u64"),
+        ("atime_nsec", "u32"),
+        ("ctime_nsec", "u32"),
+        ("mtime_nsec", "u32"),
+        ("nlink", "u32"),
+        ("uid", "u32"),
+        ("gid", "u32"),
+        ("mode", "u32"),
+        ("flags", "u32"),
+        ("data_length", "u32"),
+    ];
+
+    let inode = common::parse(
+        node_data.get(UBIFS_CH_SIZE..).ok_or(StructureError)?,
+        &inode_structure,
+        "little",
+    )?;
+
+    let inline_data = node_data
+        .get(UBIFS_INO_NODE_SIZE..UBIFS_INO_NODE_SIZE + inode["data_length"])
+        .ok_or(StructureError)?;
+
+    Ok(UbifsInodeNode {
+        inode: inode["inode"],
+        size: inode["size"],
+        mode: inode["mode"],
+        data: inline_data.to_vec(),
+    })
+}
+
+/// Stores info about a UBIFS data node
+#[derive(Debug, Default, Clone)]
+pub struct UbifsDataNode {
+    pub inode: usize,
+    pub block: usize,
+    /// Size of the uncompressed data
+    pub size: usize,
+    pub compression: usize,
+    pub compressed_data: Vec<u8>,
+}
+
+/// Parse a UBIFS data node
+pub fn parse_ubifs_data_node(node_data: &[u8]) -> Result<UbifsDataNode, StructureError> {
+    const UBIFS_CH_SIZE: usize = 24;
+    const UBIFS_DATA_NODE_SIZE: usize = 48;
+    const KEY_VALUE_MASK: usize = 0x1FFFFFFF;
+
+    let data_structure = vec![
+        ("inode", "u32"),
+        ("key_value", "u32"),
+        ("key_padding1", "u64"),
+        ("size", "u32"),
+        ("compression", "u16"),
+        ("compressed_size", "u16"),
+    ];
+
+    let data_node = common::parse(
+        node_data.get(UBIFS_CH_SIZE..).ok_or(StructureError)?,
+        &data_structure,
+        "little",
+    )?;
+
+    Ok(UbifsDataNode {
+        inode: data_node["inode"],
+        block: data_node["key_value"] & KEY_VALUE_MASK,
+        size: data_node["size"],
+        compression: data_node["compression"],
+        compressed_data: node_data
+            .get(UBIFS_DATA_NODE_SIZE..)
+            .ok_or(StructureError)?
+            .to_vec(),
+    })
+}
+
+/// Stores info about a UBIFS directory entry node
+#[derive(Debug, Default, Clone)]
+pub struct UbifsDirentNode {
+    pub parent_inode: usize,
+    pub inode: usize,
+    pub inode_type: usize,
+    pub name: String,
+}
+
+/// Parse a UBIFS directory entry node
+pub fn parse_ubifs_dirent_node(node_data: &[u8]) -> Result<UbifsDirentNode, StructureError> {
+    const UBIFS_CH_SIZE: usize = 24;
+    const UBIFS_DENT_NODE_SIZE: usize = 56;
+
+    let dirent_structure = vec![
+        ("parent_inode", "u32"),
+        ("key_value", "u32"),
+        ("key_padding1", "u64"),
+        ("inode", "u64"),
+        ("padding1", "u8"),
+        ("inode_type", "u8"),
+        ("name_length", "u16"),
+        ("cookie", "u32"),
+    ];
+
+    let dirent = common::parse(
+        node_data.get(UBIFS_CH_SIZE..).ok_or(StructureError)?,
+        &dirent_structure,
+        "little",
+    )?;
+
+    let name_bytes = node_data
+        .get(UBIFS_DENT_NODE_SIZE..UBIFS_DENT_NODE_SIZE + dirent["name_length"])
+        .ok_or(StructureError)?;
+
+    Ok(UbifsDirentNode {
+        parent_inode: dirent["parent_inode"],
+        inode: dirent["inode"],
+        inode_type: dirent["inode_type"],
+        name: String::from_utf8_lossy(name_bytes).to_string(),
+    })
+}
+
 /// Calculate a UBI checksum
 fn ubi_crc(data: &[u8]) -> usize {
     const UBI_CRC_INIT: u32 = 0xFFFFFFFF;
@@ -0,0 +1,734 @@
+use crate::structures::common::{self, StructureError};
+
+/// Offset of the volume recognition sequence from the beginning of the UDF image
+pub const UDF_VOLUME_RECOGNITION_OFFSET: usize = 32768;
+
+/// Size of each volume recognition sequence descriptor
+pub const UDF_VOLUME_RECOGNITION_DESCRIPTOR_SIZE: usize = 2048;
+
+/// Logical sector that always contains an anchor volume descriptor pointer
+pub const UDF_ANCHOR_SECTOR: usize = 256;
+
+/// Descriptor tag identifiers
+pub const UDF_TAG_ANCHOR_VOLUME_DESCRIPTOR_POINTER: usize = 2;
+pub const UDF_TAG_PARTITION_DESCRIPTOR: usize = 5;
+pub const UDF_TAG_LOGICAL_VOLUME_DESCRIPTOR: usize = 6;
+pub const UDF_TAG_TERMINATING_DESCRIPTOR: usize = 8;
+pub const UDF_TAG_FILE_SET_DESCRIPTOR: usize = 256;
+pub const UDF_TAG_FILE_IDENTIFIER_DESCRIPTOR: usize = 257;
+pub const UDF_TAG_FILE_ENTRY: usize = 261;
+pub const UDF_TAG_EXTENDED_FILE_ENTRY: usize = 266;
+
+/// Size of a descriptor tag
+const UDF_TAG_SIZE: usize = 16;
+
+/// Parses the tag at the start of a UDF descriptor, returning the tag identifier.
+///
+/// The tag checksum is always validated; the tag location is validated if an expected location is provided.
+pub fn parse_udf_tag(
+    descriptor_data: &[u8],
+    expected_location: Option<usize>,
+) -> Result<usize, StructureError> {
+    const CHECKSUM_OFFSET: usize = 4;
+
+    let tag_structure = vec![
+        ("tag_identifier", "u16"),
+        ("descriptor_version", "u16"),
+        ("checksum", "u8"),
+        ("reserved", "u8"),
+        ("serial_number", "u16"),
+        ("crc", "u16"),
+        ("crc_length", "u16"),
+        ("tag_location", "u32"),
+    ];
+
+    let tag_data = descriptor_data.get(..UDF_TAG_SIZE).ok_or(StructureError)?;
+    let tag = common::parse(tag_data, &tag_structure, "little")?;
+
+    // The checksum is the sum of all tag bytes, excluding the checksum byte itself
+    let checksum = tag_data
+        .iter()
+        .enumerate()
+        .filter(|(i, _byte)| *i != CHECKSUM_OFFSET)
+        .fold(0_u8, |checksum, (_i, byte)| checksum.wrapping_add(*byte));
+
+    if checksum as usize == tag["checksum"]
+        && tag["reserved"] == 0
+        && expected_location.is_none_or(|location| location == tag["tag_location"])
+    {
+        return Ok(tag["tag_identifier"]);
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about a UDF anchor volume descriptor pointer
+#[derive(Debug, Default, Clone)]
+pub struct UDFAnchor {
+    /// Logical sector of the main volume descriptor sequence
+    pub main_sequence_location: usize,
+    pub main_sequence_length: usize,
+    /// Logical sector of the reserve volume descriptor sequence
+    pub reserve_sequence_location: usize,
+    pub reserve_sequence_length: usize,
+}
+
+/// Parses a UDF anchor volume descriptor pointer located at the specified logical sector
+pub fn parse_udf_anchor(anchor_data: &[u8], sector: usize) -> Result<UDFAnchor, StructureError> {
+    let anchor_structure = vec![
+        ("main_sequence_length", "u32"),
+        ("main_sequence_location", "u32"),
+        ("reserve_sequence_length", "u32"),
+        ("reserve_sequence_location", "u32"),
+    ];
+
+    if parse_udf_tag(anchor_data, Some(sector))? == UDF_TAG_ANCHOR_VOLUME_DESCRIPTOR_POINTER {
+        let anchor = common::parse(&anchor_data[UDF_TAG_SIZE..], &anchor_structure, "little")?;
+
+        if anchor["main_sequence_length"] > 0 {
+            return Ok(UDFAnchor {
+                main_sequence_location: anchor["main_sequence_location"],
+                main_sequence_length: anchor["main_sequence_length"],
+                reserve_sequence_location: anchor["reserve_sequence_location"],
+                reserve_sequence_length: anchor["reserve_sequence_length"],
+            });
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about a UDF partition descriptor
+#[derive(Debug, Default, Clone)]
+pub struct UDFPartition {
+    pub number: usize,
+    /// Logical sector where the partition starts
+    pub start: usize,
+    /// Partition length, in logical sectors
+    pub length: usize,
+}
+
+/// Parses a UDF partition descriptor
+pub fn parse_udf_partition_descriptor(
+    descriptor_data: &[u8],
+) -> Result<UDFPartition, StructureError> {
+    const PARTITION_NUMBER_OFFSET: usize = 22;
+    const PARTITION_LOCATION_OFFSET: usize = 188;
+
+    let number_structure = vec![("partition_number", "u16")];
+    let location_structure = vec![("starting_location", "u32"), ("length", "u32")];
+
+    let number = common::parse(
+        descriptor_data
+            .get(PARTITION_NUMBER_OFFSET..)
+            .ok_or(StructureError)?,
+        &number_structure,
+        "little",
+    )?;
+    let location = common::parse(
+        descriptor_data
+            .get(PARTITION_LOCATION_OFFSET..)
+            .ok_or(StructureError)?,
+        &location_structure,
+        "little",
+    )?;
+
+    Ok(UDFPartition {
+        number: number["partition_number"],
+        start: location["starting_location"],
+        length: location["length"],
+    })
+}
+
+/// A UDF long allocation descriptor, which records an extent in a specific partition
+#[derive(Debug, Default, Clone)]
+pub struct UDFLongAD {
+    pub length: usize,
+    pub block: usize,
+    pub partition_reference: usize,
+}
+
+/// Parses a UDF long allocation descriptor
+pub fn parse_udf_long_ad(ad_data: &[u8]) -> Result<UDFLongAD, StructureError> {
+    let long_ad_structure = vec![
+        ("extent_length", "u32"),
+        ("logical_block_number", "u32"),
+        ("partition_reference", "u16"),
+    ];
+
+    let long_ad = common::parse(ad_data, &long_ad_structure, "little")?;
+
+    Ok(UDFLongAD {
+        length: long_ad["extent_length"],
+        block: long_ad["logical_block_number"],
+        partition_reference: long_ad["partition_reference"],
+    })
+}
+
+/// Stores info about a UDF logical volume descriptor
+#[derive(Debug, Default, Clone)]
+pub struct UDFLogicalVolume {
+    pub block_size: usize,
+    pub volume_identifier: String,
+    /// The UDF revision, e.g., 0x0201 for UDF 2.01
+    pub udf_revision: usize,
+    /// Location of the file set descriptor
+    pub file_set: UDFLongAD,
+    /// Partition numbers referenced by each partition map; None for unsupported partition map types
+    pub partition_maps: Vec<Option<usize>>,
+}
+
+/// Parses a UDF logical volume descriptor
+pub fn parse_udf_logical_volume_descriptor(
+    descriptor_data: &[u8],
+) -> Result<UDFLogicalVolume, StructureError> {
+    const VOLUME_IDENTIFIER_START: usize = 84;
+    const VOLUME_IDENTIFIER_END: usize = 212;
+    const BLOCK_SIZE_OFFSET: usize = 212;
+    const DOMAIN_IDENTIFIER_OFFSET: usize = 216;
+    const DOMAIN_SUFFIX_OFFSET: usize = 240;
+    const FILE_SET_OFFSET: usize = 248;
+    const MAP_TABLE_OFFSET: usize = 264;
+    const PARTITION_MAPS_OFFSET: usize = 440;
+    const DOMAIN_IDENTIFIER: &[u8] = b"*OSTA UDF Compliant";
+    const TYPE1_PARTITION_MAP: usize = 1;
+    const TYPE1_PARTITION_MAP_LENGTH: usize = 6;
+    const MAX_BLOCK_SIZE: usize = 32768;
+
+    let block_size_structure = vec![("block_size", "u32")];
+    let revision_structure = vec![("udf_revision", "u16")];
+    let map_count_structure = vec![("map_table_length", "u32"), ("map_count", "u32")];
+    let partition_map_structure = vec![
+        ("map_type", "u8"),
+        ("map_length", "u8"),
+        ("volume_sequence_number", "u16"),
+        ("partition_number", "u16"),
+    ];
+
+    let block_size = common::parse(
+        descriptor_data
+            .get(BLOCK_SIZE_OFFSET..)
+            .ok_or(StructureError)?,
+        &block_size_structure,
+        "little",
+    )?;
+
+    if !descriptor_data
+        .get(DOMAIN_IDENTIFIER_OFFSET + 1..)
+        .is_some_and(|domain_identifier| domain_identifier.starts_with(DOMAIN_IDENTIFIER))
+    {
+        return Err(StructureError);
+    }
+
+    let revision = common::parse(
+        &descriptor_data[DOMAIN_SUFFIX_OFFSET..],
+        &revision_structure,
+        "little",
+    )?;
+    let map_count = common::parse(
+        descriptor_data
+            .get(MAP_TABLE_OFFSET..)
+            .ok_or(StructureError)?,
+        &map_count_structure,
+        "little",
+    )?;
+
+    let mut logical_volume = UDFLogicalVolume {
+        block_size: block_size["block_size"],
+        volume_identifier: parse_udf_dstring(
+            &descriptor_data[VOLUME_IDENTIFIER_START..VOLUME_IDENTIFIER_END],
+        ),
+        udf_revision: revision["udf_revision"],
+        file_set: parse_udf_long_ad(&descriptor_data[FILE_SET_OFFSET..])?,
+        ..Default::default()
+    };
+
+    if !logical_volume.block_size.is_power_of_two()
+        || logical_volume.block_size < 512
+        || logical_volume.block_size > MAX_BLOCK_SIZE
+    {
+        return Err(StructureError);
+    }
+
+    let mut map_offset = PARTITION_MAPS_OFFSET;
+    let map_table_end = PARTITION_MAPS_OFFSET + map_count["map_table_length"];
+
+    for _ in 0..map_count["map_count"] {
+        let partition_map = common::parse(
+            descriptor_data
+                .get(map_offset..map_table_end)
+                .ok_or(StructureError)?,
+            &partition_map_structure,
+            "little",
+        )?;
+
+        if partition_map["map_length"] == 0 {
+            return Err(StructureError);
+        }
+
+        // Only type 1 partition maps, which map directly to a physical partition, are supported
+        if partition_map["map_type"] == TYPE1_PARTITION_MAP
+            && partition_map["map_length"] == TYPE1_PARTITION_MAP_LENGTH
+        {
+            logical_volume
+                .partition_maps
+                .push(Some(partition_map["partition_number"]));
+        } else {
+            logical_volume.partition_maps.push(None);
+        }
+
+        map_offset += partition_map["map_length"];
+    }
+
+    Ok(logical_volume)
+}
+
+/// Parses a UDF file set descriptor, returning the location of the root directory's ICB
+pub fn parse_udf_file_set_descriptor(descriptor_data: &[u8]) -> Result<UDFLongAD, StructureError> {
+    const ROOT_DIRECTORY_ICB_OFFSET: usize = 400;
+
+    parse_udf_long_ad(
+        descriptor_data
+            .get(ROOT_DIRECTORY_ICB_OFFSET..)
+            .ok_or(StructureError)?,
+    )
+}
+
+/// How the allocation descriptors of a UDF file entry record the file's data
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum UDFAllocationType {
+    #[default]
+    Short,
+    Long,
+    /// The file data is embedded in the file entry, in place of the allocation descriptors
+    Embedded,
+    Unsupported,
+}
+
+/// UDF file entry file types
+pub const UDF_FILE_TYPE_DIRECTORY: usize = 4;
+pub const UDF_FILE_TYPE_FILE: usize = 5;
+pub const UDF_FILE_TYPE_SYMLINK: usize = 12;
+
+/// Stores info about a UDF file entry or extended file entry
+#[derive(Debug, Default, Clone)]
+pub struct UDFFileEntry {
+    pub file_type: usize,
+    /// POSIX file mode permission bits
+    pub mode: usize,
+    pub information_length: usize,
+    pub allocation_type: UDFAllocationType,
+    /// The raw allocation descriptors, or the embedded file data
+    pub allocation_descriptors: Vec<u8>,
+}
+
+/// Parses a UDF file entry or extended file entry
+pub fn parse_udf_file_entry(
+    entry_data: &[u8],
+    block: usize,
+) -> Result<UDFFileEntry, StructureError> {
+    const ICB_TAG_OFFSET: usize = 16;
+    const PERMISSIONS_OFFSET: usize = 44;
+    const INFORMATION_LENGTH_OFFSET: usize = 56;
+    const FILE_ENTRY_LENGTHS_OFFSET: usize = 168;
+    const EXTENDED_FILE_ENTRY_LENGTHS_OFFSET: usize = 208;
+    const ALLOCATION_TYPE_MASK: usize = 0x07;
+
+    let icb_tag_structure = vec![
+        ("prior_recorded_entries", "u32"),
+        ("strategy_type", "u16"),
+        ("strategy_parameter", "u16"),
+        ("max_entries", "u16"),
+        ("reserved", "u8"),
+        ("file_type", "u8"),
+        ("parent_block", "u32"),
+        ("parent_partition", "u16"),
+        ("flags", "u16"),
+    ];
+    let permissions_structure = vec![("permissions", "u32")];
+    let information_length_structure = vec![("information_length", "u64")];
+    let lengths_structure = vec![
+        ("extended_attributes_length", "u32"),
+        ("allocation_descriptors_length", "u32"),
+    ];
+
+    let lengths_offset = match parse_udf_tag(entry_data, Some(block))? {
+        UDF_TAG_FILE_ENTRY => FILE_ENTRY_LENGTHS_OFFSET,
+        UDF_TAG_EXTENDED_FILE_ENTRY => EXTENDED_FILE_ENTRY_LENGTHS_OFFSET,
+        _ => return Err(StructureError),
+    };
+
+    let icb_tag = common::parse(&entry_data[ICB_TAG_OFFSET..], &icb_tag_structure, "little")?;
+    let permissions = common::parse(
+        entry_data.get(PERMISSIONS_OFFSET..).ok_or(StructureError)?,
+        &permissions_structure,
+        "little",
+    )?;
+    let information_length = common::parse(
+        entry_data
+            .get(INFORMATION_LENGTH_OFFSET..)
+            .ok_or(StructureError)?,
+        &information_length_structure,
+        "little",
+    )?;
+    let lengths = common::parse(
+        entry_data.get(lengths_offset..).ok_or(StructureError)?,
+        &lengths_structure,
+        "little",
+    )?;
+
+    let allocation_descriptors_start =
+        lengths_offset + common::size(&lengths_structure) + lengths["extended_attributes_length"];
+    let allocation_descriptors_end =
+        allocation_descriptors_start + lengths["allocation_descriptors_length"];
+
+    // UDF permissions store the other, group and owner execute/write/read bits in bits 0-2, 5-7 and 10-12 respectively
+    let udf_permissions = permissions["permissions"];
+    let mode = (udf_permissions & 0x7)
+        | ((udf_permissions >> 5) & 0x7) << 3
+        | ((udf_permissions >> 10) & 0x7) << 6;
+
+    Ok(UDFFileEntry {
+        file_type: icb_tag["file_type"],
+        mode,
+        information_length: information_length["information_length"],
+        allocation_type: match icb_tag["flags"] & ALLOCATION_TYPE_MASK {
+            0 => UDFAllocationType::Short,
+            1 => UDFAllocationType::Long,
+            3 => UDFAllocationType::Embedded,
+            _ => UDFAllocationType::Unsupported,
+        },
+        allocation_descriptors: entry_data
+            .get(allocation_descriptors_start..allocation_descriptors_end)
+            .ok_or(StructureError)?
+            .to_vec(),
+    })
+}
+
+/// UDF extent types, stored in the upper two bits of an allocation descriptor's extent length;
+/// types 1 and 2 are extents that have not been recorded, and read as zeros
+pub const UDF_EXTENT_RECORDED: usize = 0;
+pub const UDF_EXTENT_CONTINUATION: usize = 3;
+
+/// An extent of a file's data, as recorded in a short or long allocation descriptor
+#[derive(Debug, Default, Clone)]
+pub struct UDFExtent {
+    pub extent_type: usize,
+    pub length: usize,
+    pub block: usize,
+    /// The partition reference of long allocation descriptors; short allocation descriptors refer to the file entry's partition
+    pub partition_reference: Option<usize>,
+}
+
+/// Parses a list of short or long allocation descriptors; returns the size of each allocation descriptor, and the parsed extents
+pub fn parse_udf_allocation_descriptors(
+    allocation_descriptors: &[u8],
+    allocation_type: &UDFAllocationType,
+) -> Result<(usize, Vec<UDFExtent>), StructureError> {
+    const SHORT_AD_SIZE: usize = 8;
+    const LONG_AD_SIZE: usize = 16;
+    const EXTENT_LENGTH_MASK: usize = 0x3FFFFFFF;
+    const EXTENT_TYPE_SHIFT: usize = 30;
+
+    let short_ad_structure = vec![("extent_length", "u32"), ("extent_position", "u32")];
+
+    let ad_size = match allocation_type {
+        UDFAllocationType::Short => SHORT_AD_SIZE,
+        UDFAllocationType::Long => LONG_AD_SIZE,
+        _ => return Err(StructureError),
+    };
+
+    let mut extents: Vec<UDFExtent> = vec![];
+
+    for ad_data in allocation_descriptors.chunks_exact(ad_size) {
+        let extent = match allocation_type {
+            UDFAllocationType::Short => {
+                let short_ad = common::parse(ad_data, &short_ad_structure, "little")?;
+                UDFExtent {
+                    length: short_ad["extent_length"],
+                    block: short_ad["extent_position"],
+                    ..Default::default()
+                }
+            }
+            _ => {
+                let long_ad = parse_udf_long_ad(ad_data)?;
+                UDFExtent {
+                    length: long_ad.length,
+                    block: long_ad.block,
+                    partition_reference: Some(long_ad.partition_reference),
+                    ..Default::default()
+                }
+            }
+        };
+
+        // A zero length extent terminates the allocation descriptors
+        if extent.length == 0 {
+            break;
+        }
+
+        extents.push(UDFExtent {
+            extent_type: extent.length >> EXTENT_TYPE_SHIFT,
+            length: extent.length & EXTENT_LENGTH_MASK,
+            ..extent
+        });
+    }
+
+    Ok((ad_size, extents))
+}
+
+/// Stores info about a UDF file identifier descriptor, which is a directory entry
+#[derive(Debug, Default, Clone)]
+pub struct UDFFileIdentifier {
+    /// Size of the file identifier descriptor, including padding
+    pub size: usize,
+    pub deleted: bool,
+    /// Set for the directory's parent directory entry
+    pub parent: bool,
+    /// Location of the file's ICB
+    pub icb: UDFLongAD,
+    pub name: String,
+}
+
+/// Parses a UDF file identifier descriptor
+pub fn parse_udf_file_identifier(
+    descriptor_data: &[u8],
+) -> Result<UDFFileIdentifier, StructureError> {
+    const DELETED: usize = 0x04;
+    const PARENT: usize = 0x08;
+    const ICB_OFFSET: usize = 20;
+
+    let file_identifier_structure = vec![
+        ("file_version_number", "u16"),
+        ("file_characteristics", "u8"),
+        ("file_identifier_length", "u8"),
+        ("icb", "u64"),
+        ("icb_partition_reference", "u16"),
+        ("icb_implementation_use", "u32"),
+        ("icb_implementation_use2", "u16"),
+        ("implementation_use_length", "u16"),
+    ];
+
+    if parse_udf_tag(descriptor_data, None)? != UDF_TAG_FILE_IDENTIFIER_DESCRIPTOR {
+        return Err(StructureError);
+    }
+
+    let file_identifier = common::parse(
+        &descriptor_data[UDF_TAG_SIZE..],
+        &file_identifier_structure,
+        "little",
+    )?;
+
+    let name_start = UDF_TAG_SIZE
+        + common::size(&file_identifier_structure)
+        + file_identifier["implementation_use_length"];
+    let name_end = name_start + file_identifier["file_identifier_length"];
+
+    // File identifier descriptors are padded to a multiple of 4 bytes
+    let size = name_end.div_ceil(4) * 4;
+
+    let name_data = descriptor_data
+        .get(name_start..name_end)
+        .ok_or(StructureError)?;
+
+    Ok(UDFFileIdentifier {
+        size,
+        deleted: file_identifier["file_characteristics"] & DELETED != 0,
+        parent: file_identifier["file_characteristics"] & PARENT != 0,
+        icb: parse_udf_long_ad(&descriptor_data[ICB_OFFSET..])?,
+        name: parse_udf_dchars(name_data),
+    })
+}
+
+/// Decodes a UDF compressed unicode string; the first byte specifies whether characters are 8 or 16 bits wide
+pub fn parse_udf_dchars(dchars: &[u8]) -> String {
+    match dchars.split_first() {
+        Some((8, characters)) | Some((254, characters)) => {
+            characters.iter().map(|byte| *byte as char).collect()
+        }
+        Some((16, characters)) | Some((255, characters)) => String::from_utf16_lossy(
+            &characters
+                .chunks_exact(2)
+                .map(|character| u16::from_be_bytes([character[0], character[1]]))
+                .collect::<Vec<u16>>(),
+        ),
+        _ => "".to_string(),
+    }
+}
+
+/// Decodes a fixed length UDF string field, whose last byte is the length of the string
+pub fn parse_udf_dstring(dstring: &[u8]) -> String {
+    match dstring.split_last() {
+        Some((length, characters)) => {
+            parse_udf_dchars(characters.get(..*length as usize).unwrap_or_default())
+        }
+        None => "".to_string(),
+    }
+}
+
+/// Stores info about a UDF volume, gathered from its volume recognition sequence and volume descriptors
+#[derive(Debug, Default, Clone)]
+pub struct UDFVolume {
+    pub sector_size: usize,
+    pub total_size: usize,
+    pub logical_volume: UDFLogicalVolume,
+    pub partitions: Vec<UDFPartition>,
+}
+
+/// Parses the volume recognition sequence and main volume descriptor sequence of a UDF image
+pub fn parse_udf_volume(udf_data: &[u8]) -> Result<UDFVolume, StructureError> {
+    // The volume recognition sequence must contain a NSR descriptor, which identifies the volume as UDF
+    const NSR_IDENTIFIERS: [&[u8]; 2] = [b"\x00NSR02\x01", b"\x00NSR03\x01"];
+    const MAX_VOLUME_RECOGNITION_DESCRIPTORS: usize = 8;
+    // Sector sizes to check for an anchor volume descriptor pointer, most common first
+    const SECTOR_SIZES: [usize; 4] = [2048, 512, 1024, 4096];
+    // A second anchor is located either at the last sector of the volume, or 256 sectors before the last sector
+    const MAX_TRAILING_ANCHOR_DISTANCE: usize = 257;
+    const MAX_VOLUME_DESCRIPTORS: usize = 64;
+
+    let nsr_descriptor_found = (0..MAX_VOLUME_RECOGNITION_DESCRIPTORS).any(|i| {
+        let descriptor_offset =
+            UDF_VOLUME_RECOGNITION_OFFSET + (i * UDF_VOLUME_RECOGNITION_DESCRIPTOR_SIZE);
+        udf_data.get(descriptor_offset..).is_some_and(|descriptor| {
+            NSR_IDENTIFIERS
+                .iter()
+                .any(|nsr_identifier| descriptor.starts_with(nsr_identifier))
+        })
+    });
+
+    if !nsr_descriptor_found {
+        return Err(StructureError);
+    }
+
+    for sector_size in SECTOR_SIZES {
+        let anchor = match udf_data
+            .get(UDF_ANCHOR_SECTOR * sector_size..)
+            .and_then(|anchor_data| parse_udf_anchor(anchor_data, UDF_ANCHOR_SECTOR).ok())
+        {
+            None => continue,
+            Some(anchor) => anchor,
+        };
+
+        let mut volume = UDFVolume {
+            sector_size,
+            ..Default::default()
+        };
+
+        let mut logical_volume: Option<UDFLogicalVolume> = None;
+        let main_sequence_sectors = std::cmp::min(
+            anchor.main_sequence_length / sector_size,
+            MAX_VOLUME_DESCRIPTORS,
+        );
+
+        for i in 0..main_sequence_sectors {
+            let sector = anchor.main_sequence_location + i;
+
+            let descriptor_data = match udf_data.get(sector * sector_size..) {
+                None => break,
+                Some(descriptor_data) => descriptor_data,
+            };
+
+            match parse_udf_tag(descriptor_data, Some(sector)) {
+                Err(_) | Ok(UDF_TAG_TERMINATING_DESCRIPTOR) => break,
+                Ok(UDF_TAG_PARTITION_DESCRIPTOR) => {
+                    volume
+                        .partitions
+                        .push(parse_udf_partition_descriptor(descriptor_data)?);
+                }
+                Ok(UDF_TAG_LOGICAL_VOLUME_DESCRIPTOR) => {
+                    logical_volume = Some(parse_udf_logical_volume_descriptor(descriptor_data)?);
+                }
+                Ok(_) => (),
+            }
+        }
+
+        if let Some(logical_volume) = logical_volume {
+            if logical_volume.block_size == sector_size && !volume.partitions.is_empty() {
+                volume.logical_volume = logical_volume;
+
+                // The volume ends after the last of its partitions and volume descriptor sequences
+                let mut volume_sectors = std::cmp::max(
+                    anchor.main_sequence_location + main_sequence_sectors,
+                    anchor.reserve_sequence_location
+                        + (anchor.reserve_sequence_length / sector_size),
+                );
+
+                for partition in &volume.partitions {
+                    volume_sectors =
+                        std::cmp::max(volume_sectors, partition.start + partition.length);
+                }
+
+                // ...or at its trailing anchor volume descriptor pointer, if there is one
+                let trailing_anchor_search_start = volume_sectors;
+
+                for sector in trailing_anchor_search_start
+                    ..trailing_anchor_search_start + MAX_TRAILING_ANCHOR_DISTANCE
+                {
+                    match udf_data.get(sector * sector_size..) {
+                        None => break,
+                        Some(anchor_data) => {
+                            if parse_udf_anchor(anchor_data, sector).is_ok() {
+                                volume_sectors = sector + 1;
+                            }
+                        }
+                    }
+                }
+
+                volume.total_size = volume_sectors * sector_size;
+                return Ok(volume);
+            }
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// Parses the path components of a UDF symlink, returning the symlink target path
+pub fn parse_udf_path_components(symlink_data: &[u8]) -> Result<String, StructureError> {
+    const ROOT: usize = 1;
+    const ROOT_ALIAS: usize = 2;
+    const PARENT: usize = 3;
+    const CURRENT: usize = 4;
+    const NAMED: usize = 5;
+
+    let component_structure = vec![
+        ("component_type", "u8"),
+        ("identifier_length", "u8"),
+        ("component_file_version_number", "u16"),
+    ];
+
+    let header_size = common::size(&component_structure);
+    let mut components: Vec<String> = vec![];
+    let mut component_offset: usize = 0;
+
+    while component_offset < symlink_data.len() {
+        let component = common::parse(
+            &symlink_data[component_offset..],
+            &component_structure,
+            "little",
+        )?;
+
+        let identifier_start = component_offset + header_size;
+        let identifier_end = identifier_start + component["identifier_length"];
+
+        match component["component_type"] {
+            // The root directory can only be the first component
+            ROOT | ROOT_ALIAS if components.is_empty() => components.push("".to_string()),
+            PARENT => components.push("..".to_string()),
+            CURRENT => components.push(".".to_string()),
+            NAMED => components.push(parse_udf_dchars(
+                symlink_data
+                    .get(identifier_start..identifier_end)
+                    .ok_or(StructureError)?,
+            )),
+            _ => return Err(StructureError),
+        }
+
+        component_offset = identifier_end;
+    }
+
+    match components.as_slice() {
+        [] => Err(StructureError),
+        [root] if root.is_empty() => Ok("/".to_string()),
+        _ => Ok(components.join("/")),
+    }
+}
@@ -6,6 +6,18 @@ pub struct UEFIVolumeHeader {
     pub header_crc: usize,
     pub header_size: usize,
     pub volume_size: usize,
+    /// GUID identifying the volume's file system
+    pub file_system_guid: String,
+    /// Name of the volume's file system, or an empty string if the file system GUID is not known
+    pub file_system: String,
+    /// True if the volume's file system is a firmware file system, whose files can be walked
+    pub is_ffs: bool,
+    /// True if the file system is FFSv3, which supports files larger than 16MB
+    pub is_ffs3: bool,
+    /// If true, erased bytes are 0xFF, else they are 0x00
+    pub erase_polarity: bool,
+    /// Offset of the first file in the volume, relative to the start of the volume
+    pub files_offset: usize,
 }
 
 /// Parse a UEFI volume header
@@ -13,7 +25,16 @@ pub fn parse_uefi_volume_header(uefi_data: &[u8]) -> Result<UEFIVolumeHeader, St
     // The revision field must be 1 or 2
     let valid_revisions: Vec<usize> = vec![1, 2];
 
+    const FILE_SYSTEM_GUID_START: usize = 16;
+    const FILE_SYSTEM_GUID_END: usize = 32;
+    const ERASE_POLARITY: usize = 0x800;
+    const FILE_ALIGNMENT: usize = 8;
+
     let uefi_pi_header_structure = vec![
+        ("zero_vector_p1", "u64"),
+        ("zero_vector_p2", "u64"),
+        ("file_system_guid_p1", "u64"),
+        ("file_system_guid_p2", "u64"),
         ("volume_size", "u64"),
         ("magic", "u32"),
         ("attributes", "u32"),
@@ -24,28 +45,252 @@ pub fn parse_uefi_volume_header(uefi_data: &[u8]) -> Result<UEFIVolumeHeader, St
         ("revision", "u8"),
     ];
 
+    let extended_header_structure = vec![
+        ("volume_name_p1", "u64"),
+        ("volume_name_p2", "u64"),
+        ("extended_header_size", "u32"),
+    ];
+
     // Parse the volume header
     if let Ok(uefi_volume_header) = common::parse(uefi_data, &uefi_pi_header_structure, "little") {
-        // Make sure the header size is sane (must be smaller than the total volume size)
-        if uefi_volume_header["header_size"] < uefi_volume_header["volume_size"] {
+        // Make sure the header size is sane (must be at least as large as the header structure, and smaller than the total volume size)
+        if uefi_volume_header["header_size"] >= common::size(&uefi_pi_header_structure)
+            && uefi_volume_header["header_size"] < uefi_volume_header["volume_size"]
+        {
             // The reserved field *must* be 0
             if uefi_volume_header["reserved"] == 0 {
                 // The revision number must be 1 or 2
                 if valid_revisions.contains(&uefi_volume_header["revision"]) {
-                    return Ok(UEFIVolumeHeader {
-                        // TODO: Validate UEFI header CRC
-                        header_crc: uefi_volume_header["header_crc"],
-                        header_size: uefi_volume_header["header_size"],
-                        volume_size: uefi_volume_header["volume_size"],
-                    });
+                    // The 16-bit sum of the entire header, including the checksum field, must be 0
+                    if let Some(header_data) = uefi_data.get(0..uefi_volume_header["header_size"]) {
+                        if header_data.chunks_exact(2).fold(0u16, |sum, word| {
+                            sum.wrapping_add(u16::from_le_bytes([word[0], word[1]]))
+                        }) == 0
+                        {
+                            let file_system_guid = guid_to_string(
+                                &uefi_data[FILE_SYSTEM_GUID_START..FILE_SYSTEM_GUID_END],
+                            );
+
+                            let (file_system, is_ffs, is_ffs3) = match file_system_guid.as_str() {
+                                "7A9354D9-0468-444A-81CE-0BF617D890DF" => ("FFSv1", true, false),
+                                "8C8CE578-8A3D-4F1C-9935-896185C32DD3" => ("FFSv2", true, false),
+                                "5473C07A-3DCB-4DCA-BD6F-1E9689E7349A" => ("FFSv3", true, true),
+                                "FFF12B8D-7696-4C8B-A985-2747075B4F50" => ("NVRAM", false, false),
+                                "04ADEEAD-61FF-4D31-B6BA-64F8BF901F5A" => {
+                                    ("Apple boot volume", true, false)
+                                }
+                                "CD17FF5E-DB4B-4A58-AF85-DE99AE6AB51C" => {
+                                    ("Apple boot volume", true, false)
+                                }
+                                _ => ("", false, false),
+                            };
+
+                            // Files start after the extended header, if there is one, else after the volume header
+                            let mut files_offset = uefi_volume_header["header_size"];
+
+                            if uefi_volume_header["extended_header_offset"] != 0 {
+                                match common::parse(
+                                    uefi_data
+                                        .get(uefi_volume_header["extended_header_offset"]..)
+                                        .unwrap_or_default(),
+                                    &extended_header_structure,
+                                    "little",
+                                ) {
+                                    Err(_) => return Err(StructureError),
+                                    Ok(extended_header) => {
+                                        files_offset = uefi_volume_header["extended_header_offset"]
+                                            + extended_header["extended_header_size"];
+                                    }
+                                }
+                            }
+
+                            return Ok(UEFIVolumeHeader {
+                                header_crc: uefi_volume_header["header_crc"],
+                                header_size: uefi_volume_header["header_size"],
+                                volume_size: uefi_volume_header["volume_size"],
+                                file_system_guid,
+                                file_system: file_system.to_string(),
+                                is_ffs,
+                                is_ffs3,
+                                erase_polarity: (uefi_volume_header["attributes"] & ERASE_POLARITY)
+                                    != 0,
+                                files_offset: files_offset.next_multiple_of(FILE_ALIGNMENT),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about a firmware file system file header
+#[derive(Debug, Default, Clone)]
+pub struct FFSFileHeader {
+    /// GUID identifying the file
+    pub guid: String,
+    pub file_type: usize,
+    pub header_size: usize,
+    /// Size of the file, including the header
+    pub size: usize,
+    /// True if the file's state indicates that its data is valid and it has not been deleted
+    pub is_valid: bool,
+}
+
+/// Parse a firmware file system file header.
+/// Returns an error if the header is erased, i.e. if it is in the free space at the end of a volume.
+pub fn parse_ffs_file_header(
+    ffs_data: &[u8],
+    erase_polarity: bool,
+    is_ffs3: bool,
+) -> Result<FFSFileHeader, StructureError> {
+    const GUID_SIZE: usize = 16;
+    const LARGE_FILE_ATTRIBUTE: usize = 0x01;
+
+    // File state bits
+    const FILE_DATA_VALID: usize = 0x04;
+    const FILE_DELETED: usize = 0x10;
+    const FILE_HEADER_INVALID: usize = 0x20;
+
+    let ffs_file_structure = vec![
+        ("guid_p1", "u64"),
+        ("guid_p2", "u64"),
+        ("integrity_check", "u16"),
+        ("type", "u8"),
+        ("attributes", "u8"),
+        ("size", "u24"),
+        ("state", "u8"),
+    ];
+
+    let ffs_large_file_structure = vec![("extended_size", "u64")];
+
+    let erased_byte: u8 = match erase_polarity {
+        true => 0xFF,
+        false => 0,
+    };
+
+    let header_size = common::size(&ffs_file_structure);
+
+    if let Some(header_data) = ffs_data.get(0..header_size) {
+        // Erased headers mark the start of free space
+        if header_data.iter().all(|b| *b == erased_byte) {
+            return Err(StructureError);
+        }
+
+        if let Ok(ffs_header) = common::parse(ffs_data, &ffs_file_structure, "little") {
+            let mut file_header = FFSFileHeader {
+                guid: guid_to_string(&ffs_data[0..GUID_SIZE]),
+                file_type: ffs_header["type"],
+                header_size,
+                size: ffs_header["size"],
+                ..Default::default()
+            };
+
+            // State bits are inverted in volumes with an erase polarity of 1
+            let state = match erase_polarity {
+                true => !ffs_header["state"] & 0xFF,
+                false => ffs_header["state"],
+            };
+
+            file_header.is_valid = (state & FILE_DATA_VALID) != 0
+                && (state & (FILE_DELETED | FILE_HEADER_INVALID)) == 0;
+
+            // Large files store their size in an extended header
+            if is_ffs3 && (ffs_header["attributes"] & LARGE_FILE_ATTRIBUTE) != 0 {
+                match common::parse(
+                    ffs_data.get(header_size..).unwrap_or_default(),
+                    &ffs_large_file_structure,
+                    "little",
+                ) {
+                    Err(_) => return Err(StructureError),
+                    Ok(large_file_header) => {
+                        file_header.header_size += common::size(&ffs_large_file_structure);
+                        file_header.size = large_file_header["extended_size"];
+                    }
+                }
+            }
+
+            if file_header.size >= file_header.header_size {
+                return Ok(file_header);
+            }
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about a firmware file system section header
+#[derive(Debug, Default, Clone)]
+pub struct FFSSectionHeader {
+    pub section_type: usize,
+    pub header_size: usize,
+    /// Size of the section, including the header
+    pub size: usize,
+}
+
+/// Parse a firmware file system section header
+pub fn parse_ffs_section_header(section_data: &[u8]) -> Result<FFSSectionHeader, StructureError> {
+    // Sections larger than 16MB store their size in an extended header
+    const EXTENDED_SIZE: usize = 0xFFFFFF;
+
+    let section_structure = vec![("size", "u24"), ("type", "u8")];
+    let extended_section_structure =
+        vec![("size", "u24"), ("type", "u8"), ("extended_size", "u32")];
+
+    if let Ok(section_header) = common::parse(section_data, &section_structure, "little") {
+        let mut header = FFSSectionHeader {
+            section_type: section_header["type"],
+            header_size: common::size(&section_structure),
+            size: section_header["size"],
+        };
+
+        if header.size == EXTENDED_SIZE {
+            match common::parse(section_data, &extended_section_structure, "little") {
+                Err(_) => return Err(StructureError),
+                Ok(extended_header) => {
+                    header.header_size = common::size(&extended_section_structure);
+                    header.size = extended_header["extended_size"];
                 }
             }
         }
+
+        if header.size >= header.header_size {
+            return Ok(header);
+        }
     }
 
     Err(StructureError)
 }
 
+/// Converts a GUID, as stored in UEFI structures, to its string representation
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::uefi::guid_to_string;
+///
+/// let guid = b"\x78\xE5\x8C\x8C\x3D\x8A\x1C\x4F\x99\x35\x89\x61\x85\xC3\x2D\xD3";
+///
+/// assert_eq!(guid_to_string(guid), "8C8CE578-8A3D-4F1C-9935-896185C32DD3");
+/// ```
+pub fn guid_to_string(guid: &[u8]) -> String {
+    const GUID_SIZE: usize = 16;
+
+    match guid.get(0..GUID_SIZE) {
+        None => "".to_string(),
+        Some(guid) => format!(
+            "{:08X}-{:04X}-{:04X}-{}-{}",
+            u32::from_le_bytes([guid[0], guid[1], guid[2], guid[3]]),
+            u16::from_le_bytes([guid[4], guid[5]]),
+            u16::from_le_bytes([guid[6], guid[7]]),
+            hex::encode_upper(&guid[8..10]),
+            hex::encode_upper(&guid[10..16])
+        ),
+    }
+}
+
 /// Stores info about a UEFI capsule header
 #[derive(Debug, Default, Clone)]
 pub struct UEFICapsuleHeader {
@@ -0,0 +1,216 @@
+use crate::structures::common::{self, StructureError};
+
+/// Stores info about a WebAssembly module
+#[derive(Debug, Default, Clone)]
+pub struct WASMModule {
+    pub version: usize,
+    pub size: usize,
+    pub section_count: usize,
+    /// Names of all sections in the module, in the order they appear; custom sections are reported by their custom name
+    pub section_names: Vec<String>,
+    pub import_count: usize,
+    pub export_count: usize,
+    /// Names of the module's exports (functions, tables, memories, globals and tags)
+    pub export_names: Vec<String>,
+    pub function_count: usize,
+}
+
+/// Parses a WebAssembly binary module to determine its size
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::wasm::parse_wasm_module;
+///
+/// let wasm_module = parse_wasm_module(b"\x00asm\x01\x00\x00\x00\x07\x0A\x01\x06memory\x02\x00").unwrap();
+///
+/// assert_eq!(wasm_module.section_names, vec!["export"]);
+/// assert_eq!(wasm_module.export_names, vec!["memory"]);
+/// ```
+pub fn parse_wasm_module(wasm_data: &[u8]) -> Result<WASMModule, StructureError> {
+    const MAGIC: usize = 0x6D736100;
+    const SUPPORTED_VERSION: usize = 1;
+    const CUSTOM_SECTION_ID: u8 = 0;
+    const IMPORT_SECTION_ID: u8 = 2;
+    const FUNCTION_SECTION_ID: u8 = 3;
+    const EXPORT_SECTION_ID: u8 = 7;
+
+    /*
+     * Non-custom sections must appear in a well defined order (which is not numerical order).
+     * This lists section IDs in the order they are required to appear.
+     */
+    const SECTION_ORDER: [u8; 13] = [1, 2, 3, 4, 5, 13, 6, 7, 8, 9, 12, 10, 11];
+
+    let wasm_structure = vec![("magic", "u32"), ("version", "u32")];
+
+    let wasm_header = common::parse(wasm_data, &wasm_structure, "little")?;
+
+    if wasm_header["magic"] != MAGIC || wasm_header["version"] != SUPPORTED_VERSION {
+        return Err(StructureError);
+    }
+
+    let mut module = WASMModule {
+        version: wasm_header["version"],
+        size: common::size(&wasm_structure),
+        ..Default::default()
+    };
+
+    let mut last_section_rank: Option<usize> = None;
+
+    // Sections continue until EOF; stop at the first thing that doesn't look like a valid section
+    while let Some(section_id) = wasm_data.get(module.size) {
+        if *section_id != CUSTOM_SECTION_ID {
+            match SECTION_ORDER.iter().position(|id| id == section_id) {
+                None => break,
+                Some(rank) => {
+                    if let Some(last_rank) = last_section_rank {
+                        if rank <= last_rank {
+                            break;
+                        }
+                    }
+                    last_section_rank = Some(rank);
+                }
+            }
+        }
+
+        let (section_size, leb_size) = match parse_leb128_u32(&wasm_data[module.size + 1..]) {
+            Err(_) => break,
+            Ok(leb) => leb,
+        };
+
+        let section_start = module.size + 1 + leb_size;
+        let section_end = section_start + section_size;
+
+        if section_end > wasm_data.len() {
+            break;
+        }
+
+        let section_data = &wasm_data[section_start..section_end];
+
+        match *section_id {
+            CUSTOM_SECTION_ID => {
+                // Custom sections start with their name; a custom section without a name is not valid
+                match parse_wasm_name(section_data) {
+                    Err(_) => break,
+                    Ok(custom_name) => module.section_names.push(format!("custom:{custom_name}")),
+                }
+            }
+            _ => {
+                module
+                    .section_names
+                    .push(wasm_section_name(*section_id).to_string());
+
+                // Import, function, and export sections all start with a count of their entries
+                if let Ok((entry_count, _)) = parse_leb128_u32(section_data) {
+                    match *section_id {
+                        IMPORT_SECTION_ID => module.import_count = entry_count,
+                        FUNCTION_SECTION_ID => module.function_count = entry_count,
+                        EXPORT_SECTION_ID => {
+                            module.export_count = entry_count;
+                            module.export_names = parse_wasm_exports(section_data);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        module.size = section_end;
+        module.section_count += 1;
+    }
+
+    if module.section_count == 0 {
+        return Err(StructureError);
+    }
+
+    Ok(module)
+}
+
+/// Parses the names of the entries in an export section; stops at the first entry that can't be parsed
+fn parse_wasm_exports(section_data: &[u8]) -> Vec<String> {
+    // Each export is a name, followed by a one byte export kind and the LEB128 encoded index of the exported item
+    const EXPORT_KIND_SIZE: usize = 1;
+
+    let mut export_names: Vec<String> = vec![];
+
+    if let Ok((export_count, leb_size)) = parse_leb128_u32(section_data) {
+        let mut offset = leb_size;
+
+        for _ in 0..export_count {
+            let export_data = match section_data.get(offset..) {
+                None => break,
+                Some(export_data) => export_data,
+            };
+
+            let (name_length, name_leb_size) = match parse_leb128_u32(export_data) {
+                Err(_) => break,
+                Ok(leb) => leb,
+            };
+
+            let name = match parse_wasm_name(export_data) {
+                Err(_) => break,
+                Ok(name) => name,
+            };
+
+            let index_offset = name_leb_size + name_length + EXPORT_KIND_SIZE;
+            let index_size = match export_data.get(index_offset..).map(parse_leb128_u32) {
+                Some(Ok((_, index_size))) => index_size,
+                _ => break,
+            };
+
+            export_names.push(name);
+            offset += index_offset + index_size;
+        }
+    }
+
+    export_names
+}
+
+/// Returns the name of a non-custom WebAssembly section
+fn wasm_section_name(section_id: u8) -> &'static str {
+    match section_id {
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "data count",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
+
+/// Parses a length-prefixed WebAssembly name
+fn parse_wasm_name(name_data: &[u8]) -> Result<String, StructureError> {
+    let (name_length, leb_size) = parse_leb128_u32(name_data)?;
+    let name_bytes = name_data
+        .get(leb_size..leb_size + name_length)
+        .ok_or(StructureError)?;
+
+    Ok(String::from_utf8_lossy(name_bytes).to_string())
+}
+
+/// Parses an unsigned LEB128 encoded u32 value; returns the value and the number of bytes it occupied
+fn parse_leb128_u32(leb_data: &[u8]) -> Result<(usize, usize), StructureError> {
+    const MAX_LEB_SIZE: usize = 5;
+    const CONTINUE_BIT: u8 = 0x80;
+    const VALUE_MASK: u8 = 0x7F;
+
+    let mut value: usize = 0;
+
+    for (i, byte) in leb_data.iter().take(MAX_LEB_SIZE).enumerate() {
+        value |= ((byte & VALUE_MASK) as usize) << (7 * i);
+
+        if (byte & CONTINUE_BIT) == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+
+    Err(StructureError)
+}
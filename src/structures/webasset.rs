@@ -0,0 +1,112 @@
+use crate::common::is_text;
+use crate::structures::common::StructureError;
+
+/// Types of web assets commonly found in device UI partitions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WebAssetType {
+    Html,
+    JavaScript,
+    Css,
+}
+
+impl WebAssetType {
+    /// Human readable name of the web asset type
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebAssetType::Html => "HTML",
+            WebAssetType::JavaScript => "JavaScript",
+            WebAssetType::Css => "CSS",
+        }
+    }
+}
+
+/// Identifies the type of web asset from the start of its text.
+/// The provided data need only be the first few hundred bytes of the web asset.
+pub fn parse_web_asset_text(asset_data: &[u8]) -> Result<WebAssetType, StructureError> {
+    const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+    const HTML_PREFIXES: [&str; 3] = ["<!doctype html", "<html", "<head"];
+    const JS_PREFIXES: [&str; 8] = [
+        "!function(",
+        "(function(",
+        "\"use strict\"",
+        "'use strict'",
+        "(window.webpackjsonp",
+        "(self.webpackchunk",
+        "var ",
+        "define(",
+    ];
+    const CSS_PREFIXES: [&str; 4] = ["@charset", "@import", "@font-face", ":root{"];
+
+    let text_data = asset_data.strip_prefix(UTF8_BOM).unwrap_or(asset_data);
+
+    if text_data.is_empty() || !text_data.iter().all(|b| is_text(*b)) {
+        return Err(StructureError);
+    }
+
+    let text = String::from_utf8_lossy(text_data)
+        .trim_start()
+        .to_lowercase();
+
+    if HTML_PREFIXES.iter().any(|prefix| text.starts_with(prefix)) {
+        return Ok(WebAssetType::Html);
+    }
+
+    if JS_PREFIXES.iter().any(|prefix| text.starts_with(prefix)) {
+        return Ok(WebAssetType::JavaScript);
+    }
+
+    if CSS_PREFIXES.iter().any(|prefix| text.starts_with(prefix)) {
+        return Ok(WebAssetType::Css);
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about a block of minified JavaScript
+#[derive(Debug, Default, Clone)]
+pub struct MinifiedScript {
+    /// Offset of the start of the script, relative to the provided data
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// Given the offset of some JavaScript code, locates the start and end of the surrounding script text,
+/// and validates that it appears to be minified.
+pub fn parse_minified_script(
+    script_data: &[u8],
+    code_offset: usize,
+) -> Result<MinifiedScript, StructureError> {
+    // Minified scripts tend to be large
+    const MIN_SCRIPT_SIZE: usize = 1024;
+    // Don't walk backwards too far looking for the start of the script (e.g., leading license comments)
+    const MAX_PREAMBLE_SIZE: usize = 64 * 1024;
+    // Minified code has very long lines
+    const MIN_AVERAGE_LINE_LENGTH: usize = 200;
+
+    let mut script_start = code_offset;
+
+    while script_start > 0
+        && (code_offset - script_start) < MAX_PREAMBLE_SIZE
+        && is_text(script_data[script_start - 1])
+    {
+        script_start -= 1;
+    }
+
+    let script_end = match script_data[code_offset..].iter().position(|b| !is_text(*b)) {
+        Some(text_size) => code_offset + text_size,
+        None => script_data.len(),
+    };
+
+    let script = &script_data[script_start..script_end];
+    let line_count = script.iter().filter(|b| **b == b'\n').count() + 1;
+
+    if script.len() < MIN_SCRIPT_SIZE || (script.len() / line_count) < MIN_AVERAGE_LINE_LENGTH {
+        return Err(StructureError);
+    }
+
+    Ok(MinifiedScript {
+        offset: script_start,
+        size: script.len(),
+    })
+}
@@ -0,0 +1,408 @@
+use crate::structures::common::StructureError;
+use crate::structures::pkcs::parse_rsa_public_key;
+
+/// DER tags
+pub const DER_INTEGER: u8 = 0x02;
+pub const DER_BIT_STRING: u8 = 0x03;
+pub const DER_OCTET_STRING: u8 = 0x04;
+pub const DER_OID: u8 = 0x06;
+pub const DER_SEQUENCE: u8 = 0x30;
+pub const DER_SET: u8 = 0x31;
+pub const DER_CONTEXT_0: u8 = 0xA0;
+
+/// A DER encoded element
+#[derive(Debug, Default, Clone)]
+pub struct DERElement<'a> {
+    pub tag: u8,
+    /// The element's contents, not including the tag and length
+    pub contents: &'a [u8],
+    /// The entire encoded element, including the tag and length
+    pub encoded: &'a [u8],
+}
+
+/// Parses the DER element at the start of the data
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::x509::parse_der_element;
+///
+/// let element = parse_der_element(b"\x02\x03\x01\x00\x01\xFF").unwrap();
+///
+/// assert_eq!(element.tag, 0x02);
+/// assert_eq!(element.contents, b"\x01\x00\x01");
+/// assert_eq!(element.encoded.len(), 5);
+/// ```
+pub fn parse_der_element(data: &[u8]) -> Result<DERElement<'_>, StructureError> {
+    // Lengths of more than 4 bytes are not needed for anything found in firmware
+    const MAX_LENGTH_SIZE: usize = 4;
+    const LONG_LENGTH: u8 = 0x80;
+
+    let tag = *data.first().ok_or(StructureError)?;
+    let length_byte = *data.get(1).ok_or(StructureError)?;
+
+    let (header_size, length) = match length_byte & LONG_LENGTH {
+        0 => (2, length_byte as usize),
+        _ => {
+            // Indefinite lengths (0x80) are not valid DER
+            let length_size = (length_byte & !LONG_LENGTH) as usize;
+
+            if length_size == 0 || length_size > MAX_LENGTH_SIZE {
+                return Err(StructureError);
+            }
+
+            let length_bytes = data.get(2..2 + length_size).ok_or(StructureError)?;
+
+            // DER lengths are encoded in as few bytes as possible
+            if length_bytes[0] == 0 {
+                return Err(StructureError);
+            }
+
+            (
+                2 + length_size,
+                length_bytes
+                    .iter()
+                    .fold(0_usize, |length, b| (length << 8) | *b as usize),
+            )
+        }
+    };
+
+    let encoded = data.get(..header_size + length).ok_or(StructureError)?;
+
+    Ok(DERElement {
+        tag,
+        contents: &encoded[header_size..],
+        encoded,
+    })
+}
+
+/// Parses all the elements inside a constructed element's contents, which must be consumed entirely
+pub fn parse_der_children(contents: &[u8]) -> Result<Vec<DERElement<'_>>, StructureError> {
+    let mut children: Vec<DERElement> = vec![];
+    let mut offset: usize = 0;
+
+    while offset < contents.len() {
+        let child = parse_der_element(&contents[offset..])?;
+        offset += child.encoded.len();
+        children.push(child);
+    }
+
+    Ok(children)
+}
+
+/// Parses the constructed element at the start of the data, and returns its child elements
+fn parse_der_constructed(data: &[u8], tag: u8) -> Result<Vec<DERElement<'_>>, StructureError> {
+    let element = parse_der_element(data)?;
+
+    if element.tag != tag {
+        return Err(StructureError);
+    }
+
+    parse_der_children(element.contents)
+}
+
+/// Converts an encoded object identifier to its dotted decimal representation, e.g., "1.2.840.113549.1.1.11"
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::x509::oid_to_string;
+///
+/// assert_eq!(oid_to_string(b"\x2A\x86\x48\x86\xF7\x0D\x01\x01\x0B"), "1.2.840.113549.1.1.11");
+/// ```
+pub fn oid_to_string(oid: &[u8]) -> String {
+    let mut components: Vec<String> = vec![];
+    let mut value: usize = 0;
+
+    for b in oid {
+        value = (value << 7) | (*b & 0x7F) as usize;
+
+        // The high bit is set on all but the last byte of each component
+        if *b & 0x80 == 0 {
+            // The first encoded value holds the first two components
+            if components.is_empty() {
+                let first = std::cmp::min(value / 40, 2);
+                components.push(first.to_string());
+                components.push((value - (first * 40)).to_string());
+            } else {
+                components.push(value.to_string());
+            }
+
+            value = 0;
+        }
+    }
+
+    components.join(".")
+}
+
+/// Returns the name of well known object identifiers, or the dotted decimal OID if unknown
+pub fn oid_name(oid: &[u8]) -> String {
+    let dotted_oid = oid_to_string(oid);
+
+    let name = match dotted_oid.as_str() {
+        // Distinguished name attributes
+        "2.5.4.3" => "CN",
+        "2.5.4.5" => "serialNumber",
+        "2.5.4.6" => "C",
+        "2.5.4.7" => "L",
+        "2.5.4.8" => "ST",
+        "2.5.4.10" => "O",
+        "2.5.4.11" => "OU",
+        "1.2.840.113549.1.9.1" => "emailAddress",
+        // Public key algorithms
+        "1.2.840.113549.1.1.1" => "RSA",
+        "1.2.840.10040.4.1" => "DSA",
+        "1.2.840.10045.2.1" => "EC",
+        "1.3.101.112" => "Ed25519",
+        "1.3.101.113" => "Ed448",
+        // Elliptic curves
+        "1.2.840.10045.3.1.7" => "prime256v1",
+        "1.3.132.0.34" => "secp384r1",
+        "1.3.132.0.35" => "secp521r1",
+        "1.3.132.0.10" => "secp256k1",
+        // Signature algorithms
+        "1.2.840.113549.1.1.4" => "md5WithRSAEncryption",
+        "1.2.840.113549.1.1.5" => "sha1WithRSAEncryption",
+        "1.2.840.113549.1.1.10" => "RSASSA-PSS",
+        "1.2.840.113549.1.1.11" => "sha256WithRSAEncryption",
+        "1.2.840.113549.1.1.12" => "sha384WithRSAEncryption",
+        "1.2.840.113549.1.1.13" => "sha512WithRSAEncryption",
+        "1.2.840.10045.4.3.2" => "ecdsa-with-SHA256",
+        "1.2.840.10045.4.3.3" => "ecdsa-with-SHA384",
+        "1.2.840.10045.4.3.4" => "ecdsa-with-SHA512",
+        // Digest algorithms
+        "1.2.840.113549.2.5" => "MD5",
+        "1.3.14.3.2.26" => "SHA1",
+        "2.16.840.1.101.3.4.2.1" => "SHA256",
+        "2.16.840.1.101.3.4.2.2" => "SHA384",
+        "2.16.840.1.101.3.4.2.3" => "SHA512",
+        // PKCS#7 content types
+        "1.2.840.113549.1.7.1" => "data",
+        "1.2.840.113549.1.7.2" => "signedData",
+        "1.2.840.113549.1.7.3" => "envelopedData",
+        "1.2.840.113549.1.7.6" => "encryptedData",
+        _ => return dotted_oid,
+    };
+
+    name.to_string()
+}
+
+/// Decodes DER string types to a String
+fn der_string(element: &DERElement) -> Result<String, StructureError> {
+    const UTF8_STRING: u8 = 0x0C;
+    const PRINTABLE_STRING: u8 = 0x13;
+    const T61_STRING: u8 = 0x14;
+    const IA5_STRING: u8 = 0x16;
+    const BMP_STRING: u8 = 0x1E;
+
+    match element.tag {
+        UTF8_STRING | PRINTABLE_STRING | T61_STRING | IA5_STRING => {
+            Ok(String::from_utf8_lossy(element.contents).to_string())
+        }
+        BMP_STRING => {
+            let characters: Vec<u16> = element
+                .contents
+                .chunks_exact(2)
+                .map(|character| u16::from_be_bytes([character[0], character[1]]))
+                .collect();
+            Ok(String::from_utf16_lossy(&characters))
+        }
+        _ => Err(StructureError),
+    }
+}
+
+/// Formats an X.501 distinguished name, e.g., "C=US, O=Example, CN=example.com"
+fn der_name(element: &DERElement) -> Result<String, StructureError> {
+    let mut attributes: Vec<String> = vec![];
+
+    if element.tag != DER_SEQUENCE {
+        return Err(StructureError);
+    }
+
+    for relative_name in parse_der_children(element.contents)? {
+        if relative_name.tag != DER_SET {
+            return Err(StructureError);
+        }
+
+        for attribute in parse_der_children(relative_name.contents)? {
+            let attribute = parse_der_children(attribute.contents)?;
+
+            if attribute.len() != 2 || attribute[0].tag != DER_OID {
+                return Err(StructureError);
+            }
+
+            attributes.push(format!(
+                "{}={}",
+                oid_name(attribute[0].contents),
+                der_string(&attribute[1])?
+            ));
+        }
+    }
+
+    Ok(attributes.join(", "))
+}
+
+/// Formats a UTCTime or GeneralizedTime as "YYYY-MM-DD hh:mm:ss UTC"
+fn der_time(element: &DERElement) -> Result<String, StructureError> {
+    const UTC_TIME: u8 = 0x17;
+    const GENERALIZED_TIME: u8 = 0x18;
+
+    let time = String::from_utf8_lossy(element.contents).to_string();
+
+    // Two digit UTCTime years 50 - 99 are 1950 - 1999
+    let time = match element.tag {
+        UTC_TIME if time.len() == 13 => match time.get(..2).and_then(|y| y.parse::<usize>().ok()) {
+            Some(year) if year >= 50 => format!("19{time}"),
+            Some(_) => format!("20{time}"),
+            None => return Err(StructureError),
+        },
+        GENERALIZED_TIME if time.len() == 15 => time,
+        _ => return Err(StructureError),
+    };
+
+    if !time.ends_with('Z') || !time[..14].chars().all(|c| c.is_ascii_digit()) {
+        return Err(StructureError);
+    }
+
+    Ok(format!(
+        "{}-{}-{} {}:{}:{} UTC",
+        &time[0..4],
+        &time[4..6],
+        &time[6..8],
+        &time[8..10],
+        &time[10..12],
+        &time[12..14]
+    ))
+}
+
+/// Describes the algorithm and key size of a SubjectPublicKeyInfo, e.g., "RSA 2048 bits" or "EC prime256v1"
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::structures::x509::parse_public_key_info;
+///
+/// let mut public_key_info = b"\x30\x5C\x30\x0D\x06\x09\x2A\x86\x48\x86\xF7\x0D\x01\x01\x01\x05\x00".to_vec();
+/// public_key_info.extend(b"\x03\x4B\x00\x30\x48\x02\x41\x00\xC1");
+/// public_key_info.extend([0xAA; 63]);
+/// public_key_info.extend(b"\x02\x03\x01\x00\x01");
+///
+/// assert_eq!(parse_public_key_info(&public_key_info).unwrap(), "RSA 512 bits");
+/// ```
+pub fn parse_public_key_info(public_key_info: &[u8]) -> Result<String, StructureError> {
+    let fields = parse_der_constructed(public_key_info, DER_SEQUENCE)?;
+
+    if fields.len() != 2 || fields[1].tag != DER_BIT_STRING || fields[1].contents.is_empty() {
+        return Err(StructureError);
+    }
+
+    let algorithm = parse_der_constructed(fields[0].encoded, DER_SEQUENCE)?;
+
+    if algorithm.is_empty() || algorithm[0].tag != DER_OID {
+        return Err(StructureError);
+    }
+
+    let algorithm_name = oid_name(algorithm[0].contents);
+
+    // The first byte of the bit string is the number of unused bits
+    let public_key = &fields[1].contents[1..];
+
+    Ok(match algorithm_name.as_str() {
+        "RSA" => match parse_rsa_public_key(public_key) {
+            Ok(rsa_key) => format!("RSA {} bits", rsa_key.modulus_bits),
+            Err(_) => algorithm_name,
+        },
+        "EC" => match algorithm.get(1) {
+            Some(curve) if curve.tag == DER_OID => format!("EC {}", oid_name(curve.contents)),
+            _ => algorithm_name,
+        },
+        _ => algorithm_name,
+    })
+}
+
+/// Stores info about an X.509 certificate
+#[derive(Debug, Default, Clone)]
+pub struct X509Certificate {
+    /// Size of the DER encoded certificate
+    pub size: usize,
+    pub version: usize,
+    /// Serial number, in hex
+    pub serial: String,
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: String,
+    pub not_after: String,
+    pub signature_algorithm: String,
+    /// Public key algorithm and size, e.g., "RSA 2048 bits"
+    pub public_key: String,
+}
+
+impl X509Certificate {
+    /// Returns true if the certificate was issued by its own subject
+    pub fn is_self_signed(&self) -> bool {
+        self.subject == self.issuer
+    }
+}
+
+/// Parses a DER encoded X.509 certificate
+pub fn parse_x509_certificate(certificate_data: &[u8]) -> Result<X509Certificate, StructureError> {
+    const VERSION_1: usize = 1;
+    const MAX_VERSION: usize = 3;
+
+    let certificate_element = parse_der_element(certificate_data)?;
+    let certificate = parse_der_constructed(certificate_element.encoded, DER_SEQUENCE)?;
+
+    if certificate.len() != 3 || certificate[2].tag != DER_BIT_STRING {
+        return Err(StructureError);
+    }
+
+    let mut tbs_certificate = parse_der_constructed(certificate[0].encoded, DER_SEQUENCE)?;
+
+    // The version field is optional, and defaults to version 1
+    let mut version = VERSION_1;
+
+    if tbs_certificate
+        .first()
+        .is_some_and(|field| field.tag == DER_CONTEXT_0)
+    {
+        let version_field = tbs_certificate.remove(0);
+        let version_integer = parse_der_element(version_field.contents)?;
+
+        if version_integer.tag != DER_INTEGER || version_integer.contents.len() != 1 {
+            return Err(StructureError);
+        }
+
+        version = version_integer.contents[0] as usize + 1;
+    }
+
+    // serialNumber, signature, issuer, validity, subject, subjectPublicKeyInfo
+    if version > MAX_VERSION || tbs_certificate.len() < 6 || tbs_certificate[0].tag != DER_INTEGER {
+        return Err(StructureError);
+    }
+
+    let signature_algorithm = parse_der_constructed(certificate[1].encoded, DER_SEQUENCE)?;
+    let validity = parse_der_constructed(tbs_certificate[3].encoded, DER_SEQUENCE)?;
+
+    if signature_algorithm.is_empty()
+        || signature_algorithm[0].tag != DER_OID
+        || validity.len() != 2
+    {
+        return Err(StructureError);
+    }
+
+    Ok(X509Certificate {
+        size: certificate_element.encoded.len(),
+        version,
+        serial: tbs_certificate[0]
+            .contents
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect(),
+        issuer: der_name(&tbs_certificate[2])?,
+        not_before: der_time(&validity[0])?,
+        not_after: der_time(&validity[1])?,
+        subject: der_name(&tbs_certificate[4])?,
+        signature_algorithm: oid_name(signature_algorithm[0].contents),
+        public_key: parse_public_key_info(tbs_certificate[5].encoded)?,
+    })
+}
@@ -1,6 +1,9 @@
 use crate::common::crc32;
 use crate::structures::common::{self, StructureError};
 
+/// XZ stream header magic bytes
+pub const XZ_MAGIC: &[u8] = b"\xFD\x37\x7a\x58\x5a\x00";
+
 /// Parse and validate an XZ header, returns the header size
 pub fn parse_xz_header(xz_data: &[u8]) -> Result<usize, StructureError> {
     const XZ_CRC_END: usize = 8;
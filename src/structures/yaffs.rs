@@ -3,8 +3,9 @@ use crate::structures::common::{self, StructureError};
 /// Stores info about a YAFFS object
 #[derive(Debug, Default, Clone)]
 pub struct YAFFSObject {
-    // All that is needed for now is the object type; this may be updated in the future as necessary
     pub obj_type: usize,
+    pub parent_id: usize,
+    pub name: String,
 }
 
 /// Partially parse a YAFFS object header
@@ -14,6 +15,8 @@ pub fn parse_yaffs_obj_header(
 ) -> Result<YAFFSObject, StructureError> {
     // The name checksum field is unused and should be 0xFFFF
     const UNUSED: usize = 0xFFFF;
+    // Object names are NULL terminated, and immediately follow the first part of the object header
+    const MAX_NAME_LENGTH: usize = 256;
 
     // First part of an object header
     let yaffs_object_structure = vec![
@@ -32,8 +35,22 @@ pub fn parse_yaffs_obj_header(
             && (obj_header["parent_id"] > 0)
             && (obj_header["name_checksum"] == UNUSED)
         {
+            let name_start = common::size(&yaffs_object_structure);
+            let name_end = name_start + MAX_NAME_LENGTH;
+
+            let mut name = String::new();
+            if let Some(name_bytes) = header_data.get(name_start..name_end) {
+                let name_length = name_bytes
+                    .iter()
+                    .position(|b| *b == 0)
+                    .unwrap_or(MAX_NAME_LENGTH);
+                name = String::from_utf8_lossy(&name_bytes[..name_length]).to_string();
+            }
+
             return Ok(YAFFSObject {
                 obj_type: obj_header["type"],
+                parent_id: obj_header["parent_id"],
+                name,
             });
         }
     }
@@ -44,8 +61,14 @@ pub fn parse_yaffs_obj_header(
 /// Stores info about a YAFFS file header
 #[derive(Debug, Default, Clone)]
 pub struct YAFFSFileHeader {
-    // Only this field is needed, for now. Struct may be updated in the future if necessary.
+    pub mode: usize,
     pub file_size: usize,
+    /// Object ID of the hard link target
+    pub equiv_id: usize,
+    /// Symlink target
+    pub alias: String,
+    /// Device number, for device files
+    pub rdev: usize,
 }
 
 /// Partially parse a YAFFS file header
@@ -53,6 +76,11 @@ pub fn parse_yaffs_file_header(
     header_data: &[u8],
     endianness: &str,
 ) -> Result<YAFFSFileHeader, StructureError> {
+    // Symlink targets are NULL terminated
+    const MAX_ALIAS_LENGTH: usize = 160;
+    // Erased flash; file_size_high is not used by older versions of YAFFS
+    const UNUSED_FILE_SIZE_HIGH: usize = 0xFFFFFFFF;
+
     // Second part of an object header (after the name field)
     let yaffs_file_info = vec![
         ("mode", "u32"),
@@ -62,13 +90,108 @@ pub fn parse_yaffs_file_header(
         ("mtime", "u32"),
         ("ctime", "u32"),
         ("file_size", "u32"),
+        ("equiv_id", "u32"),
     ];
 
+    // Third part of an object header (after the alias field)
+    let yaffs_extended_info = vec![
+        ("rdev", "u32"),
+        ("win_ctime_low", "u32"),
+        ("win_ctime_high", "u32"),
+        ("win_atime_low", "u32"),
+        ("win_atime_high", "u32"),
+        ("win_mtime_low", "u32"),
+        ("win_mtime_high", "u32"),
+        ("inband_shadowed_obj_id", "u32"),
+        ("inband_is_shrink", "u32"),
+        ("file_size_high", "u32"),
+    ];
+
+    let alias_start = common::size(&yaffs_file_info);
+    let alias_end = alias_start + MAX_ALIAS_LENGTH;
+
     if let Ok(file_info) = common::parse(header_data, &yaffs_file_info, endianness) {
-        return Ok(YAFFSFileHeader {
-            file_size: file_info["file_size"],
-        });
+        if let Some(alias_bytes) = header_data.get(alias_start..alias_end) {
+            if let Ok(extended_info) =
+                common::parse(&header_data[alias_end..], &yaffs_extended_info, endianness)
+            {
+                let mut file_size = file_info["file_size"];
+                if extended_info["file_size_high"] != UNUSED_FILE_SIZE_HIGH {
+                    file_size |= extended_info["file_size_high"] << 32;
+                }
+
+                let alias_length = alias_bytes
+                    .iter()
+                    .position(|b| *b == 0)
+                    .unwrap_or(MAX_ALIAS_LENGTH);
+
+                return Ok(YAFFSFileHeader {
+                    mode: file_info["mode"],
+                    file_size,
+                    equiv_id: file_info["equiv_id"],
+                    alias: String::from_utf8_lossy(&alias_bytes[..alias_length]).to_string(),
+                    rdev: extended_info["rdev"],
+                });
+            }
+        }
     }
 
     Err(StructureError)
 }
+
+/// Stores info about YAFFS2 packed tags, stored in a chunk's spare data
+#[derive(Debug, Default, Clone)]
+pub struct YAFFSPackedTags {
+    pub sequence_number: usize,
+    pub obj_id: usize,
+    /// Chunk index in the object's data, starting at 1; object headers have a chunk ID of 0
+    pub chunk_id: usize,
+    pub byte_count: usize,
+}
+
+/// Parse YAFFS2 packed tags
+pub fn parse_yaffs_packed_tags(
+    tags_data: &[u8],
+    endianness: &str,
+) -> Result<YAFFSPackedTags, StructureError> {
+    // Valid block sequence numbers
+    const LOWEST_SEQUENCE_NUMBER: usize = 0x00001000;
+    const HIGHEST_SEQUENCE_NUMBER: usize = 0xEFFFFF00;
+    // Set in the chunk ID field of object headers that store extra object info in the tags
+    const EXTRA_HEADER_INFO_FLAG: usize = 0x80000000;
+    // When extra header info is present, the upper bits of the object ID are the object type
+    const OBJECT_ID_MASK: usize = 0x0FFFFFFF;
+
+    let yaffs_packed_tags = vec![
+        ("sequence_number", "u32"),
+        ("obj_id", "u32"),
+        ("chunk_id", "u32"),
+        ("byte_count", "u32"),
+    ];
+
+    let tags = common::parse(tags_data, &yaffs_packed_tags, endianness)?;
+
+    if tags["sequence_number"] < LOWEST_SEQUENCE_NUMBER
+        || tags["sequence_number"] > HIGHEST_SEQUENCE_NUMBER
+    {
+        return Err(StructureError);
+    }
+
+    let mut packed_tags = YAFFSPackedTags {
+        sequence_number: tags["sequence_number"],
+        obj_id: tags["obj_id"],
+        chunk_id: tags["chunk_id"],
+        byte_count: tags["byte_count"],
+    };
+
+    if (packed_tags.chunk_id & EXTRA_HEADER_INFO_FLAG) != 0 {
+        packed_tags.chunk_id = 0;
+        packed_tags.obj_id &= OBJECT_ID_MASK;
+    }
+
+    if packed_tags.obj_id == 0 {
+        return Err(StructureError);
+    }
+
+    Ok(packed_tags)
+}
@@ -7,6 +7,8 @@ pub struct ZipFileHeader {
     pub total_size: usize,
     pub version_major: usize,
     pub version_minor: usize,
+    pub compression: usize,
+    pub file_name: String,
 }
 
 /// Validate a ZIP file header
@@ -78,6 +80,14 @@ pub fn parse_zip_header(zip_data: &[u8]) -> Result<ZipFileHeader, StructureError
                     zip_local_file_header["uncompressed_size"]
                 };
                 result.total_size = result.header_size + result.data_size;
+                result.compression = zip_local_file_header["compression"];
+
+                let file_name_start = common::size(&zip_local_file_structure);
+                let file_name_end = file_name_start + zip_local_file_header["file_name_len"];
+                if let Some(file_name) = zip_data.get(file_name_start..file_name_end) {
+                    result.file_name = String::from_utf8_lossy(file_name).to_string();
+                }
+
                 return Ok(result);
             }
         }
@@ -91,6 +101,7 @@ pub fn parse_zip_header(zip_data: &[u8]) -> Result<ZipFileHeader, StructureError
 pub struct ZipEOCDHeader {
     pub size: usize,
     pub file_count: usize,
+    pub central_directory_size: usize,
 }
 
 /// Parse a ZIP end-of-central-directory header
@@ -120,6 +131,67 @@ pub fn parse_eocd_header(eocd_data: &[u8]) -> Result<ZipEOCDHeader, StructureErr
             return Ok(ZipEOCDHeader {
                 size: zip_eof,
                 file_count: zip_eocd_header["central_directory_total_entries"],
+                central_directory_size: zip_eocd_header["central_directory_size"],
+            });
+        }
+    }
+
+    Err(StructureError)
+}
+
+/// Stores info about a ZIP central directory file header
+#[derive(Debug, Default, Clone)]
+pub struct ZipCentralDirectoryEntry {
+    pub size: usize,
+    pub file_name: String,
+    pub compression: usize,
+    pub compressed_size: usize,
+    /// Offset of the file's local file header, relative to the start of the ZIP archive
+    pub local_header_offset: usize,
+    pub encrypted: bool,
+}
+
+/// Parse a ZIP central directory file header
+pub fn parse_central_directory_entry(
+    entry_data: &[u8],
+) -> Result<ZipCentralDirectoryEntry, StructureError> {
+    const CENTRAL_DIRECTORY_MAGIC: usize = 0x02014B50;
+    const FLAG_ENCRYPTED: usize = 0x0001;
+
+    let zip_central_directory_structure = vec![
+        ("magic", "u32"),
+        ("version_made_by", "u16"),
+        ("version_needed", "u16"),
+        ("flags", "u16"),
+        ("compression", "u16"),
+        ("modification_time", "u16"),
+        ("modification_date", "u16"),
+        ("crc", "u32"),
+        ("compressed_size", "u32"),
+        ("uncompressed_size", "u32"),
+        ("file_name_len", "u16"),
+        ("extra_field_len", "u16"),
+        ("comment_len", "u16"),
+        ("disk_number", "u16"),
+        ("internal_attributes", "u16"),
+        ("external_attributes", "u32"),
+        ("local_header_offset", "u32"),
+    ];
+
+    let entry_header = common::parse(entry_data, &zip_central_directory_structure, "little")?;
+
+    if entry_header["magic"] == CENTRAL_DIRECTORY_MAGIC {
+        let file_name_start = common::size(&zip_central_directory_structure);
+        let file_name_end = file_name_start + entry_header["file_name_len"];
+
+        if let Some(file_name) = entry_data.get(file_name_start..file_name_end) {
+            return Ok(ZipCentralDirectoryEntry {
+                size: file_name_end + entry_header["extra_field_len"] + entry_header["comment_len"],
+                file_name: String::from_utf8_lossy(file_name).to_string(),
+                compression: entry_header["compression"],
+                compressed_size: entry_header["compressed_size"],
+                local_header_offset: entry_header["local_header_offset"],
+                encrypted: (entry_header["flags"] & FLAG_ENCRYPTED) != 0,
             });
         }
     }
@@ -0,0 +1,45 @@
+use crate::structures::common::{self, StructureError};
+
+/// Size of the zlib header
+pub const ZLIB_HEADER_SIZE: usize = 2;
+
+/// Struct to store useful zlib header info
+#[derive(Debug, Default, Clone)]
+pub struct ZlibHeader {
+    pub window_size: usize,
+    pub compression_level: String,
+}
+
+/// Parses and validates a zlib header
+pub fn parse_zlib_header(zlib_data: &[u8]) -> Result<ZlibHeader, StructureError> {
+    const DEFLATE_METHOD: usize = 8;
+    const MAX_WINDOW_BITS: usize = 7;
+    const FLAG_PRESET_DICTIONARY: usize = 0b0010_0000;
+    const HEADER_CHECKSUM_DIVISOR: usize = 31;
+
+    let zlib_structure = vec![("cmf", "u8"), ("flg", "u8")];
+
+    let compression_levels = ["fastest", "fast", "default", "maximum"];
+
+    if let Ok(zlib_header) = common::parse(zlib_data, &zlib_structure, "big") {
+        let compression_method = zlib_header["cmf"] & 0x0F;
+        let window_bits = zlib_header["cmf"] >> 4;
+
+        // The header, interpreted as a big endian u16, must be a multiple of 31
+        let header_value = (zlib_header["cmf"] << 8) | zlib_header["flg"];
+
+        // Streams that require a preset dictionary can't be decompressed without it
+        if compression_method == DEFLATE_METHOD
+            && window_bits <= MAX_WINDOW_BITS
+            && (zlib_header["flg"] & FLAG_PRESET_DICTIONARY) == 0
+            && header_value.is_multiple_of(HEADER_CHECKSUM_DIVISOR)
+        {
+            return Ok(ZlibHeader {
+                window_size: 1 << (window_bits + 8),
+                compression_level: compression_levels[zlib_header["flg"] >> 6].to_string(),
+            });
+        }
+    }
+
+    Err(StructureError)
+}
@@ -1,5 +1,12 @@
 use crate::structures::common::{self, StructureError};
 
+/// ZSTD frame magic bytes
+pub const ZSTD_MAGIC: &[u8] = b"\x28\xb5\x2f\xfd";
+
+/// Skippable frames have one of 16 magic values, 0x184D2A50 - 0x184D2A5F
+pub const ZSTD_SKIPPABLE_FRAME_MAGIC: u32 = 0x184D2A50;
+pub const ZSTD_SKIPPABLE_FRAME_MAGIC_MASK: u32 = 0xFFFFFFF0;
+
 /// Stores info about a ZSTD file header
 #[derive(Debug, Default, Clone)]
 pub struct ZSTDHeader {
@@ -109,3 +116,19 @@ pub fn parse_block_header(block_data: &[u8]) -> Result<ZSTDBlockHeader, Structur
 
     Err(StructureError)
 }
+
+/// Parse a ZSTD skippable frame header, returns the total size of the skippable frame
+pub fn parse_skippable_frame(frame_data: &[u8]) -> Result<usize, StructureError> {
+    let skippable_frame_structure = vec![("magic", "u32"), ("frame_size", "u32")];
+
+    if let Ok(skippable_frame) = common::parse(frame_data, &skippable_frame_structure, "little") {
+        if (skippable_frame["magic"] as u32 & ZSTD_SKIPPABLE_FRAME_MAGIC_MASK)
+            == ZSTD_SKIPPABLE_FRAME_MAGIC
+        {
+            // The reported frame size does not include the skippable frame header
+            return Ok(common::size(&skippable_frame_structure) + skippable_frame["frame_size"]);
+        }
+    }
+
+    Err(StructureError)
+}
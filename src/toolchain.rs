@@ -0,0 +1,485 @@
+//! Compiler toolchain and vendor SDK fingerprinting.
+
+use crate::common::escape_non_printable;
+use crate::structures::elf::parse_elf_sections;
+use aho_corasick::AhoCorasick;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Toolchain components identified in a single file
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ToolchainFingerprint {
+    /// Compiler versions, e.g., "GCC 7.4.0 (Buildroot 2019.02)"
+    pub compilers: Vec<String>,
+    /// Linker versions, e.g., "LLD 14.0.6"
+    pub linkers: Vec<String>,
+    /// C/C++ runtime libraries, e.g., "uClibc 0.9.33.2"
+    pub libraries: Vec<String>,
+    /// Vendor SDKs and build systems, e.g., "OpenWrt r16279-5cc0535800"
+    pub sdks: Vec<String>,
+}
+
+impl ToolchainFingerprint {
+    /// Returns true if no toolchain components were identified
+    pub fn is_empty(&self) -> bool {
+        self.compilers.is_empty()
+            && self.linkers.is_empty()
+            && self.libraries.is_empty()
+            && self.sdks.is_empty()
+    }
+}
+
+/// Toolchain components identified across all analyzed files.
+/// Each HashMap maps a toolchain component to the number of files it was identified in.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ToolchainSummary {
+    /// Number of files that had at least one toolchain component identified
+    pub file_count: usize,
+    pub compilers: HashMap<String, usize>,
+    pub linkers: HashMap<String, usize>,
+    pub libraries: HashMap<String, usize>,
+    pub sdks: HashMap<String, usize>,
+}
+
+impl ToolchainSummary {
+    /// Adds a file's toolchain fingerprint to the summary
+    pub fn add(&mut self, fingerprint: &ToolchainFingerprint) {
+        if fingerprint.is_empty() {
+            return;
+        }
+
+        self.file_count += 1;
+
+        for (summary, components) in [
+            (&mut self.compilers, &fingerprint.compilers),
+            (&mut self.linkers, &fingerprint.linkers),
+            (&mut self.libraries, &fingerprint.libraries),
+            (&mut self.sdks, &fingerprint.sdks),
+        ] {
+            for component in components {
+                *summary.entry(component.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Returns true if no toolchain components have been identified
+    pub fn is_empty(&self) -> bool {
+        self.file_count == 0
+    }
+}
+
+/// Identifies the compilers, linkers, runtime libraries, and vendor SDKs used to build the provided file data.
+///
+/// ELF files are identified by their .comment sections and toolchain notes; all files are searched for
+/// characteristic version strings and library names.
+pub fn fingerprint(file_data: &[u8]) -> ToolchainFingerprint {
+    let mut fingerprint = ToolchainFingerprint {
+        ..Default::default()
+    };
+
+    fingerprint_elf_sections(file_data, &mut fingerprint);
+    fingerprint_strings(file_data, &mut fingerprint);
+
+    for components in [
+        &mut fingerprint.compilers,
+        &mut fingerprint.linkers,
+        &mut fingerprint.libraries,
+        &mut fingerprint.sdks,
+    ] {
+        remove_redundant(components);
+    }
+
+    fingerprint
+}
+
+/// Processes toolchain info from ELF sections, if the file data is an ELF file
+fn fingerprint_elf_sections(file_data: &[u8], fingerprint: &mut ToolchainFingerprint) {
+    if let Ok(sections) = parse_elf_sections(file_data) {
+        for section in sections {
+            if let Some(section_data) = file_data
+                .get(section.offset..)
+                .and_then(|data| data.get(..section.size))
+            {
+                match section.name.as_str() {
+                    // The .comment section is a list of NULL terminated version strings
+                    ".comment" => {
+                        for comment in section_data.split(|b| *b == 0) {
+                            parse_version_string(&String::from_utf8_lossy(comment), fingerprint);
+                        }
+                    }
+                    // The gold linker records its version in a note, e.g., "gold 1.16"
+                    ".note.gnu.gold-version" => {
+                        if let Some(gold_version) = printable_string_at(section_data, b"gold ") {
+                            if let Some(version) = version_token(&gold_version["gold ".len()..]) {
+                                add_component(
+                                    &mut fingerprint.linkers,
+                                    format!("GNU gold {version}"),
+                                );
+                            }
+                        }
+                    }
+                    ".note.android.ident" => {
+                        add_component(&mut fingerprint.sdks, "Android NDK".to_string());
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+/// Searches the file data for characteristic toolchain strings
+fn fingerprint_strings(file_data: &[u8], fingerprint: &mut ToolchainFingerprint) {
+    // Go build info header
+    const GO_BUILDINFO: &[u8] = b"\xFF Go buildinf:";
+    const GO_BUILDINFO_FLAGS_OFFSET: usize = 15;
+    const GO_BUILDINFO_VERSION_OFFSET: usize = 32;
+    const GO_BUILDINFO_INLINE_STRINGS: u8 = 2;
+
+    // Strings that identify compilers and linkers, parsed by parse_version_string
+    const VERSION_STRINGS: [&str; 6] = [
+        "GCC: (",
+        "gcc version ",
+        "clang version ",
+        "rustc version ",
+        "GNU ld (",
+        "GNU C Library ",
+    ];
+
+    // Library names, and the runtime library or SDK they indicate
+    const LIBRARY_NAMES: [(&str, &str); 5] = [
+        ("ld-uClibc.so.0", "uClibc"),
+        ("ld-uClibc.so.1", "uClibc-ng"),
+        ("/system/bin/linker", "Bionic"),
+        ("libstdc++.so.", "libstdc++"),
+        ("libc++.so", "libc++"),
+    ];
+
+    const SDK_NAMES: [(&str, &str); 5] = [
+        ("libbcmcrypto.so", "Broadcom SDK"),
+        ("RT288x_SDK", "Ralink/MediaTek SDK"),
+        ("libnvram-0.9.28.so", "Ralink/MediaTek SDK"),
+        ("Booting Zephyr OS", "Zephyr RTOS"),
+        ("ESP-IDF", "ESP-IDF"),
+    ];
+
+    // These patterns require additional processing
+    const UCLIBC_LIBRARY: &str = "libuClibc-";
+    const MUSL_LOADER: &str = "ld-musl-";
+    const MUSL_LIBRARY: &str = "libc.musl-";
+    const GLIBC_SYMBOL_VERSION: &str = "GLIBC_2.";
+
+    let mut patterns: Vec<&[u8]> = vec![
+        GO_BUILDINFO,
+        UCLIBC_LIBRARY.as_bytes(),
+        MUSL_LOADER.as_bytes(),
+        MUSL_LIBRARY.as_bytes(),
+        GLIBC_SYMBOL_VERSION.as_bytes(),
+    ];
+    patterns.extend(VERSION_STRINGS.iter().map(|pattern| pattern.as_bytes()));
+    patterns.extend(LIBRARY_NAMES.iter().map(|(pattern, _)| pattern.as_bytes()));
+    patterns.extend(SDK_NAMES.iter().map(|(pattern, _)| pattern.as_bytes()));
+
+    let searcher = match AhoCorasick::new(&patterns) {
+        Ok(searcher) => searcher,
+        Err(_) => return,
+    };
+
+    // Highest GLIBC symbol version that the file references
+    let mut glibc_minor_version: Option<usize> = None;
+
+    for pattern_match in searcher.find_iter(file_data) {
+        let pattern = patterns[pattern_match.pattern().as_usize()];
+        let match_data = &file_data[pattern_match.start()..];
+
+        if pattern == GO_BUILDINFO {
+            let mut go_version = "Go".to_string();
+
+            // Newer Go versions store the version string inline as a length-prefixed string
+            if let Some(flags) = match_data.get(GO_BUILDINFO_FLAGS_OFFSET) {
+                if (flags & GO_BUILDINFO_INLINE_STRINGS) != 0 {
+                    if let Some(length) = match_data.get(GO_BUILDINFO_VERSION_OFFSET) {
+                        let version_start = GO_BUILDINFO_VERSION_OFFSET + 1;
+                        let version_end = version_start + (*length as usize);
+
+                        if let Some(version_data) = match_data.get(version_start..version_end) {
+                            let version = String::from_utf8_lossy(version_data);
+                            if let Some(version) = version.strip_prefix("go") {
+                                go_version = format!("Go {version}");
+                            }
+                        }
+                    }
+                }
+            }
+
+            add_component(&mut fingerprint.compilers, go_version);
+        } else if pattern == GLIBC_SYMBOL_VERSION.as_bytes() {
+            let minor_version: String = match_data[pattern.len()..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .map(|b| *b as char)
+                .collect();
+
+            if let Ok(minor_version) = minor_version.parse::<usize>() {
+                glibc_minor_version = glibc_minor_version.max(Some(minor_version));
+            }
+        } else if pattern == UCLIBC_LIBRARY.as_bytes() {
+            // e.g., libuClibc-0.9.33.2.so; uClibc-ng started at version 1.0
+            if let Some(library_name) = printable_string_at(match_data, pattern) {
+                if let Some(version) = library_name[pattern.len()..].strip_suffix(".so") {
+                    if version.starts_with("0.") {
+                        add_component(&mut fingerprint.libraries, format!("uClibc {version}"));
+                    } else if version.starts_with(|c: char| c.is_ascii_digit()) {
+                        add_component(&mut fingerprint.libraries, format!("uClibc-ng {version}"));
+                    }
+                }
+            }
+        } else if pattern == MUSL_LOADER.as_bytes() || pattern == MUSL_LIBRARY.as_bytes() {
+            add_component(&mut fingerprint.libraries, "musl".to_string());
+        } else if let Some((_, library)) = LIBRARY_NAMES
+            .iter()
+            .find(|(name, _)| name.as_bytes() == pattern)
+        {
+            add_component(&mut fingerprint.libraries, library.to_string());
+        } else if let Some((_, sdk)) = SDK_NAMES
+            .iter()
+            .find(|(name, _)| name.as_bytes() == pattern)
+        {
+            add_component(&mut fingerprint.sdks, sdk.to_string());
+        } else if let Some(version_string) = printable_string_at(match_data, pattern) {
+            parse_version_string(&version_string, fingerprint);
+        }
+    }
+
+    // Symbol versions only indicate the minimum required glibc version, so prefer glibc's own version string
+    if let Some(minor_version) = glibc_minor_version {
+        if !fingerprint
+            .libraries
+            .iter()
+            .any(|library| library.starts_with("glibc "))
+        {
+            add_component(
+                &mut fingerprint.libraries,
+                format!("glibc >= 2.{minor_version}"),
+            );
+        }
+    }
+}
+
+/// Parses a compiler or linker version string, such as those found in ELF .comment sections
+fn parse_version_string(version_string: &str, fingerprint: &mut ToolchainFingerprint) {
+    let version_string = version_string.trim();
+
+    if let Some(gcc_version) = version_string.strip_prefix("GCC: ") {
+        // e.g., "GCC: (Buildroot 2019.02) 7.4.0"
+        let (vendor, remainder) = parenthesized_prefix(gcc_version);
+
+        if let Some(version) = version_token(remainder) {
+            add_vendor_component(&mut fingerprint.compilers, "GCC", version, vendor);
+            add_vendor_sdk(fingerprint, vendor);
+        }
+    } else if let Some(gcc_version) = version_string.strip_prefix("gcc version ") {
+        // e.g., Linux kernel banners: "gcc version 5.4.0 (OpenWrt GCC 5.4.0 r4962-7f5a7d3)"
+        if let Some(version) = version_token(gcc_version) {
+            let (vendor, _) = parenthesized_prefix(gcc_version[version.len()..].trim_start());
+
+            add_vendor_component(&mut fingerprint.compilers, "GCC", version, vendor);
+            add_vendor_sdk(fingerprint, vendor);
+        }
+    } else if let Some(ld_version) = version_string.strip_prefix("GNU ld ") {
+        // e.g., "GNU ld (GNU Binutils for Ubuntu) 2.38"
+        let (vendor, remainder) = parenthesized_prefix(ld_version);
+
+        if let Some(version) = version_token(remainder) {
+            let vendor = vendor.filter(|vendor| *vendor != "GNU Binutils");
+            add_vendor_component(&mut fingerprint.linkers, "GNU ld", version, vendor);
+            add_vendor_sdk(fingerprint, vendor);
+        }
+    } else if let Some((vendor, clang_version)) = version_string.split_once("clang version ") {
+        // e.g., "Android (8490178, based on r450784d) clang version 14.0.6 (...)"
+        if let Some(version) = version_token(clang_version) {
+            let vendor = Some(vendor.trim()).filter(|vendor| !vendor.is_empty());
+            add_vendor_component(&mut fingerprint.compilers, "clang", version, vendor);
+
+            if vendor.is_some_and(|vendor| vendor.starts_with("Android")) {
+                add_component(&mut fingerprint.sdks, "Android NDK".to_string());
+            }
+        }
+    } else if let Some(rustc_version) = version_string.strip_prefix("rustc version ") {
+        if let Some(version) = version_token(rustc_version) {
+            add_component(&mut fingerprint.compilers, format!("rustc {version}"));
+        }
+    } else if let Some(linker_version) = version_string.strip_prefix("Linker: ") {
+        // e.g., "Linker: LLD 14.0.6"
+        let mut tokens = linker_version.split_whitespace();
+
+        if let (Some(linker), Some(version)) =
+            (tokens.next(), tokens.next().and_then(version_token))
+        {
+            add_component(&mut fingerprint.linkers, format!("{linker} {version}"));
+        }
+    } else if let Some(mold_version) = version_string.strip_prefix("mold ") {
+        if let Some(version) = version_token(mold_version) {
+            add_component(&mut fingerprint.linkers, format!("mold {version}"));
+        }
+    } else if let Some(glibc_version) = version_string.strip_prefix("GNU C Library ") {
+        // e.g., "GNU C Library (Ubuntu GLIBC 2.35-0ubuntu3.1) stable release version 2.35."
+        if let Some((_, release_version)) = glibc_version.split_once("release version ") {
+            if let Some(version) = version_token(release_version.trim_end_matches('.')) {
+                add_component(&mut fingerprint.libraries, format!("glibc {version}"));
+            }
+        }
+    }
+}
+
+/// Returns the first token of the string, if it looks like a version number
+fn version_token(text: &str) -> Option<&str> {
+    let token = text
+        .split_whitespace()
+        .next()?
+        .trim_end_matches([',', ';', ')', '.']);
+
+    if token.starts_with(|c: char| c.is_ascii_digit()) {
+        return Some(token);
+    }
+
+    None
+}
+
+/// If the string starts with a parenthesized value, returns the value and the remainder of the string
+fn parenthesized_prefix(text: &str) -> (Option<&str>, &str) {
+    if text.starts_with('(') {
+        let mut depth: usize = 0;
+
+        // Parentheses may be nested, e.g., "(Android (8490178, based on r450784d))"
+        for (i, c) in text.char_indices() {
+            if c == '(' {
+                depth += 1;
+            } else if c == ')' {
+                depth -= 1;
+                if depth == 0 {
+                    return (Some(text[1..i].trim()), &text[i + 1..]);
+                }
+            }
+        }
+    }
+
+    (None, text)
+}
+
+/// Adds a component formatted as "<name> <version> (<vendor>)"; the vendor is omitted for upstream GNU builds
+fn add_vendor_component(
+    components: &mut Vec<String>,
+    name: &str,
+    version: &str,
+    vendor: Option<&str>,
+) {
+    match vendor {
+        Some(vendor) if !vendor.is_empty() && vendor != "GNU" => {
+            add_component(components, format!("{name} {version} ({vendor})"))
+        }
+        _ => add_component(components, format!("{name} {version}")),
+    }
+}
+
+/// Identifies SDKs and build systems from toolchain vendor strings, e.g., "Buildroot 2019.02"
+fn add_vendor_sdk(fingerprint: &mut ToolchainFingerprint, vendor: Option<&str>) {
+    // Vendor string keywords and the SDK or build system they identify
+    const SDK_VENDORS: [(&str, &str); 10] = [
+        ("Buildroot", "Buildroot"),
+        ("OpenWrt", "OpenWrt"),
+        ("LEDE", "LEDE"),
+        ("crosstool-NG", "crosstool-NG"),
+        ("Linaro", "Linaro"),
+        ("Sourcery", "Sourcery CodeBench"),
+        ("RSDK", "Realtek RSDK"),
+        ("stbgcc", "Broadcom STB toolchain"),
+        ("MontaVista", "MontaVista"),
+        ("Android", "Android NDK"),
+    ];
+
+    if let Some(vendor) = vendor {
+        let tokens: Vec<&str> = vendor
+            .split_whitespace()
+            .map(|token| token.trim_matches(['(', ')', ',']))
+            .collect();
+
+        for (keyword, sdk) in SDK_VENDORS {
+            if let Some(index) = tokens.iter().position(|token| token.starts_with(keyword)) {
+                // The version may be part of the keyword token (e.g., "RSDK-4.4.7"), or a subsequent token;
+                // OpenWrt-style revision numbers (e.g., "r16279") are preferred over toolchain version numbers.
+                let version = match tokens[index][keyword.len()..].strip_prefix('-') {
+                    Some(version) if version.starts_with(|c: char| c.is_ascii_digit()) => {
+                        Some(version)
+                    }
+                    _ => tokens[index + 1..]
+                        .iter()
+                        .find(|token| {
+                            token.starts_with('r')
+                                && token[1..].starts_with(|c: char| c.is_ascii_digit())
+                        })
+                        .or_else(|| {
+                            tokens[index + 1..]
+                                .iter()
+                                .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))
+                        })
+                        .copied(),
+                };
+
+                match version {
+                    Some(version) if sdk != "Android NDK" => {
+                        add_component(&mut fingerprint.sdks, format!("{sdk} {version}"))
+                    }
+                    _ => add_component(&mut fingerprint.sdks, sdk.to_string()),
+                }
+
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the printable ASCII string at the start of the data, if it starts with the specified prefix
+fn printable_string_at(data: &[u8], prefix: &[u8]) -> Option<String> {
+    // Maximum length of a version string or library name
+    const MAX_STRING_LENGTH: usize = 256;
+
+    let start = data
+        .windows(prefix.len())
+        .take(MAX_STRING_LENGTH)
+        .position(|window| window == prefix)?;
+
+    let string_bytes: Vec<u8> = data[start..]
+        .iter()
+        .take(MAX_STRING_LENGTH)
+        .take_while(|b| b.is_ascii_graphic() || **b == b' ')
+        .copied()
+        .collect();
+
+    Some(String::from_utf8_lossy(&string_bytes).to_string())
+}
+
+/// Adds a component to the list, if it is not already present
+fn add_component(components: &mut Vec<String>, component: String) {
+    // Component names are parsed from the file data
+    let component = escape_non_printable(&component);
+
+    if !components.contains(&component) {
+        components.push(component);
+    }
+}
+
+/// Removes components that are less specific versions of other components, e.g., "clang 14.0.6" vs "clang 14.0.6 (Android)"
+fn remove_redundant(components: &mut Vec<String>) {
+    let all_components = components.clone();
+
+    components.retain(|component| {
+        !all_components.iter().any(|other| {
+            other.len() > component.len()
+                && other.starts_with(component.as_str())
+                && other[component.len()..].starts_with(' ')
+        })
+    });
+
+    components.sort();
+}
@@ -0,0 +1,173 @@
+//! Transforms that clean up artifacts commonly found in flash dumps captured over JTAG or a serial console.
+//!
+//! Transforms are applied to a target file's data before it is scanned; reported offsets are relative to the
+//! transformed data.
+
+/// Names of all transforms supported by `apply`
+pub const TRANSFORMS: &[&str] = &["strip-padding", "strip-progress", "fix-line-endings"];
+
+/// Runs of erased flash bytes at least this long are removed by the `strip-padding` transform
+pub const MIN_PADDING_SIZE: usize = 4096;
+
+/// Applies the named transform, one of `TRANSFORMS`, to the data. Returns None if the transform name is unknown.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::transforms::apply;
+///
+/// assert_eq!(apply("fix-line-endings", b"\x1F\x8B\r\n"), Some(b"\x1F\x8B\n".to_vec()));
+/// assert_eq!(apply("sharpen", b"\x1F\x8B\r\n"), None);
+/// ```
+pub fn apply(transform: &str, data: &[u8]) -> Option<Vec<u8>> {
+    match transform {
+        "strip-padding" => Some(strip_padding(data, MIN_PADDING_SIZE)),
+        "strip-progress" => Some(strip_progress_lines(data)),
+        "fix-line-endings" => Some(fix_line_endings(data)),
+        _ => None,
+    }
+}
+
+/// Removes runs of erased flash, i.e. 0xFF bytes, that are at least `min_size` bytes long.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::transforms::strip_padding;
+///
+/// let data = [b"AB".to_vec(), vec![0xFF; 8], b"C\xFF\xFFD".to_vec()].concat();
+///
+/// assert_eq!(strip_padding(&data, 4), b"ABC\xFF\xFFD");
+/// ```
+pub fn strip_padding(data: &[u8], min_size: usize) -> Vec<u8> {
+    const ERASED_BYTE: u8 = 0xFF;
+
+    let mut cleaned: Vec<u8> = Vec::with_capacity(data.len());
+
+    for run in data.chunk_by(|a, b| a == b) {
+        if run[0] != ERASED_BYTE || run.len() < min_size {
+            cleaned.extend_from_slice(run);
+        }
+    }
+
+    cleaned
+}
+
+/// Removes lines of progress output, such as "Reading flash... 42%" or "Loading: #########", that were interleaved
+/// with the dumped data while it was captured from a serial console.
+///
+/// A progress line is a line of printable ASCII text that starts at the beginning of the data or immediately after a
+/// line ending, is terminated by one or more carriage returns and/or line feeds, and either contains a percentage or
+/// ends in a progress bar of '#', '.', '*' or '=' characters. The progress line and its line endings are removed.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::transforms::strip_progress_lines;
+///
+/// let data = b"\x00\x01\nReading flash... 42%\r\nLoading: ##########\r\n\x02\x03\nNot progress\n";
+///
+/// assert_eq!(strip_progress_lines(data), b"\x00\x01\n\x02\x03\nNot progress\n");
+/// ```
+pub fn strip_progress_lines(data: &[u8]) -> Vec<u8> {
+    let mut cleaned: Vec<u8> = Vec::with_capacity(data.len());
+    let mut offset: usize = 0;
+
+    while offset < data.len() {
+        let is_line_start = offset == 0 || is_line_ending(data[offset - 1]);
+
+        if is_line_start {
+            if let Some(line_size) = progress_line_size(&data[offset..]) {
+                offset += line_size;
+                continue;
+            }
+        }
+
+        cleaned.push(data[offset]);
+        offset += 1;
+    }
+
+    cleaned
+}
+
+/// Converts CRLF line endings back to LF, undoing the corruption caused by capture software or terminals that translate
+/// every line feed byte in the dumped data to a carriage return and line feed.
+///
+/// ## Example
+///
+/// ```
+/// use binwalk::transforms::fix_line_endings;
+///
+/// assert_eq!(fix_line_endings(b"\x89PNG\r\r\n\x1A\r\n"), b"\x89PNG\r\n\x1A\n");
+/// ```
+pub fn fix_line_endings(data: &[u8]) -> Vec<u8> {
+    let mut cleaned: Vec<u8> = Vec::with_capacity(data.len());
+    let mut offset: usize = 0;
+
+    while offset < data.len() {
+        if data[offset] == b'\r' && data.get(offset + 1) == Some(&b'\n') {
+            offset += 1;
+        }
+
+        cleaned.push(data[offset]);
+        offset += 1;
+    }
+
+    cleaned
+}
+
+/// Returns the size of the progress line, including its line endings, at the start of the data, if there is one
+fn progress_line_size(data: &[u8]) -> Option<usize> {
+    // Limits on the length of a progress line's text, excluding line endings
+    const MIN_LINE_LENGTH: usize = 2;
+    const MAX_LINE_LENGTH: usize = 256;
+
+    let text_size = data
+        .iter()
+        .take(MAX_LINE_LENGTH + 1)
+        .position(|b| !(0x20..=0x7E).contains(b))?;
+
+    let line_ending_size = data[text_size..]
+        .iter()
+        .take_while(|b| is_line_ending(**b))
+        .count();
+
+    if !(MIN_LINE_LENGTH..=MAX_LINE_LENGTH).contains(&text_size) || line_ending_size == 0 {
+        return None;
+    }
+
+    match is_progress_text(&data[..text_size]) {
+        true => Some(text_size + line_ending_size),
+        false => None,
+    }
+}
+
+/// Returns true if the text contains a percentage, or ends with a progress bar
+fn is_progress_text(text: &[u8]) -> bool {
+    const PROGRESS_BAR_CHARACTERS: &[u8] = b"#.*=";
+    const MIN_PROGRESS_BAR_LENGTH: usize = 8;
+
+    if text
+        .windows(2)
+        .any(|pair| pair[0].is_ascii_digit() && pair[1] == b'%')
+    {
+        return true;
+    }
+
+    // A progress bar may be preceded by a label, e.g. "Loading: ####"
+    let bar_size = text
+        .iter()
+        .rev()
+        .take_while(|b| PROGRESS_BAR_CHARACTERS.contains(b) || **b == b' ')
+        .count();
+    let bar = &text[text.len() - bar_size..];
+    let label = text[..text.len() - bar_size].trim_ascii_end();
+
+    bar.iter().filter(|b| **b != b' ').count() >= MIN_PROGRESS_BAR_LENGTH
+        && (label.is_empty() || label.ends_with(b":"))
+}
+
+/// Returns true if the byte is a carriage return or line feed
+fn is_line_ending(b: u8) -> bool {
+    b == b'\r' || b == b'\n'
+}